@@ -9,14 +9,43 @@ use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
 use anyhow::{Context, Result};
-use ht32_panel_client::DaemonClient;
-use tracing::{debug, error, info};
+use clap::{Parser, Subcommand};
+use ht32_panel_client::{autostart, DaemonClient, LedSettings};
+use tracing::{debug, error, info, warn};
 use tracing_subscriber::EnvFilter;
 
 use tray::{create_tray, TrayCommand, TrayState};
 
+#[derive(Parser)]
+#[command(name = "ht32-panel-applet")]
+#[command(about = "System tray applet for HT32 Panel control")]
+#[command(version)]
+struct Cli {
+    /// Runs the tray when omitted
+    #[command(subcommand)]
+    command: Option<Commands>,
+}
+
+#[derive(Subcommand)]
+enum Commands {
+    /// Start the applet on login
+    EnableAutostart {
+        /// Also install and start the daemon as a systemd user service
+        #[arg(long)]
+        daemon: bool,
+    },
+    /// Stop starting the applet on login
+    DisableAutostart {
+        /// Also stop and remove the daemon's systemd user service
+        #[arg(long)]
+        daemon: bool,
+    },
+}
+
 #[tokio::main]
 async fn main() -> Result<()> {
+    let cli = Cli::parse();
+
     // Setup logging
     tracing_subscriber::fmt()
         .with_env_filter(
@@ -24,6 +53,34 @@ async fn main() -> Result<()> {
         )
         .init();
 
+    match cli.command {
+        Some(Commands::EnableAutostart { daemon }) => {
+            let path = autostart::enable()?;
+            println!("Applet autostart entry written to {}", path.display());
+            if daemon {
+                let path = autostart::enable_daemon()?;
+                println!("Daemon user service installed at {}", path.display());
+            }
+            return Ok(());
+        }
+        Some(Commands::DisableAutostart { daemon }) => {
+            if autostart::disable()? {
+                println!("Applet autostart entry removed");
+            } else {
+                println!("Applet autostart was not enabled");
+            }
+            if daemon {
+                if autostart::disable_daemon()? {
+                    println!("Daemon user service removed");
+                } else {
+                    println!("Daemon user service was not installed");
+                }
+            }
+            return Ok(());
+        }
+        None => {}
+    }
+
     info!("HT32 Panel Applet starting...");
 
     // Shared state
@@ -46,27 +103,16 @@ async fn main() -> Result<()> {
                         info!("Connected to daemon via D-Bus");
 
                         // Update state from daemon
-                        if let Ok(conn) = c.is_connected().await {
-                            let mut s = cmd_state.lock().unwrap();
-                            s.connected = conn;
-                        }
-                        if let Ok(web) = c.is_web_enabled().await {
-                            let mut s = cmd_state.lock().unwrap();
-                            s.web_enabled = web;
-                        }
-                        if let Ok(orient) = c.get_orientation().await {
+                        if let Ok(daemon) = c.get_state().await {
                             let mut s = cmd_state.lock().unwrap();
-                            s.orientation = orient;
-                        }
-                        if let Ok((theme, intensity, speed)) = c.get_led_settings().await {
-                            let mut s = cmd_state.lock().unwrap();
-                            s.led_theme = theme;
-                            s.led_intensity = intensity;
-                            s.led_speed = speed;
-                        }
-                        if let Ok(face) = c.get_face().await {
-                            let mut s = cmd_state.lock().unwrap();
-                            s.face = face;
+                            s.connected = daemon.connected;
+                            s.web_enabled = daemon.web_enabled;
+                            s.orientation = daemon.orientation.to_string();
+                            s.led_theme = daemon.led.led_theme();
+                            s.led_intensity = daemon.led.intensity();
+                            s.led_speed = daemon.led.speed();
+                            s.face = daemon.face;
+                            s.ip_display = daemon.ip_display_preference;
                         }
                         if let Ok(iface) = c.get_complication_option("network", "interface").await {
                             let mut s = cmd_state.lock().unwrap();
@@ -91,11 +137,11 @@ async fn main() -> Result<()> {
                     match cmd {
                         Some(TrayCommand::SetLedTheme(theme)) => {
                             if let Some(ref c) = client {
-                                let (_, intensity, speed) = {
+                                let settings = {
                                     let s = cmd_state.lock().unwrap();
-                                    (s.led_theme, s.led_intensity, s.led_speed)
+                                    LedSettings::clamped(theme as u8, s.led_intensity, s.led_speed)
                                 };
-                                match c.set_led(theme, intensity, speed).await {
+                                match c.set_led(settings).await {
                                     Ok(()) => {
                                         let mut s = cmd_state.lock().unwrap();
                                         s.led_theme = theme;
@@ -157,6 +203,36 @@ async fn main() -> Result<()> {
                                 }
                             }
                         }
+                        Some(TrayCommand::SetIpDisplay(preference)) => {
+                            if let Some(ref c) = client {
+                                match c.set_ip_display_preference(preference).await {
+                                    Ok(()) => {
+                                        let mut s = cmd_state.lock().unwrap();
+                                        s.ip_display = preference;
+                                        debug!("IP display set to {}", preference);
+                                    }
+                                    Err(e) => {
+                                        error!("Failed to set IP display: {}", e);
+                                        client = None; // Mark for reconnection
+                                    }
+                                }
+                            }
+                        }
+                        Some(TrayCommand::SetAutostart { daemon, enable }) => {
+                            // systemctl can take a while; keep the worker responsive
+                            let result = tokio::task::spawn_blocking(move || match (daemon, enable) {
+                                (false, true) => autostart::enable().map(|_| ()),
+                                (false, false) => autostart::disable().map(|_| ()),
+                                (true, true) => autostart::enable_daemon().map(|_| ()),
+                                (true, false) => autostart::disable_daemon().map(|_| ()),
+                            })
+                            .await;
+                            match result {
+                                Ok(Ok(())) => debug!("Autostart updated"),
+                                Ok(Err(e)) => warn!("Failed to change autostart: {:#}", e),
+                                Err(e) => error!("Autostart task failed: {}", e),
+                            }
+                        }
                         Some(TrayCommand::QuitDaemon) => {
                             if let Some(ref c) = client {
                                 match c.quit().await {
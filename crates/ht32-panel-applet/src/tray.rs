@@ -1,17 +1,18 @@
 //! System tray implementation using StatusNotifierItem (SNI).
 
+use ht32_panel_client::{autostart, IpDisplayPreference, LedTheme};
 use ksni::{menu::*, Tray, TrayService};
 use std::sync::{Arc, Mutex};
 use tokio::sync::mpsc;
 use tracing::{debug, warn};
 
-/// LED theme options: (display name, theme byte value)
-const LED_THEMES: &[(&str, u8)] = &[
-    ("Rainbow", 1),
-    ("Breathing", 2),
-    ("Colors", 3),
-    ("Off", 4),
-    ("Auto", 5),
+/// LED theme options: (display name, theme)
+const LED_THEMES: &[(&str, LedTheme)] = &[
+    ("Rainbow", LedTheme::Rainbow),
+    ("Breathing", LedTheme::Breathing),
+    ("Colors", LedTheme::Colors),
+    ("Off", LedTheme::Off),
+    ("Auto", LedTheme::Auto),
 ];
 
 /// Orientation options: (display name, orientation string)
@@ -25,13 +26,23 @@ const ORIENTATIONS: &[(&str, &str)] = &[
 /// Face options: (display name, face string)
 const FACES: &[(&str, &str)] = &[("ASCII", "ascii"), ("Professional", "professional")];
 
+/// IP display options: (display name, preference string)
+const IP_DISPLAY: &[(&str, IpDisplayPreference)] = &[
+    ("IPv6 Global", IpDisplayPreference::Ipv6Gua),
+    ("IPv6 Link-Local", IpDisplayPreference::Ipv6Lla),
+    ("IPv6 Unique Local", IpDisplayPreference::Ipv6Ula),
+    ("IPv4", IpDisplayPreference::Ipv4),
+];
+
 /// Commands that can be sent from tray callbacks to the async worker.
 #[derive(Debug, Clone)]
 pub enum TrayCommand {
-    SetLedTheme(u8),
+    SetLedTheme(LedTheme),
     SetOrientation(String),
     SetFace(String),
     SetNetworkInterface(String),
+    SetIpDisplay(IpDisplayPreference),
+    SetAutostart { daemon: bool, enable: bool },
     QuitDaemon,
 }
 
@@ -39,13 +50,14 @@ pub enum TrayCommand {
 pub struct TrayState {
     pub connected: bool,
     pub web_enabled: bool,
-    pub led_theme: u8,
+    pub led_theme: LedTheme,
     pub led_intensity: u8,
     pub led_speed: u8,
     pub orientation: String,
     pub face: String,
     pub network_interface: String,
     pub network_interfaces: Vec<String>,
+    pub ip_display: IpDisplayPreference,
 }
 
 impl Default for TrayState {
@@ -53,13 +65,14 @@ impl Default for TrayState {
         Self {
             connected: false,
             web_enabled: false,
-            led_theme: 2, // Breathing
+            led_theme: LedTheme::default(),
             led_intensity: 3,
             led_speed: 3,
             orientation: "landscape".to_string(),
             face: "professional".to_string(),
             network_interface: String::new(),
             network_interfaces: Vec::new(),
+            ip_display: IpDisplayPreference::default(),
         }
     }
 }
@@ -151,6 +164,18 @@ impl HT32PanelTray {
         }
     }
 
+    fn set_ip_display(&mut self, index: usize) {
+        if let Some((_, preference)) = IP_DISPLAY.get(index) {
+            if let Err(e) = self.command_tx.send(TrayCommand::SetIpDisplay(*preference)) {
+                debug!("Failed to send IP display command: {}", e);
+            }
+            // Update local state immediately for UI feedback
+            if let Ok(mut s) = self.state.lock() {
+                s.ip_display = *preference;
+            }
+        }
+    }
+
     fn quit_daemon(&self) {
         if let Err(e) = self.command_tx.send(TrayCommand::QuitDaemon) {
             debug!("Failed to send quit command: {}", e);
@@ -162,6 +187,15 @@ impl HT32PanelTray {
             warn!("Failed to open web UI: {}", e);
         }
     }
+
+    fn set_autostart(&self, daemon: bool, enable: bool) {
+        if let Err(e) = self
+            .command_tx
+            .send(TrayCommand::SetAutostart { daemon, enable })
+        {
+            debug!("Failed to send autostart command: {}", e);
+        }
+    }
 }
 
 impl Tray for HT32PanelTray {
@@ -189,9 +223,14 @@ impl Tray for HT32PanelTray {
         let current_face = state.face.clone();
         let current_network = state.network_interface.clone();
         let network_interfaces = state.network_interfaces.clone();
+        let current_ip_display = state.ip_display;
         let web_enabled = state.web_enabled;
         drop(state);
 
+        // Autostart state lives in the user's config directory
+        let autostart_enabled = autostart::is_enabled();
+        let daemon_autostart_enabled = autostart::is_daemon_enabled();
+
         // Find current LED theme index
         let led_selected = LED_THEMES
             .iter()
@@ -221,6 +260,12 @@ impl Tray for HT32PanelTray {
                 .unwrap_or(0)
         };
 
+        // Find current IP display index
+        let ip_display_selected = IP_DISPLAY
+            .iter()
+            .position(|(_, p)| *p == current_ip_display)
+            .unwrap_or(0);
+
         // Create LED theme radio items
         let led_options: Vec<RadioItem> = LED_THEMES
             .iter()
@@ -248,6 +293,15 @@ impl Tray for HT32PanelTray {
             })
             .collect();
 
+        // Create IP display radio items
+        let ip_display_options: Vec<RadioItem> = IP_DISPLAY
+            .iter()
+            .map(|(name, _)| RadioItem {
+                label: name.to_string(),
+                ..Default::default()
+            })
+            .collect();
+
         // Create network interface radio items (Auto + available interfaces)
         let mut network_options: Vec<RadioItem> = vec![RadioItem {
             label: "Auto".to_string(),
@@ -300,6 +354,19 @@ impl Tray for HT32PanelTray {
                 ..Default::default()
             }
             .into(),
+            SubMenu {
+                label: "IP Address".to_string(),
+                submenu: vec![RadioGroup {
+                    selected: ip_display_selected,
+                    select: Box::new(|tray: &mut Self, index| {
+                        tray.set_ip_display(index);
+                    }),
+                    options: ip_display_options,
+                }
+                .into()],
+                ..Default::default()
+            }
+            .into(),
             SubMenu {
                 label: "LED Theme".to_string(),
                 submenu: vec![RadioGroup {
@@ -313,6 +380,31 @@ impl Tray for HT32PanelTray {
                 ..Default::default()
             }
             .into(),
+            SubMenu {
+                label: "Settings".to_string(),
+                submenu: vec![
+                    CheckmarkItem {
+                        label: "Start Applet on Login".to_string(),
+                        checked: autostart_enabled,
+                        activate: Box::new(move |tray: &mut Self| {
+                            tray.set_autostart(false, !autostart_enabled);
+                        }),
+                        ..Default::default()
+                    }
+                    .into(),
+                    CheckmarkItem {
+                        label: "Start Daemon on Login".to_string(),
+                        checked: daemon_autostart_enabled,
+                        activate: Box::new(move |tray: &mut Self| {
+                            tray.set_autostart(true, !daemon_autostart_enabled);
+                        }),
+                        ..Default::default()
+                    }
+                    .into(),
+                ],
+                ..Default::default()
+            }
+            .into(),
             MenuItem::Separator,
         ];
 
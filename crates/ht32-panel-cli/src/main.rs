@@ -2,14 +2,23 @@
 //!
 //! CLI for controlling the HT32 Panel daemon via D-Bus.
 
+mod setup;
+
+use std::collections::HashMap;
+
 use anyhow::{Context, Result};
 use clap::{Parser, Subcommand};
-use ht32_panel_client::{BusType, DaemonClient};
+use ht32_panel_client::{
+    autostart, Activation, BusType, Color, DaemonClient, DiscoveredPanel, FleetClient,
+    IpDisplayPreference, LedSettings, NodeResult,
+};
+use ht32_panel_hw::{LCD_HEIGHT, LCD_WIDTH};
 use tracing_subscriber::EnvFilter;
 
 #[derive(Clone, Copy, Debug, Default, clap::ValueEnum)]
 enum CliBusType {
-    /// Try session bus first, fall back to system bus
+    /// Use the bus in HT32PANEL_BUS, else try session bus first and fall
+    /// back to system bus
     #[default]
     Auto,
     /// Use session bus (for user services)
@@ -41,6 +50,10 @@ struct Cli {
     #[arg(long, default_value = "auto", value_enum)]
     bus: CliBusType,
 
+    /// Fail instead of starting the daemon through D-Bus activation
+    #[arg(long)]
+    no_start: bool,
+
     #[command(subcommand)]
     command: Commands,
 }
@@ -67,62 +80,319 @@ enum Commands {
         #[command(subcommand)]
         action: ComplicationCommands,
     },
+    /// Overlays drawn on top of every face
+    Overlay {
+        #[command(subcommand)]
+        action: OverlayCommands,
+    },
     /// Save a screenshot of the display
     Screenshot {
-        /// Output file path (default: screenshot.png)
+        /// Output file path; a .jpg or .webp extension selects the format
         #[arg(default_value = "screenshot.png")]
         output: String,
+        /// JPEG quality (1-100); only for .jpg output
+        #[arg(long, value_parser = clap::value_parser!(u8).range(1..=100))]
+        quality: Option<u8>,
+    },
+    /// Switch faces automatically while a value is outside a limit
+    Rules {
+        #[command(subcommand)]
+        action: RuleCommands,
+    },
+    /// Switch built-in sensor collectors on or off
+    Sensor {
+        #[command(subcommand)]
+        action: SensorCommands,
+    },
+    /// Hold back alert banners and LED pulses
+    Dnd {
+        /// on or off (omit to show current)
+        state: Option<String>,
+    },
+    /// Show test patterns to check for dead pixels, colors and orientation
+    TestPattern {
+        /// Pattern: bars, gradient, text, orientation or all
+        #[arg(default_value = "all")]
+        name: String,
+    },
+    /// Wake a LAN host with a Wake-on-LAN packet
+    Wake {
+        /// MAC address or name of a host configured in [sensors.hosts]
+        host: String,
+    },
+    /// Report the status of a job such as a backup
+    Job {
+        /// Job name (e.g. backup)
+        name: String,
+
+        /// Status: running, success or failed
+        status: String,
+
+        /// Progress percentage while running
+        #[arg(long)]
+        progress: Option<f64>,
+    },
+    /// List or run daemon actions
+    Action {
+        #[command(subcommand)]
+        action: ActionCommands,
     },
     /// Daemon control commands
     Daemon {
         #[command(subcommand)]
         action: DaemonCommands,
     },
+    /// Revert the last settings change
+    Undo,
+    /// Reapply the last undone settings change
+    Redo,
+    /// Show recent changes made over D-Bus and the web UI
+    Audit {
+        /// Number of entries to show
+        #[arg(short = 'n', long, default_value = "20")]
+        count: u32,
+    },
+    /// Daemon configuration file commands
+    Config {
+        #[command(subcommand)]
+        action: ConfigCommands,
+    },
+    /// Detect the panel, install udev rules, write a configuration file and
+    /// pick a face and theme
+    Setup,
+    /// Tray applet commands
+    Applet {
+        #[command(subcommand)]
+        action: AppletCommands,
+    },
+    /// Find panels on the LAN that advertise their web UI via mDNS
+    Discover {
+        /// Seconds to listen for answers
+        #[arg(long, default_value = "3")]
+        timeout: u64,
+    },
+    /// Control several panels at once through their web UIs
+    Fleet {
+        /// Web UI URL of a panel, e.g. http://office:8686 (repeatable;
+        /// panels on the LAN are discovered when omitted)
+        #[arg(long = "node", value_name = "URL")]
+        nodes: Vec<String>,
+
+        /// Seconds to listen for panels when discovering
+        #[arg(long, default_value = "3")]
+        timeout: u64,
+
+        #[command(subcommand)]
+        action: FleetCommands,
+    },
+}
+
+#[derive(Subcommand)]
+enum FleetCommands {
+    /// Show the state of every panel
+    Status,
+    /// Set the face on every panel
+    Face {
+        /// Face name
+        face: String,
+    },
+    /// Set the color theme on every panel
+    Theme {
+        /// Theme name
+        theme: String,
+    },
+    /// Set the orientation of every panel
+    Orientation {
+        /// Orientation: landscape, portrait, landscape-upside-down, portrait-upside-down or auto
+        orientation: String,
+    },
+    /// Run an action on every panel
+    Run {
+        /// Action ID (e.g., next_face, toggle_led)
+        id: String,
+        /// Action arguments
+        args: Vec<String>,
+    },
 }
 
 #[derive(Subcommand)]
 enum LcdCommands {
     /// Set display orientation
     Orientation {
-        /// Orientation: landscape, portrait, landscape-upside-down, portrait-upside-down
+        /// Orientation: landscape, portrait, landscape-upside-down, portrait-upside-down or auto
         orientation: String,
     },
     /// Clear the display to a solid color
     Clear {
-        /// Color in hex format (e.g., #FF0000 for red)
+        /// Color as #RRGGBB, #RGB, rgb(r, g, b) or a CSS name (e.g., #FF0000 or red)
         #[arg(long, default_value = "#000000")]
-        color: String,
+        color: Color,
     },
     /// Set or show the current face
     Face {
-        /// Face name (omit to show current)
+        /// Face name, or "random" for a random face and theme (omit to show current)
         face: Option<String>,
     },
+    /// Set or show the daily random face switch
+    DailyRandom {
+        /// Time of day as HH:MM, or "off" (omit to show current)
+        time: Option<String>,
+    },
     /// List available faces
     ListFaces,
+    /// Set or show the label shown instead of the hostname
+    Label {
+        /// Label text (omit to show current)
+        label: Option<String>,
+
+        /// Show the hostname again
+        #[arg(long, conflicts_with = "label")]
+        clear: bool,
+    },
+    /// Set or show post-processing effects applied to each frame
+    Effects {
+        /// Effects in order: invert, grayscale, warm (or warm:0-1), scanlines
+        /// (omit to show current)
+        effects: Vec<String>,
+
+        /// Turn all effects off
+        #[arg(long, conflicts_with = "effects")]
+        none: bool,
+    },
+    /// Set or show mirroring, for panels behind tinted glass or in mirrored
+    /// enclosures
+    Mirror {
+        /// Mirror left to right
+        #[arg(long)]
+        horizontal: bool,
+
+        /// Mirror top to bottom
+        #[arg(long)]
+        vertical: bool,
+
+        /// Turn mirroring off
+        #[arg(long, conflicts_with_all = ["horizontal", "vertical"])]
+        none: bool,
+    },
+    /// Set or show the part of the panel left visible by the case
+    VisibleArea {
+        /// Left edge in landscape panel pixels
+        #[arg(requires_all = ["y", "width", "height"])]
+        x: Option<u32>,
+        /// Top edge
+        y: Option<u32>,
+        /// Width
+        width: Option<u32>,
+        /// Height
+        height: Option<u32>,
+
+        /// Use the whole panel
+        #[arg(long, conflicts_with = "x")]
+        full: bool,
+    },
+    /// Set or show the image drawn behind every face
+    Background {
+        /// Image file (omit to show current)
+        path: Option<String>,
+
+        /// Remove the background image
+        #[arg(long, conflicts_with = "path")]
+        none: bool,
+    },
+    /// Set or show the timezone used on the display
+    Timezone {
+        /// IANA timezone name (e.g. Europe/Berlin), or "local" for the system timezone
+        timezone: Option<String>,
+    },
+    /// Set or show the units used for disk and network rates
+    Units {
+        /// Use 1024-based units (KiB/s, MiB/s)
+        #[arg(long, conflicts_with = "decimal")]
+        binary: bool,
+
+        /// Use 1000-based units (KB/s, MB/s)
+        #[arg(long)]
+        decimal: bool,
+
+        /// Show network throughput in bits (Mbit/s)
+        #[arg(long, conflicts_with = "bytes")]
+        bits: bool,
+
+        /// Show network throughput in bytes (MB/s)
+        #[arg(long)]
+        bytes: bool,
+    },
+    /// Set or show which IP address is displayed
+    Ip {
+        /// Preference: ipv4, ipv6-gua, ipv6-lla, ipv6-ula (omit to show current)
+        preference: Option<IpDisplayPreference>,
+    },
+    /// Set or show the block devices tracked for disk I/O
+    Disks {
+        /// Devices to track, e.g. nvme0n1 sda (omit to show current)
+        devices: Vec<String>,
+
+        /// Return to auto-detecting the primary disk
+        #[arg(long, conflicts_with = "devices")]
+        auto: bool,
+
+        /// List available block devices
+        #[arg(long, conflicts_with_all = ["devices", "auto"])]
+        list: bool,
+    },
+    /// Run a bandwidth test and show the result on the panel
+    Speedtest {
+        /// Wait for the test to finish and print the result
+        #[arg(long)]
+        wait: bool,
+    },
     /// Show device information
     Info,
 }
 
 #[derive(Subcommand)]
 enum LedCommands {
-    /// Set LED theme
+    /// Set the LED theme, intensity and speed; values left out are kept
+    #[command(group(
+        clap::ArgGroup::new("change")
+            .required(true)
+            .multiple(true)
+            .args(["theme", "intensity", "speed", "sync_theme"])
+    ))]
     Set {
-        /// Theme: rainbow, breathing, colors, off, auto
-        theme: String,
+        /// Theme: rainbow, breathing, colors (or static), off, auto
+        theme: Option<String>,
 
-        /// Intensity (1-5)
-        #[arg(long, default_value = "3")]
-        intensity: u8,
+        /// Intensity: 1-5 or a percentage such as 60%
+        #[arg(long)]
+        intensity: Option<String>,
 
-        /// Speed (1-5)
-        #[arg(long, default_value = "3")]
-        speed: u8,
+        /// Speed: 1-5 or a percentage such as 60%
+        #[arg(long)]
+        speed: Option<String>,
+
+        /// Follow the display theme instead of fixed settings
+        #[arg(long, conflicts_with_all = ["theme", "intensity", "speed"])]
+        sync_theme: bool,
     },
     /// Turn off LEDs
     Off,
     /// Show current LED settings
     Status,
+    /// Flash the LEDs as a notification, then restore them
+    Pulse {
+        /// Effect to flash with: rainbow, breathing, colors, auto
+        #[arg(default_value = "colors")]
+        color: String,
+        /// Number of flashes (1-20)
+        #[arg(short, long, default_value = "3")]
+        count: u32,
+    },
+    /// Set or show whether the LEDs follow the display theme
+    FollowTheme {
+        /// on or off (omit to show current)
+        state: Option<String>,
+    },
 }
 
 #[derive(Subcommand)]
@@ -131,6 +401,65 @@ enum DaemonCommands {
     Status,
     /// Request daemon shutdown
     Quit,
+    /// Show frame time percentiles
+    Stats,
+    /// Show uptime, frame count, error counts and memory use
+    Health,
+    /// Change the log level without restarting
+    LogLevel {
+        /// off, error, warn, info, debug, trace, or reset to undo a change
+        level: String,
+        /// Module to change, e.g. ht32_panel_hw (default: everything)
+        #[arg(default_value = "")]
+        target: String,
+    },
+    /// Show recent log messages
+    Logs {
+        /// Number of messages to show
+        #[arg(short = 'n', long, default_value = "50")]
+        count: u32,
+    },
+    /// Print udev rules granting access to the panel
+    UdevRule,
+}
+
+#[derive(Subcommand)]
+enum ConfigCommands {
+    /// Check a configuration file for unknown keys and invalid values
+    Validate {
+        /// Configuration file
+        #[arg(default_value = "/etc/ht32-panel/config.toml")]
+        path: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum AppletCommands {
+    /// Start the applet on login
+    EnableAutostart {
+        /// Also install and start the daemon as a systemd user service
+        #[arg(long)]
+        daemon: bool,
+    },
+    /// Stop starting the applet on login
+    DisableAutostart {
+        /// Also stop and remove the daemon's systemd user service
+        #[arg(long)]
+        daemon: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum ActionCommands {
+    /// List available actions with their arguments
+    List,
+    /// Run an action
+    Run {
+        /// Action ID (e.g., next_face, toggle_led)
+        id: String,
+        /// Action arguments
+        args: Vec<String>,
+    },
 }
 
 #[derive(Subcommand)]
@@ -139,11 +468,34 @@ enum ThemeCommands {
     Show,
     /// Set theme by name
     Set {
-        /// Theme name (default, hacker, solarized-light, solarized-dark, nord, tokyonight)
+        /// Theme name (default, hacker, solarized-light, solarized-dark, nord, tokyonight) or a saved theme
         name: String,
     },
     /// List available themes
     List,
+    /// Show the current theme colors, or change them live without saving
+    Colors {
+        /// Colors to change, as NAME=COLOR (primary, secondary, text, background)
+        colors: Vec<String>,
+    },
+    /// Save the current theme colors as a theme and switch to it
+    Save {
+        /// Theme name
+        name: String,
+    },
+    /// Delete a saved theme
+    Delete {
+        /// Theme name
+        name: String,
+    },
+    /// Save the current face rendered with a theme, without applying it
+    Preview {
+        /// Theme name
+        name: String,
+
+        /// Output file path (default: <name>.png)
+        output: Option<String>,
+    },
 }
 
 #[derive(Subcommand)]
@@ -176,10 +528,104 @@ enum ComplicationCommands {
         /// Value to set
         value: String,
     },
+    /// Move a complication to a corner or the bottom bar, or back into the
+    /// face's layout with "face"
+    Move {
+        /// Complication ID (time, date, hostname, ip_address, cpu_temp,
+        /// uptime, updates, ssh_sessions)
+        id: String,
+        /// Slot: top-left, top-right, bottom-left, bottom-right, bottom-bar
+        /// or face
+        slot: String,
+        /// Text size
+        #[arg(long, default_value = "small", value_parser = ["small", "large"])]
+        size: String,
+    },
     /// List available network interfaces
     ListInterfaces,
 }
 
+#[derive(Subcommand)]
+enum RuleCommands {
+    /// List the face switching rules
+    List,
+    /// Add a rule, e.g. `add cpu arcs --above 80 --for 30`
+    Add {
+        /// Value to watch: cpu, memory, cpu_temp, gpu, gpu_temp or a custom
+        /// metric name
+        when: String,
+        /// Face to switch to while the rule holds
+        face: String,
+        /// Hold while the value is above this
+        #[arg(long)]
+        above: Option<f64>,
+        /// Hold while the value is below this
+        #[arg(long)]
+        below: Option<f64>,
+        /// Seconds the rule must hold before switching
+        #[arg(long = "for", default_value_t = 0)]
+        hold: u64,
+    },
+    /// Remove a rule by its number in `rules list`
+    Remove {
+        /// Rule number
+        number: usize,
+    },
+    /// Return to the rules in the config file
+    Reset,
+}
+
+#[derive(Subcommand)]
+enum OverlayCommands {
+    /// List overlays and where they are shown
+    List,
+    /// Show an overlay on every face, or move it to another slot
+    Add {
+        /// Overlay ID (a movable complication such as time or hostname,
+        /// recording or connection)
+        id: String,
+        /// Slot: top-left, top-right, bottom-left, bottom-right or bottom-bar
+        slot: String,
+        /// Text size
+        #[arg(long, default_value = "small", value_parser = ["small", "large"])]
+        size: String,
+    },
+    /// Hide an overlay
+    Remove {
+        /// Overlay ID
+        id: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum SensorCommands {
+    /// List the collectors and whether they are on
+    List,
+    /// Switch a collector on
+    Enable {
+        /// Collector: temperature, disk, network, processes or sessions
+        name: String,
+    },
+    /// Switch a collector off, hiding the complications showing its data
+    Disable {
+        /// Collector name
+        name: String,
+    },
+    /// Set or show the input read for the CPU temperature
+    Temperature {
+        /// hwmon path, label (e.g. "k10temp Tctl") or chip name (omit to show current)
+        sensor: Option<String>,
+
+        /// Return to the configured or auto-detected input
+        #[arg(long, conflicts_with = "sensor")]
+        auto: bool,
+
+        /// List the temperature inputs with their readings
+        #[arg(long, conflicts_with_all = ["sensor", "auto"])]
+        list: bool,
+    },
+}
+
 #[tokio::main]
 async fn main() -> Result<()> {
     let cli = Cli::parse();
@@ -192,8 +638,30 @@ async fn main() -> Result<()> {
     };
     tracing_subscriber::fmt().with_env_filter(filter).init();
 
-    // Connect to daemon
-    let client = DaemonClient::connect_with_bus(cli.bus.into())
+    // Setup, discovery, fleet control, config checks and applet autostart
+    // work without a local daemon
+    match cli.command {
+        Commands::Setup => return setup::run().await,
+        Commands::Applet { action } => return handle_applet(action),
+        Commands::Discover { timeout } => return handle_discover(timeout).await,
+        Commands::Fleet {
+            nodes,
+            timeout,
+            action,
+        } => return handle_fleet(&nodes, timeout, action).await,
+        Commands::Config { action } => return handle_config(action),
+        _ => {}
+    }
+
+    // Connect to daemon, starting it unless only checking on or stopping it
+    let activation = match cli.command {
+        _ if cli.no_start => Activation::Never,
+        Commands::Daemon {
+            action: DaemonCommands::Status | DaemonCommands::Quit,
+        } => Activation::Never,
+        _ => Activation::IfInstalled,
+    };
+    let client = DaemonClient::connect_with_options(cli.bus.into(), activation)
         .await
         .context("Failed to connect to daemon. Is ht32paneld running?")?;
 
@@ -202,25 +670,211 @@ async fn main() -> Result<()> {
         Commands::Led { action } => handle_led(action, &client).await,
         Commands::Theme { action } => handle_theme(action, &client).await,
         Commands::Complication { action } => handle_complication(action, &client).await,
-        Commands::Screenshot { output } => handle_screenshot(&output, &client).await,
+        Commands::Overlay { action } => handle_overlay(action, &client).await,
+        Commands::Screenshot { output, quality } => {
+            handle_screenshot(&output, quality, &client).await
+        }
+        Commands::Rules { action } => handle_rules(action, &client).await,
+        Commands::Sensor { action } => handle_sensor(action, &client).await,
+        Commands::Dnd { state } => handle_dnd(state, &client).await,
+        Commands::TestPattern { name } => {
+            let seconds = client.run_test_pattern(&name).await?;
+            println!(
+                "Showing test pattern {} for {} seconds, then the face returns",
+                name, seconds
+            );
+            Ok(())
+        }
+        Commands::Wake { host } => {
+            client.wake_host(&host).await?;
+            println!("Wake-on-LAN packet sent to {}", host);
+            Ok(())
+        }
+        Commands::Job {
+            name,
+            status,
+            progress,
+        } => {
+            client.report_job(&name, &status, progress).await?;
+            println!("Reported {} for job {}", status, name);
+            Ok(())
+        }
+        Commands::Action { action } => handle_action(action, &client).await,
         Commands::Daemon { action } => handle_daemon(action, &client).await,
+        Commands::Undo => {
+            println!("Reverted {}", client.undo().await?);
+            Ok(())
+        }
+        Commands::Redo => {
+            println!("Reapplied {}", client.redo().await?);
+            Ok(())
+        }
+        Commands::Audit { count } => {
+            for entry_json in client.get_audit_log(count).await? {
+                if let Ok(entry) = serde_json::from_str::<serde_json::Value>(&entry_json) {
+                    let field = |key: &str| entry[key].as_str().unwrap_or("").to_string();
+                    println!(
+                        "{}  {} {}  {}  {:?} -> {:?}",
+                        field("timestamp"),
+                        field("source"),
+                        field("caller"),
+                        field("method"),
+                        field("old"),
+                        field("new")
+                    );
+                }
+            }
+            Ok(())
+        }
+        Commands::Setup
+        | Commands::Applet { .. }
+        | Commands::Discover { .. }
+        | Commands::Fleet { .. }
+        | Commands::Config { .. } => {
+            unreachable!("handled before connecting")
+        }
+    }
+}
+
+async fn discover_panels(timeout: u64) -> Result<Vec<DiscoveredPanel>> {
+    let timeout = std::time::Duration::from_secs(timeout);
+    tokio::task::spawn_blocking(move || ht32_panel_client::discover(timeout))
+        .await
+        .context("Discovery task failed")?
+}
+
+async fn handle_discover(timeout: u64) -> Result<()> {
+    let panels = discover_panels(timeout).await?;
+    if panels.is_empty() {
+        println!("No panels found");
+        return Ok(());
+    }
+    for panel in panels {
+        let version = panel
+            .version
+            .as_ref()
+            .map(|v| format!(" (v{})", v))
+            .unwrap_or_default();
+        println!("{}{}", panel.name, version);
+        println!(
+            "  {}",
+            panel.url().unwrap_or_else(|| panel.hostname.clone())
+        );
+    }
+    Ok(())
+}
+
+async fn handle_fleet(nodes: &[String], timeout: u64, action: FleetCommands) -> Result<()> {
+    let fleet = if nodes.is_empty() {
+        FleetClient::from_discovered(&discover_panels(timeout).await?)
+    } else {
+        FleetClient::from_urls(nodes)?
+    };
+    if fleet.nodes().is_empty() {
+        anyhow::bail!("No panels found; pass --node URL to name them");
+    }
+
+    let total = fleet.nodes().len();
+    let failed = match action {
+        FleetCommands::Status => {
+            let results = fleet.status().await;
+            report(&results, |status| {
+                format!(
+                    "{} face={} theme={} orientation={} lcd={} (v{})",
+                    status.hostname,
+                    status.face,
+                    status.theme,
+                    status.orientation,
+                    if status.connected {
+                        "connected"
+                    } else {
+                        "disconnected"
+                    },
+                    status.version
+                )
+            })
+        }
+        FleetCommands::Face { face } => report(&fleet.set_face(&face).await, String::clone),
+        FleetCommands::Theme { theme } => report(&fleet.set_theme(&theme).await, String::clone),
+        FleetCommands::Orientation { orientation } => {
+            report(&fleet.set_orientation(&orientation).await, String::clone)
+        }
+        FleetCommands::Run { id, args } => {
+            report(&fleet.run_action(&id, &args).await, String::clone)
+        }
+    };
+    if failed > 0 {
+        anyhow::bail!("{} of {} panels failed", failed, total);
+    }
+    Ok(())
+}
+
+/// Prints one line per node and returns how many failed.
+fn report<T>(results: &[NodeResult<T>], describe: impl Fn(&T) -> String) -> usize {
+    let mut failed = 0;
+    for result in results {
+        match &result.result {
+            Ok(value) => println!("{}: {}", result.node, describe(value)),
+            Err(e) => {
+                println!("{}: error: {:#}", result.node, e);
+                failed += 1;
+            }
+        }
+    }
+    failed
+}
+
+async fn handle_action(action: ActionCommands, client: &DaemonClient) -> Result<()> {
+    match action {
+        ActionCommands::List => {
+            for action_json in client.list_actions().await? {
+                if let Ok(action) = serde_json::from_str::<serde_json::Value>(&action_json) {
+                    let id = action["id"].as_str().unwrap_or("");
+                    let description = action["description"].as_str().unwrap_or("");
+                    let args: Vec<String> = action["args"]
+                        .as_array()
+                        .map(Vec::as_slice)
+                        .unwrap_or_default()
+                        .iter()
+                        .map(|a| format!("<{}>", a["id"].as_str().unwrap_or("")))
+                        .collect();
+                    println!("  {} {}", id, args.join(" "));
+                    println!("      {}", description);
+                }
+            }
+        }
+        ActionCommands::Run { id, args } => {
+            println!("{}", client.invoke_action(&id, args).await?);
+        }
     }
+    Ok(())
 }
 
 async fn handle_lcd(action: LcdCommands, client: &DaemonClient) -> Result<()> {
     match action {
         LcdCommands::Orientation { orientation } => {
             client.set_orientation(&orientation).await?;
-            println!("Orientation set to: {}", orientation);
+            if orientation == "auto" {
+                let detected = client.get_orientation().await?;
+                println!("Orientation set to: auto ({})", detected);
+            } else {
+                println!("Orientation set to: {}", orientation);
+            }
         }
         LcdCommands::Clear { color } => {
-            client.clear_display(&color).await?;
+            client.clear_display(color).await?;
             println!("Display cleared to: {}", color);
         }
         LcdCommands::Face { face } => {
             if let Some(face_name) = face {
                 client.set_face(&face_name).await?;
-                println!("Face set to: {}", face_name);
+                if face_name.eq_ignore_ascii_case("random") {
+                    let face = client.get_face().await?;
+                    let theme = client.get_theme().await?;
+                    println!("Face set to: {} (theme: {})", face, theme);
+                } else {
+                    println!("Face set to: {}", face_name);
+                }
             } else {
                 let current = client.get_face().await?;
                 println!("Current face: {}", current);
@@ -233,51 +887,273 @@ async fn handle_lcd(action: LcdCommands, client: &DaemonClient) -> Result<()> {
                 println!("  {}", face);
             }
         }
+        LcdCommands::DailyRandom { time } => {
+            if let Some(time) = time {
+                client.set_daily_random(&time).await?;
+                if time.eq_ignore_ascii_case("off") {
+                    println!("Daily random face disabled");
+                } else {
+                    println!("Daily random face at: {}", time);
+                }
+            } else {
+                let current = client.get_daily_random().await?;
+                if current.is_empty() {
+                    println!("Daily random face: off");
+                } else {
+                    println!("Daily random face at: {}", current);
+                }
+            }
+        }
+        LcdCommands::Label { label, clear } => {
+            if clear {
+                client.set_display_label("").await?;
+                println!("Label cleared, showing hostname");
+            } else if let Some(label) = label {
+                client.set_display_label(&label).await?;
+                println!("Label set to: {}", label);
+            } else {
+                let current = client.get_display_label().await?;
+                if current.is_empty() {
+                    println!("No label set, showing hostname");
+                } else {
+                    println!("Current label: {}", current);
+                }
+            }
+        }
+        LcdCommands::Effects { effects, none } => {
+            if none {
+                client.set_post_effects(Vec::new()).await?;
+                println!("Post effects off");
+            } else if !effects.is_empty() {
+                client.set_post_effects(effects.clone()).await?;
+                println!("Post effects set to: {}", effects.join(", "));
+            } else {
+                let current = client.get_post_effects().await?;
+                if current.is_empty() {
+                    println!("No post effects");
+                } else {
+                    println!("Post effects: {}", current.join(", "));
+                }
+            }
+        }
+        LcdCommands::Mirror {
+            horizontal,
+            vertical,
+            none,
+        } => {
+            if none || horizontal || vertical {
+                client.set_transform(horizontal, vertical).await?;
+            }
+            let (flip_h, flip_v) = client.get_transform().await?;
+            let mirroring = match (flip_h, flip_v) {
+                (false, false) => "off",
+                (true, false) => "horizontal",
+                (false, true) => "vertical",
+                (true, true) => "horizontal and vertical",
+            };
+            println!("Mirroring: {}", mirroring);
+        }
+        LcdCommands::VisibleArea {
+            x,
+            y,
+            width,
+            height,
+            full,
+        } => {
+            if full {
+                client
+                    .set_visible_area(0, 0, LCD_WIDTH.into(), LCD_HEIGHT.into())
+                    .await?;
+            } else if let (Some(x), Some(y), Some(width), Some(height)) = (x, y, width, height) {
+                client.set_visible_area(x, y, width, height).await?;
+            }
+            let (x, y, width, height) = client.get_visible_area().await?;
+            println!("Visible area: {}x{} at {},{}", width, height, x, y);
+        }
+        LcdCommands::Background { path, none } => {
+            if none {
+                client.set_background_image("").await?;
+                println!("Background image removed");
+            } else if let Some(path) = path {
+                // The daemon runs elsewhere, so pass it an absolute path
+                let path = std::fs::canonicalize(&path)
+                    .with_context(|| format!("Cannot find {}", path))?;
+                let path = path.to_string_lossy();
+                client.set_background_image(&path).await?;
+                println!("Background image set to: {}", path);
+            } else {
+                let current = client.get_background_image().await?;
+                if current.is_empty() {
+                    println!("No background image");
+                } else {
+                    println!("Background image: {}", current);
+                }
+            }
+        }
+        LcdCommands::Timezone { timezone } => {
+            if let Some(timezone) = timezone {
+                client.set_timezone(&timezone).await?;
+                println!("Timezone set to: {}", timezone);
+            } else {
+                let current = client.get_timezone().await?;
+                if current.is_empty() {
+                    println!("Current timezone: system default");
+                } else {
+                    println!("Current timezone: {}", current);
+                }
+            }
+        }
+        LcdCommands::Units {
+            binary,
+            decimal,
+            bits,
+            bytes,
+        } => {
+            let (mut use_binary, mut use_bits) = client.get_rate_units().await?;
+            if binary || decimal || bits || bytes {
+                if binary || decimal {
+                    use_binary = binary;
+                }
+                if bits || bytes {
+                    use_bits = bits;
+                }
+                client.set_rate_units(use_binary, use_bits).await?;
+                println!("Rate units set to: {}", format_units(use_binary, use_bits));
+            } else {
+                println!("Current rate units: {}", format_units(use_binary, use_bits));
+            }
+        }
+        LcdCommands::Ip { preference } => {
+            if let Some(preference) = preference {
+                client.set_ip_display_preference(preference).await?;
+                println!("IP display set to: {}", preference);
+            } else {
+                let current = client.get_ip_display_preference().await?;
+                println!("Current IP display: {}", current);
+            }
+        }
+        LcdCommands::Disks {
+            devices,
+            auto,
+            list,
+        } => {
+            if list {
+                println!("Available disk devices:");
+                for device in client.list_disk_devices().await? {
+                    println!("  {}", device);
+                }
+            } else if auto || !devices.is_empty() {
+                let names: Vec<&str> = devices.iter().map(String::as_str).collect();
+                client.set_disk_devices(&names).await?;
+                if names.is_empty() {
+                    println!("Disk devices set to: auto");
+                } else {
+                    println!("Disk devices set to: {}", names.join(", "));
+                }
+            } else {
+                let current = client.get_disk_devices().await?;
+                if current.is_empty() {
+                    println!("Current disk devices: auto");
+                } else {
+                    println!("Current disk devices: {}", current.join(", "));
+                }
+            }
+        }
+        LcdCommands::Speedtest { wait } => {
+            if wait {
+                println!("Running speedtest...");
+                let (download, upload, ping_ms) = client.run_speedtest_wait().await?;
+                println!("Download: {:.1} Mbit/s", download * 8.0 / 1_000_000.0);
+                if upload > 0.0 {
+                    println!("Upload:   {:.1} Mbit/s", upload * 8.0 / 1_000_000.0);
+                }
+                if ping_ms > 0.0 {
+                    println!("Ping:     {:.0} ms", ping_ms);
+                }
+            } else {
+                client.run_speedtest().await?;
+                println!("Speedtest started; the result will be shown on the panel");
+            }
+        }
         LcdCommands::Info => {
-            let connected = client.is_connected().await?;
-            let orientation = client.get_orientation().await?;
-            let face = client.get_face().await?;
+            let state = client.get_state().await?;
+            let (binary, bits) = client.get_rate_units().await?;
             println!("LCD Status:");
-            println!("  Connected: {}", if connected { "yes" } else { "no" });
-            println!("  Orientation: {}", orientation);
-            println!("  Face: {}", face);
+            println!(
+                "  Connected: {}",
+                if state.connected { "yes" } else { "no" }
+            );
+            println!("  Orientation: {}", state.orientation);
+            println!("  Face: {}", state.face);
+            println!("  IP display: {}", state.ip_display_preference);
+            println!("  Rate units: {}", format_units(binary, bits));
         }
     }
 
     Ok(())
 }
 
+/// Describes rate units for display, e.g. "binary, network in bits".
+/// Draws an LED level as five blocks with its percentage, like
+/// "■■■□□  60%".
+fn level_bar(level: u8) -> String {
+    let filled = usize::from(level.min(5));
+    format!(
+        "{}{} {:>4}",
+        "■".repeat(filled),
+        "□".repeat(5 - filled),
+        format!("{}%", LedSettings::level_percent(level))
+    )
+}
+
+fn format_units(binary: bool, bits: bool) -> String {
+    format!(
+        "{}, network in {}",
+        if binary { "binary" } else { "decimal" },
+        if bits { "bits" } else { "bytes" }
+    )
+}
+
 async fn handle_led(action: LedCommands, client: &DaemonClient) -> Result<()> {
     match action {
         LedCommands::Set {
             theme,
             intensity,
             speed,
+            sync_theme,
         } => {
-            // Convert theme string to byte
-            let theme_byte = match theme.to_lowercase().as_str() {
-                "rainbow" => 1,
-                "breathing" => 2,
-                "colors" => 3,
-                "off" => 4,
-                "auto" => 5,
-                _ => anyhow::bail!(
-                    "Invalid theme: {}. Use: rainbow, breathing, colors, off, auto",
-                    theme
-                ),
-            };
+            if sync_theme {
+                client.set_led_follow_theme(true).await?;
+                let theme = client.get_theme().await?;
+                let settings = client.get_led_settings().await?;
+                println!("LEDs follow the {} theme: {}", theme, settings);
+                return Ok(());
+            }
 
-            if !(1..=5).contains(&intensity) {
-                anyhow::bail!("Intensity must be between 1 and 5");
+            // Start from the current settings so left out values are kept
+            let mut builder = client.get_led_settings().await?.to_builder();
+            if let Some(theme) = theme {
+                builder = builder.theme(LedSettings::theme_from_name(&theme)?);
             }
-            if !(1..=5).contains(&speed) {
-                anyhow::bail!("Speed must be between 1 and 5");
+            if let Some(intensity) = intensity {
+                builder = builder.intensity(LedSettings::parse_level(&intensity)?);
             }
+            if let Some(speed) = speed {
+                builder = builder.speed(LedSettings::parse_level(&speed)?);
+            }
+            let settings = builder.build()?;
 
-            client.set_led(theme_byte, intensity, speed).await?;
+            // Fixed settings would be replaced on the next theme change
+            if client.get_led_follow_theme().await? {
+                client.set_led_follow_theme(false).await?;
+                println!("LEDs no longer follow the display theme");
+            }
+            client.set_led(settings).await?;
             println!(
-                "LED set to: {} (intensity: {}, speed: {})",
-                theme, intensity, speed
+                "LED set to: {} (intensity: {}%, speed: {}%)",
+                settings.theme_name(),
+                LedSettings::level_percent(settings.intensity()),
+                LedSettings::level_percent(settings.speed())
             );
         }
         LedCommands::Off => {
@@ -285,19 +1161,38 @@ async fn handle_led(action: LedCommands, client: &DaemonClient) -> Result<()> {
             println!("LEDs turned off");
         }
         LedCommands::Status => {
-            let (theme, intensity, speed) = client.get_led_settings().await?;
-            let theme_name = match theme {
-                1 => "rainbow",
-                2 => "breathing",
-                3 => "colors",
-                4 => "off",
-                5 => "auto",
-                _ => "unknown",
-            };
+            let settings = client.get_led_settings().await?;
+            let follow = client.get_led_follow_theme().await?;
             println!("LED Status:");
-            println!("  Theme: {}", theme_name);
-            println!("  Intensity: {}", intensity);
-            println!("  Speed: {}", speed);
+            println!("  {:<14}{}", "Theme:", settings.theme_name());
+            if !settings.is_off() {
+                println!("  {:<14}{}", "Intensity:", level_bar(settings.intensity()));
+                println!("  {:<14}{}", "Speed:", level_bar(settings.speed()));
+            }
+            if follow {
+                let theme = client.get_theme().await?;
+                println!("  {:<14}on ({})", "Follow theme:", theme);
+            } else {
+                println!("  {:<14}off", "Follow theme:");
+            }
+        }
+        LedCommands::Pulse { color, count } => {
+            client.pulse_led(&color, count).await?;
+            println!("Pulsing LEDs {} times", count);
+        }
+        LedCommands::FollowTheme { state } => {
+            if let Some(state) = state {
+                let follow = match state.to_lowercase().as_str() {
+                    "on" | "true" | "yes" => true,
+                    "off" | "false" | "no" => false,
+                    _ => anyhow::bail!("Invalid value: {}. Use: on, off", state),
+                };
+                client.set_led_follow_theme(follow).await?;
+                println!("LED follow theme: {}", if follow { "on" } else { "off" });
+            } else {
+                let follow = client.get_led_follow_theme().await?;
+                println!("LED follow theme: {}", if follow { "on" } else { "off" });
+            }
         }
     }
 
@@ -310,13 +1205,155 @@ async fn handle_daemon(action: DaemonCommands, client: &DaemonClient) -> Result<
             let connected = client.is_connected().await?;
             println!("Daemon: running");
             println!("LCD connected: {}", if connected { "yes" } else { "no" });
+            let error = client.connection_error().await?;
+            if !error.is_empty() {
+                println!("  {}", error);
+            }
         }
         DaemonCommands::Quit => {
             client.quit().await?;
             println!("Shutdown request sent to daemon");
         }
+        DaemonCommands::Stats => {
+            let (frames, stages) = client.get_render_stats().await?;
+            println!("Frame times over the last {} frames (ms):", frames);
+            println!(
+                "  {:<8} {:>8} {:>8} {:>8} {:>8}",
+                "stage", "p50", "p95", "p99", "max"
+            );
+            for (stage, p50, p95, p99, max) in stages {
+                println!(
+                    "  {:<8} {:>8.2} {:>8.2} {:>8.2} {:>8.2}",
+                    stage, p50, p95, p99, max
+                );
+            }
+        }
+        DaemonCommands::Health => {
+            let health: serde_json::Value = serde_json::from_str(&client.get_health().await?)?;
+            println!("Status: {}", health["status"].as_str().unwrap_or("unknown"));
+            for problem in health["problems"].as_array().into_iter().flatten() {
+                println!("  {}", problem.as_str().unwrap_or(""));
+            }
+            println!("Version: {}", health["version"].as_str().unwrap_or(""));
+            let features: Vec<&str> = health["capabilities"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(|feature| feature.as_str())
+                .collect();
+            println!(
+                "Features: {}",
+                if features.is_empty() {
+                    "none".to_string()
+                } else {
+                    features.join(", ")
+                }
+            );
+            println!("Uptime: {}s", health["uptime_secs"]);
+            println!("Frames rendered: {}", health["frames_rendered"]);
+            if let Some(secs) = health["last_frame_secs"].as_f64() {
+                println!("Last frame: {:.1}s ago", secs);
+            }
+            println!("USB errors: {}", health["usb_errors"]);
+            println!("Sensor failures: {}", health["sensor_failures"]);
+            if let Some(bytes) = health["memory_bytes"].as_u64() {
+                println!("Memory: {:.1} MiB", bytes as f64 / (1024.0 * 1024.0));
+            }
+            println!(
+                "LCD: {}",
+                match (
+                    health["lcd_connected"].as_bool(),
+                    health["lcd_responding"].as_bool()
+                ) {
+                    (Some(true), Some(true)) => "connected",
+                    (Some(true), _) => "not responding",
+                    _ => "not connected",
+                }
+            );
+        }
+        DaemonCommands::LogLevel { level, target } => {
+            client.set_log_level(&target, &level).await?;
+            if target.is_empty() {
+                println!("Log level set to {}", level);
+            } else {
+                println!("Log level of {} set to {}", target, level);
+            }
+        }
+        DaemonCommands::Logs { count } => {
+            for entry_json in client.get_recent_logs(count).await? {
+                if let Ok(entry) = serde_json::from_str::<serde_json::Value>(&entry_json) {
+                    let field = |key: &str| entry[key].as_str().unwrap_or("").to_string();
+                    let mut line = format!(
+                        "{} {:>5} {}: {}",
+                        field("timestamp"),
+                        field("level"),
+                        field("target"),
+                        field("message")
+                    );
+                    if let Some(fields) = entry["fields"].as_object() {
+                        for (key, value) in fields {
+                            line.push_str(&format!(" {}={}", key, value.as_str().unwrap_or("")));
+                        }
+                    }
+                    println!("{}", line);
+                }
+            }
+        }
+        DaemonCommands::UdevRule => print!("{}", client.generate_udev_rule().await?),
+    }
+
+    Ok(())
+}
+
+/// Checks configuration files with the installed daemon binary, which has
+/// the schema, so no daemon needs to be running.
+fn handle_config(action: ConfigCommands) -> Result<()> {
+    match action {
+        ConfigCommands::Validate { path } => {
+            // Prefer the daemon installed next to this binary
+            let daemon = std::env::current_exe()
+                .ok()
+                .map(|exe| exe.with_file_name("ht32paneld"))
+                .filter(|path| path.exists())
+                .unwrap_or_else(|| "ht32paneld".into());
+            let status = std::process::Command::new(&daemon)
+                .arg("--check-config")
+                .arg(&path)
+                .status()
+                .with_context(|| format!("Failed to run {}", daemon.display()))?;
+            if !status.success() {
+                std::process::exit(status.code().unwrap_or(1));
+            }
+            Ok(())
+        }
     }
+}
 
+fn handle_applet(action: AppletCommands) -> Result<()> {
+    match action {
+        AppletCommands::EnableAutostart { daemon } => {
+            let path = autostart::enable()?;
+            println!("Applet autostart entry written to {}", path.display());
+            if daemon {
+                let path = autostart::enable_daemon()?;
+                println!("Daemon user service installed at {}", path.display());
+            }
+        }
+        AppletCommands::DisableAutostart { daemon } => {
+            if autostart::disable()? {
+                println!("Applet autostart entry removed");
+            } else {
+                println!("Applet autostart was not enabled");
+            }
+            if daemon {
+                if autostart::disable_daemon()? {
+                    println!("Daemon user service removed");
+                } else {
+                    println!("Daemon user service was not installed");
+                }
+            }
+        }
+    }
     Ok(())
 }
 
@@ -337,6 +1374,39 @@ async fn handle_theme(action: ThemeCommands, client: &DaemonClient) -> Result<()
                 println!("  {}", theme);
             }
         }
+        ThemeCommands::Colors { colors } => {
+            if !colors.is_empty() {
+                let colors = colors
+                    .iter()
+                    .map(|pair| {
+                        let (name, value) = pair
+                            .split_once('=')
+                            .with_context(|| format!("Expected NAME=COLOR, got '{}'", pair))?;
+                        Ok((name.to_string(), value.parse::<Color>()?))
+                    })
+                    .collect::<Result<HashMap<_, _>>>()?;
+                client.set_theme_colors(colors).await?;
+            }
+            let mut colors: Vec<_> = client.get_theme_colors().await?.into_iter().collect();
+            colors.sort();
+            for (name, hex) in colors {
+                println!("{:<12} {}", name, hex);
+            }
+        }
+        ThemeCommands::Save { name } => {
+            client.save_current_theme(&name).await?;
+            println!("Theme saved as: {}", name);
+        }
+        ThemeCommands::Delete { name } => {
+            client.delete_theme(&name).await?;
+            println!("Theme deleted: {}", name);
+        }
+        ThemeCommands::Preview { name, output } => {
+            let output = output.unwrap_or_else(|| format!("{}.png", name));
+            let png_data = client.render_theme_preview(&name).await?;
+            std::fs::write(&output, &png_data).context("Failed to write preview file")?;
+            println!("Preview saved to: {}", output);
+        }
     }
 
     Ok(())
@@ -358,7 +1428,17 @@ async fn handle_complication(action: ComplicationCommands, client: &DaemonClient
                         let description = comp["description"].as_str().unwrap_or("");
                         let enabled = comp["enabled"].as_bool().unwrap_or(false);
                         let status = if enabled { "[x]" } else { "[ ]" };
-                        println!("  {} {} - {}", status, id, name);
+                        match comp["slot"].as_str().filter(|s| !s.is_empty()) {
+                            Some(slot) => println!(
+                                "  {} {} - {} (at {}, {})",
+                                status,
+                                id,
+                                name,
+                                slot,
+                                comp["size"].as_str().unwrap_or("small")
+                            ),
+                            None => println!("  {} {} - {}", status, id, name),
+                        }
                         println!("      {}", description);
 
                         // Show options if any
@@ -416,6 +1496,15 @@ async fn handle_complication(action: ComplicationCommands, client: &DaemonClient
                 .await?;
             println!("Set {}.{} = {}", complication, option, value);
         }
+        ComplicationCommands::Move { id, slot, size } => {
+            if slot == "face" {
+                client.set_complication_position(&id, "", "").await?;
+                println!("Moved {} back into the face", id);
+            } else {
+                client.set_complication_position(&id, &slot, &size).await?;
+                println!("Moved {} to {}", id, slot);
+            }
+        }
         ComplicationCommands::ListInterfaces => {
             let interfaces = client.list_network_interfaces().await?;
             println!("Available network interfaces:");
@@ -429,9 +1518,181 @@ async fn handle_complication(action: ComplicationCommands, client: &DaemonClient
     Ok(())
 }
 
-async fn handle_screenshot(output: &str, client: &DaemonClient) -> Result<()> {
-    let png_data = client.get_screen_png().await?;
-    std::fs::write(output, &png_data).context("Failed to write screenshot file")?;
+async fn handle_rules(action: RuleCommands, client: &DaemonClient) -> Result<()> {
+    let mut rules: Vec<serde_json::Value> = serde_json::from_str(&client.get_rules().await?)?;
+    match action {
+        RuleCommands::List => {
+            if rules.is_empty() {
+                println!("No face switching rules");
+            }
+            for (i, rule) in rules.iter().enumerate() {
+                let mut condition = rule["when"].as_str().unwrap_or("").to_string();
+                if let Some(above) = rule["above"].as_f64() {
+                    condition.push_str(&format!(" > {}", above));
+                }
+                if let Some(below) = rule["below"].as_f64() {
+                    condition.push_str(&format!(" < {}", below));
+                }
+                println!(
+                    "  {}. {} for {}s -> {}",
+                    i + 1,
+                    condition,
+                    rule["for"].as_u64().unwrap_or(0),
+                    rule["face"].as_str().unwrap_or("")
+                );
+            }
+        }
+        RuleCommands::Add {
+            when,
+            face,
+            above,
+            below,
+            hold,
+        } => {
+            if above.is_none() && below.is_none() {
+                anyhow::bail!("Set --above or --below");
+            }
+            rules.push(serde_json::json!({
+                "when": when,
+                "above": above,
+                "below": below,
+                "for": hold,
+                "face": face,
+            }));
+            client.set_rules(&serde_json::to_string(&rules)?).await?;
+            println!("Added rule {}", rules.len());
+        }
+        RuleCommands::Remove { number } => {
+            if number == 0 || number > rules.len() {
+                anyhow::bail!("No rule {}; see `ht32panelctl rules list`", number);
+            }
+            rules.remove(number - 1);
+            client.set_rules(&serde_json::to_string(&rules)?).await?;
+            println!("Removed rule {}", number);
+        }
+        RuleCommands::Reset => {
+            client.set_rules("").await?;
+            println!("Face switching rules reset to the config");
+        }
+    }
+    Ok(())
+}
+
+async fn handle_sensor(action: SensorCommands, client: &DaemonClient) -> Result<()> {
+    match action {
+        SensorCommands::List => {
+            println!("Sensors:");
+            for (name, enabled) in client.list_sensors().await? {
+                println!("  [{}] {}", if enabled { "x" } else { " " }, name);
+            }
+        }
+        SensorCommands::Enable { name } => {
+            client.set_sensor_enabled(&name, true).await?;
+            println!("Sensor {} on", name);
+        }
+        SensorCommands::Disable { name } => {
+            client.set_sensor_enabled(&name, false).await?;
+            println!("Sensor {} off", name);
+        }
+        SensorCommands::Temperature { sensor, auto, list } => {
+            if list {
+                println!("Temperature sensors:");
+                for (label, path, celsius, active) in client.list_temperature_sensors().await? {
+                    println!(
+                        "  {} {:<24} {:>5.1}°C  {}",
+                        if active { "*" } else { " " },
+                        label,
+                        celsius,
+                        path
+                    );
+                }
+            } else if auto || sensor.is_some() {
+                let sensor = sensor.unwrap_or_default();
+                client.set_temperature_sensor(&sensor).await?;
+                if sensor.is_empty() {
+                    println!("Temperature sensor set to: auto");
+                } else {
+                    println!("Temperature sensor set to: {}", sensor);
+                }
+            } else {
+                let current = client.get_temperature_sensor().await?;
+                if current.is_empty() {
+                    println!("Current temperature sensor: auto");
+                } else {
+                    println!("Current temperature sensor: {}", current);
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+async fn handle_dnd(state: Option<String>, client: &DaemonClient) -> Result<()> {
+    if let Some(state) = state {
+        let on = match state.to_lowercase().as_str() {
+            "on" | "true" | "yes" => true,
+            "off" | "false" | "no" => false,
+            _ => anyhow::bail!("Invalid value: {}. Use: on, off", state),
+        };
+        client.set_dnd(on).await?;
+        println!("Do-not-disturb: {}", if on { "on" } else { "off" });
+    } else {
+        let (on, active) = client.get_dnd().await?;
+        let status = match (on, active) {
+            (true, _) => "on",
+            (false, true) => "on (scheduled)",
+            (false, false) => "off",
+        };
+        println!("Do-not-disturb: {}", status);
+    }
+    Ok(())
+}
+
+async fn handle_overlay(action: OverlayCommands, client: &DaemonClient) -> Result<()> {
+    match action {
+        OverlayCommands::List => {
+            println!("Overlays:");
+            for (id, slot, size) in client.list_overlays().await? {
+                if slot.is_empty() {
+                    println!("  [ ] {}", id);
+                } else {
+                    println!("  [x] {} (at {}, {})", id, slot, size);
+                }
+            }
+        }
+        OverlayCommands::Add { id, slot, size } => {
+            client.set_overlay(&id, &slot, &size).await?;
+            println!("Showing {} at {}", id, slot);
+        }
+        OverlayCommands::Remove { id } => {
+            client.remove_overlay(&id).await?;
+            println!("Removed overlay {}", id);
+        }
+    }
+
+    Ok(())
+}
+
+async fn handle_screenshot(output: &str, quality: Option<u8>, client: &DaemonClient) -> Result<()> {
+    let extension = std::path::Path::new(output)
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or_default()
+        .to_lowercase();
+    let jpeg = matches!(extension.as_str(), "jpg" | "jpeg");
+    if quality.is_some() && !jpeg {
+        anyhow::bail!("--quality only applies to JPEG screenshots (.jpg output)");
+    }
+    let data = match extension.as_str() {
+        "jpg" | "jpeg" => {
+            client
+                .get_screen_image("jpeg", quality.unwrap_or(0))
+                .await?
+        }
+        "webp" => client.get_screen_image("webp", 0).await?,
+        _ => client.get_screen_png().await?,
+    };
+    std::fs::write(output, &data).context("Failed to write screenshot file")?;
     println!("Screenshot saved to: {}", output);
     Ok(())
 }
@@ -0,0 +1,364 @@
+//! First-run setup wizard.
+//!
+//! Walks through detecting the panel, installing the udev rules and writing
+//! a configuration file, then picks the network interface, face and theme
+//! on the running daemon.
+
+use std::fs;
+use std::io::{self, BufRead, Write};
+use std::os::unix::fs::MetadataExt;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use ht32_panel_client::{BusType, DaemonClient};
+use ht32_panel_hw::access::{self, Access, LED_PID, LED_SYMLINK, LED_VID};
+use ht32_panel_hw::{LCD_PID, LCD_VID};
+
+/// Where the udev rules are installed.
+const UDEV_RULES_PATH: &str = "/etc/udev/rules.d/99-ht32-panel.rules";
+
+/// Runs the setup wizard.
+pub async fn run() -> Result<()> {
+    let root = is_root();
+    println!("HT32 Panel setup\n");
+
+    // Device detection
+    println!("Looking for the panel...");
+    let lcd = find_device_node(Path::new("/sys/class/hidraw"), (LCD_VID, LCD_PID));
+    let led = find_device_node(Path::new("/sys/class/tty"), (LED_VID, LED_PID));
+    report_device("LCD", lcd.as_deref());
+    report_device("LED controller", led.as_deref());
+    if lcd.is_none() {
+        println!("  Check that the panel is plugged in; setup continues without it.");
+    }
+    println!();
+
+    // udev rules
+    let rules_installed = Path::new(UDEV_RULES_PATH).exists();
+    if rules_installed {
+        println!("udev rules are installed at {}", UDEV_RULES_PATH);
+    } else if root {
+        if confirm(&format!("Install udev rules to {}?", UDEV_RULES_PATH), true)? {
+            match install_udev_rules() {
+                Ok(()) => println!("Installed. Add the daemon's user to the plugdev group."),
+                Err(e) => println!("Could not install udev rules: {:#}", e),
+            }
+        }
+    } else {
+        println!("Without udev rules only root can open the panel. Install them with:\n");
+        println!(
+            "sudo tee {} <<'EOF'\n{}EOF",
+            UDEV_RULES_PATH,
+            access::udev_rules(access::DEFAULT_GROUP)
+        );
+        println!("sudo udevadm control --reload-rules && sudo udevadm trigger");
+        println!("sudo usermod -aG plugdev $USER\n");
+    }
+    println!();
+
+    // Configuration file
+    let bus = if root { "system" } else { "session" };
+    let default_path = default_config_path(root);
+    let path = PathBuf::from(prompt("Configuration file", &default_path)?);
+    let write =
+        !path.exists() || confirm(&format!("{} exists. Replace it?", path.display()), false)?;
+    if write {
+        let web = confirm("Enable the web UI?", false)?;
+        let listen = if web {
+            prompt("Web UI listen address", "0.0.0.0:8686")?
+        } else {
+            "[::1]:8686".to_string()
+        };
+        let led_path = if rules_installed || Path::new(LED_SYMLINK).exists() {
+            LED_SYMLINK.to_string()
+        } else {
+            led.unwrap_or_else(|| "/dev/ttyUSB0".to_string())
+        };
+        write_config(&path, web, &listen, &led_path, bus)?;
+        println!("Wrote {}", path.display());
+    }
+    println!();
+
+    // Settings stored by the daemon
+    let bus_type = if root {
+        BusType::System
+    } else {
+        BusType::Session
+    };
+    let Some(client) = connect_daemon(bus_type, &path).await? else {
+        println!("Skipped face and theme selection. Run `ht32panelctl setup` again later.");
+        return Ok(());
+    };
+    choose_settings(&client).await?;
+
+    println!("\nSetup complete.");
+    Ok(())
+}
+
+/// Picks the network interface, face and theme on the running daemon.
+async fn choose_settings(client: &DaemonClient) -> Result<()> {
+    let mut interfaces = vec!["auto".to_string()];
+    interfaces.extend(client.list_network_interfaces().await?);
+    let interface = &interfaces[choose("Network interface", &interfaces, 0)?];
+    client.set_network_interface(interface).await?;
+    println!("Network interface set to {}", interface);
+
+    // Faces are previewed on the panel itself
+    let faces = client.list_faces().await?;
+    let names: Vec<String> = faces.iter().map(|f| f.display_name.clone()).collect();
+    let current = client.get_face().await?;
+    let mut default = faces.iter().position(|f| f.id == current).unwrap_or(0);
+    loop {
+        let choice = choose("Face", &names, default)?;
+        client.set_face(&faces[choice].id).await?;
+        if confirm(
+            &format!("{} is now on the panel. Keep it?", names[choice]),
+            true,
+        )? {
+            break;
+        }
+        default = choice;
+    }
+
+    // Themes are previewed as images of the chosen face
+    let themes = client.list_themes().await?;
+    let preview_dir = std::env::temp_dir().join("ht32-panel-previews");
+    fs::create_dir_all(&preview_dir).context("Failed to create preview directory")?;
+    for theme in &themes {
+        let png = client.render_theme_preview(theme).await?;
+        fs::write(preview_dir.join(format!("{}.png", theme)), png)
+            .context("Failed to write theme preview")?;
+    }
+    println!("\nTheme previews saved to {}", preview_dir.display());
+    let current = client.get_theme().await?;
+    let default = themes.iter().position(|t| *t == current).unwrap_or(0);
+    let theme = &themes[choose("Theme", &themes, default)?];
+    client.set_theme(theme).await?;
+    Ok(())
+}
+
+/// Connects to the daemon, asking the user to start it if it is not
+/// running. Returns None if the user skips this step.
+async fn connect_daemon(bus: BusType, config: &Path) -> Result<Option<DaemonClient>> {
+    loop {
+        if let Ok(client) = DaemonClient::connect_with_bus(bus).await {
+            if client.is_connected().await.is_ok() {
+                return Ok(Some(client));
+            }
+        }
+        println!("The daemon is not running. Start it with:\n");
+        println!("  ht32paneld {}\n", config.display());
+        if prompt("Press Enter once it is running, or type skip", "")? == "skip" {
+            return Ok(None);
+        }
+        tokio::time::sleep(Duration::from_millis(500)).await;
+    }
+}
+
+/// Writes an initial configuration file.
+fn write_config(path: &Path, web: bool, listen: &str, led: &str, bus: &str) -> Result<()> {
+    if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+        fs::create_dir_all(dir).context("Failed to create configuration directory")?;
+    }
+    let content = format!(
+        "# Written by `ht32panelctl setup`. See config/default.toml for all options.\n\
+         \n\
+         [web]\n\
+         enable = {}\n\
+         listen = \"{}\"\n\
+         \n\
+         [devices]\n\
+         lcd = \"auto\"\n\
+         led = \"{}\"\n\
+         \n\
+         [dbus]\n\
+         bus = \"{}\"\n",
+        web, listen, led, bus
+    );
+    fs::write(path, content).context("Failed to write configuration file")
+}
+
+/// Installs the udev rules and applies them to connected devices.
+fn install_udev_rules() -> Result<()> {
+    fs::write(UDEV_RULES_PATH, access::udev_rules(access::DEFAULT_GROUP))
+        .context("Failed to write udev rules")?;
+    for args in [&["control", "--reload-rules"][..], &["trigger"][..]] {
+        let status = Command::new("udevadm")
+            .args(args)
+            .status()
+            .context("Failed to run udevadm")?;
+        if !status.success() {
+            anyhow::bail!("udevadm {} failed", args.join(" "));
+        }
+    }
+    Ok(())
+}
+
+/// Finds the /dev node in sysfs `class` whose USB device has the vendor and
+/// product ID `usb_id`.
+fn find_device_node(class: &Path, usb_id: (u16, u16)) -> Option<String> {
+    fs::read_dir(class).ok()?.flatten().find_map(|entry| {
+        let device = fs::canonicalize(entry.path().join("device")).ok()?;
+        device.ancestors().find_map(|dir| {
+            let hex = |name: &str| {
+                let id = fs::read_to_string(dir.join(name)).ok()?;
+                u16::from_str_radix(id.trim(), 16).ok()
+            };
+            (hex("idVendor")? == usb_id.0 && hex("idProduct")? == usb_id.1)
+                .then(|| format!("/dev/{}", entry.file_name().to_string_lossy()))
+        })
+    })
+}
+
+/// Prints whether a device was found and can be opened.
+fn report_device(name: &str, node: Option<&str>) {
+    match node.map(access::serial_access) {
+        Some(Access::Granted(path)) => println!("  {}: {}", name, path.display()),
+        Some(Access::Denied { path, detail }) => {
+            println!(
+                "  {}: {} (no permission to open it: {})",
+                name,
+                path.display(),
+                detail
+            )
+        }
+        Some(Access::Missing) | None => println!("  {}: not found", name),
+    }
+}
+
+/// Returns the configuration file path offered by default.
+fn default_config_path(root: bool) -> String {
+    if root {
+        return "/etc/ht32-panel/config.toml".to_string();
+    }
+    match ht32_panel_client::config_home() {
+        Some(config) => format!("{}/ht32-panel/config.toml", config.display()),
+        None => "config.toml".to_string(),
+    }
+}
+
+/// Whether the wizard runs as root.
+fn is_root() -> bool {
+    fs::metadata("/proc/self").is_ok_and(|m| m.uid() == 0)
+}
+
+/// Asks a question, returning the answer or `default` if it is empty.
+fn prompt(question: &str, default: &str) -> Result<String> {
+    if default.is_empty() {
+        print!("{}: ", question);
+    } else {
+        print!("{} [{}]: ", question, default);
+    }
+    io::stdout().flush()?;
+    let mut answer = String::new();
+    if io::stdin().lock().read_line(&mut answer)? == 0 {
+        anyhow::bail!("Setup cancelled");
+    }
+    let answer = answer.trim();
+    Ok(if answer.is_empty() { default } else { answer }.to_string())
+}
+
+/// Asks a yes/no question.
+fn confirm(question: &str, default: bool) -> Result<bool> {
+    let hint = if default { "Y/n" } else { "y/N" };
+    loop {
+        match prompt(&format!("{} ({})", question, hint), "")?
+            .to_lowercase()
+            .as_str()
+        {
+            "" => return Ok(default),
+            "y" | "yes" => return Ok(true),
+            "n" | "no" => return Ok(false),
+            _ => println!("Please answer y or n"),
+        }
+    }
+}
+
+/// Asks the user to pick one of `options` by number, returning its index.
+fn choose(title: &str, options: &[String], default: usize) -> Result<usize> {
+    if options.is_empty() {
+        anyhow::bail!("No {} to choose from", title.to_lowercase());
+    }
+    println!("\n{}:", title);
+    for (i, option) in options.iter().enumerate() {
+        println!("  {:>2}) {}", i + 1, option);
+    }
+    loop {
+        let answer = prompt("Choice", &(default + 1).to_string())?;
+        match parse_choice(&answer, options.len()) {
+            Some(index) => return Ok(index),
+            None => println!("Enter a number from 1 to {}", options.len()),
+        }
+    }
+}
+
+/// Parses a 1-based choice among `len` options into an index.
+fn parse_choice(answer: &str, len: usize) -> Option<usize> {
+    answer
+        .parse::<usize>()
+        .ok()
+        .filter(|n| (1..=len).contains(n))
+        .map(|n| n - 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_choice() {
+        assert_eq!(parse_choice("1", 3), Some(0));
+        assert_eq!(parse_choice("3", 3), Some(2));
+        assert_eq!(parse_choice("0", 3), None);
+        assert_eq!(parse_choice("4", 3), None);
+        assert_eq!(parse_choice("two", 3), None);
+        assert_eq!(parse_choice("1", 0), None);
+    }
+
+    #[test]
+    fn test_choose_empty() {
+        assert!(choose("Themes", &[], 0).is_err());
+    }
+
+    #[test]
+    fn test_default_config_path() {
+        assert_eq!(default_config_path(true), "/etc/ht32-panel/config.toml");
+        assert!(default_config_path(false).ends_with("config.toml"));
+    }
+
+    #[test]
+    fn test_write_config() {
+        let dir = std::env::temp_dir().join(format!("ht32-setup-{}", std::process::id()));
+        let path = dir.join("nested").join("config.toml");
+        write_config(&path, true, "0.0.0.0:8686", LED_SYMLINK, "session").unwrap();
+        let content = fs::read_to_string(&path).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert!(content.contains("enable = true\n"));
+        assert!(content.contains("listen = \"0.0.0.0:8686\"\n"));
+        assert!(content.contains("led = \"/dev/ht32-led\"\n"));
+        assert!(content.contains("bus = \"session\"\n"));
+    }
+
+    #[test]
+    fn test_find_device_node() {
+        let root = std::env::temp_dir().join(format!("ht32-sysfs-{}", std::process::id()));
+        let usb = root.join("devices/usb1/1-1");
+        let interface = usb.join("1-1:1.0");
+        fs::create_dir_all(&interface).unwrap();
+        fs::write(usb.join("idVendor"), "04d9\n").unwrap();
+        fs::write(usb.join("idProduct"), "fd01\n").unwrap();
+        let class = root.join("class");
+        fs::create_dir_all(class.join("hidraw0")).unwrap();
+        std::os::unix::fs::symlink(&interface, class.join("hidraw0/device")).unwrap();
+
+        let lcd = find_device_node(&class, (LCD_VID, LCD_PID));
+        let led = find_device_node(&class, (LED_VID, LED_PID));
+        fs::remove_dir_all(&root).unwrap();
+
+        assert_eq!(lcd.as_deref(), Some("/dev/hidraw0"));
+        assert_eq!(led, None);
+    }
+}
@@ -0,0 +1,250 @@
+//! Starting the applet, and optionally the daemon, on login.
+//!
+//! Shared by the applet's settings menu and `ht32panelctl applet`. The
+//! applet starts from an XDG autostart entry. The daemon runs as a
+//! systemd user service on the session bus, as the Home Manager module
+//! sets it up, reading the configuration written by `ht32panelctl setup`.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use anyhow::{bail, Context, Result};
+use tracing::info;
+
+/// File name of the applet's autostart entry.
+const APPLET_ENTRY: &str = "ht32-panel-applet.desktop";
+
+/// Applet executable name, looked up next to the running program and in
+/// PATH.
+const APPLET_BINARY: &str = "ht32-panel-applet";
+
+/// Name of the daemon's systemd user unit.
+const DAEMON_UNIT: &str = "ht32paneld.service";
+
+/// Daemon executable name, looked up next to the running program and in
+/// PATH.
+const DAEMON_BINARY: &str = "ht32paneld";
+
+/// Returns the user's configuration directory.
+fn user_config_dir() -> Result<PathBuf> {
+    crate::config_home().context("Neither XDG_CONFIG_HOME nor HOME is set")
+}
+
+/// Path of the applet's autostart entry.
+fn entry_path() -> Result<PathBuf> {
+    Ok(user_config_dir()?.join("autostart").join(APPLET_ENTRY))
+}
+
+/// Path of the daemon's user unit.
+fn unit_path() -> Result<PathBuf> {
+    Ok(user_config_dir()?
+        .join("systemd")
+        .join("user")
+        .join(DAEMON_UNIT))
+}
+
+/// Path of the daemon configuration the user unit starts with.
+fn daemon_config_path() -> Result<PathBuf> {
+    Ok(user_config_dir()?.join("ht32-panel").join("config.toml"))
+}
+
+/// Returns the applet executable to autostart: the AppImage itself when
+/// running from one, since the mounted image moves on every launch.
+fn applet_executable() -> Result<PathBuf> {
+    if let Some(appimage) = std::env::var_os("APPIMAGE") {
+        return Ok(PathBuf::from(appimage));
+    }
+    find_executable(APPLET_BINARY)
+}
+
+/// Returns `name` installed next to the running program, or the first one
+/// in PATH.
+fn find_executable(name: &str) -> Result<PathBuf> {
+    let beside = std::env::current_exe()
+        .ok()
+        .and_then(|exe| Some(exe.parent()?.join(name)));
+    let in_path = std::env::var_os("PATH")
+        .into_iter()
+        .flat_map(|path| std::env::split_paths(&path).collect::<Vec<_>>())
+        .map(|dir| dir.join(name));
+    beside
+        .into_iter()
+        .chain(in_path)
+        .find(|path| path.is_file())
+        .with_context(|| format!("{} not found next to this program or in PATH", name))
+}
+
+/// Quotes a path for the Exec key of a desktop entry.
+fn desktop_quote(path: &Path) -> String {
+    let mut quoted = String::from("\"");
+    for c in path.to_string_lossy().chars() {
+        match c {
+            '"' | '`' | '$' | '\\' => {
+                quoted.push('\\');
+                quoted.push(c);
+            }
+            // Field codes such as %f start with a percent sign
+            '%' => quoted.push_str("%%"),
+            _ => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Quotes a path for ExecStart in a systemd unit.
+fn systemd_quote(path: &Path) -> String {
+    let mut quoted = String::from("\"");
+    for c in path.to_string_lossy().chars() {
+        match c {
+            '"' | '\\' => {
+                quoted.push('\\');
+                quoted.push(c);
+            }
+            // Specifiers and environment variables
+            '%' => quoted.push_str("%%"),
+            '$' => quoted.push_str("$$"),
+            _ => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Returns the autostart entry starting `applet`.
+fn desktop_entry(applet: &Path) -> String {
+    format!(
+        "[Desktop Entry]\n\
+         Type=Application\n\
+         Name=HT32 Panel Applet\n\
+         Comment=System tray applet for HT32 Panel control\n\
+         Exec={}\n\
+         Icon=display-brightness-symbolic\n\
+         Categories=System;Monitor;\n\
+         StartupNotify=false\n\
+         X-GNOME-Autostart-enabled=true\n",
+        desktop_quote(applet)
+    )
+}
+
+/// Returns the user unit running `daemon` with `config`.
+fn daemon_unit(daemon: &Path, config: &Path) -> String {
+    format!(
+        "[Unit]\n\
+         Description=HT32 Panel Daemon\n\
+         After=graphical-session-pre.target\n\
+         PartOf=graphical-session.target\n\
+         \n\
+         [Service]\n\
+         Type=simple\n\
+         ExecStart={} --mode user {}\n\
+         Restart=on-failure\n\
+         RestartSec=5\n\
+         StateDirectory=ht32-panel\n\
+         \n\
+         [Install]\n\
+         WantedBy=graphical-session.target\n",
+        systemd_quote(daemon),
+        systemd_quote(config)
+    )
+}
+
+/// Runs `systemctl --user` with `args`.
+fn systemctl(args: &[&str]) -> Result<()> {
+    let status = Command::new("systemctl")
+        .arg("--user")
+        .args(args)
+        .status()
+        .context("Failed to run systemctl")?;
+    if !status.success() {
+        bail!("systemctl --user {} failed ({})", args.join(" "), status);
+    }
+    Ok(())
+}
+
+/// Whether the applet starts on login.
+pub fn is_enabled() -> bool {
+    entry_path().is_ok_and(|path| path.exists())
+}
+
+/// Whether the daemon user unit is installed.
+pub fn is_daemon_enabled() -> bool {
+    unit_path().is_ok_and(|path| path.exists())
+}
+
+/// Writes the applet's autostart entry.
+pub fn enable() -> Result<PathBuf> {
+    let path = entry_path()?;
+    let entry = desktop_entry(&applet_executable()?);
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    }
+    fs::write(&path, entry).with_context(|| format!("Failed to write {}", path.display()))?;
+    info!("Applet autostart enabled: {}", path.display());
+    Ok(path)
+}
+
+/// Removes the applet's autostart entry. Returns whether there was one.
+pub fn disable() -> Result<bool> {
+    let path = entry_path()?;
+    if !path.exists() {
+        return Ok(false);
+    }
+    fs::remove_file(&path).with_context(|| format!("Failed to remove {}", path.display()))?;
+    info!("Applet autostart disabled");
+    Ok(true)
+}
+
+/// Installs the daemon user unit, then enables and starts it.
+pub fn enable_daemon() -> Result<PathBuf> {
+    let config = daemon_config_path()?;
+    if !config.exists() {
+        bail!(
+            "No daemon configuration at {}; run `ht32panelctl setup` first",
+            config.display()
+        );
+    }
+    let path = unit_path()?;
+    let unit = daemon_unit(&find_executable(DAEMON_BINARY)?, &config);
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    }
+    fs::write(&path, unit).with_context(|| format!("Failed to write {}", path.display()))?;
+    systemctl(&["daemon-reload"])?;
+    systemctl(&["enable", "--now", DAEMON_UNIT])?;
+    info!("Daemon user service enabled: {}", path.display());
+    Ok(path)
+}
+
+/// Stops and removes the daemon user unit. Returns whether there was one.
+pub fn disable_daemon() -> Result<bool> {
+    let path = unit_path()?;
+    if !path.exists() {
+        return Ok(false);
+    }
+    systemctl(&["disable", "--now", DAEMON_UNIT])?;
+    fs::remove_file(&path).with_context(|| format!("Failed to remove {}", path.display()))?;
+    systemctl(&["daemon-reload"])?;
+    info!("Daemon user service disabled");
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_entry_and_unit_quote_paths() {
+        let applet = Path::new("/opt/My Apps/ht32-panel-100%.AppImage");
+        assert!(desktop_entry(applet).contains("Exec=\"/opt/My Apps/ht32-panel-100%%.AppImage\"\n"));
+
+        let unit = daemon_unit(
+            Path::new("/usr/bin/ht32paneld"),
+            Path::new("/home/me/$HOME/config.toml"),
+        );
+        assert!(unit.contains(
+            "ExecStart=\"/usr/bin/ht32paneld\" --mode user \"/home/me/$$HOME/config.toml\"\n"
+        ));
+    }
+}
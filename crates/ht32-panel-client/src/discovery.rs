@@ -0,0 +1,84 @@
+//! Discovery of panel daemons on the LAN via mDNS.
+//!
+//! Daemons with the web UI enabled advertise a `_ht32panel._tcp` service.
+
+use std::collections::BTreeMap;
+use std::net::IpAddr;
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result};
+use ht32_panel_types::SERVICE_TYPE;
+use mdns_sd::{ServiceDaemon, ServiceEvent};
+use tracing::debug;
+
+/// A daemon found on the network.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiscoveredPanel {
+    /// Service instance name (the daemon's hostname)
+    pub name: String,
+    /// mDNS host name, e.g. "office.local."
+    pub hostname: String,
+    /// Addresses the web UI answers on
+    pub addresses: Vec<IpAddr>,
+    /// Web UI port
+    pub port: u16,
+    /// Daemon version, if advertised
+    pub version: Option<String>,
+}
+
+impl DiscoveredPanel {
+    /// Returns the web UI URL, preferring an IPv4 address.
+    pub fn url(&self) -> Option<String> {
+        let addr = self
+            .addresses
+            .iter()
+            .find(|a| a.is_ipv4())
+            .or_else(|| self.addresses.first())?;
+        Some(match addr {
+            IpAddr::V4(v4) => format!("http://{}:{}/", v4, self.port),
+            IpAddr::V6(v6) => format!("http://[{}]:{}/", v6, self.port),
+        })
+    }
+}
+
+/// Browses the network for `timeout` and returns the panels found, sorted by
+/// name. Blocks the calling thread for the whole timeout.
+pub fn discover(timeout: Duration) -> Result<Vec<DiscoveredPanel>> {
+    let daemon = ServiceDaemon::new().context("Failed to start mDNS browser")?;
+    let events = daemon
+        .browse(SERVICE_TYPE)
+        .context("Failed to browse for panels")?;
+
+    let mut found = BTreeMap::new();
+    let deadline = Instant::now() + timeout;
+    while let Some(remaining) = deadline.checked_duration_since(Instant::now()) {
+        let Ok(event) = events.recv_timeout(remaining) else {
+            break;
+        };
+        if let ServiceEvent::ServiceResolved(info) = event {
+            debug!("Resolved {}", info.get_fullname());
+            let name = info
+                .get_fullname()
+                .strip_suffix(SERVICE_TYPE)
+                .unwrap_or(info.get_fullname())
+                .trim_end_matches('.')
+                .to_string();
+            let mut addresses: Vec<IpAddr> = info.get_addresses().iter().copied().collect();
+            addresses.sort();
+            found.insert(
+                name.clone(),
+                DiscoveredPanel {
+                    name,
+                    hostname: info.get_hostname().to_string(),
+                    addresses,
+                    port: info.get_port(),
+                    version: info.get_property_val_str("version").map(str::to_string),
+                },
+            );
+        }
+    }
+
+    let _ = daemon.stop_browse(SERVICE_TYPE);
+    let _ = daemon.shutdown();
+    Ok(found.into_values().collect())
+}
@@ -0,0 +1,309 @@
+//! Control of several daemons at once over their web UI's JSON API.
+//!
+//! Each node is a daemon's web UI base URL, either configured or found with
+//! [`discover`](crate::discover). Operations run on every node concurrently
+//! and report a result per node, so one unreachable panel does not stop the
+//! rest from being updated.
+
+use anyhow::{anyhow, bail, Context, Result};
+use tokio::task::JoinSet;
+use tracing::debug;
+
+use crate::http::{self, url_encode};
+use crate::DiscoveredPanel;
+
+/// A daemon in the fleet.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FleetNode {
+    /// Name used in reports
+    pub name: String,
+    /// Web UI base URL, e.g. "http://office.local:8686/"
+    pub url: String,
+}
+
+impl FleetNode {
+    /// Creates a node from a web UI URL, named after its host. A URL without
+    /// a scheme gets `http://`.
+    pub fn from_url(url: &str) -> Result<Self> {
+        let url = if url.contains("://") {
+            url.to_string()
+        } else {
+            format!("http://{}", url)
+        };
+        let (host, _, _) = http::parse_url(&url)?;
+        Ok(Self { name: host, url })
+    }
+}
+
+/// The outcome of an operation on one node.
+#[derive(Debug)]
+pub struct NodeResult<T> {
+    /// Node name
+    pub node: String,
+    /// Value returned by the node, or why it failed
+    pub result: Result<T>,
+}
+
+/// State reported by a node.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NodeStatus {
+    /// Hostname of the machine running the daemon
+    pub hostname: String,
+    /// Daemon version
+    pub version: String,
+    /// Whether the LCD is connected
+    pub connected: bool,
+    /// Current face
+    pub face: String,
+    /// Current color theme
+    pub theme: String,
+    /// Current orientation
+    pub orientation: String,
+}
+
+/// Client for a group of daemons.
+#[derive(Debug, Clone, Default)]
+pub struct FleetClient {
+    nodes: Vec<FleetNode>,
+}
+
+impl FleetClient {
+    /// Creates a client for the given nodes.
+    pub fn new(nodes: Vec<FleetNode>) -> Self {
+        Self { nodes }
+    }
+
+    /// Creates a client from web UI URLs.
+    pub fn from_urls<S: AsRef<str>>(urls: &[S]) -> Result<Self> {
+        let nodes = urls
+            .iter()
+            .map(|url| FleetNode::from_url(url.as_ref()))
+            .collect::<Result<_>>()?;
+        Ok(Self::new(nodes))
+    }
+
+    /// Creates a client for panels found on the LAN. Panels advertising no
+    /// address are skipped.
+    pub fn from_discovered(panels: &[DiscoveredPanel]) -> Self {
+        let nodes = panels
+            .iter()
+            .filter_map(|panel| {
+                Some(FleetNode {
+                    name: panel.name.clone(),
+                    url: panel.url()?,
+                })
+            })
+            .collect();
+        Self::new(nodes)
+    }
+
+    /// Returns the nodes in the fleet.
+    pub fn nodes(&self) -> &[FleetNode] {
+        &self.nodes
+    }
+
+    /// Gets the state of every node.
+    pub async fn status(&self) -> Vec<NodeResult<NodeStatus>> {
+        self.each(|url| async move {
+            let body = request("GET", &format!("{}api/status", url)).await?;
+            let json: serde_json::Value =
+                serde_json::from_str(&body).context("Invalid status response")?;
+            let field = |key: &str| json[key].as_str().unwrap_or_default().to_string();
+            Ok(NodeStatus {
+                hostname: field("hostname"),
+                version: field("version"),
+                connected: json["connected"].as_bool().unwrap_or(false),
+                face: field("face"),
+                theme: field("theme"),
+                orientation: field("orientation"),
+            })
+        })
+        .await
+    }
+
+    /// Runs a daemon action (see `ht32panelctl action list`) on every node,
+    /// returning each node's message.
+    pub async fn run_action(&self, id: &str, args: &[String]) -> Vec<NodeResult<String>> {
+        let path = format!(
+            "api/actions/{}?args={}",
+            url_encode(id),
+            url_encode(&args.join(","))
+        );
+        self.each(move |url| {
+            let path = path.clone();
+            async move { request("POST", &format!("{}{}", url, path)).await }
+        })
+        .await
+    }
+
+    /// Sets the face on every node.
+    pub async fn set_face(&self, face: &str) -> Vec<NodeResult<String>> {
+        self.run_action("set_face", &[face.to_string()]).await
+    }
+
+    /// Sets the color theme on every node.
+    pub async fn set_theme(&self, theme: &str) -> Vec<NodeResult<String>> {
+        self.run_action("set_theme", &[theme.to_string()]).await
+    }
+
+    /// Sets the orientation on every node.
+    pub async fn set_orientation(&self, orientation: &str) -> Vec<NodeResult<String>> {
+        self.run_action("set_orientation", &[orientation.to_string()])
+            .await
+    }
+
+    /// Sets the LED effect on every node.
+    pub async fn set_led_effect(&self, effect: &str) -> Vec<NodeResult<String>> {
+        self.run_action("set_led_effect", &[effect.to_string()])
+            .await
+    }
+
+    /// Runs `op` with each node's base URL concurrently, returning results
+    /// in node order.
+    async fn each<T, F, Fut>(&self, op: F) -> Vec<NodeResult<T>>
+    where
+        T: Send + 'static,
+        F: Fn(String) -> Fut,
+        Fut: std::future::Future<Output = Result<T>> + Send + 'static,
+    {
+        let mut tasks = JoinSet::new();
+        for (index, node) in self.nodes.iter().enumerate() {
+            let url = if node.url.ends_with('/') {
+                node.url.clone()
+            } else {
+                format!("{}/", node.url)
+            };
+            let task = op(url);
+            tasks.spawn(async move { (index, task.await) });
+        }
+
+        let mut results: Vec<Option<Result<T>>> = self.nodes.iter().map(|_| None).collect();
+        while let Some(joined) = tasks.join_next().await {
+            if let Ok((index, result)) = joined {
+                results[index] = Some(result);
+            }
+        }
+        self.nodes
+            .iter()
+            .zip(results)
+            .map(|(node, result)| NodeResult {
+                node: node.name.clone(),
+                result: result.unwrap_or_else(|| Err(anyhow!("Request task failed"))),
+            })
+            .collect()
+    }
+}
+
+/// Sends a request without a body and returns the response body. Fails
+/// unless the status is 2xx, with the body as the message.
+async fn request(method: &'static str, url: &str) -> Result<String> {
+    debug!("{} {}", method, url);
+    let url = url.to_string();
+    let response = tokio::task::spawn_blocking(move || http::request(method, &url, &[], None))
+        .await
+        .context("Request task failed")??;
+    let body = response.body.trim().to_string();
+    if !(200..300).contains(&response.status) {
+        if body.is_empty() {
+            bail!("HTTP {}", response.status);
+        }
+        bail!("{}", body);
+    }
+    Ok(body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::thread;
+
+    /// Serves one connection per entry of `responses`, returning the
+    /// request lines received.
+    fn serve(responses: Vec<&'static str>) -> (String, thread::JoinHandle<Vec<String>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let server = thread::spawn(move || {
+            responses
+                .into_iter()
+                .map(|response| {
+                    let (mut stream, _) = listener.accept().unwrap();
+                    let mut request = Vec::new();
+                    let mut buf = [0u8; 1024];
+                    while !request.ends_with(b"\r\n\r\n") {
+                        let n = stream.read(&mut buf).unwrap();
+                        request.extend_from_slice(&buf[..n]);
+                    }
+                    stream.write_all(response.as_bytes()).unwrap();
+                    let request = String::from_utf8(request).unwrap();
+                    request.lines().next().unwrap_or_default().to_string()
+                })
+                .collect()
+        });
+        (url, server)
+    }
+
+    #[test]
+    fn test_from_url() {
+        let node = FleetNode::from_url("office.local:8686").unwrap();
+        assert_eq!(node.name, "office.local");
+        assert_eq!(node.url, "http://office.local:8686");
+        let node = FleetNode::from_url("http://[fe80::1]:8686/").unwrap();
+        assert_eq!(node.name, "fe80::1");
+        assert!(FleetNode::from_url("https://office.local/").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_status() {
+        let (url, server) = serve(vec![
+            "HTTP/1.0 200 OK\r\n\r\n{\"hostname\": \"nas\", \"connected\": true, \"face\": \"ascii\"}",
+        ]);
+        let fleet = FleetClient::from_urls(&[url]).unwrap();
+        let results = fleet.status().await;
+        let status = results[0].result.as_ref().unwrap();
+        assert_eq!(status.hostname, "nas");
+        assert!(status.connected);
+        assert_eq!(status.face, "ascii");
+        assert_eq!(server.join().unwrap(), ["GET /api/status HTTP/1.0"]);
+    }
+
+    #[tokio::test]
+    async fn test_run_action() {
+        let (url, server) = serve(vec![
+            "HTTP/1.0 200 OK\r\n\r\nPulsing LEDs 3 times\n",
+            "HTTP/1.0 400 Bad Request\r\n\r\nUnknown action: nope",
+            "HTTP/1.0 500 Internal Server Error\r\n\r\n",
+        ]);
+        let fleet = FleetClient::from_urls(&[format!("{}/", url)]).unwrap();
+        let args = ["breathing".to_string(), "3 x".to_string()];
+        let results = fleet.run_action("pulse_led", &args).await;
+        assert_eq!(results[0].result.as_ref().unwrap(), "Pulsing LEDs 3 times");
+        let results = fleet.run_action("nope", &[]).await;
+        let error = results[0].result.as_ref().unwrap_err();
+        assert_eq!(error.to_string(), "Unknown action: nope");
+        let results = fleet.run_action("next_face", &[]).await;
+        let error = results[0].result.as_ref().unwrap_err();
+        assert_eq!(error.to_string(), "HTTP 500");
+
+        let requests = server.join().unwrap();
+        assert_eq!(
+            requests[0],
+            "POST /api/actions/pulse_led?args=breathing%2C3%20x HTTP/1.0"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_unreachable_node() {
+        // Bound then dropped, so nothing listens on the port
+        let port = TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let fleet = FleetClient::from_urls(&[format!("127.0.0.1:{}", port)]).unwrap();
+        let results = fleet.set_face("ascii").await;
+        assert_eq!(results[0].node, "127.0.0.1");
+        assert!(results[0].result.is_err());
+    }
+}
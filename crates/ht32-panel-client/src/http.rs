@@ -0,0 +1,387 @@
+//! Minimal blocking HTTP client.
+//!
+//! Used by the daemon's integrations and by the fleet client. Plain
+//! `http://` requests are made directly; the services polled (Pi-hole,
+//! AdGuard Home and similar) and the panels themselves normally run on the
+//! local network. Requests use HTTP/1.0 so responses are never chunked.
+//! `https://` URLs (e.g. webhooks to hosted services) are fetched through
+//! `curl`.
+
+use std::io::{Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::process::{Command, Stdio};
+use std::time::Duration;
+
+use anyhow::{anyhow, bail, Context, Result};
+
+/// Connect and read timeout for requests.
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Largest response body accepted.
+const MAX_BODY: usize = 4 * 1024 * 1024;
+
+/// User-Agent header sent with every request.
+const USER_AGENT: &str = concat!("ht32-panel/", env!("CARGO_PKG_VERSION"));
+
+/// An HTTP response.
+#[derive(Debug, Clone)]
+pub struct Response {
+    /// Status code
+    pub status: u16,
+    /// Header names and values in the order received
+    pub headers: Vec<(String, String)>,
+    /// Response body
+    pub body: String,
+}
+
+impl Response {
+    /// Returns an error unless the status is 2xx.
+    pub fn check(self) -> Result<Self> {
+        if !(200..300).contains(&self.status) {
+            bail!("HTTP {}", self.status);
+        }
+        Ok(self)
+    }
+
+    /// Returns the first header with the given name (case-insensitive).
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// Parses the body as JSON.
+    pub fn json(&self) -> Result<serde_json::Value> {
+        serde_json::from_str(&self.body).context("Response is not JSON")
+    }
+}
+
+/// Splits an `http://host[:port]/path` URL. IPv6 hosts are bracketed.
+pub fn parse_url(url: &str) -> Result<(String, u16, String)> {
+    let rest = url
+        .strip_prefix("http://")
+        .ok_or_else(|| anyhow!("Only http:// URLs are supported: {}", url))?;
+    let (authority, path) = match rest.find('/') {
+        Some(i) => (&rest[..i], &rest[i..]),
+        None => (rest, "/"),
+    };
+    let (host, port) = match authority.rsplit_once(':') {
+        Some((host, port)) if !host.is_empty() && !port.ends_with(']') => (
+            host,
+            port.parse()
+                .with_context(|| format!("Invalid port in {}", url))?,
+        ),
+        _ => (authority, 80),
+    };
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+    if host.is_empty() {
+        bail!("Missing host in {}", url);
+    }
+    Ok((host.to_string(), port, path.to_string()))
+}
+
+/// Opens a connection to `host:port` with the default timeouts.
+pub fn connect(host: &str, port: u16) -> Result<TcpStream> {
+    let addr = (host, port)
+        .to_socket_addrs()
+        .with_context(|| format!("Failed to resolve {}", host))?
+        .next()
+        .ok_or_else(|| anyhow!("No address for {}", host))?;
+    let stream = TcpStream::connect_timeout(&addr, TIMEOUT)
+        .with_context(|| format!("Failed to connect to {}", host))?;
+    stream.set_read_timeout(Some(TIMEOUT))?;
+    stream.set_write_timeout(Some(TIMEOUT))?;
+    Ok(stream)
+}
+
+/// Sends a GET request.
+pub fn get(url: &str, headers: &[(&str, &str)]) -> Result<Response> {
+    request("GET", url, headers, None)
+}
+
+/// Sends a POST request with a JSON body.
+pub fn post_json(url: &str, headers: &[(&str, &str)], body: &str) -> Result<Response> {
+    request("POST", url, headers, Some(("application/json", body)))
+}
+
+/// Sends a POST request with a form-encoded body.
+pub fn post_form(url: &str, headers: &[(&str, &str)], body: &str) -> Result<Response> {
+    request(
+        "POST",
+        url,
+        headers,
+        Some(("application/x-www-form-urlencoded", body)),
+    )
+}
+
+/// Sends a request and reads the whole response.
+pub fn request(
+    method: &str,
+    url: &str,
+    headers: &[(&str, &str)],
+    body: Option<(&str, &str)>,
+) -> Result<Response> {
+    if url.starts_with("https://") {
+        return curl_request(method, url, headers, body);
+    }
+    let (host, port, path) = parse_url(url)?;
+    let mut stream = connect(&host, port)?;
+
+    let mut head = format!(
+        "{} {} HTTP/1.0\r\nHost: {}\r\nUser-Agent: {}\r\n",
+        method,
+        path,
+        host_header(&host),
+        USER_AGENT
+    );
+    if !headers
+        .iter()
+        .any(|(name, _)| name.eq_ignore_ascii_case("accept"))
+    {
+        head.push_str("Accept: */*\r\n");
+    }
+    for (name, value) in headers {
+        head.push_str(&format!("{}: {}\r\n", name, value));
+    }
+    if let Some((content_type, body)) = body {
+        head.push_str(&format!(
+            "Content-Type: {}\r\nContent-Length: {}\r\n",
+            content_type,
+            body.len()
+        ));
+    }
+    head.push_str("Connection: close\r\n\r\n");
+    stream.write_all(head.as_bytes())?;
+    if let Some((_, body)) = body {
+        stream.write_all(body.as_bytes())?;
+    }
+
+    let mut raw = Vec::new();
+    stream
+        .take(MAX_BODY as u64 + 16 * 1024)
+        .read_to_end(&mut raw)
+        .with_context(|| format!("Failed to read response from {}", host))?;
+    parse_response(&raw, &host)
+}
+
+/// Returns `host` as written in a Host header, bracketing IPv6 addresses.
+fn host_header(host: &str) -> String {
+    if host.contains(':') {
+        format!("[{}]", host)
+    } else {
+        host.to_string()
+    }
+}
+
+/// Sends a request through `curl`, for URLs the built-in client cannot
+/// handle.
+fn curl_request(
+    method: &str,
+    url: &str,
+    headers: &[(&str, &str)],
+    body: Option<(&str, &str)>,
+) -> Result<Response> {
+    let mut command = Command::new("curl");
+    command
+        .args(["-sS", "-i", "--http1.1", "-X", method])
+        .arg("--max-time")
+        .arg(TIMEOUT.as_secs().to_string())
+        .arg("-H")
+        .arg(format!("User-Agent: {}", USER_AGENT))
+        .args(["-H", "Expect:"]);
+    for (name, value) in headers {
+        command.arg("-H").arg(format!("{}: {}", name, value));
+    }
+    if let Some((content_type, _)) = body {
+        command
+            .arg("-H")
+            .arg(format!("Content-Type: {}", content_type))
+            .args(["--data-binary", "@-"]);
+    }
+    let mut child = command
+        .arg(url)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("Failed to run curl (required for https:// URLs)")?;
+    if let Some(mut stdin) = child.stdin.take() {
+        if let Some((_, body)) = body {
+            stdin.write_all(body.as_bytes())?;
+        }
+    }
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        bail!(
+            "curl failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+
+    // Skip interim responses such as a proxy's "200 Connection established"
+    let mut raw = output.stdout.as_slice();
+    loop {
+        let response = parse_response(raw, url)?;
+        let interim = (100..200).contains(&response.status)
+            || String::from_utf8_lossy(&raw[..raw.len().min(64)])
+                .contains("Connection established");
+        if !interim {
+            return Ok(response);
+        }
+        let split = raw.windows(4).position(|w| w == b"\r\n\r\n").unwrap_or(0);
+        raw = &raw[split + 4..];
+    }
+}
+
+/// Parses a raw response with headers.
+fn parse_response(raw: &[u8], host: &str) -> Result<Response> {
+    let split = raw
+        .windows(4)
+        .position(|w| w == b"\r\n\r\n")
+        .ok_or_else(|| anyhow!("Invalid HTTP response from {}", host))?;
+    let head = String::from_utf8_lossy(&raw[..split]);
+    let mut lines = head.lines();
+    let status = lines
+        .next()
+        .and_then(|line| line.split_whitespace().nth(1))
+        .and_then(|code| code.parse().ok())
+        .ok_or_else(|| anyhow!("Invalid HTTP status line from {}", host))?;
+    let headers = lines
+        .filter_map(|line| line.split_once(':'))
+        .map(|(name, value)| (name.trim().to_string(), value.trim().to_string()))
+        .collect();
+
+    Ok(Response {
+        status,
+        headers,
+        body: String::from_utf8_lossy(&raw[split + 4..]).into_owned(),
+    })
+}
+
+/// Percent-encodes a value for a form body or query string.
+pub fn url_encode(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                out.push(byte as char)
+            }
+            _ => out.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    out
+}
+
+/// Encodes `user:password` for an `Authorization: Basic` header.
+pub fn basic_auth(user: &str, password: &str) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let input = format!("{}:{}", user, password);
+    let mut out = String::from("Basic ");
+    for chunk in input.as_bytes().chunks(3) {
+        let b = [
+            chunk[0],
+            chunk.get(1).copied().unwrap_or(0),
+            chunk.get(2).copied().unwrap_or(0),
+        ];
+        let n = ((b[0] as u32) << 16) | ((b[1] as u32) << 8) | b[2] as u32;
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(ALPHABET[((n >> (18 - 6 * i)) & 0x3F) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::TcpListener;
+    use std::thread;
+
+    #[test]
+    fn test_parse_url() {
+        assert_eq!(
+            parse_url("http://pi.hole/admin/api.php?x=1").unwrap(),
+            ("pi.hole".to_string(), 80, "/admin/api.php?x=1".to_string())
+        );
+        assert_eq!(
+            parse_url("http://10.0.0.2:3000").unwrap(),
+            ("10.0.0.2".to_string(), 3000, "/".to_string())
+        );
+        assert_eq!(
+            parse_url("http://[fe80::1]:8686/api").unwrap(),
+            ("fe80::1".to_string(), 8686, "/api".to_string())
+        );
+        assert_eq!(
+            parse_url("http://[::1]").unwrap(),
+            ("::1".to_string(), 80, "/".to_string())
+        );
+        assert!(parse_url("https://example.com/").is_err());
+        assert!(parse_url("http:///path").is_err());
+        assert!(parse_url("http://host:port/").is_err());
+    }
+
+    #[test]
+    fn test_parse_response() {
+        let raw = b"HTTP/1.1 201 Created\r\nContent-Type: application/json\r\nX-Empty:\r\n\r\n{\"ok\": true}";
+        let response = parse_response(raw, "host").unwrap();
+        assert_eq!(response.status, 201);
+        assert_eq!(response.header("content-type"), Some("application/json"));
+        assert_eq!(response.header("x-empty"), Some(""));
+        assert_eq!(response.json().unwrap()["ok"], true);
+        assert!(response.check().is_ok());
+
+        let raw = b"HTTP/1.0 500 Internal Server Error\r\n\r\n";
+        assert!(parse_response(raw, "host").unwrap().check().is_err());
+        assert!(parse_response(b"HTTP/1.0 200 OK\r\n", "host").is_err());
+        assert!(parse_response(b"garbage\r\n\r\n", "host").is_err());
+    }
+
+    #[test]
+    fn test_url_encode() {
+        assert_eq!(url_encode("abc-_.~123"), "abc-_.~123");
+        assert_eq!(url_encode("a b&c=d/é"), "a%20b%26c%3Dd%2F%C3%A9");
+    }
+
+    #[test]
+    fn test_basic_auth() {
+        assert_eq!(basic_auth("user", "pass"), "Basic dXNlcjpwYXNz");
+        assert_eq!(basic_auth("a", ""), "Basic YTo=");
+        assert_eq!(basic_auth("ab", ""), "Basic YWI6");
+        assert_eq!(basic_auth("", ""), "Basic Og==");
+    }
+
+    #[test]
+    fn test_request() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let server = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = Vec::new();
+            let mut buf = [0u8; 1024];
+            while !request.ends_with(b"{}") {
+                let n = stream.read(&mut buf).unwrap();
+                request.extend_from_slice(&buf[..n]);
+            }
+            stream
+                .write_all(b"HTTP/1.0 200 OK\r\nContent-Length: 2\r\n\r\nhi")
+                .unwrap();
+            String::from_utf8(request).unwrap()
+        });
+
+        let url = format!("http://127.0.0.1:{}/hook", port);
+        let response = post_json(&url, &[("X-Token", "t")], "{}").unwrap();
+        assert_eq!(response.status, 200);
+        assert_eq!(response.body, "hi");
+
+        let request = server.join().unwrap();
+        assert!(request.starts_with("POST /hook HTTP/1.0\r\n"));
+        assert!(request.contains("X-Token: t\r\n"));
+        assert!(request.contains("Content-Type: application/json\r\n"));
+        assert!(request.contains("Content-Length: 2\r\n"));
+    }
+}
@@ -2,22 +2,71 @@
 //!
 //! This crate provides a unified client for both CLI and applet use cases.
 
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
 use anyhow::{Context, Result};
 use tracing::debug;
 use zbus::{proxy, Connection};
 
+pub mod autostart;
+pub mod discovery;
+pub mod fleet;
+pub mod http;
+
+pub use discovery::{discover, DiscoveredPanel};
+pub use fleet::{FleetClient, FleetNode, NodeResult, NodeStatus};
+pub use ht32_panel_types::{
+    Color, ComplicationInfo, DaemonState, Error as TypesError, FaceInfo, IpDisplayPreference,
+    LedSettings, LedTheme, Orientation, ThemeInfo,
+};
+
 /// D-Bus bus type selection.
-#[derive(Debug, Clone, Copy, Default)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
 pub enum BusType {
     /// Session bus (user session).
     Session,
     /// System bus (system-wide).
     System,
-    /// Try session first, fall back to system.
+    /// Try session first, fall back to system, unless `HT32PANEL_BUS`
+    /// names the bus.
     #[default]
     Auto,
 }
 
+impl BusType {
+    /// Environment variable naming the bus `Auto` uses, "session" or
+    /// "system", for machines running both a user and a system daemon.
+    pub const ENV_HINT: &'static str = "HT32PANEL_BUS";
+
+    /// Resolves `Auto` with the bus named by [`ENV_HINT`](Self::ENV_HINT).
+    fn resolve(self, hint: Option<&str>) -> Self {
+        match (self, hint.map(str::trim)) {
+            (BusType::Auto, Some(hint)) if hint.eq_ignore_ascii_case("session") => BusType::Session,
+            (BusType::Auto, Some(hint)) if hint.eq_ignore_ascii_case("system") => BusType::System,
+            (bus, _) => bus,
+        }
+    }
+}
+
+/// Whether connecting may start the daemon.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Activation {
+    /// Only connect to a running daemon.
+    #[default]
+    Never,
+    /// Start the daemon through D-Bus activation if it is not running and
+    /// the bus has an activation file for it.
+    IfInstalled,
+}
+
+/// Well-known bus name of the daemon.
+const SERVICE_NAME: &str = "org.ht32panel.Daemon";
+
+/// Frame time percentiles of one stage: (stage, p50, p95, p99, max) in
+/// milliseconds.
+pub type StageTimes = (String, f64, f64, f64, f64);
+
 /// D-Bus proxy for the HT32 Panel Daemon.
 #[proxy(
     interface = "org.ht32panel.Daemon1",
@@ -29,10 +78,10 @@ trait Daemon1 {
     fn set_orientation(&self, orientation: &str) -> zbus::Result<()>;
 
     /// Gets the current orientation.
-    fn get_orientation(&self) -> zbus::Result<String>;
+    fn get_orientation(&self) -> zbus::Result<Orientation>;
 
     /// Clears the display to a solid color.
-    fn clear_display(&self, color: &str) -> zbus::Result<()>;
+    fn clear_display(&self, color: Color) -> zbus::Result<()>;
 
     /// Sets the display face.
     fn set_face(&self, face: &str) -> zbus::Result<()>;
@@ -40,14 +89,63 @@ trait Daemon1 {
     /// Gets the current face name.
     fn get_face(&self) -> zbus::Result<String>;
 
-    /// Sets LED parameters.
+    /// Sets the daily random face switch time ("HH:MM", empty disables).
+    fn set_daily_random(&self, time: &str) -> zbus::Result<()>;
+
+    /// Gets the daily random face switch time.
+    fn get_daily_random(&self) -> zbus::Result<String>;
+
+    /// Sets LED parameters (kept for older daemons).
     fn set_led(&self, theme: u8, intensity: u8, speed: u8) -> zbus::Result<()>;
 
+    /// Sets the LED theme, intensity and speed.
+    fn set_led_settings(&self, settings: LedSettings) -> zbus::Result<()>;
+
     /// Turns off LEDs.
     fn led_off(&self) -> zbus::Result<()>;
 
-    /// Gets current LED settings as (theme, intensity, speed).
-    fn get_led_settings(&self) -> zbus::Result<(u8, u8, u8)>;
+    /// Flashes the LEDs count times with an effect, then restores them.
+    fn pulse_led(&self, color: &str, count: u32) -> zbus::Result<()>;
+
+    /// Switches do-not-disturb on or off.
+    fn set_dnd(&self, on: bool) -> zbus::Result<()>;
+
+    /// Gets do-not-disturb as (switched on by hand, in effect now).
+    fn get_dnd(&self) -> zbus::Result<(bool, bool)>;
+
+    /// Returns the face switching rules as JSON.
+    fn get_rules(&self) -> zbus::Result<String>;
+
+    /// Replaces the face switching rules with JSON; "" restores the config.
+    fn set_rules(&self, rules: &str) -> zbus::Result<()>;
+
+    /// Lists the built-in collectors as (name, switched on).
+    fn list_sensors(&self) -> zbus::Result<Vec<(String, bool)>>;
+
+    /// Switches a built-in collector on or off.
+    fn set_sensor_enabled(&self, name: &str, enabled: bool) -> zbus::Result<()>;
+
+    /// Lists the readable temperature inputs as (label, path, Celsius,
+    /// being read).
+    fn list_temperature_sensors(&self) -> zbus::Result<Vec<(String, String, f64, bool)>>;
+
+    /// Selects the CPU temperature input; "" returns to the config.
+    fn set_temperature_sensor(&self, path_or_label: &str) -> zbus::Result<()>;
+
+    /// Gets the selected CPU temperature input.
+    fn get_temperature_sensor(&self) -> zbus::Result<String>;
+
+    /// Enables or disables LED settings following the display theme.
+    fn set_led_follow_theme(&self, follow: bool) -> zbus::Result<()>;
+
+    /// Gets whether LED settings follow the display theme.
+    fn get_led_follow_theme(&self) -> zbus::Result<bool>;
+
+    /// Gets current LED settings.
+    fn get_led_settings(&self) -> zbus::Result<LedSettings>;
+
+    /// Gets the settings a client shows in one call.
+    fn get_state(&self) -> zbus::Result<DaemonState>;
 
     /// Gets the current color theme name.
     fn get_theme(&self) -> zbus::Result<String>;
@@ -55,24 +153,103 @@ trait Daemon1 {
     /// Sets the color theme by name.
     fn set_theme(&self, name: &str) -> zbus::Result<()>;
 
+    /// Gets the current theme colors as "#RRGGBB" by name.
+    fn get_theme_colors(&self) -> zbus::Result<HashMap<String, String>>;
+
+    /// Changes some of the current theme colors live, without saving them.
+    fn set_theme_colors(&self, colors: HashMap<String, Color>) -> zbus::Result<()>;
+
+    /// Saves the current theme colors as a theme and switches to it.
+    fn save_current_theme(&self, name: &str) -> zbus::Result<()>;
+
+    /// Deletes a saved theme.
+    fn delete_theme(&self, name: &str) -> zbus::Result<()>;
+
     /// Lists available color themes (IDs only).
     fn list_themes(&self) -> zbus::Result<Vec<String>>;
 
-    /// Lists available color themes with display names (JSON-encoded).
+    /// Lists available color themes with display names (JSON-encoded), for
+    /// daemons without `ListThemeInfo`.
     fn list_themes_detailed(&self) -> zbus::Result<Vec<String>>;
 
+    /// Lists available color themes with display names.
+    fn list_theme_info(&self) -> zbus::Result<Vec<ThemeInfo>>;
+
     /// Lists available faces (IDs only).
     fn list_face_ids(&self) -> zbus::Result<Vec<String>>;
 
-    /// Lists available faces with display names (JSON-encoded).
+    /// Lists available faces with display names.
+    fn list_face_info(&self) -> zbus::Result<Vec<FaceInfo>>;
+
+    /// Lists available faces with display names (JSON-encoded), for
+    /// daemons without `ListFaceInfo`.
     fn list_faces(&self) -> zbus::Result<Vec<String>>;
 
     /// Lists all available network interfaces.
     fn list_network_interfaces(&self) -> zbus::Result<Vec<String>>;
 
+    /// Sets the network interface to monitor ("auto" to detect it).
+    fn set_network_interface(&self, interface: &str) -> zbus::Result<()>;
+
+    /// Overrides the hostname shown on faces (empty restores the hostname).
+    fn set_display_label(&self, label: &str) -> zbus::Result<()>;
+
+    /// Gets the label shown instead of the hostname.
+    fn get_display_label(&self) -> zbus::Result<String>;
+
+    /// Sets the post-processing effects applied to each frame.
+    fn set_post_effects(&self, effects: Vec<String>) -> zbus::Result<()>;
+
+    /// Gets the post-processing effects.
+    fn get_post_effects(&self) -> zbus::Result<Vec<String>>;
+
+    /// Mirrors each frame horizontally and/or vertically.
+    fn set_transform(&self, flip_h: bool, flip_v: bool) -> zbus::Result<()>;
+
+    /// Gets the horizontal and vertical mirroring.
+    fn get_transform(&self) -> zbus::Result<(bool, bool)>;
+
+    /// Draws frames only in a rectangle of the panel.
+    fn set_visible_area(&self, x: u32, y: u32, width: u32, height: u32) -> zbus::Result<()>;
+
+    /// Gets the visible area as x, y, width and height.
+    fn get_visible_area(&self) -> zbus::Result<(u32, u32, u32, u32)>;
+
+    /// Sets the image drawn behind every face (empty removes it).
+    fn set_background_image(&self, path: &str) -> zbus::Result<()>;
+
+    /// Gets the background image path.
+    fn get_background_image(&self) -> zbus::Result<String>;
+
+    /// Sets the timezone override (empty or "local" uses the system timezone).
+    fn set_timezone(&self, timezone: &str) -> zbus::Result<()>;
+
+    /// Gets the timezone override.
+    fn get_timezone(&self) -> zbus::Result<String>;
+
+    /// Sets the rate units (binary prefixes, network in bits).
+    fn set_rate_units(&self, binary: bool, network_bits: bool) -> zbus::Result<()>;
+
+    /// Gets the rate units as (binary, network_bits).
+    fn get_rate_units(&self) -> zbus::Result<(bool, bool)>;
+
+    /// Sets the block devices tracked for disk I/O (empty for auto-detect).
+    fn set_disk_devices(&self, devices: &[&str]) -> zbus::Result<()>;
+
+    /// Gets the configured disk devices.
+    fn get_disk_devices(&self) -> zbus::Result<Vec<String>>;
+
+    /// Lists block devices available for disk I/O tracking.
+    fn list_disk_devices(&self) -> zbus::Result<Vec<String>>;
+
+    /// Sets the IP address display preference.
+    fn set_ip_display_preference(&self, preference: IpDisplayPreference) -> zbus::Result<()>;
+
+    /// Gets the IP address display preference.
+    fn get_ip_display_preference(&self) -> zbus::Result<IpDisplayPreference>;
+
     /// Lists available complications for the current face.
-    /// Returns (id, name, description, enabled) tuples.
-    fn list_complications(&self) -> zbus::Result<Vec<(String, String, String, bool)>>;
+    fn list_complications(&self) -> zbus::Result<Vec<ComplicationInfo>>;
 
     /// Lists available complications with full details including options.
     /// Returns JSON-encoded complication data.
@@ -102,9 +279,98 @@ trait Daemon1 {
         value: &str,
     ) -> zbus::Result<()>;
 
+    /// Gets where a complication is placed as (slot, size); an empty slot
+    /// means the face lays it out.
+    fn get_complication_position(&self, complication_id: &str) -> zbus::Result<(String, String)>;
+
+    /// Moves a complication to a slot, or back to the face with "".
+    fn set_complication_position(
+        &self,
+        complication_id: &str,
+        slot: &str,
+        size: &str,
+    ) -> zbus::Result<()>;
+
+    /// Lists all overlays as (id, slot, size); hidden ones have an empty slot.
+    fn list_overlays(&self) -> zbus::Result<Vec<(String, String, String)>>;
+
+    /// Shows an overlay on every face in a slot with a size.
+    fn set_overlay(&self, id: &str, slot: &str, size: &str) -> zbus::Result<()>;
+
+    /// Hides an overlay.
+    fn remove_overlay(&self, id: &str) -> zbus::Result<()>;
+
     /// Returns the current framebuffer as PNG data.
     fn get_screen_png(&self) -> zbus::Result<Vec<u8>>;
 
+    /// Returns the current framebuffer as "png", "jpeg" or "webp" data.
+    fn get_screen_image(&self, format: &str, quality: u8) -> zbus::Result<Vec<u8>>;
+
+    /// Renders the current face with a theme as PNG data, without applying it.
+    fn render_theme_preview(&self, theme: &str) -> zbus::Result<Vec<u8>>;
+
+    /// Starts a bandwidth test in the background.
+    fn run_speedtest(&self) -> zbus::Result<()>;
+
+    /// Shows a test pattern, returning how many seconds it runs.
+    fn run_test_pattern(&self, name: &str) -> zbus::Result<u32>;
+
+    /// Sends a Wake-on-LAN packet to a MAC address or configured host name.
+    fn wake_host(&self, mac: &str) -> zbus::Result<()>;
+
+    /// Reports the status of an external job (progress is negative if unknown).
+    fn report_job(&self, name: &str, status: &str, progress: f64) -> zbus::Result<()>;
+
+    /// Lists the actions accepted by `InvokeAction` (JSON-encoded).
+    fn list_actions(&self) -> zbus::Result<Vec<String>>;
+
+    /// Runs an action with positional arguments, returning a result message.
+    fn invoke_action(&self, id: &str, args: Vec<String>) -> zbus::Result<String>;
+
+    /// Reverts the last settings change, returning what changed.
+    fn undo(&self) -> zbus::Result<String>;
+
+    /// Reapplies the last undone settings change, returning what changed.
+    fn redo(&self) -> zbus::Result<String>;
+
+    /// Gets the most recent changes, oldest first (JSON-encoded).
+    fn get_audit_log(&self, count: u32) -> zbus::Result<Vec<String>>;
+
+    /// Sets the log level of a target ("" for all targets).
+    fn set_log_level(&self, target: &str, level: &str) -> zbus::Result<()>;
+
+    /// Gets the most recent log events, oldest first (JSON-encoded).
+    fn get_recent_logs(&self, count: u32) -> zbus::Result<Vec<String>>;
+
+    /// Gets udev rules granting access to the LCD and LED adapter.
+    fn generate_udev_rule(&self) -> zbus::Result<String>;
+
+    /// Checks configuration file contents, returning one message per problem.
+    fn validate_config(&self, content: &str) -> zbus::Result<Vec<String>>;
+
+    /// Gets the health of the daemon (JSON-encoded).
+    fn get_health(&self) -> zbus::Result<String>;
+
+    /// Lists the optional features the daemon was built with.
+    fn list_capabilities(&self) -> zbus::Result<Vec<String>>;
+
+    /// Gets the number of recent frames and their frame time percentiles.
+    fn get_render_stats(&self) -> zbus::Result<(u32, Vec<StageTimes>)>;
+
+    /// Emitted when a panel button is pressed, with its report code.
+    #[zbus(signal)]
+    fn button_pressed(&self, code: &str) -> zbus::Result<()>;
+
+    /// Emitted when a speedtest completes (rates in bytes/second).
+    #[zbus(signal)]
+    fn speedtest_finished(
+        &self,
+        success: bool,
+        download: f64,
+        upload: f64,
+        ping_ms: f64,
+    ) -> zbus::Result<()>;
+
     /// Shuts down the daemon.
     fn quit(&self) -> zbus::Result<()>;
 
@@ -112,6 +378,11 @@ trait Daemon1 {
     #[zbus(property)]
     fn connected(&self) -> zbus::Result<bool>;
 
+    /// Why the LCD is not connected or the last frame failed (empty while
+    /// frames are getting through).
+    #[zbus(property)]
+    fn connection_error(&self) -> zbus::Result<String>;
+
     /// Whether the web UI is enabled.
     #[zbus(property)]
     fn web_enabled(&self) -> zbus::Result<bool>;
@@ -135,6 +406,10 @@ trait Daemon1 {
     /// Current display face name.
     #[zbus(property)]
     fn face(&self) -> zbus::Result<String>;
+
+    /// Current IP address display preference.
+    #[zbus(property)]
+    fn ip_display_preference(&self) -> zbus::Result<String>;
 }
 
 /// D-Bus client wrapper for the daemon.
@@ -152,18 +427,35 @@ impl DaemonClient {
 
     /// Attempts to connect to the daemon via D-Bus with specified bus type.
     pub async fn connect_with_bus(bus_type: BusType) -> Result<Self> {
-        let connection = match bus_type {
+        Self::connect_with_options(bus_type, Activation::Never).await
+    }
+
+    /// Attempts to connect to the daemon via D-Bus with specified bus type,
+    /// starting the daemon through D-Bus activation when it is not running
+    /// and `activation` allows it.
+    pub async fn connect_with_options(bus_type: BusType, activation: Activation) -> Result<Self> {
+        let activate = activation == Activation::IfInstalled;
+        let hint = std::env::var(BusType::ENV_HINT).ok();
+        let connection = match bus_type.resolve(hint.as_deref()) {
             BusType::Session => {
                 debug!("Connecting to session bus");
-                Connection::session()
+                let conn = Connection::session()
                     .await
-                    .context("Failed to connect to session bus")?
+                    .context("Failed to connect to session bus")?;
+                if activate && !Self::service_exists(&conn).await {
+                    Self::activate(&conn).await?;
+                }
+                conn
             }
             BusType::System => {
                 debug!("Connecting to system bus");
-                Connection::system()
+                let conn = Connection::system()
                     .await
-                    .context("Failed to connect to system bus")?
+                    .context("Failed to connect to system bus")?;
+                if activate && !Self::service_exists(&conn).await {
+                    Self::activate(&conn).await?;
+                }
+                conn
             }
             BusType::Auto => {
                 // Try session bus first, but verify the service exists
@@ -180,6 +472,10 @@ impl DaemonClient {
                         if Self::service_exists(&sys_conn).await {
                             debug!("Found daemon on system bus");
                             sys_conn
+                        } else if activate && Self::activate(&conn).await? {
+                            conn
+                        } else if activate && Self::activate(&sys_conn).await? {
+                            sys_conn
                         } else {
                             // Neither bus has the service, return session for better error
                             anyhow::bail!("Daemon service not found on session or system bus. Is ht32paneld running?")
@@ -187,9 +483,13 @@ impl DaemonClient {
                     }
                 } else {
                     debug!("Session bus unavailable, trying system bus");
-                    Connection::system()
+                    let sys_conn = Connection::system()
                         .await
-                        .context("Failed to connect to any D-Bus")?
+                        .context("Failed to connect to any D-Bus")?;
+                    if activate && !Self::service_exists(&sys_conn).await {
+                        Self::activate(&sys_conn).await?;
+                    }
+                    sys_conn
                 }
             }
         };
@@ -206,7 +506,7 @@ impl DaemonClient {
         use zbus::fdo::DBusProxy;
         if let Ok(dbus_proxy) = DBusProxy::new(conn).await {
             dbus_proxy
-                .name_has_owner("org.ht32panel.Daemon".try_into().unwrap())
+                .name_has_owner(SERVICE_NAME.try_into().unwrap())
                 .await
                 .unwrap_or(false)
         } else {
@@ -214,7 +514,33 @@ impl DaemonClient {
         }
     }
 
-    /// Sets the display orientation.
+    /// Starts the daemon through D-Bus activation if the bus has an
+    /// activation file for it. Returns false if it has none.
+    async fn activate(conn: &Connection) -> Result<bool> {
+        use zbus::fdo::DBusProxy;
+        let dbus_proxy = DBusProxy::new(conn)
+            .await
+            .context("Failed to create D-Bus proxy")?;
+        let activatable = dbus_proxy
+            .list_activatable_names()
+            .await
+            .context("Failed to list activatable D-Bus services")?
+            .iter()
+            .any(|name| name.as_str() == SERVICE_NAME);
+        if !activatable {
+            debug!("Daemon is not activatable on this bus");
+            return Ok(false);
+        }
+        debug!("Starting daemon through D-Bus activation");
+        dbus_proxy
+            .start_service_by_name(SERVICE_NAME.try_into().unwrap(), 0)
+            .await
+            .context("Failed to start ht32paneld through D-Bus activation")?;
+        Ok(true)
+    }
+
+    /// Sets the display orientation: an [`Orientation`] name, or "auto" to
+    /// detect it.
     pub async fn set_orientation(&self, orientation: &str) -> Result<()> {
         self.proxy
             .set_orientation(orientation)
@@ -223,7 +549,7 @@ impl DaemonClient {
     }
 
     /// Gets the current orientation.
-    pub async fn get_orientation(&self) -> Result<String> {
+    pub async fn get_orientation(&self) -> Result<Orientation> {
         self.proxy
             .get_orientation()
             .await
@@ -231,7 +557,7 @@ impl DaemonClient {
     }
 
     /// Clears the display to a solid color.
-    pub async fn clear_display(&self, color: &str) -> Result<()> {
+    pub async fn clear_display(&self, color: Color) -> Result<()> {
         self.proxy
             .clear_display(color)
             .await
@@ -254,12 +580,34 @@ impl DaemonClient {
             .context("Failed to get face via D-Bus")
     }
 
-    /// Sets LED parameters.
-    pub async fn set_led(&self, theme: u8, intensity: u8, speed: u8) -> Result<()> {
+    /// Sets the time of day ("HH:MM") to switch to a random face and theme.
+    /// An empty string or "off" disables it.
+    pub async fn set_daily_random(&self, time: &str) -> Result<()> {
         self.proxy
-            .set_led(theme, intensity, speed)
+            .set_daily_random(time)
             .await
-            .context("Failed to set LED via D-Bus")
+            .context("Failed to set daily random via D-Bus")
+    }
+
+    /// Gets the daily random switch time (empty when disabled).
+    pub async fn get_daily_random(&self) -> Result<String> {
+        self.proxy
+            .get_daily_random()
+            .await
+            .context("Failed to get daily random via D-Bus")
+    }
+
+    /// Sets the LED theme, intensity and speed.
+    pub async fn set_led(&self, settings: LedSettings) -> Result<()> {
+        let result = match self.proxy.set_led_settings(settings).await {
+            Err(e) if is_unknown_method(&e) => {
+                let (theme, intensity, speed) =
+                    (settings.theme(), settings.intensity(), settings.speed());
+                self.proxy.set_led(theme, intensity, speed).await
+            }
+            result => result,
+        };
+        result.context("Failed to set LED via D-Bus")
     }
 
     /// Turns off LEDs.
@@ -270,14 +618,128 @@ impl DaemonClient {
             .context("Failed to turn off LED via D-Bus")
     }
 
+    /// Flashes the LEDs `count` times with the given effect, then restores them.
+    pub async fn pulse_led(&self, color: &str, count: u32) -> Result<()> {
+        self.proxy
+            .pulse_led(color, count)
+            .await
+            .context("Failed to pulse LED via D-Bus")
+    }
+
+    /// Switches do-not-disturb on or off by hand. The configured schedule
+    /// still applies while it is off.
+    pub async fn set_dnd(&self, on: bool) -> Result<()> {
+        self.proxy
+            .set_dnd(on)
+            .await
+            .context("Failed to set do-not-disturb via D-Bus")
+    }
+
+    /// Gets do-not-disturb as (switched on by hand, in effect now, by hand
+    /// or by the schedule).
+    pub async fn get_dnd(&self) -> Result<(bool, bool)> {
+        self.proxy
+            .get_dnd()
+            .await
+            .context("Failed to get do-not-disturb via D-Bus")
+    }
+
+    /// Returns the face switching rules in effect as a JSON array of
+    /// objects with "when", "above", "below", "for" and "face".
+    pub async fn get_rules(&self) -> Result<String> {
+        self.proxy
+            .get_rules()
+            .await
+            .context("Failed to get rules via D-Bus")
+    }
+
+    /// Replaces the face switching rules with a JSON array as returned by
+    /// `get_rules`. An empty string returns to the configured rules.
+    pub async fn set_rules(&self, rules: &str) -> Result<()> {
+        self.proxy
+            .set_rules(rules)
+            .await
+            .context("Failed to set rules via D-Bus")
+    }
+
+    /// Lists the built-in collectors as (name, switched on).
+    pub async fn list_sensors(&self) -> Result<Vec<(String, bool)>> {
+        self.proxy
+            .list_sensors()
+            .await
+            .context("Failed to list sensors via D-Bus")
+    }
+
+    /// Switches a built-in collector (temperature, disk, network, processes
+    /// or sessions) on or off. Complications showing its data are hidden
+    /// while it is off.
+    pub async fn set_sensor_enabled(&self, name: &str, enabled: bool) -> Result<()> {
+        self.proxy
+            .set_sensor_enabled(name, enabled)
+            .await
+            .context("Failed to set sensor via D-Bus")
+    }
+
+    /// Lists the readable temperature inputs as (label, path, Celsius,
+    /// being read).
+    pub async fn list_temperature_sensors(&self) -> Result<Vec<(String, String, f64, bool)>> {
+        self.proxy
+            .list_temperature_sensors()
+            .await
+            .context("Failed to list temperature sensors via D-Bus")
+    }
+
+    /// Selects the CPU temperature input by hwmon path, label such as
+    /// "k10temp Tctl" or chip name. An empty string returns to the
+    /// configured input.
+    pub async fn set_temperature_sensor(&self, path_or_label: &str) -> Result<()> {
+        self.proxy
+            .set_temperature_sensor(path_or_label)
+            .await
+            .context("Failed to set temperature sensor via D-Bus")
+    }
+
+    /// Gets the selected CPU temperature input (empty when auto-detecting).
+    pub async fn get_temperature_sensor(&self) -> Result<String> {
+        self.proxy
+            .get_temperature_sensor()
+            .await
+            .context("Failed to get temperature sensor via D-Bus")
+    }
+
+    /// Enables or disables deriving the LED settings from the display theme.
+    pub async fn set_led_follow_theme(&self, follow: bool) -> Result<()> {
+        self.proxy
+            .set_led_follow_theme(follow)
+            .await
+            .context("Failed to set LED follow theme via D-Bus")
+    }
+
+    /// Gets whether the LED settings follow the display theme.
+    pub async fn get_led_follow_theme(&self) -> Result<bool> {
+        self.proxy
+            .get_led_follow_theme()
+            .await
+            .context("Failed to get LED follow theme via D-Bus")
+    }
+
     /// Gets current LED settings.
-    pub async fn get_led_settings(&self) -> Result<(u8, u8, u8)> {
+    pub async fn get_led_settings(&self) -> Result<LedSettings> {
         self.proxy
             .get_led_settings()
             .await
             .context("Failed to get LED settings via D-Bus")
     }
 
+    /// Gets the connection, orientation, face, theme, LED and IP display
+    /// settings in one call.
+    pub async fn get_state(&self) -> Result<DaemonState> {
+        self.proxy
+            .get_state()
+            .await
+            .context("Failed to get daemon state via D-Bus")
+    }
+
     /// Gets the current color theme name.
     pub async fn get_theme(&self) -> Result<String> {
         self.proxy
@@ -294,6 +756,39 @@ impl DaemonClient {
             .context("Failed to set theme via D-Bus")
     }
 
+    /// Gets the current theme colors as "#RRGGBB" by name.
+    pub async fn get_theme_colors(&self) -> Result<HashMap<String, String>> {
+        self.proxy
+            .get_theme_colors()
+            .await
+            .context("Failed to get theme colors via D-Bus")
+    }
+
+    /// Changes some of the current theme colors live, without saving them.
+    pub async fn set_theme_colors(&self, colors: HashMap<String, Color>) -> Result<()> {
+        self.proxy
+            .set_theme_colors(colors)
+            .await
+            .context("Failed to set theme colors via D-Bus")
+    }
+
+    /// Saves the current theme colors as a theme and switches to it.
+    pub async fn save_current_theme(&self, name: &str) -> Result<()> {
+        self.proxy
+            .save_current_theme(name)
+            .await
+            .context("Failed to save theme via D-Bus")
+    }
+
+    /// Deletes a saved theme, switching to the default theme if it is the
+    /// current one.
+    pub async fn delete_theme(&self, name: &str) -> Result<()> {
+        self.proxy
+            .delete_theme(name)
+            .await
+            .context("Failed to delete theme via D-Bus")
+    }
+
     /// Lists available color themes (IDs only).
     pub async fn list_themes(&self) -> Result<Vec<String>> {
         self.proxy
@@ -302,12 +797,15 @@ impl DaemonClient {
             .context("Failed to list themes via D-Bus")
     }
 
-    /// Lists available color themes with display names (JSON-encoded).
-    pub async fn list_themes_detailed(&self) -> Result<Vec<String>> {
-        self.proxy
-            .list_themes_detailed()
-            .await
-            .context("Failed to list themes detailed via D-Bus")
+    /// Lists available color themes with display names, presets first.
+    pub async fn list_theme_info(&self) -> Result<Vec<ThemeInfo>> {
+        match self.proxy.list_theme_info().await {
+            Err(e) if is_unknown_method(&e) => {
+                let themes = self.proxy.list_themes_detailed().await;
+                parse_json_list(themes.context("Failed to list themes via D-Bus")?)
+            }
+            themes => themes.context("Failed to list themes via D-Bus"),
+        }
     }
 
     /// Lists available faces (IDs only).
@@ -318,12 +816,15 @@ impl DaemonClient {
             .context("Failed to list face IDs via D-Bus")
     }
 
-    /// Lists available faces with display names (JSON-encoded).
-    pub async fn list_faces(&self) -> Result<Vec<String>> {
-        self.proxy
-            .list_faces()
-            .await
-            .context("Failed to list faces via D-Bus")
+    /// Lists available faces with display names.
+    pub async fn list_faces(&self) -> Result<Vec<FaceInfo>> {
+        match self.proxy.list_face_info().await {
+            Err(e) if is_unknown_method(&e) => {
+                let faces = self.proxy.list_faces().await;
+                parse_json_list(faces.context("Failed to list faces via D-Bus")?)
+            }
+            faces => faces.context("Failed to list faces via D-Bus"),
+        }
     }
 
     /// Lists available network interfaces.
@@ -334,6 +835,173 @@ impl DaemonClient {
             .context("Failed to list network interfaces via D-Bus")
     }
 
+    /// Sets the network interface to monitor on the current face; "auto"
+    /// detects it.
+    pub async fn set_network_interface(&self, interface: &str) -> Result<()> {
+        self.proxy
+            .set_network_interface(interface)
+            .await
+            .context("Failed to set network interface via D-Bus")
+    }
+
+    /// Overrides the hostname shown on faces. An empty label restores the hostname.
+    pub async fn set_display_label(&self, label: &str) -> Result<()> {
+        self.proxy
+            .set_display_label(label)
+            .await
+            .context("Failed to set display label via D-Bus")
+    }
+
+    /// Gets the label shown instead of the hostname (empty when unset).
+    pub async fn get_display_label(&self) -> Result<String> {
+        self.proxy
+            .get_display_label()
+            .await
+            .context("Failed to get display label via D-Bus")
+    }
+
+    /// Sets the post-processing effects (invert, grayscale, warm[:0-1],
+    /// scanlines), applied in order. An empty list turns them off.
+    pub async fn set_post_effects(&self, effects: Vec<String>) -> Result<()> {
+        self.proxy
+            .set_post_effects(effects)
+            .await
+            .context("Failed to set post effects via D-Bus")
+    }
+
+    /// Gets the post-processing effects.
+    pub async fn get_post_effects(&self) -> Result<Vec<String>> {
+        self.proxy
+            .get_post_effects()
+            .await
+            .context("Failed to get post effects via D-Bus")
+    }
+
+    /// Mirrors each frame horizontally and/or vertically as seen by the
+    /// viewer, independent of the orientation.
+    pub async fn set_transform(&self, flip_h: bool, flip_v: bool) -> Result<()> {
+        self.proxy
+            .set_transform(flip_h, flip_v)
+            .await
+            .context("Failed to set transform via D-Bus")
+    }
+
+    /// Gets the horizontal and vertical mirroring.
+    pub async fn get_transform(&self) -> Result<(bool, bool)> {
+        self.proxy
+            .get_transform()
+            .await
+            .context("Failed to get transform via D-Bus")
+    }
+
+    /// Draws frames only in the given rectangle of the panel, in landscape
+    /// panel coordinates, leaving the rest black.
+    pub async fn set_visible_area(&self, x: u32, y: u32, width: u32, height: u32) -> Result<()> {
+        self.proxy
+            .set_visible_area(x, y, width, height)
+            .await
+            .context("Failed to set visible area via D-Bus")
+    }
+
+    /// Gets the visible area as x, y, width and height.
+    pub async fn get_visible_area(&self) -> Result<(u32, u32, u32, u32)> {
+        self.proxy
+            .get_visible_area()
+            .await
+            .context("Failed to get visible area via D-Bus")
+    }
+
+    /// Sets the image drawn behind every face. The path must be readable by
+    /// the daemon; an empty path removes the image.
+    pub async fn set_background_image(&self, path: &str) -> Result<()> {
+        self.proxy
+            .set_background_image(path)
+            .await
+            .context("Failed to set background image via D-Bus")
+    }
+
+    /// Gets the background image path (empty when there is none).
+    pub async fn get_background_image(&self) -> Result<String> {
+        self.proxy
+            .get_background_image()
+            .await
+            .context("Failed to get background image via D-Bus")
+    }
+
+    /// Sets the timezone shown on faces. An empty name or "local" uses the system timezone.
+    pub async fn set_timezone(&self, timezone: &str) -> Result<()> {
+        self.proxy
+            .set_timezone(timezone)
+            .await
+            .context("Failed to set timezone via D-Bus")
+    }
+
+    /// Gets the timezone override (empty when using the system timezone).
+    pub async fn get_timezone(&self) -> Result<String> {
+        self.proxy
+            .get_timezone()
+            .await
+            .context("Failed to get timezone via D-Bus")
+    }
+
+    /// Sets the units used for rates: 1024-based prefixes when `binary`,
+    /// and bits per second for network throughput when `network_bits`.
+    pub async fn set_rate_units(&self, binary: bool, network_bits: bool) -> Result<()> {
+        self.proxy
+            .set_rate_units(binary, network_bits)
+            .await
+            .context("Failed to set rate units via D-Bus")
+    }
+
+    /// Gets the rate units as (binary, network_bits).
+    pub async fn get_rate_units(&self) -> Result<(bool, bool)> {
+        self.proxy
+            .get_rate_units()
+            .await
+            .context("Failed to get rate units via D-Bus")
+    }
+
+    /// Sets the block devices tracked for disk I/O. An empty list auto-detects
+    /// the primary disk.
+    pub async fn set_disk_devices(&self, devices: &[&str]) -> Result<()> {
+        self.proxy
+            .set_disk_devices(devices)
+            .await
+            .context("Failed to set disk devices via D-Bus")
+    }
+
+    /// Gets the configured disk devices (empty when auto-detecting).
+    pub async fn get_disk_devices(&self) -> Result<Vec<String>> {
+        self.proxy
+            .get_disk_devices()
+            .await
+            .context("Failed to get disk devices via D-Bus")
+    }
+
+    /// Lists block devices available for disk I/O tracking.
+    pub async fn list_disk_devices(&self) -> Result<Vec<String>> {
+        self.proxy
+            .list_disk_devices()
+            .await
+            .context("Failed to list disk devices via D-Bus")
+    }
+
+    /// Sets the IP address display preference.
+    pub async fn set_ip_display_preference(&self, preference: IpDisplayPreference) -> Result<()> {
+        self.proxy
+            .set_ip_display_preference(preference)
+            .await
+            .context("Failed to set IP display preference via D-Bus")
+    }
+
+    /// Gets the IP address display preference.
+    pub async fn get_ip_display_preference(&self) -> Result<IpDisplayPreference> {
+        self.proxy
+            .get_ip_display_preference()
+            .await
+            .context("Failed to get IP display preference via D-Bus")
+    }
+
     /// Gets the screen as PNG data.
     pub async fn get_screen_png(&self) -> Result<Vec<u8>> {
         self.proxy
@@ -342,6 +1010,189 @@ impl DaemonClient {
             .context("Failed to get screen PNG via D-Bus")
     }
 
+    /// Gets the screen as "png", "jpeg" or "webp" data. `quality` (1-100,
+    /// 0 for the default) applies to JPEG.
+    pub async fn get_screen_image(&self, format: &str, quality: u8) -> Result<Vec<u8>> {
+        self.proxy
+            .get_screen_image(format, quality)
+            .await
+            .context("Failed to get screen image via D-Bus")
+    }
+
+    /// Renders the current face with a theme as PNG data.
+    pub async fn render_theme_preview(&self, theme: &str) -> Result<Vec<u8>> {
+        self.proxy
+            .render_theme_preview(theme)
+            .await
+            .context("Failed to render theme preview via D-Bus")
+    }
+
+    /// Starts a bandwidth test; the result is shown on the panel.
+    pub async fn run_speedtest(&self) -> Result<()> {
+        self.proxy
+            .run_speedtest()
+            .await
+            .context("Failed to start speedtest via D-Bus")
+    }
+
+    /// Runs a bandwidth test and waits for it to finish.
+    /// Returns (download, upload, ping_ms) with rates in bytes/second;
+    /// values the daemon's backend did not measure are 0.
+    pub async fn run_speedtest_wait(&self) -> Result<(f64, f64, f64)> {
+        use std::future::poll_fn;
+        use std::pin::Pin;
+        use zbus::export::futures_core::Stream;
+
+        // Subscribe first so a fast test cannot finish unnoticed
+        let mut finished = self
+            .proxy
+            .receive_speedtest_finished()
+            .await
+            .context("Failed to subscribe to speedtest results")?;
+        self.run_speedtest().await?;
+
+        let signal = poll_fn(|cx| Pin::new(&mut finished).poll_next(cx))
+            .await
+            .context("D-Bus connection closed during speedtest")?;
+        let args = signal.args().context("Invalid SpeedtestFinished signal")?;
+        if !args.success {
+            anyhow::bail!("Speedtest failed; see the daemon log for details");
+        }
+        Ok((args.download, args.upload, args.ping_ms))
+    }
+
+    /// Shows a test pattern in place of the face: "bars", "gradient",
+    /// "text", "orientation" or "all". Returns how many seconds it runs.
+    pub async fn run_test_pattern(&self, name: &str) -> Result<u32> {
+        self.proxy
+            .run_test_pattern(name)
+            .await
+            .context("Failed to run test pattern via D-Bus")
+    }
+
+    /// Sends a Wake-on-LAN packet to a MAC address or configured host name.
+    pub async fn wake_host(&self, mac: &str) -> Result<()> {
+        self.proxy
+            .wake_host(mac)
+            .await
+            .context("Failed to wake host via D-Bus")
+    }
+
+    /// Reports the status ("running", "success" or "failed") of an external
+    /// job such as a backup, with optional progress (0-100).
+    pub async fn report_job(&self, name: &str, status: &str, progress: Option<f64>) -> Result<()> {
+        self.proxy
+            .report_job(name, status, progress.unwrap_or(-1.0))
+            .await
+            .context("Failed to report job via D-Bus")
+    }
+
+    /// Lists the actions accepted by `invoke_action` (JSON-encoded).
+    pub async fn list_actions(&self) -> Result<Vec<String>> {
+        self.proxy
+            .list_actions()
+            .await
+            .context("Failed to list actions via D-Bus")
+    }
+
+    /// Runs an action by ID with positional arguments. Returns a short
+    /// description of the result.
+    pub async fn invoke_action(&self, id: &str, args: Vec<String>) -> Result<String> {
+        self.proxy
+            .invoke_action(id, args)
+            .await
+            .context("Failed to invoke action via D-Bus")
+    }
+
+    /// Reverts the last display settings change. Returns the settings that
+    /// changed, e.g. "theme, LEDs".
+    pub async fn undo(&self) -> Result<String> {
+        self.proxy.undo().await.context("Failed to undo via D-Bus")
+    }
+
+    /// Reapplies the last undone display settings change. Returns the
+    /// settings that changed.
+    pub async fn redo(&self) -> Result<String> {
+        self.proxy.redo().await.context("Failed to redo via D-Bus")
+    }
+
+    /// Gets up to `count` of the most recent changes made over D-Bus and
+    /// HTTP, oldest first. Each entry is JSON with timestamp, source, caller,
+    /// method, old and new fields.
+    pub async fn get_audit_log(&self, count: u32) -> Result<Vec<String>> {
+        self.proxy
+            .get_audit_log(count)
+            .await
+            .context("Failed to get audit log via D-Bus")
+    }
+
+    /// Sets the log level of `target`, a module path such as
+    /// "ht32_panel_hw" or "" for all targets, to "off", "error", "warn",
+    /// "info", "debug" or "trace". "reset" removes a level set before.
+    pub async fn set_log_level(&self, target: &str, level: &str) -> Result<()> {
+        self.proxy
+            .set_log_level(target, level)
+            .await
+            .context("Failed to set log level via D-Bus")
+    }
+
+    /// Gets up to `count` of the most recent log events, oldest first. Each
+    /// entry is JSON with timestamp, level, target, message and fields.
+    pub async fn get_recent_logs(&self, count: u32) -> Result<Vec<String>> {
+        self.proxy
+            .get_recent_logs(count)
+            .await
+            .context("Failed to get recent logs via D-Bus")
+    }
+
+    /// Gets udev rules giving the `plugdev` group access to the LCD and the
+    /// LED serial adapter.
+    pub async fn generate_udev_rule(&self) -> Result<String> {
+        self.proxy
+            .generate_udev_rule()
+            .await
+            .context("Failed to generate udev rule via D-Bus")
+    }
+
+    /// Checks the contents of a configuration file against the daemon's
+    /// schema. Returns one message per problem, such as an unknown key or
+    /// out-of-range value, prefixed with its line and column when known; an
+    /// empty list means the file is valid.
+    pub async fn validate_config(&self, content: &str) -> Result<Vec<String>> {
+        self.proxy
+            .validate_config(content)
+            .await
+            .context("Failed to validate configuration via D-Bus")
+    }
+
+    /// Gets the health of the daemon as JSON, with its status ("ok",
+    /// "testing" or "degraded"), problems, uptime, frame count, error counts and memory use.
+    pub async fn get_health(&self) -> Result<String> {
+        self.proxy
+            .get_health()
+            .await
+            .context("Failed to get health via D-Bus")
+    }
+
+    /// Lists the optional features the daemon was built with, such as "web"
+    /// or "audio".
+    pub async fn list_capabilities(&self) -> Result<Vec<String>> {
+        self.proxy
+            .list_capabilities()
+            .await
+            .context("Failed to list capabilities via D-Bus")
+    }
+
+    /// Gets frame time statistics over recent frames: the number of frames
+    /// and, for each stage ("sensors", "render", "present" and "total"), the
+    /// 50th, 95th and 99th percentile and maximum in milliseconds.
+    pub async fn get_render_stats(&self) -> Result<(u32, Vec<StageTimes>)> {
+        self.proxy
+            .get_render_stats()
+            .await
+            .context("Failed to get render stats via D-Bus")
+    }
+
     /// Shuts down the daemon.
     pub async fn quit(&self) -> Result<()> {
         self.proxy
@@ -358,6 +1209,16 @@ impl DaemonClient {
             .context("Failed to get connection status via D-Bus")
     }
 
+    /// Gets why the LCD is not connected, such as missing permission to
+    /// open its device node, or why the last frame failed to reach it;
+    /// empty while frames are getting through.
+    pub async fn connection_error(&self) -> Result<String> {
+        self.proxy
+            .connection_error()
+            .await
+            .context("Failed to get connection error via D-Bus")
+    }
+
     /// Checks if the web UI is enabled.
     pub async fn is_web_enabled(&self) -> Result<bool> {
         self.proxy
@@ -367,8 +1228,7 @@ impl DaemonClient {
     }
 
     /// Lists complications for the current face.
-    /// Returns (id, name, description, enabled) tuples.
-    pub async fn list_complications(&self) -> Result<Vec<(String, String, String, bool)>> {
+    pub async fn list_complications(&self) -> Result<Vec<ComplicationInfo>> {
         self.proxy
             .list_complications()
             .await
@@ -432,4 +1292,147 @@ impl DaemonClient {
             .await
             .context("Failed to set complication option via D-Bus")
     }
+
+    /// Gets where a complication of the current face is placed as
+    /// (slot, size); an empty slot means the face lays it out.
+    pub async fn get_complication_position(
+        &self,
+        complication_id: &str,
+    ) -> Result<(String, String)> {
+        self.proxy
+            .get_complication_position(complication_id)
+            .await
+            .context("Failed to get complication position via D-Bus")
+    }
+
+    /// Moves a complication of the current face to a slot ("top-left",
+    /// "top-right", "bottom-left", "bottom-right" or "bottom-bar") with a
+    /// size ("small" or "large"). An empty slot returns it to the face.
+    pub async fn set_complication_position(
+        &self,
+        complication_id: &str,
+        slot: &str,
+        size: &str,
+    ) -> Result<()> {
+        self.proxy
+            .set_complication_position(complication_id, slot, size)
+            .await
+            .context("Failed to set complication position via D-Bus")
+    }
+
+    /// Lists all overlays drawn on top of every face as (id, slot, size).
+    /// Overlays that are not shown have an empty slot and size.
+    pub async fn list_overlays(&self) -> Result<Vec<(String, String, String)>> {
+        self.proxy
+            .list_overlays()
+            .await
+            .context("Failed to list overlays via D-Bus")
+    }
+
+    /// Shows an overlay on every face in a slot ("top-left", "top-right",
+    /// "bottom-left", "bottom-right" or "bottom-bar") with a size ("small"
+    /// or "large"), moving it if already shown.
+    pub async fn set_overlay(&self, id: &str, slot: &str, size: &str) -> Result<()> {
+        self.proxy
+            .set_overlay(id, slot, size)
+            .await
+            .context("Failed to set overlay via D-Bus")
+    }
+
+    /// Hides an overlay.
+    pub async fn remove_overlay(&self, id: &str) -> Result<()> {
+        self.proxy
+            .remove_overlay(id)
+            .await
+            .context("Failed to remove overlay via D-Bus")
+    }
+}
+
+/// Returns the user's XDG configuration directory, from `XDG_CONFIG_HOME`
+/// or `HOME`.
+pub fn config_home() -> Option<PathBuf> {
+    config_home_from(
+        std::env::var("XDG_CONFIG_HOME").ok().as_deref(),
+        std::env::var("HOME").ok().as_deref(),
+    )
+}
+
+/// Returns `XDG_CONFIG_HOME`, or `~/.config` when it is empty or unset.
+fn config_home_from(xdg_config_home: Option<&str>, home: Option<&str>) -> Option<PathBuf> {
+    match (xdg_config_home, home) {
+        (Some(config), _) if !config.is_empty() => Some(PathBuf::from(config)),
+        (_, Some(home)) if !home.is_empty() => Some(Path::new(home).join(".config")),
+        _ => None,
+    }
+}
+
+/// Returns whether `e` says the daemon has no such method, as daemons
+/// older than the method do.
+fn is_unknown_method(e: &zbus::Error) -> bool {
+    match e {
+        zbus::Error::MethodError(name, _, _) => {
+            name.as_str() == "org.freedesktop.DBus.Error.UnknownMethod"
+        }
+        zbus::Error::FDO(e) => matches!(**e, zbus::fdo::Error::UnknownMethod(_)),
+        _ => false,
+    }
+}
+
+/// Parses a list of JSON-encoded values, as older daemons return them.
+fn parse_json_list<T: serde::de::DeserializeOwned>(items: Vec<String>) -> Result<Vec<T>> {
+    items
+        .iter()
+        .map(|item| {
+            serde_json::from_str(item).with_context(|| format!("Unexpected D-Bus reply: {}", item))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_json_list() {
+        // As returned by ListThemesDetailed before ListThemeInfo
+        let themes: Vec<ThemeInfo> = parse_json_list(vec![
+            r#"{"id":"nord","display_name":"Nord"}"#.to_string(),
+            r#"{"id":"harbor","display_name":"harbor","custom":true}"#.to_string(),
+        ])
+        .unwrap();
+        assert!(!themes[0].custom);
+        assert_eq!(themes[1].id, "harbor");
+        assert!(themes[1].custom);
+        assert!(parse_json_list::<FaceInfo>(vec!["nord".to_string()]).is_err());
+
+        let unknown = zbus::fdo::Error::UnknownMethod("ListFaceInfo".to_string());
+        assert!(is_unknown_method(&unknown.into()));
+        let denied = zbus::fdo::Error::AccessDenied("no".to_string());
+        assert!(!is_unknown_method(&denied.into()));
+    }
+
+    #[test]
+    fn test_bus_type_resolve() {
+        assert_eq!(BusType::Auto.resolve(None), BusType::Auto);
+        assert_eq!(BusType::Auto.resolve(Some(" System\n")), BusType::System);
+        assert_eq!(BusType::Auto.resolve(Some("session")), BusType::Session);
+        // An unknown hint keeps trying both buses
+        assert_eq!(BusType::Auto.resolve(Some("both")), BusType::Auto);
+        // An explicit bus wins over the hint
+        assert_eq!(BusType::Session.resolve(Some("system")), BusType::Session);
+        assert_eq!(BusType::System.resolve(Some("session")), BusType::System);
+    }
+
+    #[test]
+    fn test_config_home() {
+        assert_eq!(
+            config_home_from(Some("/xdg"), Some("/home/me")),
+            Some(PathBuf::from("/xdg"))
+        );
+        assert_eq!(
+            config_home_from(Some(""), Some("/home/me")),
+            Some(PathBuf::from("/home/me/.config"))
+        );
+        assert_eq!(config_home_from(None, None), None);
+    }
 }
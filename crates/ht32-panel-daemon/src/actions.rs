@@ -0,0 +1,431 @@
+//! Runtime operations as discoverable actions.
+//!
+//! Each action has an ID, a display name and a list of arguments (with the
+//! accepted values where they are known), so frontends can build menus and
+//! button mappings from `ListActions` instead of hardcoding every operation.
+
+use std::sync::Arc;
+
+use anyhow::{anyhow, bail, Context, Result};
+use ht32_panel_hw::{led::LedTheme, Orientation};
+use tokio::runtime::Handle;
+use tracing::{debug, warn};
+
+use crate::events::{Event, EventBus};
+use crate::faces;
+use crate::state::AppState;
+
+/// Brightness change of the brightness up/down actions, in percent.
+const BRIGHTNESS_STEP: i32 = 10;
+
+/// An argument taken by an action.
+pub struct ActionArg {
+    /// Argument ID
+    pub id: &'static str,
+    /// Human-readable name
+    pub name: &'static str,
+    /// Accepted values (empty for free text)
+    pub choices: Vec<String>,
+}
+
+/// An operation that can be invoked by ID.
+pub struct Action {
+    /// Action ID passed to `invoke`
+    pub id: &'static str,
+    /// Human-readable name
+    pub name: &'static str,
+    /// What the action does
+    pub description: &'static str,
+    /// Positional arguments
+    pub args: Vec<ActionArg>,
+}
+
+impl Action {
+    fn new(id: &'static str, name: &'static str, description: &'static str) -> Self {
+        Self {
+            id,
+            name,
+            description,
+            args: Vec::new(),
+        }
+    }
+
+    fn arg(mut self, id: &'static str, name: &'static str, choices: Vec<String>) -> Self {
+        self.args.push(ActionArg { id, name, choices });
+        self
+    }
+
+    /// Returns the action as JSON for D-Bus clients.
+    pub fn to_json(&self) -> serde_json::Value {
+        let args: Vec<_> = self
+            .args
+            .iter()
+            .map(|a| {
+                serde_json::json!({
+                    "id": a.id,
+                    "name": a.name,
+                    "choices": a.choices,
+                })
+            })
+            .collect();
+        serde_json::json!({
+            "id": self.id,
+            "name": self.name,
+            "description": self.description,
+            "args": args,
+        })
+    }
+}
+
+/// Result of a successful action.
+pub struct ActionOutcome {
+    /// Short description of what happened
+    pub message: String,
+    /// Change event to publish, if any
+    pub event: Option<Event>,
+}
+
+impl ActionOutcome {
+    fn new(message: impl Into<String>, event: Option<Event>) -> Self {
+        Self {
+            message: message.into(),
+            event,
+        }
+    }
+}
+
+fn strings(values: &[&str]) -> Vec<String> {
+    values.iter().map(|v| v.to_string()).collect()
+}
+
+/// Returns every available action.
+pub fn available_actions() -> Vec<Action> {
+    let face_ids: Vec<String> = faces::available_faces()
+        .iter()
+        .map(|f| f.id.to_string())
+        .collect();
+    let theme_ids: Vec<String> = faces::available_themes()
+        .iter()
+        .map(|t| t.id.to_string())
+        .collect();
+    let led_effects = strings(&["rainbow", "breathing", "colors", "auto"]);
+
+    vec![
+        Action::new("next_face", "Next face", "Switch to the next face"),
+        Action::new(
+            "previous_face",
+            "Previous face",
+            "Switch to the previous face",
+        ),
+        Action::new(
+            "random_face",
+            "Random face",
+            "Switch to a random face and theme",
+        ),
+        Action::new("set_face", "Set face", "Switch to a face").arg("face", "Face", face_ids),
+        Action::new("next_theme", "Next theme", "Switch to the next color theme"),
+        Action::new("set_theme", "Set theme", "Switch to a color theme")
+            .arg("theme", "Theme", theme_ids),
+        Action::new("set_orientation", "Set orientation", "Rotate the display").arg(
+            "orientation",
+            "Orientation",
+            strings(&[
+                "landscape",
+                "portrait",
+                "landscape-upside-down",
+                "portrait-upside-down",
+            ]),
+        ),
+        Action::new(
+            "toggle_display",
+            "Toggle display",
+            "Blank the panel, or show frames again",
+        ),
+        Action::new(
+            "brightness_up",
+            "Brightness up",
+            "Raise the display brightness by 10%",
+        ),
+        Action::new(
+            "brightness_down",
+            "Brightness down",
+            "Lower the display brightness by 10%",
+        ),
+        Action::new(
+            "set_brightness",
+            "Set brightness",
+            "Set the display brightness (10-100%)",
+        )
+        .arg("percent", "Percent", Vec::new()),
+        Action::new(
+            "toggle_led",
+            "Toggle LEDs",
+            "Turn the LEDs off, or back on with their previous effect",
+        ),
+        Action::new("led_off", "LEDs off", "Turn the LEDs off"),
+        Action::new(
+            "set_led_effect",
+            "Set LED effect",
+            "Change the LED effect, keeping intensity and speed",
+        )
+        .arg("effect", "Effect", led_effects.clone()),
+        Action::new(
+            "pulse_led",
+            "Pulse LEDs",
+            "Flash the LEDs as a notification",
+        )
+        .arg("effect", "Effect", led_effects)
+        .arg("count", "Count", Vec::new()),
+        Action::new(
+            "toggle_dnd",
+            "Toggle do-not-disturb",
+            "Hold back alert banners and LED pulses, or let them through again",
+        ),
+        Action::new("undo", "Undo", "Revert the last settings change"),
+        Action::new("redo", "Redo", "Reapply the last undone settings change"),
+        #[cfg(feature = "speedtest")]
+        Action::new(
+            "run_speedtest",
+            "Run speedtest",
+            "Measure bandwidth and show the result on the panel",
+        ),
+        Action::new(
+            "wake_host",
+            "Wake host",
+            "Send a Wake-on-LAN packet to a MAC address or configured host",
+        )
+        .arg("host", "Host", Vec::new()),
+    ]
+}
+
+/// Runs `action` (an ID followed by its arguments) on the tokio runtime,
+/// for callers on plain threads. `source` names the caller in log messages.
+pub fn spawn(
+    runtime: &Handle,
+    state: &Arc<AppState>,
+    events: &EventBus,
+    action: &[String],
+    source: &'static str,
+) {
+    let Some((id, args)) = action.split_first() else {
+        return;
+    };
+    let state = state.clone();
+    let events = events.clone();
+    let id = id.clone();
+    let args = args.to_vec();
+    runtime.spawn(async move {
+        match invoke(&state, &id, &args).await {
+            Ok(outcome) => {
+                if let Some(event) = outcome.event {
+                    events.publish(event);
+                }
+                debug!("{} {}: {}", source, id, outcome.message);
+            }
+            Err(e) => warn!("{} action {} failed: {:#}", source, id, e),
+        }
+    });
+}
+
+/// Returns the argument at `index`, or an error naming it.
+fn arg<'a>(action: &Action, args: &'a [String], index: usize) -> Result<&'a str> {
+    args.get(index)
+        .map(String::as_str)
+        .ok_or_else(|| anyhow!("Missing argument: {}", action.args[index].id))
+}
+
+/// Formats an action and its arguments as in `ht32panelctl action run`.
+pub fn describe(id: &str, args: &[String]) -> String {
+    std::iter::once(id)
+        .chain(args.iter().map(String::as_str))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Runs the action `id` with positional `args`.
+pub async fn invoke(state: &Arc<AppState>, id: &str, args: &[String]) -> Result<ActionOutcome> {
+    let action = available_actions()
+        .into_iter()
+        .find(|a| a.id == id)
+        .ok_or_else(|| anyhow!("Unknown action: {}", id))?;
+    if args.len() > action.args.len() {
+        bail!(
+            "{} takes {} argument(s), got {}",
+            id,
+            action.args.len(),
+            args.len()
+        );
+    }
+    let display = Some(Event::DisplaySettingsChanged);
+    let led = Some(Event::LedChanged);
+
+    let outcome = match id {
+        "next_face" => {
+            let face = state.cycle_face(1)?;
+            ActionOutcome::new(format!("Face set to {}", face), display)
+        }
+        "previous_face" => {
+            let face = state.cycle_face(-1)?;
+            ActionOutcome::new(format!("Face set to {}", face), display)
+        }
+        "random_face" => {
+            state.shuffle_face()?;
+            ActionOutcome::new(format!("Face set to {}", state.face_name()), display)
+        }
+        "set_face" => {
+            let face = arg(&action, args, 0)?;
+            state.set_face(face)?;
+            ActionOutcome::new(format!("Face set to {}", face), display)
+        }
+        "next_theme" => {
+            let theme = state.cycle_theme(1)?;
+            ActionOutcome::new(format!("Theme set to {}", theme), display)
+        }
+        "set_theme" => {
+            let theme = arg(&action, args, 0)?;
+            state.set_theme(theme)?;
+            ActionOutcome::new(format!("Theme set to {}", theme), display)
+        }
+        "set_orientation" => {
+            let orientation: Orientation = arg(&action, args, 0)?
+                .parse()
+                .map_err(|_| anyhow!("Invalid orientation"))?;
+            state.set_orientation(orientation)?;
+            ActionOutcome::new(
+                format!("Orientation set to {}", orientation),
+                Some(Event::OrientationChanged),
+            )
+        }
+        "toggle_display" => {
+            let on = !state.display_on();
+            state.set_display_on(on);
+            ActionOutcome::new(if on { "Display on" } else { "Display off" }, None)
+        }
+        "brightness_up" | "brightness_down" | "set_brightness" => {
+            let percent = match id {
+                "brightness_up" => state.brightness() as i32 + BRIGHTNESS_STEP,
+                "brightness_down" => state.brightness() as i32 - BRIGHTNESS_STEP,
+                _ => arg(&action, args, 0)?
+                    .trim_end_matches('%')
+                    .parse()
+                    .context("Brightness must be a number")?,
+            };
+            let brightness = state.set_brightness(percent);
+            ActionOutcome::new(format!("Brightness {}%", brightness), display)
+        }
+        "toggle_led" => {
+            let on = state.toggle_led().await?;
+            ActionOutcome::new(if on { "LEDs on" } else { "LEDs off" }, led)
+        }
+        "led_off" => {
+            state.led_off().await?;
+            ActionOutcome::new("LEDs off", led)
+        }
+        "set_led_effect" => {
+            let effect = arg(&action, args, 0)?;
+            let theme: LedTheme = effect
+                .parse()
+                .map_err(|_| anyhow!("Invalid LED effect: {}", effect))?;
+            let settings = state
+                .led_settings()
+                .to_builder()
+                .theme(theme as u8)
+                .build()?;
+            state.set_led(settings).await?;
+            ActionOutcome::new(format!("LED effect set to {}", effect), led)
+        }
+        "pulse_led" => {
+            let effect = arg(&action, args, 0)?;
+            let theme: LedTheme = effect
+                .parse()
+                .ok()
+                .filter(|t| *t != LedTheme::Off)
+                .ok_or_else(|| anyhow!("Invalid LED effect: {}", effect))?;
+            let count: u32 = match args.get(1) {
+                Some(count) => count.parse().context("Count must be a number")?,
+                None => 3,
+            };
+            if !(1..=20).contains(&count) {
+                bail!("Count must be 1-20");
+            }
+            let state = state.clone();
+            tokio::spawn(async move {
+                if let Err(e) = state.pulse_led(theme, count).await {
+                    warn!("LED pulse failed: {:#}", e);
+                }
+            });
+            ActionOutcome::new(format!("Pulsing LEDs {} times", count), None)
+        }
+        "toggle_dnd" => {
+            let on = !state.dnd();
+            state.set_dnd(on);
+            ActionOutcome::new(
+                if on {
+                    "Do-not-disturb on"
+                } else {
+                    "Do-not-disturb off"
+                },
+                display,
+            )
+        }
+        "undo" => {
+            let changes = state.undo()?;
+            ActionOutcome::new(format!("Reverted {}", changes), display)
+        }
+        "redo" => {
+            let changes = state.redo()?;
+            ActionOutcome::new(format!("Reapplied {}", changes), display)
+        }
+        #[cfg(feature = "speedtest")]
+        "run_speedtest" => {
+            // The result is shown on the panel; nothing waits for it here
+            drop(state.run_speedtest()?);
+            ActionOutcome::new("Speedtest started", None)
+        }
+        "wake_host" => {
+            let mac = state.wake_host(arg(&action, args, 0)?)?;
+            ActionOutcome::new(format!("Wake-on-LAN packet sent to {}", mac), None)
+        }
+        _ => bail!("Action {} is not implemented", id),
+    };
+    Ok(outcome)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_available_actions() {
+        let actions = available_actions();
+        let mut ids: Vec<_> = actions.iter().map(|a| a.id).collect();
+        ids.sort_unstable();
+        ids.dedup();
+        assert_eq!(ids.len(), actions.len());
+
+        let set_face = actions.iter().find(|a| a.id == "set_face").unwrap();
+        let json = set_face.to_json();
+        assert_eq!(json["args"][0]["id"], "face");
+        assert!(json["args"][0]["choices"]
+            .as_array()
+            .unwrap()
+            .contains(&"ascii".into()));
+    }
+
+    #[test]
+    fn test_arg() {
+        let actions = available_actions();
+        let pulse = actions.iter().find(|a| a.id == "pulse_led").unwrap();
+        let args = vec!["breathing".to_string()];
+        assert_eq!(arg(pulse, &args, 0).unwrap(), "breathing");
+        let error = arg(pulse, &args, 1).unwrap_err();
+        assert_eq!(error.to_string(), "Missing argument: count");
+    }
+
+    #[test]
+    fn test_describe() {
+        assert_eq!(describe("next_face", &[]), "next_face");
+        let args = vec!["breathing".to_string(), "3".to_string()];
+        assert_eq!(describe("pulse_led", &args), "pulse_led breathing 3");
+    }
+}
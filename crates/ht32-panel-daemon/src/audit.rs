@@ -0,0 +1,155 @@
+//! Audit log of control operations.
+//!
+//! Every change made over D-Bus or HTTP is appended as a JSON line to
+//! `audit.log` in the state directory, with who made it and the value before
+//! and after. The file is rotated to `audit.log.1`, `audit.log.2`, ... once
+//! it grows past [`MAX_FILE_BYTES`]. The most recent entries are also kept in
+//! memory for `GetAuditLog`.
+
+use std::collections::VecDeque;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use tracing::{debug, warn};
+
+/// Size at which the log file is rotated.
+const MAX_FILE_BYTES: u64 = 1024 * 1024;
+
+/// Number of rotated files kept besides the current one.
+const ROTATED_FILES: u32 = 3;
+
+/// Number of entries kept in memory.
+const MAX_RECENT: usize = 500;
+
+/// A recorded change.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditEntry {
+    /// When the change was made (RFC 3339)
+    pub timestamp: String,
+    /// How the change arrived: "dbus" or "http"
+    pub source: String,
+    /// Who made the change: D-Bus caller's user and unique name, or the
+    /// HTTP client's address
+    pub caller: String,
+    /// Operation, named after the D-Bus method
+    pub method: String,
+    /// Value before the change (empty when not applicable)
+    pub old: String,
+    /// Value after the change, or the arguments
+    pub new: String,
+}
+
+/// Append-only audit log.
+pub struct AuditLog {
+    path: PathBuf,
+    recent: Mutex<VecDeque<AuditEntry>>,
+}
+
+impl AuditLog {
+    /// Opens the log in `state_dir`, loading its most recent entries.
+    pub fn open(state_dir: &Path) -> Self {
+        let path = state_dir.join("audit.log");
+        let mut recent = VecDeque::new();
+        if let Ok(contents) = fs::read_to_string(&path) {
+            for line in contents.lines() {
+                if let Ok(entry) = serde_json::from_str::<AuditEntry>(line) {
+                    if recent.len() == MAX_RECENT {
+                        recent.pop_front();
+                    }
+                    recent.push_back(entry);
+                }
+            }
+        }
+        Self {
+            path,
+            recent: Mutex::new(recent),
+        }
+    }
+
+    /// Records a change.
+    pub fn record(&self, source: &str, caller: &str, method: &str, old: &str, new: &str) {
+        let entry = AuditEntry {
+            timestamp: chrono::Local::now().to_rfc3339(),
+            source: source.to_string(),
+            caller: caller.to_string(),
+            method: method.to_string(),
+            old: old.to_string(),
+            new: new.to_string(),
+        };
+        debug!(
+            "Audit: {} {} {}: {:?} -> {:?}",
+            source, caller, method, old, new
+        );
+        if let Err(e) = self.append(&entry) {
+            warn!("Failed to write audit log {:?}: {}", self.path, e);
+        }
+
+        let mut recent = self.recent.lock().unwrap();
+        if recent.len() == MAX_RECENT {
+            recent.pop_front();
+        }
+        recent.push_back(entry);
+    }
+
+    /// Returns up to `count` of the most recent entries, oldest first.
+    pub fn recent(&self, count: usize) -> Vec<AuditEntry> {
+        let recent = self.recent.lock().unwrap();
+        let skip = recent.len().saturating_sub(count);
+        recent.iter().skip(skip).cloned().collect()
+    }
+
+    /// Appends an entry to the file, rotating it first if it is full.
+    fn append(&self, entry: &AuditEntry) -> std::io::Result<()> {
+        let size = fs::metadata(&self.path).map(|m| m.len()).unwrap_or(0);
+        if size >= MAX_FILE_BYTES {
+            self.rotate();
+        }
+        let mut line = serde_json::to_string(entry)?;
+        line.push('\n');
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?
+            .write_all(line.as_bytes())
+    }
+
+    /// Shifts `audit.log.N` to `audit.log.N+1`, dropping the oldest.
+    fn rotate(&self) {
+        let rotated = |n: u32| PathBuf::from(format!("{}.{}", self.path.display(), n));
+        let _ = fs::remove_file(rotated(ROTATED_FILES));
+        for n in (1..ROTATED_FILES).rev() {
+            let _ = fs::rename(rotated(n), rotated(n + 1));
+        }
+        if let Err(e) = fs::rename(&self.path, rotated(1)) {
+            warn!("Failed to rotate audit log {:?}: {}", self.path, e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_and_reload() {
+        let dir = std::env::temp_dir().join(format!("ht32-audit-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+
+        let log = AuditLog::open(&dir);
+        log.record("dbus", "uid 1000 (:1.42)", "SetTheme", "default", "nord");
+        log.record("http", "192.168.1.20", "SetFace", "professional", "clocks");
+        assert_eq!(log.recent(1)[0].method, "SetFace");
+        assert_eq!(log.recent(10).len(), 2);
+
+        let reopened = AuditLog::open(&dir);
+        let entries = reopened.recent(10);
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].old, "default");
+        assert_eq!(entries[0].new, "nord");
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,114 @@
+//! Orientation auto-detection.
+//!
+//! Guesses how the panel is mounted from hints the host exposes: an iio
+//! accelerometer gives the direction of gravity, and failing that the DMI
+//! chassis type tells tower cases, whose panel stands upright, from desktop
+//! cases, whose panel lies on its long edge.
+
+use std::fs;
+use std::path::Path;
+
+use ht32_panel_hw::Orientation;
+use tracing::debug;
+
+/// Directory holding iio devices.
+const IIO_DEVICES: &str = "/sys/bus/iio/devices";
+
+/// DMI chassis type of the host.
+const CHASSIS_TYPE: &str = "/sys/class/dmi/id/chassis_type";
+
+/// Returns the orientation hinted by the host, or None without a usable
+/// hint.
+pub fn detect() -> Option<Orientation> {
+    if let Some(orientation) = read_accelerometer() {
+        debug!("Accelerometer suggests {} orientation", orientation);
+        return Some(orientation);
+    }
+    let chassis = fs::read_to_string(CHASSIS_TYPE).ok()?;
+    let orientation = from_chassis_type(chassis.trim().parse().ok()?)?;
+    debug!(
+        "Chassis type {} suggests {} orientation",
+        chassis.trim(),
+        orientation
+    );
+    Some(orientation)
+}
+
+/// Reads the first iio accelerometer and converts its reading.
+fn read_accelerometer() -> Option<Orientation> {
+    let read_axis = |device: &Path, axis: &str| -> Option<f64> {
+        fs::read_to_string(device.join(format!("in_accel_{}_raw", axis)))
+            .ok()?
+            .trim()
+            .parse()
+            .ok()
+    };
+    fs::read_dir(IIO_DEVICES)
+        .ok()?
+        .flatten()
+        .map(|entry| entry.path())
+        .find_map(|device| {
+            let x = read_axis(&device, "x")?;
+            let y = read_axis(&device, "y")?;
+            let z = read_axis(&device, "z")?;
+            from_gravity(x, y, z)
+        })
+}
+
+/// Converts an accelerometer reading to the orientation that keeps the
+/// top edge up. Returns None when the device lies flat or reads zero.
+fn from_gravity(x: f64, y: f64, z: f64) -> Option<Orientation> {
+    let (ax, ay, az) = (x.abs(), y.abs(), z.abs());
+    if az >= ax.max(ay) || ax.max(ay) == 0.0 {
+        return None;
+    }
+    Some(if ay >= ax {
+        if y > 0.0 {
+            Orientation::Landscape
+        } else {
+            Orientation::LandscapeUpsideDown
+        }
+    } else if x > 0.0 {
+        Orientation::Portrait
+    } else {
+        Orientation::PortraitUpsideDown
+    })
+}
+
+/// Maps an SMBIOS chassis type to the usual orientation of a panel in it.
+fn from_chassis_type(chassis: u32) -> Option<Orientation> {
+    match chassis {
+        // Desktop, low profile desktop and pizza box
+        3..=5 => Some(Orientation::Landscape),
+        // Mini tower and tower
+        6 | 7 => Some(Orientation::Portrait),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hints_map_to_orientations() {
+        assert_eq!(
+            from_gravity(10.0, 980.0, 30.0),
+            Some(Orientation::Landscape)
+        );
+        assert_eq!(
+            from_gravity(5.0, -970.0, 0.0),
+            Some(Orientation::LandscapeUpsideDown)
+        );
+        assert_eq!(
+            from_gravity(-990.0, 20.0, 40.0),
+            Some(Orientation::PortraitUpsideDown)
+        );
+        assert_eq!(from_gravity(10.0, 20.0, 985.0), None);
+        assert_eq!(from_gravity(0.0, 0.0, 0.0), None);
+
+        assert_eq!(from_chassis_type(3), Some(Orientation::Landscape));
+        assert_eq!(from_chassis_type(7), Some(Orientation::Portrait));
+        assert_eq!(from_chassis_type(10), None);
+    }
+}
@@ -0,0 +1,106 @@
+//! Buttons on the panel hardware.
+//!
+//! Polls the panel's input interfaces on a background thread. Every press
+//! is broadcast as `ButtonPressed` (and logged, so codes can be found for
+//! the configuration); presses with a binding also run its action.
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use ht32_panel_hw::lcd::PanelInput;
+use tokio::runtime::Handle;
+use tracing::{debug, info, warn};
+
+use crate::actions;
+use crate::config::ButtonsConfig;
+use crate::events::{Event, EventBus};
+use crate::state::AppState;
+
+/// Delay between polls for input reports.
+const POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+/// Delay before reopening the panel after it was not found or failed.
+const REOPEN_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Starts reading panel buttons. Must be called from within the tokio
+/// runtime, which runs the bound actions.
+pub fn start(config: &ButtonsConfig, state: Arc<AppState>, events: EventBus) {
+    let bindings = bindings(config);
+    let runtime = Handle::current();
+
+    let spawned = thread::Builder::new()
+        .name("buttons".to_string())
+        .spawn(move || loop {
+            let input = match PanelInput::open() {
+                Ok(input) => input,
+                Err(e) => {
+                    debug!("Panel input not available: {}", e);
+                    thread::sleep(REOPEN_INTERVAL);
+                    continue;
+                }
+            };
+            loop {
+                match input.poll() {
+                    Ok(Some(report)) if !report.is_release() => {
+                        let code = report.code();
+                        info!(
+                            "Panel button report {} (interface {})",
+                            code, report.interface
+                        );
+                        events.publish(Event::ButtonPressed(code.clone()));
+                        if let Some(action) = bindings.get(&code) {
+                            actions::spawn(&runtime, &state, &events, action, "Button");
+                        }
+                    }
+                    Ok(Some(_)) => {}
+                    Ok(None) => thread::sleep(POLL_INTERVAL),
+                    Err(e) => {
+                        warn!("Reading panel input failed: {}", e);
+                        break;
+                    }
+                }
+            }
+            thread::sleep(REOPEN_INTERVAL);
+        });
+    if let Err(e) = spawned {
+        warn!("Failed to start panel button reader: {}", e);
+    }
+}
+
+/// Returns the configured actions (split into an ID and arguments) by
+/// report code, with codes normalized to match `InputReport::code`.
+fn bindings(config: &ButtonsConfig) -> BTreeMap<String, Vec<String>> {
+    config
+        .bindings
+        .iter()
+        .map(|(code, action)| {
+            (
+                code.trim().to_lowercase(),
+                action.split_whitespace().map(String::from).collect(),
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bindings() {
+        let config: ButtonsConfig = toml::from_str(
+            r#"
+            enable = true
+            [bindings]
+            " 01 0A " = "next_face"
+            "02" = "pulse_led  breathing 3"
+            "#,
+        )
+        .unwrap();
+        let bindings = bindings(&config);
+        assert_eq!(bindings["01 0a"], ["next_face"]);
+        assert_eq!(bindings["02"], ["pulse_led", "breathing", "3"]);
+    }
+}
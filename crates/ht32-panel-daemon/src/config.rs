@@ -2,12 +2,20 @@
 
 #![allow(dead_code)]
 
-use anyhow::{Context, Result};
+use anyhow::{anyhow, Context, Result};
+use ht32_panel_hw::lcd::{ReportKind, TransferSettings, MAX_CHUNK_SIZE};
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
 use std::path::Path;
+use std::time::Duration;
+
+use crate::config_check;
+use crate::persist::write_atomic;
+use crate::sensors::data::collector_names;
 
 /// Main configuration structure.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct Config {
     /// Web server configuration
     #[serde(default)]
@@ -29,6 +37,11 @@ pub struct Config {
     #[serde(default = "default_heartbeat")]
     pub heartbeat: u64,
 
+    /// Read-only mode: reject changes over D-Bus (except from root) and the
+    /// web UI
+    #[serde(default)]
+    pub kiosk: bool,
+
     /// Device configuration
     #[serde(default)]
     pub devices: DevicesConfig,
@@ -36,10 +49,63 @@ pub struct Config {
     /// Canvas configuration
     #[serde(default)]
     pub canvas: CanvasConfig,
+
+    /// Boot splash and shutdown screen configuration
+    #[serde(default)]
+    pub splash: SplashConfig,
+
+    /// Optional sensors configuration
+    #[serde(default)]
+    pub sensors: SensorsConfig,
+
+    /// LED configuration
+    #[serde(default)]
+    pub led: LedConfig,
+
+    /// On-demand speedtest configuration
+    #[serde(default)]
+    pub speedtest: SpeedtestConfig,
+
+    /// Outbound webhooks notified of daemon events
+    #[serde(default)]
+    pub webhooks: Vec<WebhookConfig>,
+
+    /// Stream Deck and macro button HTTP endpoint
+    #[serde(default)]
+    pub streamdeck: StreamDeckConfig,
+
+    /// Keyboard hotkeys (requires the `hotkeys` feature)
+    #[serde(default)]
+    pub hotkeys: HotkeysConfig,
+
+    /// Buttons on the panel hardware
+    #[serde(default)]
+    pub buttons: ButtonsConfig,
+
+    /// Scheduled warm colors at night
+    #[serde(default)]
+    pub night_light: NightLightConfig,
+
+    /// Do-not-disturb schedule
+    #[serde(default)]
+    pub dnd: DndConfig,
+
+    /// How alerts are announced besides the banner
+    #[serde(default)]
+    pub alerts: AlertsConfig,
+
+    /// Faces switched to automatically while a condition holds
+    #[serde(default)]
+    pub rules: Vec<RuleConfig>,
+
+    /// Background skins by face ID
+    #[serde(default)]
+    pub skins: HashMap<String, SkinConfig>,
 }
 
 /// Web server configuration.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct WebConfig {
     /// Whether to enable the web server
     #[serde(default)]
@@ -48,6 +114,10 @@ pub struct WebConfig {
     /// Server listen address (e.g., "0.0.0.0:8686")
     #[serde(default = "default_listen")]
     pub listen: String,
+
+    /// Whether to advertise the web UI on the LAN via mDNS
+    #[serde(default = "default_true")]
+    pub mdns: bool,
 }
 
 impl Default for WebConfig {
@@ -55,6 +125,7 @@ impl Default for WebConfig {
         Self {
             enable: false,
             listen: default_listen(),
+            mdns: true,
         }
     }
 }
@@ -74,43 +145,150 @@ pub enum DbusBusType {
 
 /// D-Bus configuration.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct DbusConfig {
     /// Which D-Bus bus to use.
     #[serde(default)]
     pub bus: DbusBusType,
+
+    /// Whether to check callers with polkit before changes (system bus only).
+    #[serde(default)]
+    pub polkit: bool,
 }
 
 impl Default for DbusConfig {
     fn default() -> Self {
         Self {
             bus: DbusBusType::Auto,
+            polkit: false,
         }
     }
 }
 
+/// Display output type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputKind {
+    /// The HT32 panel's LCD over USB HID
+    #[default]
+    Ht32,
+    /// A Linux framebuffer device, such as an SPI or HDMI status screen
+    Fbdev,
+    /// A panel on another machine running `ht32panelrx`
+    Network,
+}
+
 /// Device configuration for LCD and LED hardware.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct DevicesConfig {
+    /// Display the frames are sent to
+    #[serde(default)]
+    pub output: OutputKind,
+
     /// LCD device path or "auto" for auto-detection
     #[serde(default = "default_lcd_device")]
     pub lcd: String,
 
+    /// Framebuffer device used by the fbdev output
+    #[serde(default = "default_fbdev_device")]
+    pub fbdev: String,
+
+    /// Receiver address ("host:port") used by the network output
+    #[serde(default)]
+    pub remote: String,
+
     /// LED serial port path
     #[serde(default = "default_led_device")]
     pub led: String,
+
+    /// USB transfer tuning for the HT32 panel's LCD
+    #[serde(default)]
+    pub transfer: TransferConfig,
 }
 
 impl Default for DevicesConfig {
     fn default() -> Self {
         Self {
+            output: OutputKind::default(),
             lcd: default_lcd_device(),
+            fbdev: default_fbdev_device(),
+            remote: String::new(),
             led: default_led_device(),
+            transfer: TransferConfig::default(),
+        }
+    }
+}
+
+/// Kind of HID report redraws are sent in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum TransferReport {
+    /// Output reports on the interrupt endpoint
+    #[default]
+    Output,
+    /// Feature reports on the control endpoint
+    Feature,
+}
+
+/// How redraws are sent to the HT32 panel. The defaults send full reports
+/// back to back; some USB hubs need a gentler pace.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TransferConfig {
+    /// Try candidate settings at startup and use the fastest that works,
+    /// falling back to the settings below. Only USB errors count as not
+    /// working; frames garbled without one are not noticed
+    #[serde(default)]
+    pub auto: bool,
+
+    /// Pixel bytes per report, a multiple of 512 up to 4096
+    #[serde(default = "default_chunk_size")]
+    pub chunk_size: usize,
+
+    /// Pause between reports in milliseconds (0-100)
+    #[serde(default)]
+    pub chunk_delay: u64,
+
+    /// Report kind
+    #[serde(default)]
+    pub report: TransferReport,
+
+    /// Send only the changed parts of each frame as partial refreshes,
+    /// falling back to full redraws if the panel rejects them
+    #[serde(default)]
+    pub partial_updates: bool,
+}
+
+impl Default for TransferConfig {
+    fn default() -> Self {
+        Self {
+            auto: false,
+            chunk_size: default_chunk_size(),
+            chunk_delay: 0,
+            report: TransferReport::default(),
+            partial_updates: false,
+        }
+    }
+}
+
+impl TransferConfig {
+    /// Returns the configured settings.
+    pub fn settings(&self) -> TransferSettings {
+        TransferSettings {
+            chunk_size: self.chunk_size,
+            chunk_delay: Duration::from_millis(self.chunk_delay),
+            report: match self.report {
+                TransferReport::Output => ReportKind::Output,
+                TransferReport::Feature => ReportKind::Feature,
+            },
         }
     }
 }
 
 /// Canvas configuration.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct CanvasConfig {
     /// Canvas width
     #[serde(default = "default_width")]
@@ -119,6 +297,11 @@ pub struct CanvasConfig {
     /// Canvas height
     #[serde(default = "default_height")]
     pub height: u32,
+
+    /// Orientation until one is chosen at runtime: an orientation name or
+    /// "auto" to detect it from the host
+    #[serde(default = "default_orientation")]
+    pub orientation: String,
 }
 
 impl Default for CanvasConfig {
@@ -126,66 +309,1173 @@ impl Default for CanvasConfig {
         Self {
             width: default_width(),
             height: default_height(),
+            orientation: default_orientation(),
         }
     }
 }
 
-// Default value functions
-fn default_listen() -> String {
-    "[::1]:8686".to_string()
+/// Boot splash and shutdown screen configuration.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SplashConfig {
+    /// Show a splash screen while sensors warm up
+    #[serde(default = "default_true")]
+    pub enable: bool,
+
+    /// How long the splash stays on screen in milliseconds
+    #[serde(default = "default_splash_duration")]
+    pub duration: u64,
+
+    /// Optional logo image shown above the hostname
+    #[serde(default)]
+    pub logo: Option<String>,
+
+    /// Push a goodbye frame when the daemon exits
+    #[serde(default = "default_true")]
+    pub shutdown_screen: bool,
+
+    /// Message shown on the shutdown screen
+    #[serde(default = "default_shutdown_message")]
+    pub shutdown_message: String,
 }
 
-fn default_state_dir() -> String {
-    // Check STATE_DIRECTORY first (set by systemd when StateDirectory= is configured)
-    // Then fall back to XDG state directory or /var/lib
-    if let Ok(state_dir) = std::env::var("STATE_DIRECTORY") {
-        state_dir
-    } else if let Ok(state_home) = std::env::var("XDG_STATE_HOME") {
-        format!("{}/ht32-panel", state_home)
-    } else if let Ok(home) = std::env::var("HOME") {
-        format!("{}/.local/state/ht32-panel", home)
-    } else {
-        "/var/lib/ht32-panel".to_string()
+impl Default for SplashConfig {
+    fn default() -> Self {
+        Self {
+            enable: true,
+            duration: default_splash_duration(),
+            logo: None,
+            shutdown_screen: true,
+            shutdown_message: default_shutdown_message(),
+        }
     }
 }
 
-fn default_refresh_interval() -> u64 {
-    2500
+/// LED settings applied for a display theme.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LedThemeMapping {
+    /// LED theme byte (1=rainbow, 2=breathing, 3=colors, 4=off, 5=auto)
+    pub theme: u8,
+
+    /// LED intensity (1-5)
+    pub intensity: u8,
+
+    /// LED speed (1-5)
+    pub speed: u8,
 }
 
-fn default_heartbeat() -> u64 {
-    1000
+impl LedThemeMapping {
+    const fn new(theme: u8, intensity: u8, speed: u8) -> Self {
+        Self {
+            theme,
+            intensity,
+            speed,
+        }
+    }
 }
 
-fn default_lcd_device() -> String {
-    "auto".to_string()
+/// LED configuration.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LedConfig {
+    /// Per display theme LED settings used when the LEDs follow the theme.
+    /// Entries here override the built-in mapping.
+    #[serde(default)]
+    pub theme_map: HashMap<String, LedThemeMapping>,
 }
 
-fn default_led_device() -> String {
-    "/dev/ttyUSB0".to_string()
+impl LedConfig {
+    /// Returns the LED settings for a display theme.
+    pub fn mapping_for(&self, theme: &str) -> LedThemeMapping {
+        if let Some(mapping) = self.theme_map.get(theme) {
+            return mapping.clone();
+        }
+        match theme {
+            // Warm, lively glow
+            "ember" => LedThemeMapping::new(2, 4, 3),
+            // Busy color cycling to match the terminal look
+            "hacker" => LedThemeMapping::new(3, 3, 4),
+            // Calm, dim and slow
+            "nord" | "tokyonight" => LedThemeMapping::new(2, 2, 1),
+            "solarized-dark" | "solarized-light" => LedThemeMapping::new(2, 2, 2),
+            _ => LedThemeMapping::new(1, 3, 3),
+        }
+    }
 }
 
-fn default_width() -> u32 {
-    320
+/// Optional sensors that are off by default.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SensorsConfig {
+    /// Built-in collectors that can be switched off
+    #[serde(default)]
+    pub collectors: CollectorsConfig,
+
+    /// CPU temperature input
+    #[serde(default)]
+    pub temperature: TemperatureConfig,
+
+    /// Pending package updates
+    #[serde(default)]
+    pub updates: UpdatesConfig,
+
+    /// Audio capture (requires the `audio` feature)
+    #[serde(default)]
+    pub audio: AudioConfig,
+
+    /// LAN hosts shown on the hosts face
+    #[serde(default)]
+    pub hosts: HostsConfig,
+
+    /// SNMP agents polled for custom metrics
+    #[serde(default)]
+    pub snmp: Vec<SnmpTargetConfig>,
+
+    /// HTTP JSON endpoints scraped for custom metrics
+    #[serde(default)]
+    pub json: Vec<JsonSourceConfig>,
+
+    /// Commands whose `key=value` output becomes custom metrics
+    #[serde(default)]
+    pub exec: Vec<ExecSourceConfig>,
+
+    /// Pi-hole or AdGuard Home blocking statistics
+    #[serde(default)]
+    pub dns_filter: DnsFilterConfig,
+
+    /// GPU readings (NVIDIA via nvidia-smi, AMD via sysfs)
+    #[serde(default)]
+    pub gpu: GpuConfig,
+
+    /// Libvirt virtual machines
+    #[serde(default)]
+    pub vms: VmsConfig,
+
+    /// ZFS pool and md array health
+    #[serde(default)]
+    pub storage: StorageConfig,
+
+    /// OctoPrint or Moonraker print progress
+    #[serde(default)]
+    pub printer: PrinterConfig,
+
+    /// qBittorrent or Transmission download statistics
+    #[serde(default)]
+    pub torrent: TorrentConfig,
+
+    /// Jellyfin or Plex active streams
+    #[serde(default)]
+    pub media_server: MediaServerConfig,
+
+    /// System journal warnings shown on the timeline face
+    #[serde(default)]
+    pub journal: JournalConfig,
 }
 
-fn default_height() -> u32 {
-    170
+/// Built-in sensor collectors. A collector that is switched off is not
+/// read, and the complications showing its data are hidden.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CollectorsConfig {
+    /// CPU temperature
+    #[serde(default = "default_true")]
+    pub temperature: bool,
+
+    /// Disk I/O rates
+    #[serde(default = "default_true")]
+    pub disk: bool,
+
+    /// Network rates and addresses
+    #[serde(default = "default_true")]
+    pub network: bool,
+
+    /// Process and thread counts
+    #[serde(default = "default_true")]
+    pub processes: bool,
+
+    /// SSH sessions
+    #[serde(default = "default_true")]
+    pub sessions: bool,
+}
+
+impl CollectorsConfig {
+    /// Returns whether the collector `name` is switched on.
+    pub fn is_enabled(&self, name: &str) -> bool {
+        match name {
+            collector_names::TEMPERATURE => self.temperature,
+            collector_names::DISK => self.disk,
+            collector_names::NETWORK => self.network,
+            collector_names::PROCESSES => self.processes,
+            collector_names::SESSIONS => self.sessions,
+            _ => true,
+        }
+    }
+}
+
+impl Default for CollectorsConfig {
+    fn default() -> Self {
+        Self {
+            temperature: true,
+            disk: true,
+            network: true,
+            processes: true,
+            sessions: true,
+        }
+    }
+}
+
+/// CPU temperature input selection.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TemperatureConfig {
+    /// Path to an hwmon input, or a label or chip name from
+    /// `ht32panelctl sensor temperature --list` (empty to auto-detect)
+    #[serde(default)]
+    pub sensor: String,
+}
+
+/// System journal watching configuration.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct JournalConfig {
+    /// Whether to follow the journal for warnings and errors
+    #[serde(default)]
+    pub enable: bool,
+
+    /// Systemd units to follow (empty follows the whole journal)
+    #[serde(default)]
+    pub units: Vec<String>,
+}
+
+/// Media server type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum MediaServerKind {
+    /// Jellyfin (also works with Emby)
+    #[default]
+    Jellyfin,
+    /// Plex Media Server
+    Plex,
+}
+
+/// Jellyfin / Plex configuration.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MediaServerConfig {
+    /// Whether to poll the server
+    #[serde(default)]
+    pub enable: bool,
+
+    /// Server type
+    #[serde(default)]
+    pub kind: MediaServerKind,
+
+    /// Base URL of the server
+    #[serde(default = "default_media_server_url")]
+    pub url: String,
+
+    /// Jellyfin API key or Plex token
+    #[serde(default)]
+    pub token: String,
+
+    /// Poll interval in seconds
+    #[serde(default = "default_media_server_interval")]
+    pub interval: u64,
+}
+
+impl Default for MediaServerConfig {
+    fn default() -> Self {
+        Self {
+            enable: false,
+            kind: MediaServerKind::default(),
+            url: default_media_server_url(),
+            token: String::new(),
+            interval: default_media_server_interval(),
+        }
+    }
+}
+
+/// Torrent client type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum TorrentKind {
+    /// qBittorrent Web UI API
+    #[default]
+    Qbittorrent,
+    /// Transmission RPC
+    Transmission,
+}
+
+/// qBittorrent / Transmission configuration.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TorrentConfig {
+    /// Whether to poll the client
+    #[serde(default)]
+    pub enable: bool,
+
+    /// Client type
+    #[serde(default)]
+    pub kind: TorrentKind,
+
+    /// Base URL of the web interface
+    #[serde(default = "default_torrent_url")]
+    pub url: String,
+
+    /// Web UI / RPC username
+    #[serde(default)]
+    pub username: String,
+
+    /// Web UI / RPC password
+    #[serde(default)]
+    pub password: String,
+
+    /// Poll interval in seconds
+    #[serde(default = "default_torrent_interval")]
+    pub interval: u64,
+}
+
+impl Default for TorrentConfig {
+    fn default() -> Self {
+        Self {
+            enable: false,
+            kind: TorrentKind::default(),
+            url: default_torrent_url(),
+            username: String::new(),
+            password: String::new(),
+            interval: default_torrent_interval(),
+        }
+    }
+}
+
+/// 3D printer server type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum PrinterKind {
+    /// OctoPrint
+    #[default]
+    Octoprint,
+    /// Moonraker (Klipper)
+    Moonraker,
+}
+
+/// OctoPrint / Moonraker printer configuration.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PrinterConfig {
+    /// Whether to poll the printer
+    #[serde(default)]
+    pub enable: bool,
+
+    /// Server type
+    #[serde(default)]
+    pub kind: PrinterKind,
+
+    /// Base URL of the server
+    #[serde(default = "default_printer_url")]
+    pub url: String,
+
+    /// API key (required by OctoPrint, optional for Moonraker)
+    #[serde(default)]
+    pub api_key: String,
+
+    /// Poll interval in seconds
+    #[serde(default = "default_printer_interval")]
+    pub interval: u64,
+}
+
+impl Default for PrinterConfig {
+    fn default() -> Self {
+        Self {
+            enable: false,
+            kind: PrinterKind::default(),
+            url: default_printer_url(),
+            api_key: String::new(),
+            interval: default_printer_interval(),
+        }
+    }
+}
+
+/// Storage pool health sensor configuration.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct StorageConfig {
+    /// Whether to monitor ZFS pools and md arrays
+    #[serde(default = "default_true")]
+    pub enable: bool,
+
+    /// Poll interval in seconds
+    #[serde(default = "default_storage_interval")]
+    pub interval: u64,
+
+    /// Shell command run when a pool becomes degraded or recovers. The pool
+    /// is passed in `HT32_POOL`, `HT32_POOL_KIND`, `HT32_POOL_STATE` and
+    /// `HT32_POOL_DEGRADED` (1 or 0).
+    #[serde(default)]
+    pub alert_command: String,
+}
+
+impl Default for StorageConfig {
+    fn default() -> Self {
+        Self {
+            enable: true,
+            interval: default_storage_interval(),
+            alert_command: String::new(),
+        }
+    }
+}
+
+/// Libvirt VM sensor configuration.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct VmsConfig {
+    /// Whether to poll libvirt
+    #[serde(default)]
+    pub enable: bool,
+
+    /// Libvirt connection URI
+    #[serde(default = "default_vms_uri")]
+    pub uri: String,
+
+    /// Poll interval in seconds
+    #[serde(default = "default_vms_interval")]
+    pub interval: u64,
+}
+
+impl Default for VmsConfig {
+    fn default() -> Self {
+        Self {
+            enable: false,
+            uri: default_vms_uri(),
+            interval: default_vms_interval(),
+        }
+    }
+}
+
+/// GPU sensor configuration.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+pub struct GpuConfig {
+    /// Whether to monitor the first detected GPU. Off by default because
+    /// `nvidia-smi` is run every poll.
+    #[serde(default)]
+    pub enable: bool,
+}
+
+/// DNS filtering service type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum DnsFilterKind {
+    /// Pi-hole (v6 API, falling back to v5)
+    #[default]
+    Pihole,
+    /// AdGuard Home
+    Adguard,
+}
+
+/// Pi-hole / AdGuard Home statistics configuration.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DnsFilterConfig {
+    /// Whether to poll the service
+    #[serde(default)]
+    pub enable: bool,
+
+    /// Service type
+    #[serde(default)]
+    pub kind: DnsFilterKind,
+
+    /// Base URL of the web interface
+    #[serde(default = "default_dns_filter_url")]
+    pub url: String,
+
+    /// AdGuard Home username
+    #[serde(default)]
+    pub username: String,
+
+    /// Pi-hole v6 password, Pi-hole v5 API token or AdGuard Home password
+    #[serde(default)]
+    pub password: String,
+
+    /// Poll interval in seconds
+    #[serde(default = "default_dns_filter_interval")]
+    pub interval: u64,
+}
+
+impl Default for DnsFilterConfig {
+    fn default() -> Self {
+        Self {
+            enable: false,
+            kind: DnsFilterKind::default(),
+            url: default_dns_filter_url(),
+            username: String::new(),
+            password: String::new(),
+            interval: default_dns_filter_interval(),
+        }
+    }
+}
+
+/// An SNMP agent and the OIDs polled from it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SnmpTargetConfig {
+    /// Agent address ("host", "host:port" or "[ipv6]:port", default port 161)
+    pub host: String,
+
+    /// SNMPv2c community string
+    #[serde(default = "default_snmp_community")]
+    pub community: String,
+
+    /// Poll interval in seconds
+    #[serde(default = "default_snmp_interval")]
+    pub interval: u64,
+
+    /// Metrics read from this agent
+    #[serde(default)]
+    pub metrics: Vec<SnmpMetricConfig>,
+}
+
+/// A custom metric read from an SNMP OID.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SnmpMetricConfig {
+    /// Metric name used by faces and alerts
+    pub name: String,
+
+    /// Dotted OID, e.g. "1.3.6.1.2.1.31.1.1.1.6.2"
+    pub oid: String,
+
+    /// Treat the value as a counter and report its per-second rate
+    #[serde(default)]
+    pub rate: bool,
+
+    /// Factor applied to the value (e.g. 0.000008 for bytes to Mbit)
+    #[serde(default = "default_snmp_scale")]
+    pub scale: f64,
+
+    /// Unit shown after the value
+    #[serde(default)]
+    pub unit: String,
+
+    /// Show an alert banner when the value rises above this
+    #[serde(default)]
+    pub alert_above: Option<f64>,
+
+    /// Show an alert banner when the value falls below this
+    #[serde(default)]
+    pub alert_below: Option<f64>,
+}
+
+/// An HTTP endpoint returning JSON and the fields extracted from it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct JsonSourceConfig {
+    /// URL to fetch (http:// only)
+    pub url: String,
+
+    /// Poll interval in seconds
+    #[serde(default = "default_json_interval")]
+    pub interval: u64,
+
+    /// Extra request headers (e.g. an API key)
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+
+    /// Fields extracted into named metrics
+    #[serde(default)]
+    pub fields: Vec<JsonFieldConfig>,
+}
+
+/// A custom metric extracted from a JSON response.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct JsonFieldConfig {
+    /// Metric name used by faces and alerts
+    pub name: String,
+
+    /// Path to the value, e.g. "$.current.temperature" or "data.items[0].count".
+    /// Strings are kept as text; numbers and booleans become values.
+    pub path: String,
+
+    /// Factor applied to numeric values
+    #[serde(default = "default_snmp_scale")]
+    pub scale: f64,
+
+    /// Unit shown after numeric values
+    #[serde(default)]
+    pub unit: String,
+
+    /// Show an alert banner when the value rises above this
+    #[serde(default)]
+    pub alert_above: Option<f64>,
+
+    /// Show an alert banner when the value falls below this
+    #[serde(default)]
+    pub alert_below: Option<f64>,
+}
+
+/// A command run periodically whose output lines `key=value` become
+/// custom metrics.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ExecSourceConfig {
+    /// Shell command (run with `sh -c`)
+    pub command: String,
+
+    /// Run interval in seconds
+    #[serde(default = "default_exec_interval")]
+    pub interval: u64,
+
+    /// Seconds before the command is killed
+    #[serde(default = "default_exec_timeout")]
+    pub timeout: u64,
+
+    /// Prefix added to every metric name (e.g. "ups_")
+    #[serde(default)]
+    pub prefix: String,
+
+    /// Run with an empty environment apart from PATH
+    #[serde(default)]
+    pub clear_env: bool,
+
+    /// Working directory
+    #[serde(default = "default_exec_working_dir")]
+    pub working_dir: String,
+
+    /// Niceness added to the command's scheduling priority (0-19)
+    #[serde(default)]
+    pub nice: u8,
+
+    /// Wrapper the command is run under, e.g. ["setpriv", "--reuid=nobody",
+    /// "--regid=nogroup", "--clear-groups"] or ["systemd-run", "--user",
+    /// "--pipe", "--quiet", "-p", "ProtectHome=read-only"]
+    #[serde(default)]
+    pub wrapper: Vec<String>,
+}
+
+/// LAN host monitoring configuration.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct HostsConfig {
+    /// Ping interval in seconds
+    #[serde(default = "default_hosts_interval")]
+    pub interval: u64,
+
+    /// Hosts to monitor
+    #[serde(default)]
+    pub hosts: Vec<HostConfig>,
+}
+
+impl Default for HostsConfig {
+    fn default() -> Self {
+        Self {
+            interval: default_hosts_interval(),
+            hosts: Vec::new(),
+        }
+    }
+}
+
+/// A monitored LAN host.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct HostConfig {
+    /// Display name (defaults to the address)
+    #[serde(default)]
+    pub name: String,
+
+    /// Hostname or IP address to ping
+    pub address: String,
+
+    /// MAC address for Wake-on-LAN
+    #[serde(default)]
+    pub mac: Option<String>,
+}
+
+impl HostConfig {
+    /// Returns the name shown on the panel.
+    pub fn display_name(&self) -> &str {
+        if self.name.is_empty() {
+            &self.address
+        } else {
+            &self.name
+        }
+    }
+}
+
+/// Audio capture configuration.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AudioConfig {
+    /// Whether to capture audio for the spectrum face
+    #[serde(default)]
+    pub enable: bool,
+
+    /// PulseAudio/PipeWire source name (empty for the default output monitor)
+    #[serde(default)]
+    pub source: String,
+
+    /// Flash the LEDs on detected beats
+    #[serde(default)]
+    pub led_beat: bool,
+}
+
+/// Pending package updates sensor configuration.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct UpdatesConfig {
+    /// Whether to check for pending updates
+    #[serde(default)]
+    pub enable: bool,
+
+    /// Check interval in seconds
+    #[serde(default = "default_updates_interval")]
+    pub interval: u64,
+}
+
+impl Default for UpdatesConfig {
+    fn default() -> Self {
+        Self {
+            enable: false,
+            interval: default_updates_interval(),
+        }
+    }
+}
+
+/// On-demand speedtest configuration.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SpeedtestConfig {
+    /// Command printing JSON results (`speedtest-cli --json` or
+    /// `speedtest -f json`); empty for the built-in HTTP download test
+    #[serde(default)]
+    pub command: String,
+
+    /// Plain HTTP URL downloaded by the built-in test, best a large file
+    /// on a server the operator runs; empty disables the built-in test
+    #[serde(default)]
+    pub url: String,
+
+    /// Maximum duration of the built-in test in seconds
+    #[serde(default = "default_speedtest_duration")]
+    pub duration: u64,
+}
+
+impl Default for SpeedtestConfig {
+    fn default() -> Self {
+        Self {
+            command: String::new(),
+            url: String::new(),
+            duration: default_speedtest_duration(),
+        }
+    }
+}
+
+/// Stream Deck and macro button HTTP endpoint configuration.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct StreamDeckConfig {
+    /// Whether to serve the endpoint
+    #[serde(default)]
+    pub enable: bool,
+
+    /// Listen address; keep it on localhost since requests are not
+    /// authenticated
+    #[serde(default = "default_streamdeck_listen")]
+    pub listen: String,
+}
+
+impl Default for StreamDeckConfig {
+    fn default() -> Self {
+        Self {
+            enable: false,
+            listen: default_streamdeck_listen(),
+        }
+    }
+}
+
+/// Keyboard hotkey configuration.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct HotkeysConfig {
+    /// Whether to listen for hotkeys
+    #[serde(default)]
+    pub enable: bool,
+
+    /// Input devices to read (e.g. "/dev/input/by-id/usb-...-kbd");
+    /// empty reads every keyboard
+    #[serde(default)]
+    pub devices: Vec<String>,
+
+    /// Key combinations (e.g. "ctrl+alt+f") mapped to action IDs
+    #[serde(default)]
+    pub bindings: BTreeMap<String, String>,
+}
+
+/// Panel button configuration.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ButtonsConfig {
+    /// Whether to read button reports from the panel
+    #[serde(default)]
+    pub enable: bool,
+
+    /// Report codes (e.g. "01 05", as logged when a button is pressed)
+    /// mapped to action IDs
+    #[serde(default)]
+    pub bindings: BTreeMap<String, String>,
+}
+
+/// Night light configuration.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct NightLightConfig {
+    /// Whether to warm the display at night
+    #[serde(default)]
+    pub enable: bool,
+
+    /// Local time the night starts ("HH:MM")
+    #[serde(default = "default_night_light_start")]
+    pub start: String,
+
+    /// Local time the night ends ("HH:MM")
+    #[serde(default = "default_night_light_end")]
+    pub end: String,
+
+    /// Warmth at full night (0-1)
+    #[serde(default = "default_night_light_strength")]
+    pub strength: f32,
+
+    /// Minutes to fade in after the start and out before the end
+    #[serde(default = "default_night_light_transition")]
+    pub transition: u32,
+
+    /// Latitude in degrees; with `longitude`, the night runs from sunset to
+    /// sunrise instead of `start` to `end`
+    #[serde(default)]
+    pub latitude: Option<f64>,
+
+    /// Longitude in degrees, east positive
+    #[serde(default)]
+    pub longitude: Option<f64>,
+}
+
+impl Default for NightLightConfig {
+    fn default() -> Self {
+        Self {
+            enable: false,
+            start: default_night_light_start(),
+            end: default_night_light_end(),
+            strength: default_night_light_strength(),
+            transition: default_night_light_transition(),
+            latitude: None,
+            longitude: None,
+        }
+    }
+}
+
+/// Do-not-disturb configuration.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DndConfig {
+    /// Daily local time windows ("HH:MM-HH:MM") in which alert banners and
+    /// LED pulses are held back
+    #[serde(default)]
+    pub schedule: Vec<String>,
+}
+
+/// Alert configuration.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AlertsConfig {
+    /// Flash the LEDs when an alert is raised
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pulse: Option<AlertPulseConfig>,
+}
+
+/// LED flash for newly raised alerts.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AlertPulseConfig {
+    /// LED effect to flash: rainbow, breathing, colors or auto
+    pub theme: String,
+
+    /// Number of flashes (1-20)
+    #[serde(default = "default_alert_pulse_count")]
+    pub count: u32,
+}
+
+/// A rule switching to a face while a value is outside its limits.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RuleConfig {
+    /// Value to watch: "cpu", "memory", "cpu_temp", "gpu", "gpu_temp" or
+    /// the name of a custom metric
+    pub when: String,
+
+    /// The rule holds while the value is above this
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub above: Option<f64>,
+
+    /// The rule holds while the value is below this
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub below: Option<f64>,
+
+    /// Seconds the rule must hold before the face switches
+    #[serde(default, rename = "for")]
+    pub hold: u64,
+
+    /// Face to switch to; the previous face returns when the rule stops
+    /// holding
+    pub face: String,
+}
+
+/// A background image drawn behind one face.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SkinConfig {
+    /// Image file (PNG, JPEG, ...)
+    pub image: String,
+
+    /// Nine-patch insets in image pixels as [top, right, bottom, left];
+    /// without them the image is scaled to cover the canvas
+    #[serde(default)]
+    pub slice: Option<[u32; 4]>,
+
+    /// Canvas pixels kept clear of face content as [top, right, bottom, left]
+    #[serde(default)]
+    pub margin: [u32; 4],
+}
+
+/// A URL that receives a JSON POST for daemon events.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct WebhookConfig {
+    /// URL to POST to (http:// or https://)
+    pub url: String,
+
+    /// Events sent to this URL ("alert", "device_disconnected",
+    /// "device_reconnected", "face_changed"); empty sends all of them
+    #[serde(default)]
+    pub events: Vec<String>,
+
+    /// Extra request headers (e.g. authorization tokens)
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+
+    /// Request body with `{{event}}`, `{{message}}`, `{{hostname}}` and
+    /// `{{timestamp}}` placeholders; None sends a JSON object of all four
+    #[serde(default)]
+    pub template: Option<String>,
+
+    /// Content type of the request body
+    #[serde(default = "default_webhook_content_type")]
+    pub content_type: String,
+
+    /// Extra attempts after a failed delivery
+    #[serde(default = "default_webhook_retries")]
+    pub retries: u32,
+}
+
+// Default value functions
+fn default_true() -> bool {
+    true
+}
+
+fn default_listen() -> String {
+    "[::1]:8686".to_string()
+}
+
+pub fn default_state_dir() -> String {
+    // Check STATE_DIRECTORY first (set by systemd when StateDirectory= is configured)
+    // Then fall back to XDG state directory or /var/lib
+    if let Ok(state_dir) = std::env::var("STATE_DIRECTORY") {
+        state_dir
+    } else if let Ok(state_home) = std::env::var("XDG_STATE_HOME") {
+        format!("{}/ht32-panel", state_home)
+    } else if let Ok(home) = std::env::var("HOME") {
+        format!("{}/.local/state/ht32-panel", home)
+    } else {
+        "/var/lib/ht32-panel".to_string()
+    }
+}
+
+fn default_refresh_interval() -> u64 {
+    2500
+}
+
+fn default_heartbeat() -> u64 {
+    1000
+}
+
+fn default_lcd_device() -> String {
+    "auto".to_string()
+}
+
+fn default_fbdev_device() -> String {
+    "/dev/fb0".to_string()
+}
+
+fn default_led_device() -> String {
+    "/dev/ttyUSB0".to_string()
+}
+
+fn default_chunk_size() -> usize {
+    MAX_CHUNK_SIZE
+}
+
+fn default_width() -> u32 {
+    320
+}
+
+fn default_height() -> u32 {
+    170
+}
+
+fn default_orientation() -> String {
+    "landscape".to_string()
+}
+
+fn default_splash_duration() -> u64 {
+    3000
+}
+
+fn default_shutdown_message() -> String {
+    "Panel offline".to_string()
+}
+
+fn default_updates_interval() -> u64 {
+    3600
+}
+
+fn default_hosts_interval() -> u64 {
+    30
+}
+
+fn default_snmp_community() -> String {
+    "public".to_string()
+}
+
+fn default_snmp_interval() -> u64 {
+    10
+}
+
+fn default_snmp_scale() -> f64 {
+    1.0
+}
+
+fn default_json_interval() -> u64 {
+    60
+}
+
+fn default_exec_interval() -> u64 {
+    60
+}
+
+fn default_exec_timeout() -> u64 {
+    10
+}
+
+fn default_exec_working_dir() -> String {
+    "/".to_string()
+}
+
+fn default_streamdeck_listen() -> String {
+    "127.0.0.1:8687".to_string()
+}
+
+fn default_night_light_start() -> String {
+    "21:00".to_string()
+}
+
+fn default_night_light_end() -> String {
+    "07:00".to_string()
+}
+
+fn default_alert_pulse_count() -> u32 {
+    3
+}
+
+fn default_night_light_strength() -> f32 {
+    0.6
+}
+
+fn default_night_light_transition() -> u32 {
+    30
+}
+
+fn default_webhook_content_type() -> String {
+    "application/json".to_string()
+}
+
+fn default_webhook_retries() -> u32 {
+    3
+}
+
+fn default_dns_filter_url() -> String {
+    "http://localhost".to_string()
+}
+
+fn default_dns_filter_interval() -> u64 {
+    30
+}
+
+fn default_vms_uri() -> String {
+    "qemu:///system".to_string()
+}
+
+fn default_vms_interval() -> u64 {
+    5
+}
+
+fn default_storage_interval() -> u64 {
+    60
+}
+
+fn default_printer_url() -> String {
+    "http://localhost".to_string()
+}
+
+fn default_printer_interval() -> u64 {
+    5
+}
+
+fn default_torrent_url() -> String {
+    "http://localhost:8080".to_string()
+}
+
+fn default_torrent_interval() -> u64 {
+    5
+}
+
+fn default_media_server_url() -> String {
+    "http://localhost:8096".to_string()
+}
+
+fn default_media_server_interval() -> u64 {
+    15
+}
+
+fn default_speedtest_duration() -> u64 {
+    10
 }
 
 impl Config {
-    /// Loads configuration from a TOML file.
+    /// Loads configuration from a TOML file, rejecting unknown keys and
+    /// invalid values.
     pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
         let content =
             std::fs::read_to_string(path.as_ref()).context("Failed to read configuration file")?;
-        let config: Config = toml::from_str(&content).context("Failed to parse configuration")?;
-        Ok(config)
+        config_check::check(&content).map_err(|problems| {
+            let problems: Vec<String> = problems.iter().map(|p| p.to_string()).collect();
+            anyhow!("Invalid configuration:\n  {}", problems.join("\n  "))
+        })
     }
 
     /// Saves configuration to a TOML file.
     pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
         let content = toml::to_string_pretty(self).context("Failed to serialize configuration")?;
-        std::fs::write(path.as_ref(), content).context("Failed to write configuration file")?;
+        write_atomic(path.as_ref(), content).context("Failed to write configuration file")?;
         Ok(())
     }
 }
@@ -198,8 +1488,22 @@ impl Default for Config {
             state_dir: default_state_dir(),
             refresh_interval: default_refresh_interval(),
             heartbeat: default_heartbeat(),
+            kiosk: false,
             devices: DevicesConfig::default(),
             canvas: CanvasConfig::default(),
+            splash: SplashConfig::default(),
+            sensors: SensorsConfig::default(),
+            led: LedConfig::default(),
+            speedtest: SpeedtestConfig::default(),
+            webhooks: Vec::new(),
+            streamdeck: StreamDeckConfig::default(),
+            hotkeys: HotkeysConfig::default(),
+            buttons: ButtonsConfig::default(),
+            night_light: NightLightConfig::default(),
+            dnd: DndConfig::default(),
+            alerts: AlertsConfig::default(),
+            rules: Vec::new(),
+            skins: HashMap::new(),
         }
     }
 }
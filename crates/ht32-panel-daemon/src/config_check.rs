@@ -0,0 +1,425 @@
+//! Configuration file validation.
+//!
+//! A configuration file is checked against the schema (unknown keys, wrong
+//! types and unknown enum values) and then for values the daemon cannot use,
+//! such as out-of-range intervals, malformed times or addresses. Problems are
+//! reported with the line and column of the offending key or value.
+
+use std::fmt;
+use std::net::SocketAddr;
+use std::ops::Range;
+
+use ht32_panel_hw::lcd::{CHUNK_SIZE_STEP, MAX_CHUNK_DELAY, MAX_CHUNK_SIZE};
+use ht32_panel_hw::Orientation;
+use ht32_panel_types::LedTheme;
+use toml_edit::{ImDocument, Item};
+
+use crate::config::{Config, OutputKind};
+use crate::dnd;
+use crate::faces;
+use crate::night_light;
+use crate::sensors::parse_mac;
+#[cfg(feature = "webhooks")]
+use crate::webhooks;
+
+/// Webhook events that can be subscribed to.
+#[cfg(feature = "webhooks")]
+const WEBHOOK_EVENTS: &[&str] = &[
+    webhooks::ALERT,
+    webhooks::DEVICE_DISCONNECTED,
+    webhooks::DEVICE_RECONNECTED,
+    webhooks::FACE_CHANGED,
+];
+
+/// A problem found in a configuration file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Problem {
+    /// 1-based line and column, if the problem could be located
+    pub location: Option<(usize, usize)>,
+
+    /// What is wrong
+    pub message: String,
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.location {
+            Some((line, column)) => write!(f, "line {}, column {}: {}", line, column, self.message),
+            None => write!(f, "{}", self.message),
+        }
+    }
+}
+
+/// Parses and checks `content`, returning the configuration or every
+/// problem found.
+pub fn check(content: &str) -> Result<Config, Vec<Problem>> {
+    let config: Config = toml::from_str(content).map_err(|e| {
+        vec![Problem {
+            location: e.span().map(|span| line_column(content, span.start)),
+            message: e.message().trim().to_string(),
+        }]
+    })?;
+
+    let mut checker = Checker {
+        content,
+        document: ImDocument::parse(content).ok(),
+        problems: Vec::new(),
+    };
+    checker.check(&config);
+    if checker.problems.is_empty() {
+        Ok(config)
+    } else {
+        Err(checker.problems)
+    }
+}
+
+/// Converts a byte offset into a 1-based line and column.
+fn line_column(content: &str, offset: usize) -> (usize, usize) {
+    let before = &content[..offset.min(content.len())];
+    let line = before.matches('\n').count() + 1;
+    let column = before
+        .rfind('\n')
+        .map_or(before, |newline| &before[newline + 1..])
+        .chars()
+        .count()
+        + 1;
+    (line, column)
+}
+
+/// Collects problems with the values of a parsed configuration.
+struct Checker<'a> {
+    content: &'a str,
+    document: Option<ImDocument<&'a str>>,
+    problems: Vec<Problem>,
+}
+
+impl Checker<'_> {
+    fn check(&mut self, config: &Config) {
+        self.range("refresh_interval", config.refresh_interval, 500, 10000);
+        self.positive("heartbeat", config.heartbeat);
+        if config.web.enable {
+            self.socket_addr("web.listen", &config.web.listen);
+        }
+        if config.devices.output == OutputKind::Network && config.devices.remote.is_empty() {
+            self.problem(
+                "devices.output",
+                "set devices.remote to the receiver's address, like panel-host:8688",
+            );
+        }
+        let transfer = &config.devices.transfer;
+        if transfer.chunk_size == 0
+            || transfer.chunk_size > MAX_CHUNK_SIZE
+            || !transfer.chunk_size.is_multiple_of(CHUNK_SIZE_STEP)
+        {
+            self.problem(
+                "devices.transfer.chunk_size",
+                format!(
+                    "must be a multiple of {} up to {}, got {}",
+                    CHUNK_SIZE_STEP, MAX_CHUNK_SIZE, transfer.chunk_size
+                ),
+            );
+        }
+        self.range(
+            "devices.transfer.chunk_delay",
+            transfer.chunk_delay,
+            0,
+            MAX_CHUNK_DELAY.as_millis() as u64,
+        );
+        self.positive("canvas.width", config.canvas.width as u64);
+        self.positive("canvas.height", config.canvas.height as u64);
+        if config.canvas.orientation != "auto"
+            && config.canvas.orientation.parse::<Orientation>().is_err()
+        {
+            self.problem(
+                "canvas.orientation",
+                format!(
+                    "unknown orientation \"{}\"; use landscape, portrait, \
+                     landscape-upside-down, portrait-upside-down or auto",
+                    config.canvas.orientation
+                ),
+            );
+        }
+
+        for (theme, mapping) in &config.led.theme_map {
+            let path = format!("led.theme_map.{}", theme);
+            self.range(&format!("{}.theme", path), mapping.theme as u64, 1, 5);
+            self.range(
+                &format!("{}.intensity", path),
+                mapping.intensity as u64,
+                1,
+                5,
+            );
+            self.range(&format!("{}.speed", path), mapping.speed as u64, 1, 5);
+        }
+
+        let sensors = &config.sensors;
+        self.positive("sensors.updates.interval", sensors.updates.interval);
+        self.positive("sensors.hosts.interval", sensors.hosts.interval);
+        self.positive("sensors.dns_filter.interval", sensors.dns_filter.interval);
+        self.positive("sensors.vms.interval", sensors.vms.interval);
+        self.positive("sensors.storage.interval", sensors.storage.interval);
+        self.positive("sensors.printer.interval", sensors.printer.interval);
+        self.positive("sensors.torrent.interval", sensors.torrent.interval);
+        self.positive(
+            "sensors.media_server.interval",
+            sensors.media_server.interval,
+        );
+        for (i, host) in sensors.hosts.hosts.iter().enumerate() {
+            if let Some(mac) = &host.mac {
+                if parse_mac(mac).is_err() {
+                    self.problem(
+                        &format!("sensors.hosts.hosts.{}.mac", i),
+                        format!("\"{}\" is not a MAC address like aa:bb:cc:dd:ee:ff", mac),
+                    );
+                }
+            }
+        }
+        for (i, target) in sensors.snmp.iter().enumerate() {
+            self.positive(&format!("sensors.snmp.{}.interval", i), target.interval);
+        }
+        for (i, source) in sensors.json.iter().enumerate() {
+            self.positive(&format!("sensors.json.{}.interval", i), source.interval);
+            self.url(
+                &format!("sensors.json.{}.url", i),
+                &source.url,
+                &["http://"],
+            );
+        }
+        for (i, source) in sensors.exec.iter().enumerate() {
+            let path = format!("sensors.exec.{}", i);
+            self.positive(&format!("{}.interval", path), source.interval);
+            self.positive(&format!("{}.timeout", path), source.timeout);
+            self.range(&format!("{}.nice", path), source.nice as u64, 0, 19);
+        }
+
+        if !config.speedtest.url.is_empty() {
+            self.url("speedtest.url", &config.speedtest.url, &["http://"]);
+        }
+        self.positive("speedtest.duration", config.speedtest.duration);
+
+        for (i, hook) in config.webhooks.iter().enumerate() {
+            let path = format!("webhooks.{}", i);
+            self.url(
+                &format!("{}.url", path),
+                &hook.url,
+                &["http://", "https://"],
+            );
+            #[cfg(feature = "webhooks")]
+            for (j, event) in hook.events.iter().enumerate() {
+                if !WEBHOOK_EVENTS.contains(&event.as_str()) {
+                    self.problem(
+                        &format!("{}.events.{}", path, j),
+                        format!(
+                            "unknown event \"{}\", expected one of {}",
+                            event,
+                            WEBHOOK_EVENTS.join(", ")
+                        ),
+                    );
+                }
+            }
+        }
+
+        if config.streamdeck.enable {
+            self.socket_addr("streamdeck.listen", &config.streamdeck.listen);
+        }
+
+        let mut skins: Vec<_> = config.skins.iter().collect();
+        skins.sort_by_key(|(face, _)| face.as_str());
+        for (face, skin) in skins {
+            let path = format!("skins.{}", face);
+            if !faces::available_faces()
+                .iter()
+                .any(|f| f.id == face.as_str())
+            {
+                self.problem(&path, format!("unknown face \"{}\"", face));
+            }
+            if skin.image.is_empty() {
+                self.problem(&format!("{}.image", path), "must not be empty");
+            }
+        }
+
+        let night = &config.night_light;
+        for (key, time) in [("start", &night.start), ("end", &night.end)] {
+            if night_light::parse_time(time).is_none() {
+                self.problem(
+                    &format!("night_light.{}", key),
+                    format!("\"{}\" is not a time like 21:00", time),
+                );
+            }
+        }
+        if !(0.0..=1.0).contains(&night.strength) {
+            self.problem(
+                "night_light.strength",
+                format!("must be between 0 and 1, got {}", night.strength),
+            );
+        }
+        if let Some(latitude) = night.latitude {
+            if !(-90.0..=90.0).contains(&latitude) {
+                self.problem(
+                    "night_light.latitude",
+                    format!("must be between -90 and 90, got {}", latitude),
+                );
+            }
+        }
+        if let Some(longitude) = night.longitude {
+            if !(-180.0..=180.0).contains(&longitude) {
+                self.problem(
+                    "night_light.longitude",
+                    format!("must be between -180 and 180, got {}", longitude),
+                );
+            }
+        }
+        match (night.latitude, night.longitude) {
+            (Some(_), None) => self.problem("night_light.latitude", "set longitude as well"),
+            (None, Some(_)) => self.problem("night_light.longitude", "set latitude as well"),
+            _ => {}
+        }
+
+        for (i, rule) in config.rules.iter().enumerate() {
+            if !faces::available_faces()
+                .iter()
+                .any(|f| f.id == rule.face.as_str())
+            {
+                self.problem(
+                    &format!("rules.{}.face", i),
+                    format!("unknown face \"{}\"", rule.face),
+                );
+            }
+            if rule.above.is_none() && rule.below.is_none() {
+                self.problem(&format!("rules.{}", i), "set above or below");
+            }
+        }
+        for (i, window) in config.dnd.schedule.iter().enumerate() {
+            if dnd::parse_window(window).is_none() {
+                self.problem(
+                    &format!("dnd.schedule.{}", i),
+                    format!("\"{}\" is not a window like 22:00-07:00", window),
+                );
+            }
+        }
+        if let Some(ref pulse) = config.alerts.pulse {
+            match pulse.theme.parse::<LedTheme>() {
+                Ok(LedTheme::Off) => {
+                    self.problem("alerts.pulse.theme", "cannot pulse with LEDs off")
+                }
+                Ok(_) => {}
+                Err(_) => self.problem(
+                    "alerts.pulse.theme",
+                    format!(
+                        "unknown LED effect \"{}\" (rainbow, breathing, colors, auto)",
+                        pulse.theme
+                    ),
+                ),
+            }
+            self.range("alerts.pulse.count", pulse.count.into(), 1, 20);
+        }
+    }
+
+    fn range(&mut self, path: &str, value: u64, min: u64, max: u64) {
+        if !(min..=max).contains(&value) {
+            self.problem(
+                path,
+                format!("must be between {} and {}, got {}", min, max, value),
+            );
+        }
+    }
+
+    fn positive(&mut self, path: &str, value: u64) {
+        if value == 0 {
+            self.problem(path, "must be greater than 0");
+        }
+    }
+
+    fn socket_addr(&mut self, path: &str, value: &str) {
+        if value.parse::<SocketAddr>().is_err() {
+            self.problem(
+                path,
+                format!(
+                    "\"{}\" is not an address like 0.0.0.0:8686 or [::1]:8686",
+                    value
+                ),
+            );
+        }
+    }
+
+    fn url(&mut self, path: &str, value: &str, schemes: &[&str]) {
+        if !schemes.iter().any(|scheme| value.starts_with(scheme)) {
+            self.problem(
+                path,
+                format!("\"{}\" must start with {}", value, schemes.join(" or ")),
+            );
+        }
+    }
+
+    /// Records a problem with the value at `path`, a dotted key path where
+    /// numbers index arrays (e.g. "sensors.exec.0.nice").
+    fn problem(&mut self, path: &str, message: impl Into<String>) {
+        let location = self
+            .span(path)
+            .map(|span| line_column(self.content, span.start));
+        self.problems.push(Problem {
+            location,
+            message: format!("{}: {}", path, message.into()),
+        });
+    }
+
+    /// Finds where the value at `path` is written, if it is in the file.
+    fn span(&self, path: &str) -> Option<Range<usize>> {
+        let mut item: &Item = self.document.as_ref()?.as_item();
+        for segment in path.split('.') {
+            item = match segment.parse::<usize>() {
+                Ok(index) if item.is_array() || item.is_array_of_tables() => item.get(index)?,
+                _ => item.get(segment)?,
+            };
+        }
+        item.span()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_config_is_valid() {
+        let content = include_str!("../../../config/default.toml");
+        assert_eq!(check(content).err(), None);
+    }
+
+    #[test]
+    fn test_unknown_key() {
+        let problems = check("kiosk = false\nrefresh_intervall = 1000\n").unwrap_err();
+        assert_eq!(problems.len(), 1);
+        assert_eq!(problems[0].location, Some((2, 1)));
+        assert!(problems[0]
+            .message
+            .contains("unknown field `refresh_intervall`"));
+    }
+
+    #[test]
+    fn test_out_of_range_values() {
+        let content = "refresh_interval = 50\n\
+                       \n\
+                       [[sensors.exec]]\n\
+                       command = \"true\"\n\
+                       nice = 40\n\
+                       \n\
+                       [night_light]\n\
+                       start = \"25:00\"\n\
+                       \n\
+                       [devices.transfer]\n\
+                       chunk_size = 1000\n";
+        let problems = check(content).unwrap_err();
+        let found: Vec<String> = problems.iter().map(|p| p.to_string()).collect();
+        assert_eq!(
+            found,
+            [
+                "line 1, column 20: refresh_interval: must be between 500 and 10000, got 50",
+                "line 11, column 14: devices.transfer.chunk_size: \
+                 must be a multiple of 512 up to 4096, got 1000",
+                "line 5, column 8: sensors.exec.0.nice: must be between 0 and 19, got 40",
+                "line 8, column 9: night_light.start: \"25:00\" is not a time like 21:00",
+            ]
+        );
+    }
+}
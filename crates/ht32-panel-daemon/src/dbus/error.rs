@@ -0,0 +1,76 @@
+//! Named D-Bus errors of the `org.ht32panel.Daemon1` interface.
+//!
+//! Errors clients are expected to tell apart get a name under
+//! [`DBUS_ERROR_PREFIX`], one per kind of [`ht32_panel_types::Error`];
+//! anything else is returned as the standard `org.freedesktop.DBus.Error`
+//! it wraps.
+
+#[cfg(doc)]
+use ht32_panel_types::DBUS_ERROR_PREFIX;
+use zbus::DBusError;
+
+/// An error returned by a method of the daemon interface.
+#[derive(Debug, DBusError)]
+#[zbus(prefix = "org.ht32panel.Daemon1.Error")]
+pub enum Error {
+    /// A standard error, such as access denied or invalid arguments.
+    #[zbus(error)]
+    ZBus(zbus::Error),
+    /// A color could not be parsed.
+    InvalidColor(String),
+    /// An orientation name could not be parsed.
+    InvalidOrientation(String),
+    /// An LED theme, intensity or speed was outside 1-5.
+    InvalidLedSetting(String),
+    /// An LED theme name could not be parsed.
+    InvalidLedTheme(String),
+    /// An LED intensity or speed could not be parsed.
+    InvalidLedLevel(String),
+    /// An IP display preference could not be parsed.
+    InvalidIpDisplayPreference(String),
+}
+
+impl From<ht32_panel_types::Error> for Error {
+    fn from(e: ht32_panel_types::Error) -> Self {
+        use ht32_panel_types::Error as Types;
+        let message = e.to_string();
+        match e {
+            Types::InvalidColor(_) => Self::InvalidColor(message),
+            Types::InvalidOrientation(_) => Self::InvalidOrientation(message),
+            Types::InvalidLedSetting(_) => Self::InvalidLedSetting(message),
+            Types::InvalidLedTheme(_) => Self::InvalidLedTheme(message),
+            Types::InvalidLedLevel(_) => Self::InvalidLedLevel(message),
+            Types::InvalidIpDisplayPreference(_) => Self::InvalidIpDisplayPreference(message),
+        }
+    }
+}
+
+impl From<zbus::fdo::Error> for Error {
+    fn from(e: zbus::fdo::Error) -> Self {
+        Self::ZBus(e.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_types_error_names() {
+        use ht32_panel_types::Error as Types;
+        for e in [
+            Types::InvalidColor("#12".to_string()),
+            Types::InvalidOrientation("sideways".to_string()),
+            Types::InvalidLedSetting("speed=9".to_string()),
+            Types::InvalidLedTheme("disco".to_string()),
+            Types::InvalidLedLevel("fast".to_string()),
+            Types::InvalidIpDisplayPreference("ipv5".to_string()),
+        ] {
+            let name = e.dbus_name();
+            let message = e.to_string();
+            let error = Error::from(e);
+            assert_eq!(error.name().as_str(), name);
+            assert_eq!(error.description(), Some(message.as_str()));
+        }
+    }
+}
@@ -2,96 +2,217 @@
 //!
 //! Provides the `org.ht32panel.Daemon1` interface.
 
+use std::collections::HashMap;
 use std::sync::Arc;
 
-use ht32_panel_hw::{lcd::parse_hex_color, Orientation};
-use tokio::sync::broadcast;
+use ht32_panel_hw::{access, led::LedTheme, Orientation};
+use ht32_panel_types::{Color, ComplicationInfo, DaemonState, FaceInfo, LedSettings, ThemeInfo};
 use tracing::{debug, info, warn};
-use zbus::{interface, Connection};
+use zbus::{interface, message::Header, object_server::SignalEmitter, Connection};
 
-use crate::config::DbusBusType;
+use super::error::Error;
+use super::polkit::{self, Authorizer, Caller, Privilege};
+use crate::config::{DbusBusType, RuleConfig};
+use crate::config_check;
+use crate::events::{self, Event, EventBus};
+use crate::faces::{overlays, placement};
+use crate::health;
+use crate::logging;
+use crate::rendering::{ScreenFormat, VisibleArea};
+use crate::sensors::data::{IpDisplayPreference, RateUnits};
 use crate::state::AppState;
 
-/// D-Bus signal types for state change notifications.
-#[derive(Clone, Debug)]
-#[allow(clippy::enum_variant_names)]
-pub enum DaemonSignals {
-    /// Orientation was changed.
-    OrientationChanged,
-    /// LED settings changed.
-    LedChanged,
-    /// Display settings (theme, face, etc.) changed.
-    DisplaySettingsChanged,
-    /// Complication option changed.
-    ComplicationOptionChanged,
-}
+/// Frame time percentiles of one stage as returned by `GetRenderStats`:
+/// (stage, p50, p95, p99, max) in milliseconds.
+type StageTimes = (String, f64, f64, f64, f64);
 
 /// D-Bus interface implementation for the HT32 Panel Daemon.
 pub struct Daemon1Interface {
     state: Arc<AppState>,
-    signal_tx: broadcast::Sender<DaemonSignals>,
+    events: EventBus,
     shutdown_tx: tokio::sync::mpsc::Sender<()>,
+    auth: Authorizer,
 }
 
 impl Daemon1Interface {
     /// Creates a new D-Bus interface.
     pub fn new(
         state: Arc<AppState>,
-        signal_tx: broadcast::Sender<DaemonSignals>,
+        events: EventBus,
         shutdown_tx: tokio::sync::mpsc::Sender<()>,
+        auth: Authorizer,
     ) -> Self {
         Self {
             state,
-            signal_tx,
+            events,
             shutdown_tx,
+            auth,
         }
     }
+
+    /// Records a change made by `caller` in the audit log.
+    fn audit(&self, caller: &Caller, method: &str, old: &str, new: &str) {
+        self.state
+            .audit_log()
+            .record("dbus", &caller.to_string(), method, old, new);
+    }
+
+    /// Applies LED settings for `caller`, auditing them under `method`.
+    async fn apply_led(
+        &self,
+        caller: &Caller,
+        method: &str,
+        settings: LedSettings,
+    ) -> zbus::fdo::Result<()> {
+        let old = self.state.led_settings().to_string();
+        self.state
+            .set_led(settings)
+            .await
+            .map_err(|e| zbus::fdo::Error::Failed(e.to_string()))?;
+        self.audit(caller, method, &old, &settings.to_string());
+
+        // Emit signal
+        self.events.publish(Event::LedChanged);
+
+        debug!("D-Bus: {}({})", method, settings);
+        Ok(())
+    }
+
+    /// Starts a speedtest for `caller`, emitting `SpeedtestFinished` when it
+    /// completes.
+    #[cfg(feature = "speedtest")]
+    fn start_speedtest(
+        &self,
+        caller: &Caller,
+        emitter: SignalEmitter<'static>,
+    ) -> zbus::fdo::Result<()> {
+        let done = self
+            .state
+            .run_speedtest()
+            .map_err(|e| zbus::fdo::Error::Failed(e.to_string()))?;
+
+        self.audit(caller, "RunSpeedtest", "", "");
+        tokio::spawn(async move {
+            let (success, download, upload, ping_ms) = match done.await {
+                Ok(Ok(result)) => (
+                    true,
+                    result.download,
+                    result.upload.unwrap_or(0.0),
+                    result.ping_ms.unwrap_or(0.0),
+                ),
+                _ => (false, 0.0, 0.0, 0.0),
+            };
+            if let Err(e) =
+                Self::speedtest_finished(&emitter, success, download, upload, ping_ms).await
+            {
+                warn!("Failed to emit SpeedtestFinished: {}", e);
+            }
+        });
+        Ok(())
+    }
+
+    #[cfg(not(feature = "speedtest"))]
+    fn start_speedtest(
+        &self,
+        _caller: &Caller,
+        _emitter: SignalEmitter<'static>,
+    ) -> zbus::fdo::Result<()> {
+        Err(zbus::fdo::Error::NotSupported(
+            "The daemon was built without the speedtest feature".to_string(),
+        ))
+    }
+
+    /// Returns the slot overlay `id` is shown in, empty if hidden.
+    fn overlay_position(&self, id: &str) -> String {
+        self.state
+            .overlays()
+            .iter()
+            .find(|o| o.id == id)
+            .map(|o| o.slot.name().to_string())
+            .unwrap_or_default()
+    }
 }
 
 #[interface(name = "org.ht32panel.Daemon1")]
 impl Daemon1Interface {
-    /// Sets the display orientation.
-    async fn set_orientation(&self, orientation: &str) -> zbus::fdo::Result<()> {
-        let orientation: Orientation = orientation
-            .parse()
-            .map_err(|_| zbus::fdo::Error::InvalidArgs("Invalid orientation".to_string()))?;
+    /// Sets the display orientation. "auto" detects it from an
+    /// accelerometer or the DMI chassis type, falling back to the
+    /// configured orientation.
+    async fn set_orientation(
+        &self,
+        #[zbus(header)] header: Header<'_>,
+        orientation: &str,
+    ) -> Result<(), Error> {
+        let caller = self.auth.check(&header, Privilege::Configure).await?;
+        let old = self.state.orientation().to_string();
+        if orientation == "auto" {
+            let detected = self
+                .state
+                .set_orientation_auto()
+                .map_err(|e| zbus::fdo::Error::Failed(e.to_string()))?;
+            self.audit(
+                &caller,
+                "SetOrientation",
+                &old,
+                &format!("auto ({})", detected),
+            );
+            self.events.publish(Event::OrientationChanged);
+            debug!("D-Bus: SetOrientation(auto) -> {}", detected);
+            return Ok(());
+        }
+        let orientation: Orientation = orientation.parse()?;
 
         self.state
             .set_orientation(orientation)
             .map_err(|e| zbus::fdo::Error::Failed(e.to_string()))?;
+        self.audit(&caller, "SetOrientation", &old, &orientation.to_string());
 
         // Emit signal
-        let _ = self.signal_tx.send(DaemonSignals::OrientationChanged);
+        self.events.publish(Event::OrientationChanged);
 
         debug!("D-Bus: SetOrientation({})", orientation);
         Ok(())
     }
 
     /// Gets the current orientation.
-    fn get_orientation(&self) -> String {
-        self.state.orientation().to_string()
+    fn get_orientation(&self) -> Orientation {
+        self.state.orientation()
     }
 
-    /// Clears the display to a solid color.
-    fn clear_display(&self, color: &str) -> zbus::fdo::Result<()> {
-        let color_u16 = parse_hex_color(color)
-            .ok_or_else(|| zbus::fdo::Error::InvalidArgs("Invalid color format".to_string()))?;
+    /// Clears the display to a solid color. Fails with
+    /// `org.ht32panel.Daemon1.Error.InvalidColor` if `color` cannot be
+    /// parsed.
+    async fn clear_display(
+        &self,
+        #[zbus(header)] header: Header<'_>,
+        color: &str,
+    ) -> Result<(), Error> {
+        let caller = self.auth.check(&header, Privilege::Admin).await?;
+        let parsed: Color = color.parse()?;
 
         self.state
-            .clear_display(color_u16)
+            .clear_display(parsed.to_rgb565())
             .map_err(|e| zbus::fdo::Error::Failed(e.to_string()))?;
+        self.audit(&caller, "ClearDisplay", "", color);
 
         debug!("D-Bus: ClearDisplay({})", color);
         Ok(())
     }
 
-    /// Sets the display face.
-    fn set_face(&self, face: &str) -> zbus::fdo::Result<()> {
+    /// Sets the display face. "random" picks a random face and theme.
+    async fn set_face(
+        &self,
+        #[zbus(header)] header: Header<'_>,
+        face: &str,
+    ) -> zbus::fdo::Result<()> {
+        let caller = self.auth.check(&header, Privilege::Configure).await?;
+        let old = self.state.face_name();
         self.state
             .set_face(face)
             .map_err(|e| zbus::fdo::Error::InvalidArgs(e.to_string()))?;
+        self.audit(&caller, "SetFace", &old, &self.state.face_name());
 
-        let _ = self.signal_tx.send(DaemonSignals::DisplaySettingsChanged);
+        self.events.publish(Event::DisplaySettingsChanged);
         debug!("D-Bus: SetFace({})", face);
         Ok(())
     }
@@ -101,44 +222,137 @@ impl Daemon1Interface {
         self.state.face_name()
     }
 
+    /// Sets the time of day ("HH:MM") to switch to a random face and theme.
+    /// An empty string or "off" disables it.
+    async fn set_daily_random(
+        &self,
+        #[zbus(header)] header: Header<'_>,
+        time: &str,
+    ) -> zbus::fdo::Result<()> {
+        let caller = self.auth.check(&header, Privilege::Configure).await?;
+        let old = self.state.daily_random().unwrap_or_default();
+        self.state
+            .set_daily_random(time)
+            .map_err(|e| zbus::fdo::Error::InvalidArgs(e.to_string()))?;
+        let new = self.state.daily_random().unwrap_or_default();
+        self.audit(&caller, "SetDailyRandom", &old, &new);
+
+        self.events.publish(Event::DisplaySettingsChanged);
+        debug!("D-Bus: SetDailyRandom({})", time);
+        Ok(())
+    }
+
+    /// Gets the daily random switch time (empty when disabled).
+    fn get_daily_random(&self) -> String {
+        self.state.daily_random().unwrap_or_default()
+    }
+
     /// Gets the current color theme name.
     fn get_theme(&self) -> String {
         self.state.theme_name()
     }
 
     /// Sets the color theme by name.
-    fn set_theme(&self, name: &str) -> zbus::fdo::Result<()> {
+    async fn set_theme(
+        &self,
+        #[zbus(header)] header: Header<'_>,
+        name: &str,
+    ) -> zbus::fdo::Result<()> {
+        let caller = self.auth.check(&header, Privilege::Configure).await?;
+        let old = self.state.theme_name();
         self.state
             .set_theme(name)
             .map_err(|e| zbus::fdo::Error::InvalidArgs(e.to_string()))?;
+        self.audit(&caller, "SetTheme", &old, &self.state.theme_name());
 
-        let _ = self.signal_tx.send(DaemonSignals::DisplaySettingsChanged);
+        self.events.publish(Event::DisplaySettingsChanged);
         debug!("D-Bus: SetTheme({})", name);
         Ok(())
     }
 
-    /// Lists available color themes (IDs only, for backwards compatibility).
+    /// Gets the current theme colors as "#RRGGBB" by name ("primary",
+    /// "secondary", "text" and "background"), including unsaved edits.
+    fn get_theme_colors(&self) -> HashMap<String, String> {
+        self.state.theme_colors().into_iter().collect()
+    }
+
+    /// Changes some of the current theme colors live, without saving them,
+    /// so a theme editor can update the panel as its sliders move.
+    async fn set_theme_colors(
+        &self,
+        #[zbus(header)] header: Header<'_>,
+        colors: HashMap<String, String>,
+    ) -> zbus::fdo::Result<()> {
+        let caller = self.auth.check(&header, Privilege::Configure).await?;
+        let old = self.state.describe_theme_colors();
+        self.state
+            .set_theme_colors(&colors)
+            .map_err(|e| zbus::fdo::Error::InvalidArgs(e.to_string()))?;
+        let new = self.state.describe_theme_colors();
+        self.audit(&caller, "SetThemeColors", &old, &new);
+        debug!("D-Bus: SetThemeColors({:?})", colors);
+        Ok(())
+    }
+
+    /// Saves the current theme colors as a theme and switches to it.
+    async fn save_current_theme(
+        &self,
+        #[zbus(header)] header: Header<'_>,
+        name: &str,
+    ) -> zbus::fdo::Result<()> {
+        let caller = self.auth.check(&header, Privilege::Configure).await?;
+        let old = self.state.theme_name();
+        self.state
+            .save_current_theme(name)
+            .map_err(|e| zbus::fdo::Error::InvalidArgs(e.to_string()))?;
+        self.audit(&caller, "SaveCurrentTheme", &old, name);
+
+        self.events.publish(Event::DisplaySettingsChanged);
+        debug!("D-Bus: SaveCurrentTheme({})", name);
+        Ok(())
+    }
+
+    /// Deletes a saved theme, switching to the default theme if it is the
+    /// current one.
+    async fn delete_theme(
+        &self,
+        #[zbus(header)] header: Header<'_>,
+        name: &str,
+    ) -> zbus::fdo::Result<()> {
+        let caller = self.auth.check(&header, Privilege::Configure).await?;
+        self.state
+            .delete_theme(name)
+            .map_err(|e| zbus::fdo::Error::InvalidArgs(e.to_string()))?;
+        self.audit(&caller, "DeleteTheme", name, "");
+
+        self.events.publish(Event::DisplaySettingsChanged);
+        debug!("D-Bus: DeleteTheme({})", name);
+        Ok(())
+    }
+
+    /// Lists available color themes (IDs only, for backwards compatibility),
+    /// presets first, then saved themes.
     fn list_themes(&self) -> Vec<String> {
         self.state
             .available_themes()
             .iter()
             .map(|t| t.id.to_string())
+            .chain(self.state.custom_themes())
             .collect()
     }
 
-    /// Lists available color themes with display names.
-    /// Returns JSON-encoded theme data.
+    /// Lists available color themes, presets first, then saved themes.
+    fn list_theme_info(&self) -> Vec<ThemeInfo> {
+        self.state.theme_infos()
+    }
+
+    /// Lists available color themes as JSON-encoded `ThemeInfo`. Kept for
+    /// older clients; `ListThemeInfo` returns them typed.
     fn list_themes_detailed(&self) -> Vec<String> {
         self.state
-            .available_themes()
+            .theme_infos()
             .iter()
-            .map(|t| {
-                serde_json::json!({
-                    "id": t.id,
-                    "display_name": t.display_name
-                })
-                .to_string()
-            })
+            .filter_map(|t| serde_json::to_string(t).ok())
             .collect()
     }
 
@@ -151,80 +365,585 @@ impl Daemon1Interface {
     }
 
     /// Lists available faces with display names.
-    /// Returns JSON-encoded face data.
-    fn list_faces(&self) -> Vec<String> {
+    fn list_face_info(&self) -> Vec<FaceInfo> {
         crate::faces::available_faces()
             .iter()
-            .map(|f| {
-                serde_json::json!({
-                    "id": f.id,
-                    "display_name": f.display_name
-                })
-                .to_string()
+            .map(|f| FaceInfo {
+                id: f.id.to_string(),
+                display_name: f.display_name.to_string(),
             })
             .collect()
     }
 
+    /// Lists available faces as JSON-encoded `FaceInfo`. Kept for older
+    /// clients; `ListFaceInfo` returns them typed.
+    fn list_faces(&self) -> Vec<String> {
+        self.list_face_info()
+            .iter()
+            .filter_map(|f| serde_json::to_string(f).ok())
+            .collect()
+    }
+
     /// Returns the current framebuffer as PNG data.
-    fn get_screen_png(&self) -> zbus::fdo::Result<Vec<u8>> {
+    async fn get_screen_png(&self) -> zbus::fdo::Result<Vec<u8>> {
         self.state
-            .get_screen_png()
+            .get_screen_image(ScreenFormat::Png, 0)
+            .await
             .map_err(|e| zbus::fdo::Error::Failed(e.to_string()))
     }
 
-    /// Sets LED parameters.
-    async fn set_led(&self, theme: u8, intensity: u8, speed: u8) -> zbus::fdo::Result<()> {
-        // Validate parameters
-        if !(1..=5).contains(&theme) {
-            return Err(zbus::fdo::Error::InvalidArgs(
-                "Theme must be 1-5".to_string(),
-            ));
-        }
-        if !(1..=5).contains(&intensity) {
+    /// Returns the current framebuffer as "png", "jpeg" or "webp" data.
+    /// `quality` (1-100, 0 for the default) applies to JPEG; WebP is
+    /// lossless.
+    async fn get_screen_image(&self, format: &str, quality: u8) -> zbus::fdo::Result<Vec<u8>> {
+        let format: ScreenFormat = format.parse().map_err(zbus::fdo::Error::InvalidArgs)?;
+        self.state
+            .get_screen_image(format, quality)
+            .await
+            .map_err(|e| zbus::fdo::Error::Failed(e.to_string()))
+    }
+
+    /// Renders the current face with a theme as PNG data, without applying it.
+    fn render_theme_preview(&self, theme: &str) -> zbus::fdo::Result<Vec<u8>> {
+        let png_data = self
+            .state
+            .render_theme_preview(theme)
+            .map_err(|e| zbus::fdo::Error::InvalidArgs(e.to_string()))?;
+        debug!("D-Bus: RenderThemePreview({})", theme);
+        Ok(png_data)
+    }
+
+    /// Sets LED parameters. Kept for older clients; `SetLedSettings` takes
+    /// them as one struct.
+    async fn set_led(
+        &self,
+        #[zbus(header)] header: Header<'_>,
+        theme: u8,
+        intensity: u8,
+        speed: u8,
+    ) -> Result<(), Error> {
+        let caller = self.auth.check(&header, Privilege::Configure).await?;
+        let settings = LedSettings::new(theme, intensity, speed)?;
+        Ok(self.apply_led(&caller, "SetLed", settings).await?)
+    }
+
+    /// Sets the LED theme, intensity and speed from one `(yyy)` struct.
+    /// Values outside 1-5 are refused when the call is decoded.
+    async fn set_led_settings(
+        &self,
+        #[zbus(header)] header: Header<'_>,
+        settings: LedSettings,
+    ) -> zbus::fdo::Result<()> {
+        let caller = self.auth.check(&header, Privilege::Configure).await?;
+        self.apply_led(&caller, "SetLedSettings", settings).await
+    }
+
+    /// Flashes the LEDs `count` times as a notification, then restores the
+    /// current LED settings. `color` is an LED effect name (rainbow,
+    /// breathing, colors, auto) since the strip has no per-color control.
+    /// Returns immediately; the pulse runs in the background.
+    async fn pulse_led(
+        &self,
+        #[zbus(header)] header: Header<'_>,
+        color: &str,
+        count: u32,
+    ) -> zbus::fdo::Result<()> {
+        let caller = self.auth.check(&header, Privilege::Configure).await?;
+        let theme: LedTheme = color.parse().map_err(|_| {
+            zbus::fdo::Error::InvalidArgs(format!(
+                "Invalid LED color: {}. Use: rainbow, breathing, colors, auto",
+                color
+            ))
+        })?;
+        if theme == LedTheme::Off {
             return Err(zbus::fdo::Error::InvalidArgs(
-                "Intensity must be 1-5".to_string(),
+                "Cannot pulse with LEDs off".to_string(),
             ));
         }
-        if !(1..=5).contains(&speed) {
+        if !(1..=20).contains(&count) {
             return Err(zbus::fdo::Error::InvalidArgs(
-                "Speed must be 1-5".to_string(),
+                "Count must be 1-20".to_string(),
             ));
         }
 
+        self.audit(&caller, "PulseLed", "", &format!("{} x{}", color, count));
+        let state = self.state.clone();
+        tokio::spawn(async move {
+            if let Err(e) = state.pulse_led(theme, count).await {
+                warn!("LED pulse failed: {:#}", e);
+            }
+        });
+
+        debug!("D-Bus: PulseLed({}, {})", color, count);
+        Ok(())
+    }
+
+    /// Switches do-not-disturb on or off by hand. While it is on, alert
+    /// banners and LED pulses are held back; the face keeps rendering.
+    async fn set_dnd(&self, #[zbus(header)] header: Header<'_>, on: bool) -> zbus::fdo::Result<()> {
+        let caller = self.auth.check(&header, Privilege::Configure).await?;
+        let old = self.state.dnd();
+        self.state.set_dnd(on);
+        self.audit(&caller, "SetDnd", &old.to_string(), &on.to_string());
+        self.events.publish(Event::DisplaySettingsChanged);
+        debug!("D-Bus: SetDnd({})", on);
+        Ok(())
+    }
+
+    /// Gets do-not-disturb as (switched on by hand, in effect now, by hand
+    /// or by the schedule).
+    fn get_dnd(&self) -> (bool, bool) {
+        (self.state.dnd(), self.state.dnd_active())
+    }
+
+    /// Returns the face switching rules in effect as a JSON array of
+    /// objects with "when", "above", "below", "for" (seconds) and "face".
+    fn get_rules(&self) -> zbus::fdo::Result<String> {
+        serde_json::to_string(&self.state.rules())
+            .map_err(|e| zbus::fdo::Error::Failed(e.to_string()))
+    }
+
+    /// Replaces the face switching rules with a JSON array as returned by
+    /// `GetRules`. An empty string returns to the configured rules.
+    async fn set_rules(
+        &self,
+        #[zbus(header)] header: Header<'_>,
+        rules: &str,
+    ) -> zbus::fdo::Result<()> {
+        let caller = self.auth.check(&header, Privilege::Configure).await?;
+        let parsed: Option<Vec<RuleConfig>> = if rules.trim().is_empty() {
+            None
+        } else {
+            Some(
+                serde_json::from_str(rules)
+                    .map_err(|e| zbus::fdo::Error::InvalidArgs(format!("Invalid rules: {}", e)))?,
+            )
+        };
+        let old = self.get_rules()?;
+        self.state
+            .set_rules(parsed)
+            .map_err(|e| zbus::fdo::Error::InvalidArgs(e.to_string()))?;
+        self.audit(&caller, "SetRules", &old, &self.get_rules()?);
+        self.events.publish(Event::DisplaySettingsChanged);
+        debug!("D-Bus: SetRules({})", rules);
+        Ok(())
+    }
+
+    /// Lists the built-in collectors as (name, switched on).
+    fn list_sensors(&self) -> Vec<(String, bool)> {
+        self.state
+            .collectors()
+            .into_iter()
+            .map(|(name, enabled)| (name.to_string(), enabled))
+            .collect()
+    }
+
+    /// Switches a built-in collector (temperature, disk, network, processes
+    /// or sessions) on or off. Complications showing its data are hidden
+    /// while it is off.
+    async fn set_sensor_enabled(
+        &self,
+        #[zbus(header)] header: Header<'_>,
+        name: &str,
+        enabled: bool,
+    ) -> zbus::fdo::Result<()> {
+        let caller = self.auth.check(&header, Privilege::Configure).await?;
+        let old = self
+            .state
+            .collectors()
+            .into_iter()
+            .find(|(collector, _)| *collector == name)
+            .map(|(_, on)| on.to_string())
+            .unwrap_or_default();
+        self.state
+            .set_sensor_enabled(name, enabled)
+            .map_err(|e| zbus::fdo::Error::InvalidArgs(e.to_string()))?;
+        self.audit(
+            &caller,
+            "SetSensorEnabled",
+            &format!("{}={}", name, old),
+            &format!("{}={}", name, enabled),
+        );
+        self.events.publish(Event::DisplaySettingsChanged);
+        debug!("D-Bus: SetSensorEnabled({}, {})", name, enabled);
+        Ok(())
+    }
+
+    /// Lists the readable temperature inputs as (label, path, Celsius,
+    /// being read).
+    fn list_temperature_sensors(&self) -> Vec<(String, String, f64, bool)> {
+        self.state
+            .list_temperature_sensors()
+            .into_iter()
+            .map(|(candidate, celsius, active)| {
+                (
+                    candidate.label,
+                    candidate.path.display().to_string(),
+                    celsius,
+                    active,
+                )
+            })
+            .collect()
+    }
+
+    /// Selects the CPU temperature input by hwmon path, label such as
+    /// "k10temp Tctl" or chip name. An empty string returns to the
+    /// configured input.
+    async fn set_temperature_sensor(
+        &self,
+        #[zbus(header)] header: Header<'_>,
+        path_or_label: &str,
+    ) -> zbus::fdo::Result<()> {
+        let caller = self.auth.check(&header, Privilege::Configure).await?;
+        let old = self.state.temperature_sensor().unwrap_or_default();
+        self.state
+            .set_temperature_sensor(Some(path_or_label.to_string()))
+            .map_err(|e| zbus::fdo::Error::InvalidArgs(e.to_string()))?;
+        self.audit(&caller, "SetTemperatureSensor", &old, path_or_label);
+        self.events.publish(Event::DisplaySettingsChanged);
+        debug!("D-Bus: SetTemperatureSensor({})", path_or_label);
+        Ok(())
+    }
+
+    /// Gets the selected CPU temperature input (empty when auto-detecting).
+    fn get_temperature_sensor(&self) -> String {
+        self.state.temperature_sensor().unwrap_or_default()
+    }
+
+    /// Starts a bandwidth test in the background. Progress and the result
+    /// are shown on the panel, and `SpeedtestFinished` is emitted when done.
+    async fn run_speedtest(
+        &self,
+        #[zbus(header)] header: Header<'_>,
+        #[zbus(signal_emitter)] emitter: SignalEmitter<'_>,
+    ) -> zbus::fdo::Result<()> {
+        let caller = self.auth.check(&header, Privilege::Network).await?;
+        self.start_speedtest(&caller, emitter.into_owned())?;
+        debug!("D-Bus: RunSpeedtest");
+        Ok(())
+    }
+
+    /// Shows a test pattern in place of the face: "bars", "gradient",
+    /// "text", "orientation" (flipping through each orientation) or "all".
+    /// Returns how many seconds it runs before the face returns.
+    async fn run_test_pattern(
+        &self,
+        #[zbus(header)] header: Header<'_>,
+        name: &str,
+    ) -> zbus::fdo::Result<u32> {
+        let caller = self.auth.check(&header, Privilege::Configure).await?;
+        let steps = self
+            .state
+            .start_test_pattern(name)
+            .map_err(|e| zbus::fdo::Error::InvalidArgs(e.to_string()))?;
+        let duration = AppState::test_pattern_duration(&steps);
+
+        self.audit(&caller, "RunTestPattern", "", name);
+        let state = self.state.clone();
+        tokio::spawn(async move { state.play_test_pattern(steps).await });
+
+        debug!("D-Bus: RunTestPattern({})", name);
+        Ok(duration.as_secs() as u32)
+    }
+
+    /// Sends a Wake-on-LAN magic packet. `mac` is a MAC address or the name
+    /// of a host from `[sensors.hosts]` with a configured MAC.
+    async fn wake_host(
+        &self,
+        #[zbus(header)] header: Header<'_>,
+        mac: &str,
+    ) -> zbus::fdo::Result<()> {
+        let caller = self.auth.check(&header, Privilege::Network).await?;
         self.state
-            .set_led(theme, intensity, speed)
+            .wake_host(mac)
+            .map_err(|e| zbus::fdo::Error::InvalidArgs(e.to_string()))?;
+        self.audit(&caller, "WakeHost", "", mac);
+
+        debug!("D-Bus: WakeHost({})", mac);
+        Ok(())
+    }
+
+    /// Reports the status of an external job such as a backup. `status` is
+    /// "running", "success" or "failed"; `progress` is 0-100, or negative if
+    /// unknown.
+    async fn report_job(
+        &self,
+        #[zbus(header)] header: Header<'_>,
+        name: &str,
+        status: &str,
+        progress: f64,
+    ) -> zbus::fdo::Result<()> {
+        // Job reports are status updates that can arrive every few seconds,
+        // so they are not audited
+        self.auth.check(&header, Privilege::Configure).await?;
+        let progress = (progress >= 0.0).then_some(progress as f32);
+        self.state
+            .report_job(name, status, progress)
+            .map_err(|e| zbus::fdo::Error::InvalidArgs(e.to_string()))?;
+
+        debug!(
+            "D-Bus: ReportJob({}, {}, {})",
+            name,
+            status,
+            progress.unwrap_or(-1.0)
+        );
+        Ok(())
+    }
+
+    /// Lists the actions accepted by `InvokeAction`.
+    /// Returns JSON-encoded action data with each action's arguments.
+    fn list_actions(&self) -> Vec<String> {
+        crate::actions::available_actions()
+            .iter()
+            .map(|a| a.to_json().to_string())
+            .collect()
+    }
+
+    /// Runs an action from `ListActions` with positional arguments.
+    /// Returns a short description of the result.
+    async fn invoke_action(
+        &self,
+        #[zbus(header)] header: Header<'_>,
+        id: &str,
+        args: Vec<String>,
+    ) -> zbus::fdo::Result<String> {
+        let caller = self
+            .auth
+            .check(&header, polkit::action_privilege(id))
+            .await?;
+        let outcome = crate::actions::invoke(&self.state, id, &args)
             .await
+            .map_err(|e| zbus::fdo::Error::InvalidArgs(e.to_string()))?;
+        let action = crate::actions::describe(id, &args);
+        self.audit(&caller, "InvokeAction", "", &action);
+        if let Some(event) = outcome.event {
+            self.events.publish(event);
+        }
+
+        debug!("D-Bus: InvokeAction({}, {:?})", id, args);
+        Ok(outcome.message)
+    }
+
+    /// Reverts the last display settings change, whatever made it.
+    /// Returns the settings that changed, e.g. "theme, LEDs".
+    async fn undo(&self, #[zbus(header)] header: Header<'_>) -> zbus::fdo::Result<String> {
+        let caller = self.auth.check(&header, Privilege::Configure).await?;
+        let changes = self
+            .state
+            .undo()
             .map_err(|e| zbus::fdo::Error::Failed(e.to_string()))?;
+        self.audit(&caller, "Undo", "", &changes);
 
-        // Emit signal
-        let _ = self.signal_tx.send(DaemonSignals::LedChanged);
+        self.events.publish(Event::OrientationChanged);
+        self.events.publish(Event::DisplaySettingsChanged);
+        self.events.publish(Event::LedChanged);
+        debug!("D-Bus: Undo");
+        Ok(changes)
+    }
+
+    /// Reapplies the last undone display settings change.
+    /// Returns the settings that changed.
+    async fn redo(&self, #[zbus(header)] header: Header<'_>) -> zbus::fdo::Result<String> {
+        let caller = self.auth.check(&header, Privilege::Configure).await?;
+        let changes = self
+            .state
+            .redo()
+            .map_err(|e| zbus::fdo::Error::Failed(e.to_string()))?;
+        self.audit(&caller, "Redo", "", &changes);
+
+        self.events.publish(Event::OrientationChanged);
+        self.events.publish(Event::DisplaySettingsChanged);
+        self.events.publish(Event::LedChanged);
+        debug!("D-Bus: Redo");
+        Ok(changes)
+    }
+
+    /// Returns up to `count` of the most recent changes, oldest first.
+    /// Returns JSON-encoded entries with timestamp, source, caller, method,
+    /// old and new values.
+    fn get_audit_log(&self, count: u32) -> Vec<String> {
+        self.state
+            .audit_log()
+            .recent(count as usize)
+            .iter()
+            .filter_map(|entry| serde_json::to_string(entry).ok())
+            .collect()
+    }
 
-        debug!("D-Bus: SetLed({}, {}, {})", theme, intensity, speed);
+    /// Sets the log level of `target`, a module path such as
+    /// "ht32_panel_hw" or "" for all targets, to "off", "error", "warn",
+    /// "info", "debug" or "trace". "reset" removes a level set before.
+    async fn set_log_level(
+        &self,
+        #[zbus(header)] header: Header<'_>,
+        target: &str,
+        level: &str,
+    ) -> zbus::fdo::Result<()> {
+        let caller = self.auth.check(&header, Privilege::Admin).await?;
+        logging::set_level(target, level)
+            .map_err(|e| zbus::fdo::Error::InvalidArgs(e.to_string()))?;
+        let target = if target.is_empty() { "*" } else { target };
+        self.audit(&caller, "SetLogLevel", "", &format!("{}={}", target, level));
+        info!("Log level of {} set to {}", target, level);
         Ok(())
     }
 
+    /// Returns up to `count` of the most recent log events, oldest first.
+    /// Returns JSON-encoded entries with timestamp, level, target, message
+    /// and other fields. Admin only, as messages can name hosts, paths and
+    /// command output.
+    async fn get_recent_logs(
+        &self,
+        #[zbus(header)] header: Header<'_>,
+        count: u32,
+    ) -> zbus::fdo::Result<Vec<String>> {
+        self.auth.check(&header, Privilege::Admin).await?;
+        Ok(logging::recent(count as usize)
+            .iter()
+            .filter_map(|entry| serde_json::to_string(entry).ok())
+            .collect())
+    }
+
+    /// Returns udev rules giving the `plugdev` group access to the LCD and
+    /// the LED serial adapter, for /etc/udev/rules.d/99-ht32-panel.rules.
+    fn generate_udev_rule(&self) -> String {
+        access::udev_rules(access::DEFAULT_GROUP)
+    }
+
+    /// Checks the contents of a configuration file without loading it.
+    /// Returns one message per problem, prefixed with its line and column
+    /// when known; an empty list means the file is valid.
+    fn validate_config(&self, content: &str) -> Vec<String> {
+        match config_check::check(content) {
+            Ok(_) => Vec::new(),
+            Err(problems) => problems.iter().map(|p| p.to_string()).collect(),
+        }
+    }
+
+    /// Returns the health of the daemon as JSON: status ("ok", "testing" or
+    /// "degraded"), problems, uptime, frames rendered, USB errors, sensor
+    /// failures and memory use. The same as `/healthz` on the web UI.
+    fn get_health(&self) -> zbus::fdo::Result<String> {
+        serde_json::to_string(&self.state.health())
+            .map_err(|e| zbus::fdo::Error::Failed(e.to_string()))
+    }
+
+    /// Lists the optional features the daemon was built with: "web" (web
+    /// UI, Stream Deck endpoint and mDNS), "audio", "hotkeys" and "hidraw"
+    /// (the pure-Rust USB HID backend).
+    fn list_capabilities(&self) -> Vec<String> {
+        health::capabilities()
+            .into_iter()
+            .map(String::from)
+            .collect()
+    }
+
+    /// Returns how many recent frames the statistics cover and, for the
+    /// sensors, render and present stages and the whole frame, the 50th,
+    /// 95th and 99th percentile and maximum time in milliseconds.
+    fn get_render_stats(&self) -> (u32, Vec<StageTimes>) {
+        let (frames, stages) = self.state.render_stats();
+        let stages = stages
+            .into_iter()
+            .map(|s| (s.stage.to_string(), s.p50, s.p95, s.p99, s.max))
+            .collect();
+        (frames as u32, stages)
+    }
+
+    /// Emitted when a panel button is pressed, with the report code as
+    /// used in `[buttons.bindings]`.
+    #[zbus(signal)]
+    async fn button_pressed(emitter: &SignalEmitter<'_>, code: &str) -> zbus::Result<()>;
+
+    /// Emitted when a speedtest completes. Rates are in bytes/second; values
+    /// the backend did not measure are 0.
+    #[zbus(signal)]
+    async fn speedtest_finished(
+        emitter: &SignalEmitter<'_>,
+        success: bool,
+        download: f64,
+        upload: f64,
+        ping_ms: f64,
+    ) -> zbus::Result<()>;
+
+    /// Whether the LED settings follow the display theme.
+    #[zbus(property)]
+    fn led_follow_theme(&self) -> bool {
+        self.state.led_follow_theme()
+    }
+
+    /// Enables or disables deriving the LED settings from the display theme.
+    async fn set_led_follow_theme(
+        &self,
+        #[zbus(header)] header: Header<'_>,
+        #[zbus(signal_emitter)] emitter: SignalEmitter<'_>,
+        follow: bool,
+    ) -> zbus::fdo::Result<()> {
+        let caller = self.auth.check(&header, Privilege::Configure).await?;
+        let old = self.state.led_follow_theme();
+        self.state.set_led_follow_theme(follow);
+        self.audit(
+            &caller,
+            "SetLedFollowTheme",
+            &old.to_string(),
+            &follow.to_string(),
+        );
+
+        if let Err(e) = self.led_follow_theme_changed(&emitter).await {
+            warn!("Failed to emit LedFollowTheme change: {}", e);
+        }
+
+        self.events.publish(Event::LedChanged);
+        debug!("D-Bus: SetLedFollowTheme({})", follow);
+        Ok(())
+    }
+
+    /// Gets whether the LED settings follow the display theme.
+    fn get_led_follow_theme(&self) -> bool {
+        self.state.led_follow_theme()
+    }
+
     /// Turns off LEDs.
-    async fn led_off(&self) -> zbus::fdo::Result<()> {
+    async fn led_off(&self, #[zbus(header)] header: Header<'_>) -> zbus::fdo::Result<()> {
+        let caller = self.auth.check(&header, Privilege::Configure).await?;
+        let old = self.state.led_settings().to_string();
         self.state
             .led_off()
             .await
             .map_err(|e| zbus::fdo::Error::Failed(e.to_string()))?;
+        let new = self.state.led_settings().to_string();
+        self.audit(&caller, "LedOff", &old, &new);
 
         // Emit signal
-        let _ = self.signal_tx.send(DaemonSignals::LedChanged);
+        self.events.publish(Event::LedChanged);
 
         debug!("D-Bus: LedOff");
         Ok(())
     }
 
     /// Gets current LED settings as (theme, intensity, speed).
-    fn get_led_settings(&self) -> (u8, u8, u8) {
+    fn get_led_settings(&self) -> LedSettings {
         self.state.led_settings()
     }
 
+    /// Gets the settings a client shows in one call.
+    fn get_state(&self) -> DaemonState {
+        DaemonState {
+            connected: self.state.is_lcd_connected(),
+            web_enabled: self.state.is_web_enabled(),
+            orientation: self.state.orientation(),
+            face: self.state.face_name(),
+            theme: self.state.theme_info(),
+            led: self.state.led_settings(),
+            ip_display_preference: self.state.ip_display(),
+        }
+    }
+
     /// Shuts down the daemon.
-    async fn quit(&self) -> zbus::fdo::Result<()> {
+    async fn quit(&self, #[zbus(header)] header: Header<'_>) -> zbus::fdo::Result<()> {
+        let caller = self.auth.check(&header, Privilege::Admin).await?;
         info!("D-Bus: Quit requested");
+        self.audit(&caller, "Quit", "", "");
         self.shutdown_tx
             .send(())
             .await
@@ -240,6 +959,14 @@ impl Daemon1Interface {
         self.state.is_lcd_connected()
     }
 
+    /// Why the LCD is not connected, such as the device node the daemon
+    /// has no permission to open, or why the last frame failed to reach
+    /// it; empty while frames are getting through.
+    #[zbus(property)]
+    fn connection_error(&self) -> String {
+        self.state.lcd_error()
+    }
+
     /// Whether the web UI is enabled.
     #[zbus(property)]
     fn web_enabled(&self) -> bool {
@@ -255,19 +982,19 @@ impl Daemon1Interface {
     /// Current LED theme (1-5).
     #[zbus(property)]
     fn led_theme(&self) -> u8 {
-        self.state.led_settings().0
+        self.state.led_settings().theme()
     }
 
     /// Current LED intensity (1-5).
     #[zbus(property)]
     fn led_intensity(&self) -> u8 {
-        self.state.led_settings().1
+        self.state.led_settings().intensity()
     }
 
     /// Current LED speed (1-5).
     #[zbus(property)]
     fn led_speed(&self) -> u8 {
-        self.state.led_settings().2
+        self.state.led_settings().speed()
     }
 
     /// Current color theme name.
@@ -282,21 +1009,333 @@ impl Daemon1Interface {
         self.state.face_name()
     }
 
+    /// Label shown instead of the hostname (empty when showing the hostname).
+    #[zbus(property)]
+    fn display_label(&self) -> String {
+        self.state.display_label().unwrap_or_default()
+    }
+
+    /// Overrides the hostname shown on faces. An empty label restores the hostname.
+    async fn set_display_label(
+        &self,
+        #[zbus(header)] header: Header<'_>,
+        #[zbus(signal_emitter)] emitter: SignalEmitter<'_>,
+        label: &str,
+    ) -> zbus::fdo::Result<()> {
+        let caller = self.auth.check(&header, Privilege::Configure).await?;
+        if label.chars().count() > 64 {
+            return Err(zbus::fdo::Error::InvalidArgs(
+                "Label must be at most 64 characters".to_string(),
+            ));
+        }
+
+        let old = self.state.display_label().unwrap_or_default();
+        self.state.set_display_label(label);
+        self.audit(&caller, "SetDisplayLabel", &old, label);
+
+        if let Err(e) = self.display_label_changed(&emitter).await {
+            warn!("Failed to emit DisplayLabel change: {}", e);
+        }
+        self.events.publish(Event::DisplaySettingsChanged);
+        debug!("D-Bus: SetDisplayLabel({})", label);
+        Ok(())
+    }
+
+    /// Gets the label shown instead of the hostname (empty when showing the hostname).
+    fn get_display_label(&self) -> String {
+        self.state.display_label().unwrap_or_default()
+    }
+
+    /// Sets the post-processing effects applied to each frame before it is
+    /// sent to the panel: invert, grayscale, warm (or warm:0-1) and
+    /// scanlines, applied in order. An empty list turns them off.
+    async fn set_post_effects(
+        &self,
+        #[zbus(header)] header: Header<'_>,
+        effects: Vec<String>,
+    ) -> zbus::fdo::Result<()> {
+        let caller = self.auth.check(&header, Privilege::Configure).await?;
+        let old = self.state.post_effects().join(",");
+        self.state
+            .set_post_effects(&effects)
+            .map_err(|e| zbus::fdo::Error::InvalidArgs(e.to_string()))?;
+        let new = self.state.post_effects().join(",");
+        self.audit(&caller, "SetPostEffects", &old, &new);
+
+        self.events.publish(Event::DisplaySettingsChanged);
+        debug!("D-Bus: SetPostEffects({:?})", effects);
+        Ok(())
+    }
+
+    /// Gets the post-processing effects.
+    fn get_post_effects(&self) -> Vec<String> {
+        self.state.post_effects()
+    }
+
+    /// Mirrors each frame horizontally and/or vertically as seen by the
+    /// viewer, independent of the orientation, for panels behind tinted
+    /// glass or in mirrored enclosures.
+    async fn set_transform(
+        &self,
+        #[zbus(header)] header: Header<'_>,
+        flip_h: bool,
+        flip_v: bool,
+    ) -> zbus::fdo::Result<()> {
+        let caller = self.auth.check(&header, Privilege::Configure).await?;
+        let (old_h, old_v) = self.state.transform();
+        self.state.set_transform(flip_h, flip_v);
+        self.audit(
+            &caller,
+            "SetTransform",
+            &format!("flip_h={} flip_v={}", old_h, old_v),
+            &format!("flip_h={} flip_v={}", flip_h, flip_v),
+        );
+
+        self.events.publish(Event::DisplaySettingsChanged);
+        debug!("D-Bus: SetTransform({}, {})", flip_h, flip_v);
+        Ok(())
+    }
+
+    /// Gets the horizontal and vertical mirroring.
+    fn get_transform(&self) -> (bool, bool) {
+        self.state.transform()
+    }
+
+    /// Draws frames only in the given rectangle of the panel, in landscape
+    /// panel coordinates, for case cutouts that hide its edges. Faces lay
+    /// out within it and the rest stays black. 0, 0, 320, 170 uses the
+    /// whole panel.
+    async fn set_visible_area(
+        &self,
+        #[zbus(header)] header: Header<'_>,
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+    ) -> zbus::fdo::Result<()> {
+        let caller = self.auth.check(&header, Privilege::Configure).await?;
+        let area = VisibleArea::new(x, y, width, height).map_err(zbus::fdo::Error::InvalidArgs)?;
+        let old = self.state.visible_area().to_string();
+        self.state.set_visible_area(area);
+        self.audit(&caller, "SetVisibleArea", &old, &area.to_string());
+
+        self.events.publish(Event::DisplaySettingsChanged);
+        debug!("D-Bus: SetVisibleArea({})", area);
+        Ok(())
+    }
+
+    /// Gets the visible area as x, y, width and height.
+    fn get_visible_area(&self) -> (u32, u32, u32, u32) {
+        let area = self.state.visible_area();
+        (area.x, area.y, area.width, area.height)
+    }
+
+    /// Sets the image drawn behind every face. An empty path removes it.
+    async fn set_background_image(
+        &self,
+        #[zbus(header)] header: Header<'_>,
+        path: &str,
+    ) -> zbus::fdo::Result<()> {
+        let caller = self.auth.check(&header, Privilege::Configure).await?;
+        let path = (!path.is_empty()).then_some(path);
+        let old = self.state.background_image().unwrap_or_default();
+        self.state
+            .set_background_image(path)
+            .map_err(|e| zbus::fdo::Error::InvalidArgs(format!("{:#}", e)))?;
+        let new = self.state.background_image().unwrap_or_default();
+        self.audit(&caller, "SetBackgroundImage", &old, &new);
+
+        self.events.publish(Event::DisplaySettingsChanged);
+        debug!("D-Bus: SetBackgroundImage({:?})", path);
+        Ok(())
+    }
+
+    /// Gets the background image path (empty when there is none).
+    fn get_background_image(&self) -> String {
+        self.state.background_image().unwrap_or_default()
+    }
+
+    /// Timezone override (empty when using the system timezone).
+    #[zbus(property)]
+    fn timezone(&self) -> String {
+        self.state.timezone().unwrap_or_default()
+    }
+
+    /// Sets the timezone shown on faces. An empty name or "local" uses the system timezone.
+    async fn set_timezone(
+        &self,
+        #[zbus(header)] header: Header<'_>,
+        #[zbus(signal_emitter)] emitter: SignalEmitter<'_>,
+        timezone: &str,
+    ) -> zbus::fdo::Result<()> {
+        let caller = self.auth.check(&header, Privilege::Configure).await?;
+        let old = self.state.timezone().unwrap_or_default();
+        self.state
+            .set_timezone(timezone)
+            .map_err(|e| zbus::fdo::Error::InvalidArgs(e.to_string()))?;
+        let new = self.state.timezone().unwrap_or_default();
+        self.audit(&caller, "SetTimezone", &old, &new);
+
+        if let Err(e) = self.timezone_changed(&emitter).await {
+            warn!("Failed to emit Timezone change: {}", e);
+        }
+
+        self.events.publish(Event::DisplaySettingsChanged);
+        debug!("D-Bus: SetTimezone({})", timezone);
+        Ok(())
+    }
+
+    /// Gets the timezone override (empty when using the system timezone).
+    fn get_timezone(&self) -> String {
+        self.state.timezone().unwrap_or_default()
+    }
+
+    /// Sets the units used for rates: 1024-based prefixes when `binary`,
+    /// and bits per second for network throughput when `network_bits`.
+    async fn set_rate_units(
+        &self,
+        #[zbus(header)] header: Header<'_>,
+        binary: bool,
+        network_bits: bool,
+    ) -> zbus::fdo::Result<()> {
+        let caller = self.auth.check(&header, Privilege::Configure).await?;
+        let old = self.state.rate_units();
+        self.state.set_rate_units(RateUnits {
+            binary,
+            network_bits,
+        });
+        self.audit(
+            &caller,
+            "SetRateUnits",
+            &format!("binary {}, network bits {}", old.binary, old.network_bits),
+            &format!("binary {}, network bits {}", binary, network_bits),
+        );
+
+        self.events.publish(Event::DisplaySettingsChanged);
+        debug!("D-Bus: SetRateUnits({}, {})", binary, network_bits);
+        Ok(())
+    }
+
+    /// Gets the rate units as (binary, network_bits).
+    fn get_rate_units(&self) -> (bool, bool) {
+        let units = self.state.rate_units();
+        (units.binary, units.network_bits)
+    }
+
+    /// Sets the block devices tracked for disk I/O (empty for auto-detect).
+    async fn set_disk_devices(
+        &self,
+        #[zbus(header)] header: Header<'_>,
+        devices: Vec<String>,
+    ) -> zbus::fdo::Result<()> {
+        let caller = self.auth.check(&header, Privilege::Configure).await?;
+        let old = self.state.disk_devices().join(",");
+        self.state
+            .set_disk_devices(devices.clone())
+            .map_err(|e| zbus::fdo::Error::InvalidArgs(e.to_string()))?;
+        self.audit(&caller, "SetDiskDevices", &old, &devices.join(","));
+
+        self.events.publish(Event::DisplaySettingsChanged);
+        debug!("D-Bus: SetDiskDevices({:?})", devices);
+        Ok(())
+    }
+
+    /// Gets the configured disk devices (empty when auto-detecting).
+    fn get_disk_devices(&self) -> Vec<String> {
+        self.state.disk_devices()
+    }
+
+    /// Lists block devices available for disk I/O tracking.
+    fn list_disk_devices(&self) -> Vec<String> {
+        self.state.list_disk_devices()
+    }
+
+    /// Current IP address display preference.
+    #[zbus(property)]
+    fn ip_display_preference(&self) -> String {
+        self.state.ip_display().to_string()
+    }
+
+    /// Sets which address family is shown (ipv4, ipv6-gua, ipv6-lla, ipv6-ula).
+    async fn set_ip_display_preference(
+        &self,
+        #[zbus(header)] header: Header<'_>,
+        #[zbus(signal_emitter)] emitter: SignalEmitter<'_>,
+        preference: &str,
+    ) -> Result<(), Error> {
+        let caller = self.auth.check(&header, Privilege::Configure).await?;
+        let preference: IpDisplayPreference = preference.parse()?;
+
+        let old = self.state.ip_display().to_string();
+        self.state.set_ip_display(preference);
+        self.audit(
+            &caller,
+            "SetIpDisplayPreference",
+            &old,
+            &preference.to_string(),
+        );
+
+        if let Err(e) = self.ip_display_preference_changed(&emitter).await {
+            warn!("Failed to emit IpDisplayPreference change: {}", e);
+        }
+        self.events.publish(Event::DisplaySettingsChanged);
+        debug!("D-Bus: SetIpDisplayPreference({})", preference);
+        Ok(())
+    }
+
+    /// Gets the current IP address display preference.
+    fn get_ip_display_preference(&self) -> IpDisplayPreference {
+        self.state.ip_display()
+    }
+
     /// Lists all available network interfaces.
     fn list_network_interfaces(&self) -> Vec<String> {
         self.state.list_network_interfaces()
     }
 
+    /// Sets the network interface to monitor on the current face; "auto"
+    /// picks the interface with the default route.
+    async fn set_network_interface(
+        &self,
+        #[zbus(header)] header: Header<'_>,
+        interface: &str,
+    ) -> zbus::fdo::Result<()> {
+        let caller = self.auth.check(&header, Privilege::Network).await?;
+        if interface != "auto"
+            && !self
+                .state
+                .list_network_interfaces()
+                .iter()
+                .any(|i| i == interface)
+        {
+            return Err(zbus::fdo::Error::InvalidArgs(format!(
+                "Unknown interface '{}'",
+                interface
+            )));
+        }
+        let old = self
+            .state
+            .network_interface()
+            .unwrap_or_else(|| "auto".to_string());
+        self.state
+            .set_network_interface((interface != "auto").then(|| interface.to_string()));
+        self.audit(&caller, "SetNetworkInterface", &old, interface);
+        self.events.publish(Event::ComplicationOptionChanged);
+        debug!("D-Bus: SetNetworkInterface({})", interface);
+        Ok(())
+    }
+
     /// Lists available complications for the current face.
-    /// Returns a list of (id, name, description, enabled) tuples.
-    fn list_complications(&self) -> Vec<(String, String, String, bool)> {
+    fn list_complications(&self) -> Vec<ComplicationInfo> {
         let available = self.state.available_complications();
         let enabled = self.state.enabled_complications();
         available
             .into_iter()
-            .map(|c| {
-                let is_enabled = enabled.contains(&c.id);
-                (c.id, c.name, c.description, is_enabled)
+            .map(|c| ComplicationInfo {
+                enabled: enabled.contains(&c.id),
+                id: c.id,
+                name: c.name,
+                description: c.description,
             })
             .collect()
     }
@@ -379,13 +1418,17 @@ impl Daemon1Interface {
                     }
                 }).collect();
 
+                let placement = self.state.complication_placement(&c.id);
                 serde_json::json!({
                     "id": c.id,
                     "name": c.name,
                     "description": c.description,
                     "enabled": is_enabled,
                     "options": options,
-                    "face": face_name
+                    "face": face_name,
+                    "movable": placement::PLACEABLE.contains(&c.id.as_str()),
+                    "slot": placement.map(|p| p.slot.name()).unwrap_or(""),
+                    "size": placement.map(|p| p.size.name()).unwrap_or("")
                 }).to_string()
             })
             .collect()
@@ -397,21 +1440,33 @@ impl Daemon1Interface {
     }
 
     /// Enables a complication for the current face.
-    fn enable_complication(&self, complication_id: &str) -> zbus::fdo::Result<()> {
+    async fn enable_complication(
+        &self,
+        #[zbus(header)] header: Header<'_>,
+        complication_id: &str,
+    ) -> zbus::fdo::Result<()> {
+        let caller = self.auth.check(&header, Privilege::Configure).await?;
         self.state
             .set_complication_enabled(complication_id, true)
             .map_err(|e| zbus::fdo::Error::InvalidArgs(e.to_string()))?;
-        let _ = self.signal_tx.send(DaemonSignals::DisplaySettingsChanged);
+        self.audit(&caller, "EnableComplication", "", complication_id);
+        self.events.publish(Event::DisplaySettingsChanged);
         debug!("D-Bus: EnableComplication({})", complication_id);
         Ok(())
     }
 
     /// Disables a complication for the current face.
-    fn disable_complication(&self, complication_id: &str) -> zbus::fdo::Result<()> {
+    async fn disable_complication(
+        &self,
+        #[zbus(header)] header: Header<'_>,
+        complication_id: &str,
+    ) -> zbus::fdo::Result<()> {
+        let caller = self.auth.check(&header, Privilege::Configure).await?;
         self.state
             .set_complication_enabled(complication_id, false)
             .map_err(|e| zbus::fdo::Error::InvalidArgs(e.to_string()))?;
-        let _ = self.signal_tx.send(DaemonSignals::DisplaySettingsChanged);
+        self.audit(&caller, "DisableComplication", complication_id, "");
+        self.events.publish(Event::DisplaySettingsChanged);
         debug!("D-Bus: DisableComplication({})", complication_id);
         Ok(())
     }
@@ -433,28 +1488,153 @@ impl Daemon1Interface {
     }
 
     /// Sets a complication option value.
-    fn set_complication_option(
+    async fn set_complication_option(
         &self,
+        #[zbus(header)] header: Header<'_>,
         complication_id: &str,
         option_id: &str,
         value: &str,
     ) -> zbus::fdo::Result<()> {
+        let privilege = if complication_id == "network" && option_id == "interface" {
+            Privilege::Network
+        } else {
+            Privilege::Configure
+        };
+        let caller = self.auth.check(&header, privilege).await?;
+        let old = self
+            .state
+            .get_complication_option(complication_id, option_id)
+            .unwrap_or_default();
         self.state
             .set_complication_option(complication_id, option_id, value)
             .map_err(|e| zbus::fdo::Error::InvalidArgs(e.to_string()))?;
-        let _ = self
-            .signal_tx
-            .send(DaemonSignals::ComplicationOptionChanged);
+        self.audit(
+            &caller,
+            "SetComplicationOption",
+            &format!("{}.{}={}", complication_id, option_id, old),
+            &format!("{}.{}={}", complication_id, option_id, value),
+        );
+        self.events.publish(Event::ComplicationOptionChanged);
         debug!(
             "D-Bus: SetComplicationOption({}, {}, {})",
             complication_id, option_id, value
         );
         Ok(())
     }
+
+    /// Gets where a complication of the current face is placed.
+    /// Returns (slot, size); an empty slot means the face lays it out.
+    fn get_complication_position(&self, complication_id: &str) -> (String, String) {
+        match self.state.complication_placement(complication_id) {
+            Some(p) => (p.slot.name().to_string(), p.size.name().to_string()),
+            None => (String::new(), String::new()),
+        }
+    }
+
+    /// Moves a complication of the current face to a slot ("top-left",
+    /// "top-right", "bottom-left", "bottom-right" or "bottom-bar") with a
+    /// size ("small" or "large"). An empty slot returns it to the face.
+    async fn set_complication_position(
+        &self,
+        #[zbus(header)] header: Header<'_>,
+        complication_id: &str,
+        slot: &str,
+        size: &str,
+    ) -> zbus::fdo::Result<()> {
+        let caller = self.auth.check(&header, Privilege::Configure).await?;
+        let (old_slot, _) = self.get_complication_position(complication_id);
+        self.state
+            .set_complication_placement(complication_id, slot, size)
+            .map_err(|e| zbus::fdo::Error::InvalidArgs(e.to_string()))?;
+        self.audit(
+            &caller,
+            "SetComplicationPosition",
+            &format!("{}@{}", complication_id, old_slot),
+            &format!("{}@{}", complication_id, slot),
+        );
+        self.events.publish(Event::DisplaySettingsChanged);
+        debug!(
+            "D-Bus: SetComplicationPosition({}, {}, {})",
+            complication_id, slot, size
+        );
+        Ok(())
+    }
+
+    /// Lists all overlays drawn on top of every face as (id, slot, size).
+    /// Overlays that are not shown have an empty slot and size.
+    fn list_overlays(&self) -> Vec<(String, String, String)> {
+        let shown = self.state.overlays();
+        overlays::available()
+            .into_iter()
+            .map(|id| match shown.iter().find(|o| o.id == id) {
+                Some(o) => (
+                    id.to_string(),
+                    o.slot.name().to_string(),
+                    o.size.name().to_string(),
+                ),
+                None => (id.to_string(), String::new(), String::new()),
+            })
+            .collect()
+    }
+
+    /// Shows an overlay on every face in a slot ("top-left", "top-right",
+    /// "bottom-left", "bottom-right" or "bottom-bar") with a size ("small"
+    /// or "large"), moving it if already shown.
+    async fn set_overlay(
+        &self,
+        #[zbus(header)] header: Header<'_>,
+        id: &str,
+        slot: &str,
+        size: &str,
+    ) -> zbus::fdo::Result<()> {
+        let caller = self.auth.check(&header, Privilege::Configure).await?;
+        let old = self.overlay_position(id);
+        self.state
+            .set_overlay(id, slot, size)
+            .map_err(|e| zbus::fdo::Error::InvalidArgs(e.to_string()))?;
+        self.audit(
+            &caller,
+            "SetOverlay",
+            &format!("{}@{}", id, old),
+            &format!("{}@{}", id, slot),
+        );
+        self.events.publish(Event::DisplaySettingsChanged);
+        debug!("D-Bus: SetOverlay({}, {}, {})", id, slot, size);
+        Ok(())
+    }
+
+    /// Hides an overlay.
+    async fn remove_overlay(
+        &self,
+        #[zbus(header)] header: Header<'_>,
+        id: &str,
+    ) -> zbus::fdo::Result<()> {
+        let caller = self.auth.check(&header, Privilege::Configure).await?;
+        let old = self.overlay_position(id);
+        if !self.state.remove_overlay(id) {
+            return Err(zbus::fdo::Error::InvalidArgs(format!(
+                "Overlay '{}' is not shown",
+                id
+            )));
+        }
+        self.audit(&caller, "RemoveOverlay", &format!("{}@{}", id, old), id);
+        self.events.publish(Event::DisplaySettingsChanged);
+        debug!("D-Bus: RemoveOverlay({})", id);
+        Ok(())
+    }
 }
 
 /// Connects to the appropriate D-Bus bus based on configuration.
 async fn connect_to_bus(bus_type: DbusBusType) -> anyhow::Result<(Connection, &'static str)> {
+    // When a bus starts the daemon through D-Bus activation, register there
+    let bus_type = match bus_type {
+        DbusBusType::Auto => match std::env::var("DBUS_STARTER_BUS_TYPE").as_deref() {
+            Ok("session") => DbusBusType::Session,
+            Ok("system") => DbusBusType::System,
+            _ => DbusBusType::Auto,
+        },
+        other => other,
+    };
     match bus_type {
         DbusBusType::Session => {
             let conn = Connection::session()
@@ -494,14 +1674,22 @@ async fn connect_to_bus(bus_type: DbusBusType) -> anyhow::Result<(Connection, &'
 /// Runs the D-Bus server.
 pub async fn run_dbus_server(
     state: Arc<AppState>,
-    signal_tx: broadcast::Sender<DaemonSignals>,
+    events: EventBus,
     shutdown_tx: tokio::sync::mpsc::Sender<()>,
     bus_type: DbusBusType,
+    polkit: bool,
+    kiosk: bool,
 ) -> anyhow::Result<Connection> {
-    let interface = Daemon1Interface::new(state, signal_tx, shutdown_tx);
-
+    let mut receiver = events.subscribe();
     let (connection, bus_name) = connect_to_bus(bus_type).await?;
 
+    // Only the caller's own user can reach the session bus
+    if polkit && bus_name != "system" {
+        info!("Not checking D-Bus callers with polkit on the session bus");
+    }
+    let auth = Authorizer::new(&connection, polkit && bus_name == "system", kiosk).await;
+    let interface = Daemon1Interface::new(state, events, shutdown_tx, auth);
+
     connection
         .object_server()
         .at("/org/ht32panel/Daemon", interface)
@@ -517,5 +1705,17 @@ pub async fn run_dbus_server(
         "D-Bus service registered at org.ht32panel.Daemon on {} bus",
         bus_name
     );
+
+    // Forward button presses as D-Bus signals
+    let emitter = SignalEmitter::new(&connection, "/org/ht32panel/Daemon")?.into_owned();
+    tokio::spawn(async move {
+        while let Some(event) = events::next(&mut receiver, "D-Bus signals").await {
+            if let Event::ButtonPressed(code) = event {
+                if let Err(e) = Daemon1Interface::button_pressed(&emitter, &code).await {
+                    warn!("Failed to emit ButtonPressed: {}", e);
+                }
+            }
+        }
+    });
     Ok(connection)
 }
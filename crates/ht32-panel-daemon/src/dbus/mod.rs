@@ -2,6 +2,8 @@
 //!
 //! Provides the `org.ht32panel.Daemon1` interface on the session or system bus.
 
+mod error;
 mod interface;
+mod polkit;
 
-pub use interface::{run_dbus_server, DaemonSignals};
+pub use interface::run_dbus_server;
@@ -0,0 +1,196 @@
+//! polkit authorization of D-Bus callers.
+//!
+//! On the system bus any local user can reach the daemon. With `dbus.polkit`
+//! enabled, each mutating method checks the caller against one of the
+//! actions in `packaging/org.ht32panel.policy`, so everyday changes can be
+//! left to any user while stopping the daemon or driving the hardware
+//! directly needs an administrator. Root is always allowed.
+//!
+//! In kiosk mode every mutating method is refused to callers other than
+//! root, on either bus.
+
+use std::collections::HashMap;
+
+use tracing::{debug, info, warn};
+use zbus::message::Header;
+use zbus::names::BusName;
+use zbus::proxy::CacheProperties;
+use zbus::zvariant::Value;
+use zbus::{proxy, Connection};
+
+/// What a method call is allowed to do.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Privilege {
+    /// Change faces, themes, LEDs and other display settings
+    Configure,
+    /// Change the monitored network interface or send network traffic
+    Network,
+    /// Stop the daemon or send commands straight to the panel
+    Admin,
+}
+
+impl Privilege {
+    /// Returns the polkit action ID.
+    pub fn action_id(self) -> &'static str {
+        match self {
+            Privilege::Configure => "org.ht32panel.configure",
+            Privilege::Network => "org.ht32panel.network",
+            Privilege::Admin => "org.ht32panel.admin",
+        }
+    }
+}
+
+/// Returns the privilege needed to run an action from
+/// [`crate::actions::available_actions`].
+pub fn action_privilege(id: &str) -> Privilege {
+    match id {
+        "run_speedtest" | "wake_host" => Privilege::Network,
+        _ => Privilege::Configure,
+    }
+}
+
+/// polkit authority interface.
+#[proxy(
+    interface = "org.freedesktop.PolicyKit1.Authority",
+    default_service = "org.freedesktop.PolicyKit1",
+    default_path = "/org/freedesktop/PolicyKit1/Authority"
+)]
+trait Authority {
+    /// Checks whether `subject` may perform `action_id`.
+    fn check_authorization(
+        &self,
+        subject: &(&str, HashMap<&str, Value<'_>>),
+        action_id: &str,
+        details: HashMap<&str, &str>,
+        flags: u32,
+        cancellation_id: &str,
+    ) -> zbus::Result<(bool, bool, HashMap<String, String>)>;
+}
+
+/// The sender of a method call.
+#[derive(Debug, Clone)]
+pub struct Caller {
+    /// Unique bus name, e.g. ":1.42"
+    pub name: String,
+    /// Unix user ID, if it could be looked up
+    pub uid: Option<u32>,
+}
+
+impl std::fmt::Display for Caller {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.uid {
+            Some(uid) => write!(f, "uid {} ({})", uid, self.name),
+            None => write!(f, "{}", self.name),
+        }
+    }
+}
+
+/// Lets polkit ask the user for a password when the policy needs one.
+const ALLOW_USER_INTERACTION: u32 = 1;
+
+/// Checks callers before mutating methods run.
+pub struct Authorizer {
+    connection: Connection,
+    enabled: bool,
+    kiosk: bool,
+    authority: Option<AuthorityProxy<'static>>,
+}
+
+impl Authorizer {
+    /// Creates an authorizer for calls arriving on `connection`. polkit is
+    /// only consulted when `enabled`; with `kiosk` only root may make
+    /// changes.
+    pub async fn new(connection: &Connection, enabled: bool, kiosk: bool) -> Self {
+        let mut authority = None;
+        if enabled {
+            match AuthorityProxy::builder(connection)
+                .cache_properties(CacheProperties::No)
+                .build()
+                .await
+            {
+                Ok(proxy) => {
+                    info!("Checking D-Bus callers with polkit");
+                    authority = Some(proxy);
+                }
+                Err(e) => warn!("polkit unavailable, only root may make changes: {}", e),
+            }
+        }
+        Self {
+            connection: connection.clone(),
+            enabled,
+            kiosk,
+            authority,
+        }
+    }
+
+    /// Returns the Unix user ID of the caller of a method call.
+    pub async fn caller_uid(&self, header: &Header<'_>) -> zbus::fdo::Result<u32> {
+        let sender = header
+            .sender()
+            .ok_or_else(|| zbus::fdo::Error::AccessDenied("Unknown caller".to_string()))?;
+        zbus::fdo::DBusProxy::new(&self.connection)
+            .await?
+            .get_connection_unix_user(BusName::Unique(sender.clone()))
+            .await
+    }
+
+    /// Returns the caller of the method call in `header`, or an error unless
+    /// they hold `privilege`.
+    pub async fn check(
+        &self,
+        header: &Header<'_>,
+        privilege: Privilege,
+    ) -> zbus::fdo::Result<Caller> {
+        let name = header.sender().map(|s| s.to_string()).unwrap_or_default();
+        if !self.enabled && !self.kiosk {
+            // Only needed for the audit log, so a failed lookup is no error
+            let uid = self.caller_uid(header).await.ok();
+            return Ok(Caller { name, uid });
+        }
+        let uid = self.caller_uid(header).await?;
+        let caller = Caller {
+            name,
+            uid: Some(uid),
+        };
+        if uid == 0 {
+            return Ok(caller);
+        }
+        if self.kiosk {
+            return Err(zbus::fdo::Error::AccessDenied(
+                "Panel is in kiosk mode; only root may make changes".to_string(),
+            ));
+        }
+        let Some(authority) = &self.authority else {
+            return Err(zbus::fdo::Error::AccessDenied(
+                "polkit is unavailable".to_string(),
+            ));
+        };
+
+        // Checked above by caller_uid
+        let sender = header.sender().unwrap();
+        let subject = (
+            "system-bus-name",
+            HashMap::from([("name", Value::from(sender.as_str()))]),
+        );
+        let (authorized, _, _) = authority
+            .check_authorization(
+                &subject,
+                privilege.action_id(),
+                HashMap::new(),
+                ALLOW_USER_INTERACTION,
+                "",
+            )
+            .await
+            .map_err(|e| zbus::fdo::Error::Failed(format!("polkit check failed: {}", e)))?;
+        let method = header.member().map(|m| m.as_str()).unwrap_or_default();
+        if !authorized {
+            debug!("polkit denied {} to {}", method, sender);
+            return Err(zbus::fdo::Error::AccessDenied(format!(
+                "Not authorized to call {} ({})",
+                method,
+                privilege.action_id()
+            )));
+        }
+        Ok(caller)
+    }
+}
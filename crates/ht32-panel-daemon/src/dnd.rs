@@ -0,0 +1,83 @@
+//! Do-not-disturb schedule.
+//!
+//! While do-not-disturb is on, the face keeps rendering but alert banners
+//! and LED pulses are held back. It is switched on by hand or during the
+//! configured daily windows, e.g. "22:00-07:00" for the night.
+
+use chrono::{DateTime, FixedOffset, Timelike};
+use tracing::warn;
+
+use crate::night_light::parse_time;
+
+const MINUTES_PER_DAY: f32 = 1440.0;
+
+/// Daily windows in which do-not-disturb is on.
+#[derive(Debug, Default)]
+pub struct DndSchedule {
+    /// Start and end of each window, in minutes after midnight
+    windows: Vec<(f32, f32)>,
+}
+
+impl DndSchedule {
+    /// Parses windows written as "HH:MM-HH:MM". Invalid windows are
+    /// skipped with a warning.
+    pub fn from_config(windows: &[String]) -> Self {
+        let windows = windows
+            .iter()
+            .filter_map(|window| {
+                let parsed = parse_window(window);
+                if parsed.is_none() {
+                    warn!(
+                        "Ignoring do-not-disturb window '{}' (expected HH:MM-HH:MM)",
+                        window
+                    );
+                }
+                parsed
+            })
+            .collect();
+        Self { windows }
+    }
+
+    /// Returns whether local time `now` falls in a window.
+    pub fn is_active(&self, now: DateTime<FixedOffset>) -> bool {
+        let minute = now.hour() as f32 * 60.0 + now.minute() as f32;
+        self.windows
+            .iter()
+            .any(|&(start, end)| in_window(minute, start, end))
+    }
+}
+
+/// Parses "HH:MM-HH:MM" into start and end minutes.
+pub fn parse_window(window: &str) -> Option<(f32, f32)> {
+    let (start, end) = window.split_once('-')?;
+    Some((parse_time(start)?, parse_time(end)?))
+}
+
+/// Whether `minute` is in the window from `start` to `end`, which wraps
+/// past midnight when the end is earlier than the start.
+fn in_window(minute: f32, start: f32, end: f32) -> bool {
+    let length = (end - start).rem_euclid(MINUTES_PER_DAY);
+    (minute - start).rem_euclid(MINUTES_PER_DAY) < length
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_windows() {
+        let night = parse_window("22:00-07:00").unwrap();
+        assert_eq!(night, (1320.0, 420.0));
+        assert!(in_window(23.0 * 60.0, night.0, night.1));
+        assert!(in_window(3.0 * 60.0, night.0, night.1));
+        assert!(!in_window(7.0 * 60.0, night.0, night.1));
+        assert!(!in_window(12.0 * 60.0, night.0, night.1));
+
+        let lunch = parse_window("12:00 - 13:00").unwrap();
+        assert!(in_window(12.0 * 60.0 + 30.0, lunch.0, lunch.1));
+        assert!(!in_window(13.0 * 60.0, lunch.0, lunch.1));
+
+        assert!(parse_window("22:00").is_none());
+        assert!(parse_window("25:00-07:00").is_none());
+    }
+}
@@ -0,0 +1,190 @@
+//! Typed in-daemon event bus.
+//!
+//! Subsystems publish what happened on one broadcast channel instead of
+//! notifying each other directly: the D-Bus and web frontends announce
+//! setting changes, the render loop reports alerts, face changes and the
+//! LCD disconnecting or reconnecting, and the journal watcher forwards
+//! warnings. D-Bus signals, the web UI's live updates, webhooks and the
+//! timeline are all subscribers.
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use tokio::sync::broadcast;
+use tracing::warn;
+
+use crate::sensors::data::{EventKind, TimelineEvent};
+
+/// Events buffered for subscribers that fall behind.
+const CHANNEL_CAPACITY: usize = 64;
+
+/// Number of events kept for the timeline.
+const TIMELINE_CAPACITY: usize = 50;
+
+/// Something that happened in the daemon.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Event {
+    /// Orientation was changed.
+    OrientationChanged,
+    /// LED settings changed.
+    LedChanged,
+    /// Display settings (theme, face, etc.) changed.
+    DisplaySettingsChanged,
+    /// Complication option changed.
+    ComplicationOptionChanged,
+    /// A panel button sent the given report code.
+    ButtonPressed(String),
+    /// A metric, storage pool or job entered an alert state.
+    Alert(String),
+    /// The display face changed to the named face.
+    FaceChanged(String),
+    /// The LCD stopped accepting frames, with the error.
+    DeviceDisconnected(String),
+    /// The LCD accepts frames again.
+    DeviceReconnected,
+    /// A warning or error was logged to the system journal.
+    Journal(String),
+}
+
+impl Event {
+    /// Returns the kind and message of events shown on the timeline and
+    /// sent to webhooks.
+    pub fn notice(&self) -> Option<(EventKind, String)> {
+        match self {
+            Event::Alert(message) => Some((EventKind::Alert, message.clone())),
+            Event::FaceChanged(face) => {
+                Some((EventKind::FaceChanged, format!("Face changed to {}", face)))
+            }
+            Event::DeviceDisconnected(error) => Some((
+                EventKind::DeviceDisconnected,
+                format!("LCD stopped responding: {}", error),
+            )),
+            Event::DeviceReconnected => Some((
+                EventKind::DeviceReconnected,
+                "LCD is responding again".to_string(),
+            )),
+            Event::Journal(message) => Some((EventKind::Journal, message.clone())),
+            _ => None,
+        }
+    }
+}
+
+/// Sending side of the event bus. Cloning it is cheap.
+#[derive(Clone, Debug)]
+pub struct EventBus {
+    sender: broadcast::Sender<Event>,
+}
+
+impl EventBus {
+    /// Creates a bus without subscribers.
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(CHANNEL_CAPACITY);
+        Self { sender }
+    }
+
+    /// Publishes `event` to every current subscriber.
+    pub fn publish(&self, event: Event) {
+        // Sending only fails when nobody is subscribed
+        let _ = self.sender.send(event);
+    }
+
+    /// Subscribes to events published from now on.
+    pub fn subscribe(&self) -> broadcast::Receiver<Event> {
+        self.sender.subscribe()
+    }
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Receives the next event, skipping over any missed by a lagging
+/// subscriber. Returns None once the bus is gone.
+pub async fn next(receiver: &mut broadcast::Receiver<Event>, subscriber: &str) -> Option<Event> {
+    loop {
+        match receiver.recv().await {
+            Ok(event) => return Some(event),
+            Err(broadcast::error::RecvError::Lagged(missed)) => {
+                warn!("{} missed {} events", subscriber, missed);
+            }
+            Err(broadcast::error::RecvError::Closed) => return None,
+        }
+    }
+}
+
+/// Recent notable events, shown on the timeline face.
+#[derive(Debug, Default)]
+pub struct Timeline {
+    /// Oldest first
+    events: Mutex<VecDeque<TimelineEvent>>,
+}
+
+impl Timeline {
+    /// Creates an empty timeline.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records the notable events from `receiver` until the bus is gone.
+    pub async fn record(self: Arc<Self>, mut receiver: broadcast::Receiver<Event>) {
+        while let Some(event) = next(&mut receiver, "Timeline").await {
+            if let Some((kind, message)) = event.notice() {
+                self.push(kind, message);
+            }
+        }
+    }
+
+    /// Adds an event stamped with the current time.
+    fn push(&self, kind: EventKind, message: String) {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        let mut events = self.events.lock().unwrap();
+        if events.len() == TIMELINE_CAPACITY {
+            events.pop_front();
+        }
+        events.push_back(TimelineEvent {
+            timestamp,
+            kind,
+            message,
+        });
+    }
+
+    /// Returns the kept events, newest first.
+    pub fn recent(&self) -> Vec<TimelineEvent> {
+        self.events.lock().unwrap().iter().rev().cloned().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_timeline_records_notices() {
+        let bus = EventBus::new();
+        let timeline = Arc::new(Timeline::new());
+        let recorder = tokio::spawn(timeline.clone().record(bus.subscribe()));
+
+        bus.publish(Event::LedChanged);
+        for i in 0..TIMELINE_CAPACITY + 5 {
+            bus.publish(Event::FaceChanged(i.to_string()));
+            // Let the recorder keep up with the bounded channel
+            tokio::task::yield_now().await;
+        }
+        drop(bus);
+        recorder.await.unwrap();
+
+        let recent = timeline.recent();
+        assert_eq!(recent.len(), TIMELINE_CAPACITY);
+        assert_eq!(
+            recent[0].message,
+            format!("Face changed to {}", TIMELINE_CAPACITY + 4)
+        );
+        assert_eq!(recent[TIMELINE_CAPACITY - 1].message, "Face changed to 5");
+    }
+}
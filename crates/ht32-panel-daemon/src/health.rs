@@ -0,0 +1,118 @@
+//! Daemon health.
+//!
+//! `GetHealth` and `/healthz` report how long the daemon has been running,
+//! how many frames it has rendered and how many USB writes and sensor reads
+//! failed, along with its memory use and the optional features it was
+//! built with. The status is "degraded" while any problem is listed, such as
+//! an LCD that stopped responding, and "testing" while a test pattern
+//! replaces the face. Only "degraded" makes `/healthz` answer 503.
+
+use serde::Serialize;
+
+/// Health of the running daemon.
+#[derive(Debug, Clone, Serialize)]
+pub struct Health {
+    /// "ok", "testing" during a test pattern, or "degraded" when there
+    /// are problems
+    pub status: &'static str,
+    /// Descriptions of current problems
+    pub problems: Vec<String>,
+    pub version: &'static str,
+    /// Optional features built in, from [`capabilities`]
+    pub capabilities: Vec<&'static str>,
+    pub uptime_secs: u64,
+    /// Frames sent to the display since startup
+    pub frames_rendered: u64,
+    /// Seconds since the last complete frame, if any
+    pub last_frame_secs: Option<f64>,
+    /// Failed LCD redraws and heartbeats
+    pub usb_errors: u64,
+    /// Local sensor reads that stalled or panicked
+    pub sensor_failures: u64,
+    /// Resident memory of the daemon, where available
+    pub memory_bytes: Option<u64>,
+    pub lcd_connected: bool,
+    pub lcd_responding: bool,
+}
+
+impl Health {
+    /// Returns true when there are no problems.
+    #[cfg(feature = "web")]
+    pub fn is_ok(&self) -> bool {
+        self.problems.is_empty()
+    }
+}
+
+/// Returns the status for a list of problems, and whether a test pattern
+/// is showing.
+pub fn status(problems: &[String], testing: bool) -> &'static str {
+    if !problems.is_empty() {
+        "degraded"
+    } else if testing {
+        "testing"
+    } else {
+        "ok"
+    }
+}
+
+/// Optional cargo features and whether this build has them.
+const FEATURES: [(&str, bool); 10] = [
+    ("web", cfg!(feature = "web")),
+    ("audio", cfg!(feature = "audio")),
+    ("hotkeys", cfg!(feature = "hotkeys")),
+    ("hidraw", cfg!(feature = "hidraw")),
+    ("scripting", cfg!(feature = "scripting")),
+    ("webhooks", cfg!(feature = "webhooks")),
+    ("scrapers", cfg!(feature = "scrapers")),
+    ("snmp", cfg!(feature = "snmp")),
+    ("speedtest", cfg!(feature = "speedtest")),
+    ("torrent", cfg!(feature = "torrent")),
+];
+
+/// Returns the optional features this daemon was built with, such as "web"
+/// for the web UI. Faces and settings needing other features are missing.
+pub fn capabilities() -> Vec<&'static str> {
+    FEATURES
+        .iter()
+        .filter(|(_, built)| *built)
+        .map(|(name, _)| *name)
+        .collect()
+}
+
+/// Returns the resident memory of this process in bytes.
+pub fn resident_memory() -> Option<u64> {
+    parse_vm_rss(&std::fs::read_to_string("/proc/self/status").ok()?)
+}
+
+/// Parses the `VmRSS` line of `/proc/<pid>/status`.
+fn parse_vm_rss(status: &str) -> Option<u64> {
+    let line = status.lines().find(|l| l.starts_with("VmRSS:"))?;
+    let kb: u64 = line
+        .trim_start_matches("VmRSS:")
+        .trim()
+        .trim_end_matches("kB")
+        .trim()
+        .parse()
+        .ok()?;
+    Some(kb * 1024)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_vm_rss() {
+        let proc_status =
+            "Name:\tht32paneld\nVmPeak:\t  812344 kB\nVmRSS:\t   24576 kB\nThreads:\t9\n";
+        assert_eq!(parse_vm_rss(proc_status), Some(24576 * 1024));
+        assert_eq!(parse_vm_rss("Name:\tkthreadd\n"), None);
+        assert_eq!(status(&[], false), "ok");
+        assert_eq!(status(&[], true), "testing");
+        let problems = ["LCD is not responding".to_string()];
+        assert_eq!(status(&problems, false), "degraded");
+        assert_eq!(status(&problems, true), "degraded");
+        assert_eq!(capabilities().contains(&"audio"), cfg!(feature = "audio"));
+        assert_eq!(capabilities().contains(&"snmp"), cfg!(feature = "snmp"));
+    }
+}
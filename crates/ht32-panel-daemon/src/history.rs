@@ -0,0 +1,194 @@
+//! Undo and redo of display settings changes.
+//!
+//! Each time the display settings are saved, the settings they replace are
+//! pushed on the undo stack, whatever made the change (D-Bus, the web UI,
+//! buttons or a schedule). Undoing restores them through the usual setters
+//! while recording is paused.
+
+use std::collections::VecDeque;
+
+use crate::state::DisplaySettings;
+
+/// Number of changes that can be undone.
+const MAX_UNDO: usize = 50;
+
+/// Bounded undo and redo stacks of display settings.
+pub struct SettingsHistory {
+    current: DisplaySettings,
+    undo: VecDeque<DisplaySettings>,
+    redo: Vec<DisplaySettings>,
+    paused: bool,
+}
+
+impl SettingsHistory {
+    /// Creates an empty history starting from `current`.
+    pub fn new(current: &DisplaySettings) -> Self {
+        Self {
+            current: comparable(current),
+            undo: VecDeque::new(),
+            redo: Vec::new(),
+            paused: false,
+        }
+    }
+
+    /// Returns the settings the next change will be recorded against.
+    pub fn current(&self) -> &DisplaySettings {
+        &self.current
+    }
+
+    /// Records that the settings are now `settings`. The previous settings
+    /// become undoable and anything undone can no longer be redone.
+    pub fn record(&mut self, settings: &DisplaySettings) {
+        let settings = comparable(settings);
+        if self.paused || settings == self.current {
+            return;
+        }
+        let previous = std::mem::replace(&mut self.current, settings);
+        self.push_undo(previous);
+        self.redo.clear();
+    }
+
+    /// Starts undoing the last change, returning the settings to restore.
+    /// Recording is paused until [`SettingsHistory::finish`].
+    pub fn begin_undo(&mut self) -> Option<DisplaySettings> {
+        let target = self.undo.pop_back()?;
+        self.redo
+            .push(std::mem::replace(&mut self.current, target.clone()));
+        self.paused = true;
+        Some(target)
+    }
+
+    /// Starts redoing the last undone change, returning the settings to
+    /// restore. Recording is paused until [`SettingsHistory::finish`].
+    pub fn begin_redo(&mut self) -> Option<DisplaySettings> {
+        let target = self.redo.pop()?;
+        let previous = std::mem::replace(&mut self.current, target.clone());
+        self.push_undo(previous);
+        self.paused = true;
+        Some(target)
+    }
+
+    /// Resumes recording once restored settings were applied as `applied`.
+    pub fn finish(&mut self, applied: &DisplaySettings) {
+        self.current = comparable(applied);
+        self.paused = false;
+    }
+
+    /// Returns the number of changes that can be undone and redone.
+    pub fn depth(&self) -> (usize, usize) {
+        (self.undo.len(), self.redo.len())
+    }
+
+    fn push_undo(&mut self, settings: DisplaySettings) {
+        if self.undo.len() == MAX_UNDO {
+            self.undo.pop_front();
+        }
+        self.undo.push_back(settings);
+    }
+}
+
+/// Drops bookkeeping fields that are not settings, so saving them alone is
+/// not a change and restoring never rewinds them.
+fn comparable(settings: &DisplaySettings) -> DisplaySettings {
+    DisplaySettings {
+        last_random: None,
+        network_interface: None,
+        ip_display: None,
+        ..settings.clone()
+    }
+}
+
+/// Lists the settings that differ between `from` and `to`, e.g.
+/// "theme, LEDs".
+pub fn describe_changes(from: &DisplaySettings, to: &DisplaySettings) -> String {
+    let mut changed = Vec::new();
+    let mut check = |differs: bool, name: &'static str| {
+        if differs {
+            changed.push(name);
+        }
+    };
+    check(from.face != to.face, "face");
+    check(from.orientation != to.orientation, "orientation");
+    check(from.theme != to.theme, "theme");
+    check(from.custom_themes != to.custom_themes, "saved themes");
+    check(
+        (from.led_theme, from.led_intensity, from.led_speed)
+            != (to.led_theme, to.led_intensity, to.led_speed),
+        "LEDs",
+    );
+    check(
+        from.led_follow_theme != to.led_follow_theme,
+        "LED theme following",
+    );
+    check(
+        from.refresh_interval != to.refresh_interval,
+        "refresh interval",
+    );
+    check(from.complications != to.complications, "complications");
+    check(from.display_label != to.display_label, "label");
+    check(from.timezone != to.timezone, "timezone");
+    check(
+        (from.binary_units, from.network_bits) != (to.binary_units, to.network_bits),
+        "rate units",
+    );
+    check(from.daily_random != to.daily_random, "daily random face");
+    check(from.disk_devices != to.disk_devices, "disk devices");
+    check(from.brightness != to.brightness, "brightness");
+    check(from.post_effects != to.post_effects, "effects");
+    check(
+        (from.flip_h, from.flip_v) != (to.flip_h, to.flip_v),
+        "mirroring",
+    );
+    check(from.visible_area != to.visible_area, "visible area");
+    check(
+        from.background_image != to.background_image,
+        "background image",
+    );
+    check(from.overlays != to.overlays, "overlays");
+    check(from.rules != to.rules, "rules");
+    check(from.sensors != to.sensors, "sensors");
+    check(
+        from.temperature_sensor != to.temperature_sensor,
+        "temperature sensor",
+    );
+    check(from.dnd != to.dnd, "do-not-disturb");
+    if changed.is_empty() {
+        "nothing".to_string()
+    } else {
+        changed.join(", ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_undo_redo() {
+        let initial = DisplaySettings::default();
+        let mut history = SettingsHistory::new(&initial);
+        assert!(history.begin_undo().is_none());
+
+        let themed = DisplaySettings {
+            theme: "nord".to_string(),
+            ..initial.clone()
+        };
+        history.record(&themed);
+        // Saving the same settings again is not a change
+        history.record(&DisplaySettings {
+            last_random: Some("2024-01-01".to_string()),
+            ..themed.clone()
+        });
+        assert_eq!(history.depth(), (1, 0));
+
+        let restored = history.begin_undo().unwrap();
+        assert_eq!(restored.theme, initial.theme);
+        assert_eq!(describe_changes(&themed, &restored), "theme");
+        history.finish(&restored);
+        assert_eq!(history.depth(), (0, 1));
+
+        assert_eq!(history.begin_redo().unwrap().theme, "nord");
+        history.finish(&themed);
+        assert_eq!(history.depth(), (1, 0));
+    }
+}
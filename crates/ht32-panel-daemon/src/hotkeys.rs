@@ -0,0 +1,287 @@
+//! Keyboard hotkeys from evdev input devices.
+//!
+//! Reads key events from `/dev/input/event*` (the daemon user needs to be in
+//! the `input` group) and runs the action bound to each key combination, so
+//! a keyboard attached to a headless box can cycle faces or dim the panel.
+//! Devices are not grabbed; keys still reach other programs. Keyboards
+//! plugged in later are picked up by a periodic rescan.
+
+use std::collections::HashSet;
+use std::fs::{self, File};
+use std::io::Read;
+use std::mem::size_of;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use tokio::runtime::Handle;
+use tracing::{debug, info, warn};
+
+use crate::actions;
+use crate::config::HotkeysConfig;
+use crate::events::EventBus;
+use crate::state::AppState;
+
+/// Event type of key presses and releases.
+const EV_KEY: u16 = 1;
+
+/// How often to look for newly attached keyboards.
+const RESCAN_INTERVAL: Duration = Duration::from_secs(5);
+
+const MOD_CTRL: u8 = 1;
+const MOD_ALT: u8 = 2;
+const MOD_SHIFT: u8 = 4;
+const MOD_META: u8 = 8;
+
+/// A key combination bound to an action.
+struct Binding {
+    modifiers: u8,
+    code: u16,
+    /// Action ID followed by its arguments
+    action: Vec<String>,
+}
+
+/// Everything a reader thread needs to run actions.
+struct Context {
+    bindings: Vec<Binding>,
+    state: Arc<AppState>,
+    events: EventBus,
+    runtime: Handle,
+    /// Devices currently being read
+    open: Mutex<HashSet<PathBuf>>,
+}
+
+/// Starts listening for the configured hotkeys. Must be called from within
+/// the tokio runtime, which runs the bound actions.
+pub fn start(config: &HotkeysConfig, state: Arc<AppState>, events: EventBus) {
+    let bindings: Vec<Binding> = config
+        .bindings
+        .iter()
+        .filter_map(|(combo, action)| match parse_combo(combo) {
+            Some((modifiers, code)) => Some(Binding {
+                modifiers,
+                code,
+                action: action.split_whitespace().map(String::from).collect(),
+            }),
+            None => {
+                warn!("Ignoring invalid hotkey: {}", combo);
+                None
+            }
+        })
+        .filter(|b| !b.action.is_empty())
+        .collect();
+    if bindings.is_empty() {
+        warn!("Hotkeys enabled but no valid bindings are configured");
+        return;
+    }
+
+    let context = Arc::new(Context {
+        bindings,
+        state,
+        events,
+        runtime: Handle::current(),
+        open: Mutex::new(HashSet::new()),
+    });
+    let devices: Vec<PathBuf> = config.devices.iter().map(PathBuf::from).collect();
+    let spawned = thread::Builder::new()
+        .name("hotkeys".to_string())
+        .spawn(move || loop {
+            let candidates = if devices.is_empty() {
+                find_keyboards()
+            } else {
+                devices.clone()
+            };
+            for path in candidates {
+                if context.open.lock().unwrap().insert(path.clone()) {
+                    let worker = context.clone();
+                    let spawned = thread::Builder::new()
+                        .name("hotkeys-device".to_string())
+                        .spawn(move || {
+                            read_device(&path, &worker);
+                            worker.open.lock().unwrap().remove(&path);
+                        });
+                    if let Err(e) = spawned {
+                        warn!("Failed to start hotkey reader: {}", e);
+                    }
+                }
+            }
+            thread::sleep(RESCAN_INTERVAL);
+        });
+    if let Err(e) = spawned {
+        warn!("Failed to start hotkey listener: {}", e);
+    }
+}
+
+/// Lists event devices that report letter keys.
+fn find_keyboards() -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir("/sys/class/input") else {
+        return Vec::new();
+    };
+    let mut keyboards: Vec<PathBuf> = entries
+        .flatten()
+        .filter(|e| e.file_name().to_string_lossy().starts_with("event"))
+        .filter(|e| {
+            // The key bitmap is space-separated words, most significant
+            // first; letter keys are in the last one
+            fs::read_to_string(e.path().join("device/capabilities/key"))
+                .ok()
+                .and_then(|bits| u64::from_str_radix(bits.split_whitespace().last()?, 16).ok())
+                .is_some_and(|low| low & (1 << 16) != 0 && low & (1 << 30) != 0)
+        })
+        .map(|e| Path::new("/dev/input").join(e.file_name()))
+        .collect();
+    keyboards.sort();
+    keyboards
+}
+
+/// Reads key events from one device until it fails or disappears.
+fn read_device(path: &Path, context: &Context) {
+    let mut file = match File::open(path) {
+        Ok(file) => file,
+        Err(e) => {
+            debug!("Cannot open {}: {}", path.display(), e);
+            return;
+        }
+    };
+    info!("Listening for hotkeys on {}", path.display());
+
+    // struct input_event is a timeval followed by type, code and value
+    let header = size_of::<libc::timeval>();
+    let mut event = vec![0u8; size_of::<libc::input_event>()];
+    let mut modifiers = 0u8;
+    while file.read_exact(&mut event).is_ok() {
+        let kind = u16::from_ne_bytes([event[header], event[header + 1]]);
+        let code = u16::from_ne_bytes([event[header + 2], event[header + 3]]);
+        let value = i32::from_ne_bytes([
+            event[header + 4],
+            event[header + 5],
+            event[header + 6],
+            event[header + 7],
+        ]);
+        if kind != EV_KEY {
+            continue;
+        }
+
+        // Value 1 is a press, 0 a release and 2 an autorepeat
+        if let Some(bit) = modifier_bit(code) {
+            match value {
+                1 => modifiers |= bit,
+                0 => modifiers &= !bit,
+                _ => {}
+            }
+            continue;
+        }
+        if value != 1 {
+            continue;
+        }
+        if let Some(binding) = context
+            .bindings
+            .iter()
+            .find(|b| b.code == code && b.modifiers == modifiers)
+        {
+            actions::spawn(
+                &context.runtime,
+                &context.state,
+                &context.events,
+                &binding.action,
+                "Hotkey",
+            );
+        }
+    }
+    info!("Stopped reading hotkeys from {}", path.display());
+}
+
+fn modifier_bit(code: u16) -> Option<u8> {
+    match code {
+        29 | 97 => Some(MOD_CTRL),
+        56 | 100 => Some(MOD_ALT),
+        42 | 54 => Some(MOD_SHIFT),
+        125 | 126 => Some(MOD_META),
+        _ => None,
+    }
+}
+
+/// Parses a combination like "ctrl+alt+f" into modifier bits and a key code.
+fn parse_combo(combo: &str) -> Option<(u8, u16)> {
+    let mut modifiers = 0;
+    let mut code = None;
+    for part in combo.split('+').map(|p| p.trim().to_lowercase()) {
+        match part.as_str() {
+            "ctrl" | "control" => modifiers |= MOD_CTRL,
+            "alt" => modifiers |= MOD_ALT,
+            "shift" => modifiers |= MOD_SHIFT,
+            "meta" | "super" | "win" => modifiers |= MOD_META,
+            key if code.is_none() => code = Some(key_code(key)?),
+            _ => return None,
+        }
+    }
+    Some((modifiers, code?))
+}
+
+/// Maps a key name (with or without a `key_` prefix) to its Linux key code.
+fn key_code(name: &str) -> Option<u16> {
+    let name = name.strip_prefix("key_").unwrap_or(name);
+    let rows: [(&str, u16); 4] = [
+        ("1234567890", 2),
+        ("qwertyuiop", 16),
+        ("asdfghjkl", 30),
+        ("zxcvbnm", 44),
+    ];
+    let mut chars = name.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        return rows
+            .iter()
+            .find_map(|(row, first)| row.find(c).map(|i| first + i as u16));
+    }
+    if let Some(n) = name.strip_prefix('f').and_then(|n| n.parse::<u16>().ok()) {
+        return match n {
+            1..=10 => Some(58 + n),
+            11 | 12 => Some(76 + n),
+            _ => None,
+        };
+    }
+    let code = match name {
+        "esc" | "escape" => 1,
+        "minus" => 12,
+        "equal" => 13,
+        "backspace" => 14,
+        "tab" => 15,
+        "enter" | "return" => 28,
+        "space" => 57,
+        "home" => 102,
+        "up" => 103,
+        "pageup" => 104,
+        "left" => 105,
+        "right" => 106,
+        "end" => 107,
+        "down" => 108,
+        "pagedown" => 109,
+        "insert" => 110,
+        "delete" => 111,
+        "mute" => 113,
+        "volumedown" => 114,
+        "volumeup" => 115,
+        "pause" => 119,
+        "nextsong" => 163,
+        "playpause" => 164,
+        "previoussong" => 165,
+        _ => return None,
+    };
+    Some(code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_combo() {
+        assert_eq!(parse_combo("ctrl+alt+f"), Some((MOD_CTRL | MOD_ALT, 33)));
+        assert_eq!(parse_combo("Super + KEY_F12"), Some((MOD_META, 88)));
+        assert_eq!(parse_combo("shift+up"), Some((MOD_SHIFT, 103)));
+        assert_eq!(parse_combo("0"), Some((0, 11)));
+        assert_eq!(parse_combo("ctrl+a+b"), None);
+        assert_eq!(parse_combo("ctrl"), None);
+    }
+}
@@ -0,0 +1,205 @@
+//! Logging setup and runtime log levels.
+//!
+//! Events go to the journal, with their fields as journal fields, when the
+//! daemon runs under systemd, and to stderr otherwise. The most recent
+//! events are also kept in memory for `GetRecentLogs`. `SetLogLevel`
+//! changes the level of a target, such as `ht32_panel_hw` when diagnosing
+//! USB issues, without a restart.
+
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt;
+use std::sync::{Arc, Mutex, OnceLock};
+
+use anyhow::{anyhow, bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use tracing::field::{Field, Visit};
+use tracing::{Event, Subscriber};
+use tracing_subscriber::filter::{Directive, EnvFilter, LevelFilter};
+use tracing_subscriber::layer::{Context as LayerContext, Layer, SubscriberExt};
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{reload, Registry};
+
+/// Number of events kept in memory.
+const MAX_RECENT: usize = 500;
+
+/// A logged event.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LogEntry {
+    /// When the event was logged (RFC 3339)
+    pub timestamp: String,
+    /// "ERROR", "WARN", "INFO", "DEBUG" or "TRACE"
+    pub level: String,
+    /// Module path the event came from
+    pub target: String,
+    pub message: String,
+    /// Other fields of the event
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub fields: BTreeMap<String, String>,
+}
+
+/// Layer keeping the most recent events.
+#[derive(Clone, Default)]
+struct RecentLogs(Arc<Mutex<VecDeque<LogEntry>>>);
+
+impl RecentLogs {
+    fn push(&self, entry: LogEntry) {
+        let mut recent = self.0.lock().unwrap();
+        if recent.len() == MAX_RECENT {
+            recent.pop_front();
+        }
+        recent.push_back(entry);
+    }
+}
+
+impl<S: Subscriber> Layer<S> for RecentLogs {
+    fn on_event(&self, event: &Event<'_>, _ctx: LayerContext<'_, S>) {
+        let mut fields = FieldVisitor::default();
+        event.record(&mut fields);
+        let metadata = event.metadata();
+        self.push(LogEntry {
+            timestamp: chrono::Local::now().to_rfc3339(),
+            level: metadata.level().to_string(),
+            target: metadata.target().to_string(),
+            message: fields.message,
+            fields: fields.fields,
+        });
+    }
+}
+
+/// Collects the message and other fields of an event.
+#[derive(Default)]
+struct FieldVisitor {
+    message: String,
+    fields: BTreeMap<String, String>,
+}
+
+impl Visit for FieldVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message = value.to_string();
+        } else {
+            self.fields
+                .insert(field.name().to_string(), value.to_string());
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name() == "message" {
+            self.message = format!("{:?}", value);
+        } else {
+            self.fields
+                .insert(field.name().to_string(), format!("{:?}", value));
+        }
+    }
+}
+
+/// Logging state shared with the D-Bus methods.
+struct Logging {
+    filter: reload::Handle<EnvFilter, Registry>,
+    /// Levels set with `SetLogLevel`, by target ("" for all targets)
+    levels: Mutex<BTreeMap<String, LevelFilter>>,
+    recent: RecentLogs,
+}
+
+static LOGGING: OnceLock<Logging> = OnceLock::new();
+
+/// Builds the filter from `RUST_LOG`, defaulting to info, with `levels`
+/// applied on top.
+fn build_filter(levels: &BTreeMap<String, LevelFilter>) -> Result<EnvFilter> {
+    let mut filter = EnvFilter::from_default_env().add_directive("info".parse()?);
+    for (target, level) in levels {
+        let directive: Directive = if target.is_empty() {
+            level.to_string().parse()?
+        } else {
+            format!("{}={}", target, level).parse()?
+        };
+        filter = filter.add_directive(directive);
+    }
+    Ok(filter)
+}
+
+/// Installs the global subscriber.
+pub fn init() -> Result<()> {
+    let (filter, handle) = reload::Layer::new(build_filter(&BTreeMap::new())?);
+    let recent = RecentLogs::default();
+
+    // systemd sets JOURNAL_STREAM when stderr is connected to the journal
+    let journald = std::env::var_os("JOURNAL_STREAM").and_then(|_| tracing_journald::layer().ok());
+    let stderr = journald.is_none().then(tracing_subscriber::fmt::layer);
+
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(recent.clone())
+        .with(journald)
+        .with(stderr)
+        .try_init()
+        .context("Failed to set up logging")?;
+
+    let _ = LOGGING.set(Logging {
+        filter: handle,
+        levels: Mutex::new(BTreeMap::new()),
+        recent,
+    });
+    Ok(())
+}
+
+/// Sets the level of `target` ("" for all targets) to "off", "error",
+/// "warn", "info", "debug" or "trace". "reset" removes a level set before.
+pub fn set_level(target: &str, level: &str) -> Result<()> {
+    let target = target.trim();
+    if target.contains(|c: char| c.is_whitespace() || c == '=' || c == ',') {
+        bail!("Invalid log target: {}", target);
+    }
+    let logging = LOGGING.get().context("Logging is not set up")?;
+    let mut levels = logging.levels.lock().unwrap();
+    if level.eq_ignore_ascii_case("reset") {
+        levels.remove(target);
+    } else {
+        let level: LevelFilter = level.parse().map_err(|_| {
+            anyhow!(
+                "Invalid log level: {} (expected off, error, warn, info, debug, trace or reset)",
+                level
+            )
+        })?;
+        levels.insert(target.to_string(), level);
+    }
+    logging.filter.reload(build_filter(&levels)?)?;
+    Ok(())
+}
+
+/// Returns up to `count` of the most recent events, oldest first.
+pub fn recent(count: usize) -> Vec<LogEntry> {
+    let Some(logging) = LOGGING.get() else {
+        return Vec::new();
+    };
+    let recent = logging.recent.0.lock().unwrap();
+    recent
+        .iter()
+        .skip(recent.len().saturating_sub(count))
+        .cloned()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recent_logs_layer() {
+        let recent = RecentLogs::default();
+        let subscriber = tracing_subscriber::registry().with(recent.clone());
+        tracing::subscriber::with_default(subscriber, || {
+            for _ in 0..MAX_RECENT {
+                tracing::info!("filler");
+            }
+            tracing::warn!(device = "lcd", "Write failed: {}", 5);
+        });
+        let entries = recent.0.lock().unwrap();
+        assert_eq!(entries.len(), MAX_RECENT);
+        let last = entries.back().unwrap();
+        assert_eq!(last.level, "WARN");
+        assert_eq!(last.message, "Write failed: 5");
+        assert_eq!(last.fields["device"], "lcd");
+        assert!(last.target.ends_with("logging::tests"));
+    }
+}
@@ -2,46 +2,138 @@
 //!
 //! Background service with HTMX web UI and D-Bus interface for LCD and LED control.
 
+mod actions;
+mod audit;
+mod auto_orientation;
+mod buttons;
 mod config;
+mod config_check;
 mod dbus;
-mod faces;
-mod rendering;
+mod dnd;
+mod events;
+mod health;
+mod history;
+#[cfg(feature = "hotkeys")]
+mod hotkeys;
+mod logging;
+#[cfg(feature = "web")]
+mod mdns;
+mod mode;
+mod night_light;
+mod output;
+mod pacing;
+mod persist;
+mod render_stats;
+mod replay;
+mod rules;
 mod sensors;
 mod state;
+#[cfg(feature = "web")]
+mod streamdeck;
+mod uploads;
+#[cfg(feature = "web")]
 mod web;
+#[cfg(feature = "webhooks")]
+mod webhooks;
 
 use anyhow::{Context, Result};
-use std::net::SocketAddr;
+use clap::Parser;
+use std::path::PathBuf;
 use std::sync::Arc;
-use tokio::net::TcpListener;
-use tokio::sync::broadcast;
-use tracing::{info, warn};
-use tracing_subscriber::EnvFilter;
+use tracing::{error, info, warn};
 
 use config::Config;
-use dbus::DaemonSignals;
+use ht32_panel_render::{faces, rendering};
 use state::AppState;
 
+/// HT32 panel daemon
+#[derive(Parser)]
+#[command(name = "ht32paneld", version)]
+struct Args {
+    /// Configuration file [default: from --mode, else config/default.toml]
+    config: Option<String>,
+
+    /// Deploy as a per-user daemon or a system service
+    #[arg(long, value_enum)]
+    mode: Option<mode::Mode>,
+
+    /// Only accept changes from root over D-Bus
+    #[arg(long)]
+    kiosk: bool,
+
+    /// Record sensor data to FILE
+    #[arg(long, value_name = "FILE", conflicts_with = "replay")]
+    record: Option<PathBuf>,
+
+    /// Replay sensor data recorded with --record instead of reading sensors
+    #[arg(long, value_name = "FILE")]
+    replay: Option<PathBuf>,
+
+    /// Replay speed, e.g. 10x
+    #[arg(long, default_value = "1x", value_parser = replay::parse_speed, requires = "replay")]
+    speed: f64,
+
+    /// Check the configuration file, print any problems and exit
+    #[arg(long)]
+    check_config: bool,
+}
+
 #[tokio::main]
 async fn main() -> Result<()> {
-    // Setup logging
-    tracing_subscriber::fmt()
-        .with_env_filter(EnvFilter::from_default_env().add_directive("info".parse()?))
-        .init();
+    logging::init()?;
 
     // Load configuration
-    let config_path = std::env::args()
-        .nth(1)
-        .unwrap_or_else(|| "config/default.toml".to_string());
+    let args = Args::parse();
+    let config_path = match (args.config, args.mode) {
+        (Some(path), _) => path,
+        (None, Some(mode)) => mode.config_path().display().to_string(),
+        (None, None) => "config/default.toml".to_string(),
+    };
+    if args.check_config {
+        return check_config(&config_path);
+    }
 
-    let config = Config::load(&config_path).context("Failed to load configuration")?;
+    let mut config = Config::load(&config_path).context("Failed to load configuration")?;
     info!("Loaded configuration from: {}", config_path);
+    if let Some(mode) = args.mode {
+        mode.apply(&mut config);
+        mode.set_umask();
+    }
+    if args.kiosk {
+        config.kiosk = true;
+    }
+    if config.kiosk {
+        info!("Kiosk mode: changes are only accepted from root over D-Bus");
+    }
+
+    // Subscribe the timeline and webhooks to the event bus before anything
+    // publishes, so events from startup such as journal warnings reach them
+    let events = events::EventBus::new();
+    let timeline = Arc::new(events::Timeline::new());
+    tokio::spawn(timeline.clone().record(events.subscribe()));
+    #[cfg(feature = "webhooks")]
+    {
+        let hostname = sensors::SystemInfo::new().hostname();
+        let webhooks = webhooks::Webhooks::start(&config.webhooks, hostname);
+        tokio::spawn(webhooks.run(events.subscribe()));
+    }
+    #[cfg(not(feature = "webhooks"))]
+    if !config.webhooks.is_empty() {
+        warn!("Webhooks configured but the daemon was built without the webhooks feature");
+    }
 
     // Initialize application state
-    let state = Arc::new(AppState::new(config.clone())?);
+    let mut state = AppState::new(config.clone(), events.clone(), timeline)?;
+    if let Some(path) = args.record {
+        state.set_recorder(replay::Recorder::create(&path)?);
+    }
+    if let Some(path) = args.replay {
+        state.set_replay(replay::Replay::load(&path, args.speed)?);
+    }
+    let state = Arc::new(state);
+    let _save_traffic = SaveTrafficOnExit(state.clone());
 
-    // Create channels for D-Bus signals and shutdown
-    let (signal_tx, _signal_rx) = broadcast::channel::<DaemonSignals>(16);
+    // Create the shutdown channel
     let (shutdown_tx, mut shutdown_rx) = tokio::sync::mpsc::channel::<()>(1);
 
     // Keep a clone of shutdown_tx to prevent the channel from closing if D-Bus fails
@@ -49,27 +141,46 @@ async fn main() -> Result<()> {
 
     // Start D-Bus service
     let dbus_state = state.clone();
-    let dbus_signal_tx = signal_tx.clone();
+    let dbus_events = events.clone();
     let dbus_bus_type = config.dbus.bus;
-    let _dbus_connection =
-        match dbus::run_dbus_server(dbus_state, dbus_signal_tx, shutdown_tx, dbus_bus_type).await {
-            Ok(conn) => {
-                info!("D-Bus service started");
-                Some(conn)
-            }
-            Err(e) => {
-                warn!(
-                    "Failed to start D-Bus service: {}. Continuing without D-Bus.",
-                    e
-                );
-                None
-            }
-        };
+    let dbus_polkit = config.dbus.polkit;
+    let dbus_kiosk = config.kiosk;
+    let _dbus_connection = match dbus::run_dbus_server(
+        dbus_state,
+        dbus_events,
+        shutdown_tx,
+        dbus_bus_type,
+        dbus_polkit,
+        dbus_kiosk,
+    )
+    .await
+    {
+        Ok(conn) => {
+            info!("D-Bus service started");
+            Some(conn)
+        }
+        Err(e) => {
+            warn!(
+                "Failed to start D-Bus service: {}. Continuing without D-Bus.",
+                e
+            );
+            None
+        }
+    };
 
-    // Start render loop
+    // Start render loop, showing the boot splash first if enabled
     let render_state = state.clone();
+    let splash = config.splash.clone();
     tokio::spawn(async move {
-        render_loop(render_state).await;
+        if splash.enable {
+            match render_state.show_splash().await {
+                Ok(()) => {
+                    tokio::time::sleep(std::time::Duration::from_millis(splash.duration)).await;
+                }
+                Err(e) => warn!("Failed to show splash screen: {}", e),
+            }
+        }
+        render_supervisor(render_state).await;
     });
 
     // Start heartbeat loop
@@ -79,26 +190,41 @@ async fn main() -> Result<()> {
         heartbeat_loop(heartbeat_state, heartbeat_interval).await;
     });
 
+    // Flash the LEDs to the music if audio capture is enabled
+    #[cfg(feature = "audio")]
+    if config.sensors.audio.enable && config.sensors.audio.led_beat {
+        let beat_state = state.clone();
+        tokio::spawn(async move {
+            beat_state.led_beat_loop().await;
+        });
+    }
+
+    // Listen for keyboard hotkeys
+    if config.hotkeys.enable {
+        #[cfg(feature = "hotkeys")]
+        hotkeys::start(&config.hotkeys, state.clone(), events.clone());
+        #[cfg(not(feature = "hotkeys"))]
+        warn!("Hotkeys enabled but the daemon was built without the hotkeys feature");
+    }
+
+    // Read buttons on the panel
+    if config.buttons.enable {
+        buttons::start(&config.buttons, state.clone(), events.clone());
+    }
+
+    // Optionally serve the Stream Deck endpoint
+    if config.streamdeck.enable {
+        #[cfg(feature = "web")]
+        streamdeck::serve(&config.streamdeck, state.clone(), events.clone()).await?;
+        #[cfg(not(feature = "web"))]
+        warn!("Stream Deck endpoint enabled but the daemon was built without the web feature");
+    }
+
     // Setup Unix signal handlers
     let mut sigterm = tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())?;
     let mut sigint = tokio::signal::unix::signal(tokio::signal::unix::SignalKind::interrupt())?;
-
-    // Optionally start web server
-    if config.web.enable {
-        let app = web::create_router(state.clone(), signal_tx.clone());
-        let addr: SocketAddr = config
-            .web
-            .listen
-            .parse()
-            .context("Invalid listen address")?;
-        let listener = TcpListener::bind(addr).await?;
-        info!("Web server listening on http://{}", addr);
-
-        // Run server with shutdown handling
+    let shutdown = async {
         tokio::select! {
-            result = axum::serve(listener, app) => {
-                result?;
-            }
             _ = shutdown_rx.recv() => {
                 info!("Shutdown requested via D-Bus");
             }
@@ -109,28 +235,113 @@ async fn main() -> Result<()> {
                 info!("Received SIGINT, shutting down");
             }
         }
+    };
+
+    // Optionally start web server, then run until shut down
+    let result = if config.web.enable {
+        #[cfg(feature = "web")]
+        let result = web::serve(&config.web, state.clone(), events.clone(), shutdown).await;
+        #[cfg(not(feature = "web"))]
+        let result = {
+            warn!("Web UI enabled but the daemon was built without the web feature");
+            shutdown.await;
+            Ok(())
+        };
+        result
     } else {
         info!("Web server disabled");
-        // Wait for shutdown signal
-        tokio::select! {
-            _ = shutdown_rx.recv() => {
-                info!("Shutdown requested via D-Bus");
-            }
-            _ = sigterm.recv() => {
-                info!("Received SIGTERM, shutting down");
-            }
-            _ = sigint.recv() => {
-                info!("Received SIGINT, shutting down");
-            }
+        shutdown.await;
+        Ok(())
+    };
+
+    // Leave the panel on the shutdown screen even when the web server failed
+    if config.splash.shutdown_screen {
+        if let Err(e) = state.show_shutdown_screen() {
+            warn!("Failed to show shutdown screen: {}", e);
         }
     }
 
-    Ok(())
+    result
+}
+
+/// Saves the network traffic totals when dropped, so they are written on
+/// every exit path of `main`.
+struct SaveTrafficOnExit(Arc<AppState>);
+
+impl Drop for SaveTrafficOnExit {
+    fn drop(&mut self) {
+        self.0.save_traffic_totals();
+    }
+}
+
+/// Runs the render loop, restarting it with backoff if it panics.
+async fn render_supervisor(state: Arc<AppState>) {
+    let mut backoff = std::time::Duration::from_secs(5);
+
+    loop {
+        let started = std::time::Instant::now();
+        let result = tokio::spawn(render_loop(state.clone())).await;
+        let Err(e) = result else {
+            return;
+        };
+        if !e.is_panic() {
+            return;
+        }
+
+        let summary = panic_message(e.into_panic());
+        error!("Render loop panicked: {}", summary);
+
+        // Drawing the panic screen touches the same state that just panicked
+        let screen = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            state.show_panic_screen(&summary)
+        }));
+        match screen {
+            Ok(Ok(())) => {}
+            Ok(Err(e)) => warn!("Failed to show panic screen: {}", e),
+            Err(_) => warn!("Panic screen could not be drawn"),
+        }
+
+        // Reset backoff if the loop ran for a while before crashing
+        if started.elapsed() > std::time::Duration::from_secs(300) {
+            backoff = std::time::Duration::from_secs(5);
+        }
+        info!("Restarting render loop in {:?}", backoff);
+        tokio::time::sleep(backoff).await;
+        backoff = (backoff * 2).min(std::time::Duration::from_secs(60));
+    }
+}
+
+/// Extracts a readable message from a panic payload.
+fn panic_message(payload: Box<dyn std::any::Any + Send>) -> String {
+    if let Some(s) = payload.downcast_ref::<&str>() {
+        s.to_string()
+    } else if let Some(s) = payload.downcast_ref::<String>() {
+        s.clone()
+    } else {
+        "unknown panic".to_string()
+    }
+}
+
+/// Prints the problems found in the configuration file at `path`, failing
+/// if there are any.
+fn check_config(path: &str) -> Result<()> {
+    let content =
+        std::fs::read_to_string(path).with_context(|| format!("Failed to read {}", path))?;
+    let Err(problems) = config_check::check(&content) else {
+        println!("{}: OK", path);
+        return Ok(());
+    };
+    for problem in &problems {
+        println!("{}: {}", path, problem);
+    }
+    anyhow::bail!("{} problem(s) found in {}", problems.len(), path);
 }
 
 async fn render_loop(state: Arc<AppState>) {
     let mut consecutive_errors: u32 = 0;
     let mut last_error_log = std::time::Instant::now();
+    let refresh_interval = || std::time::Duration::from_millis(state.refresh_interval_ms() as u64);
+    let mut pacer = pacing::FramePacer::new(refresh_interval());
 
     loop {
         if let Err(e) = state.render_frame().await {
@@ -152,8 +363,7 @@ async fn render_loop(state: Arc<AppState>) {
         } else {
             consecutive_errors = 0;
         }
-        let ms = state.refresh_interval_ms();
-        tokio::time::sleep(std::time::Duration::from_millis(ms as u64)).await;
+        pacer.tick(refresh_interval()).await;
     }
 }
 
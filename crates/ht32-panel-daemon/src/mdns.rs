@@ -0,0 +1,73 @@
+//! mDNS advertisement of the web UI.
+//!
+//! Registers a `_ht32panel._tcp` service named after the host so clients
+//! such as `ht32panelctl discover` can find panels on the LAN.
+
+use std::net::SocketAddr;
+
+use anyhow::{Context, Result};
+use ht32_panel_types::SERVICE_TYPE;
+use mdns_sd::{ServiceDaemon, ServiceInfo};
+use tracing::{debug, info, warn};
+
+/// A running advertisement. Dropping it leaves the service registered until
+/// the process exits; call [`Advertisement::stop`] to withdraw it.
+pub struct Advertisement {
+    daemon: ServiceDaemon,
+    fullname: String,
+}
+
+impl Advertisement {
+    /// Advertises the web UI listening on `addr` under the name `hostname`.
+    /// An unspecified address advertises every interface's addresses and
+    /// follows them as they change.
+    pub fn start(addr: SocketAddr, hostname: &str) -> Result<Self> {
+        let daemon = ServiceDaemon::new().context("Failed to start mDNS responder")?;
+        let properties = [
+            ("version", env!("CARGO_PKG_VERSION")),
+            ("hostname", hostname),
+            ("path", "/"),
+        ];
+        let host = format!("{}.local.", hostname);
+        let service = if addr.ip().is_unspecified() {
+            ServiceInfo::new(
+                SERVICE_TYPE,
+                hostname,
+                &host,
+                "",
+                addr.port(),
+                &properties[..],
+            )
+            .map(ServiceInfo::enable_addr_auto)
+        } else {
+            ServiceInfo::new(
+                SERVICE_TYPE,
+                hostname,
+                &host,
+                addr.ip(),
+                addr.port(),
+                &properties[..],
+            )
+        }
+        .context("Invalid mDNS service")?;
+        let fullname = service.get_fullname().to_string();
+        daemon
+            .register(service)
+            .context("Failed to register mDNS service")?;
+        info!("Advertising web UI via mDNS as {}", fullname);
+        Ok(Self { daemon, fullname })
+    }
+
+    /// Withdraws the service and stops the responder.
+    pub fn stop(self) {
+        match self.daemon.unregister(&self.fullname) {
+            Ok(status) => {
+                // Wait briefly so the goodbye packets go out before exit
+                let _ = status.recv_timeout(std::time::Duration::from_secs(1));
+                debug!("Withdrew mDNS service {}", self.fullname);
+            }
+            Err(e) => warn!("Failed to withdraw mDNS service: {}", e),
+        }
+        let _ = self.daemon.shutdown();
+    }
+}
@@ -0,0 +1,105 @@
+//! Deployment mode.
+//!
+//! A user daemon runs in the desktop session: it reads its configuration
+//! from the user's config directory, registers on the session bus and keeps
+//! its files private. A system daemon runs as a service: it reads
+//! `/etc/ht32-panel/config.toml`, registers on the system bus and lets its
+//! group read its files.
+
+use std::path::PathBuf;
+
+use tracing::{info, warn};
+
+use crate::config::{default_state_dir, Config, DbusBusType};
+
+/// Where the daemon is deployed, set with `--mode`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Mode {
+    /// Per-user daemon on the session bus
+    User,
+    /// System service on the system bus
+    System,
+}
+
+impl Mode {
+    /// Configuration file used when none is given.
+    pub fn config_path(self) -> PathBuf {
+        match self {
+            // The working directory if neither XDG_CONFIG_HOME nor HOME is set
+            Mode::User => ht32_panel_client::config_home()
+                .unwrap_or_else(|| PathBuf::from("."))
+                .join("ht32-panel")
+                .join("config.toml"),
+            Mode::System => PathBuf::from("/etc/ht32-panel/config.toml"),
+        }
+    }
+
+    /// Bus to register on.
+    pub fn bus(self) -> DbusBusType {
+        match self {
+            Mode::User => DbusBusType::Session,
+            Mode::System => DbusBusType::System,
+        }
+    }
+
+    /// Sets the permissions of files the daemon creates: private to the
+    /// user, or readable by the service's group.
+    pub fn set_umask(self) {
+        let mask = match self {
+            Mode::User => 0o077,
+            Mode::System => 0o027,
+        };
+        // SAFETY: umask only changes this process's file creation mask
+        unsafe {
+            libc::umask(mask);
+        }
+    }
+
+    /// Applies the mode to a loaded configuration. Settings made in the
+    /// configuration file win over the mode's defaults.
+    pub fn apply(self, config: &mut Config) {
+        match config.dbus.bus {
+            DbusBusType::Auto => config.dbus.bus = self.bus(),
+            bus if bus != self.bus() => warn!(
+                "Using the {:?} bus from the configuration in {:?} mode",
+                bus, self
+            ),
+            _ => {}
+        }
+
+        // A state directory from systemd or the configuration is kept
+        if self == Mode::System
+            && std::env::var_os("STATE_DIRECTORY").is_none()
+            && config.state_dir == default_state_dir()
+        {
+            config.state_dir = "/var/lib/ht32-panel".to_string();
+        }
+
+        info!(
+            "Running in {:?} mode (bus {:?}, state in {})",
+            self, config.dbus.bus, config.state_dir
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mode_fills_in_unset_bus() {
+        let mut config = Config::default();
+        Mode::User.apply(&mut config);
+        assert_eq!(config.dbus.bus, DbusBusType::Session);
+
+        // An explicit bus is kept
+        config.dbus.bus = DbusBusType::System;
+        Mode::User.apply(&mut config);
+        assert_eq!(config.dbus.bus, DbusBusType::System);
+
+        assert_eq!(
+            Mode::System.config_path(),
+            PathBuf::from("/etc/ht32-panel/config.toml")
+        );
+    }
+}
@@ -0,0 +1,185 @@
+//! Night light schedule.
+//!
+//! Works out how strongly to warm the frame at a given time: zero during the
+//! day, ramping up to the configured strength over the transition period
+//! after the night starts and back down before it ends. The night runs
+//! between fixed times of day, or from sunset to sunrise when a location is
+//! configured.
+
+use chrono::{DateTime, Datelike, FixedOffset, NaiveTime, Timelike};
+use tracing::warn;
+
+use crate::config::NightLightConfig;
+
+const MINUTES_PER_DAY: f32 = 1440.0;
+
+/// Sun elevation at sunrise and sunset, allowing for refraction and the
+/// size of the disc.
+const SUN_ZENITH: f64 = 90.833;
+
+/// When the night starts and ends.
+enum Schedule {
+    /// Fixed local times, in minutes after midnight
+    Fixed { start: f32, end: f32 },
+    /// Sunset to sunrise at a location, with fixed times as a fallback for
+    /// days the sun does not rise or set
+    Sun {
+        latitude: f64,
+        longitude: f64,
+        start: f32,
+        end: f32,
+    },
+}
+
+/// A parsed night light configuration.
+pub struct NightLight {
+    schedule: Schedule,
+    strength: f32,
+    transition: f32,
+}
+
+impl NightLight {
+    /// Builds the schedule from `config`. Returns None when the night light
+    /// is disabled or misconfigured (with a warning).
+    pub fn from_config(config: &NightLightConfig) -> Option<Self> {
+        if !config.enable {
+            return None;
+        }
+        let (Some(start), Some(end)) = (parse_time(&config.start), parse_time(&config.end)) else {
+            warn!(
+                "Night light disabled: invalid start or end time (expected HH:MM): {} - {}",
+                config.start, config.end
+            );
+            return None;
+        };
+        let schedule = match (config.latitude, config.longitude) {
+            (Some(latitude), Some(longitude)) => Schedule::Sun {
+                latitude,
+                longitude,
+                start,
+                end,
+            },
+            (None, None) => Schedule::Fixed { start, end },
+            _ => {
+                warn!("Night light: set both latitude and longitude; using fixed times");
+                Schedule::Fixed { start, end }
+            }
+        };
+        Some(Self {
+            schedule,
+            strength: config.strength.clamp(0.0, 1.0),
+            transition: config.transition as f32,
+        })
+    }
+
+    /// Returns the warm effect strength (0 to the configured strength) at
+    /// local time `now`.
+    pub fn strength(&self, now: DateTime<FixedOffset>) -> f32 {
+        let minute = now.hour() as f32 * 60.0 + now.minute() as f32 + now.second() as f32 / 60.0;
+        let (start, end) = match self.schedule {
+            Schedule::Fixed { start, end } => (start, end),
+            Schedule::Sun {
+                latitude,
+                longitude,
+                start,
+                end,
+            } => {
+                let offset = now.offset().local_minus_utc() as f32 / 60.0;
+                let local = |utc: f64| (utc as f32 + offset).rem_euclid(MINUTES_PER_DAY);
+                match sun_times(now.ordinal(), latitude, longitude) {
+                    Some((sunrise, sunset)) => (local(sunset), local(sunrise)),
+                    None => (start, end),
+                }
+            }
+        };
+        self.strength * ramp(minute, start, end, self.transition)
+    }
+}
+
+/// Parses "HH:MM" into minutes after midnight.
+pub fn parse_time(s: &str) -> Option<f32> {
+    let time = NaiveTime::parse_from_str(s.trim(), "%H:%M").ok()?;
+    Some(time.hour() as f32 * 60.0 + time.minute() as f32)
+}
+
+/// How far into the night `now` is, from 0 to 1. The night runs from `start`
+/// to `end` (wrapping past midnight), easing in and out over `transition`
+/// minutes.
+fn ramp(now: f32, start: f32, end: f32, transition: f32) -> f32 {
+    let length = (end - start).rem_euclid(MINUTES_PER_DAY);
+    let elapsed = (now - start).rem_euclid(MINUTES_PER_DAY);
+    if length == 0.0 || elapsed >= length {
+        return 0.0;
+    }
+    let transition = transition.min(length / 2.0);
+    if transition <= 0.0 {
+        return 1.0;
+    }
+    (elapsed.min(length - elapsed) / transition).min(1.0)
+}
+
+/// Returns sunrise and sunset on day `day_of_year` in minutes after UTC
+/// midnight, or None during polar day or night.
+fn sun_times(day_of_year: u32, latitude: f64, longitude: f64) -> Option<(f64, f64)> {
+    let event = |rising: bool| -> Option<f64> {
+        let lng_hour = longitude / 15.0;
+        let approx = day_of_year as f64 + ((if rising { 6.0 } else { 18.0 }) - lng_hour) / 24.0;
+
+        // Sun's mean anomaly and true longitude
+        let mean_anomaly = 0.9856 * approx - 3.289;
+        let true_longitude = (mean_anomaly
+            + 1.916 * mean_anomaly.to_radians().sin()
+            + 0.020 * (2.0 * mean_anomaly).to_radians().sin()
+            + 282.634)
+            .rem_euclid(360.0);
+
+        // Right ascension, in the same quadrant as the true longitude
+        let mut ascension = (0.91764 * true_longitude.to_radians().tan())
+            .atan()
+            .to_degrees()
+            .rem_euclid(360.0);
+        ascension += (true_longitude / 90.0).floor() * 90.0 - (ascension / 90.0).floor() * 90.0;
+        let ascension = ascension / 15.0;
+
+        // Declination and local hour angle
+        let sin_dec = 0.39782 * true_longitude.to_radians().sin();
+        let cos_dec = sin_dec.asin().cos();
+        let cos_hour = (SUN_ZENITH.to_radians().cos() - sin_dec * latitude.to_radians().sin())
+            / (cos_dec * latitude.to_radians().cos());
+        if !(-1.0..=1.0).contains(&cos_hour) {
+            return None;
+        }
+        let hour_angle = cos_hour.acos().to_degrees();
+        let hour_angle = if rising {
+            360.0 - hour_angle
+        } else {
+            hour_angle
+        } / 15.0;
+
+        let local_mean = hour_angle + ascension - 0.06571 * approx - 6.622;
+        Some((local_mean - lng_hour).rem_euclid(24.0) * 60.0)
+    };
+    Some((event(true)?, event(false)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_night_schedule() {
+        // 21:00 to 07:00 with a 30 minute transition
+        let (start, end) = (21.0 * 60.0, 7.0 * 60.0);
+        assert_eq!(ramp(12.0 * 60.0, start, end, 30.0), 0.0);
+        assert_eq!(ramp(21.0 * 60.0 + 15.0, start, end, 30.0), 0.5);
+        assert_eq!(ramp(2.0 * 60.0, start, end, 30.0), 1.0);
+        assert_eq!(ramp(7.0 * 60.0 - 6.0, start, end, 30.0), 0.2);
+        assert_eq!(ramp(7.0 * 60.0, start, end, 30.0), 0.0);
+
+        // London at midsummer: sunrise about 03:43 UTC, sunset about 20:21 UTC
+        let (sunrise, sunset) = sun_times(172, 51.5, -0.13).unwrap();
+        assert!((sunrise - 223.0).abs() < 5.0, "sunrise {}", sunrise);
+        assert!((sunset - 1221.0).abs() < 5.0, "sunset {}", sunset);
+        assert!(sun_times(172, 80.0, 0.0).is_none());
+    }
+}
@@ -0,0 +1,270 @@
+//! Linux framebuffer output.
+//!
+//! Writes frames to a framebuffer device (`/dev/fbN`), as provided by fbtft
+//! SPI displays and by the fbdev emulation of DRM drivers for HDMI and
+//! tinydrm screens. Frames are scaled to fit the screen, keeping their
+//! aspect ratio, and converted to the screen's pixel format.
+
+use std::fs::{File, OpenOptions};
+use std::os::unix::fs::FileExt;
+use std::os::unix::io::AsRawFd;
+use std::sync::Mutex;
+
+use anyhow::{bail, Context, Result};
+use ht32_panel_hw::lcd::Framebuffer;
+use tracing::info;
+
+use super::Output;
+
+const FBIOGET_VSCREENINFO: libc::c_ulong = 0x4600;
+const FBIOGET_FSCREENINFO: libc::c_ulong = 0x4602;
+
+/// `struct fb_bitfield` from linux/fb.h.
+#[repr(C)]
+#[derive(Default, Clone, Copy)]
+struct FbBitfield {
+    offset: u32,
+    length: u32,
+    msb_right: u32,
+}
+
+/// `struct fb_var_screeninfo` from linux/fb.h.
+#[repr(C)]
+#[derive(Default)]
+struct FbVarScreeninfo {
+    xres: u32,
+    yres: u32,
+    xres_virtual: u32,
+    yres_virtual: u32,
+    xoffset: u32,
+    yoffset: u32,
+    bits_per_pixel: u32,
+    grayscale: u32,
+    red: FbBitfield,
+    green: FbBitfield,
+    blue: FbBitfield,
+    transp: FbBitfield,
+    nonstd: u32,
+    activate: u32,
+    height: u32,
+    width: u32,
+    accel_flags: u32,
+    pixclock: u32,
+    left_margin: u32,
+    right_margin: u32,
+    upper_margin: u32,
+    lower_margin: u32,
+    hsync_len: u32,
+    vsync_len: u32,
+    sync: u32,
+    vmode: u32,
+    rotate: u32,
+    colorspace: u32,
+    reserved: [u32; 4],
+}
+
+/// `struct fb_fix_screeninfo` from linux/fb.h.
+#[repr(C)]
+#[derive(Default)]
+struct FbFixScreeninfo {
+    id: [u8; 16],
+    smem_start: libc::c_ulong,
+    smem_len: u32,
+    type_: u32,
+    type_aux: u32,
+    visual: u32,
+    xpanstep: u16,
+    ypanstep: u16,
+    ywrapstep: u16,
+    line_length: u32,
+    mmio_start: libc::c_ulong,
+    mmio_len: u32,
+    accel: u32,
+    capabilities: u16,
+    reserved: [u16; 2],
+}
+
+/// Layout of the screen's memory.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Layout {
+    width: usize,
+    height: usize,
+    /// Bytes from one row to the next
+    stride: usize,
+    bytes_per_pixel: usize,
+    /// Offset and length in bits of the red, green and blue channels
+    red: (u32, u32),
+    green: (u32, u32),
+    blue: (u32, u32),
+    /// Byte offset of the visible screen
+    offset: u64,
+}
+
+/// A Linux framebuffer device.
+pub struct FbdevOutput {
+    file: File,
+    layout: Layout,
+    /// Converted frame, reused between frames
+    buffer: Mutex<Vec<u8>>,
+}
+
+impl FbdevOutput {
+    /// Opens the framebuffer device at `path`.
+    pub fn open(path: &str) -> Result<Self> {
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open(path)
+            .with_context(|| format!("Failed to open framebuffer {}", path))?;
+
+        let mut var = FbVarScreeninfo::default();
+        let mut fix = FbFixScreeninfo::default();
+        // SAFETY: both structs match the kernel's layout and outlive the calls.
+        unsafe {
+            if libc::ioctl(file.as_raw_fd(), FBIOGET_VSCREENINFO as _, &mut var) != 0
+                || libc::ioctl(file.as_raw_fd(), FBIOGET_FSCREENINFO as _, &mut fix) != 0
+            {
+                return Err(std::io::Error::last_os_error())
+                    .with_context(|| format!("{} is not a framebuffer device", path));
+            }
+        }
+
+        if !matches!(var.bits_per_pixel, 16 | 24 | 32) {
+            bail!(
+                "Unsupported framebuffer format: {} bits per pixel",
+                var.bits_per_pixel
+            );
+        }
+        let bytes_per_pixel = var.bits_per_pixel as usize / 8;
+        let layout = Layout {
+            width: var.xres as usize,
+            height: var.yres as usize,
+            stride: fix.line_length as usize,
+            bytes_per_pixel,
+            red: (var.red.offset, var.red.length),
+            green: (var.green.offset, var.green.length),
+            blue: (var.blue.offset, var.blue.length),
+            offset: var.yoffset as u64 * fix.line_length as u64
+                + var.xoffset as u64 * bytes_per_pixel as u64,
+        };
+
+        let name = String::from_utf8_lossy(&fix.id);
+        info!(
+            "Framebuffer {} ({}) opened: {}x{}, {} bits per pixel",
+            path,
+            name.trim_end_matches('\0'),
+            layout.width,
+            layout.height,
+            var.bits_per_pixel
+        );
+
+        Ok(Self {
+            file,
+            buffer: Mutex::new(vec![0; layout.stride * layout.height]),
+            layout,
+        })
+    }
+}
+
+impl Output for FbdevOutput {
+    fn redraw(&self, framebuffer: &Framebuffer) -> Result<()> {
+        let mut buffer = self.buffer.lock().unwrap();
+        convert(framebuffer, &self.layout, &mut buffer);
+        self.file
+            .write_all_at(&buffer, self.layout.offset)
+            .context("Failed to write to framebuffer")
+    }
+}
+
+/// Scales `framebuffer` to fit `layout`, centered on black, and writes it in
+/// the screen's pixel format to `out`.
+fn convert(framebuffer: &Framebuffer, layout: &Layout, out: &mut [u8]) {
+    let src_w = framebuffer.width() as usize;
+    let src_h = framebuffer.height() as usize;
+    let src = framebuffer.data();
+
+    // Largest size with the frame's aspect ratio that fits the screen
+    let (dst_w, dst_h) = if layout.width * src_h <= layout.height * src_w {
+        (layout.width, layout.width * src_h / src_w)
+    } else {
+        (layout.height * src_w / src_h, layout.height)
+    };
+    let left = (layout.width - dst_w) / 2;
+    let top = (layout.height - dst_h) / 2;
+
+    out.fill(0);
+    for y in 0..dst_h {
+        let src_row = &src[(y * src_h / dst_h) * src_w..][..src_w];
+        let row = &mut out[(top + y) * layout.stride..];
+        for x in 0..dst_w {
+            let pixel = encode(src_row[x * src_w / dst_w], layout);
+            let at = (left + x) * layout.bytes_per_pixel;
+            row[at..at + layout.bytes_per_pixel]
+                .copy_from_slice(&pixel.to_le_bytes()[..layout.bytes_per_pixel]);
+        }
+    }
+}
+
+/// Converts an RGB565 pixel to the screen's channel layout.
+fn encode(rgb565: u16, layout: &Layout) -> u32 {
+    let channel = |value: u32, bits: u32, (offset, length): (u32, u32)| {
+        // Scale to 8 bits, then to the channel's width
+        let value8 = (value << (8 - bits)) | (value >> (2 * bits - 8));
+        (value8 >> (8 - length.min(8))) << offset
+    };
+    let r = (rgb565 >> 11) as u32 & 0x1F;
+    let g = (rgb565 >> 5) as u32 & 0x3F;
+    let b = rgb565 as u32 & 0x1F;
+    channel(r, 5, layout.red) | channel(g, 6, layout.green) | channel(b, 5, layout.blue)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn layout(width: usize, height: usize, bytes_per_pixel: usize) -> Layout {
+        let (red, green, blue) = if bytes_per_pixel == 2 {
+            ((11, 5), (5, 6), (0, 5))
+        } else {
+            ((16, 8), (8, 8), (0, 8))
+        };
+        Layout {
+            width,
+            height,
+            stride: width * bytes_per_pixel,
+            bytes_per_pixel,
+            red,
+            green,
+            blue,
+            offset: 0,
+        }
+    }
+
+    #[test]
+    fn test_encode() {
+        let rgb565 = layout(1, 1, 2);
+        assert_eq!(encode(0xF800, &rgb565), 0xF800);
+        assert_eq!(encode(0x07E0, &rgb565), 0x07E0);
+
+        let xrgb = layout(1, 1, 4);
+        assert_eq!(encode(0xFFFF, &xrgb), 0xFFFFFF);
+        assert_eq!(encode(0xF800, &xrgb), 0xFF0000);
+        assert_eq!(encode(0x001F, &xrgb), 0x0000FF);
+    }
+
+    #[test]
+    fn test_convert_letterboxes() {
+        let mut framebuffer = Framebuffer::new();
+        framebuffer.clear(0xFFFF);
+
+        // 320x170 on a 320x240 screen leaves 35 black rows above and below
+        let layout = layout(320, 240, 2);
+        let mut out = vec![0xAA; layout.stride * layout.height];
+        convert(&framebuffer, &layout, &mut out);
+        let row = |y: usize| &out[y * layout.stride..(y + 1) * layout.stride];
+        assert!(row(34).iter().all(|&b| b == 0));
+        assert!(row(35).iter().all(|&b| b == 0xFF));
+        assert!(row(204).iter().all(|&b| b == 0xFF));
+        assert!(row(205).iter().all(|&b| b == 0));
+    }
+}
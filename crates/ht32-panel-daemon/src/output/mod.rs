@@ -0,0 +1,96 @@
+//! Display outputs.
+//!
+//! Frames are rendered to a 320x170 RGB565 framebuffer and handed to an
+//! output: the HT32 panel's LCD, a Linux framebuffer device such as a
+//! small SPI or HDMI status screen, or a panel on another machine.
+
+mod fbdev;
+mod network;
+
+use anyhow::Result;
+use ht32_panel_hw::lcd::{Framebuffer, TransferSettings};
+use ht32_panel_hw::{LcdDevice, Orientation};
+use tracing::{info, warn};
+
+use crate::config::{DevicesConfig, OutputKind, TransferConfig};
+
+pub use fbdev::FbdevOutput;
+pub use network::NetworkOutput;
+
+/// A display that frames are sent to.
+pub trait Output: Send {
+    /// Sends a full frame.
+    fn redraw(&self, framebuffer: &Framebuffer) -> Result<()>;
+
+    /// Keeps the display awake. Displays that need no heartbeat do nothing.
+    fn heartbeat(&self) -> Result<()> {
+        Ok(())
+    }
+
+    /// Puts the display in landscape mode; portrait orientations are
+    /// rotated in software.
+    fn set_landscape(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl Output for LcdDevice {
+    fn redraw(&self, framebuffer: &Framebuffer) -> Result<()> {
+        Ok(self.update(framebuffer)?)
+    }
+
+    fn heartbeat(&self) -> Result<()> {
+        Ok(LcdDevice::heartbeat(self)?)
+    }
+
+    fn set_landscape(&self) -> Result<()> {
+        Ok(self.set_orientation(Orientation::Landscape)?)
+    }
+}
+
+/// Opens the output selected in `devices`.
+pub fn open(devices: &DevicesConfig) -> Result<Box<dyn Output>> {
+    let output: Box<dyn Output> = match devices.output {
+        OutputKind::Ht32 => Box::new(open_lcd(&devices.transfer)?),
+        OutputKind::Fbdev => Box::new(FbdevOutput::open(&devices.fbdev)?),
+        OutputKind::Network => Box::new(NetworkOutput::new(&devices.remote)),
+    };
+
+    // Send an initial heartbeat to wake up the device
+    if let Err(e) = output.heartbeat() {
+        warn!("Failed to send initial heartbeat: {}", e);
+    }
+    if let Err(e) = output.set_landscape() {
+        warn!("Failed to set initial orientation: {}", e);
+    }
+    info!("Display output opened successfully");
+    Ok(output)
+}
+
+/// Opens the HT32 panel's LCD with the configured transfer settings, tuning
+/// them first when `transfer.auto` is set. Invalid settings fall back to the
+/// defaults rather than leaving the panel dark.
+fn open_lcd(transfer: &TransferConfig) -> Result<LcdDevice> {
+    let lcd = LcdDevice::open()?;
+    if let Err(e) = lcd.set_transfer(transfer.settings()) {
+        warn!(
+            "Invalid LCD transfer settings, using {}: {}",
+            lcd.transfer(),
+            e
+        );
+    }
+    if transfer.auto {
+        // Tuning only sees USB errors; frames a hub garbles silently still
+        // pass, so it stays opt-in
+        match lcd.tune_transfer(&TransferSettings::candidates()) {
+            Ok(_) => info!(
+                "If frames come out garbled, turn off devices.transfer.auto and set the pace by hand"
+            ),
+            Err(e) => warn!("LCD transfer tuning failed: {}", e),
+        }
+    } else {
+        info!("LCD transfer: {}", lcd.transfer());
+    }
+    lcd.set_partial_updates(transfer.partial_updates);
+    Ok(lcd)
+}
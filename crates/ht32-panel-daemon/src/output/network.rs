@@ -0,0 +1,83 @@
+//! Network output.
+//!
+//! Streams frames over TCP to `ht32panelrx` on another machine, which shows
+//! them on its panel. This lets a headless server drive a panel attached to
+//! a different host. The connection is opened lazily and re-established on
+//! the next frame after a failure, so the receiver can restart freely.
+
+use std::net::{TcpStream, ToSocketAddrs};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use anyhow::{bail, Context, Result};
+use ht32_panel_hw::{lcd::Framebuffer, stream};
+use tracing::info;
+
+use super::Output;
+
+/// How long to wait for the receiver to accept a connection or a frame.
+const TIMEOUT: Duration = Duration::from_secs(2);
+
+/// How long to wait after a failed connection before trying again, so an
+/// unreachable receiver does not stall every frame.
+const RETRY_DELAY: Duration = Duration::from_secs(10);
+
+/// A panel on another machine, reached over TCP.
+pub struct NetworkOutput {
+    address: String,
+    connection: Mutex<Option<TcpStream>>,
+    /// When the last connection attempt failed
+    failed_at: Mutex<Option<Instant>>,
+}
+
+impl NetworkOutput {
+    /// Creates an output streaming to `address` ("host:port"). The
+    /// receiver need not be running yet.
+    pub fn new(address: &str) -> Self {
+        Self {
+            address: address.to_string(),
+            connection: Mutex::new(None),
+            failed_at: Mutex::new(None),
+        }
+    }
+
+    fn connect(&self) -> Result<TcpStream> {
+        let mut failed_at = self.failed_at.lock().unwrap();
+        if failed_at.is_some_and(|at| at.elapsed() < RETRY_DELAY) {
+            bail!("Not connected to receiver at {}", self.address);
+        }
+        let result = self.try_connect();
+        *failed_at = result.is_err().then(Instant::now);
+        result
+    }
+
+    fn try_connect(&self) -> Result<TcpStream> {
+        let target = self
+            .address
+            .to_socket_addrs()
+            .with_context(|| format!("Failed to resolve {}", self.address))?
+            .next()
+            .with_context(|| format!("{} has no addresses", self.address))?;
+        let socket = TcpStream::connect_timeout(&target, TIMEOUT)
+            .with_context(|| format!("Failed to connect to receiver at {}", self.address))?;
+        socket.set_nodelay(true)?;
+        socket.set_write_timeout(Some(TIMEOUT))?;
+        info!("Streaming frames to {}", self.address);
+        Ok(socket)
+    }
+}
+
+impl Output for NetworkOutput {
+    fn redraw(&self, framebuffer: &Framebuffer) -> Result<()> {
+        let mut connection = self.connection.lock().unwrap();
+        let writer = match connection.as_mut() {
+            Some(writer) => writer,
+            None => connection.insert(self.connect()?),
+        };
+        if let Err(e) = stream::write_frame(writer, framebuffer) {
+            *connection = None;
+            return Err(e).with_context(|| format!("Failed to send frame to {}", self.address));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,96 @@
+//! Frame pacing for the render loop.
+//!
+//! Frames are rendered on wall-clock multiples of the refresh interval
+//! (e.g. on every whole second at 1000 ms), so the clock changes on the
+//! minute rather than up to one interval late. Intervals that do not divide
+//! a minute get an extra frame on each minute boundary. A frame that takes
+//! longer than the interval skips the ticks it missed instead of rendering
+//! them in a burst.
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use tokio::time::{Instant, Interval, MissedTickBehavior};
+use tracing::debug;
+
+const MINUTE: Duration = Duration::from_secs(60);
+
+/// Distance from a boundary beyond which ticks are re-aligned, e.g. after
+/// the system clock was stepped or the machine resumed from suspend.
+const MAX_DRIFT: Duration = Duration::from_millis(50);
+
+/// Schedules render loop frames.
+pub struct FramePacer {
+    period: Duration,
+    interval: Interval,
+}
+
+impl FramePacer {
+    /// Creates a pacer ticking every `period`, aligned to the wall clock.
+    pub fn new(period: Duration) -> Self {
+        let start = Instant::now() + until_next_boundary(since_epoch(), period);
+        let mut interval = tokio::time::interval_at(start, period);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
+        Self { period, interval }
+    }
+
+    /// Waits until the next frame is due. `period` is the current refresh
+    /// interval; ticks are re-aligned when it changes.
+    pub async fn tick(&mut self, period: Duration) {
+        if period != self.period {
+            *self = Self::new(period);
+        }
+        if MINUTE.as_millis().is_multiple_of(period.as_millis()) {
+            self.interval.tick().await;
+        } else {
+            // Off-schedule frame on the minute; alignment is checked on ticks
+            let minute = tokio::time::sleep(until_next_boundary(since_epoch(), MINUTE));
+            tokio::select! {
+                _ = self.interval.tick() => {}
+                _ = minute => return,
+            }
+        }
+
+        let offset = until_next_boundary(since_epoch(), period);
+        if offset.min(period.saturating_sub(offset)) > MAX_DRIFT {
+            debug!(
+                "Frame ticks drifted {:?} from the wall clock, re-aligning",
+                offset
+            );
+            *self = Self::new(period);
+        }
+    }
+}
+
+/// Returns the time since the Unix epoch.
+fn since_epoch() -> Duration {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+}
+
+/// Returns how long after `now` (since the epoch) the next multiple of
+/// `period` falls. Zero when `now` is exactly on one.
+fn until_next_boundary(now: Duration, period: Duration) -> Duration {
+    let period_ms = period.as_millis().max(1);
+    let remainder = now.as_millis() % period_ms;
+    if remainder == 0 {
+        Duration::ZERO
+    } else {
+        Duration::from_millis((period_ms - remainder) as u64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_until_next_boundary() {
+        let ms = Duration::from_millis;
+        let second = Duration::from_secs(1);
+        assert_eq!(until_next_boundary(ms(1_700_000_000_250), second), ms(750));
+        assert_eq!(until_next_boundary(ms(1_700_000_000_000), second), ms(0));
+        assert_eq!(until_next_boundary(ms(1_800_000_059_900), MINUTE), ms(100));
+        assert_eq!(until_next_boundary(ms(2_600), ms(2_500)), ms(2_400));
+    }
+}
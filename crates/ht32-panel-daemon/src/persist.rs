@@ -0,0 +1,81 @@
+//! Crash-safe writes of state files.
+//!
+//! State is written to a temporary file next to its destination, flushed
+//! to disk and renamed over the old file, so a power loss leaves either the
+//! old or the new contents rather than a truncated file.
+
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Numbers temporary files, so concurrent writes of one path do not share
+/// a temporary file.
+static TEMP_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// Replaces the contents of `path` with `contents` atomically.
+pub fn write_atomic(path: &Path, contents: impl AsRef<[u8]>) -> io::Result<()> {
+    let temp = temp_path(path);
+    let result = (|| {
+        let mut file = File::options().write(true).create_new(true).open(&temp)?;
+        file.write_all(contents.as_ref())?;
+        file.sync_all()?;
+        fs::rename(&temp, path)
+    })();
+    if result.is_err() {
+        let _ = fs::remove_file(&temp);
+        return result;
+    }
+
+    // Persist the rename itself
+    if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+        File::open(dir)?.sync_all()?;
+    }
+    Ok(())
+}
+
+/// Returns a new temporary file name to write before renaming over `path`,
+/// unique to this process and call.
+fn temp_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(format!(
+        ".{}.{}.tmp",
+        std::process::id(),
+        TEMP_COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+    path.with_file_name(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_atomic() {
+        let dir = std::env::temp_dir().join(format!("ht32-persist-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("display.toml");
+
+        write_atomic(&path, "face = \"ascii\"\n").unwrap();
+        write_atomic(&path, "face = \"digits\"\n").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "face = \"digits\"\n");
+        assert_ne!(temp_path(&path), temp_path(&path));
+
+        // A failed write leaves the old contents in place
+        assert!(write_atomic(&dir.join("missing/display.toml"), "").is_err());
+        assert_eq!(fs::read_to_string(&path).unwrap(), "face = \"digits\"\n");
+
+        // Concurrent writes each rename a complete file of their own
+        std::thread::scope(|scope| {
+            for i in 0..8 {
+                let path = &path;
+                scope.spawn(move || write_atomic(path, format!("face = \"{}\"\n", i)).unwrap());
+            }
+        });
+        let contents = fs::read_to_string(&path).unwrap();
+        assert!(contents.starts_with("face = \"") && contents.ends_with("\"\n"));
+        let leftovers = fs::read_dir(&dir).unwrap().count();
+        assert_eq!(leftovers, 1, "temporary files were left behind");
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,134 @@
+//! Frame time statistics.
+//!
+//! Each rendered frame records how long its stages took. `GetRenderStats`
+//! reports percentiles over the most recent frames.
+
+use std::collections::VecDeque;
+use std::time::Duration;
+
+/// Number of frames the statistics cover.
+const WINDOW: usize = 300;
+
+/// Stages reported, ending with the whole frame.
+const STAGES: [&str; 4] = ["sensors", "render", "present", "total"];
+
+/// How long the stages of one frame took.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FrameTimes {
+    /// Sampling sensors and checking alerts
+    pub sensors: Duration,
+    /// Drawing the face on the canvas
+    pub render: Duration,
+    /// Converting to RGB565, applying effects and sending to the LCD
+    pub present: Duration,
+}
+
+impl FrameTimes {
+    /// Returns the time of the whole frame.
+    pub fn total(&self) -> Duration {
+        self.sensors + self.render + self.present
+    }
+
+    /// Returns the time of one of [`STAGES`].
+    fn stage(&self, stage: &str) -> Duration {
+        match stage {
+            "sensors" => self.sensors,
+            "render" => self.render,
+            "present" => self.present,
+            _ => self.total(),
+        }
+    }
+}
+
+/// Percentiles of one stage, in milliseconds.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StageStats {
+    pub stage: &'static str,
+    pub p50: f64,
+    pub p95: f64,
+    pub p99: f64,
+    pub max: f64,
+}
+
+/// Frame times of the most recent frames.
+#[derive(Default)]
+pub struct RenderStats {
+    frames: VecDeque<FrameTimes>,
+}
+
+impl RenderStats {
+    /// Records a rendered frame.
+    pub fn record(&mut self, times: FrameTimes) {
+        if self.frames.len() == WINDOW {
+            self.frames.pop_front();
+        }
+        self.frames.push_back(times);
+    }
+
+    /// Returns the number of frames covered.
+    pub fn frames(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// Returns percentiles of each stage and of the whole frame, or nothing
+    /// before the first frame.
+    pub fn summary(&self) -> Vec<StageStats> {
+        if self.frames.is_empty() {
+            return Vec::new();
+        }
+        STAGES
+            .iter()
+            .map(|stage| {
+                let mut ms: Vec<f64> = self
+                    .frames
+                    .iter()
+                    .map(|t| t.stage(stage).as_secs_f64() * 1000.0)
+                    .collect();
+                ms.sort_by(f64::total_cmp);
+                StageStats {
+                    stage,
+                    p50: percentile(&ms, 50.0),
+                    p95: percentile(&ms, 95.0),
+                    p99: percentile(&ms, 99.0),
+                    max: ms[ms.len() - 1],
+                }
+            })
+            .collect()
+    }
+}
+
+/// Returns the nearest-rank percentile `p` of non-empty `sorted` values.
+fn percentile(sorted: &[f64], p: f64) -> f64 {
+    let rank = (p / 100.0 * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_percentiles() {
+        let mut stats = RenderStats::default();
+        assert!(stats.summary().is_empty());
+        for ms in 1..=100 {
+            stats.record(FrameTimes {
+                render: Duration::from_millis(ms),
+                ..Default::default()
+            });
+        }
+        let summary = stats.summary();
+        let render = summary.iter().find(|s| s.stage == "render").unwrap();
+        assert_eq!(
+            (render.p50, render.p95, render.p99, render.max),
+            (50.0, 95.0, 99.0, 100.0)
+        );
+        assert_eq!(summary.last().unwrap().max, 100.0);
+
+        for _ in 0..WINDOW {
+            stats.record(FrameTimes::default());
+        }
+        assert_eq!(stats.frames(), WINDOW);
+        assert_eq!(stats.summary()[1].max, 0.0);
+    }
+}
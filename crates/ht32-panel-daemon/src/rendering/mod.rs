@@ -1,8 +0,0 @@
-//! Rendering module for canvas.
-
-#![allow(dead_code)]
-
-mod canvas;
-mod text;
-
-pub use canvas::Canvas;
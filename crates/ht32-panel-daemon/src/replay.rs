@@ -0,0 +1,192 @@
+//! Recording and replay of sensor data.
+//!
+//! `ht32paneld --record FILE` appends every [`SystemData`] sample to FILE as
+//! a JSON line. `ht32paneld --replay FILE` feeds the recorded samples to the
+//! faces instead of reading the sensors, keeping their original timing
+//! scaled by `--speed`, so the exact conditions behind a rendering glitch
+//! can be reproduced.
+
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use crate::sensors::data::SystemData;
+
+/// One line of a recording.
+#[derive(Serialize, Deserialize)]
+struct Sample<D> {
+    /// Time since the recording started, in milliseconds
+    offset_ms: u64,
+    data: D,
+}
+
+/// Appends samples to a recording.
+pub struct Recorder {
+    file: Mutex<Option<File>>,
+    started: Instant,
+}
+
+impl Recorder {
+    /// Starts a recording at `path`, appending if it already exists.
+    pub fn create(path: &Path) -> Result<Self> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .with_context(|| format!("Failed to open recording {}", path.display()))?;
+        info!("Recording sensor data to {}", path.display());
+        Ok(Self {
+            file: Mutex::new(Some(file)),
+            started: Instant::now(),
+        })
+    }
+
+    /// Appends a sample. Recording stops after the first write error.
+    pub fn record(&self, data: &SystemData) {
+        let mut file = self.file.lock().unwrap();
+        let Some(ref mut writer) = *file else {
+            return;
+        };
+        let sample = Sample {
+            offset_ms: self.started.elapsed().as_millis() as u64,
+            data,
+        };
+        let result = serde_json::to_string(&sample)
+            .map_err(std::io::Error::from)
+            .and_then(|mut line| {
+                line.push('\n');
+                writer.write_all(line.as_bytes())
+            });
+        if let Err(e) = result {
+            warn!("Stopped recording sensor data: {}", e);
+            *file = None;
+        }
+    }
+}
+
+/// A loaded recording, played back in a loop.
+pub struct Replay {
+    samples: Vec<(Duration, SystemData)>,
+    period: Duration,
+    speed: f64,
+    started: Instant,
+}
+
+impl Replay {
+    /// Loads the recording at `path` to play back at `speed` times the
+    /// recorded rate.
+    pub fn load(path: &Path, speed: f64) -> Result<Self> {
+        let file = File::open(path)
+            .with_context(|| format!("Failed to open recording {}", path.display()))?;
+        let mut samples = Vec::new();
+        for (index, line) in BufReader::new(file).lines().enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let sample: Sample<SystemData> = serde_json::from_str(&line)
+                .with_context(|| format!("Invalid sample on line {}", index + 1))?;
+            samples.push((Duration::from_millis(sample.offset_ms), sample.data));
+        }
+        Self::new(samples, speed)
+            .with_context(|| format!("Failed to load recording {}", path.display()))
+    }
+
+    fn new(mut samples: Vec<(Duration, SystemData)>, speed: f64) -> Result<Self> {
+        if samples.is_empty() {
+            bail!("Recording has no samples");
+        }
+        // Appended recordings restart their offsets; play them in order
+        for i in 1..samples.len() {
+            if samples[i].0 < samples[i - 1].0 {
+                let base = samples[i - 1].0;
+                for sample in &mut samples[i..] {
+                    sample.0 += base;
+                }
+            }
+        }
+        // Leave one average interval after the last sample before looping
+        let last = samples[samples.len() - 1].0;
+        let period = match samples.len() {
+            1 => Duration::ZERO,
+            n => last + last / (n as u32 - 1),
+        };
+        info!(
+            "Replaying {} samples ({:.0}s) at {}x",
+            samples.len(),
+            last.as_secs_f64(),
+            speed
+        );
+        Ok(Self {
+            samples,
+            period,
+            speed,
+            started: Instant::now(),
+        })
+    }
+
+    /// Returns the sample due now, starting over after the last one.
+    pub fn current(&self) -> SystemData {
+        self.at(self.started.elapsed().mul_f64(self.speed)).clone()
+    }
+
+    /// Returns the sample due `elapsed` into the (looped) recording.
+    fn at(&self, elapsed: Duration) -> &SystemData {
+        let position = if self.period.is_zero() {
+            Duration::ZERO
+        } else {
+            Duration::from_nanos((elapsed.as_nanos() % self.period.as_nanos()) as u64)
+        };
+        let index = self
+            .samples
+            .partition_point(|(offset, _)| *offset <= position)
+            .saturating_sub(1);
+        &self.samples[index].1
+    }
+}
+
+/// Parses a replay speed such as "10x", "0.5x" or "2".
+pub fn parse_speed(s: &str) -> Result<f64, String> {
+    let number = s.trim().trim_end_matches(['x', 'X']);
+    match number.parse::<f64>() {
+        Ok(speed) if speed > 0.0 && speed.is_finite() => Ok(speed),
+        _ => Err(format!("Invalid speed '{}' (expected e.g. 10x)", s)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_replay_timing() {
+        let sample = |cpu: f64| SystemData {
+            cpu_percent: cpu,
+            ..Default::default()
+        };
+        let replay = Replay::new(
+            vec![
+                (Duration::from_secs(0), sample(10.0)),
+                (Duration::from_secs(2), sample(20.0)),
+                (Duration::from_secs(4), sample(30.0)),
+            ],
+            1.0,
+        )
+        .unwrap();
+        assert_eq!(replay.at(Duration::from_secs(1)).cpu_percent, 10.0);
+        assert_eq!(replay.at(Duration::from_secs(3)).cpu_percent, 20.0);
+        assert_eq!(replay.at(Duration::from_secs(5)).cpu_percent, 30.0);
+        // Loops after one more interval
+        assert_eq!(replay.at(Duration::from_secs(7)).cpu_percent, 10.0);
+
+        assert_eq!(parse_speed("10x"), Ok(10.0));
+        assert_eq!(parse_speed("0.5"), Ok(0.5));
+        assert!(parse_speed("0x").is_err());
+    }
+}
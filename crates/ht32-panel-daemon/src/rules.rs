@@ -0,0 +1,192 @@
+//! Condition-based face switching.
+//!
+//! A rule watches one value, such as CPU usage, and switches to its face once
+//! the value has been outside the rule's limits for a while. When the value
+//! recovers, the face shown before returns, unless the face was changed by
+//! hand in the meantime. Limits are checked the same way as custom metric
+//! alerts. One rule is in effect at a time; the first to fire wins.
+
+use std::time::{Duration, Instant};
+
+use crate::config::RuleConfig;
+use crate::sensors::data::{collector_names, outside_limits, SystemData};
+
+/// Returns the current value named `name`: "cpu", "memory", "cpu_temp",
+/// "gpu", "gpu_temp" or a custom metric. None if it is unavailable.
+pub fn value(data: &SystemData, name: &str) -> Option<f64> {
+    match name {
+        "cpu" => Some(data.cpu_percent),
+        "memory" => Some(data.ram_percent),
+        "cpu_temp" => data.cpu_temp,
+        "gpu" => data.gpu.as_ref()?.utilization.map(f64::from),
+        "gpu_temp" => data.gpu.as_ref()?.temperature.map(f64::from),
+        _ => data
+            .metrics
+            .get(name)
+            .filter(|metric| metric.text.is_none())
+            .map(|metric| metric.value),
+    }
+}
+
+/// Returns the built-in collector that reads the value named `name`, if
+/// it is not always read.
+pub fn collector(name: &str) -> Option<&'static str> {
+    match name {
+        "cpu_temp" => Some(collector_names::TEMPERATURE),
+        _ => None,
+    }
+}
+
+/// Evaluates rules frame by frame.
+#[derive(Debug)]
+pub struct RuleEngine {
+    rules: Vec<RuleConfig>,
+    /// When each rule started holding
+    since: Vec<Option<Instant>>,
+    /// The rule in effect and the face shown before it fired
+    active: Option<(usize, String)>,
+}
+
+impl RuleEngine {
+    /// Creates an engine for `rules`.
+    pub fn new(rules: Vec<RuleConfig>) -> Self {
+        Self {
+            since: vec![None; rules.len()],
+            rules,
+            active: None,
+        }
+    }
+
+    /// Returns the rules being evaluated.
+    pub fn rules(&self) -> &[RuleConfig] {
+        &self.rules
+    }
+
+    /// Replaces the rules. Returns the face to switch back to if a rule
+    /// was in effect and `current_face` is still its face.
+    pub fn set_rules(&mut self, rules: Vec<RuleConfig>, current_face: &str) -> Option<String> {
+        let restore = self.active.take().and_then(|(rule, previous)| {
+            (self.rules[rule].face == current_face).then_some(previous)
+        });
+        *self = Self::new(rules);
+        restore
+    }
+
+    /// Returns the face shown before the rule in effect fired, if
+    /// `current_face` is still the rule's face. This is the face to save,
+    /// as the rule only switches faces for a while.
+    pub fn saved_face(&self, current_face: &str) -> Option<&str> {
+        let (rule, previous) = self.active.as_ref()?;
+        (self.rules[*rule].face == current_face).then_some(previous.as_str())
+    }
+
+    /// Checks the rules against `data` at `now`. Returns the face to switch
+    /// to, if any.
+    pub fn evaluate(
+        &mut self,
+        data: &SystemData,
+        current_face: &str,
+        now: Instant,
+    ) -> Option<String> {
+        for (rule, since) in self.rules.iter().zip(&mut self.since) {
+            let holds =
+                value(data, &rule.when).is_some_and(|v| outside_limits(v, rule.above, rule.below));
+            *since = if holds { since.or(Some(now)) } else { None };
+        }
+
+        if let Some((rule, previous)) = &self.active {
+            if self.since[*rule].is_some() {
+                return None;
+            }
+            let switch_back = self.rules[*rule].face == current_face;
+            let previous = previous.clone();
+            self.active = None;
+            return switch_back.then_some(previous);
+        }
+
+        let fired = self
+            .rules
+            .iter()
+            .zip(&self.since)
+            .position(|(rule, since)| {
+                rule.face != current_face
+                    && since.is_some_and(|since| {
+                        now.duration_since(since) >= Duration::from_secs(rule.hold)
+                    })
+            })?;
+        self.active = Some((fired, current_face.to_string()));
+        Some(self.rules[fired].face.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cpu_rule() -> RuleConfig {
+        RuleConfig {
+            when: "cpu".to_string(),
+            above: Some(80.0),
+            below: None,
+            hold: 30,
+            face: "arcs".to_string(),
+        }
+    }
+
+    fn cpu(percent: f64) -> SystemData {
+        SystemData {
+            cpu_percent: percent,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_switches_and_returns() {
+        let mut engine = RuleEngine::new(vec![cpu_rule()]);
+        let start = Instant::now();
+        let at = |secs| start + Duration::from_secs(secs);
+
+        assert_eq!(engine.evaluate(&cpu(90.0), "clock", at(0)), None);
+        assert_eq!(engine.evaluate(&cpu(90.0), "clock", at(20)), None);
+        assert_eq!(
+            engine.evaluate(&cpu(90.0), "clock", at(30)),
+            Some("arcs".to_string())
+        );
+        assert_eq!(engine.saved_face("arcs"), Some("clock"));
+        assert_eq!(engine.saved_face("gauges"), None);
+        assert_eq!(engine.evaluate(&cpu(95.0), "arcs", at(40)), None);
+        assert_eq!(
+            engine.evaluate(&cpu(50.0), "arcs", at(50)),
+            Some("clock".to_string())
+        );
+        assert_eq!(engine.evaluate(&cpu(50.0), "clock", at(60)), None);
+        assert_eq!(engine.saved_face("clock"), None);
+    }
+
+    #[test]
+    fn test_dip_restarts_hold() {
+        let mut engine = RuleEngine::new(vec![cpu_rule()]);
+        let start = Instant::now();
+        let at = |secs| start + Duration::from_secs(secs);
+
+        engine.evaluate(&cpu(90.0), "clock", at(0));
+        engine.evaluate(&cpu(70.0), "clock", at(20));
+        assert_eq!(engine.evaluate(&cpu(90.0), "clock", at(30)), None);
+        assert_eq!(
+            engine.evaluate(&cpu(90.0), "clock", at(60)),
+            Some("arcs".to_string())
+        );
+    }
+
+    #[test]
+    fn test_manual_change_is_kept() {
+        let mut rule = cpu_rule();
+        rule.hold = 0;
+        let mut engine = RuleEngine::new(vec![rule]);
+        let now = Instant::now();
+
+        assert!(engine.evaluate(&cpu(90.0), "clock", now).is_some());
+        // Changed by hand while the rule was in effect
+        assert_eq!(engine.evaluate(&cpu(50.0), "ascii", now), None);
+    }
+}
@@ -0,0 +1,160 @@
+//! Per-application CPU usage.
+//!
+//! Every [`INTERVAL`] the CPU time of each process in /proc is compared
+//! with the previous scan and summed by command name, so the many
+//! processes of a browser or compiler count as one application. The
+//! busiest application is reported as a share of all CPU time, like the
+//! overall CPU usage.
+
+use std::collections::HashMap;
+use std::fs;
+use std::time::{Duration, Instant};
+
+use super::data::AppUsage;
+
+/// Minimum time between scans; reads in between return the last result.
+const INTERVAL: Duration = Duration::from_secs(3);
+
+/// Sensor reporting the application using the most CPU.
+pub struct AppCpuSensor {
+    /// CPU ticks of each process at the previous scan, by PID
+    last_ticks: HashMap<u32, u64>,
+    /// Total CPU ticks at the previous scan
+    last_total: Option<u64>,
+    /// When the previous scan ran
+    last_scan: Option<Instant>,
+    /// Result of the previous scan
+    latest: Option<AppUsage>,
+}
+
+impl AppCpuSensor {
+    /// Creates a new application CPU sensor.
+    pub fn new() -> Self {
+        Self {
+            last_ticks: HashMap::new(),
+            last_total: None,
+            last_scan: None,
+            latest: None,
+        }
+    }
+
+    /// Returns the busiest application since the previous scan. None until
+    /// two scans have run or if /proc cannot be read.
+    pub fn sample(&mut self) -> Option<AppUsage> {
+        if self.last_scan.is_some_and(|at| at.elapsed() < INTERVAL) {
+            return self.latest.clone();
+        }
+        self.last_scan = Some(Instant::now());
+
+        let total = total_ticks();
+        let ticks = process_ticks();
+        let elapsed = match (self.last_total, total) {
+            (Some(last), Some(now)) if now > last => Some(now - last),
+            _ => None,
+        };
+        self.latest = elapsed.and_then(|elapsed| {
+            // Processes started since the previous scan count in full
+            let deltas = ticks.iter().map(|(pid, (name, now))| {
+                let last = self.last_ticks.get(pid).copied().unwrap_or(0);
+                (name.as_str(), now.saturating_sub(last))
+            });
+            let (name, used) = busiest(deltas)?;
+            Some(AppUsage {
+                name: name.to_string(),
+                cpu_percent: (used as f64 / elapsed as f64 * 100.0).min(100.0),
+            })
+        });
+
+        self.last_total = total;
+        self.last_ticks = ticks
+            .into_iter()
+            .map(|(pid, (_, ticks))| (pid, ticks))
+            .collect();
+        self.latest.clone()
+    }
+}
+
+impl Default for AppCpuSensor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Returns the CPU ticks spent on all CPUs since boot.
+fn total_ticks() -> Option<u64> {
+    let content = fs::read_to_string("/proc/stat").ok()?;
+    let line = content.lines().next()?.strip_prefix("cpu ")?;
+    Some(
+        line.split_whitespace()
+            .filter_map(|s| s.parse::<u64>().ok())
+            .sum(),
+    )
+}
+
+/// Returns the command name and CPU ticks of each process, by PID.
+fn process_ticks() -> HashMap<u32, (String, u64)> {
+    let Ok(entries) = fs::read_dir("/proc") else {
+        return HashMap::new();
+    };
+    entries
+        .flatten()
+        .filter_map(|entry| {
+            let pid = entry.file_name().to_str()?.parse().ok()?;
+            // Processes can exit between listing and reading
+            let stat = fs::read_to_string(entry.path().join("stat")).ok()?;
+            Some((pid, parse_stat(&stat)?))
+        })
+        .collect()
+}
+
+/// Parses the application name and user plus system CPU ticks from a
+/// /proc/PID/stat line. Kernel threads such as "kworker/0:1" are grouped
+/// under the part before the slash.
+fn parse_stat(stat: &str) -> Option<(String, u64)> {
+    // The command name may contain spaces and parentheses, so take
+    // everything between the first "(" and the last ")"
+    let (head, rest) = stat.rsplit_once(')')?;
+    let (_, comm) = head.split_once('(')?;
+    let name = comm.split('/').next().unwrap_or(comm);
+
+    // utime and stime are the 12th and 13th fields after the name
+    let mut fields = rest.split_whitespace().skip(11);
+    let utime: u64 = fields.next()?.parse().ok()?;
+    let stime: u64 = fields.next()?.parse().ok()?;
+    Some((name.to_string(), utime + stime))
+}
+
+/// Sums `deltas` by application and returns the one that used the most
+/// ticks, None if none used any.
+fn busiest<'a>(deltas: impl Iterator<Item = (&'a str, u64)>) -> Option<(&'a str, u64)> {
+    let mut by_app: HashMap<&str, u64> = HashMap::new();
+    for (name, ticks) in deltas {
+        *by_app.entry(name).or_default() += ticks;
+    }
+    by_app
+        .into_iter()
+        .filter(|&(_, ticks)| ticks > 0)
+        // Break ties by name so the result is stable
+        .max_by(|a, b| a.1.cmp(&b.1).then_with(|| b.0.cmp(a.0)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_stat_and_group() {
+        let stat = "4242 (Web Content) S 1 4242 4242 0 -1 4194560 1200 0 0 0 350 45 0 0 20 0 \
+                    31 0 123456 0 0";
+        assert_eq!(parse_stat(stat), Some(("Web Content".to_string(), 395)));
+        assert_eq!(
+            parse_stat("17 (kworker/0:1-events) I 2 0 0 0 -1 0 0 0 0 0 3 7 0 0 20 0 1 0 9"),
+            Some(("kworker".to_string(), 10))
+        );
+        assert_eq!(parse_stat("garbage"), None);
+
+        let deltas = [("cc1", 40), ("firefox", 50), ("cc1", 30), ("idle", 0)];
+        assert_eq!(busiest(deltas.into_iter()), Some(("cc1", 70)));
+        assert_eq!(busiest([("idle", 0)].into_iter()), None);
+    }
+}
@@ -0,0 +1,288 @@
+//! Audio level and spectrum sensor.
+//!
+//! Captures the default output monitor through `parec`, which talks to both
+//! PulseAudio and PipeWire (via pipewire-pulse). A background thread reads
+//! fixed-size blocks, runs an FFT and publishes the overall level, a set of
+//! log-spaced frequency bands and a beat counter.
+
+use std::io::Read;
+use std::process::{Child, Command, Stdio};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use rustfft::{num_complex::Complex, FftPlanner};
+use tracing::{debug, warn};
+
+use super::data::AudioLevels;
+
+/// Capture sample rate in Hz.
+const SAMPLE_RATE: u32 = 22050;
+
+/// Samples per analysis block (~46ms at 22.05kHz).
+const BLOCK_SIZE: usize = 1024;
+
+/// Number of spectrum bands published.
+const BAND_COUNT: usize = 16;
+
+/// Frequency range covered by the bands, in Hz.
+const MIN_FREQ: f32 = 40.0;
+const MAX_FREQ: f32 = 10000.0;
+
+/// Levels below this (in dBFS) are shown as silence.
+const FLOOR_DB: f32 = -60.0;
+
+/// Number of leading bands treated as bass for beat detection.
+const BASS_BANDS: usize = 3;
+
+/// Audio sensor backed by a `parec` capture thread.
+pub struct AudioSensor {
+    /// Last analysis result from the capture thread
+    latest: Arc<Mutex<AudioLevels>>,
+}
+
+impl AudioSensor {
+    /// Starts capturing from `source`, or the default output monitor if empty.
+    /// Returns None if `parec` cannot be started.
+    pub fn start(source: &str) -> Option<Self> {
+        let source = if source.is_empty() {
+            "@DEFAULT_MONITOR@".to_string()
+        } else {
+            source.to_string()
+        };
+
+        // Fail early if parec is missing rather than retrying forever
+        let child = spawn_capture(&source)?;
+        debug!("Capturing audio from {}", source);
+
+        let latest = Arc::new(Mutex::new(AudioLevels::default()));
+        let worker = latest.clone();
+        thread::Builder::new()
+            .name("audio-capture".to_string())
+            .spawn(move || {
+                let mut analyzer = Analyzer::new();
+                let mut child = Some(child);
+                loop {
+                    if let Some(mut capture) = child.take() {
+                        analyzer.run(&mut capture, &worker);
+                        let _ = capture.kill();
+                        let _ = capture.wait();
+                        *worker.lock().unwrap() = AudioLevels {
+                            beats: analyzer.beats,
+                            ..AudioLevels::default()
+                        };
+                    }
+                    // The sound server may have restarted; try again shortly
+                    thread::sleep(Duration::from_secs(5));
+                    child = spawn_capture(&source);
+                }
+            })
+            .ok()?;
+
+        Some(Self { latest })
+    }
+
+    /// Returns the latest audio levels.
+    pub fn levels(&self) -> AudioLevels {
+        self.latest.lock().unwrap().clone()
+    }
+}
+
+/// Spawns `parec` writing raw mono samples to stdout.
+fn spawn_capture(source: &str) -> Option<Child> {
+    let rate = format!("--rate={}", SAMPLE_RATE);
+    let latency = format!("--latency-msec={}", BLOCK_SIZE as u32 * 1000 / SAMPLE_RATE);
+    match Command::new("parec")
+        .args([
+            "--raw",
+            "--format=s16le",
+            "--channels=1",
+            &rate,
+            &latency,
+            "-d",
+            source,
+        ])
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+    {
+        Ok(child) => Some(child),
+        Err(e) => {
+            warn!("Failed to start parec: {}", e);
+            None
+        }
+    }
+}
+
+/// FFT state and beat tracking carried across blocks.
+struct Analyzer {
+    fft: Arc<dyn rustfft::Fft<f32>>,
+    window: Vec<f32>,
+    /// FFT bin range for each band
+    band_bins: Vec<(usize, usize)>,
+    /// Slow moving average of bass energy
+    bass_average: f32,
+    /// Blocks since the last beat, to avoid double triggers
+    since_beat: u32,
+    beats: u64,
+}
+
+impl Analyzer {
+    fn new() -> Self {
+        let fft = FftPlanner::new().plan_fft_forward(BLOCK_SIZE);
+        let window = (0..BLOCK_SIZE)
+            .map(|i| {
+                let phase = 2.0 * std::f32::consts::PI * i as f32 / (BLOCK_SIZE - 1) as f32;
+                0.5 - 0.5 * phase.cos()
+            })
+            .collect();
+
+        // Log-spaced band edges mapped onto FFT bins
+        let bin_hz = SAMPLE_RATE as f32 / BLOCK_SIZE as f32;
+        let ratio = (MAX_FREQ / MIN_FREQ).powf(1.0 / BAND_COUNT as f32);
+        let band_bins = (0..BAND_COUNT)
+            .map(|i| {
+                let lo = MIN_FREQ * ratio.powi(i as i32);
+                let hi = lo * ratio;
+                let start = ((lo / bin_hz) as usize).max(1);
+                let end = ((hi / bin_hz) as usize).max(start + 1);
+                (start, end.min(BLOCK_SIZE / 2))
+            })
+            .collect();
+
+        Self {
+            fft,
+            window,
+            band_bins,
+            bass_average: 0.0,
+            since_beat: 0,
+            beats: 0,
+        }
+    }
+
+    /// Reads blocks from the capture until it ends, publishing each result.
+    fn run(&mut self, child: &mut Child, latest: &Mutex<AudioLevels>) {
+        let Some(stdout) = child.stdout.as_mut() else {
+            return;
+        };
+        let mut raw = vec![0u8; BLOCK_SIZE * 2];
+        while stdout.read_exact(&mut raw).is_ok() {
+            let samples: Vec<f32> = raw
+                .chunks_exact(2)
+                .map(|b| i16::from_le_bytes([b[0], b[1]]) as f32 / 32768.0)
+                .collect();
+            *latest.lock().unwrap() = self.analyze(&samples);
+        }
+        debug!("Audio capture ended");
+    }
+
+    /// Computes level, bands and beat state for one block.
+    fn analyze(&mut self, samples: &[f32]) -> AudioLevels {
+        let rms = (samples.iter().map(|s| s * s).sum::<f32>() / samples.len() as f32).sqrt();
+
+        let mut buffer: Vec<Complex<f32>> = samples
+            .iter()
+            .zip(&self.window)
+            .map(|(s, w)| Complex::new(s * w, 0.0))
+            .collect();
+        self.fft.process(&mut buffer);
+
+        // Hann window halves the amplitude; scale so a full-scale sine is ~0dB
+        let scale = 4.0 / BLOCK_SIZE as f32;
+        let bands: Vec<f32> = self
+            .band_bins
+            .iter()
+            .map(|&(start, end)| {
+                let peak = buffer[start..end]
+                    .iter()
+                    .map(|c| c.norm() * scale)
+                    .fold(0.0, f32::max);
+                normalize_db(peak)
+            })
+            .collect();
+
+        // A beat is a bass spike well above the recent average
+        let bass = bands[..BASS_BANDS].iter().sum::<f32>() / BASS_BANDS as f32;
+        self.since_beat = self.since_beat.saturating_add(1);
+        if bass > 0.3 && bass > self.bass_average * 1.3 && self.since_beat >= 5 {
+            self.beats += 1;
+            self.since_beat = 0;
+        }
+        self.bass_average = self.bass_average * 0.9 + bass * 0.1;
+
+        AudioLevels {
+            level: normalize_db(rms * std::f32::consts::SQRT_2),
+            bands,
+            beats: self.beats,
+        }
+    }
+}
+
+/// Maps a linear amplitude onto 0.0-1.0 between `FLOOR_DB` and 0dBFS.
+fn normalize_db(amplitude: f32) -> f32 {
+    if amplitude <= 0.0 {
+        return 0.0;
+    }
+    let db = 20.0 * amplitude.log10();
+    ((db - FLOOR_DB) / -FLOOR_DB).clamp(0.0, 1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// One block of sines centred on the given FFT bins.
+    fn tone(bins: &[usize], amplitude: f32) -> Vec<f32> {
+        (0..BLOCK_SIZE)
+            .map(|i| {
+                bins.iter()
+                    .map(|&bin| {
+                        let phase = 2.0 * std::f32::consts::PI * (bin * i) as f32;
+                        amplitude * (phase / BLOCK_SIZE as f32).sin()
+                    })
+                    .sum()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_normalize_db() {
+        assert_eq!(normalize_db(0.0), 0.0);
+        assert_eq!(normalize_db(0.001), 0.0);
+        assert!((normalize_db(10f32.powf(-1.5)) - 0.5).abs() < 1e-4);
+        assert_eq!(normalize_db(1.0), 1.0);
+        assert_eq!(normalize_db(2.0), 1.0);
+    }
+
+    #[test]
+    fn test_analyze() {
+        let mut analyzer = Analyzer::new();
+        let silence = analyzer.analyze(&[0.0; BLOCK_SIZE]);
+        assert_eq!(silence.level, 0.0);
+        assert_eq!(silence.bands, vec![0.0; BAND_COUNT]);
+
+        // A full-scale 86Hz sine peaks in the third band, with window
+        // leakage into the next one
+        let levels = analyzer.analyze(&tone(&[4], 1.0));
+        assert!(levels.level > 0.99);
+        assert!(levels.bands[2] > 0.99);
+        assert!(levels.bands[3] < levels.bands[2]);
+        assert!(levels.bands[..2].iter().all(|&band| band == 0.0));
+        assert!(levels.bands[4..].iter().all(|&band| band == 0.0));
+    }
+
+    #[test]
+    fn test_beats() {
+        let mut analyzer = Analyzer::new();
+        let bass = tone(&[1, 2, 4], 0.3);
+        let mut beats = |block: &[f32], blocks: usize| {
+            (0..blocks).fold(0, |_, _| analyzer.analyze(block).beats)
+        };
+
+        assert_eq!(beats(&[0.0; BLOCK_SIZE], 10), 0);
+        // No second beat within five blocks of the first
+        assert_eq!(beats(&bass, 4), 1);
+        assert_eq!(beats(&[0.0; BLOCK_SIZE], 20), 1);
+        assert_eq!(beats(&bass, 1), 2);
+    }
+}
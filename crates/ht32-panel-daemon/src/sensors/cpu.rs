@@ -1,6 +1,8 @@
 //! CPU usage sensor.
 
+use super::data::HISTORY_SIZE;
 use super::Sensor;
+use std::collections::VecDeque;
 use std::fs;
 use std::time::Instant;
 
@@ -11,6 +13,17 @@ pub struct CpuSensor {
     last_total: u64,
     last_sample: f64,
     last_time: Option<Instant>,
+    /// Recent usage samples, oldest first
+    history: VecDeque<f64>,
+}
+
+/// A CPU usage sample with its recent history.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CpuReading {
+    /// Current usage percentage (0-100)
+    pub percent: f64,
+    /// Usage over the last [`HISTORY_SIZE`] samples, oldest first
+    pub history: VecDeque<f64>,
 }
 
 impl CpuSensor {
@@ -22,6 +35,20 @@ impl CpuSensor {
             last_total: 0,
             last_sample: 0.0,
             last_time: None,
+            history: VecDeque::with_capacity(HISTORY_SIZE),
+        }
+    }
+
+    /// Samples the usage and records it in the history.
+    pub fn read(&mut self) -> CpuReading {
+        let percent = self.sample();
+        if self.history.len() == HISTORY_SIZE {
+            self.history.pop_front();
+        }
+        self.history.push_back(percent);
+        CpuReading {
+            percent,
+            history: self.history.clone(),
         }
     }
 
@@ -1,18 +1,23 @@
 //! Disk I/O sensor.
 
-use super::data::HISTORY_SIZE;
+use super::data::{DiskDeviceRate, HISTORY_SIZE};
 use super::Sensor;
-use std::collections::VecDeque;
+use std::collections::{HashMap, VecDeque};
 use std::fs;
 use std::time::Instant;
 
 /// Disk I/O sensor that reads from /proc/diskstats.
+///
+/// Tracks one or more block devices. The aggregate rates and histories are
+/// the sum over all tracked devices; per-device rates are kept alongside.
 pub struct DiskSensor {
     name: String,
-    device: String,
-    last_read_sectors: u64,
-    last_write_sectors: u64,
+    devices: Vec<String>,
+    /// Last (sectors read, sectors written) per device
+    last_sectors: HashMap<String, (u64, u64)>,
     last_time: Option<Instant>,
+    /// Current rates per tracked device
+    device_rates: Vec<DiskDeviceRate>,
     last_read_rate: f64,
     last_write_rate: f64,
     /// History of combined I/O rates (bytes/sec)
@@ -23,15 +28,26 @@ pub struct DiskSensor {
     write_history: VecDeque<f64>,
 }
 
+/// Rates of the tracked devices at one sample.
+#[derive(Debug, Clone, Default)]
+pub struct DiskReading {
+    pub read_rate: f64,
+    pub write_rate: f64,
+    pub history: VecDeque<f64>,
+    pub read_history: VecDeque<f64>,
+    pub write_history: VecDeque<f64>,
+    pub devices: Vec<DiskDeviceRate>,
+}
+
 impl DiskSensor {
     /// Creates a new disk sensor for a specific device (e.g., "sda", "nvme0n1").
     pub fn new(device: &str) -> Self {
         Self {
             name: format!("disk_{}", device),
-            device: device.to_string(),
-            last_read_sectors: 0,
-            last_write_sectors: 0,
+            devices: vec![device.to_string()],
+            last_sectors: HashMap::new(),
             last_time: None,
+            device_rates: Vec::new(),
             last_read_rate: 0.0,
             last_write_rate: 0.0,
             history: VecDeque::with_capacity(HISTORY_SIZE),
@@ -47,6 +63,59 @@ impl DiskSensor {
         Self::new(&device)
     }
 
+    /// Sets the devices to track. An empty list auto-detects the primary disk.
+    pub fn set_devices(&mut self, devices: &[String]) {
+        self.devices = if devices.is_empty() {
+            vec![Self::detect_primary_disk().unwrap_or_else(|| "sda".to_string())]
+        } else {
+            devices.to_vec()
+        };
+        self.name = format!("disk_{}", self.devices.join("+"));
+        self.last_sectors.clear();
+        self.last_time = None;
+        self.last_read_rate = 0.0;
+        self.last_write_rate = 0.0;
+        self.device_rates.clear();
+        self.history.clear();
+        self.read_history.clear();
+        self.write_history.clear();
+    }
+
+    /// Samples the tracked devices and returns the reading.
+    pub fn read(&mut self) -> DiskReading {
+        self.sample();
+        DiskReading {
+            read_rate: self.last_read_rate,
+            write_rate: self.last_write_rate,
+            history: self.history.clone(),
+            read_history: self.read_history.clone(),
+            write_history: self.write_history.clone(),
+            devices: self.device_rates.clone(),
+        }
+    }
+
+    /// Returns the tracked devices.
+    pub fn devices(&self) -> &[String] {
+        &self.devices
+    }
+
+    /// Lists whole block devices that can be tracked.
+    pub fn list_devices() -> Vec<String> {
+        let mut devices = Vec::new();
+        if let Ok(entries) = fs::read_dir("/sys/block") {
+            for entry in entries.flatten() {
+                let name = entry.file_name().to_string_lossy().to_string();
+                // Skip loop, RAM and compressed swap devices
+                if name.starts_with("loop") || name.starts_with("ram") || name.starts_with("zram") {
+                    continue;
+                }
+                devices.push(name);
+            }
+        }
+        devices.sort();
+        devices
+    }
+
     /// Detects the primary disk device.
     fn detect_primary_disk() -> Option<String> {
         // Try common disk device names in order of preference
@@ -68,21 +137,15 @@ impl DiskSensor {
     /// Fields: major minor name reads_completed reads_merged sectors_read time_reading
     ///         writes_completed writes_merged sectors_written time_writing
     ///         ios_in_progress time_doing_io weighted_time_doing_io
-    fn read_stats(&self) -> Option<(u64, u64)> {
+    fn read_stats(&self) -> Option<HashMap<String, (u64, u64)>> {
         let content = fs::read_to_string("/proc/diskstats").ok()?;
+        let stats = parse_diskstats(&content, &self.devices);
+        (!stats.is_empty()).then_some(stats)
+    }
 
-        for line in content.lines() {
-            let parts: Vec<&str> = line.split_whitespace().collect();
-            if parts.len() >= 10 && parts[2] == self.device {
-                // sectors_read is field 5 (0-indexed)
-                // sectors_written is field 9 (0-indexed)
-                let read_sectors: u64 = parts[5].parse().ok()?;
-                let write_sectors: u64 = parts[9].parse().ok()?;
-                return Some((read_sectors, write_sectors));
-            }
-        }
-
-        None
+    /// Returns the current rates of each tracked device.
+    pub fn device_rates(&self) -> &[DiskDeviceRate] {
+        &self.device_rates
     }
 
     /// Returns the current read rate in bytes/second.
@@ -117,17 +180,14 @@ impl Sensor for DiskSensor {
     }
 
     fn sample(&mut self) -> f64 {
-        if let Some((read_sectors, write_sectors)) = self.read_stats() {
+        if let Some(stats) = self.read_stats() {
             if let Some(last_time) = self.last_time {
                 let elapsed = last_time.elapsed().as_secs_f64();
                 if elapsed > 0.0 {
-                    let read_delta = read_sectors.saturating_sub(self.last_read_sectors);
-                    let write_delta = write_sectors.saturating_sub(self.last_write_sectors);
-
-                    // Sectors are typically 512 bytes
-                    const SECTOR_SIZE: f64 = 512.0;
-                    self.last_read_rate = (read_delta as f64 * SECTOR_SIZE) / elapsed;
-                    self.last_write_rate = (write_delta as f64 * SECTOR_SIZE) / elapsed;
+                    self.device_rates =
+                        device_rates(&self.devices, &self.last_sectors, &stats, elapsed);
+                    self.last_read_rate = self.device_rates.iter().map(|d| d.read_rate).sum();
+                    self.last_write_rate = self.device_rates.iter().map(|d| d.write_rate).sum();
 
                     // Record combined rate in history
                     let combined = self.last_read_rate + self.last_write_rate;
@@ -148,8 +208,7 @@ impl Sensor for DiskSensor {
                 }
             }
 
-            self.last_read_sectors = read_sectors;
-            self.last_write_sectors = write_sectors;
+            self.last_sectors = stats;
             self.last_time = Some(Instant::now());
         }
 
@@ -169,3 +228,103 @@ impl Sensor for DiskSensor {
         "KB/s"
     }
 }
+
+/// Picks the (sectors read, sectors written) counters of `devices` out of
+/// /proc/diskstats contents.
+fn parse_diskstats(content: &str, devices: &[String]) -> HashMap<String, (u64, u64)> {
+    let mut stats = HashMap::new();
+    for line in content.lines() {
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        if parts.len() >= 10 && devices.iter().any(|d| d == parts[2]) {
+            // sectors_read is field 5 (0-indexed)
+            // sectors_written is field 9 (0-indexed)
+            let (Ok(read_sectors), Ok(write_sectors)) =
+                (parts[5].parse::<u64>(), parts[9].parse::<u64>())
+            else {
+                continue;
+            };
+            stats.insert(parts[2].to_string(), (read_sectors, write_sectors));
+        }
+    }
+    stats
+}
+
+/// Computes the rates of `devices` between two diskstats samples taken
+/// `elapsed` seconds apart. Devices new in `current` start at zero.
+fn device_rates(
+    devices: &[String],
+    last: &HashMap<String, (u64, u64)>,
+    current: &HashMap<String, (u64, u64)>,
+    elapsed: f64,
+) -> Vec<DiskDeviceRate> {
+    // Sectors in /proc/diskstats are always 512 bytes
+    const SECTOR_SIZE: f64 = 512.0;
+    devices
+        .iter()
+        .filter_map(|device| {
+            let (read, write) = current.get(device)?;
+            let (last_read, last_write) = last.get(device).copied().unwrap_or((*read, *write));
+            Some(DiskDeviceRate {
+                device: device.clone(),
+                read_rate: read.saturating_sub(last_read) as f64 * SECTOR_SIZE / elapsed,
+                write_rate: write.saturating_sub(last_write) as f64 * SECTOR_SIZE / elapsed,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DISKSTATS: &str = "\
+ 259       0 nvme0n1 81234 1234 4096000 20000 51234 4321 2048000 30000 0 40000 50000
+ 259       1 nvme0n1p1 120 0 8000 10 2 0 16 0 0 10 10
+   8       0 sda 5000 10 800000 3000 2000 20 400000 4000 0 5000 7000
+   7       0 loop0 50 0 1000 5 0 0 0 0 0 5 5
+";
+
+    #[test]
+    fn test_parse_diskstats() {
+        let devices = vec!["nvme0n1".to_string(), "sda".to_string(), "sdb".to_string()];
+        let stats = parse_diskstats(DISKSTATS, &devices);
+        assert_eq!(stats.len(), 2);
+        assert_eq!(stats["nvme0n1"], (4096000, 2048000));
+        assert_eq!(stats["sda"], (800000, 400000));
+        assert!(parse_diskstats("garbage\n", &devices).is_empty());
+    }
+
+    #[test]
+    fn test_device_rates() {
+        let devices = vec!["nvme0n1".to_string(), "sda".to_string()];
+        let last = HashMap::from([("nvme0n1".to_string(), (1000, 2000))]);
+        let current = HashMap::from([
+            ("nvme0n1".to_string(), (3000, 2500)),
+            ("sda".to_string(), (500, 500)),
+        ]);
+        let rates = device_rates(&devices, &last, &current, 2.0);
+        assert_eq!(
+            rates,
+            vec![
+                DiskDeviceRate {
+                    device: "nvme0n1".to_string(),
+                    read_rate: 512_000.0,
+                    write_rate: 128_000.0,
+                },
+                // No earlier sample yet
+                DiskDeviceRate {
+                    device: "sda".to_string(),
+                    read_rate: 0.0,
+                    write_rate: 0.0,
+                },
+            ]
+        );
+
+        // A counter reset reads as idle rather than underflowing
+        let reset = HashMap::from([("nvme0n1".to_string(), (10, 10))]);
+        assert_eq!(
+            device_rates(&devices, &last, &reset, 1.0)[0].total_rate(),
+            0.0
+        );
+    }
+}
@@ -0,0 +1,269 @@
+//! Pi-hole and AdGuard Home blocking statistics.
+//!
+//! Polls the service's HTTP API on a background thread. Pi-hole v6 is tried
+//! first (`/api/stats/summary`, with session login when a password is set),
+//! falling back to the v5 `api.php` endpoint.
+
+use std::time::Duration;
+
+use anyhow::{bail, Context, Result};
+
+use super::data::DnsStats;
+use super::http;
+use super::poller::Poller;
+use crate::config::{DnsFilterConfig, DnsFilterKind};
+
+/// DNS filter sensor backed by a background polling thread.
+pub struct DnsFilterSensor {
+    /// Last result (None until the first successful poll)
+    latest: Poller<Option<DnsStats>>,
+}
+
+impl DnsFilterSensor {
+    /// Starts polling the configured service. Returns None if disabled.
+    pub fn start(config: &DnsFilterConfig) -> Option<Self> {
+        if !config.enable {
+            return None;
+        }
+
+        let interval = Duration::from_secs(config.interval.max(5));
+        let mut client = Client::new(config.clone());
+        let latest = Poller::start_fallible("dns-filter", interval, move || client.poll())?;
+
+        Some(Self { latest })
+    }
+
+    /// Returns the last known statistics.
+    pub fn stats(&self) -> Option<DnsStats> {
+        self.latest.latest()
+    }
+}
+
+/// Polling state for one service.
+struct Client {
+    config: DnsFilterConfig,
+    /// Base URL without a trailing slash
+    base: String,
+    /// Pi-hole v6 session ID
+    sid: Option<String>,
+}
+
+impl Client {
+    fn new(config: DnsFilterConfig) -> Self {
+        let base = config.url.trim_end_matches('/').to_string();
+        Self {
+            config,
+            base,
+            sid: None,
+        }
+    }
+
+    fn poll(&mut self) -> Result<DnsStats> {
+        match self.config.kind {
+            DnsFilterKind::Pihole => self.poll_pihole(),
+            DnsFilterKind::Adguard => self.poll_adguard(),
+        }
+    }
+
+    /// Polls Pi-hole v6, logging in if required, or falls back to v5.
+    fn poll_pihole(&mut self) -> Result<DnsStats> {
+        let url = format!("{}/api/stats/summary", self.base);
+        let mut response = self.pihole_get(&url)?;
+        if response.status == 401 && !self.config.password.is_empty() {
+            self.pihole_login()?;
+            response = self.pihole_get(&url)?;
+        }
+        if response.status == 404 {
+            return self.poll_pihole_v5();
+        }
+
+        let json = response.check()?.json()?;
+        let queries = &json["queries"];
+        let total = queries["total"].as_u64().context("Missing query count")?;
+        let blocked = queries["blocked"].as_u64().unwrap_or(0);
+
+        let enabled = self
+            .pihole_get(&format!("{}/api/dns/blocking", self.base))
+            .and_then(|r| r.check()?.json())
+            .map(|j| j["blocking"].as_str() != Some("disabled"))
+            .unwrap_or(true);
+
+        Ok(DnsStats::new(total, blocked, enabled))
+    }
+
+    fn pihole_get(&self, url: &str) -> Result<http::Response> {
+        match &self.sid {
+            Some(sid) => http::get(url, &[("sid", sid)]),
+            None => http::get(url, &[]),
+        }
+    }
+
+    /// Creates a Pi-hole v6 session.
+    fn pihole_login(&mut self) -> Result<()> {
+        let body = serde_json::json!({ "password": self.config.password }).to_string();
+        let json = http::post_json(&format!("{}/api/auth", self.base), &[], &body)?
+            .check()
+            .context("Pi-hole login failed")?
+            .json()?;
+        let sid = json["session"]["sid"]
+            .as_str()
+            .context("Pi-hole login returned no session")?;
+        self.sid = Some(sid.to_string());
+        Ok(())
+    }
+
+    /// Polls the Pi-hole v5 API (`password` is the API token).
+    fn poll_pihole_v5(&self) -> Result<DnsStats> {
+        let mut url = format!("{}/admin/api.php?summaryRaw", self.base);
+        if !self.config.password.is_empty() {
+            url.push_str(&format!(
+                "&auth={}",
+                http::url_encode(&self.config.password)
+            ));
+        }
+        let json = http::get(&url, &[])?.check()?.json()?;
+        let total = json["dns_queries_today"]
+            .as_u64()
+            .context("Missing query count (is the API token set?)")?;
+        let blocked = json["ads_blocked_today"].as_u64().unwrap_or(0);
+        let enabled = json["status"].as_str() != Some("disabled");
+        Ok(DnsStats::new(total, blocked, enabled))
+    }
+
+    /// Polls AdGuard Home's statistics and status.
+    fn poll_adguard(&self) -> Result<DnsStats> {
+        let auth = http::basic_auth(&self.config.username, &self.config.password);
+        let headers = [("Authorization", auth.as_str())];
+        let stats = http::get(&format!("{}/control/stats", self.base), &headers)?;
+        if stats.status == 401 || stats.status == 403 {
+            bail!("AdGuard Home rejected the credentials");
+        }
+        let json = stats.check()?.json()?;
+        let total = json["num_dns_queries"]
+            .as_u64()
+            .context("Missing query count")?;
+        let blocked = json["num_blocked_filtering"].as_u64().unwrap_or(0);
+
+        let enabled = http::get(&format!("{}/control/status", self.base), &headers)
+            .and_then(|r| r.check()?.json())
+            .map(|j| j["protection_enabled"].as_bool().unwrap_or(true))
+            .unwrap_or(true);
+
+        Ok(DnsStats::new(total, blocked, enabled))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::thread;
+
+    /// Serves requests on a local port, answering each with `handler`'s
+    /// (status, body) for the request head. Returns the base URL.
+    fn serve(handler: fn(&str) -> (u16, &'static str)) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                let mut request = Vec::new();
+                let mut buf = [0u8; 1024];
+                // Read the head and any body so closing does not reset
+                let complete = |request: &[u8]| {
+                    let text = String::from_utf8_lossy(request);
+                    text.split_once("\r\n\r\n").is_some_and(|(head, body)| {
+                        let length = head
+                            .lines()
+                            .find_map(|line| line.strip_prefix("Content-Length: "))
+                            .map_or(0, |len| len.parse().unwrap());
+                        body.len() >= length
+                    })
+                };
+                while !complete(&request) {
+                    let n = stream.read(&mut buf).unwrap();
+                    request.extend_from_slice(&buf[..n]);
+                }
+                let (status, body) = handler(&String::from_utf8_lossy(&request));
+                let _ = write!(
+                    stream,
+                    "HTTP/1.0 {} X\r\nContent-Length: {}\r\n\r\n{}",
+                    status,
+                    body.len(),
+                    body
+                );
+            }
+        });
+        format!("http://127.0.0.1:{}/", port)
+    }
+
+    fn config(kind: DnsFilterKind, url: String, username: &str, password: &str) -> DnsFilterConfig {
+        DnsFilterConfig {
+            enable: true,
+            kind,
+            url,
+            username: username.to_string(),
+            password: password.to_string(),
+            ..DnsFilterConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_pihole_v6_login() {
+        let url = serve(|request| {
+            if request.starts_with("POST /api/auth ") {
+                (200, r#"{"session": {"sid": "abc"}}"#)
+            } else if !request.contains("sid: abc") {
+                (401, "{}")
+            } else if request.starts_with("GET /api/stats/summary ") {
+                (200, r#"{"queries": {"total": 200, "blocked": 50}}"#)
+            } else if request.starts_with("GET /api/dns/blocking ") {
+                (200, r#"{"blocking": "disabled"}"#)
+            } else {
+                (404, "")
+            }
+        });
+        let mut client = Client::new(config(DnsFilterKind::Pihole, url, "", "secret"));
+        assert_eq!(client.poll().unwrap(), DnsStats::new(200, 50, false));
+        assert_eq!(client.sid.as_deref(), Some("abc"));
+    }
+
+    #[test]
+    fn test_pihole_v5_fallback() {
+        let url = serve(|request| {
+            if request.starts_with("GET /admin/api.php?summaryRaw&auth=a%2Bb ") {
+                (200, r#"{"dns_queries_today": 10, "ads_blocked_today": 1}"#)
+            } else {
+                (404, "")
+            }
+        });
+        let mut client = Client::new(config(DnsFilterKind::Pihole, url, "", "a+b"));
+        assert_eq!(client.poll().unwrap(), DnsStats::new(10, 1, true));
+    }
+
+    #[test]
+    fn test_adguard() {
+        let url = serve(|request| {
+            if !request.contains("Authorization: Basic dXNlcjpwYXNz") {
+                (401, "")
+            } else if request.starts_with("GET /control/stats ") {
+                (
+                    200,
+                    r#"{"num_dns_queries": 40, "num_blocked_filtering": 10}"#,
+                )
+            } else if request.starts_with("GET /control/status ") {
+                (200, r#"{"protection_enabled": true}"#)
+            } else {
+                (404, "")
+            }
+        });
+        let mut client = Client::new(config(DnsFilterKind::Adguard, url.clone(), "user", "pass"));
+        let stats = client.poll().unwrap();
+        assert_eq!(stats, DnsStats::new(40, 10, true));
+        assert_eq!(stats.percent, 25.0);
+
+        let mut client = Client::new(config(DnsFilterKind::Adguard, url, "user", "wrong"));
+        assert!(client.poll().is_err());
+    }
+}
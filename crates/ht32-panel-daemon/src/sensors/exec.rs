@@ -0,0 +1,238 @@
+//! Command output as named custom metrics.
+//!
+//! Each configured command runs periodically on its own background thread.
+//! Every `key=value` line it prints becomes a metric; values with a leading
+//! number are numeric (any suffix is the unit), others are shown as text.
+
+use std::collections::BTreeMap;
+use std::io::Read;
+use std::os::unix::process::CommandExt;
+use std::process::{Command, Stdio};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use anyhow::{bail, Context, Result};
+use tracing::debug;
+
+use super::data::Metric;
+use super::poller::Poller;
+use crate::config::ExecSourceConfig;
+
+/// Largest amount of output read from a command.
+const MAX_OUTPUT: u64 = 64 * 1024;
+
+/// Exec sensor running all configured commands.
+pub struct ExecSensor {
+    /// Latest value of every metric, keyed by name
+    latest: Poller<BTreeMap<String, Metric>>,
+}
+
+impl ExecSensor {
+    /// Starts running `sources`. Returns None if none are configured.
+    pub fn start(sources: &[ExecSourceConfig]) -> Option<Self> {
+        if sources.is_empty() {
+            return None;
+        }
+
+        let latest = Poller::new(BTreeMap::new());
+        for source in sources {
+            let config = source.clone();
+            // Names produced by the previous run, removed if the next run fails
+            let mut previous = Vec::new();
+            let interval = Duration::from_secs(source.interval.max(1));
+            latest.spawn("exec-sensor", interval, move |latest| {
+                run_once(&config, &mut previous, latest)
+            });
+        }
+
+        Some(Self { latest })
+    }
+
+    /// Returns the latest metric values.
+    pub fn metrics(&self) -> BTreeMap<String, Metric> {
+        self.latest.latest()
+    }
+}
+
+/// Runs one command, replacing the metrics of its `previous` run.
+fn run_once(
+    source: &ExecSourceConfig,
+    previous: &mut Vec<String>,
+    latest: &Mutex<BTreeMap<String, Metric>>,
+) {
+    let metrics = match run(source) {
+        Ok(output) => parse_output(&output, &source.prefix),
+        Err(e) => {
+            debug!("Exec sensor '{}' failed: {:#}", source.command, e);
+            BTreeMap::new()
+        }
+    };
+    let mut latest = latest.lock().unwrap();
+    for name in previous.drain(..) {
+        latest.remove(&name);
+    }
+    *previous = metrics.keys().cloned().collect();
+    latest.extend(metrics);
+}
+
+/// Runs the command with its sandboxing options and returns its stdout.
+fn run(source: &ExecSourceConfig) -> Result<String> {
+    let mut argv: Vec<String> = source.wrapper.clone();
+    if source.nice > 0 {
+        argv.extend([
+            "nice".to_string(),
+            "-n".to_string(),
+            source.nice.to_string(),
+        ]);
+    }
+    argv.extend(["sh".to_string(), "-c".to_string(), source.command.clone()]);
+
+    let mut command = Command::new(&argv[0]);
+    command
+        .args(&argv[1..])
+        .current_dir(&source.working_dir)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        // In its own process group so a timeout also kills what it started
+        .process_group(0);
+    if source.clear_env {
+        command
+            .env_clear()
+            .env("PATH", "/usr/local/bin:/usr/bin:/bin");
+    }
+    let mut child = command
+        .spawn()
+        .with_context(|| format!("Failed to run {}", argv[0]))?;
+
+    // Read on another thread so a chatty command cannot block on a full pipe
+    let stdout = child.stdout.take().context("No stdout")?;
+    let reader = thread::spawn(move || {
+        let mut output = String::new();
+        let _ = stdout.take(MAX_OUTPUT).read_to_string(&mut output);
+        output
+    });
+
+    let deadline = Instant::now() + Duration::from_secs(source.timeout.max(1));
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+        if Instant::now() >= deadline {
+            // SAFETY: kill() has no memory safety requirements; the group ID
+            // is the child's PID, which is not reaped until wait() below
+            unsafe {
+                libc::kill(-(child.id() as libc::pid_t), libc::SIGKILL);
+            }
+            let _ = child.wait();
+            bail!("Timed out after {}s", source.timeout.max(1));
+        }
+        thread::sleep(Duration::from_millis(50));
+    };
+    let output = reader.join().unwrap_or_default();
+    if !status.success() {
+        bail!("Exited with {}", status);
+    }
+    Ok(output)
+}
+
+/// Parses `key=value` lines, ignoring blank lines and `#` comments.
+fn parse_output(output: &str, prefix: &str) -> BTreeMap<String, Metric> {
+    output
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| line.split_once('='))
+        .filter(|(key, _)| !key.trim().is_empty())
+        .map(|(key, value)| {
+            (
+                format!("{}{}", prefix, key.trim()),
+                parse_value(value.trim()),
+            )
+        })
+        .collect()
+}
+
+/// Splits a value like "42.5°C" or "1.2e5 B" into a number and unit, or
+/// keeps it as text.
+fn parse_value(value: &str) -> Metric {
+    let end = number_len(value);
+    match value[..end].parse::<f64>() {
+        Ok(number) => Metric::new(number, value[end..].trim(), None, None),
+        Err(_) => Metric::text(value),
+    }
+}
+
+/// Returns the length of the leading number in `value`: an optional sign,
+/// digits and dots, and an exponent if digits follow the `e`.
+fn number_len(value: &str) -> usize {
+    let bytes = value.as_bytes();
+    let digits_from = |start: usize| {
+        start
+            + bytes[start.min(bytes.len())..]
+                .iter()
+                .take_while(|b| b.is_ascii_digit() || **b == b'.')
+                .count()
+    };
+    let sign = usize::from(matches!(bytes.first(), Some(b'-' | b'+')));
+    let end = digits_from(sign);
+    if !matches!(bytes.get(end), Some(b'e' | b'E')) {
+        return end;
+    }
+    let exponent = end + 1 + usize::from(matches!(bytes.get(end + 1), Some(b'-' | b'+')));
+    if bytes.get(exponent).is_some_and(u8::is_ascii_digit) {
+        exponent
+            + bytes[exponent..]
+                .iter()
+                .take_while(|b| b.is_ascii_digit())
+                .count()
+    } else {
+        end
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_output() {
+        let output = "# UPS status\ncharge=97%\nload = 12.5\nstatus=OL\n\nnot a metric\n";
+        let metrics = parse_output(output, "ups_");
+        assert_eq!(metrics.len(), 3);
+        assert_eq!(metrics["ups_charge"].value, 97.0);
+        assert_eq!(metrics["ups_charge"].unit, "%");
+        assert_eq!(metrics["ups_load"].format(), "12.5");
+        assert_eq!(metrics["ups_status"].format(), "OL");
+    }
+
+    #[test]
+    fn test_parse_value() {
+        let metric = parse_value("1e5");
+        assert_eq!((metric.value, metric.unit.as_str()), (100_000.0, ""));
+        let metric = parse_value("-2.5E-3 V");
+        assert_eq!((metric.value, metric.unit.as_str()), (-0.0025, "V"));
+        let metric = parse_value("3eggs");
+        assert_eq!((metric.value, metric.unit.as_str()), (3.0, "eggs"));
+        let metric = parse_value("42.5°C");
+        assert_eq!((metric.value, metric.unit.as_str()), (42.5, "°C"));
+        assert_eq!(parse_value("e5").format(), "e5");
+        assert_eq!(parse_value("-").format(), "-");
+    }
+
+    #[test]
+    fn test_timeout_kills_process_group() {
+        let marker = std::env::temp_dir().join(format!("ht32-exec-{}", std::process::id()));
+        let _ = std::fs::remove_file(&marker);
+        // The backgrounded sleep outlives `sh` unless the whole group is killed
+        let source: ExecSourceConfig = toml::from_str(&format!(
+            "command = \"(sleep 2; touch {}) & wait\"\ntimeout = 1",
+            marker.display()
+        ))
+        .unwrap();
+        assert!(run(&source).is_err());
+        thread::sleep(Duration::from_millis(1500));
+        assert!(!marker.exists());
+    }
+}
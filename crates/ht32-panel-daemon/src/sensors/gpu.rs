@@ -0,0 +1,153 @@
+//! GPU utilization, temperature, fan and power sensor.
+//!
+//! NVIDIA cards are queried through `nvidia-smi`; AMD cards through the
+//! amdgpu sysfs and hwmon files. Readings are taken on a background thread
+//! because `nvidia-smi` can take a noticeable time to answer.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::Duration;
+
+use tracing::debug;
+
+use super::data::GpuStats;
+use super::poller::Poller;
+
+/// How often the GPU is queried.
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Detected GPU backend.
+#[derive(Debug, Clone)]
+enum Backend {
+    /// `nvidia-smi` is available
+    Nvidia,
+    /// amdgpu device directory (`/sys/class/drm/cardN/device`)
+    Amd(PathBuf),
+}
+
+impl Backend {
+    /// Detects the first supported GPU.
+    fn detect() -> Option<Self> {
+        if Path::new("/proc/driver/nvidia/version").exists() {
+            return Some(Self::Nvidia);
+        }
+        let mut cards: Vec<PathBuf> = fs::read_dir("/sys/class/drm")
+            .ok()?
+            .flatten()
+            .map(|e| e.path())
+            .filter(|p| {
+                p.file_name()
+                    .and_then(|n| n.to_str())
+                    .is_some_and(|n| n.starts_with("card") && !n.contains('-'))
+            })
+            .collect();
+        cards.sort();
+        cards
+            .into_iter()
+            .map(|card| card.join("device"))
+            .find(|device| {
+                read_trimmed(&device.join("vendor")).as_deref() == Some("0x1002")
+                    && device.join("gpu_busy_percent").exists()
+            })
+            .map(Self::Amd)
+    }
+
+    fn sample(&self) -> Option<GpuStats> {
+        match self {
+            Self::Nvidia => sample_nvidia(),
+            Self::Amd(device) => Some(sample_amd(device)),
+        }
+    }
+}
+
+/// GPU sensor backed by a background polling thread.
+pub struct GpuSensor {
+    /// Last reading (None until the first successful query)
+    latest: Poller<Option<GpuStats>>,
+}
+
+impl GpuSensor {
+    /// Starts polling the first supported GPU. Returns None if there is none
+    /// or the first query fails (e.g. `nvidia-smi` is not installed), so a
+    /// broken backend is probed once rather than every poll.
+    pub fn start() -> Option<Self> {
+        let backend = Backend::detect()?;
+        let Some(first) = backend.sample() else {
+            debug!("GPU query via {:?} failed; not monitoring the GPU", backend);
+            return None;
+        };
+        debug!("Monitoring GPU via {:?}", backend);
+
+        let mut first = Some(first);
+        let latest = Poller::start("gpu-poll", None, POLL_INTERVAL, move || {
+            Some(first.take().or_else(|| backend.sample()))
+        })?;
+
+        Some(Self { latest })
+    }
+
+    /// Returns the last reading.
+    pub fn stats(&self) -> Option<GpuStats> {
+        self.latest.latest()
+    }
+}
+
+/// Queries the first NVIDIA GPU.
+fn sample_nvidia() -> Option<GpuStats> {
+    let output = Command::new("nvidia-smi")
+        .args([
+            "--query-gpu=name,utilization.gpu,temperature.gpu,fan.speed,power.draw",
+            "--format=csv,noheader,nounits",
+            "--id=0",
+        ])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let fields: Vec<&str> = stdout.lines().next()?.split(',').map(str::trim).collect();
+    // Unsupported fields read "[N/A]" or "[Not Supported]"
+    let number = |i: usize| fields.get(i).and_then(|v| v.parse::<f32>().ok());
+    Some(GpuStats {
+        name: fields.first().map(|s| s.to_string()).unwrap_or_default(),
+        utilization: number(1),
+        temperature: number(2),
+        fan_percent: number(3),
+        fan_rpm: None,
+        power_watts: number(4),
+    })
+}
+
+/// Reads an amdgpu device and its hwmon directory.
+fn sample_amd(device: &Path) -> GpuStats {
+    let hwmon = fs::read_dir(device.join("hwmon"))
+        .ok()
+        .and_then(|mut entries| entries.next())
+        .and_then(|e| e.ok())
+        .map(|e| e.path());
+    let read =
+        |name: &str| -> Option<f64> { read_trimmed(&hwmon.as_ref()?.join(name))?.parse().ok() };
+
+    // Fan as a percentage of the PWM range, with RPM when reported
+    let fan_percent = read("pwm1")
+        .zip(read("pwm1_max").or(Some(255.0)))
+        .filter(|(_, max)| *max > 0.0)
+        .map(|(pwm, max)| (pwm / max * 100.0) as f32);
+
+    GpuStats {
+        name: "AMD GPU".to_string(),
+        utilization: read_trimmed(&device.join("gpu_busy_percent")).and_then(|v| v.parse().ok()),
+        temperature: read("temp1_input").map(|t| (t / 1000.0) as f32),
+        fan_percent,
+        fan_rpm: read("fan1_input").map(|r| r as u32).filter(|r| *r > 0),
+        power_watts: read("power1_average")
+            .or_else(|| read("power1_input"))
+            .map(|uw| (uw / 1_000_000.0) as f32),
+    }
+}
+
+fn read_trimmed(path: &Path) -> Option<String> {
+    fs::read_to_string(path).ok().map(|s| s.trim().to_string())
+}
@@ -0,0 +1,174 @@
+//! LAN host reachability and Wake-on-LAN.
+//!
+//! Hosts are pinged with the system `ping` binary (no raw socket privileges
+//! needed) on a background thread; the render loop only reads the last
+//! result.
+
+use std::net::UdpSocket;
+use std::process::{Command, Stdio};
+use std::time::Duration;
+
+use anyhow::{bail, Context, Result};
+use tracing::{debug, warn};
+
+use super::data::HostStatus;
+use super::poller::Poller;
+use crate::config::HostConfig;
+
+/// Hosts sensor backed by a background ping thread.
+pub struct HostsSensor {
+    /// Last status of each configured host, in configuration order
+    latest: Poller<Vec<HostStatus>>,
+}
+
+impl HostsSensor {
+    /// Starts pinging `hosts` every `interval`. Returns None if no hosts
+    /// are configured.
+    pub fn start(hosts: &[HostConfig], interval: Duration) -> Option<Self> {
+        if hosts.is_empty() {
+            return None;
+        }
+
+        let initial: Vec<HostStatus> = hosts
+            .iter()
+            .map(|h| HostStatus {
+                name: h.display_name().to_string(),
+                address: h.address.clone(),
+                up: None,
+                latency_ms: None,
+            })
+            .collect();
+        let mut statuses = initial.clone();
+        let addresses: Vec<String> = hosts.iter().map(|h| h.address.clone()).collect();
+        let latest = Poller::start("hosts-ping", initial, interval, move || {
+            let results = ping_all(&addresses);
+            for (status, latency) in statuses.iter_mut().zip(results) {
+                status.up = Some(latency.is_some());
+                status.latency_ms = latency;
+            }
+            Some(statuses.clone())
+        })?;
+
+        debug!("Monitoring {} hosts", hosts.len());
+        Some(Self { latest })
+    }
+
+    /// Returns the last known status of each host.
+    pub fn statuses(&self) -> Vec<HostStatus> {
+        self.latest.latest()
+    }
+}
+
+/// Pings all addresses in parallel. Returns the round-trip time in
+/// milliseconds for each reachable host.
+fn ping_all(addresses: &[String]) -> Vec<Option<f32>> {
+    let children: Vec<_> = addresses
+        .iter()
+        .map(|address| {
+            Command::new("ping")
+                .args(["-c", "1", "-W", "1", "-n", address])
+                .stdout(Stdio::piped())
+                .stderr(Stdio::null())
+                .spawn()
+                .map_err(|e| warn!("Failed to run ping: {}", e))
+                .ok()
+        })
+        .collect();
+
+    children
+        .into_iter()
+        .map(|child| {
+            let output = child?.wait_with_output().ok()?;
+            if !output.status.success() {
+                return None;
+            }
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            Some(parse_ping_latency(&stdout).unwrap_or(0.0))
+        })
+        .collect()
+}
+
+/// Extracts the round-trip time from `ping` output, e.g.
+/// "64 bytes from 10.0.0.1: icmp_seq=1 ttl=64 time=0.42 ms".
+fn parse_ping_latency(output: &str) -> Option<f32> {
+    output
+        .split("time=")
+        .nth(1)
+        .and_then(|rest| rest.split_whitespace().next())
+        .and_then(|value| value.parse().ok())
+}
+
+/// Parses a MAC address in `aa:bb:cc:dd:ee:ff` or `aa-bb-cc-dd-ee-ff` form.
+pub fn parse_mac(mac: &str) -> Result<[u8; 6]> {
+    let parts: Vec<&str> = mac.split([':', '-']).collect();
+    if parts.len() != 6 {
+        bail!("Invalid MAC address: {}", mac);
+    }
+    let mut bytes = [0u8; 6];
+    for (byte, part) in bytes.iter_mut().zip(parts) {
+        *byte = u8::from_str_radix(part, 16)
+            .with_context(|| format!("Invalid MAC address: {}", mac))?;
+    }
+    Ok(bytes)
+}
+
+/// Broadcasts a Wake-on-LAN magic packet for `mac`.
+pub fn wake_on_lan(mac: &str) -> Result<()> {
+    let packet = magic_packet(&parse_mac(mac)?);
+    let socket = UdpSocket::bind("0.0.0.0:0").context("Failed to open UDP socket")?;
+    socket.set_broadcast(true)?;
+    socket
+        .send_to(&packet, "255.255.255.255:9")
+        .context("Failed to send magic packet")?;
+    Ok(())
+}
+
+/// Builds a magic packet: six 0xFF bytes followed by the MAC repeated 16
+/// times.
+fn magic_packet(mac: &[u8; 6]) -> Vec<u8> {
+    let mut packet = vec![0xFFu8; 6];
+    for _ in 0..16 {
+        packet.extend_from_slice(mac);
+    }
+    packet
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_mac() {
+        let expected = [0xaa, 0xbb, 0xcc, 0x01, 0x02, 0x03];
+        assert_eq!(parse_mac("aa:bb:cc:01:02:03").unwrap(), expected);
+        assert_eq!(parse_mac("AA-BB-CC-01-02-03").unwrap(), expected);
+        assert!(parse_mac("aa:bb:cc:01:02").is_err());
+        assert!(parse_mac("aa:bb:cc:01:02:03:04").is_err());
+        assert!(parse_mac("aa:bb:cc:01:02:zz").is_err());
+        assert!(parse_mac("aa:bb:cc:01:02:123").is_err());
+    }
+
+    #[test]
+    fn test_magic_packet() {
+        let mac = [1, 2, 3, 4, 5, 6];
+        let packet = magic_packet(&mac);
+        assert_eq!(packet.len(), 102);
+        assert_eq!(&packet[..6], &[0xFF; 6]);
+        assert!(packet[6..].chunks(6).all(|chunk| chunk == mac));
+    }
+
+    #[test]
+    fn test_parse_ping_latency() {
+        let output = "PING 10.0.0.1 (10.0.0.1) 56(84) bytes of data.\n\
+                      64 bytes from 10.0.0.1: icmp_seq=1 ttl=64 time=0.42 ms\n";
+        assert_eq!(parse_ping_latency(output), Some(0.42));
+        assert_eq!(
+            parse_ping_latency("64 bytes from 10.0.0.1: time=12 ms"),
+            Some(12.0)
+        );
+        assert_eq!(
+            parse_ping_latency("1 packets transmitted, 0 received"),
+            None
+        );
+    }
+}
@@ -0,0 +1,135 @@
+//! Externally reported job status.
+//!
+//! Scripts such as restic or borg backups report their status over D-Bus
+//! (`ReportJob`). The last status of each job is stored in the state
+//! directory so results survive daemon restarts.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use tracing::{debug, warn};
+
+use super::data::{JobState, JobStatus};
+use crate::persist::write_atomic;
+
+/// Jobs file contents.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct JobsFile {
+    #[serde(default)]
+    jobs: BTreeMap<String, JobStatus>,
+}
+
+/// Job status tracker backed by a TOML file.
+pub struct JobTracker {
+    path: PathBuf,
+    jobs: BTreeMap<String, JobStatus>,
+}
+
+impl JobTracker {
+    /// Loads the jobs from `path`, starting empty if it is missing or invalid.
+    pub fn load(path: PathBuf) -> Self {
+        let file = match fs::read_to_string(&path) {
+            Ok(content) => toml::from_str(&content).unwrap_or_else(|e| {
+                warn!("Ignoring invalid job status in {:?}: {}", path, e);
+                JobsFile::default()
+            }),
+            Err(_) => JobsFile::default(),
+        };
+        Self {
+            path,
+            jobs: file.jobs,
+        }
+    }
+
+    /// Records a job report at `now` (Unix time). Completed jobs are written
+    /// to disk immediately; progress updates are kept in memory only.
+    pub fn report(&mut self, name: &str, state: JobState, progress: Option<f32>, now: u64) {
+        let progress = match state {
+            JobState::Running => progress.map(|p| p.clamp(0.0, 100.0)),
+            _ => None,
+        };
+        self.jobs.insert(
+            name.to_string(),
+            JobStatus {
+                state,
+                progress,
+                updated: now,
+            },
+        );
+        if state != JobState::Running {
+            self.save();
+        }
+    }
+
+    /// Returns all jobs by name.
+    pub fn jobs(&self) -> &BTreeMap<String, JobStatus> {
+        &self.jobs
+    }
+
+    fn save(&self) {
+        let file = JobsFile {
+            jobs: self.jobs.clone(),
+        };
+        match toml::to_string_pretty(&file) {
+            Ok(content) => match write_atomic(&self.path, content) {
+                Ok(()) => debug!("Saved job status to {:?}", self.path),
+                Err(e) => warn!("Failed to save job status: {}", e),
+            },
+            Err(e) => warn!("Failed to serialize job status: {}", e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_job_tracker() {
+        let dir = std::env::temp_dir().join(format!("ht32-jobs-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("jobs.toml");
+        let _ = fs::remove_file(&path);
+
+        let mut tracker = JobTracker::load(path.clone());
+        tracker.report("backup", JobState::Running, Some(142.0), 1000);
+        assert_eq!(tracker.jobs()["backup"].progress, Some(100.0));
+        assert_eq!(tracker.jobs()["backup"].format(1000), "100%");
+        // Progress updates are not written to disk
+        assert!(!path.exists());
+
+        tracker.report("backup", JobState::Succeeded, Some(50.0), 1000);
+        tracker.report("sync", JobState::Failed, None, 2000);
+        assert_eq!(tracker.jobs()["backup"].progress, None);
+
+        // Finished jobs survive a restart
+        let tracker = JobTracker::load(path.clone());
+        assert_eq!(tracker.jobs().len(), 2);
+        assert_eq!(
+            tracker.jobs()["backup"].format(1000 + 5 * 3600),
+            "ok 5h ago"
+        );
+        assert_eq!(tracker.jobs()["sync"].format(2030), "failed 30s ago");
+
+        // An unreadable file starts empty
+        fs::write(&path, "jobs = 3").unwrap();
+        assert!(JobTracker::load(path).jobs().is_empty());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_job_state() {
+        assert_eq!("OK".parse::<JobState>(), Ok(JobState::Succeeded));
+        assert_eq!("start".parse::<JobState>(), Ok(JobState::Running));
+        assert_eq!("error".parse::<JobState>(), Ok(JobState::Failed));
+        assert!("maybe".parse::<JobState>().is_err());
+
+        let running = JobStatus {
+            state: JobState::Running,
+            progress: None,
+            updated: 0,
+        };
+        assert_eq!(running.format(10), "running");
+    }
+}
@@ -0,0 +1,281 @@
+//! System journal watcher.
+//!
+//! Follows `journalctl` on a background thread and publishes each warning
+//! or error logged after the daemon started on the event bus. Entries of
+//! error priority or worse are also counted over the last [`RECENT`],
+//! including those logged before the daemon started. If journalctl exits
+//! it is restarted after a delay, resuming after the last entry read.
+
+use std::collections::VecDeque;
+use std::io::{BufRead, BufReader};
+use std::process::{Command, Stdio};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use serde_json::Value;
+use tracing::{debug, warn};
+
+use super::data::JournalErrors;
+use crate::config::JournalConfig;
+use crate::events::{Event, EventBus};
+
+/// Delay before restarting journalctl after it exits.
+const RESTART_DELAY: Duration = Duration::from_secs(60);
+
+/// How long an error counts as recent.
+const RECENT: Duration = Duration::from_secs(60 * 60);
+
+/// Most recent errors counted; a journal flooded with errors shows this
+/// many rather than growing the log without bound.
+const MAX_ERRORS: usize = 10_000;
+
+/// Syslog priority of errors; lower numbers are more severe.
+const PRIORITY_ERR: u8 = 3;
+
+/// Priority assumed for entries without one: the journal is followed from
+/// warning priority up.
+const PRIORITY_WARNING: u8 = 4;
+
+/// Errors logged to the journal recently.
+#[derive(Debug, Default)]
+pub struct JournalErrorLog {
+    errors: Mutex<RecentErrors>,
+}
+
+/// When each recent error was logged, oldest first, and the latest message.
+#[derive(Debug, Default)]
+struct RecentErrors {
+    logged: VecDeque<Instant>,
+    latest: Option<String>,
+}
+
+impl RecentErrors {
+    /// Drops errors older than [`RECENT`], and the latest message with
+    /// the last of them.
+    fn expire(&mut self) {
+        while self.logged.front().is_some_and(|at| at.elapsed() > RECENT) {
+            self.logged.pop_front();
+        }
+        if self.logged.is_empty() {
+            self.latest = None;
+        }
+    }
+}
+
+impl JournalErrorLog {
+    /// Records an error logged `age` ago. Errors arrive in the order they
+    /// were logged; those older than [`RECENT`] are ignored.
+    fn record(&self, message: String, age: Duration) {
+        if age > RECENT {
+            return;
+        }
+        let Some(logged) = Instant::now().checked_sub(age) else {
+            return;
+        };
+        let mut errors = self.errors.lock().unwrap();
+        if errors.logged.len() == MAX_ERRORS {
+            errors.logged.pop_front();
+        }
+        errors.logged.push_back(logged);
+        errors.latest = Some(message);
+    }
+
+    /// Returns the number of recent errors and the latest message.
+    pub fn summary(&self) -> JournalErrors {
+        let mut errors = self.errors.lock().unwrap();
+        errors.expire();
+        JournalErrors {
+            count: errors.logged.len() as u32,
+            latest: errors.latest.clone(),
+        }
+    }
+}
+
+/// A text entry read from the journal.
+#[derive(Debug, PartialEq)]
+struct Entry {
+    /// Syslog priority
+    priority: u8,
+    /// "identifier: message"
+    message: String,
+    /// When the entry was logged
+    logged: Option<SystemTime>,
+    /// Journal position to resume after
+    cursor: Option<String>,
+}
+
+/// Starts following the journal if enabled in `config`. Returns the log of
+/// recent errors it fills.
+pub fn watch_journal(config: &JournalConfig, events: EventBus) -> Option<Arc<JournalErrorLog>> {
+    if !config.enable {
+        return None;
+    }
+    let errors = Arc::new(JournalErrorLog::default());
+
+    let mut args = vec![
+        "--follow".to_string(),
+        "--priority=warning".to_string(),
+        "--output=json".to_string(),
+    ];
+    args.extend(config.units.iter().map(|unit| format!("--unit={}", unit)));
+
+    // Errors from before the start count too, but are not published
+    let started = SystemTime::now();
+    let since = started
+        .checked_sub(RECENT)
+        .and_then(|since| since.duration_since(UNIX_EPOCH).ok())
+        .map_or(0, |since| since.as_secs());
+    let log = errors.clone();
+    let spawned = thread::Builder::new()
+        .name("journal".to_string())
+        .spawn(move || {
+            let mut cursor = None;
+            loop {
+                let mut run_args = args.clone();
+                run_args.push(match cursor {
+                    Some(ref cursor) => format!("--after-cursor={}", cursor),
+                    None => format!("--since=@{}", since),
+                });
+                if let Err(e) = follow(&run_args, started, &events, &log, &mut cursor) {
+                    warn!("Failed to follow the journal: {}", e);
+                }
+                thread::sleep(RESTART_DELAY);
+            }
+        });
+    if let Err(e) = spawned {
+        warn!("Failed to start the journal watcher: {}", e);
+        return None;
+    }
+    Some(errors)
+}
+
+/// Runs journalctl with `args` until it exits, publishing each entry logged
+/// after `started`, recording errors in `errors` and keeping the cursor of
+/// the last entry in `cursor`.
+fn follow(
+    args: &[String],
+    started: SystemTime,
+    events: &EventBus,
+    errors: &JournalErrorLog,
+    cursor: &mut Option<String>,
+) -> std::io::Result<()> {
+    let mut child = Command::new("journalctl")
+        .args(args)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()?;
+    debug!("Following the journal");
+
+    if let Some(stdout) = child.stdout.take() {
+        for line in BufReader::new(stdout).lines() {
+            let Some(entry) = parse_entry(&line?) else {
+                continue;
+            };
+            if entry.cursor.is_some() {
+                cursor.clone_from(&entry.cursor);
+            }
+            let logged = entry.logged.unwrap_or_else(SystemTime::now);
+            if entry.priority <= PRIORITY_ERR {
+                let age = logged.elapsed().unwrap_or_default();
+                errors.record(entry.message.clone(), age);
+            }
+            if logged >= started {
+                events.publish(Event::Journal(entry.message));
+            }
+        }
+    }
+    let status = child.wait()?;
+    debug!("journalctl exited with {}", status);
+    Ok(())
+}
+
+/// Parses a JSON journal entry. Returns None for entries without a text
+/// message.
+fn parse_entry(line: &str) -> Option<Entry> {
+    let entry: Value = serde_json::from_str(line).ok()?;
+    // Binary messages are encoded as byte arrays and are skipped
+    let message = entry.get("MESSAGE")?.as_str()?.trim();
+    let identifier = entry
+        .get("SYSLOG_IDENTIFIER")
+        .or_else(|| entry.get("_SYSTEMD_UNIT"))
+        .and_then(Value::as_str);
+    let priority = entry
+        .get("PRIORITY")
+        .and_then(Value::as_str)
+        .and_then(|p| p.parse().ok())
+        .unwrap_or(PRIORITY_WARNING);
+    let message = match identifier {
+        Some(identifier) => format!("{}: {}", identifier, message),
+        None => message.to_string(),
+    };
+    // Microseconds since the epoch
+    let logged = entry
+        .get("__REALTIME_TIMESTAMP")
+        .and_then(Value::as_str)
+        .and_then(|micros| micros.parse().ok())
+        .and_then(|micros| UNIX_EPOCH.checked_add(Duration::from_micros(micros)));
+    let cursor = entry
+        .get("__CURSOR")
+        .and_then(Value::as_str)
+        .map(String::from);
+    Some(Entry {
+        priority,
+        message,
+        logged,
+        cursor,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_entry() {
+        assert_eq!(
+            parse_entry(r#"{"MESSAGE":"Disk almost full ","SYSLOG_IDENTIFIER":"smartd"}"#),
+            Some(Entry {
+                priority: PRIORITY_WARNING,
+                message: "smartd: Disk almost full".to_string(),
+                logged: None,
+                cursor: None,
+            })
+        );
+        let entry = parse_entry(
+            r#"{"MESSAGE":"Failed","_SYSTEMD_UNIT":"nginx.service","PRIORITY":"3",
+                "__REALTIME_TIMESTAMP":"1700000000500000","__CURSOR":"s=1;i=2"}"#,
+        )
+        .unwrap();
+        assert_eq!(entry.priority, PRIORITY_ERR);
+        assert_eq!(entry.message, "nginx.service: Failed");
+        assert_eq!(
+            entry.logged,
+            Some(UNIX_EPOCH + Duration::from_millis(1_700_000_000_500))
+        );
+        assert_eq!(entry.cursor.as_deref(), Some("s=1;i=2"));
+        assert_eq!(parse_entry(r#"{"MESSAGE":[104,105]}"#), None);
+        assert_eq!(parse_entry("not json"), None);
+    }
+
+    #[test]
+    fn test_error_log() {
+        let log = JournalErrorLog::default();
+        log.record("a: first".to_string(), Duration::from_secs(10 * 60));
+        log.record("b: second".to_string(), Duration::ZERO);
+        // Logged before the last hour
+        log.record("c: old".to_string(), RECENT * 2);
+        assert_eq!(
+            log.summary(),
+            JournalErrors {
+                count: 2,
+                latest: Some("b: second".to_string()),
+            }
+        );
+
+        for i in 0..MAX_ERRORS {
+            log.record(i.to_string(), Duration::ZERO);
+        }
+        assert_eq!(log.summary().count, MAX_ERRORS as u32);
+    }
+}
@@ -0,0 +1,152 @@
+//! HTTP JSON scraping into named custom metrics.
+//!
+//! Each configured source is fetched on its own background thread and the
+//! configured fields are extracted with simple paths such as
+//! `$.current.temperature` or `items[0].count`.
+
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use anyhow::Result;
+use serde_json::Value;
+use tracing::debug;
+
+use super::data::Metric;
+use super::http;
+use super::poller::Poller;
+use crate::config::{JsonFieldConfig, JsonSourceConfig};
+
+/// JSON scraper polling all configured sources.
+pub struct JsonScraper {
+    /// Latest value of every field, keyed by name
+    latest: Poller<BTreeMap<String, Metric>>,
+}
+
+impl JsonScraper {
+    /// Starts polling `sources`. Returns None if none are configured.
+    pub fn start(sources: &[JsonSourceConfig]) -> Option<Self> {
+        if sources.is_empty() {
+            return None;
+        }
+
+        let latest = Poller::new(BTreeMap::new());
+        for source in sources {
+            if source.fields.is_empty() {
+                continue;
+            }
+            debug!(
+                "Scraping {} JSON fields from {}",
+                source.fields.len(),
+                source.url
+            );
+            let config = source.clone();
+            let interval = Duration::from_secs(source.interval.max(1));
+            latest.spawn("json-scraper", interval, move |latest| {
+                poll_once(&config, latest)
+            });
+        }
+
+        Some(Self { latest })
+    }
+
+    /// Returns the latest field values.
+    pub fn metrics(&self) -> BTreeMap<String, Metric> {
+        self.latest.latest()
+    }
+}
+
+/// Polls one source, updating its fields.
+fn poll_once(source: &JsonSourceConfig, latest: &Mutex<BTreeMap<String, Metric>>) {
+    match fetch(source) {
+        Ok(json) => {
+            let mut metrics = latest.lock().unwrap();
+            for field in &source.fields {
+                match extract(&json, &field.path).and_then(|v| to_metric(field, v)) {
+                    Some(metric) => metrics.insert(field.name.clone(), metric),
+                    None => metrics.remove(&field.name),
+                };
+            }
+        }
+        Err(e) => {
+            debug!("JSON poll of {} failed: {:#}", source.url, e);
+            let mut metrics = latest.lock().unwrap();
+            for field in &source.fields {
+                metrics.remove(&field.name);
+            }
+        }
+    }
+}
+
+fn fetch(source: &JsonSourceConfig) -> Result<Value> {
+    let headers: Vec<(&str, &str)> = source
+        .headers
+        .iter()
+        .map(|(name, value)| (name.as_str(), value.as_str()))
+        .collect();
+    http::get(&source.url, &headers)?.check()?.json()
+}
+
+/// Converts an extracted value into a metric. Objects, arrays and nulls
+/// yield None.
+fn to_metric(field: &JsonFieldConfig, value: &Value) -> Option<Metric> {
+    let number = match value {
+        Value::Number(n) => n.as_f64()?,
+        Value::Bool(b) => f64::from(u8::from(*b)),
+        // Numbers sent as strings are still numbers
+        Value::String(s) => match s.trim().parse::<f64>() {
+            Ok(n) => n,
+            Err(_) => return Some(Metric::text(s)),
+        },
+        _ => return None,
+    };
+    Some(Metric::new(
+        number * field.scale,
+        &field.unit,
+        field.alert_above,
+        field.alert_below,
+    ))
+}
+
+/// Follows a path of object keys and array indices. A leading `$` is
+/// optional; keys are separated by `.` and indices written as `[n]` or as
+/// a numeric key.
+fn extract<'a>(json: &'a Value, path: &str) -> Option<&'a Value> {
+    let path = path.trim().trim_start_matches('$');
+    let mut current = json;
+    for segment in path.split('.').filter(|s| !s.is_empty()) {
+        let (key, indices) = match segment.find('[') {
+            Some(i) => (&segment[..i], &segment[i..]),
+            None => (segment, ""),
+        };
+        if !key.is_empty() {
+            current = match current {
+                Value::Array(items) => items.get(key.parse::<usize>().ok()?)?,
+                _ => current.get(key)?,
+            };
+        }
+        for index in indices.split('[').filter(|s| !s.is_empty()) {
+            let index: usize = index.strip_suffix(']')?.parse().ok()?;
+            current = current.get(index)?;
+        }
+    }
+    Some(current)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract() {
+        let json: Value = serde_json::from_str(
+            r#"{"current": {"temp": 21.5}, "items": [{"count": 3}, {"count": 7}]}"#,
+        )
+        .unwrap();
+        assert_eq!(extract(&json, "$.current.temp"), Some(&Value::from(21.5)));
+        assert_eq!(extract(&json, "items[1].count"), Some(&Value::from(7)));
+        assert_eq!(extract(&json, "items.0.count"), Some(&Value::from(3)));
+        assert_eq!(extract(&json, "items[2].count"), None);
+        assert_eq!(extract(&json, "missing"), None);
+    }
+}
@@ -0,0 +1,114 @@
+//! Jellyfin and Plex active streams.
+//!
+//! Polls the server's session list on a background thread and reports what
+//! is playing, by whom, and whether the server is transcoding it.
+
+use std::time::Duration;
+
+use anyhow::{bail, Result};
+use serde_json::Value;
+
+use super::data::MediaStream;
+use super::http;
+use super::poller::Poller;
+use crate::config::{MediaServerConfig, MediaServerKind};
+
+/// Media server sensor backed by a background polling thread.
+pub struct MediaServerSensor {
+    /// Last result (None until the first successful poll)
+    latest: Poller<Option<Vec<MediaStream>>>,
+}
+
+impl MediaServerSensor {
+    /// Starts polling the configured server. Returns None if disabled.
+    pub fn start(config: &MediaServerConfig) -> Option<Self> {
+        if !config.enable {
+            return None;
+        }
+
+        let config = config.clone();
+        let base = config.url.trim_end_matches('/').to_string();
+        let interval = Duration::from_secs(config.interval.max(5));
+        let latest = Poller::start_fallible("media-server", interval, move || match config.kind {
+            MediaServerKind::Jellyfin => poll_jellyfin(&base, &config.token),
+            MediaServerKind::Plex => poll_plex(&base, &config.token),
+        })?;
+
+        Some(Self { latest })
+    }
+
+    /// Returns the last known streams.
+    pub fn streams(&self) -> Option<Vec<MediaStream>> {
+        self.latest.latest()
+    }
+}
+
+/// Joins a series name and episode title when both are present.
+fn full_title(series: Option<&str>, title: Option<&str>) -> String {
+    match (series, title) {
+        (Some(series), Some(title)) if !series.is_empty() => format!("{} - {}", series, title),
+        (_, Some(title)) => title.to_string(),
+        (Some(series), None) => series.to_string(),
+        (None, None) => "Unknown".to_string(),
+    }
+}
+
+/// Lists Jellyfin sessions that have something playing.
+fn poll_jellyfin(base: &str, token: &str) -> Result<Vec<MediaStream>> {
+    let response = http::get(
+        &format!("{}/Sessions?activeWithinSeconds=960", base),
+        &[("X-Emby-Token", token)],
+    )?;
+    if response.status == 401 {
+        bail!("Jellyfin rejected the API key");
+    }
+    let sessions = response.check()?.json()?;
+
+    Ok(sessions
+        .as_array()
+        .map(Vec::as_slice)
+        .unwrap_or_default()
+        .iter()
+        .filter(|s| s["NowPlayingItem"].is_object())
+        .map(|s| {
+            let item = &s["NowPlayingItem"];
+            MediaStream {
+                title: full_title(item["SeriesName"].as_str(), item["Name"].as_str()),
+                user: s["UserName"].as_str().unwrap_or_default().to_string(),
+                transcoding: s["PlayState"]["PlayMethod"].as_str() == Some("Transcode"),
+            }
+        })
+        .collect())
+}
+
+/// Lists Plex sessions.
+fn poll_plex(base: &str, token: &str) -> Result<Vec<MediaStream>> {
+    let response = http::get(
+        &format!("{}/status/sessions", base),
+        &[("X-Plex-Token", token), ("Accept", "application/json")],
+    )?;
+    if response.status == 401 {
+        bail!("Plex rejected the token");
+    }
+    let json = response.check()?.json()?;
+
+    Ok(json["MediaContainer"]["Metadata"]
+        .as_array()
+        .map(Vec::as_slice)
+        .unwrap_or_default()
+        .iter()
+        .map(|m| MediaStream {
+            title: full_title(m["grandparentTitle"].as_str(), m["title"].as_str()),
+            user: m["User"]["title"].as_str().unwrap_or_default().to_string(),
+            transcoding: plex_transcoding(&m["TranscodeSession"]),
+        })
+        .collect())
+}
+
+/// Whether a Plex transcode session converts video or audio (as opposed to
+/// only remuxing the container, which Plex calls direct stream).
+fn plex_transcoding(session: &Value) -> bool {
+    ["videoDecision", "audioDecision"]
+        .iter()
+        .any(|key| session[key].as_str() == Some("transcode"))
+}
@@ -4,20 +4,80 @@
 
 #![allow(dead_code, unused_imports)]
 
+mod apps;
+#[cfg(feature = "audio")]
+mod audio;
 mod cpu;
-pub mod data;
 mod disk;
+mod dns_filter;
+#[cfg(feature = "scripting")]
+mod exec;
+mod gpu;
+mod hosts;
+mod jobs;
+mod journal;
+#[cfg(feature = "scrapers")]
+mod json_scraper;
+mod media_server;
 mod memory;
 mod network;
+mod poller;
+mod printer;
+mod processes;
+mod sampler;
+mod sessions;
+#[cfg(feature = "snmp")]
+mod snmp;
+#[cfg(feature = "speedtest")]
+mod speedtest;
+mod storage;
 mod system;
 mod temperature;
+mod throttle;
+#[cfg(feature = "torrent")]
+mod torrent;
+mod traffic;
+mod updates;
+mod vms;
 
-pub use cpu::CpuSensor;
-pub use disk::DiskSensor;
+pub use ht32_panel_client::http;
+pub use ht32_panel_render::data;
+
+pub use apps::AppCpuSensor;
+#[cfg(feature = "audio")]
+pub use audio::AudioSensor;
+pub use cpu::{CpuReading, CpuSensor};
+pub use data::COLLECTORS;
+pub use disk::{DiskReading, DiskSensor};
+pub use dns_filter::DnsFilterSensor;
+#[cfg(feature = "scripting")]
+pub use exec::ExecSensor;
+pub use gpu::GpuSensor;
+pub use hosts::{parse_mac, wake_on_lan, HostsSensor};
+pub use jobs::JobTracker;
+pub use journal::{watch_journal, JournalErrorLog};
+#[cfg(feature = "scrapers")]
+pub use json_scraper::JsonScraper;
+pub use media_server::MediaServerSensor;
 pub use memory::MemorySensor;
-pub use network::NetworkSensor;
+pub use network::{NetworkReading, NetworkSensor};
+pub use printer::PrinterSensor;
+pub use processes::ProcessSensor;
+pub use sampler::{wait_for_reads, ReadHealth, Sampled};
+pub use sessions::SessionSensor;
+#[cfg(feature = "snmp")]
+pub use snmp::SnmpSensor;
+#[cfg(feature = "speedtest")]
+pub use speedtest::{Speedtest, SpeedtestResult, SpeedtestStatus};
+pub use storage::StorageSensor;
 pub use system::SystemInfo;
-pub use temperature::TemperatureSensor;
+pub use temperature::{TemperatureCandidate, TemperatureReading, TemperatureSensor};
+pub use throttle::ThrottleSensor;
+#[cfg(feature = "torrent")]
+pub use torrent::TorrentSensor;
+pub use traffic::TrafficCounter;
+pub use updates::UpdatesSensor;
+pub use vms::VmSensor;
 
 /// Trait for all sensors.
 pub trait Sensor: Send + Sync {
@@ -25,6 +25,8 @@ pub struct NetworkSensor {
     cached_ipv6_lla: Option<String>,
     /// IPv6 Unique Local Address (fc00::/7)
     cached_ipv6_ula: Option<String>,
+    /// Every address on the interface: IPv4 first, then GUA, ULA and LLA
+    cached_all: Vec<String>,
     last_ip_check: Option<Instant>,
     /// History of combined I/O rates (bytes/sec)
     history: VecDeque<f64>,
@@ -34,6 +36,23 @@ pub struct NetworkSensor {
     tx_history: VecDeque<f64>,
 }
 
+/// Rates and addresses of the monitored interface at one sample.
+#[derive(Debug, Clone, Default)]
+pub struct NetworkReading {
+    pub interface: String,
+    pub rx_rate: f64,
+    pub tx_rate: f64,
+    pub history: VecDeque<f64>,
+    pub rx_history: VecDeque<f64>,
+    pub tx_history: VecDeque<f64>,
+    pub ipv4: Option<String>,
+    pub ipv6_gua: Option<String>,
+    pub ipv6_lla: Option<String>,
+    pub ipv6_ula: Option<String>,
+    /// Every address, ordered IPv4, GUA, ULA, LLA
+    pub addresses: Vec<String>,
+}
+
 impl NetworkSensor {
     /// Creates a new network sensor for a specific interface.
     pub fn new(interface: &str) -> Self {
@@ -49,6 +68,7 @@ impl NetworkSensor {
             cached_ipv6_gua: None,
             cached_ipv6_lla: None,
             cached_ipv6_ula: None,
+            cached_all: Vec::new(),
             last_ip_check: None,
             history: VecDeque::with_capacity(HISTORY_SIZE),
             rx_history: VecDeque::with_capacity(HISTORY_SIZE),
@@ -77,6 +97,7 @@ impl NetworkSensor {
         self.cached_ipv6_gua = None;
         self.cached_ipv6_lla = None;
         self.cached_ipv6_ula = None;
+        self.cached_all.clear();
         self.last_ip_check = None;
         self.history.clear();
         self.rx_history.clear();
@@ -213,6 +234,31 @@ impl NetworkSensor {
         self.cached_ipv6_ula.clone()
     }
 
+    /// Returns every address on this interface, ordered IPv4, GUA, ULA, LLA.
+    pub fn all_addresses(&mut self) -> Vec<String> {
+        self.refresh_ip_cache();
+        self.cached_all.clone()
+    }
+
+    /// Samples the interface and returns the reading.
+    pub fn read(&mut self) -> NetworkReading {
+        self.sample();
+        self.refresh_ip_cache();
+        NetworkReading {
+            interface: self.interface.clone(),
+            rx_rate: self.last_rx_rate,
+            tx_rate: self.last_tx_rate,
+            history: self.history.clone(),
+            rx_history: self.rx_history.clone(),
+            tx_history: self.tx_history.clone(),
+            ipv4: self.cached_ipv4.clone(),
+            ipv6_gua: self.cached_ipv6_gua.clone(),
+            ipv6_lla: self.cached_ipv6_lla.clone(),
+            ipv6_ula: self.cached_ipv6_ula.clone(),
+            addresses: self.cached_all.clone(),
+        }
+    }
+
     /// Refreshes the IP address cache if stale (older than 30 seconds).
     fn refresh_ip_cache(&mut self) {
         let should_refresh = self
@@ -226,6 +272,7 @@ impl NetworkSensor {
             self.cached_ipv6_gua = addrs.ipv6_gua;
             self.cached_ipv6_lla = addrs.ipv6_lla;
             self.cached_ipv6_ula = addrs.ipv6_ula;
+            self.cached_all = addrs.all;
             self.last_ip_check = Some(Instant::now());
         }
     }
@@ -233,6 +280,8 @@ impl NetworkSensor {
     /// Gets all IP addresses for an interface using getifaddrs.
     fn get_ip_addresses(interface: &str) -> IpAddresses {
         let mut addrs = IpAddresses::default();
+        let (mut all_v4, mut all_gua, mut all_ula, mut all_lla) =
+            (Vec::new(), Vec::new(), Vec::new(), Vec::new());
 
         // SAFETY: getifaddrs is a standard POSIX function. We properly free the
         // list with freeifaddrs when done.
@@ -252,12 +301,15 @@ impl NetworkSensor {
                     if name == interface && !ifa.ifa_addr.is_null() {
                         let family = (*ifa.ifa_addr).sa_family as i32;
 
-                        if family == libc::AF_INET && addrs.ipv4.is_none() {
+                        if family == libc::AF_INET {
                             // IPv4 address
                             let sockaddr_in = ifa.ifa_addr as *const libc::sockaddr_in;
                             let addr_bytes = (*sockaddr_in).sin_addr.s_addr.to_ne_bytes();
-                            let addr = Ipv4Addr::from(addr_bytes);
-                            addrs.ipv4 = Some(addr.to_string());
+                            let addr = Ipv4Addr::from(addr_bytes).to_string();
+                            if addrs.ipv4.is_none() {
+                                addrs.ipv4 = Some(addr.clone());
+                            }
+                            all_v4.push(addr);
                         } else if family == libc::AF_INET6 {
                             // IPv6 address - classify by type
                             let sockaddr_in6 = ifa.ifa_addr as *const libc::sockaddr_in6;
@@ -270,18 +322,21 @@ impl NetworkSensor {
                             if first_byte == 0xfe && (addr_bytes[1] & 0xc0) == 0x80 {
                                 // Link-Local (fe80::/10)
                                 if addrs.ipv6_lla.is_none() {
-                                    addrs.ipv6_lla = Some(addr_str);
+                                    addrs.ipv6_lla = Some(addr_str.clone());
                                 }
+                                all_lla.push(addr_str);
                             } else if first_byte == 0xfc || first_byte == 0xfd {
                                 // Unique Local (fc00::/7, typically fd00::/8)
                                 if addrs.ipv6_ula.is_none() {
-                                    addrs.ipv6_ula = Some(addr_str);
+                                    addrs.ipv6_ula = Some(addr_str.clone());
                                 }
+                                all_ula.push(addr_str);
                             } else if (first_byte & 0xe0) == 0x20 {
                                 // Global Unicast (2000::/3)
                                 if addrs.ipv6_gua.is_none() {
-                                    addrs.ipv6_gua = Some(addr_str);
+                                    addrs.ipv6_gua = Some(addr_str.clone());
                                 }
+                                all_gua.push(addr_str);
                             }
                         }
                     }
@@ -293,6 +348,7 @@ impl NetworkSensor {
             libc::freeifaddrs(ifaddrs);
         }
 
+        addrs.all = [all_v4, all_gua, all_ula, all_lla].concat();
         addrs
     }
 }
@@ -304,6 +360,7 @@ struct IpAddresses {
     ipv6_gua: Option<String>,
     ipv6_lla: Option<String>,
     ipv6_ula: Option<String>,
+    all: Vec<String>,
 }
 
 impl Sensor for NetworkSensor {
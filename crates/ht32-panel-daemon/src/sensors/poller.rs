@@ -0,0 +1,153 @@
+//! Background polling threads for slow integrations.
+//!
+//! Sensors that run a command or query a network service poll on their own
+//! thread at a fixed interval; the render loop only reads the last value.
+
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use anyhow::Result;
+use tracing::{debug, warn};
+
+/// The latest value produced by one or more polling threads.
+pub struct Poller<T> {
+    latest: Arc<Mutex<T>>,
+}
+
+impl<T: Send + 'static> Poller<T> {
+    /// Creates a poller holding `initial`, with no threads yet.
+    pub fn new(initial: T) -> Self {
+        Self {
+            latest: Arc::new(Mutex::new(initial)),
+        }
+    }
+
+    /// Starts a thread named `name` that calls `poll` with the shared value
+    /// every `interval`. `poll` should finish its slow work before locking.
+    /// Returns false if the thread could not be started.
+    pub fn spawn(
+        &self,
+        name: &str,
+        interval: Duration,
+        mut poll: impl FnMut(&Mutex<T>) + Send + 'static,
+    ) -> bool {
+        let latest = self.latest.clone();
+        thread::Builder::new()
+            .name(name.to_string())
+            .spawn(move || loop {
+                poll(&latest);
+                thread::sleep(interval);
+            })
+            .map_err(|e| warn!("Failed to start {} thread: {}", name, e))
+            .is_ok()
+    }
+
+    /// Starts a thread that replaces the value with each result of `poll`;
+    /// None keeps the previous value. Returns None if the thread could not
+    /// be started.
+    pub fn start(
+        name: &str,
+        initial: T,
+        interval: Duration,
+        mut poll: impl FnMut() -> Option<T> + Send + 'static,
+    ) -> Option<Self> {
+        let poller = Self::new(initial);
+        let started = poller.spawn(name, interval, move |latest| {
+            if let Some(value) = poll() {
+                *latest.lock().unwrap() = value;
+            }
+        });
+        started.then_some(poller)
+    }
+}
+
+impl<T: Send + 'static> Poller<Option<T>> {
+    /// Starts a thread storing each successful result of `poll`, or None
+    /// after a failure, which is logged at debug level.
+    pub fn start_fallible(
+        name: &str,
+        interval: Duration,
+        mut poll: impl FnMut() -> Result<T> + Send + 'static,
+    ) -> Option<Self> {
+        let what = name.to_string();
+        Self::start(name, None, interval, move || match poll() {
+            Ok(value) => Some(Some(value)),
+            Err(e) => {
+                debug!("{} poll failed: {:#}", what, e);
+                Some(None)
+            }
+        })
+    }
+}
+
+impl<T: Clone> Poller<T> {
+    /// Returns the latest value.
+    pub fn latest(&self) -> T {
+        self.latest.lock().unwrap().clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::bail;
+    use std::time::Instant;
+
+    /// Waits up to a second for `poller` to hold `expected`.
+    fn wait_for<T: Clone + PartialEq>(poller: &Poller<T>, expected: T) -> bool {
+        let deadline = Instant::now() + Duration::from_secs(1);
+        while Instant::now() < deadline {
+            if poller.latest() == expected {
+                return true;
+            }
+            thread::sleep(Duration::from_millis(5));
+        }
+        false
+    }
+
+    #[test]
+    fn test_start() {
+        let interval = Duration::from_millis(5);
+        let mut count = 0;
+        let poller = Poller::start("test-poller", 0, interval, move || {
+            count += 1;
+            // Odd polls keep the previous value; stop changing at 4
+            (count % 2 == 0 && count <= 4).then_some(count)
+        })
+        .unwrap();
+        assert!(wait_for(&poller, 4));
+
+        let mut count = 0;
+        let poller = Poller::start_fallible("test-poller", interval, move || {
+            count += 1;
+            if count > 1 {
+                bail!("gone");
+            }
+            Ok(count)
+        })
+        .unwrap();
+        assert!(wait_for(&poller, None));
+    }
+
+    #[test]
+    fn test_spawn_shares_value() {
+        let poller = Poller::new(Vec::new());
+        for source in ["a", "b"] {
+            let mut polled = false;
+            poller.spawn("test-poller", Duration::from_millis(5), move |latest| {
+                if !polled {
+                    latest.lock().unwrap().push(source);
+                    polled = true;
+                }
+            });
+        }
+        let deadline = Instant::now() + Duration::from_secs(1);
+        while poller.latest().len() < 2 && Instant::now() < deadline {
+            thread::sleep(Duration::from_millis(5));
+        }
+        let mut sources = poller.latest();
+        sources.sort();
+        assert_eq!(sources, ["a", "b"]);
+    }
+}
@@ -0,0 +1,128 @@
+//! OctoPrint and Moonraker print progress.
+//!
+//! Polls the server's HTTP API on a background thread for the job progress,
+//! time remaining and heater temperatures.
+
+use std::time::Duration;
+
+use anyhow::Result;
+use serde_json::Value;
+
+use super::data::{HeaterTemp, PrinterStatus};
+use super::http;
+use super::poller::Poller;
+use crate::config::{PrinterConfig, PrinterKind};
+
+/// Printer sensor backed by a background polling thread.
+pub struct PrinterSensor {
+    /// Last status (None until the first successful poll)
+    latest: Poller<Option<PrinterStatus>>,
+}
+
+impl PrinterSensor {
+    /// Starts polling the configured server. Returns None if disabled.
+    pub fn start(config: &PrinterConfig) -> Option<Self> {
+        if !config.enable {
+            return None;
+        }
+
+        let config = config.clone();
+        let base = config.url.trim_end_matches('/').to_string();
+        let interval = Duration::from_secs(config.interval.max(1));
+        let latest = Poller::start_fallible("printer", interval, move || match config.kind {
+            PrinterKind::Octoprint => poll_octoprint(&base, &config.api_key),
+            PrinterKind::Moonraker => poll_moonraker(&base, &config.api_key),
+        })?;
+
+        Some(Self { latest })
+    }
+
+    /// Returns the last known status.
+    pub fn status(&self) -> Option<PrinterStatus> {
+        self.latest.latest()
+    }
+}
+
+/// Reads a heater's `actual`/`target` (OctoPrint) or
+/// `temperature`/`target` (Moonraker) fields.
+fn heater(value: &Value) -> Option<HeaterTemp> {
+    let actual = value["actual"]
+        .as_f64()
+        .or_else(|| value["temperature"].as_f64())?;
+    Some(HeaterTemp {
+        actual: actual as f32,
+        target: value["target"].as_f64().unwrap_or(0.0) as f32,
+    })
+}
+
+/// Polls OctoPrint's job and printer endpoints.
+fn poll_octoprint(base: &str, api_key: &str) -> Result<PrinterStatus> {
+    let headers = [("X-Api-Key", api_key)];
+    let job = http::get(&format!("{}/api/job", base), &headers)?
+        .check()?
+        .json()?;
+    let state = job["state"].as_str().unwrap_or("Unknown").to_string();
+    let active = state.starts_with("Printing") || state.starts_with("Paus");
+
+    // The printer endpoint answers 409 while the printer is disconnected
+    let printer = http::get(&format!("{}/api/printer", base), &headers)?;
+    let temps = if printer.status == 200 {
+        printer.json()?["temperature"].clone()
+    } else {
+        Value::Null
+    };
+
+    let progress = &job["progress"];
+    Ok(PrinterStatus {
+        state,
+        active,
+        file: job["job"]["file"]["name"].as_str().map(str::to_string),
+        progress: progress["completion"].as_f64().map(|p| p as f32),
+        elapsed: progress["printTime"].as_u64(),
+        remaining: progress["printTimeLeft"].as_u64(),
+        nozzle: heater(&temps["tool0"]),
+        bed: heater(&temps["bed"]),
+    })
+}
+
+/// Polls Moonraker's printer object query. Time remaining is extrapolated
+/// from the print duration and progress.
+fn poll_moonraker(base: &str, api_key: &str) -> Result<PrinterStatus> {
+    let url = format!(
+        "{}/printer/objects/query?print_stats&display_status&virtual_sdcard&extruder&heater_bed",
+        base
+    );
+    let response = if api_key.is_empty() {
+        http::get(&url, &[])?
+    } else {
+        http::get(&url, &[("X-Api-Key", api_key)])?
+    };
+    let json = response.check()?.json()?;
+    let status = &json["result"]["status"];
+    let stats = &status["print_stats"];
+
+    let state = stats["state"].as_str().unwrap_or("unknown").to_string();
+    let active = state == "printing" || state == "paused";
+    let progress = status["display_status"]["progress"]
+        .as_f64()
+        .or_else(|| status["virtual_sdcard"]["progress"].as_f64());
+    let elapsed = stats["print_duration"].as_f64();
+    let remaining = match (progress, elapsed) {
+        (Some(p), Some(e)) if active && p > 0.0 => Some((e / p - e).max(0.0) as u64),
+        _ => None,
+    };
+
+    Ok(PrinterStatus {
+        state,
+        active,
+        file: stats["filename"]
+            .as_str()
+            .filter(|f| !f.is_empty())
+            .map(str::to_string),
+        progress: progress.map(|p| (p * 100.0) as f32),
+        elapsed: elapsed.map(|e| e as u64),
+        remaining,
+        nozzle: heater(&status["extruder"]),
+        bed: heater(&status["heater_bed"]),
+    })
+}
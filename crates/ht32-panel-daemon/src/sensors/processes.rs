@@ -0,0 +1,56 @@
+//! Process count sensor with zombie detection.
+
+use std::fs;
+
+use super::data::ProcessCounts;
+
+/// Process and thread count sensor.
+pub struct ProcessSensor;
+
+impl ProcessSensor {
+    /// Creates a new process sensor.
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Counts processes, threads and zombies by scanning /proc.
+    pub fn sample(&self) -> ProcessCounts {
+        let mut counts = ProcessCounts::default();
+        let Ok(entries) = fs::read_dir("/proc") else {
+            return counts;
+        };
+
+        for entry in entries.flatten() {
+            let name = entry.file_name();
+            if !name.to_string_lossy().bytes().all(|b| b.is_ascii_digit()) {
+                continue;
+            }
+            // Processes can exit between listing and reading
+            let Ok(stat) = fs::read_to_string(entry.path().join("stat")) else {
+                continue;
+            };
+            // The command name may contain spaces, so parse after its closing paren:
+            // state is the first field after it and num_threads the 18th
+            let Some((_, rest)) = stat.rsplit_once(')') else {
+                continue;
+            };
+            let mut fields = rest.split_whitespace();
+            let state = fields.next();
+            let threads = fields.nth(16).and_then(|s| s.parse::<u32>().ok());
+
+            counts.processes += 1;
+            counts.threads += threads.unwrap_or(1);
+            if state == Some("Z") {
+                counts.zombies += 1;
+            }
+        }
+
+        counts
+    }
+}
+
+impl Default for ProcessSensor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
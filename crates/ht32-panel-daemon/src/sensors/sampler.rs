@@ -0,0 +1,236 @@
+//! Sensor reads off the render path.
+//!
+//! Each local sensor is read on its own task on the blocking thread pool,
+//! and frames use its latest completed reading. A frame waits up to
+//! [`READ_WAIT`] for fresh readings. A read that takes longer, such as a
+//! hung hwmon file, is left running and the sensor keeps its previous
+//! reading until the read returns; no new read is started meanwhile.
+//! Stalled and panicked reads are counted for `GetHealth`. A panicked read
+//! does not disable the sensor: later reads and changes use it as the
+//! panic left it.
+
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::time::{Duration, Instant};
+
+use futures::FutureExt;
+use tokio::sync::oneshot;
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
+
+/// How long a frame waits for sensor reads before using earlier readings.
+pub const READ_WAIT: Duration = Duration::from_millis(100);
+
+/// How long a read may run before it is reported as stalled.
+const STALLED: Duration = Duration::from_secs(10);
+
+/// A sensor read on a background task. `I` is passed to each read.
+pub struct Sampled<S, T, I = ()> {
+    name: &'static str,
+    sensor: Arc<Mutex<S>>,
+    read: fn(&mut S, I) -> T,
+    latest: Arc<Mutex<T>>,
+    /// Read in progress or last finished, with its start time
+    running: Option<(JoinHandle<()>, Instant)>,
+    stalled: bool,
+    /// Reads that stalled or panicked
+    failures: u64,
+}
+
+/// Read status of a sampled sensor.
+#[derive(Debug, Clone, Copy)]
+pub struct ReadHealth {
+    pub name: &'static str,
+    pub failures: u64,
+    pub stalled: bool,
+}
+
+impl<S, T, I> Sampled<S, T, I>
+where
+    S: Send + 'static,
+    T: Clone + Default + Send + 'static,
+    I: Send + 'static,
+{
+    /// Wraps `sensor`, read with `read`. Readings are the default until the
+    /// first read completes.
+    pub fn new(name: &'static str, sensor: S, read: fn(&mut S, I) -> T) -> Self {
+        Self {
+            name,
+            sensor: Arc::new(Mutex::new(sensor)),
+            read,
+            latest: Arc::new(Mutex::new(T::default())),
+            running: None,
+            stalled: false,
+            failures: 0,
+        }
+    }
+
+    /// Starts a read unless the previous one is still running. The returned
+    /// receiver completes when the read has finished. Must be called from
+    /// within the Tokio runtime.
+    pub fn start(&mut self, input: I) -> Option<oneshot::Receiver<()>> {
+        if let Some((ref mut handle, started)) = self.running {
+            if !handle.is_finished() {
+                if !self.stalled && started.elapsed() >= STALLED {
+                    warn!(
+                        "{} sensor read has not returned for {:?}, using its last reading",
+                        self.name,
+                        started.elapsed()
+                    );
+                    self.stalled = true;
+                    self.failures += 1;
+                }
+                return None;
+            }
+            if let Some(Err(e)) = handle.now_or_never() {
+                warn!("{} sensor read failed: {}", self.name, e);
+                self.failures += 1;
+            }
+            if self.stalled {
+                info!("{} sensor read returned again", self.name);
+                self.stalled = false;
+            }
+        }
+
+        let (done_tx, done) = oneshot::channel();
+        let sensor = self.sensor.clone();
+        let latest = self.latest.clone();
+        let read = self.read;
+        let handle = tokio::task::spawn_blocking(move || {
+            let reading = read(&mut lock(&sensor), input);
+            *lock(&latest) = reading;
+            let _ = done_tx.send(());
+        });
+        self.running = Some((handle, Instant::now()));
+        Some(done)
+    }
+
+    /// Returns the latest completed reading.
+    pub fn latest(&self) -> T {
+        lock(&self.latest).clone()
+    }
+
+    /// Returns the read status of the sensor.
+    pub fn health(&self) -> ReadHealth {
+        ReadHealth {
+            name: self.name,
+            failures: self.failures,
+            stalled: self.stalled,
+        }
+    }
+
+    /// Runs `f` on the sensor, waiting for a read in progress to finish.
+    /// Use [`handle`](Self::handle) instead while holding another lock.
+    pub fn with<R>(&self, f: impl FnOnce(&mut S) -> R) -> R {
+        f(&mut lock(&self.sensor))
+    }
+
+    /// Returns a handle to the sensor, so it can be changed after locks on
+    /// whatever holds this `Sampled` are released.
+    pub fn handle(&self) -> SensorHandle<S> {
+        SensorHandle(self.sensor.clone())
+    }
+}
+
+/// A sampled sensor, detached from its readings.
+pub struct SensorHandle<S>(Arc<Mutex<S>>);
+
+impl<S> SensorHandle<S> {
+    /// Runs `f` on the sensor, waiting for a read in progress to finish.
+    pub fn with<R>(&self, f: impl FnOnce(&mut S) -> R) -> R {
+        f(&mut lock(&self.0))
+    }
+}
+
+/// Locks `mutex`, ignoring poisoning by a read that panicked.
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Waits up to [`READ_WAIT`] for the reads behind `done` to finish.
+pub async fn wait_for_reads(done: Vec<oneshot::Receiver<()>>) {
+    let _ = tokio::time::timeout(READ_WAIT, futures::future::join_all(done)).await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_slow_read_keeps_last_reading() {
+        fn read(count: &mut u32, delay: Duration) -> u32 {
+            std::thread::sleep(delay);
+            *count += 1;
+            *count
+        }
+        let mut sampled = Sampled::new("test", 0, read);
+        assert_eq!(sampled.latest(), 0);
+
+        let done = sampled.start(Duration::ZERO).unwrap();
+        wait_for_reads(vec![done]).await;
+        assert_eq!(sampled.latest(), 1);
+
+        // A read outlasting the wait leaves the previous reading in place
+        // and blocks further reads until it returns
+        let done = sampled.start(READ_WAIT * 3).unwrap();
+        wait_for_reads(vec![done]).await;
+        assert_eq!(sampled.latest(), 1);
+        assert!(sampled.start(Duration::ZERO).is_none());
+
+        tokio::time::sleep(READ_WAIT * 3).await;
+        assert_eq!(sampled.latest(), 2);
+        let done = sampled.start(Duration::ZERO).unwrap();
+        wait_for_reads(vec![done]).await;
+        assert_eq!(sampled.latest(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_panicked_read_keeps_sensor() {
+        fn read(count: &mut u32, panic: bool) -> u32 {
+            *count += 1;
+            if panic {
+                panic!("read failed");
+            }
+            *count
+        }
+        let mut sampled = Sampled::new("test", 0, read);
+        let done = sampled.start(false).unwrap();
+        wait_for_reads(vec![done]).await;
+        assert_eq!(sampled.latest(), 1);
+
+        let done = sampled.start(true).unwrap();
+        wait_for_reads(vec![done]).await;
+        assert_eq!(sampled.latest(), 1);
+        // Let the panicked task finish unwinding
+        tokio::time::sleep(READ_WAIT).await;
+
+        // The sensor lock was poisoned, but reads and changes carry on
+        let done = sampled.start(false).unwrap();
+        wait_for_reads(vec![done]).await;
+        assert_eq!(sampled.latest(), 3);
+        assert_eq!(sampled.health().failures, 1);
+        assert_eq!(sampled.with(|count| *count), 3);
+        assert_eq!(sampled.handle().with(|count| *count), 3);
+    }
+
+    #[tokio::test]
+    async fn test_handle_waits_for_read() {
+        fn read(count: &mut u32, delay: Duration) -> u32 {
+            std::thread::sleep(delay);
+            *count += 1;
+            *count
+        }
+        let mut sampled = Sampled::new("test", 0, read);
+        let handle = sampled.handle();
+        let done = sampled.start(READ_WAIT).unwrap();
+        // Give the read time to take the sensor lock
+        tokio::time::sleep(READ_WAIT / 4).await;
+        // The handle is usable without borrowing the Sampled, and sees the
+        // sensor only after the read has finished
+        let count = tokio::task::spawn_blocking(move || handle.with(|count| *count))
+            .await
+            .unwrap();
+        assert_eq!(count, 1);
+        wait_for_reads(vec![done]).await;
+        assert_eq!(sampled.latest(), 1);
+    }
+}
@@ -0,0 +1,95 @@
+//! Active SSH session sensor.
+//!
+//! Reads the login records in utmp directly rather than shelling out to
+//! `who`, so it is cheap enough to sample every frame.
+
+use std::fs;
+use std::path::Path;
+
+use super::data::SshSessions;
+
+/// Path to the utmp login records.
+const UTMP_PATH: &str = "/var/run/utmp";
+
+/// Size of a glibc `struct utmp` record on Linux.
+const RECORD_SIZE: usize = 384;
+
+/// `ut_type` value for a normal user login.
+const USER_PROCESS: i16 = 7;
+
+/// Field offsets within a utmp record.
+const OFFSET_PID: usize = 4;
+const OFFSET_LINE: usize = 8;
+const LINE_LEN: usize = 32;
+const OFFSET_HOST: usize = 76;
+const HOST_LEN: usize = 256;
+const OFFSET_TV_SEC: usize = 340;
+
+/// Active SSH session sensor.
+pub struct SessionSensor;
+
+impl SessionSensor {
+    /// Creates a new session sensor.
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Counts active remote sessions and finds the most recent source address.
+    pub fn sample(&self) -> SshSessions {
+        let Ok(bytes) = fs::read(UTMP_PATH) else {
+            return SshSessions::default();
+        };
+
+        let mut sessions = SshSessions::default();
+        let mut latest_login = 0;
+
+        for record in bytes.chunks_exact(RECORD_SIZE) {
+            let ut_type = i16::from_ne_bytes([record[0], record[1]]);
+            if ut_type != USER_PROCESS {
+                continue;
+            }
+
+            // Remote logins have a pseudo-terminal and a source host;
+            // local graphical sessions use an X display as the host
+            let line = c_string(&record[OFFSET_LINE..OFFSET_LINE + LINE_LEN]);
+            let host = c_string(&record[OFFSET_HOST..OFFSET_HOST + HOST_LEN]);
+            if !line.starts_with("pts/") || host.is_empty() || host.starts_with(':') {
+                continue;
+            }
+
+            // Skip stale records left behind by sessions that did not log out cleanly
+            let pid = read_i32(record, OFFSET_PID);
+            if !Path::new(&format!("/proc/{}", pid)).exists() {
+                continue;
+            }
+
+            sessions.count += 1;
+            let login = read_i32(record, OFFSET_TV_SEC);
+            if login >= latest_login {
+                latest_login = login;
+                sessions.last_source = Some(host);
+            }
+        }
+
+        sessions
+    }
+}
+
+impl Default for SessionSensor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Reads a native-endian i32 at `offset`.
+fn read_i32(record: &[u8], offset: usize) -> i32 {
+    let mut buf = [0u8; 4];
+    buf.copy_from_slice(&record[offset..offset + 4]);
+    i32::from_ne_bytes(buf)
+}
+
+/// Converts a NUL-padded fixed-size field to a string.
+fn c_string(field: &[u8]) -> String {
+    let end = field.iter().position(|&b| b == 0).unwrap_or(field.len());
+    String::from_utf8_lossy(&field[..end]).into_owned()
+}
@@ -0,0 +1,455 @@
+//! SNMP polling into named custom metrics.
+//!
+//! A minimal SNMPv2c GET client: each configured target is polled on its
+//! own background thread and the values of its OIDs are published as named
+//! metrics (e.g. router WAN throughput, NAS temperatures). Counters can be
+//! turned into per-second rates.
+
+use std::collections::{BTreeMap, HashMap};
+use std::net::{Ipv6Addr, SocketAddr, ToSocketAddrs, UdpSocket};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use anyhow::{bail, Context, Result};
+use tracing::{debug, warn};
+
+use super::data::Metric;
+use super::poller::Poller;
+use crate::config::{SnmpMetricConfig, SnmpTargetConfig};
+
+/// How long to wait for a response.
+const TIMEOUT: Duration = Duration::from_secs(2);
+
+// BER tags used by SNMP
+const TAG_INTEGER: u8 = 0x02;
+const TAG_OCTET_STRING: u8 = 0x04;
+const TAG_NULL: u8 = 0x05;
+const TAG_OID: u8 = 0x06;
+const TAG_SEQUENCE: u8 = 0x30;
+const TAG_COUNTER32: u8 = 0x41;
+const TAG_GAUGE32: u8 = 0x42;
+const TAG_TIMETICKS: u8 = 0x43;
+const TAG_COUNTER64: u8 = 0x46;
+const TAG_GET_REQUEST: u8 = 0xA0;
+const TAG_GET_RESPONSE: u8 = 0xA2;
+
+/// SNMP sensor polling all configured targets.
+pub struct SnmpSensor {
+    /// Latest value of every metric, keyed by name
+    latest: Poller<BTreeMap<String, Metric>>,
+}
+
+impl SnmpSensor {
+    /// Starts polling `targets`. Returns None if none are configured.
+    pub fn start(targets: &[SnmpTargetConfig]) -> Option<Self> {
+        if targets.is_empty() {
+            return None;
+        }
+
+        let latest = Poller::new(BTreeMap::new());
+        for target in targets {
+            if target.metrics.is_empty() {
+                continue;
+            }
+            let oids = match target
+                .metrics
+                .iter()
+                .map(|m| parse_oid(&m.oid))
+                .collect::<Result<Vec<_>>>()
+            {
+                Ok(oids) => oids,
+                Err(e) => {
+                    warn!("Not polling SNMP agent {}: {:#}", target.host, e);
+                    continue;
+                }
+            };
+            debug!(
+                "Polling {} SNMP metrics from {}",
+                target.metrics.len(),
+                target.host
+            );
+            let mut agent = Agent {
+                target: target.clone(),
+                oids,
+                counters: HashMap::new(),
+                request_id: 1,
+            };
+            let interval = Duration::from_secs(target.interval.max(1));
+            latest.spawn(&format!("snmp-{}", target.host), interval, move |latest| {
+                agent.poll(latest)
+            });
+        }
+
+        Some(Self { latest })
+    }
+
+    /// Returns the latest metric values.
+    pub fn metrics(&self) -> BTreeMap<String, Metric> {
+        self.latest.latest()
+    }
+}
+
+/// Polling state for one target.
+struct Agent {
+    target: SnmpTargetConfig,
+    oids: Vec<Vec<u32>>,
+    /// Last raw counter value and sample time for rate metrics
+    counters: HashMap<String, (f64, Instant)>,
+    request_id: i32,
+}
+
+impl Agent {
+    /// Polls the target once, updating its metrics.
+    fn poll(&mut self, latest: &Mutex<BTreeMap<String, Metric>>) {
+        let target = &self.target;
+        self.request_id = self.request_id.wrapping_add(1);
+        match get(&target.host, &target.community, &self.oids, self.request_id) {
+            Ok(values) => {
+                let now = Instant::now();
+                let mut metrics = latest.lock().unwrap();
+                for (config, raw) in target.metrics.iter().zip(values) {
+                    let Some(raw) = raw else {
+                        metrics.remove(&config.name);
+                        continue;
+                    };
+                    let value = if config.rate {
+                        let last = self.counters.insert(config.name.clone(), (raw, now));
+                        match last {
+                            // A smaller value means the counter wrapped or reset
+                            Some((last_raw, at)) if raw >= last_raw => {
+                                (raw - last_raw) / now.duration_since(at).as_secs_f64().max(0.001)
+                            }
+                            _ => continue,
+                        }
+                    } else {
+                        raw
+                    };
+                    metrics.insert(config.name.clone(), to_metric(config, value * config.scale));
+                }
+            }
+            Err(e) => {
+                debug!("SNMP poll of {} failed: {:#}", target.host, e);
+                let mut metrics = latest.lock().unwrap();
+                for config in &target.metrics {
+                    metrics.remove(&config.name);
+                }
+            }
+        }
+    }
+}
+
+/// Builds a metric, checking its alert thresholds.
+fn to_metric(config: &SnmpMetricConfig, value: f64) -> Metric {
+    Metric::new(value, &config.unit, config.alert_above, config.alert_below)
+}
+
+/// Sends a GET for `oids` and returns the numeric value of each, in order.
+/// OIDs the agent does not have (or non-numeric values) yield None.
+fn get(
+    host: &str,
+    community: &str,
+    oids: &[Vec<u32>],
+    request_id: i32,
+) -> Result<Vec<Option<f64>>> {
+    let address = agent_address(host);
+    let target = address
+        .to_socket_addrs()
+        .with_context(|| format!("Failed to resolve {}", address))?
+        .next()
+        .with_context(|| format!("No address for {}", address))?;
+    let local = match target {
+        SocketAddr::V4(_) => "0.0.0.0:0",
+        SocketAddr::V6(_) => "[::]:0",
+    };
+    let socket = UdpSocket::bind(local).context("Failed to open UDP socket")?;
+    socket.set_read_timeout(Some(TIMEOUT))?;
+    socket
+        .send_to(&encode_get(community, oids, request_id)?, target)
+        .with_context(|| format!("Failed to send SNMP request to {}", address))?;
+
+    let mut buf = [0u8; 65535];
+    loop {
+        let len = socket.recv(&mut buf).context("No SNMP response")?;
+        match decode_response(&buf[..len]) {
+            Ok((id, values)) if id == request_id => return Ok(values),
+            // A late reply to an earlier request; keep waiting
+            Ok(_) => continue,
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// Adds the default port to an agent address unless it has one. Bare IPv6
+/// literals are bracketed; "[addr]:port" and "host:port" are kept.
+fn agent_address(host: &str) -> String {
+    if host.parse::<Ipv6Addr>().is_ok() {
+        format!("[{}]:161", host)
+    } else if host.starts_with('[') && host.ends_with(']') {
+        format!("{}:161", host)
+    } else if host.contains(':') {
+        host.to_string()
+    } else {
+        format!("{}:161", host)
+    }
+}
+
+/// Parses a dotted OID such as "1.3.6.1.2.1.1.3.0".
+fn parse_oid(oid: &str) -> Result<Vec<u32>> {
+    let arcs = oid
+        .trim_start_matches('.')
+        .split('.')
+        .map(|arc| arc.parse::<u32>())
+        .collect::<Result<Vec<_>, _>>()
+        .with_context(|| format!("Invalid OID: {}", oid))?;
+    if arcs.len() < 2 || first_subidentifier(&arcs).is_none() {
+        bail!("Invalid OID: {}", oid);
+    }
+    Ok(arcs)
+}
+
+/// Packs the first two arcs into one subidentifier. The first arc must be
+/// 0, 1 or 2, and the second below 40 unless the first is 2.
+fn first_subidentifier(arcs: &[u32]) -> Option<u32> {
+    match arcs {
+        [first @ 0..=1, second @ 0..=39, ..] | [first @ 2, second, ..] => {
+            first.checked_mul(40)?.checked_add(*second)
+        }
+        _ => None,
+    }
+}
+
+/// Appends a BER length.
+fn push_length(out: &mut Vec<u8>, len: usize) {
+    if len < 0x80 {
+        out.push(len as u8);
+    } else {
+        let bytes = len.to_be_bytes();
+        let skip = bytes.iter().take_while(|&&b| b == 0).count();
+        out.push(0x80 | (bytes.len() - skip) as u8);
+        out.extend_from_slice(&bytes[skip..]);
+    }
+}
+
+/// Appends a TLV.
+fn push_tlv(out: &mut Vec<u8>, tag: u8, value: &[u8]) {
+    out.push(tag);
+    push_length(out, value.len());
+    out.extend_from_slice(value);
+}
+
+/// Encodes a signed integer in the minimal two's complement form.
+fn encode_integer(value: i64) -> Vec<u8> {
+    let bytes = value.to_be_bytes();
+    let mut start = 0;
+    while start < 7 {
+        let redundant = (bytes[start] == 0x00 && bytes[start + 1] & 0x80 == 0)
+            || (bytes[start] == 0xFF && bytes[start + 1] & 0x80 != 0);
+        if !redundant {
+            break;
+        }
+        start += 1;
+    }
+    bytes[start..].to_vec()
+}
+
+/// Encodes OID arcs (the first two are packed into one subidentifier).
+fn encode_oid(arcs: &[u32]) -> Result<Vec<u8>> {
+    let Some(first) = first_subidentifier(arcs) else {
+        bail!("Invalid OID arcs: {:?}", arcs);
+    };
+    let mut out = Vec::new();
+    let mut push_arc = |arc: u32| {
+        let mut chunks = vec![(arc & 0x7F) as u8];
+        let mut rest = arc >> 7;
+        while rest > 0 {
+            chunks.push(0x80 | (rest & 0x7F) as u8);
+            rest >>= 7;
+        }
+        out.extend(chunks.iter().rev());
+    };
+    push_arc(first);
+    for &arc in &arcs[2..] {
+        push_arc(arc);
+    }
+    Ok(out)
+}
+
+/// Encodes an SNMPv2c GetRequest.
+fn encode_get(community: &str, oids: &[Vec<u32>], request_id: i32) -> Result<Vec<u8>> {
+    let mut varbinds = Vec::new();
+    for oid in oids {
+        let mut varbind = Vec::new();
+        push_tlv(&mut varbind, TAG_OID, &encode_oid(oid)?);
+        push_tlv(&mut varbind, TAG_NULL, &[]);
+        push_tlv(&mut varbinds, TAG_SEQUENCE, &varbind);
+    }
+
+    let mut pdu = Vec::new();
+    push_tlv(&mut pdu, TAG_INTEGER, &encode_integer(request_id as i64));
+    push_tlv(&mut pdu, TAG_INTEGER, &[0]); // error-status
+    push_tlv(&mut pdu, TAG_INTEGER, &[0]); // error-index
+    push_tlv(&mut pdu, TAG_SEQUENCE, &varbinds);
+
+    let mut message = Vec::new();
+    push_tlv(&mut message, TAG_INTEGER, &[1]); // version: v2c
+    push_tlv(&mut message, TAG_OCTET_STRING, community.as_bytes());
+    push_tlv(&mut message, TAG_GET_REQUEST, &pdu);
+
+    let mut out = Vec::new();
+    push_tlv(&mut out, TAG_SEQUENCE, &message);
+    Ok(out)
+}
+
+/// Reads one TLV from the start of `buf`, returning (tag, value, rest).
+fn read_tlv(buf: &[u8]) -> Result<(u8, &[u8], &[u8])> {
+    if buf.len() < 2 {
+        bail!("Truncated SNMP message");
+    }
+    let tag = buf[0];
+    let (len, header) = if buf[1] & 0x80 == 0 {
+        (buf[1] as usize, 2)
+    } else {
+        let count = (buf[1] & 0x7F) as usize;
+        if count == 0 || count > 4 || buf.len() < 2 + count {
+            bail!("Invalid SNMP length");
+        }
+        let len = buf[2..2 + count]
+            .iter()
+            .fold(0usize, |acc, &b| (acc << 8) | b as usize);
+        (len, 2 + count)
+    };
+    if buf.len() < header + len {
+        bail!("Truncated SNMP message");
+    }
+    Ok((tag, &buf[header..header + len], &buf[header + len..]))
+}
+
+/// Reads a TLV and checks its tag.
+fn expect_tlv(buf: &[u8], tag: u8) -> Result<(&[u8], &[u8])> {
+    let (found, value, rest) = read_tlv(buf)?;
+    if found != tag {
+        bail!("Unexpected SNMP tag {:#04x}, expected {:#04x}", found, tag);
+    }
+    Ok((value, rest))
+}
+
+/// Decodes a signed integer.
+fn decode_integer(bytes: &[u8]) -> i64 {
+    let initial = if bytes.first().is_some_and(|b| b & 0x80 != 0) {
+        -1
+    } else {
+        0
+    };
+    bytes.iter().fold(initial, |acc, &b| (acc << 8) | b as i64)
+}
+
+/// Decodes an unsigned integer (Counter32, Gauge32, TimeTicks, Counter64).
+fn decode_unsigned(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0u64, |acc, &b| (acc << 8) | b as u64)
+}
+
+/// Decodes a GetResponse into its request ID and varbind values.
+fn decode_response(buf: &[u8]) -> Result<(i32, Vec<Option<f64>>)> {
+    let (message, _) = expect_tlv(buf, TAG_SEQUENCE)?;
+    let (_version, rest) = expect_tlv(message, TAG_INTEGER)?;
+    let (_community, rest) = expect_tlv(rest, TAG_OCTET_STRING)?;
+    let (pdu, _) = expect_tlv(rest, TAG_GET_RESPONSE)?;
+
+    let (request_id, rest) = expect_tlv(pdu, TAG_INTEGER)?;
+    let (error_status, rest) = expect_tlv(rest, TAG_INTEGER)?;
+    let (_error_index, rest) = expect_tlv(rest, TAG_INTEGER)?;
+    let error_status = decode_integer(error_status);
+    if error_status != 0 {
+        bail!("SNMP agent returned error status {}", error_status);
+    }
+
+    let (mut varbinds, _) = expect_tlv(rest, TAG_SEQUENCE)?;
+    let mut values = Vec::new();
+    while !varbinds.is_empty() {
+        let (varbind, rest) = expect_tlv(varbinds, TAG_SEQUENCE)?;
+        varbinds = rest;
+        let (_oid, rest) = expect_tlv(varbind, TAG_OID)?;
+        let (tag, value, _) = read_tlv(rest)?;
+        values.push(match tag {
+            TAG_INTEGER => Some(decode_integer(value) as f64),
+            TAG_COUNTER32 | TAG_GAUGE32 | TAG_TIMETICKS | TAG_COUNTER64 => {
+                Some(decode_unsigned(value) as f64)
+            }
+            // Some agents report sensor readings as strings, e.g. "42.5"
+            TAG_OCTET_STRING => std::str::from_utf8(value)
+                .ok()
+                .and_then(|s| s.trim().parse().ok()),
+            // noSuchObject, noSuchInstance, endOfMibView and other types
+            _ => None,
+        });
+    }
+
+    Ok((decode_integer(request_id) as i32, values))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_integer_minimal() {
+        assert_eq!(encode_integer(0), vec![0x00]);
+        assert_eq!(encode_integer(127), vec![0x7F]);
+        assert_eq!(encode_integer(128), vec![0x00, 0x80]);
+        assert_eq!(encode_integer(-1), vec![0xFF]);
+        assert_eq!(decode_integer(&encode_integer(300)), 300);
+        assert_eq!(decode_integer(&encode_integer(-300)), -300);
+    }
+
+    #[test]
+    fn test_encode_oid() {
+        let oid = parse_oid("1.3.6.1.2.1.1.3.0").unwrap();
+        assert_eq!(encode_oid(&oid).unwrap(), vec![0x2B, 6, 1, 2, 1, 1, 3, 0]);
+        // Arcs above 127 use base-128 continuation bytes
+        assert_eq!(encode_oid(&[1, 3, 200]).unwrap(), vec![0x2B, 0x81, 0x48]);
+        // Under joint-iso-itu-t the second arc may exceed 39
+        assert_eq!(encode_oid(&[2, 100]).unwrap(), vec![0x81, 0x34]);
+        assert!(parse_oid("1.3.x").is_err());
+        assert!(parse_oid("3.1").is_err());
+        assert!(parse_oid("1.40").is_err());
+        assert!(parse_oid("2.4294967295").is_err());
+        assert!(encode_oid(&[4294967295, 1]).is_err());
+        assert!(encode_oid(&[1]).is_err());
+    }
+
+    #[test]
+    fn test_agent_address() {
+        assert_eq!(agent_address("192.168.1.1"), "192.168.1.1:161");
+        assert_eq!(agent_address("router.lan:1161"), "router.lan:1161");
+        assert_eq!(agent_address("fd00::1"), "[fd00::1]:161");
+        assert_eq!(agent_address("[fd00::1]"), "[fd00::1]:161");
+        assert_eq!(agent_address("[fd00::1]:1161"), "[fd00::1]:1161");
+    }
+
+    #[test]
+    fn test_decode_response() {
+        // Build a response by hand: one Gauge32 and one noSuchInstance
+        let mut varbinds = Vec::new();
+        for (tag, value) in [(TAG_GAUGE32, vec![0x01, 0x00]), (0x81, vec![])] {
+            let mut varbind = Vec::new();
+            push_tlv(&mut varbind, TAG_OID, &encode_oid(&[1, 3, 6, 1]).unwrap());
+            push_tlv(&mut varbind, tag, &value);
+            push_tlv(&mut varbinds, TAG_SEQUENCE, &varbind);
+        }
+        let mut pdu = Vec::new();
+        push_tlv(&mut pdu, TAG_INTEGER, &encode_integer(42));
+        push_tlv(&mut pdu, TAG_INTEGER, &[0]);
+        push_tlv(&mut pdu, TAG_INTEGER, &[0]);
+        push_tlv(&mut pdu, TAG_SEQUENCE, &varbinds);
+        let mut message = Vec::new();
+        push_tlv(&mut message, TAG_INTEGER, &[1]);
+        push_tlv(&mut message, TAG_OCTET_STRING, b"public");
+        push_tlv(&mut message, TAG_GET_RESPONSE, &pdu);
+        let mut buf = Vec::new();
+        push_tlv(&mut buf, TAG_SEQUENCE, &message);
+
+        let (id, values) = decode_response(&buf).unwrap();
+        assert_eq!(id, 42);
+        assert_eq!(values, vec![Some(256.0), None]);
+    }
+}
@@ -0,0 +1,324 @@
+//! On-demand bandwidth test.
+//!
+//! Runs on a background thread using either a configured command
+//! (`speedtest-cli --json` or Ookla `speedtest -f json`) or a built-in plain
+//! HTTP download, and publishes progress for the panel overlay.
+
+use std::io::{Read, Write};
+use std::process::Command;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use anyhow::{bail, Context, Result};
+use tokio::sync::oneshot;
+use tracing::{debug, info, warn};
+
+use super::http;
+use crate::config::SpeedtestConfig;
+
+/// Result of a completed test. Rates are in bytes/second.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct SpeedtestResult {
+    /// Download rate
+    pub download: f64,
+    /// Upload rate (None if the backend does not measure it)
+    pub upload: Option<f64>,
+    /// Latency in milliseconds
+    pub ping_ms: Option<f64>,
+}
+
+/// Current state of the speedtest.
+#[derive(Debug, Clone, Default)]
+pub enum SpeedtestStatus {
+    /// No test has run yet
+    #[default]
+    Idle,
+    /// A test is running. Progress is 0.0-1.0 and rate is the download rate
+    /// so far; both stay at zero for command backends.
+    Running { progress: f32, rate: f64 },
+    /// The last test finished
+    Finished {
+        result: SpeedtestResult,
+        at: Instant,
+    },
+    /// The last test failed
+    Failed { error: String, at: Instant },
+}
+
+/// Runs speedtests and tracks the latest status.
+#[derive(Default)]
+pub struct Speedtest {
+    status: Arc<Mutex<SpeedtestStatus>>,
+}
+
+impl Speedtest {
+    /// Creates an idle speedtest runner.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the current status.
+    pub fn status(&self) -> SpeedtestStatus {
+        self.status.lock().unwrap().clone()
+    }
+
+    /// Starts a test in the background. The returned receiver resolves when
+    /// the test finishes. Fails if neither a command nor a URL is configured
+    /// or a test is already running.
+    pub fn start(
+        &self,
+        config: &SpeedtestConfig,
+    ) -> Result<oneshot::Receiver<Result<SpeedtestResult, String>>> {
+        if config.command.is_empty() && config.url.is_empty() {
+            bail!("No speedtest configured; set speedtest.url or speedtest.command");
+        }
+        {
+            let mut status = self.status.lock().unwrap();
+            if matches!(*status, SpeedtestStatus::Running { .. }) {
+                bail!("A speedtest is already running");
+            }
+            *status = SpeedtestStatus::Running {
+                progress: 0.0,
+                rate: 0.0,
+            };
+        }
+
+        let (done_tx, done_rx) = oneshot::channel();
+        let config = config.clone();
+        let status = self.status.clone();
+        let spawned = thread::Builder::new()
+            .name("speedtest".to_string())
+            .spawn(move || {
+                let result = if config.command.is_empty() {
+                    download_test(&config.url, Duration::from_secs(config.duration), &status)
+                } else {
+                    command_test(&config.command)
+                };
+                let at = Instant::now();
+                let outcome = match result {
+                    Ok(result) => {
+                        info!(
+                            "Speedtest finished: {:.0} B/s down, {:?} B/s up",
+                            result.download, result.upload
+                        );
+                        *status.lock().unwrap() = SpeedtestStatus::Finished { result, at };
+                        Ok(result)
+                    }
+                    Err(e) => {
+                        warn!("Speedtest failed: {:#}", e);
+                        let error = format!("{:#}", e);
+                        *status.lock().unwrap() = SpeedtestStatus::Failed {
+                            error: error.clone(),
+                            at,
+                        };
+                        Err(error)
+                    }
+                };
+                let _ = done_tx.send(outcome);
+            });
+
+        if let Err(e) = spawned {
+            *self.status.lock().unwrap() = SpeedtestStatus::Idle;
+            return Err(e).context("Failed to start speedtest thread");
+        }
+        Ok(done_rx)
+    }
+}
+
+/// Runs a speedtest command and parses its JSON output.
+fn command_test(command: &str) -> Result<SpeedtestResult> {
+    debug!("Running speedtest command: {}", command);
+    let output = Command::new("sh")
+        .args(["-c", command])
+        .output()
+        .context("Failed to run speedtest command")?;
+    if !output.status.success() {
+        bail!("Speedtest command exited with {}", output.status);
+    }
+    parse_command_output(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// Parses `speedtest-cli --json` (bits/second) or Ookla `speedtest -f json`
+/// (bytes/second) output.
+fn parse_command_output(output: &str) -> Result<SpeedtestResult> {
+    let json: serde_json::Value =
+        serde_json::from_str(output.trim()).context("Speedtest output is not JSON")?;
+
+    // Ookla: {"ping": {"latency": ms}, "download": {"bandwidth": B/s}, ...}
+    if let Some(download) = json["download"]["bandwidth"].as_f64() {
+        return Ok(SpeedtestResult {
+            download,
+            upload: json["upload"]["bandwidth"].as_f64(),
+            ping_ms: json["ping"]["latency"].as_f64(),
+        });
+    }
+
+    // speedtest-cli: {"download": bit/s, "upload": bit/s, "ping": ms}
+    if let Some(download) = json["download"].as_f64() {
+        return Ok(SpeedtestResult {
+            download: download / 8.0,
+            upload: json["upload"].as_f64().map(|u| u / 8.0),
+            ping_ms: json["ping"].as_f64(),
+        });
+    }
+
+    bail!("Unrecognized speedtest output")
+}
+
+/// Downloads from a plain HTTP URL for up to `limit`, measuring the rate.
+/// Latency is the TCP connect time.
+fn download_test(
+    url: &str,
+    limit: Duration,
+    status: &Mutex<SpeedtestStatus>,
+) -> Result<SpeedtestResult> {
+    let (host, port, path) = http::parse_url(url)?;
+    debug!("Speedtest download from {}:{}{}", host, port, path);
+
+    let connect_start = Instant::now();
+    let mut stream = http::connect(&host, port)?;
+    let ping_ms = connect_start.elapsed().as_secs_f64() * 1000.0;
+
+    write!(
+        stream,
+        "GET {} HTTP/1.0\r\nHost: {}\r\nUser-Agent: ht32paneld\r\nConnection: close\r\n\r\n",
+        path, host
+    )?;
+
+    // Read until the end of the headers
+    let mut buf = vec![0u8; 64 * 1024];
+    let mut head = Vec::new();
+    let body_start = loop {
+        let n = stream.read(&mut buf)?;
+        if n == 0 {
+            bail!("Connection closed before response headers");
+        }
+        head.extend_from_slice(&buf[..n]);
+        if let Some(pos) = head.windows(4).position(|w| w == b"\r\n\r\n") {
+            break pos + 4;
+        }
+        if head.len() > 16 * 1024 {
+            bail!("Response headers too large");
+        }
+    };
+    let headers = String::from_utf8_lossy(&head[..body_start]).to_string();
+    let status_line = headers.lines().next().unwrap_or_default();
+    if status_line.split_whitespace().nth(1) != Some("200") {
+        bail!("Unexpected response: {}", status_line);
+    }
+    let content_length = headers.lines().find_map(|line| {
+        let (name, value) = line.split_once(':')?;
+        if name.eq_ignore_ascii_case("content-length") {
+            value.trim().parse::<u64>().ok()
+        } else {
+            None
+        }
+    });
+
+    let start = Instant::now();
+    let mut received = (head.len() - body_start) as u64;
+    let mut last_update = start;
+    while start.elapsed() < limit {
+        let n = match stream.read(&mut buf) {
+            Ok(0) => break,
+            Ok(n) => n,
+            Err(e) => return Err(e).context("Download failed"),
+        };
+        received += n as u64;
+
+        if last_update.elapsed() >= Duration::from_millis(250) {
+            last_update = Instant::now();
+            let elapsed = start.elapsed().as_secs_f64();
+            let by_time = elapsed / limit.as_secs_f64();
+            let by_size = content_length.map_or(0.0, |len| received as f64 / len.max(1) as f64);
+            *status.lock().unwrap() = SpeedtestStatus::Running {
+                progress: by_time.max(by_size).min(1.0) as f32,
+                rate: received as f64 / elapsed.max(0.001),
+            };
+        }
+    }
+
+    let elapsed = start.elapsed().as_secs_f64();
+    if received == 0 || elapsed <= 0.0 {
+        bail!("No data received");
+    }
+    Ok(SpeedtestResult {
+        download: received as f64 / elapsed,
+        upload: None,
+        ping_ms: Some(ping_ms),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::TcpListener;
+
+    /// Serves one canned HTTP response on a local port and returns its URL.
+    fn serve(response: &'static [u8]) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            // Read the whole request; closing with unread data resets the
+            // connection before the client sees the response
+            let mut request = Vec::new();
+            let mut buf = [0u8; 1024];
+            while !request.ends_with(b"\r\n\r\n") {
+                match stream.read(&mut buf) {
+                    Ok(0) | Err(_) => break,
+                    Ok(n) => request.extend_from_slice(&buf[..n]),
+                }
+            }
+            stream.write_all(response).unwrap();
+        });
+        format!("http://127.0.0.1:{}/file.bin", port)
+    }
+
+    #[test]
+    fn test_parse_command_output() {
+        let ookla = r#"{"ping": {"latency": 12.5}, "download": {"bandwidth": 1000000},
+            "upload": {"bandwidth": 250000}}"#;
+        assert_eq!(
+            parse_command_output(ookla).unwrap(),
+            SpeedtestResult {
+                download: 1_000_000.0,
+                upload: Some(250_000.0),
+                ping_ms: Some(12.5),
+            }
+        );
+
+        let cli = r#"{"download": 8000000.0, "upload": 800000.0, "ping": 20.0}"#;
+        assert_eq!(
+            parse_command_output(cli).unwrap(),
+            SpeedtestResult {
+                download: 1_000_000.0,
+                upload: Some(100_000.0),
+                ping_ms: Some(20.0),
+            }
+        );
+
+        assert!(parse_command_output("not json").is_err());
+        assert!(parse_command_output(r#"{"result": "ok"}"#).is_err());
+    }
+
+    #[test]
+    fn test_download_test() {
+        let url = serve(b"HTTP/1.0 200 OK\r\nContent-Length: 11\r\n\r\nhello world");
+        let status = Mutex::new(SpeedtestStatus::Idle);
+        let result = download_test(&url, Duration::from_secs(5), &status).unwrap();
+        assert!(result.download > 0.0);
+        assert!(result.upload.is_none());
+        assert!(result.ping_ms.is_some());
+
+        let url = serve(b"HTTP/1.0 404 Not Found\r\nContent-Length: 0\r\n\r\n");
+        assert!(download_test(&url, Duration::from_secs(5), &status).is_err());
+    }
+
+    #[test]
+    fn test_start_requires_backend() {
+        let config = SpeedtestConfig::default();
+        assert!(Speedtest::new().start(&config).is_err());
+    }
+}
@@ -0,0 +1,266 @@
+//! ZFS pool and Linux software RAID health sensor.
+//!
+//! Parses `zpool status` and `/proc/mdstat` on a background thread. When a
+//! pool becomes degraded or recovers, the configured alert command is run
+//! with the pool's details in its environment.
+
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use std::thread;
+use std::time::Duration;
+
+use tracing::{debug, info, warn};
+
+use super::data::{PoolKind, PoolStatus};
+use super::poller::Poller;
+use crate::config::StorageConfig;
+
+/// Storage pool sensor backed by a background polling thread.
+pub struct StorageSensor {
+    /// Last listing (empty until the first poll)
+    latest: Poller<Vec<PoolStatus>>,
+}
+
+impl StorageSensor {
+    /// Starts polling. Returns None if disabled or neither ZFS nor md is
+    /// present.
+    pub fn start(config: &StorageConfig) -> Option<Self> {
+        if !config.enable {
+            return None;
+        }
+        let zfs = Path::new("/sys/module/zfs").exists();
+        let md = Path::new("/proc/mdstat").exists();
+        if !zfs && !md {
+            debug!("No ZFS or md support found, storage pool sensor disabled");
+            return None;
+        }
+
+        let alert_command = config.alert_command.clone();
+        let interval = Duration::from_secs(config.interval.max(5));
+        // Pools reported degraded by the previous poll
+        let mut degraded: Vec<String> = Vec::new();
+        let latest = Poller::start("storage-poll", Vec::new(), interval, move || {
+            let mut pools = Vec::new();
+            if zfs {
+                pools.extend(zpool_status());
+            }
+            if md {
+                if let Ok(mdstat) = fs::read_to_string("/proc/mdstat") {
+                    pools.extend(parse_mdstat(&mdstat));
+                }
+            }
+
+            for pool in &pools {
+                let was_degraded = degraded.contains(&pool.name);
+                if pool.degraded != was_degraded {
+                    if pool.degraded {
+                        warn!("Storage pool {} is {}", pool.name, pool.state);
+                    } else {
+                        info!("Storage pool {} is {}", pool.name, pool.state);
+                    }
+                    run_alert(&alert_command, pool);
+                }
+            }
+            degraded = pools
+                .iter()
+                .filter(|p| p.degraded)
+                .map(|p| p.name.clone())
+                .collect();
+            Some(pools)
+        })?;
+
+        Some(Self { latest })
+    }
+
+    /// Returns the last known pool states.
+    pub fn pools(&self) -> Vec<PoolStatus> {
+        self.latest.latest()
+    }
+}
+
+/// Runs the alert command for a pool whose health changed.
+fn run_alert(command: &str, pool: &PoolStatus) {
+    if command.is_empty() {
+        return;
+    }
+    let kind = match pool.kind {
+        PoolKind::Zfs => "zfs",
+        PoolKind::Md => "md",
+    };
+    let result = Command::new("sh")
+        .args(["-c", command])
+        .env("HT32_POOL", &pool.name)
+        .env("HT32_POOL_KIND", kind)
+        .env("HT32_POOL_STATE", &pool.state)
+        .env("HT32_POOL_DEGRADED", if pool.degraded { "1" } else { "0" })
+        .spawn();
+    match result {
+        // Reap the child in the background so it does not linger as a zombie
+        Ok(mut child) => {
+            thread::spawn(move || child.wait());
+        }
+        Err(e) => warn!("Failed to run storage alert command: {}", e),
+    }
+}
+
+/// Runs `zpool status` and parses its output.
+fn zpool_status() -> Vec<PoolStatus> {
+    match Command::new("zpool").arg("status").output() {
+        Ok(output) if output.status.success() => {
+            parse_zpool_status(&String::from_utf8_lossy(&output.stdout))
+        }
+        Ok(output) => {
+            debug!(
+                "zpool status failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+            Vec::new()
+        }
+        Err(e) => {
+            debug!("Failed to run zpool: {}", e);
+            Vec::new()
+        }
+    }
+}
+
+/// Parses `zpool status` output. Scrub and resilver progress come from the
+/// "N% done" figure following a "scan: ... in progress" line.
+fn parse_zpool_status(output: &str) -> Vec<PoolStatus> {
+    let mut pools: Vec<PoolStatus> = Vec::new();
+    let mut scanning = false;
+    for line in output.lines() {
+        let line = line.trim();
+        if let Some(name) = line.strip_prefix("pool:") {
+            pools.push(PoolStatus {
+                name: name.trim().to_string(),
+                kind: PoolKind::Zfs,
+                ..Default::default()
+            });
+            scanning = false;
+            continue;
+        }
+        let Some(pool) = pools.last_mut() else {
+            continue;
+        };
+        if let Some(state) = line.strip_prefix("state:") {
+            pool.state = state.trim().to_string();
+            pool.degraded = pool.state != "ONLINE";
+        } else if let Some(scan) = line.strip_prefix("scan:") {
+            scanning = scan.contains("in progress");
+        } else if line.starts_with("config:") {
+            scanning = false;
+        } else if scanning {
+            if let Some(percent) = percent_before(line, "% done") {
+                pool.scrub_progress = Some(percent);
+            }
+        }
+    }
+    pools
+}
+
+/// Parses `/proc/mdstat`. An array is degraded when its member map (e.g.
+/// `[U_]`) shows a missing device or the array is inactive.
+fn parse_mdstat(mdstat: &str) -> Vec<PoolStatus> {
+    let mut pools: Vec<PoolStatus> = Vec::new();
+    for line in mdstat.lines() {
+        if let Some((name, rest)) = line.split_once(" : ") {
+            let name = name.trim();
+            if !name.starts_with("md") {
+                continue;
+            }
+            let inactive = rest.split_whitespace().next() == Some("inactive");
+            pools.push(PoolStatus {
+                name: name.to_string(),
+                kind: PoolKind::Md,
+                state: if inactive { "INACTIVE" } else { "ONLINE" }.to_string(),
+                degraded: inactive,
+                scrub_progress: None,
+            });
+            continue;
+        }
+        let Some(pool) = pools.last_mut() else {
+            continue;
+        };
+        let line = line.trim();
+        if line.starts_with('[') || line.contains("blocks") {
+            let map = line
+                .split_whitespace()
+                .rev()
+                .find(|w| w.starts_with('[') && w.chars().all(|c| "[U_]".contains(c)));
+            if map.is_some_and(|m| m.contains('_')) {
+                pool.state = "DEGRADED".to_string();
+                pool.degraded = true;
+            }
+        }
+        for action in ["check", "resync", "recovery", "reshape"] {
+            if let Some(pos) = line.find(&format!("{} =", action)) {
+                pool.scrub_progress = percent_before(&line[pos..], "%");
+            }
+        }
+    }
+    pools
+}
+
+/// Returns the number immediately before the first `marker` in `text`.
+fn percent_before(text: &str, marker: &str) -> Option<f32> {
+    let end = text.find(marker)?;
+    let start = text[..end]
+        .rfind(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .map_or(0, |i| i + 1);
+    text[start..end].parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_zpool_status_scrub() {
+        let output = "  pool: tank
+ state: DEGRADED
+status: One or more devices could not be used.
+  scan: scrub in progress since Sun Oct 13 00:24:01 2024
+\t1.23T scanned at 1.2G/s, 500G issued at 500M/s, 2.00T total
+\t0B repaired, 24.41% done, 00:51:12 to go
+config:
+
+\tNAME        STATE     READ WRITE CKSUM
+\ttank        DEGRADED     0     0     0
+
+  pool: boot
+ state: ONLINE
+  scan: scrub repaired 0B in 00:00:02 with 0 errors on Sun Oct 13 00:24:03 2024
+config:
+";
+        let pools = parse_zpool_status(output);
+        assert_eq!(pools.len(), 2);
+        assert_eq!(pools[0].name, "tank");
+        assert!(pools[0].degraded);
+        assert_eq!(pools[0].scrub_progress, Some(24.41));
+        assert_eq!(pools[1].state, "ONLINE");
+        assert!(!pools[1].degraded);
+        assert_eq!(pools[1].scrub_progress, None);
+    }
+
+    #[test]
+    fn test_parse_mdstat() {
+        let mdstat = "Personalities : [raid1]
+md0 : active raid1 sdb1[1] sda1[0]
+      976630464 blocks super 1.2 [2/2] [UU]
+      [==>..................]  check = 12.5% (122/976) finish=80.1min speed=100K/sec
+
+md1 : active raid1 sdc1[0] sdd1[1](F)
+      976630464 blocks super 1.2 [2/1] [U_]
+
+unused devices: <none>
+";
+        let pools = parse_mdstat(mdstat);
+        assert_eq!(pools.len(), 2);
+        assert_eq!(pools[0].name, "md0");
+        assert!(!pools[0].degraded);
+        assert_eq!(pools[0].scrub_progress, Some(12.5));
+        assert_eq!(pools[1].state, "DEGRADED");
+        assert!(pools[1].degraded);
+    }
+}
@@ -1,15 +1,81 @@
 //! System information sensor for hostname, uptime, and time.
 
-use chrono::{Datelike, Local, Timelike};
+use chrono::{DateTime, Datelike, FixedOffset, Local, TimeZone, Timelike, Utc};
+use chrono_tz::Tz;
 use std::fs;
 
+use super::data::{CpuInfo, OsInfo};
+
 /// System information provider.
-pub struct SystemInfo;
+pub struct SystemInfo {
+    /// Timezone override; None follows the system timezone (/etc/localtime, TZ)
+    timezone: Option<Tz>,
+    /// OS info, read once since it only changes across reboots
+    os_info: OsInfo,
+    /// CPU model and core counts, read once
+    cpu_info: CpuInfo,
+}
 
 impl SystemInfo {
     /// Creates a new system info provider.
     pub fn new() -> Self {
-        Self
+        Self {
+            timezone: None,
+            os_info: Self::read_os_info(),
+            cpu_info: Self::read_cpu_info(),
+        }
+    }
+
+    /// Returns the CPU model and core counts.
+    pub fn cpu_info(&self) -> &CpuInfo {
+        &self.cpu_info
+    }
+
+    /// Reads the CPU model and core counts from /proc/cpuinfo.
+    fn read_cpu_info() -> CpuInfo {
+        parse_cpu_info(&fs::read_to_string("/proc/cpuinfo").unwrap_or_default())
+    }
+
+    /// Returns the distribution, kernel and architecture.
+    pub fn os_info(&self) -> &OsInfo {
+        &self.os_info
+    }
+
+    /// Reads OS info from os-release and the kernel's sysctl files.
+    fn read_os_info() -> OsInfo {
+        let os_release = fs::read_to_string("/etc/os-release")
+            .or_else(|_| fs::read_to_string("/usr/lib/os-release"))
+            .unwrap_or_default();
+        let field = |key: &str| {
+            os_release.lines().find_map(|line| {
+                line.strip_prefix(key)
+                    .and_then(|rest| rest.strip_prefix('='))
+                    .map(|value| value.trim_matches('"').to_string())
+            })
+        };
+        let distro = field("PRETTY_NAME")
+            .or_else(|| field("NAME"))
+            .unwrap_or_else(|| "Linux".to_string());
+
+        let kernel = fs::read_to_string("/proc/sys/kernel/osrelease")
+            .map(|s| s.trim().to_string())
+            .unwrap_or_default();
+
+        OsInfo {
+            distro,
+            kernel,
+            arch: std::env::consts::ARCH.to_string(),
+        }
+    }
+
+    /// Returns the timezone override, if any.
+    pub fn timezone(&self) -> Option<Tz> {
+        self.timezone
+    }
+
+    /// Sets the timezone used for time and date. None uses the system timezone.
+    pub fn set_timezone(&mut self, timezone: Option<Tz>) {
+        self.timezone = timezone;
     }
 
     /// Returns the hostname of the system.
@@ -30,10 +96,24 @@ impl SystemInfo {
 
     /// Returns individual time/date components: (hour, minute, day, month, year, day_of_week).
     /// Day of week: 0=Sunday, 1=Monday, ..., 6=Saturday.
-    /// Uses the system's local timezone.
+    /// Uses the timezone override if set, otherwise the system's local timezone.
     pub fn time_components(&self) -> (u8, u8, u8, u8, u16, u8, u64) {
-        let now = Local::now();
+        match self.timezone {
+            Some(tz) => Self::components(Utc::now().with_timezone(&tz)),
+            None => Self::components(Local::now()),
+        }
+    }
+
+    /// Returns the current time in the display timezone.
+    pub fn now(&self) -> DateTime<FixedOffset> {
+        match self.timezone {
+            Some(tz) => Utc::now().with_timezone(&tz).fixed_offset(),
+            None => Local::now().fixed_offset(),
+        }
+    }
 
+    /// Splits a zoned timestamp into display components.
+    fn components<Z: TimeZone>(now: DateTime<Z>) -> (u8, u8, u8, u8, u16, u8, u64) {
         let hours = now.hour() as u8;
         let minutes = now.minute() as u8;
         let day = now.day() as u8;
@@ -46,20 +126,12 @@ impl SystemInfo {
         (hours, minutes, day, month, year, day_of_week, timestamp)
     }
 
-    /// Returns the system uptime formatted as "Xd Yh Zm".
-    pub fn uptime(&self) -> String {
-        let uptime_secs = self.uptime_seconds();
-
-        let days = uptime_secs / 86400;
-        let hours = (uptime_secs % 86400) / 3600;
-        let minutes = (uptime_secs % 3600) / 60;
-
-        if days > 0 {
-            format!("{}d {}h {}m", days, hours, minutes)
-        } else if hours > 0 {
-            format!("{}h {}m", hours, minutes)
-        } else {
-            format!("{}m", minutes)
+    /// Returns the boot time formatted as "Jan 15 09:32" in the display timezone.
+    pub fn boot_time(&self, uptime_secs: u64) -> String {
+        let boot = Utc::now() - chrono::Duration::seconds(uptime_secs as i64);
+        match self.timezone {
+            Some(tz) => boot.with_timezone(&tz).format("%b %d %H:%M").to_string(),
+            None => boot.with_timezone(&Local).format("%b %d %H:%M").to_string(),
         }
     }
 
@@ -78,8 +150,116 @@ impl SystemInfo {
     }
 }
 
+/// Parses the CPU model and core counts from /proc/cpuinfo contents.
+fn parse_cpu_info(cpuinfo: &str) -> CpuInfo {
+    let mut model = None;
+    let mut threads = 0;
+    let mut cores = std::collections::HashSet::new();
+    let mut physical_id = "";
+
+    for line in cpuinfo.lines() {
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        match key.trim() {
+            "processor" => threads += 1,
+            // x86 uses "model name"; ARM boards report "Model" or "Hardware"
+            "model name" | "Model" | "Hardware" if model.is_none() => {
+                model = Some(shorten_cpu_model(value))
+            }
+            "physical id" => physical_id = value,
+            "core id" => {
+                cores.insert((physical_id, value));
+            }
+            _ => {}
+        }
+    }
+
+    // Without topology info (e.g. on ARM) assume one thread per core
+    let cores = if cores.is_empty() {
+        threads
+    } else {
+        cores.len() as u32
+    };
+
+    CpuInfo {
+        model: model.unwrap_or_else(|| "CPU".to_string()),
+        cores,
+        threads,
+    }
+}
+
+/// Strips vendor boilerplate from a CPU model name, e.g.
+/// "AMD Ryzen 7 5800U with Radeon Graphics" becomes "Ryzen 7 5800U" and
+/// "Intel(R) Core(TM) i7-8550U CPU @ 1.80GHz" becomes "Core i7-8550U".
+fn shorten_cpu_model(model: &str) -> String {
+    let mut model = model;
+    for cut in [" @ ", " with ", " CPU", " Processor", " Mobile"] {
+        if let Some(pos) = model.find(cut) {
+            model = &model[..pos];
+        }
+    }
+    model
+        .replace("(R)", "")
+        .replace("(TM)", "")
+        .split_whitespace()
+        .filter(|word| !matches!(*word, "AMD" | "Intel" | "Genuine"))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
 impl Default for SystemInfo {
     fn default() -> Self {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shorten_cpu_model() {
+        assert_eq!(
+            shorten_cpu_model("AMD Ryzen 7 5800U with Radeon Graphics"),
+            "Ryzen 7 5800U"
+        );
+        assert_eq!(
+            shorten_cpu_model("Intel(R) Core(TM) i7-8550U CPU @ 1.80GHz"),
+            "Core i7-8550U"
+        );
+        assert_eq!(
+            shorten_cpu_model("Raspberry Pi 4 Model B Rev 1.4"),
+            "Raspberry Pi 4 Model B Rev 1.4"
+        );
+    }
+
+    #[test]
+    fn test_parse_cpu_info() {
+        // Two cores with two threads each
+        let x86 = ["0", "0", "1", "1"]
+            .iter()
+            .enumerate()
+            .map(|(n, core)| {
+                format!(
+                    "processor\t: {n}\nmodel name\t: Intel(R) Core(TM) i3-7100U CPU @ 2.40GHz\n\
+                     physical id\t: 0\ncore id\t\t: {core}\n\n"
+                )
+            })
+            .collect::<String>();
+        let info = parse_cpu_info(&x86);
+        assert_eq!(info.model, "Core i3-7100U");
+        assert_eq!((info.cores, info.threads), (2, 4));
+        assert_eq!(info.summary(), "Core i3-7100U \u{b7} 2C/4T");
+
+        // ARM boards list no topology and name the board at the end
+        let arm = "processor\t: 0\nBogoMIPS\t: 108.00\n\nprocessor\t: 1\nBogoMIPS\t: 108.00\n\n\
+                   Model\t\t: Raspberry Pi 4 Model B Rev 1.4\n";
+        let info = parse_cpu_info(arm);
+        assert_eq!(info.model, "Raspberry Pi 4 Model B Rev 1.4");
+        assert_eq!((info.cores, info.threads), (2, 2));
+
+        assert_eq!(parse_cpu_info("").model, "CPU");
+    }
+}
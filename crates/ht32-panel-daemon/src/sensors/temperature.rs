@@ -1,10 +1,19 @@
 //! CPU temperature sensor.
 
+use super::data::{TEMP_HISTORY_INTERVAL, TEMP_HISTORY_SIZE};
 use super::Sensor;
+use std::collections::VecDeque;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
 use tracing::debug;
 
+/// Directory of hwmon devices.
+const HWMON: &str = "/sys/class/hwmon";
+
+/// Directory of thermal zones.
+const THERMAL_ZONES: &str = "/sys/class/thermal";
+
 /// CPU temperature sensor.
 pub struct TemperatureSensor {
     name: String,
@@ -12,6 +21,65 @@ pub struct TemperatureSensor {
     temp_path: Option<PathBuf>,
     /// Last sampled temperature in Celsius
     last_temp: Option<f64>,
+    /// Temperatures over the last hour, oldest first
+    history: VecDeque<f64>,
+    /// When the history last gained a sample
+    last_history: Option<Instant>,
+}
+
+/// A temperature sample with its recent history.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TemperatureReading {
+    /// Current temperature in Celsius
+    pub temperature: Option<f64>,
+    /// Temperatures over the last hour, oldest first
+    pub history: VecDeque<f64>,
+}
+
+/// A temperature input that can be selected as the CPU temperature.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TemperatureCandidate {
+    /// hwmon chip name and input label, e.g. "k10temp Tctl", or the
+    /// thermal zone type
+    pub label: String,
+    /// Path to the input file (millidegrees Celsius)
+    pub path: PathBuf,
+}
+
+impl TemperatureCandidate {
+    /// Returns the hwmon chip name or thermal zone type.
+    fn chip(&self) -> &str {
+        self.label.split(' ').next().unwrap_or_default()
+    }
+
+    /// Ranks how likely the input is the CPU temperature, lower being
+    /// better. None if it is not a CPU temperature.
+    fn rank(&self) -> Option<u8> {
+        let chip = self.chip();
+        let input = self.label[chip.len()..].trim();
+        let is_zone = self.path.starts_with(THERMAL_ZONES);
+        match chip {
+            // Control and die temperatures of AMD and Intel package sensors
+            "k10temp" | "zenpower" if input == "Tctl" || input == "Tdie" => Some(0),
+            "coretemp" if input.starts_with("Package id") => Some(0),
+            "coretemp" | "k10temp" | "zenpower" | "cpu_thermal" => Some(1),
+            _ if is_zone && ["cpu", "core", "x86"].iter().any(|t| chip.contains(t)) => Some(2),
+            // ACPI zones often report the motherboard rather than the CPU
+            "acpitz" => Some(3),
+            _ if self.path == Path::new(THERMAL_ZONES).join("thermal_zone0/temp") => Some(4),
+            _ => None,
+        }
+    }
+
+    /// Returns whether `selector`, a label or chip name, names this input.
+    fn matches(&self, selector: &str) -> bool {
+        self.label.eq_ignore_ascii_case(selector) || self.chip().eq_ignore_ascii_case(selector)
+    }
+
+    /// Reads the current temperature in Celsius.
+    pub fn read(&self) -> Option<f64> {
+        read_celsius(&self.path)
+    }
 }
 
 impl TemperatureSensor {
@@ -28,68 +96,95 @@ impl TemperatureSensor {
             name: "cpu_temperature".to_string(),
             temp_path,
             last_temp: None,
+            history: VecDeque::with_capacity(TEMP_HISTORY_SIZE),
+            last_history: None,
         }
     }
 
-    /// Detects the best temperature sensor path.
-    /// Prefers CPU-specific sensors over generic thermal zones.
-    fn detect_temp_path() -> Option<PathBuf> {
-        // Try hwmon first - look for CPU-related sensors
-        // Common labels: "coretemp", "k10temp", "cpu_thermal"
-        if let Ok(entries) = fs::read_dir("/sys/class/hwmon") {
-            for entry in entries.flatten() {
-                let hwmon_path = entry.path();
-
-                // Check the name of this hwmon device
-                let name_path = hwmon_path.join("name");
-                if let Ok(name) = fs::read_to_string(&name_path) {
-                    let name = name.trim();
-                    // CPU temperature sensors
-                    if name == "coretemp"
-                        || name == "k10temp"
-                        || name == "zenpower"
-                        || name == "cpu_thermal"
-                        || name == "acpitz"
-                    {
-                        // Find temp1_input (primary temperature)
-                        let temp_path = hwmon_path.join("temp1_input");
-                        if temp_path.exists() {
-                            return Some(temp_path);
-                        }
-                    }
-                }
+    /// Lists the hwmon temperature inputs and thermal zones, in path order.
+    pub fn candidates() -> Vec<TemperatureCandidate> {
+        let mut candidates = Vec::new();
+        for hwmon_path in sorted_entries(HWMON) {
+            let Some(name) = read_trimmed(&hwmon_path.join("name")) else {
+                continue;
+            };
+            for path in sorted_entries(&hwmon_path) {
+                let file = path.file_name().unwrap_or_default().to_string_lossy();
+                let Some(input) = file
+                    .strip_prefix("temp")
+                    .and_then(|rest| rest.strip_suffix("_input"))
+                else {
+                    continue;
+                };
+                let label = read_trimmed(&hwmon_path.join(format!("temp{}_label", input)))
+                    .unwrap_or_else(|| format!("temp{}", input));
+                candidates.push(TemperatureCandidate {
+                    label: format!("{} {}", name, label),
+                    path,
+                });
             }
         }
-
-        // Fallback to thermal_zone - prefer zone0 (usually CPU)
-        for i in 0..10 {
-            let zone_path = PathBuf::from(format!("/sys/class/thermal/thermal_zone{}", i));
-            if zone_path.exists() {
-                // Check the type to prefer CPU zones
-                let type_path = zone_path.join("type");
-                if let Ok(zone_type) = fs::read_to_string(&type_path) {
-                    let zone_type = zone_type.trim().to_lowercase();
-                    if zone_type.contains("cpu")
-                        || zone_type.contains("core")
-                        || zone_type.contains("x86")
-                        || zone_type == "acpitz"
-                    {
-                        let temp_path = zone_path.join("temp");
-                        if temp_path.exists() {
-                            return Some(temp_path);
-                        }
-                    }
-                }
+        for zone_path in sorted_entries(THERMAL_ZONES) {
+            let zone = zone_path.file_name().unwrap_or_default().to_string_lossy();
+            if !zone.starts_with("thermal_zone") {
+                continue;
+            }
+            if let Some(zone_type) = read_trimmed(&zone_path.join("type")) {
+                candidates.push(TemperatureCandidate {
+                    label: zone_type,
+                    path: zone_path.join("temp"),
+                });
             }
         }
+        candidates
+    }
 
-        // Last resort: just use thermal_zone0 if it exists
-        let zone0_temp = PathBuf::from("/sys/class/thermal/thermal_zone0/temp");
-        if zone0_temp.exists() {
-            return Some(zone0_temp);
+    /// Resolves `selector`, a path to a temperature input or the label or
+    /// chip name of a candidate (case-insensitive), to a path.
+    pub fn resolve(selector: &str) -> Option<PathBuf> {
+        if selector.starts_with('/') {
+            let path = PathBuf::from(selector);
+            return read_celsius(&path).is_some().then_some(path);
         }
+        Self::candidates()
+            .into_iter()
+            .find(|candidate| candidate.matches(selector))
+            .map(|candidate| candidate.path)
+    }
 
-        None
+    /// Returns the input read for `selector`: the one it resolves to, or
+    /// the auto-detected input for None or a selector matching nothing.
+    pub fn selected_path(selector: Option<&str>) -> Option<PathBuf> {
+        selector
+            .and_then(Self::resolve)
+            .or_else(Self::detect_temp_path)
+    }
+
+    /// Reads `selector` instead of the auto-detected sensor, or returns to
+    /// auto-detection for None. Returns false, auto-detecting, if the
+    /// selector matches no sensor.
+    pub fn select(&mut self, selector: Option<&str>) -> bool {
+        let resolved = selector.map(Self::resolve);
+        self.temp_path = match resolved {
+            Some(Some(ref path)) => Some(path.clone()),
+            _ => Self::detect_temp_path(),
+        };
+        debug!("Temperature sensor using: {:?}", self.temp_path);
+        // The history of another input would make a misleading curve
+        self.history.clear();
+        self.last_history = None;
+        !matches!(resolved, Some(None))
+    }
+
+    /// Returns the path of the input being read.
+    pub fn path(&self) -> Option<&Path> {
+        self.temp_path.as_deref()
+    }
+
+    /// Detects the best temperature sensor path.
+    /// Prefers CPU package sensors over generic and ACPI thermal zones.
+    fn detect_temp_path() -> Option<PathBuf> {
+        best_candidate(Self::candidates()).map(|candidate| candidate.path)
     }
 
     /// Returns the last sampled temperature in Celsius.
@@ -97,16 +192,65 @@ impl TemperatureSensor {
         self.last_temp
     }
 
+    /// Samples the temperature, adding it to the history if
+    /// [`TEMP_HISTORY_INTERVAL`] has passed since the last history sample.
+    pub fn read(&mut self) -> TemperatureReading {
+        self.sample();
+        if let Some(temp) = self.last_temp {
+            let interval = Duration::from_secs(TEMP_HISTORY_INTERVAL);
+            if self.last_history.is_none_or(|at| at.elapsed() >= interval) {
+                if self.history.len() == TEMP_HISTORY_SIZE {
+                    self.history.pop_front();
+                }
+                self.history.push_back(temp);
+                self.last_history = Some(Instant::now());
+            }
+        }
+        TemperatureReading {
+            temperature: self.last_temp,
+            history: self.history.clone(),
+        }
+    }
+
     /// Reads the current temperature from the sensor.
     fn read_temp(&self) -> Option<f64> {
-        let path = self.temp_path.as_ref()?;
-        let content = fs::read_to_string(path).ok()?;
-        let millidegrees: f64 = content.trim().parse().ok()?;
-        // Temperature is in millidegrees Celsius
-        Some(millidegrees / 1000.0)
+        read_celsius(self.temp_path.as_ref()?)
     }
 }
 
+/// Returns the best ranked candidate, the first of equal rank.
+fn best_candidate(candidates: Vec<TemperatureCandidate>) -> Option<TemperatureCandidate> {
+    candidates
+        .into_iter()
+        .filter_map(|candidate| Some((candidate.rank()?, candidate)))
+        .min_by_key(|(rank, _)| *rank)
+        .map(|(_, candidate)| candidate)
+}
+
+/// Reads a temperature input file in Celsius.
+fn read_celsius(path: &Path) -> Option<f64> {
+    let millidegrees: f64 = read_trimmed(path)?.parse().ok()?;
+    // Temperature is in millidegrees Celsius
+    Some(millidegrees / 1000.0)
+}
+
+/// Reads a sysfs attribute without surrounding whitespace.
+fn read_trimmed(path: &Path) -> Option<String> {
+    fs::read_to_string(path).ok().map(|s| s.trim().to_string())
+}
+
+/// Returns the entries of `dir` sorted by path.
+fn sorted_entries(dir: impl AsRef<Path>) -> Vec<PathBuf> {
+    let mut paths: Vec<PathBuf> = fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| entry.path())
+        .collect();
+    paths.sort();
+    paths
+}
+
 impl Default for TemperatureSensor {
     fn default() -> Self {
         Self::new()
@@ -135,3 +279,79 @@ impl Sensor for TemperatureSensor {
         "°C"
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidate(label: &str, path: &str) -> TemperatureCandidate {
+        TemperatureCandidate {
+            label: label.to_string(),
+            path: PathBuf::from(path),
+        }
+    }
+
+    #[test]
+    fn test_prefers_package_sensor_over_acpitz() {
+        let candidates = vec![
+            candidate("acpitz temp1", "/sys/class/hwmon/hwmon0/temp1_input"),
+            candidate("nvme Composite", "/sys/class/hwmon/hwmon1/temp1_input"),
+            candidate("k10temp Tctl", "/sys/class/hwmon/hwmon2/temp1_input"),
+            candidate("k10temp Tccd1", "/sys/class/hwmon/hwmon2/temp3_input"),
+            candidate("acpitz", "/sys/class/thermal/thermal_zone0/temp"),
+        ];
+        assert_eq!(
+            best_candidate(candidates.clone()).unwrap().label,
+            "k10temp Tctl"
+        );
+        assert_eq!(
+            best_candidate(candidates[..2].to_vec()).unwrap().label,
+            "acpitz temp1"
+        );
+        assert!(best_candidate(vec![candidates[1].clone()]).is_none());
+
+        assert!(candidates[3].matches("K10TEMP"));
+        assert!(candidates[3].matches("k10temp tccd1"));
+        assert!(!candidates[3].matches("k10temp Tctl"));
+    }
+
+    #[test]
+    fn test_history() {
+        let path = std::env::temp_dir().join(format!("ht32-temp-{}", std::process::id()));
+        let mut sensor = TemperatureSensor::new();
+        sensor.temp_path = Some(path.clone());
+        let interval = Duration::from_secs(TEMP_HISTORY_INTERVAL);
+
+        // No sample, no history
+        let reading = sensor.read();
+        assert_eq!(reading.temperature, None);
+        assert!(reading.history.is_empty());
+
+        fs::write(&path, "45500\n").unwrap();
+        let reading = sensor.read();
+        assert_eq!(reading.temperature, Some(45.5));
+        assert_eq!(reading.history, [45.5]);
+
+        // Samples within the interval are not added
+        fs::write(&path, "50000\n").unwrap();
+        let reading = sensor.read();
+        assert_eq!(reading.temperature, Some(50.0));
+        assert_eq!(reading.history, [45.5]);
+
+        sensor.last_history = Instant::now().checked_sub(interval);
+        assert_eq!(sensor.read().history, [45.5, 50.0]);
+
+        // The oldest sample is dropped once full
+        sensor.history = (0..TEMP_HISTORY_SIZE).map(|i| i as f64).collect();
+        sensor.last_history = Instant::now().checked_sub(interval);
+        let history = sensor.read().history;
+        assert_eq!(history.len(), TEMP_HISTORY_SIZE);
+        assert_eq!(history.front(), Some(&1.0));
+        assert_eq!(history.back(), Some(&50.0));
+
+        // Selecting another input starts a new history
+        sensor.select(Some(path.to_str().unwrap()));
+        assert_eq!(sensor.read().history, [50.0]);
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,215 @@
+//! CPU thermal throttling detection.
+//!
+//! Intel CPUs count throttling events in `thermal_throttle`, and the CPU
+//! is reported as throttled until [`HOLD`] after those counters last rose.
+//! On other systems a thermal cooling device acting on the CPU, such as
+//! `cpufreq-cpu0` on ARM boards or an ACPI `Processor` device, counts as
+//! throttling while it is active. The current clock as a share of the hardware maximum is read
+//! from cpufreq.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use super::data::ThrottleStatus;
+
+/// Directory of CPU devices.
+const CPUS: &str = "/sys/devices/system/cpu";
+
+/// Directory of thermal cooling devices.
+const COOLING_DEVICES: &str = "/sys/class/thermal";
+
+/// How long the CPU counts as throttled after the last throttling event,
+/// so bursts of events do not flicker the state.
+const HOLD: Duration = Duration::from_secs(30);
+
+/// CPU thermal throttling sensor.
+pub struct ThrottleSensor {
+    /// Throttling events counted at the previous read
+    last_count: Option<u64>,
+    /// When the count last rose
+    last_event: Option<Instant>,
+}
+
+impl ThrottleSensor {
+    /// Creates a new throttling sensor.
+    pub fn new() -> Self {
+        Self {
+            last_count: None,
+            last_event: None,
+        }
+    }
+
+    /// Reads whether the CPU is throttled. None if the system reports
+    /// neither throttling counters, CPU cooling devices nor clock speeds.
+    pub fn sample(&mut self) -> Option<ThrottleStatus> {
+        self.sample_from(Path::new(CPUS), Path::new(COOLING_DEVICES))
+    }
+
+    /// Reads whether the CPU is throttled from the CPU devices in `cpus`
+    /// and the cooling devices in `cooling_devices`.
+    fn sample_from(&mut self, cpus: &Path, cooling_devices: &Path) -> Option<ThrottleStatus> {
+        let cpus = cpu_dirs(cpus);
+        let count = throttle_count(&cpus);
+        let cooling = cpu_cooling_states(cooling_devices);
+        let clock_percent = clock_percent(&cpus);
+        if count.is_none() && cooling.is_empty() && clock_percent.is_none() {
+            return None;
+        }
+
+        if matches!((self.last_count, count), (Some(last), Some(now)) if now > last) {
+            self.last_event = Some(Instant::now());
+        }
+        self.last_count = count;
+        let recent = self.last_event.is_some_and(|at| at.elapsed() < HOLD);
+        Some(ThrottleStatus {
+            throttled: recent || cooling.iter().any(|&state| state > 0.0),
+            clock_percent,
+        })
+    }
+}
+
+impl Default for ThrottleSensor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Returns the `cpuN` directories in `dir`.
+fn cpu_dirs(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .and_then(|name| name.strip_prefix("cpu"))
+                .is_some_and(|n| !n.is_empty() && n.bytes().all(|b| b.is_ascii_digit()))
+        })
+        .collect()
+}
+
+/// Sums the core and package throttling events of all CPUs. None if no
+/// CPU counts them.
+fn throttle_count(cpus: &[PathBuf]) -> Option<u64> {
+    cpus.iter()
+        .flat_map(|cpu| {
+            ["core_throttle_count", "package_throttle_count"]
+                .map(|counter| read_number(&cpu.join("thermal_throttle").join(counter)))
+        })
+        .flatten()
+        .map(|count| count as u64)
+        .reduce(|a, b| a + b)
+}
+
+/// Returns the current state of each cooling device in `dir` acting on
+/// the CPU.
+fn cpu_cooling_states(dir: &Path) -> Vec<f64> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with("cooling_device"))
+        })
+        .filter(|path| {
+            fs::read_to_string(path.join("type")).is_ok_and(|kind| is_cpu_cooling(kind.trim()))
+        })
+        .filter_map(|path| read_number(&path.join("cur_state")))
+        .collect()
+}
+
+/// Whether a cooling device of type `kind` slows the CPU down.
+fn is_cpu_cooling(kind: &str) -> bool {
+    kind == "Processor" || kind.starts_with("cpufreq") || kind == "intel_powerclamp"
+}
+
+/// Returns the summed current clock of the CPUs as a percentage of their
+/// summed hardware maximum.
+fn clock_percent(cpus: &[PathBuf]) -> Option<f64> {
+    let (current, max) = cpus
+        .iter()
+        .filter_map(|cpu| {
+            let cpufreq = cpu.join("cpufreq");
+            Some((
+                read_number(&cpufreq.join("scaling_cur_freq"))?,
+                read_number(&cpufreq.join("cpuinfo_max_freq"))?,
+            ))
+        })
+        .fold((0.0, 0.0), |(current, max), (c, m)| (current + c, max + m));
+    (max > 0.0).then(|| current / max * 100.0)
+}
+
+/// Reads a sysfs attribute holding a number.
+fn read_number(path: &Path) -> Option<f64> {
+    fs::read_to_string(path).ok()?.trim().parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cpu_cooling_types() {
+        assert!(is_cpu_cooling("Processor"));
+        assert!(is_cpu_cooling("cpufreq-cpu0"));
+        assert!(is_cpu_cooling("intel_powerclamp"));
+        assert!(!is_cpu_cooling("Fan"));
+        assert!(!is_cpu_cooling("TCC Offset"));
+    }
+
+    /// Writes `value` to `path`, creating its directory.
+    fn write(path: &Path, value: &str) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, value).unwrap();
+    }
+
+    #[test]
+    fn test_sample() {
+        let root = std::env::temp_dir().join(format!("ht32-throttle-{}", std::process::id()));
+        let (cpus, cooling) = (root.join("cpu"), root.join("thermal"));
+        let mut sensor = ThrottleSensor::new();
+        assert_eq!(sensor.sample_from(&cpus, &cooling), None);
+
+        // Two CPUs at half and full clock; "cpufreq" is not a CPU
+        for (cpu, current) in [("cpu0", "1000000"), ("cpu1", "2000000")] {
+            write(&cpus.join(cpu).join("cpufreq/scaling_cur_freq"), current);
+            write(
+                &cpus.join(cpu).join("cpufreq/cpuinfo_max_freq"),
+                "2000000\n",
+            );
+            write(
+                &cpus.join(cpu).join("thermal_throttle/core_throttle_count"),
+                "3",
+            );
+        }
+        write(&cpus.join("cpufreq/scaling_cur_freq"), "1");
+        let status = sensor.sample_from(&cpus, &cooling).unwrap();
+        assert!(!status.throttled);
+        assert_eq!(status.clock_percent, Some(75.0));
+
+        // A rising count marks the CPU throttled for a while
+        write(&cpus.join("cpu1/thermal_throttle/core_throttle_count"), "4");
+        assert!(sensor.sample_from(&cpus, &cooling).unwrap().throttled);
+        assert!(sensor.sample_from(&cpus, &cooling).unwrap().throttled);
+
+        // So does an active CPU cooling device, but not a fan
+        let mut sensor = ThrottleSensor::new();
+        write(&cooling.join("cooling_device0/type"), "Fan\n");
+        write(&cooling.join("cooling_device0/cur_state"), "3");
+        write(&cooling.join("cooling_device1/type"), "Processor\n");
+        write(&cooling.join("cooling_device1/cur_state"), "0");
+        assert!(!sensor.sample_from(&cpus, &cooling).unwrap().throttled);
+        write(&cooling.join("cooling_device1/cur_state"), "1");
+        let status = sensor.sample_from(&cpus, &cooling).unwrap();
+        fs::remove_dir_all(&root).unwrap();
+        assert!(status.throttled);
+    }
+}
@@ -0,0 +1,222 @@
+//! qBittorrent and Transmission download statistics.
+//!
+//! Polls the client's HTTP API on a background thread. qBittorrent uses the
+//! Web UI API with a session cookie; Transmission uses its JSON-RPC endpoint,
+//! which requires echoing back the `X-Transmission-Session-Id` header.
+
+use std::time::Duration;
+
+use anyhow::{bail, Context, Result};
+
+use super::data::{Torrent, TorrentStats};
+use super::http;
+use super::poller::Poller;
+use crate::config::{TorrentConfig, TorrentKind};
+
+/// qBittorrent reports this ETA for torrents that will not finish.
+const QBITTORRENT_INFINITE_ETA: u64 = 8_640_000;
+
+/// Torrent sensor backed by a background polling thread.
+pub struct TorrentSensor {
+    /// Last result (None until the first successful poll)
+    latest: Poller<Option<TorrentStats>>,
+}
+
+impl TorrentSensor {
+    /// Starts polling the configured client. Returns None if disabled.
+    pub fn start(config: &TorrentConfig) -> Option<Self> {
+        if !config.enable {
+            return None;
+        }
+
+        let interval = Duration::from_secs(config.interval.max(1));
+        let mut client = Client::new(config.clone());
+        let latest = Poller::start_fallible("torrent", interval, move || client.poll())?;
+
+        Some(Self { latest })
+    }
+
+    /// Returns the last known statistics.
+    pub fn stats(&self) -> Option<TorrentStats> {
+        self.latest.latest()
+    }
+}
+
+/// Polling state for one client.
+struct Client {
+    config: TorrentConfig,
+    /// Base URL without a trailing slash
+    base: String,
+    /// qBittorrent `SID` cookie or Transmission session ID
+    session: Option<String>,
+}
+
+impl Client {
+    fn new(config: TorrentConfig) -> Self {
+        let base = config.url.trim_end_matches('/').to_string();
+        Self {
+            config,
+            base,
+            session: None,
+        }
+    }
+
+    fn poll(&mut self) -> Result<TorrentStats> {
+        let mut stats = match self.config.kind {
+            TorrentKind::Qbittorrent => self.poll_qbittorrent()?,
+            TorrentKind::Transmission => self.poll_transmission()?,
+        };
+        stats
+            .downloading
+            .sort_by(|a, b| b.download_rate.total_cmp(&a.download_rate));
+        Ok(stats)
+    }
+
+    fn qbittorrent_get(&self, path: &str) -> Result<http::Response> {
+        let url = format!("{}{}", self.base, path);
+        match &self.session {
+            Some(sid) => http::get(&url, &[("Cookie", &format!("SID={}", sid))]),
+            None => http::get(&url, &[]),
+        }
+    }
+
+    /// Logs in to the qBittorrent Web UI and keeps the session cookie.
+    fn qbittorrent_login(&mut self) -> Result<()> {
+        let body = format!(
+            "username={}&password={}",
+            http::url_encode(&self.config.username),
+            http::url_encode(&self.config.password)
+        );
+        // qBittorrent rejects logins without a matching Referer
+        let response = http::post_form(
+            &format!("{}/api/v2/auth/login", self.base),
+            &[("Referer", &self.base)],
+            &body,
+        )?
+        .check()
+        .context("qBittorrent login failed")?;
+        let sid = response
+            .headers
+            .iter()
+            .filter(|(name, _)| name.eq_ignore_ascii_case("set-cookie"))
+            .find_map(|(_, value)| value.strip_prefix("SID="))
+            .and_then(|v| v.split(';').next())
+            .context("qBittorrent rejected the credentials")?;
+        self.session = Some(sid.to_string());
+        Ok(())
+    }
+
+    /// Polls qBittorrent's transfer totals and torrent list.
+    fn poll_qbittorrent(&mut self) -> Result<TorrentStats> {
+        let mut transfer = self.qbittorrent_get("/api/v2/transfer/info")?;
+        if transfer.status == 401 || transfer.status == 403 {
+            self.qbittorrent_login()?;
+            transfer = self.qbittorrent_get("/api/v2/transfer/info")?;
+        }
+        let transfer = transfer.check()?.json()?;
+        let torrents = self
+            .qbittorrent_get("/api/v2/torrents/info")?
+            .check()?
+            .json()?;
+        let torrents = torrents
+            .as_array()
+            .context("Torrent list is not an array")?;
+
+        let downloading = torrents
+            .iter()
+            .filter(|t| {
+                let state = t["state"].as_str().unwrap_or_default();
+                state.ends_with("DL")
+                    && !state.starts_with("paused")
+                    && !state.starts_with("stopped")
+            })
+            .map(|t| Torrent {
+                name: t["name"].as_str().unwrap_or_default().to_string(),
+                progress: (t["progress"].as_f64().unwrap_or(0.0) * 100.0) as f32,
+                download_rate: t["dlspeed"].as_f64().unwrap_or(0.0),
+                upload_rate: t["upspeed"].as_f64().unwrap_or(0.0),
+                eta: t["eta"]
+                    .as_u64()
+                    .filter(|eta| *eta < QBITTORRENT_INFINITE_ETA),
+            })
+            .collect();
+
+        Ok(TorrentStats {
+            download_rate: transfer["dl_info_speed"].as_f64().unwrap_or(0.0),
+            upload_rate: transfer["up_info_speed"].as_f64().unwrap_or(0.0),
+            downloading,
+            total: torrents.len(),
+        })
+    }
+
+    /// Polls Transmission's torrent list over JSON-RPC.
+    fn poll_transmission(&mut self) -> Result<TorrentStats> {
+        let url = format!("{}/transmission/rpc", self.base);
+        let body = serde_json::json!({
+            "method": "torrent-get",
+            "arguments": {
+                "fields": ["name", "percentDone", "rateDownload", "rateUpload", "status", "eta"]
+            }
+        })
+        .to_string();
+        let auth = (!self.config.username.is_empty())
+            .then(|| http::basic_auth(&self.config.username, &self.config.password));
+
+        let send = |session: Option<&str>| {
+            let mut headers = Vec::new();
+            if let Some(ref auth) = auth {
+                headers.push(("Authorization", auth.as_str()));
+            }
+            if let Some(session) = session {
+                headers.push(("X-Transmission-Session-Id", session));
+            }
+            http::post_json(&url, &headers, &body)
+        };
+
+        let mut response = send(self.session.as_deref())?;
+        if response.status == 409 {
+            // The session ID expired or was never set; retry with the new one
+            let session = response
+                .header("X-Transmission-Session-Id")
+                .context("Transmission did not provide a session ID")?
+                .to_string();
+            response = send(Some(&session))?;
+            self.session = Some(session);
+        }
+        if response.status == 401 {
+            bail!("Transmission rejected the credentials");
+        }
+        let json = response.check()?.json()?;
+        let torrents = json["arguments"]["torrents"]
+            .as_array()
+            .context("Missing torrent list")?;
+
+        let mut stats = TorrentStats {
+            total: torrents.len(),
+            ..Default::default()
+        };
+        for t in torrents {
+            let download_rate = t["rateDownload"].as_f64().unwrap_or(0.0);
+            let upload_rate = t["rateUpload"].as_f64().unwrap_or(0.0);
+            stats.download_rate += download_rate;
+            stats.upload_rate += upload_rate;
+
+            // Status 3 is queued for download, 4 is downloading
+            let status = t["status"].as_u64().unwrap_or(0);
+            if status == 3 || status == 4 {
+                stats.downloading.push(Torrent {
+                    name: t["name"].as_str().unwrap_or_default().to_string(),
+                    progress: (t["percentDone"].as_f64().unwrap_or(0.0) * 100.0) as f32,
+                    download_rate,
+                    upload_rate,
+                    // Negative values mean unknown or not applicable
+                    eta: t["eta"]
+                        .as_i64()
+                        .filter(|eta| *eta >= 0)
+                        .map(|eta| eta as u64),
+                });
+            }
+        }
+        Ok(stats)
+    }
+}
@@ -0,0 +1,219 @@
+//! Persistent network traffic totals.
+//!
+//! Accumulates the bytes received and sent per interface for the current
+//! day and month, and stores them in the state directory so the totals
+//! survive daemon restarts. Interface counters reset on reboot, so only
+//! deltas between samples are added. The file is replaced atomically every
+//! minute and when the day or month rolls over.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+use tracing::{debug, warn};
+
+use super::data::NetworkTotals;
+use super::NetworkSensor;
+use crate::persist::write_atomic;
+
+/// How often the totals are written to disk.
+const SAVE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Totals file contents.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct TrafficFile {
+    /// Day the daily totals belong to ("YYYY-MM-DD")
+    #[serde(default)]
+    day: String,
+    /// Month the monthly totals belong to ("YYYY-MM")
+    #[serde(default)]
+    month: String,
+    /// Totals per interface
+    #[serde(default)]
+    interfaces: BTreeMap<String, NetworkTotals>,
+}
+
+/// Network traffic counter backed by a TOML file.
+pub struct TrafficCounter {
+    path: PathBuf,
+    totals: TrafficFile,
+    /// Last (rx_bytes, tx_bytes) seen per interface
+    last: HashMap<String, (u64, u64)>,
+    last_save: Instant,
+    dirty: bool,
+}
+
+impl TrafficCounter {
+    /// Loads the totals from `path`, starting empty if it is missing or invalid.
+    pub fn load(path: PathBuf) -> Self {
+        let totals = match fs::read_to_string(&path) {
+            Ok(content) => toml::from_str(&content).unwrap_or_else(|e| {
+                warn!("Ignoring invalid traffic totals in {:?}: {}", path, e);
+                TrafficFile::default()
+            }),
+            Err(_) => TrafficFile::default(),
+        };
+        Self {
+            path,
+            totals,
+            last: HashMap::new(),
+            last_save: Instant::now(),
+            dirty: false,
+        }
+    }
+
+    /// Adds the traffic since the last sample, rolling over the daily and
+    /// monthly totals when the date changes. Saves periodically and on
+    /// rollover.
+    pub fn sample(&mut self, year: u16, month: u8, day: u8) {
+        let rolled_over = self.roll_over(year, month, day);
+        for interface in NetworkSensor::list_interfaces() {
+            if let Some((rx, tx)) = read_counters(&interface) {
+                self.count(interface, rx, tx);
+            }
+        }
+
+        if rolled_over || self.last_save.elapsed() >= SAVE_INTERVAL {
+            self.save();
+        }
+    }
+
+    /// Starts new daily and monthly totals when the date changes. Returns
+    /// true when the day changed.
+    fn roll_over(&mut self, year: u16, month: u8, day: u8) -> bool {
+        let today = format!("{:04}-{:02}-{:02}", year, month, day);
+        let this_month = format!("{:04}-{:02}", year, month);
+        let rolled_over = self.totals.day != today;
+        if rolled_over {
+            for totals in self.totals.interfaces.values_mut() {
+                totals.day_rx = 0;
+                totals.day_tx = 0;
+            }
+            self.totals.day = today;
+            self.dirty = true;
+        }
+        if self.totals.month != this_month {
+            for totals in self.totals.interfaces.values_mut() {
+                totals.month_rx = 0;
+                totals.month_tx = 0;
+            }
+            self.totals.month = this_month;
+            self.dirty = true;
+        }
+        rolled_over
+    }
+
+    /// Adds the bytes `interface` moved since its previous counter values.
+    fn count(&mut self, interface: String, rx: u64, tx: u64) {
+        let Some((last_rx, last_tx)) = self.last.insert(interface.clone(), (rx, tx)) else {
+            return;
+        };
+        // A smaller value means the counter was reset; count from zero
+        let rx_delta = if rx >= last_rx { rx - last_rx } else { rx };
+        let tx_delta = if tx >= last_tx { tx - last_tx } else { tx };
+        if rx_delta > 0 || tx_delta > 0 {
+            let totals = self.totals.interfaces.entry(interface).or_default();
+            totals.day_rx += rx_delta;
+            totals.day_tx += tx_delta;
+            totals.month_rx += rx_delta;
+            totals.month_tx += tx_delta;
+            self.dirty = true;
+        }
+    }
+
+    /// Returns the totals for an interface.
+    pub fn totals(&self, interface: &str) -> NetworkTotals {
+        self.totals
+            .interfaces
+            .get(interface)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Returns the totals of every interface seen.
+    pub fn all_totals(&self) -> BTreeMap<String, NetworkTotals> {
+        self.totals.interfaces.clone()
+    }
+
+    /// Writes the totals to disk if they changed since the last save.
+    pub fn save(&mut self) {
+        self.last_save = Instant::now();
+        if !self.dirty {
+            return;
+        }
+        match toml::to_string_pretty(&self.totals) {
+            Ok(content) => {
+                if let Err(e) = write_atomic(&self.path, content) {
+                    warn!("Failed to save traffic totals: {}", e);
+                    return;
+                }
+                self.dirty = false;
+                debug!("Saved traffic totals to {:?}", self.path);
+            }
+            Err(e) => warn!("Failed to serialize traffic totals: {}", e),
+        }
+    }
+}
+
+/// Reads the kernel's byte counters for an interface.
+fn read_counters(interface: &str) -> Option<(u64, u64)> {
+    let read = |name: &str| -> Option<u64> {
+        fs::read_to_string(format!("/sys/class/net/{}/statistics/{}", interface, name))
+            .ok()?
+            .trim()
+            .parse()
+            .ok()
+    };
+    Some((read("rx_bytes")?, read("tx_bytes")?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_traffic_totals() {
+        let dir = std::env::temp_dir().join(format!("ht32-traffic-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("traffic.toml");
+        let _ = fs::remove_file(&path);
+
+        let mut counter = TrafficCounter::load(path.clone());
+        counter.roll_over(2026, 10, 16);
+        // The first reading only sets the baseline
+        counter.count("eth0".to_string(), 1000, 500);
+        assert_eq!(counter.totals("eth0"), NetworkTotals::default());
+        counter.count("eth0".to_string(), 1600, 700);
+        // After a reboot the counter starts again from zero
+        counter.count("eth0".to_string(), 100, 50);
+        let expected = NetworkTotals {
+            day_rx: 700,
+            day_tx: 250,
+            month_rx: 700,
+            month_tx: 250,
+        };
+        assert_eq!(counter.totals("eth0"), expected);
+
+        // Totals survive a restart
+        counter.save();
+        let mut counter = TrafficCounter::load(path.clone());
+        assert_eq!(counter.totals("eth0"), expected);
+
+        // A new day keeps the monthly totals, a new month clears them
+        assert!(!counter.roll_over(2026, 10, 16));
+        assert!(counter.roll_over(2026, 10, 17));
+        assert_eq!(
+            counter.totals("eth0"),
+            NetworkTotals {
+                day_rx: 0,
+                day_tx: 0,
+                ..expected
+            }
+        );
+        assert!(counter.roll_over(2026, 11, 1));
+        assert_eq!(counter.totals("eth0"), NetworkTotals::default());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,190 @@
+//! Pending package updates sensor.
+//!
+//! Package manager queries can take tens of seconds, so they run on a
+//! background thread at a long interval and the render loop only reads the
+//! last result.
+
+use std::collections::HashSet;
+use std::path::Path;
+use std::process::Command;
+use std::time::Duration;
+use tracing::{debug, warn};
+
+use super::data::PendingUpdates;
+use super::poller::Poller;
+
+/// Supported package managers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PackageManager {
+    Apt,
+    Dnf,
+    Pacman,
+}
+
+impl PackageManager {
+    /// Detects the package manager from the helper binaries present.
+    fn detect() -> Option<Self> {
+        if Path::new("/usr/bin/apt-get").exists() {
+            Some(Self::Apt)
+        } else if Path::new("/usr/bin/dnf").exists() {
+            Some(Self::Dnf)
+        } else if Path::new("/usr/bin/checkupdates").exists() {
+            Some(Self::Pacman)
+        } else {
+            None
+        }
+    }
+
+    /// Queries pending updates. Returns None if the helper failed.
+    fn check(self) -> Option<PendingUpdates> {
+        match self {
+            Self::Apt => {
+                // Simulated upgrade lists one "Inst" line per package
+                let output = run("apt-get", &["-s", "-q", "upgrade"])?;
+                let installs: Vec<&str> =
+                    output.lines().filter(|l| l.starts_with("Inst ")).collect();
+                Some(PendingUpdates {
+                    total: installs.len() as u32,
+                    security: installs.iter().filter(|l| l.contains("-security")).count() as u32,
+                })
+            }
+            Self::Dnf => {
+                let total = run("dnf", &["-q", "check-update"])
+                    .map(|out| count_dnf_updates(&out))
+                    .unwrap_or(0);
+                let security = run("dnf", &["-q", "updateinfo", "list", "--security"])
+                    .map(|out| count_dnf_advisory_packages(&out))
+                    .unwrap_or(0);
+                Some(PendingUpdates { total, security })
+            }
+            Self::Pacman => {
+                let output = run("checkupdates", &[])?;
+                Some(PendingUpdates {
+                    total: count_package_lines(&output),
+                    security: 0,
+                })
+            }
+        }
+    }
+}
+
+/// Runs a helper command and returns its stdout.
+///
+/// `dnf check-update` exits with 100 and `checkupdates` with 2 to signal
+/// "updates available" / "none available", so only a failure to spawn is
+/// treated as an error.
+fn run(program: &str, args: &[&str]) -> Option<String> {
+    match Command::new(program).args(args).output() {
+        Ok(output) => Some(String::from_utf8_lossy(&output.stdout).into_owned()),
+        Err(e) => {
+            warn!("Failed to run {}: {}", program, e);
+            None
+        }
+    }
+}
+
+/// Architectures that end the package names dnf prints.
+const RPM_ARCHES: &[&str] = &[
+    "noarch", "x86_64", "i686", "aarch64", "armv7hl", "ppc64le", "s390x", "src",
+];
+
+/// Returns true for "name.arch" or "name-version-release.arch" fields.
+fn is_rpm_package(field: &str) -> bool {
+    field
+        .rsplit_once('.')
+        .is_some_and(|(name, arch)| !name.is_empty() && RPM_ARCHES.contains(&arch))
+}
+
+/// Counts the packages listed by `dnf check-update`: "name.arch version
+/// repo" lines, up to the obsoleted packages section.
+fn count_dnf_updates(output: &str) -> u32 {
+    let packages: HashSet<&str> = output
+        .lines()
+        .take_while(|l| !l.starts_with("Obsoleting"))
+        .filter_map(|l| l.split_whitespace().next())
+        .filter(|name| is_rpm_package(name))
+        .collect();
+    packages.len() as u32
+}
+
+/// Counts the packages named by `dnf updateinfo list --security`. It prints
+/// one line per advisory and package, so a package fixed by several
+/// advisories is counted once.
+fn count_dnf_advisory_packages(output: &str) -> u32 {
+    let packages: HashSet<&str> = output
+        .lines()
+        .filter_map(|l| l.split_whitespace().skip(1).find(|f| is_rpm_package(f)))
+        .collect();
+    packages.len() as u32
+}
+
+/// Counts non-empty lines that look like package entries.
+fn count_package_lines(output: &str) -> u32 {
+    output
+        .lines()
+        .filter(|l| !l.trim().is_empty() && !l.starts_with("Obsoleting") && !l.starts_with(' '))
+        .count() as u32
+}
+
+/// Pending updates sensor backed by a background checker thread.
+pub struct UpdatesSensor {
+    /// Last result from the checker thread
+    latest: Poller<Option<PendingUpdates>>,
+}
+
+impl UpdatesSensor {
+    /// Starts checking for updates every `interval`.
+    /// Returns None if no supported package manager is installed.
+    pub fn start(interval: Duration) -> Option<Self> {
+        let manager = PackageManager::detect()?;
+        debug!("Checking for updates with {:?}", manager);
+
+        let latest = Poller::start("updates-check", None, interval, move || {
+            let updates = manager.check()?;
+            debug!(
+                "Pending updates: {} ({} security)",
+                updates.total, updates.security
+            );
+            Some(Some(updates))
+        })?;
+
+        Some(Self { latest })
+    }
+
+    /// Returns the last known pending updates, if a check has completed.
+    pub fn pending(&self) -> Option<PendingUpdates> {
+        self.latest.latest()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_count_dnf_updates() {
+        let check_update = "\
+Last metadata expiration check: 0:12:41 ago on Fri 16 Oct 2026 09:10:11.
+
+kernel.x86_64                      6.11.4-301.fc41          updates
+openssl-libs.x86_64                1:3.2.2-9.fc41           updates
+python3-pip.noarch                 24.2-1.fc41              updates
+Obsoleting Packages
+grub2-tools.x86_64                 1:2.12-9.fc41            updates
+    grub2-tools-efi.x86_64         1:2.12-8.fc41            @updates
+";
+        assert_eq!(count_dnf_updates(check_update), 3);
+        assert_eq!(count_dnf_updates(""), 0);
+
+        // openssl-libs is fixed by two advisories
+        let security = "\
+FEDORA-2026-1a2b3c4d5e Important/Sec. kernel-6.11.4-301.fc41.x86_64
+FEDORA-2026-6f7a8b9c0d Moderate/Sec.  openssl-libs-1:3.2.2-9.fc41.x86_64
+FEDORA-2026-0d9c8b7a6f Low/Sec.       openssl-libs-1:3.2.2-9.fc41.x86_64
+";
+        assert_eq!(count_dnf_advisory_packages(security), 2);
+
+        let pacman = "linux 6.11.3.arch1-1 -> 6.11.4.arch1-1\nopenssl 3.3.2-1 -> 3.4.0-1\n";
+        assert_eq!(count_package_lines(pacman), 2);
+    }
+}
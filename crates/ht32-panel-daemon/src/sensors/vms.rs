@@ -0,0 +1,182 @@
+//! Libvirt virtual machine sensor.
+//!
+//! Runs `virsh domstats` on a background thread to list defined domains with
+//! their state and CPU time. CPU usage is the change in CPU time between
+//! polls relative to the domain's vCPU count.
+
+use std::collections::HashMap;
+use std::process::Command;
+use std::time::{Duration, Instant};
+
+use tracing::{debug, warn};
+
+use super::data::{VmState, VmStatus};
+use super::poller::Poller;
+
+/// Raw statistics of one domain from `virsh domstats`.
+#[derive(Debug, Default)]
+struct DomainStats {
+    name: String,
+    state: u32,
+    /// Total CPU time in nanoseconds
+    cpu_time: Option<u64>,
+    vcpus: Option<u32>,
+}
+
+/// VM sensor backed by a background `virsh` polling thread.
+pub struct VmSensor {
+    /// Last listing (empty until the first successful poll)
+    latest: Poller<Vec<VmStatus>>,
+}
+
+impl VmSensor {
+    /// Starts polling libvirt at `uri` every `interval`.
+    pub fn start(uri: &str, interval: Duration) -> Option<Self> {
+        let uri = uri.to_string();
+        // CPU time and sample instant per domain from the last poll
+        let mut last: HashMap<String, (u64, Instant)> = HashMap::new();
+        let latest = Poller::start("vms-poll", Vec::new(), interval, move || {
+            let now = Instant::now();
+            let statuses = domstats(&uri)?
+                .into_iter()
+                .map(|d| {
+                    let cpu_percent = d.cpu_time.and_then(|time| {
+                        let (prev, at) = last.insert(d.name.clone(), (time, now))?;
+                        // Less CPU time than before means a restart
+                        let used = time.checked_sub(prev)?;
+                        cpu_percent(used, now - at, d.vcpus)
+                    });
+                    VmStatus {
+                        name: d.name,
+                        state: VmState::from_libvirt(d.state),
+                        cpu_percent,
+                    }
+                })
+                .collect();
+            Some(statuses)
+        })?;
+
+        debug!("Polling libvirt VMs");
+        Some(Self { latest })
+    }
+
+    /// Returns the last known VM states.
+    pub fn vms(&self) -> Vec<VmStatus> {
+        self.latest.latest()
+    }
+}
+
+/// Runs `virsh domstats` and parses its output.
+fn domstats(uri: &str) -> Option<Vec<DomainStats>> {
+    let output = match Command::new("virsh")
+        .args([
+            "-c",
+            uri,
+            "domstats",
+            "--state",
+            "--cpu-total",
+            "--vcpu",
+            "--raw",
+        ])
+        .output()
+    {
+        Ok(output) => output,
+        Err(e) => {
+            warn!("Failed to run virsh: {}", e);
+            return None;
+        }
+    };
+    if !output.status.success() {
+        debug!(
+            "virsh domstats failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+        return None;
+    }
+    Some(parse_domstats(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// Converts CPU time used over `elapsed` into a percentage of the domain's
+/// vCPUs. None if no time has passed.
+fn cpu_percent(cpu_time: u64, elapsed: Duration, vcpus: Option<u32>) -> Option<f32> {
+    let elapsed = elapsed.as_nanos() as f64;
+    let vcpus = vcpus.unwrap_or(1).max(1) as f64;
+    (elapsed > 0.0).then(|| (cpu_time as f64 / elapsed / vcpus * 100.0).clamp(0.0, 100.0) as f32)
+}
+
+/// Parses blocks like:
+///
+/// ```text
+/// Domain: 'web'
+///   state.state=1
+///   cpu.time=123456789
+///   vcpu.current=2
+/// ```
+fn parse_domstats(output: &str) -> Vec<DomainStats> {
+    let mut domains: Vec<DomainStats> = Vec::new();
+    for line in output.lines() {
+        let line = line.trim();
+        if let Some(name) = line.strip_prefix("Domain:") {
+            domains.push(DomainStats {
+                name: name.trim().trim_matches('\'').to_string(),
+                ..Default::default()
+            });
+            continue;
+        }
+        let (Some(domain), Some((key, value))) = (domains.last_mut(), line.split_once('=')) else {
+            continue;
+        };
+        match key {
+            "state.state" => domain.state = value.parse().unwrap_or(0),
+            "cpu.time" => domain.cpu_time = value.parse().ok(),
+            "vcpu.current" => domain.vcpus = value.parse().ok(),
+            _ => {}
+        }
+    }
+    domains.sort_by(|a, b| a.name.cmp(&b.name));
+    domains
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_domstats() {
+        let output = "Domain: 'web'
+  state.state=1
+  state.reason=1
+  cpu.time=123456789
+  vcpu.current=2
+  vcpu.maximum=4
+
+Domain: 'backup'
+  state.state=5
+";
+        let domains = parse_domstats(output);
+        assert_eq!(domains.len(), 2);
+        assert_eq!(domains[0].name, "backup");
+        assert_eq!(domains[0].state, 5);
+        assert_eq!(domains[0].cpu_time, None);
+        assert_eq!(domains[1].name, "web");
+        assert_eq!(domains[1].state, 1);
+        assert_eq!(domains[1].cpu_time, Some(123_456_789));
+        assert_eq!(domains[1].vcpus, Some(2));
+        assert!(parse_domstats("").is_empty());
+
+        assert_eq!(VmState::from_libvirt(domains[0].state), VmState::Stopped);
+        assert_eq!(VmState::from_libvirt(domains[1].state), VmState::Running);
+        assert_eq!(VmState::from_libvirt(3), VmState::Paused);
+        assert_eq!(VmState::from_libvirt(7), VmState::Other);
+    }
+
+    #[test]
+    fn test_cpu_percent() {
+        let second = Duration::from_secs(1);
+        assert_eq!(cpu_percent(500_000_000, second, None), Some(50.0));
+        assert_eq!(cpu_percent(500_000_000, second, Some(2)), Some(25.0));
+        assert_eq!(cpu_percent(500_000_000, second, Some(0)), Some(50.0));
+        assert_eq!(cpu_percent(3_000_000_000, second, Some(2)), Some(100.0));
+        assert_eq!(cpu_percent(1, Duration::ZERO, Some(1)), None);
+    }
+}
@@ -4,38 +4,108 @@
 
 use anyhow::{Context, Result};
 use ht32_panel_hw::{
-    lcd::{Framebuffer, LcdDevice},
+    access::{self, Access},
+    lcd::Framebuffer,
     led::{LedDevice, LedTheme},
     Orientation,
 };
+use ht32_panel_types::{LedSettings, ThemeInfo};
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::path::{Path, PathBuf};
-use std::sync::{Mutex, RwLock};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
 use tracing::{debug, info, warn};
 
-use crate::config::Config;
+use crate::audit::AuditLog;
+use crate::auto_orientation;
+use crate::config::{AlertPulseConfig, CollectorsConfig, Config, RuleConfig, SkinConfig};
+use crate::dnd::DndSchedule;
+use crate::events::{Event, EventBus, Timeline};
+use crate::faces::custom::{self, Layout};
+use crate::faces::overlays::{self, Overlay};
+use crate::faces::placement::{self, BadgeSize, Placement, Slot};
 use crate::faces::{self, EnabledComplications, Face, Theme};
-use crate::rendering::Canvas;
+use crate::health::{self, Health};
+use crate::history::{self, SettingsHistory};
+use crate::night_light::NightLight;
+use crate::output::{self, Output};
+use crate::persist::write_atomic;
+use crate::render_stats::{FrameTimes, RenderStats, StageStats};
+use crate::rendering::{
+    apply_effects, dim, draw_banner, encode_png, encode_screen, load_pixmap, mirror, render_panic,
+    render_shutdown, render_splash, render_test_pattern, test_steps, Canvas, PostEffect,
+    ScreenCache, ScreenFormat, Skin, TestPattern, VisibleArea,
+};
+use crate::replay::{Recorder, Replay};
+use crate::rules::{self, RuleEngine};
+#[cfg(feature = "audio")]
+use crate::sensors::AudioSensor;
+#[cfg(feature = "scripting")]
+use crate::sensors::ExecSensor;
+#[cfg(feature = "scrapers")]
+use crate::sensors::JsonScraper;
+#[cfg(feature = "snmp")]
+use crate::sensors::SnmpSensor;
+#[cfg(feature = "torrent")]
+use crate::sensors::TorrentSensor;
 use crate::sensors::{
-    data::{IpDisplayPreference, SystemData},
-    CpuSensor, DiskSensor, MemorySensor, NetworkSensor, Sensor, SystemInfo, TemperatureSensor,
+    data::{
+        collector_names, format_uptime, AppUsage, AudioLevels, IpDisplayMode, IpDisplayPreference,
+        JobState, Metric, NetworkTotals, ProcessCounts, RateUnits, SshSessions, SystemData,
+        ThrottleStatus, TorrentStats,
+    },
+    wait_for_reads, wake_on_lan, watch_journal, AppCpuSensor, CpuReading, CpuSensor, DiskReading,
+    DiskSensor, DnsFilterSensor, GpuSensor, HostsSensor, JobTracker, JournalErrorLog,
+    MediaServerSensor, MemorySensor, NetworkReading, NetworkSensor, PrinterSensor, ProcessSensor,
+    ReadHealth, Sampled, Sensor, SessionSensor, StorageSensor, SystemInfo, TemperatureCandidate,
+    TemperatureReading, TemperatureSensor, ThrottleSensor, TrafficCounter, UpdatesSensor, VmSensor,
+    COLLECTORS,
 };
+#[cfg(feature = "speedtest")]
+use crate::sensors::{Speedtest, SpeedtestResult, SpeedtestStatus};
+use crate::uploads;
+
+/// How long the LEDs stay lit for each notification pulse.
+const LED_PULSE_ON: Duration = Duration::from_millis(600);
+
+/// How long the LEDs stay dark between notification pulses.
+const LED_PULSE_OFF: Duration = Duration::from_millis(400);
+
+/// Lowest software brightness, so the panel never looks switched off.
+const MIN_BRIGHTNESS: u8 = 10;
+
+/// Allowed refresh intervals in milliseconds.
+const REFRESH_INTERVALS: std::ops::RangeInclusive<u32> = 500..=10000;
+
+/// How long a speedtest result stays on the panel.
+#[cfg(feature = "speedtest")]
+const SPEEDTEST_DISPLAY: Duration = Duration::from_secs(60);
+
+/// How long each step of a test pattern is shown.
+const TEST_PATTERN_STEP: Duration = Duration::from_secs(4);
 
 /// Display settings persisted to state directory.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct DisplaySettings {
     /// Current face name.
     #[serde(default = "default_face")]
     pub face: String,
 
-    /// Display orientation.
+    /// Display orientation, "auto" to detect it or empty for the
+    /// configured orientation.
     #[serde(default)]
     pub orientation: String,
 
-    /// Color theme preset name.
+    /// Color theme preset or saved theme name.
     #[serde(default = "default_theme")]
     pub theme: String,
 
+    /// Themes saved from the theme editor: colors as "#RRGGBB" by color
+    /// name, by theme name.
+    #[serde(default)]
+    pub custom_themes: BTreeMap<String, BTreeMap<String, String>>,
+
     /// LED theme (1=rainbow, 2=breathing, 3=colors, 4=off, 5=auto).
     #[serde(default = "default_led_theme")]
     pub led_theme: u8,
@@ -63,6 +133,83 @@ pub struct DisplaySettings {
     /// Enabled complications per face.
     #[serde(default)]
     pub complications: EnabledComplications,
+
+    /// Label shown on faces instead of the hostname.
+    #[serde(default)]
+    pub display_label: Option<String>,
+
+    /// IANA timezone override (e.g. "Europe/Berlin"); None uses the system timezone.
+    #[serde(default)]
+    pub timezone: Option<String>,
+
+    /// Format rates with 1024-based units (KiB/s) instead of 1000-based.
+    #[serde(default)]
+    pub binary_units: bool,
+
+    /// Show network throughput in bits per second.
+    #[serde(default)]
+    pub network_bits: bool,
+
+    /// Local time ("HH:MM") to switch to a random face and theme each day.
+    #[serde(default)]
+    pub daily_random: Option<String>,
+
+    /// Date ("YYYY-MM-DD") of the last daily random switch.
+    #[serde(default)]
+    pub last_random: Option<String>,
+
+    /// Derive the LED settings from the display theme.
+    #[serde(default)]
+    pub led_follow_theme: bool,
+
+    /// Block devices tracked by the disk sensor (empty for auto-detect).
+    #[serde(default)]
+    pub disk_devices: Vec<String>,
+
+    /// Display brightness in percent (10-100), applied in software.
+    #[serde(default = "default_brightness")]
+    pub brightness: u8,
+
+    /// Post-processing effects applied to each frame (e.g. "grayscale").
+    #[serde(default)]
+    pub post_effects: Vec<String>,
+
+    /// Mirror each frame left to right, as seen by the viewer.
+    #[serde(default)]
+    pub flip_h: bool,
+
+    /// Mirror each frame top to bottom, as seen by the viewer.
+    #[serde(default)]
+    pub flip_v: bool,
+
+    /// Part of the panel left visible by the case.
+    #[serde(default)]
+    pub visible_area: VisibleArea,
+
+    /// Image drawn behind every face.
+    #[serde(default)]
+    pub background_image: Option<String>,
+
+    /// Overlays drawn on top of every face.
+    #[serde(default)]
+    pub overlays: Vec<Overlay>,
+
+    /// Face switching rules set over D-Bus, replacing the configured ones.
+    #[serde(default)]
+    pub rules: Option<Vec<RuleConfig>>,
+
+    /// Built-in collectors switched on or off over D-Bus, overriding the
+    /// config.
+    #[serde(default)]
+    pub sensors: BTreeMap<String, bool>,
+
+    /// CPU temperature input selected over D-Bus, overriding the config.
+    #[serde(default)]
+    pub temperature_sensor: Option<String>,
+
+    /// Do-not-disturb switched on by hand.
+    #[serde(default)]
+    pub dnd: bool,
 }
 
 fn default_face() -> String {
@@ -85,12 +232,17 @@ fn default_refresh_interval() -> u32 {
     2500 // 2.5s default
 }
 
+fn default_brightness() -> u8 {
+    100
+}
+
 impl Default for DisplaySettings {
     fn default() -> Self {
         Self {
             face: default_face(),
-            orientation: "landscape".to_string(),
+            orientation: String::new(),
             theme: default_theme(),
+            custom_themes: BTreeMap::new(),
             led_theme: default_led_theme(),
             led_intensity: default_led_value(),
             led_speed: default_led_value(),
@@ -98,62 +250,227 @@ impl Default for DisplaySettings {
             network_interface: None,
             ip_display: None,
             complications: EnabledComplications::new(),
+            display_label: None,
+            timezone: None,
+            binary_units: false,
+            network_bits: false,
+            daily_random: None,
+            last_random: None,
+            led_follow_theme: false,
+            disk_devices: Vec::new(),
+            brightness: default_brightness(),
+            post_effects: Vec::new(),
+            flip_h: false,
+            flip_v: false,
+            visible_area: VisibleArea::FULL,
+            background_image: None,
+            overlays: Vec::new(),
+            rules: None,
+            sensors: BTreeMap::new(),
+            temperature_sensor: None,
+            dnd: false,
         }
     }
 }
 
+/// Traffic counter read with the current (year, month, day), giving the
+/// totals per interface.
+type TrafficTotals = Sampled<TrafficCounter, BTreeMap<String, NetworkTotals>, (u16, u8, u8)>;
+
 /// Sensors collection for sampling system data.
+///
+/// Local sensors are read on background tasks (see [`Sampled`]); the other
+/// optional sensors poll on their own threads.
 struct Sensors {
-    cpu: CpuSensor,
-    temperature: TemperatureSensor,
-    memory: MemorySensor,
-    network: NetworkSensor,
-    disk: DiskSensor,
+    cpu: Sampled<CpuSensor, CpuReading>,
+    temperature: Sampled<TemperatureSensor, TemperatureReading>,
+    throttle: Sampled<ThrottleSensor, Option<ThrottleStatus>>,
+    memory: Sampled<MemorySensor, f64>,
+    network: Sampled<NetworkSensor, NetworkReading>,
+    disk: Sampled<DiskSensor, DiskReading>,
     system: SystemInfo,
+    processes: Sampled<ProcessSensor, ProcessCounts>,
+    apps: Sampled<AppCpuSensor, Option<AppUsage>>,
+    sessions: Sampled<SessionSensor, SshSessions>,
+    updates: Option<UpdatesSensor>,
+    hosts: Option<HostsSensor>,
+    #[cfg(feature = "snmp")]
+    snmp: Option<SnmpSensor>,
+    #[cfg(feature = "scrapers")]
+    json: Option<JsonScraper>,
+    #[cfg(feature = "scripting")]
+    exec: Option<ExecSensor>,
+    dns_filter: Option<DnsFilterSensor>,
+    gpu: Option<GpuSensor>,
+    vms: Option<VmSensor>,
+    storage: Option<StorageSensor>,
+    printer: Option<PrinterSensor>,
+    #[cfg(feature = "torrent")]
+    torrent: Option<TorrentSensor>,
+    media_server: Option<MediaServerSensor>,
+    traffic: Option<TrafficTotals>,
+    jobs: Option<JobTracker>,
+    #[cfg(feature = "audio")]
+    audio: Option<AudioSensor>,
+    /// Built-in collectors that are switched off
+    disabled: BTreeSet<&'static str>,
 }
 
 impl Sensors {
-    fn new(network_interface: &str) -> Self {
+    fn new(network: NetworkSensor) -> Self {
         Self {
-            cpu: CpuSensor::new(),
-            temperature: TemperatureSensor::new(),
-            memory: MemorySensor::new(),
-            network: NetworkSensor::new(network_interface),
-            disk: DiskSensor::auto(),
+            cpu: Sampled::new("CPU", CpuSensor::new(), |cpu, ()| cpu.read()),
+            temperature: Sampled::new("Temperature", TemperatureSensor::new(), |temp, ()| {
+                temp.read()
+            }),
+            throttle: Sampled::new("Throttle", ThrottleSensor::new(), |throttle, ()| {
+                throttle.sample()
+            }),
+            memory: Sampled::new("Memory", MemorySensor::new(), |memory, ()| memory.sample()),
+            network: Sampled::new("Network", network, |network, ()| network.read()),
+            disk: Sampled::new("Disk", DiskSensor::auto(), |disk, ()| disk.read()),
             system: SystemInfo::new(),
+            processes: Sampled::new("Process", ProcessSensor::new(), |processes, ()| {
+                processes.sample()
+            }),
+            apps: Sampled::new("Application", AppCpuSensor::new(), |apps, ()| apps.sample()),
+            sessions: Sampled::new("Session", SessionSensor::new(), |sessions, ()| {
+                sessions.sample()
+            }),
+            updates: None,
+            hosts: None,
+            #[cfg(feature = "snmp")]
+            snmp: None,
+            #[cfg(feature = "scrapers")]
+            json: None,
+            #[cfg(feature = "scripting")]
+            exec: None,
+            dns_filter: None,
+            gpu: None,
+            vms: None,
+            storage: None,
+            printer: None,
+            #[cfg(feature = "torrent")]
+            torrent: None,
+            media_server: None,
+            traffic: None,
+            jobs: None,
+            #[cfg(feature = "audio")]
+            audio: None,
+            disabled: BTreeSet::new(),
         }
     }
 
-    fn new_auto() -> Self {
-        Self {
-            cpu: CpuSensor::new(),
-            temperature: TemperatureSensor::new(),
-            memory: MemorySensor::new(),
-            network: NetworkSensor::auto(),
-            disk: DiskSensor::auto(),
-            system: SystemInfo::new(),
+    /// Returns whether the built-in collector `name` is read.
+    fn enabled(&self, name: &str) -> bool {
+        !self.disabled.contains(name)
+    }
+
+    /// Starts reading the local sensors. CPU and memory are always read,
+    /// the built-in collectors only when enabled and in `needed`. The
+    /// receivers complete as each read finishes; sensors still busy with
+    /// an earlier read are skipped.
+    fn start_reads(&mut self, needed: &BTreeSet<&str>) -> Vec<tokio::sync::oneshot::Receiver<()>> {
+        let (_, _, day, month, year, _, _) = self.system.time_components();
+        let read = |name: &str| self.enabled(name) && needed.contains(name);
+        let (temperature, network, disk, processes, sessions) = (
+            read(collector_names::TEMPERATURE),
+            read(collector_names::NETWORK),
+            read(collector_names::DISK),
+            read(collector_names::PROCESSES),
+            read(collector_names::SESSIONS),
+        );
+
+        let mut reads = vec![self.cpu.start(()), self.memory.start(())];
+        if temperature {
+            reads.push(self.temperature.start(()));
+            reads.push(self.throttle.start(()));
+        }
+        if network {
+            reads.push(self.network.start(()));
         }
+        if disk {
+            reads.push(self.disk.start(()));
+        }
+        if processes {
+            reads.push(self.processes.start(()));
+            reads.push(self.apps.start(()));
+        }
+        if sessions {
+            reads.push(self.sessions.start(()));
+        }
+        if let Some(ref mut traffic) = self.traffic {
+            reads.push(traffic.start((year, month, day)));
+        }
+        reads.into_iter().flatten().collect()
     }
 
-    fn sample(&mut self, ip_preference: IpDisplayPreference) -> SystemData {
-        // Sample all sensors
-        let cpu_percent = self.cpu.sample();
-        let _ = self.temperature.sample(); // Updates internal state
-        let cpu_temp = self.temperature.temperature();
-        let ram_percent = self.memory.sample();
-        let _ = self.network.sample(); // Updates internal state
-        let _ = self.disk.sample(); // Updates internal state
+    /// Returns the read status of the local sensors.
+    fn read_health(&self) -> Vec<ReadHealth> {
+        let mut health = vec![
+            self.cpu.health(),
+            self.temperature.health(),
+            self.throttle.health(),
+            self.memory.health(),
+            self.network.health(),
+            self.disk.health(),
+            self.processes.health(),
+            self.apps.health(),
+            self.sessions.health(),
+        ];
+        health.extend(self.traffic.as_ref().map(|traffic| traffic.health()));
+        health
+    }
 
-        // Get the IP address based on preference
-        let display_ip = match ip_preference {
-            IpDisplayPreference::Ipv6Gua => self.network.ipv6_gua(),
-            IpDisplayPreference::Ipv6Lla => self.network.ipv6_lla(),
-            IpDisplayPreference::Ipv6Ula => self.network.ipv6_ula(),
-            IpDisplayPreference::Ipv4 => self.network.ipv4_address(),
+    fn sample(
+        &self,
+        ip_preference: IpDisplayPreference,
+        ip_mode: IpDisplayMode,
+        rotate_secs: u64,
+    ) -> SystemData {
+        // Latest completed readings of the local sensors, empty for those
+        // switched off
+        let network = if self.enabled(collector_names::NETWORK) {
+            self.network.latest()
+        } else {
+            NetworkReading::default()
+        };
+        let disk = if self.enabled(collector_names::DISK) {
+            self.disk.latest()
+        } else {
+            DiskReading::default()
+        };
+        let cpu = self.cpu.latest();
+        let temperature = if self.enabled(collector_names::TEMPERATURE) {
+            self.temperature.latest()
+        } else {
+            TemperatureReading::default()
         };
 
         // Get time components
-        let (hour, minute, day, month, year, day_of_week, _) = self.system.time_components();
+        let (hour, minute, day, month, year, day_of_week, timestamp) =
+            self.system.time_components();
+        let uptime_secs = self.system.uptime_seconds();
+
+        // Get the IP address based on preference
+        let preferred_ip = match ip_preference {
+            IpDisplayPreference::Ipv6Gua => network.ipv6_gua,
+            IpDisplayPreference::Ipv6Lla => network.ipv6_lla,
+            IpDisplayPreference::Ipv6Ula => network.ipv6_ula,
+            IpDisplayPreference::Ipv4 => network.ipv4,
+        };
+        let ip_addresses = network.addresses;
+
+        // Rotate through all addresses; list mode also rotates for faces
+        // that only have room for one line
+        let display_ip = match ip_mode {
+            IpDisplayMode::Single => preferred_ip,
+            IpDisplayMode::Rotate | IpDisplayMode::List if !ip_addresses.is_empty() => {
+                let index = (timestamp / rotate_secs.max(1)) as usize % ip_addresses.len();
+                Some(ip_addresses[index].clone())
+            }
+            _ => preferred_ip,
+        };
 
         SystemData {
             hostname: self.system.hostname(),
@@ -164,23 +481,121 @@ impl Sensors {
             month,
             year,
             day_of_week,
-            uptime: self.system.uptime(),
-            cpu_percent,
-            cpu_temp,
-            ram_percent,
-            disk_read_rate: self.disk.read_rate(),
-            disk_write_rate: self.disk.write_rate(),
-            disk_history: self.disk.history().clone(),
-            disk_read_history: self.disk.read_history().clone(),
-            disk_write_history: self.disk.write_history().clone(),
-            net_interface: self.network.interface_name().to_string(),
-            net_rx_rate: self.network.rx_rate(),
-            net_tx_rate: self.network.tx_rate(),
-            net_history: self.network.history().clone(),
-            net_rx_history: self.network.rx_history().clone(),
-            net_tx_history: self.network.tx_history().clone(),
+            timestamp,
+            uptime: format_uptime(uptime_secs),
+            uptime_secs,
+            boot_time: self.system.boot_time(uptime_secs),
+            cpu_percent: cpu.percent,
+            cpu_history: cpu.history,
+            cpu_temp: temperature.temperature,
+            temp_history: temperature.history,
+            cpu_throttle: if self.enabled(collector_names::TEMPERATURE) {
+                self.throttle.latest()
+            } else {
+                None
+            },
+            ram_percent: self.memory.latest(),
+            disk_read_rate: disk.read_rate,
+            disk_write_rate: disk.write_rate,
+            disk_history: disk.history,
+            disk_read_history: disk.read_history,
+            disk_write_history: disk.write_history,
+            disk_devices: disk.devices,
+            net_totals: self
+                .traffic
+                .as_ref()
+                .and_then(|t| t.latest().get(&network.interface).copied())
+                .unwrap_or_default(),
+            net_interface: network.interface,
+            net_rx_rate: network.rx_rate,
+            net_tx_rate: network.tx_rate,
+            net_history: network.history,
+            net_rx_history: network.rx_history,
+            net_tx_history: network.tx_history,
             display_ip,
+            ip_addresses,
+            rate_units: RateUnits::default(),
+            pending_updates: self.updates.as_ref().and_then(|u| u.pending()),
+            ssh_sessions: if self.enabled(collector_names::SESSIONS) {
+                self.sessions.latest()
+            } else {
+                SshSessions::default()
+            },
+            process_counts: if self.enabled(collector_names::PROCESSES) {
+                self.processes.latest()
+            } else {
+                ProcessCounts::default()
+            },
+            top_app: if self.enabled(collector_names::PROCESSES) {
+                self.apps.latest()
+            } else {
+                None
+            },
+            os_info: self.system.os_info().clone(),
+            cpu_info: self.system.cpu_info().clone(),
+            audio: self.audio_levels(),
+            hosts: self
+                .hosts
+                .as_ref()
+                .map(|h| h.statuses())
+                .unwrap_or_default(),
+            metrics: self.custom_metrics(),
+            dns_stats: self.dns_filter.as_ref().and_then(|d| d.stats()),
+            gpu: self.gpu.as_ref().and_then(|g| g.stats()),
+            vms: self.vms.as_ref().map(|v| v.vms()).unwrap_or_default(),
+            pools: self.storage.as_ref().map(|s| s.pools()).unwrap_or_default(),
+            jobs: self
+                .jobs
+                .as_ref()
+                .map(|j| j.jobs().clone())
+                .unwrap_or_default(),
+            printer: self.printer.as_ref().and_then(|p| p.status()),
+            torrents: self.torrent_stats(),
+            media_streams: self.media_server.as_ref().and_then(|m| m.streams()),
+            // Filled in from the event bus and journal watcher by the caller
+            events: Vec::new(),
+            journal_errors: None,
+        }
+    }
+
+    /// Collects the custom metrics from SNMP, JSON and exec sources.
+    fn custom_metrics(&self) -> BTreeMap<String, Metric> {
+        // Not added to in a build without any custom metric source
+        #[allow(unused_mut)]
+        let mut metrics = BTreeMap::new();
+        #[cfg(feature = "snmp")]
+        if let Some(ref snmp) = self.snmp {
+            metrics.extend(snmp.metrics());
+        }
+        #[cfg(feature = "scrapers")]
+        if let Some(ref json) = self.json {
+            metrics.extend(json.metrics());
+        }
+        #[cfg(feature = "scripting")]
+        if let Some(ref exec) = self.exec {
+            metrics.extend(exec.metrics());
         }
+        metrics
+    }
+
+    #[cfg(feature = "torrent")]
+    fn torrent_stats(&self) -> Option<TorrentStats> {
+        self.torrent.as_ref().and_then(|t| t.stats())
+    }
+
+    #[cfg(not(feature = "torrent"))]
+    fn torrent_stats(&self) -> Option<TorrentStats> {
+        None
+    }
+
+    #[cfg(feature = "audio")]
+    fn audio_levels(&self) -> Option<AudioLevels> {
+        self.audio.as_ref().map(|a| a.levels())
+    }
+
+    #[cfg(not(feature = "audio"))]
+    fn audio_levels(&self) -> Option<AudioLevels> {
+        None
     }
 }
 
@@ -192,8 +607,11 @@ pub struct AppState {
     /// State directory for persisting runtime state
     state_dir: PathBuf,
 
-    /// LCD device (optional - may not be present)
-    lcd: Option<Mutex<LcdDevice>>,
+    /// Display output (optional - may not be present)
+    output: Option<Mutex<Box<dyn Output>>>,
+
+    /// Why the display could not be opened or the last frame failed
+    output_error: RwLock<Option<String>>,
 
     /// LED device path
     led_device_path: String,
@@ -201,6 +619,9 @@ pub struct AppState {
     /// Current orientation
     orientation: RwLock<Orientation>,
 
+    /// Whether the orientation was detected from the host
+    orientation_auto: RwLock<bool>,
+
     /// Render canvas
     canvas: RwLock<Canvas>,
 
@@ -211,13 +632,24 @@ pub struct AppState {
     needs_redraw: RwLock<bool>,
 
     /// Current LED settings
-    led_theme: RwLock<u8>,
-    led_intensity: RwLock<u8>,
-    led_speed: RwLock<u8>,
+    led: RwLock<LedSettings>,
 
     /// Flag indicating LED update is needed
     needs_led_update: RwLock<bool>,
 
+    /// Whether the LED settings follow the display theme
+    led_follow_theme: RwLock<bool>,
+
+    /// Whether a notification pulse currently owns the LEDs
+    led_pulsing: RwLock<bool>,
+
+    /// LED theme restored when the LEDs are toggled back on
+    led_theme_before_off: RwLock<u8>,
+
+    /// On-demand bandwidth test
+    #[cfg(feature = "speedtest")]
+    speedtest: Speedtest,
+
     /// System sensors
     sensors: Mutex<Sensors>,
 
@@ -227,16 +659,152 @@ pub struct AppState {
     /// Current color theme name
     theme_name: RwLock<String>,
 
+    /// Current theme colors, which may be edited live without saving
+    theme: RwLock<Theme>,
+
+    /// Themes saved from the theme editor, by name
+    custom_themes: RwLock<BTreeMap<String, BTreeMap<String, String>>>,
+
     /// Refresh interval in milliseconds (500-10000)
     refresh_interval: RwLock<u32>,
 
     /// Enabled complications per face (with options)
     complications: RwLock<EnabledComplications>,
+
+    /// Label shown instead of the hostname (None shows the hostname)
+    display_label: RwLock<Option<String>>,
+
+    /// Block devices tracked by the disk sensor (empty for auto-detect)
+    disk_devices: RwLock<Vec<String>>,
+
+    /// Units used when formatting disk and network rates.
+    rate_units: RwLock<RateUnits>,
+
+    /// Time of day to switch to a random face and theme (None disables)
+    daily_random: RwLock<Option<chrono::NaiveTime>>,
+
+    /// Date of the last daily random switch
+    last_random: RwLock<Option<chrono::NaiveDate>>,
+
+    /// Set once the shutdown screen is shown; stops further face rendering
+    shutting_down: RwLock<bool>,
+
+    /// Set while a test pattern is shown instead of the face
+    testing: RwLock<bool>,
+
+    /// Whether frames are shown; when off the panel is kept black
+    display_on: RwLock<bool>,
+
+    /// Display brightness in percent (10-100)
+    brightness: RwLock<u8>,
+
+    /// Post-processing effects applied to each frame
+    post_effects: RwLock<Vec<PostEffect>>,
+
+    /// Horizontal and vertical mirroring of each frame
+    transform: RwLock<(bool, bool)>,
+
+    /// Part of the panel frames are drawn in
+    visible_area: RwLock<VisibleArea>,
+
+    /// Night light schedule (None when disabled)
+    night_light: Option<NightLight>,
+
+    /// Current night light warmth (0-1)
+    night_light_strength: RwLock<f32>,
+
+    /// Whether do-not-disturb was switched on by hand
+    dnd: RwLock<bool>,
+
+    /// Daily do-not-disturb windows
+    dnd_schedule: DndSchedule,
+
+    /// Whether the current time falls in a do-not-disturb window
+    dnd_scheduled: RwLock<bool>,
+
+    /// Bus on which changes and notable events are published
+    events: EventBus,
+
+    /// Recent notable events for the timeline face
+    timeline: Arc<Timeline>,
+
+    /// Recent journal errors, while the journal is followed
+    journal_errors: Option<Arc<JournalErrorLog>>,
+
+    /// Log of changes made over D-Bus and HTTP
+    audit: AuditLog,
+
+    /// Undo and redo stacks of display settings
+    history: Mutex<SettingsHistory>,
+
+    /// Where sampled sensor data is recorded, with `--record`
+    recorder: Option<Recorder>,
+
+    /// Recorded sensor data played back instead of sampling, with `--replay`
+    replay: Option<Replay>,
+
+    /// Alerts active on the previous frame, so each alert is published once
+    active_alerts: Mutex<BTreeSet<String>>,
+
+    /// Stage times of recent frames
+    render_stats: Mutex<RenderStats>,
+
+    /// Whether the last frame reached the LCD
+    lcd_responding: RwLock<bool>,
+
+    /// Sensor data of the last rendered frame, for previews
+    last_system_data: RwLock<Option<SystemData>>,
+
+    /// Number of frames presented, identifying the canvas contents
+    frame_number: RwLock<u64>,
+
+    /// Screenshots of the current frame
+    screen_cache: Mutex<ScreenCache>,
+
+    /// When the daemon started
+    started: Instant,
+
+    /// When the last frame was rendered
+    last_frame: RwLock<Option<Instant>>,
+
+    /// Failed LCD redraws and heartbeats
+    usb_errors: RwLock<u64>,
+
+    /// Path of the image drawn behind every face
+    background_image: RwLock<Option<String>>,
+
+    /// Skin of the current face, loaded at the canvas size
+    skin: Mutex<Option<LoadedSkin>>,
+
+    /// Overlays drawn on top of every face
+    overlays: RwLock<Vec<Overlay>>,
+
+    /// Face switching rules
+    rules: Mutex<RuleEngine>,
+
+    /// Whether the rules were set over D-Bus rather than configured
+    custom_rules: RwLock<bool>,
+
+    /// Built-in collectors switched on or off over D-Bus
+    sensor_overrides: RwLock<BTreeMap<String, bool>>,
+
+    /// CPU temperature input selected over D-Bus
+    temperature_override: RwLock<Option<String>>,
+}
+
+/// A face skin with the settings it was loaded for.
+struct LoadedSkin {
+    face: String,
+    config: SkinConfig,
+    size: (u32, u32),
+    /// None if the image failed to load
+    skin: Option<Arc<Skin>>,
 }
 
 impl AppState {
-    /// Creates a new application state.
-    pub fn new(config: Config) -> Result<Self> {
+    /// Creates a new application state publishing to `events`, whose
+    /// notable events `timeline` should already be recording.
+    pub fn new(config: Config, events: EventBus, timeline: Arc<Timeline>) -> Result<Self> {
         // Setup state directory
         let state_dir = PathBuf::from(&config.state_dir);
         if let Err(e) = std::fs::create_dir_all(&state_dir) {
@@ -246,32 +814,53 @@ impl AppState {
         // Load display settings from state
         let settings = Self::load_display_settings(&state_dir);
 
-        // Parse orientation from settings
-        let orientation: Orientation = settings.orientation.parse().unwrap_or_default();
+        // The saved orientation takes precedence over the configured one
+        let requested = match settings.orientation.as_str() {
+            "" => config.canvas.orientation.as_str(),
+            saved => saved,
+        };
+        let orientation_auto = requested == "auto";
+        let orientation = if orientation_auto {
+            detect_orientation(&config.canvas.orientation)
+        } else {
+            requested.parse().unwrap_or_default()
+        };
 
-        // Try to open LCD device
-        let lcd = match LcdDevice::open() {
-            Ok(device) => {
-                // Send initial heartbeat to wake up the device
-                if let Err(e) = device.heartbeat() {
-                    warn!("Failed to send initial heartbeat: {}", e);
-                }
-                // Always use hardware landscape mode - orientation is handled in software
-                if let Err(e) = device.set_orientation(Orientation::Landscape) {
-                    warn!("Failed to set initial orientation: {}", e);
-                }
-                info!("LCD device opened successfully");
-                Some(Mutex::new(device))
-            }
+        // Try to open the display
+        let mut output_error = None;
+        let output = match output::open(&config.devices) {
+            Ok(output) => Some(Mutex::new(output)),
             Err(e) => {
-                warn!("LCD device not found: {}. Running in headless mode.", e);
+                warn!("Display not available: {:#}. Running in headless mode.", e);
+                if let Some(ht32_panel_hw::Error::PermissionDenied { .. }) =
+                    e.downcast_ref::<ht32_panel_hw::Error>()
+                {
+                    warn!(
+                        "udev rules granting access (GenerateUdevRule over D-Bus):\n{}",
+                        access::udev_rules(access::DEFAULT_GROUP)
+                    );
+                }
+                output_error = Some(format!("{:#}", e));
                 None
             }
         };
+        if let Access::Denied { path, detail } = access::serial_access(&config.devices.led) {
+            warn!(
+                "No permission to open LED serial port {} ({}); install the udev rules",
+                path.display(),
+                detail
+            );
+        }
 
-        // Create canvas with dimensions based on saved orientation
-        let (canvas_w, canvas_h) = orientation.dimensions();
-        let mut canvas = Canvas::new(canvas_w as u32, canvas_h as u32);
+        // Create canvas with dimensions based on saved orientation and area
+        let area = settings.visible_area;
+        let visible_area = VisibleArea::new(area.x, area.y, area.width, area.height)
+            .unwrap_or_else(|e| {
+                warn!("Ignoring visible area {}: {}", area, e);
+                VisibleArea::FULL
+            });
+        let (canvas_w, canvas_h) = visible_area.canvas_size(orientation);
+        let mut canvas = Canvas::new(canvas_w, canvas_h);
         let framebuffer = Framebuffer::new();
 
         // Load face from settings
@@ -325,69 +914,270 @@ impl AppState {
             .cloned();
 
         // Initialize sensors - use complication setting or auto-detect
-        let sensors = match network_interface_value.as_ref() {
-            Some(iface) if iface != "auto" && !iface.is_empty() => Sensors::new(iface),
-            _ => Sensors::new_auto(),
+        let mut sensors = match network_interface_value.as_ref() {
+            Some(iface) if iface != "auto" && !iface.is_empty() => {
+                Sensors::new(NetworkSensor::new(iface))
+            }
+            _ => Sensors::new(NetworkSensor::auto()),
         };
 
+        // Start optional sensors
+        if config.sensors.updates.enable {
+            let interval = std::time::Duration::from_secs(config.sensors.updates.interval.max(60));
+            sensors.updates = UpdatesSensor::start(interval);
+            if sensors.updates.is_none() {
+                warn!("Update checks enabled but no supported package manager found");
+            }
+        }
+        let hosts = &config.sensors.hosts;
+        sensors.hosts = HostsSensor::start(
+            &hosts.hosts,
+            std::time::Duration::from_secs(hosts.interval.max(5)),
+        );
+        #[cfg(feature = "snmp")]
+        {
+            sensors.snmp = SnmpSensor::start(&config.sensors.snmp);
+        }
+        #[cfg(not(feature = "snmp"))]
+        if !config.sensors.snmp.is_empty() {
+            warn!("SNMP targets configured but the daemon was built without the snmp feature");
+        }
+        #[cfg(feature = "scrapers")]
+        {
+            sensors.json = JsonScraper::start(&config.sensors.json);
+        }
+        #[cfg(not(feature = "scrapers"))]
+        if !config.sensors.json.is_empty() {
+            warn!("JSON sources configured but the daemon was built without the scrapers feature");
+        }
+        #[cfg(feature = "scripting")]
+        {
+            sensors.exec = ExecSensor::start(&config.sensors.exec);
+        }
+        #[cfg(not(feature = "scripting"))]
+        if !config.sensors.exec.is_empty() {
+            warn!("Exec sources configured but the daemon was built without the scripting feature");
+        }
+        sensors.dns_filter = DnsFilterSensor::start(&config.sensors.dns_filter);
+        if config.sensors.vms.enable {
+            let vms = &config.sensors.vms;
+            sensors.vms = VmSensor::start(
+                &vms.uri,
+                std::time::Duration::from_secs(vms.interval.max(1)),
+            );
+        }
+        sensors.storage = StorageSensor::start(&config.sensors.storage);
+        sensors.printer = PrinterSensor::start(&config.sensors.printer);
+        #[cfg(feature = "torrent")]
+        {
+            sensors.torrent = TorrentSensor::start(&config.sensors.torrent);
+        }
+        #[cfg(not(feature = "torrent"))]
+        if config.sensors.torrent.enable {
+            warn!(
+                "Torrent statistics enabled but the daemon was built without the torrent feature"
+            );
+        }
+        sensors.media_server = MediaServerSensor::start(&config.sensors.media_server);
+        if config.sensors.gpu.enable {
+            sensors.gpu = GpuSensor::start();
+        }
+        if config.sensors.audio.enable {
+            #[cfg(feature = "audio")]
+            {
+                sensors.audio = AudioSensor::start(&config.sensors.audio.source);
+                if sensors.audio.is_none() {
+                    warn!("Audio capture enabled but parec could not be started");
+                }
+            }
+            #[cfg(not(feature = "audio"))]
+            warn!("Audio capture enabled but the daemon was built without the audio feature");
+        }
+
+        if !settings.disk_devices.is_empty() {
+            sensors
+                .disk
+                .with(|disk| disk.set_devices(&settings.disk_devices));
+        }
+        let temperature_sensor = temperature_selector(&config, &settings.temperature_sensor);
+        if let Some(ref selector) = temperature_sensor {
+            let found = sensors.temperature.with(|temp| temp.select(Some(selector)));
+            if !found {
+                warn!(
+                    "Temperature sensor '{}' not found, auto-detecting",
+                    selector
+                );
+            }
+        }
+        sensors.traffic = Some(Sampled::new(
+            "Traffic",
+            TrafficCounter::load(state_dir.join("traffic.toml")),
+            |traffic, (year, month, day)| {
+                traffic.sample(year, month, day);
+                traffic.all_totals()
+            },
+        ));
+        sensors.jobs = Some(JobTracker::load(state_dir.join("jobs.toml")));
+        sensors.disabled = disabled_collectors(&config.sensors.collectors, &settings.sensors);
+
+        // Apply timezone override
+        if let Some(ref name) = settings.timezone {
+            match name.parse::<chrono_tz::Tz>() {
+                Ok(tz) => {
+                    sensors.system.set_timezone(Some(tz));
+                    info!("Timezone: {}", tz);
+                }
+                Err(_) => warn!("Unknown timezone '{}', using system timezone", name),
+            }
+        }
+
         // Load theme and set canvas background
-        let theme = Theme::from_preset(&settings.theme);
+        let theme = resolve_theme(&settings.theme, &settings.custom_themes)
+            .unwrap_or_else(|| Theme::from_preset(&settings.theme));
         canvas.set_background(theme.background);
+        if let Some(ref path) = settings.background_image {
+            Self::load_background_image(&mut canvas, path);
+        }
 
         info!("Display orientation: {}", orientation);
         info!("Theme: {}", settings.theme);
 
-        Ok(Self {
+        let journal_errors = watch_journal(&config.sensors.journal, events.clone());
+        let night_light = NightLight::from_config(&config.night_light);
+        let dnd_schedule = DndSchedule::from_config(&config.dnd.schedule);
+        let rules = RuleEngine::new(
+            settings
+                .rules
+                .clone()
+                .unwrap_or_else(|| config.rules.clone()),
+        );
+        let audit = AuditLog::open(&state_dir);
+
+        let state = Self {
             led_device_path: config.devices.led.clone(),
-            led_theme: RwLock::new(settings.led_theme),
-            led_intensity: RwLock::new(settings.led_intensity),
-            led_speed: RwLock::new(settings.led_speed),
+            led: RwLock::new(LedSettings::clamped(
+                settings.led_theme,
+                settings.led_intensity,
+                settings.led_speed,
+            )),
             state_dir,
             config: RwLock::new(config),
-            lcd,
+            output,
+            output_error: RwLock::new(output_error),
             orientation: RwLock::new(orientation),
+            orientation_auto: RwLock::new(orientation_auto),
             canvas: RwLock::new(canvas),
             framebuffer: RwLock::new(framebuffer),
             needs_redraw: RwLock::new(true),
             needs_led_update: RwLock::new(true),
+            led_follow_theme: RwLock::new(settings.led_follow_theme),
+            led_pulsing: RwLock::new(false),
+            led_theme_before_off: RwLock::new(if settings.led_theme == LedTheme::Off as u8 {
+                LedTheme::Auto as u8
+            } else {
+                settings.led_theme
+            }),
+            #[cfg(feature = "speedtest")]
+            speedtest: Speedtest::new(),
             sensors: Mutex::new(sensors),
             face: RwLock::new(face),
             theme_name: RwLock::new(settings.theme),
-            refresh_interval: RwLock::new(settings.refresh_interval),
+            theme: RwLock::new(theme),
+            custom_themes: RwLock::new(settings.custom_themes),
+            // A zero interval from a hand-edited state file would panic the
+            // frame pacer
+            refresh_interval: RwLock::new(
+                settings
+                    .refresh_interval
+                    .clamp(*REFRESH_INTERVALS.start(), *REFRESH_INTERVALS.end()),
+            ),
             complications: RwLock::new(complications),
-        })
+            display_label: RwLock::new(settings.display_label),
+            disk_devices: RwLock::new(settings.disk_devices.clone()),
+            rate_units: RwLock::new(RateUnits {
+                binary: settings.binary_units,
+                network_bits: settings.network_bits,
+            }),
+            daily_random: RwLock::new(
+                settings
+                    .daily_random
+                    .as_deref()
+                    .and_then(|t| chrono::NaiveTime::parse_from_str(t, "%H:%M").ok()),
+            ),
+            last_random: RwLock::new(settings.last_random.as_deref().and_then(|d| d.parse().ok())),
+            shutting_down: RwLock::new(false),
+            testing: RwLock::new(false),
+            display_on: RwLock::new(true),
+            brightness: RwLock::new(settings.brightness.clamp(MIN_BRIGHTNESS, 100)),
+            post_effects: RwLock::new(
+                settings
+                    .post_effects
+                    .iter()
+                    .filter_map(|e| e.parse().ok())
+                    .collect(),
+            ),
+            transform: RwLock::new((settings.flip_h, settings.flip_v)),
+            visible_area: RwLock::new(visible_area),
+            night_light,
+            night_light_strength: RwLock::new(0.0),
+            dnd: RwLock::new(settings.dnd),
+            dnd_schedule,
+            dnd_scheduled: RwLock::new(false),
+            events,
+            timeline,
+            journal_errors,
+            audit,
+            history: Mutex::new(SettingsHistory::new(&DisplaySettings::default())),
+            recorder: None,
+            replay: None,
+            active_alerts: Mutex::new(BTreeSet::new()),
+            render_stats: Mutex::new(RenderStats::default()),
+            lcd_responding: RwLock::new(true),
+            last_system_data: RwLock::new(None),
+            frame_number: RwLock::new(0),
+            screen_cache: Mutex::new(ScreenCache::default()),
+            started: Instant::now(),
+            last_frame: RwLock::new(None),
+            usb_errors: RwLock::new(0),
+            background_image: RwLock::new(settings.background_image.clone()),
+            skin: Mutex::new(None),
+            overlays: RwLock::new(settings.overlays.clone()),
+            rules: Mutex::new(rules),
+            custom_rules: RwLock::new(settings.rules.is_some()),
+            sensor_overrides: RwLock::new(settings.sensors.clone()),
+            temperature_override: RwLock::new(settings.temperature_sensor.clone()),
+        };
+        // Start undo history from the settings as applied, not as loaded
+        let applied = state.display_settings();
+        state.history.lock().unwrap().finish(&applied);
+        Ok(state)
     }
 
     /// Loads display settings from state directory.
     fn load_display_settings(state_dir: &Path) -> DisplaySettings {
         let settings_file = state_dir.join("display.toml");
         if let Ok(content) = std::fs::read_to_string(&settings_file) {
-            if let Ok(settings) = toml::from_str(&content) {
-                return settings;
+            match toml::from_str(&content) {
+                Ok(settings) => return settings,
+                Err(e) => warn!(
+                    "Ignoring invalid display settings in {:?}: {}",
+                    settings_file, e
+                ),
             }
         }
         DisplaySettings::default()
     }
 
-    /// Saves display settings to state directory.
+    /// Saves display settings to state directory atomically, recording the
+    /// change for undo.
     fn save_display_settings(&self) {
-        let settings = DisplaySettings {
-            face: self.face.read().unwrap().name().to_string(),
-            orientation: self.orientation.read().unwrap().to_string(),
-            theme: self.theme_name.read().unwrap().clone(),
-            led_theme: *self.led_theme.read().unwrap(),
-            led_intensity: *self.led_intensity.read().unwrap(),
-            led_speed: *self.led_speed.read().unwrap(),
-            refresh_interval: *self.refresh_interval.read().unwrap(),
-            network_interface: None, // Legacy field, skip serializing
-            ip_display: None,        // Legacy field, skip serializing
-            complications: self.complications.read().unwrap().clone(),
-        };
+        let settings = self.display_settings();
+        self.history.lock().unwrap().record(&settings);
 
         let settings_file = self.state_dir.join("display.toml");
         match toml::to_string_pretty(&settings) {
             Ok(content) => {
-                if let Err(e) = std::fs::write(&settings_file, content) {
+                if let Err(e) = write_atomic(&settings_file, content) {
                     warn!("Failed to save display settings: {}", e);
                 }
             }
@@ -416,31 +1206,88 @@ impl AppState {
         *self.orientation.read().unwrap()
     }
 
-    /// Returns true if the LCD device is connected.
+    /// Returns true if the display is connected.
     pub fn is_lcd_connected(&self) -> bool {
-        self.lcd.is_some()
+        self.output.is_some()
+    }
+
+    /// Returns why the display could not be opened or the last frame
+    /// failed to reach it, or "" while frames are getting through.
+    pub fn lcd_error(&self) -> String {
+        self.output_error
+            .read()
+            .unwrap()
+            .clone()
+            .unwrap_or_default()
+    }
+
+    /// Records every sensor sample to `recorder`.
+    pub fn set_recorder(&mut self, recorder: Recorder) {
+        self.recorder = Some(recorder);
+    }
+
+    /// Plays back `replay` instead of sampling the sensors.
+    pub fn set_replay(&mut self, replay: Replay) {
+        self.replay = Some(replay);
+    }
+
+    /// Returns true in kiosk mode, where only root may make changes.
+    pub fn is_kiosk(&self) -> bool {
+        self.config.read().unwrap().kiosk
     }
 
-    /// Returns true if the web UI is enabled.
+    /// Returns true if the web UI is enabled and built in.
     pub fn is_web_enabled(&self) -> bool {
-        self.config.read().unwrap().web.enable
+        cfg!(feature = "web") && self.config.read().unwrap().web.enable
+    }
+
+    /// Returns true if the orientation is detected from the host.
+    pub fn is_orientation_auto(&self) -> bool {
+        *self.orientation_auto.read().unwrap()
     }
 
     /// Sets the display orientation.
     pub fn set_orientation(&self, orientation: Orientation) -> Result<()> {
+        self.apply_orientation(orientation, false)
+    }
+
+    /// Detects the orientation from an accelerometer or the DMI chassis
+    /// type, falling back to the configured orientation, and keeps
+    /// detecting it on restart. Returns the orientation now in use.
+    pub fn set_orientation_auto(&self) -> Result<Orientation> {
+        let configured = self.config.read().unwrap().canvas.orientation.clone();
+        let orientation = detect_orientation(&configured);
+        self.apply_orientation(orientation, true)?;
+        Ok(orientation)
+    }
+
+    /// Switches to `orientation`, recording whether it was detected.
+    fn apply_orientation(&self, orientation: Orientation, auto: bool) -> Result<()> {
         // Always keep hardware in landscape mode - we handle orientation in software
-        if let Some(ref lcd) = self.lcd {
-            let device = lcd.lock().unwrap();
+        if let Some(ref output) = self.output {
             // Use hardware landscape mode always - portrait is handled via software rotation
-            device.set_orientation(Orientation::Landscape)?;
+            output.lock().unwrap().set_landscape()?;
         }
         *self.orientation.write().unwrap() = orientation;
+        *self.orientation_auto.write().unwrap() = auto;
+
+        self.resize_canvas();
+        self.save_display_settings();
+        info!("Orientation set to: {}", orientation);
+        Ok(())
+    }
 
+    /// Resizes the canvas for the orientation and visible area and clears
+    /// the frame.
+    fn resize_canvas(&self) {
         // Resize canvas for the logical orientation (faces render to this)
-        let (width, height) = orientation.dimensions();
+        let (width, height) = self.visible_area().canvas_size(self.orientation());
         {
             let mut canvas = self.canvas.write().unwrap();
-            canvas.resize(width as u32, height as u32);
+            canvas.resize(width, height);
+            if let Some(ref path) = *self.background_image.read().unwrap() {
+                Self::load_background_image(&mut canvas, path);
+            }
             canvas.clear(); // Clear to avoid stale content
         }
         // Keep framebuffer at hardware native size (320x170) - we transform canvas into it
@@ -451,9 +1298,20 @@ impl AppState {
         }
 
         *self.needs_redraw.write().unwrap() = true;
+    }
+
+    /// Gets the part of the panel frames are drawn in.
+    pub fn visible_area(&self) -> VisibleArea {
+        *self.visible_area.read().unwrap()
+    }
+
+    /// Draws frames only in `area` of the panel, for case cutouts that hide
+    /// its edges, leaving the rest black. Faces lay out within the area.
+    pub fn set_visible_area(&self, area: VisibleArea) {
+        *self.visible_area.write().unwrap() = area;
+        self.resize_canvas();
         self.save_display_settings();
-        info!("Orientation set to: {}", orientation);
-        Ok(())
+        info!("Visible area set to: {}", area);
     }
 
     /// Gets the current refresh interval in milliseconds.
@@ -468,34 +1326,96 @@ impl AppState {
 
     /// Sets the refresh interval in milliseconds (clamped to 500-10000).
     pub fn set_refresh_interval(&self, ms: u32) {
-        let clamped = ms.clamp(500, 10000);
+        let clamped = ms.clamp(*REFRESH_INTERVALS.start(), *REFRESH_INTERVALS.end());
         *self.refresh_interval.write().unwrap() = clamped;
         self.save_display_settings();
         info!("Refresh interval set to {}ms", clamped);
     }
 
+    /// Returns whether frames are shown on the panel.
+    pub fn display_on(&self) -> bool {
+        *self.display_on.read().unwrap()
+    }
+
+    /// Shows frames, or keeps the panel black when `on` is false. The
+    /// panel has no backlight control, so "off" is a black frame.
+    pub fn set_display_on(&self, on: bool) {
+        *self.display_on.write().unwrap() = on;
+        *self.needs_redraw.write().unwrap() = true;
+        info!("Display {}", if on { "on" } else { "off" });
+    }
+
+    /// Gets the display brightness in percent.
+    pub fn brightness(&self) -> u8 {
+        *self.brightness.read().unwrap()
+    }
+
+    /// Sets the display brightness in percent (clamped to 10-100).
+    /// Returns the new brightness.
+    pub fn set_brightness(&self, percent: i32) -> u8 {
+        let clamped = percent.clamp(MIN_BRIGHTNESS as i32, 100) as u8;
+        *self.brightness.write().unwrap() = clamped;
+        self.save_display_settings();
+        info!("Brightness set to {}%", clamped);
+        clamped
+    }
+
+    /// Gets the post-processing effects as names.
+    pub fn post_effects(&self) -> Vec<String> {
+        self.post_effects
+            .read()
+            .unwrap()
+            .iter()
+            .map(|e| e.to_string())
+            .collect()
+    }
+
+    /// Sets the post-processing effects, applied in order. An empty list
+    /// turns them off.
+    pub fn set_post_effects(&self, names: &[String]) -> Result<()> {
+        let effects = names
+            .iter()
+            .map(|name| name.parse::<PostEffect>())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| anyhow::anyhow!(e))?;
+        *self.post_effects.write().unwrap() = effects;
+        *self.needs_redraw.write().unwrap() = true;
+        self.save_display_settings();
+        info!("Post effects set to: {:?}", names);
+        Ok(())
+    }
+
+    /// Gets the horizontal and vertical mirroring.
+    pub fn transform(&self) -> (bool, bool) {
+        *self.transform.read().unwrap()
+    }
+
+    /// Mirrors every frame horizontally and/or vertically, as seen by the
+    /// viewer, for panels behind tinted glass or in mirrored enclosures.
+    pub fn set_transform(&self, flip_h: bool, flip_v: bool) {
+        *self.transform.write().unwrap() = (flip_h, flip_v);
+        *self.needs_redraw.write().unwrap() = true;
+        self.save_display_settings();
+        info!("Transform set to: flip_h={} flip_v={}", flip_h, flip_v);
+    }
+
     /// Gets the current LED settings.
-    pub fn led_settings(&self) -> (u8, u8, u8) {
-        (
-            *self.led_theme.read().unwrap(),
-            *self.led_intensity.read().unwrap(),
-            *self.led_speed.read().unwrap(),
-        )
+    pub fn led_settings(&self) -> LedSettings {
+        *self.led.read().unwrap()
     }
 
     /// Sets the LED theme and parameters.
     /// Always updates state, but logs an error if hardware communication fails.
-    pub async fn set_led(&self, theme: u8, intensity: u8, speed: u8) -> Result<()> {
+    pub async fn set_led(&self, settings: LedSettings) -> Result<()> {
         // Always update state so UI reflects user's choice
-        *self.led_theme.write().unwrap() = theme;
-        *self.led_intensity.write().unwrap() = intensity;
-        *self.led_speed.write().unwrap() = speed;
+        *self.led.write().unwrap() = settings;
         self.save_display_settings();
 
         // Try to send to hardware
         let led = LedDevice::new(&self.led_device_path);
-        let led_theme = LedTheme::from_byte(theme)?;
-        if let Err(e) = led.set_theme(led_theme, intensity, speed).await {
+        let (theme, intensity, speed) =
+            (settings.led_theme(), settings.intensity(), settings.speed());
+        if let Err(e) = led.set_theme(theme, intensity, speed).await {
             warn!(
                 "Failed to send LED command to {}: {}",
                 self.led_device_path, e
@@ -514,27 +1434,231 @@ impl AppState {
     pub async fn led_off(&self) -> Result<()> {
         let led = LedDevice::new(&self.led_device_path);
         led.set_off().await?;
-        *self.led_theme.write().unwrap() = 4; // Off
+        let previous = {
+            let mut settings = self.led.write().unwrap();
+            let previous = settings.theme();
+            *settings = settings.to_builder().theme(LedTheme::Off as u8).build()?;
+            previous
+        };
+        if previous != LedTheme::Off as u8 {
+            *self.led_theme_before_off.write().unwrap() = previous;
+        }
         self.save_display_settings();
         info!("LED turned off");
         Ok(())
     }
 
-    /// Sends a heartbeat to the LCD device.
-    pub fn send_heartbeat(&self) -> Result<()> {
-        if let Some(ref lcd) = self.lcd {
-            let device = lcd.lock().unwrap();
-            device.heartbeat()?;
+    /// Turns the LEDs off, or back on with the theme used before they were
+    /// turned off. Returns whether the LEDs are now on.
+    pub async fn toggle_led(&self) -> Result<bool> {
+        let settings = self.led_settings();
+        if !settings.is_off() {
+            self.led_off().await?;
+            return Ok(false);
+        }
+        let previous = *self.led_theme_before_off.read().unwrap();
+        self.set_led(settings.to_builder().theme(previous).build()?)
+            .await?;
+        Ok(true)
+    }
+
+    /// Flashes the LEDs `count` times with the given effect, then restores
+    /// the configured LED settings. The strip has no per-color control, so
+    /// the pulse "color" is one of the LED effects. Does nothing during
+    /// do-not-disturb.
+    pub async fn pulse_led(&self, theme: LedTheme, count: u32) -> Result<()> {
+        if self.dnd_active() {
+            info!("Skipping LED pulse during do-not-disturb");
+            return Ok(());
+        }
+        {
+            let mut pulsing = self.led_pulsing.write().unwrap();
+            if *pulsing {
+                anyhow::bail!("An LED pulse is already running");
+            }
+            *pulsing = true;
+        }
+        info!("Pulsing LEDs {} times with {}", count, theme);
+
+        let led = LedDevice::new(&self.led_device_path);
+        let mut result = Ok(());
+        for _ in 0..count {
+            if let Err(e) = led.set_theme(theme, 5, 5).await {
+                result = Err(e);
+                break;
+            }
+            tokio::time::sleep(LED_PULSE_ON).await;
+            if let Err(e) = led.set_off().await {
+                result = Err(e);
+                break;
+            }
+            tokio::time::sleep(LED_PULSE_OFF).await;
+        }
+
+        // Restore whatever is configured now, including changes made mid-pulse
+        *self.led_pulsing.write().unwrap() = false;
+        if self.set_led(self.led_settings()).await.is_err() {
+            *self.needs_led_update.write().unwrap() = true;
+        }
+        result.with_context(|| format!("Failed to pulse LEDs on {}", self.led_device_path))
+    }
+
+    /// Flashes the LEDs on each detected audio beat until the daemon exits.
+    /// Beats are skipped while a notification pulse owns the LEDs or
+    /// do-not-disturb is on.
+    #[cfg(feature = "audio")]
+    pub async fn led_beat_loop(&self) {
+        let led = LedDevice::new(&self.led_device_path);
+        let mut last_beats = 0;
+        let mut lit = false;
+        loop {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            let beats = {
+                let sensors = self.sensors.lock().unwrap();
+                match sensors.audio.as_ref() {
+                    Some(audio) => audio.levels().beats,
+                    None => return,
+                }
+            };
+            if *self.led_pulsing.read().unwrap()
+                || *self.shutting_down.read().unwrap()
+                || self.dnd_active()
+            {
+                if lit {
+                    // Let the render loop restore the configured effect
+                    lit = false;
+                    *self.needs_led_update.write().unwrap() = true;
+                }
+                last_beats = beats;
+                continue;
+            }
+
+            let settings = self.led_settings();
+            let (intensity, speed) = (settings.intensity(), settings.speed());
+            let theme = match settings.led_theme() {
+                LedTheme::Off => LedTheme::Breathing,
+                theme => theme,
+            };
+            if beats != last_beats {
+                last_beats = beats;
+                if led.set_theme(theme, 5, speed).await.is_ok() {
+                    lit = true;
+                }
+            } else if lit {
+                // Dim back down until the next beat
+                lit = false;
+                if let Err(e) = led.set_theme(theme, intensity.min(2), speed).await {
+                    debug!("LED beat update failed: {}", e);
+                }
+            }
+        }
+    }
+
+    /// Sends a heartbeat to the display.
+    pub fn send_heartbeat(&self) -> Result<()> {
+        if let Some(ref output) = self.output {
+            let device = output.lock().unwrap();
+            device.heartbeat().inspect_err(|_| self.count_usb_error())?;
             debug!("Heartbeat sent");
         }
         Ok(())
     }
 
-    /// Samples all sensors and returns the current system data.
+    /// Reads the local sensors, waiting briefly for the reads to finish.
+    /// Sensors that take longer keep their previous reading, as do the
+    /// built-in collectors not in `needed`.
+    async fn read_sensors(&self, needed: &BTreeSet<&str>) {
+        if self.replay.is_some() {
+            return;
+        }
+        let reads = self.sensors.lock().unwrap().start_reads(needed);
+        wait_for_reads(reads).await;
+    }
+
+    /// Returns the built-in collectors whose data is on screen: those the
+    /// face requires (less the process and SSH session scans while their
+    /// complications are off), those behind its placed complications and overlays, the
+    /// process counts for the zombie banner, and those read by rules.
+    fn needed_collectors(&self) -> BTreeSet<&'static str> {
+        let face = self.face.read().unwrap();
+        let complications = self.complications.read().unwrap();
+        let mut needed: BTreeSet<&'static str> = face.required_sensors().iter().copied().collect();
+        // The /proc and utmp scans only feed the process and SSH session
+        // complications, so skip them while those are switched off
+        let available = face.available_complications();
+        let shown = |collector: &str| {
+            available.iter().any(|c| {
+                faces::collector_complications(collector).contains(&c.id.as_str())
+                    && complications.is_enabled(face.name(), &c.id, c.default_enabled)
+            })
+        };
+        for collector in [collector_names::PROCESSES, collector_names::SESSIONS] {
+            if !shown(collector) {
+                needed.remove(collector);
+            }
+        }
+        needed.extend(
+            complications
+                .placed(face.name())
+                .iter()
+                .filter_map(|(id, _)| faces::complication_collector(id)),
+        );
+        needed.extend(self.overlays.read().unwrap().iter().filter_map(|overlay| {
+            match overlay.id.as_str() {
+                overlays::CONNECTION => Some(collector_names::NETWORK),
+                id => faces::complication_collector(id),
+            }
+        }));
+        if complications.is_enabled(face.name(), faces::complication_names::PROCESSES, false) {
+            needed.insert(collector_names::PROCESSES);
+        }
+        needed.extend(
+            self.rules
+                .lock()
+                .unwrap()
+                .rules()
+                .iter()
+                .filter_map(|rule| rules::collector(&rule.when)),
+        );
+        needed
+    }
+
+    /// Returns the current system data from the latest sensor readings.
     fn sample_sensors(&self) -> SystemData {
-        let mut sensors = self.sensors.lock().unwrap();
+        if let Some(ref replay) = self.replay {
+            return replay.current();
+        }
+        let sensors = self.sensors.lock().unwrap();
         let ip_preference = self.get_ip_display_from_complications();
-        sensors.sample(ip_preference)
+        let (ip_mode, rotate_secs) = self.get_ip_mode_from_complications();
+        let mut data = sensors.sample(ip_preference, ip_mode, rotate_secs);
+        if let Some(label) = self.display_label.read().unwrap().as_ref() {
+            data.hostname = label.clone();
+        }
+        data.rate_units = *self.rate_units.read().unwrap();
+        data.events = self.timeline.recent();
+        data.journal_errors = self.journal_errors.as_ref().map(|log| log.summary());
+        if let Some(ref recorder) = self.recorder {
+            recorder.record(&data);
+        }
+        data
+    }
+
+    /// Gets the multi-address display mode and rotation interval from complications.
+    fn get_ip_mode_from_complications(&self) -> (IpDisplayMode, u64) {
+        let face_name = self.face.read().unwrap().name().to_string();
+        let complications = self.complications.read().unwrap();
+        let option = |id: &str| {
+            complications.get_option(&face_name, faces::complication_names::IP_ADDRESS, id)
+        };
+        let mode = option(faces::complication_options::IP_MODE)
+            .and_then(|s| s.parse().ok())
+            .unwrap_or_default();
+        let rotate_secs = option(faces::complication_options::ROTATE_INTERVAL)
+            .and_then(|s| s.parse::<f32>().ok())
+            .map(|secs| secs as u64)
+            .unwrap_or(5);
+        (mode, rotate_secs)
     }
 
     /// Gets the IP display preference from complications.
@@ -565,55 +1689,554 @@ impl AppState {
             .cloned()
     }
 
+    /// Starts a speedtest with the configured backend. The returned receiver
+    /// resolves with the result once the test completes.
+    #[cfg(feature = "speedtest")]
+    pub fn run_speedtest(
+        &self,
+    ) -> Result<tokio::sync::oneshot::Receiver<Result<SpeedtestResult, String>>> {
+        let config = self.config.read().unwrap().speedtest.clone();
+        let done = self.speedtest.start(&config)?;
+        info!("Speedtest started");
+        Ok(done)
+    }
+
+    /// Sends a Wake-on-LAN packet to a MAC address or to the MAC of a
+    /// configured host matched by name or address. Returns the MAC used.
+    pub fn wake_host(&self, target: &str) -> Result<String> {
+        let mac = if target.contains([':', '-']) && target.len() == 17 {
+            target.to_string()
+        } else {
+            let config = self.config.read().unwrap();
+            let host = config
+                .sensors
+                .hosts
+                .hosts
+                .iter()
+                .find(|h| h.display_name().eq_ignore_ascii_case(target) || h.address == target)
+                .with_context(|| format!("Unknown host: {}", target))?;
+            host.mac
+                .clone()
+                .with_context(|| format!("No MAC address configured for {}", target))?
+        };
+        wake_on_lan(&mac)?;
+        info!("Sent Wake-on-LAN packet to {}", mac);
+        Ok(mac)
+    }
+
+    /// Records the status of an external job such as a backup. `status` is
+    /// "running", "success" or "failed"; `progress` (0-100) is only used
+    /// while running.
+    pub fn report_job(&self, name: &str, status: &str, progress: Option<f32>) -> Result<()> {
+        let name = name.trim();
+        if name.is_empty() {
+            anyhow::bail!("Job name must not be empty");
+        }
+        let state: JobState = status.parse().map_err(anyhow::Error::msg)?;
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+
+        if let Some(ref mut jobs) = self.sensors.lock().unwrap().jobs {
+            jobs.report(name, state, progress, now);
+        }
+        if state != JobState::Running {
+            info!("Job {} reported {:?}", name, state);
+        }
+        *self.needs_redraw.write().unwrap() = true;
+        Ok(())
+    }
+
+    #[cfg(not(feature = "speedtest"))]
+    fn speedtest_banner(&self, _data: &SystemData) -> Option<String> {
+        None
+    }
+
+    /// Returns the speedtest progress or a recent result as banner text.
+    #[cfg(feature = "speedtest")]
+    fn speedtest_banner(&self, data: &SystemData) -> Option<String> {
+        match self.speedtest.status() {
+            SpeedtestStatus::Idle => None,
+            SpeedtestStatus::Running { progress, rate } => {
+                if rate > 0.0 {
+                    Some(format!(
+                        "Speedtest {:.0}% \u{2193}{}",
+                        progress * 100.0,
+                        data.format_net_rate_compact(rate)
+                    ))
+                } else {
+                    Some("Running speedtest...".to_string())
+                }
+            }
+            SpeedtestStatus::Finished { result, at } if at.elapsed() < SPEEDTEST_DISPLAY => {
+                let mut text = format!("\u{2193}{}", data.format_net_rate_compact(result.download));
+                if let Some(upload) = result.upload {
+                    text.push_str(&format!(
+                        " \u{2191}{}",
+                        data.format_net_rate_compact(upload)
+                    ));
+                }
+                if let Some(ping) = result.ping_ms {
+                    text.push_str(&format!(" {:.0}ms", ping));
+                }
+                Some(text)
+            }
+            SpeedtestStatus::Failed { at, .. } if at.elapsed() < SPEEDTEST_DISPLAY => {
+                Some("Speedtest failed".to_string())
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the banner to draw over the face, if any: a degraded storage
+    /// pool, a custom metric outside its thresholds, a zombie process alert above its threshold,
+    /// otherwise a freshly reached uptime milestone.
+    fn overlay_banner(
+        face_name: &str,
+        complications: &EnabledComplications,
+        data: &SystemData,
+    ) -> Option<String> {
+        if let Some(pool) = data.pools.iter().find(|p| p.degraded) {
+            return Some(format!("{} {}", pool.name, pool.state));
+        }
+        if let Some((name, metric)) = data.metrics.iter().find(|(_, m)| m.alert) {
+            return Some(format!("{} {}", name, metric.format()));
+        }
+
+        let processes = faces::complication_names::PROCESSES;
+        if complications.is_enabled(face_name, processes, false) {
+            let threshold = complications
+                .get_option(
+                    face_name,
+                    processes,
+                    faces::complication_options::ZOMBIE_THRESHOLD,
+                )
+                .and_then(|s| s.parse::<f32>().ok())
+                .unwrap_or(0.0) as u32;
+            let zombies = data.process_counts.zombies;
+            if threshold > 0 && zombies >= threshold {
+                return Some(format!("{} zombie processes", zombies));
+            }
+        }
+
+        let uptime = faces::complication_names::UPTIME;
+        if !complications.is_enabled(face_name, uptime, true) {
+            return None;
+        }
+        let milestones_on = complications
+            .get_option(face_name, uptime, faces::complication_options::MILESTONES)
+            .is_some_and(|v| v == "on");
+        if milestones_on {
+            data.uptime_milestone()
+                .map(|days| format!("{} days up!", days))
+        } else {
+            None
+        }
+    }
+
+    /// Publishes an alert for each degraded pool, alerting metric, failed
+    /// job or CPU throttling that was not active on the previous frame, and
+    /// flashes the LEDs if an alert pulse is configured.
+    fn check_alerts(self: &Arc<Self>, data: &SystemData) {
+        let mut current = BTreeMap::new();
+        for pool in data.pools.iter().filter(|p| p.degraded) {
+            current.insert(
+                format!("pool:{}", pool.name),
+                format!("Storage pool {} is {}", pool.name, pool.state),
+            );
+        }
+        for (name, metric) in data.metrics.iter().filter(|(_, m)| m.alert) {
+            current.insert(
+                format!("metric:{}", name),
+                format!("{} is {}", name, metric.format()),
+            );
+        }
+        for (name, _) in data
+            .jobs
+            .iter()
+            .filter(|(_, j)| j.state == JobState::Failed)
+        {
+            current.insert(format!("job:{}", name), format!("Job {} failed", name));
+        }
+        if data.cpu_throttle.is_some_and(|t| t.throttled) {
+            current.insert(
+                "throttle".to_string(),
+                "CPU is thermally throttled".to_string(),
+            );
+        }
+
+        let raised = raise_alerts(&mut self.active_alerts.lock().unwrap(), current);
+        for message in &raised {
+            self.events.publish(Event::Alert(message.clone()));
+        }
+
+        let config = self.config.read().unwrap().alerts.pulse.clone();
+        if let Some((theme, count)) = alert_pulse(&raised, config.as_ref(), self.dnd_active()) {
+            let state = Arc::clone(self);
+            tokio::spawn(async move {
+                if let Err(e) = state.pulse_led(theme, count).await {
+                    warn!("Alert LED pulse failed: {:#}", e);
+                }
+            });
+        }
+    }
+
     /// Renders a frame and updates the display.
-    pub async fn render_frame(&self) -> Result<()> {
+    pub async fn render_frame(self: &Arc<Self>) -> Result<()> {
+        if *self.shutting_down.read().unwrap() || *self.testing.read().unwrap() {
+            return Ok(());
+        }
+
         // Always sample sensors and render the face (faces update every frame)
+        let started = Instant::now();
+        self.read_sensors(&self.needed_collectors()).await;
         let system_data = self.sample_sensors();
+        self.check_daily_random(&system_data);
+        self.check_rules(&system_data);
+        self.check_alerts(&system_data);
+        self.update_night_light();
+        self.update_dnd_schedule();
+        let sampled = Instant::now();
 
-        // Get theme from current preset
-        let theme = Theme::from_preset(&self.theme_name.read().unwrap());
+        let theme = *self.theme.read().unwrap();
 
         {
-            // Get canvas and render face
             let mut canvas = self.canvas.write().unwrap();
-            let face = self.face.read().unwrap();
-            let complications = self.complications.read().unwrap();
+            self.draw_face(&mut canvas, &system_data, &theme);
+        }
+        *self.last_system_data.write().unwrap() = Some(system_data);
+        let drawn = Instant::now();
+
+        self.present_canvas(false)?;
+        *self.last_frame.write().unwrap() = Some(Instant::now());
+        self.render_stats.lock().unwrap().record(FrameTimes {
+            sensors: sampled - started,
+            render: drawn - sampled,
+            present: drawn.elapsed(),
+        });
 
-            // Clear and render face
-            canvas.clear();
-            face.render(&mut canvas, &system_data, &theme, &complications);
+        // Handle LED updates (deferred while a pulse owns the LEDs)
+        let needs_led =
+            *self.needs_led_update.read().unwrap() && !*self.led_pulsing.read().unwrap();
+        if needs_led {
+            if let Err(e) = self.set_led(self.led_settings()).await {
+                tracing::warn!("LED update failed: {}", e);
+            }
+            *self.needs_led_update.write().unwrap() = false;
         }
 
-        // Render canvas to framebuffer with orientation transformation
-        {
-            let canvas = self.canvas.read().unwrap();
-            let mut framebuffer = self.framebuffer.write().unwrap();
-            let orientation = *self.orientation.read().unwrap();
+        Ok(())
+    }
 
-            // Transform canvas to framebuffer based on orientation
-            self.render_with_orientation(&canvas, &mut framebuffer, orientation)?;
+    /// Clears `canvas` and draws the current face and any banner on it.
+    fn draw_face(&self, canvas: &mut Canvas, system_data: &SystemData, theme: &Theme) {
+        let face = self.face.read().unwrap();
+        let complications = self.complications.read().unwrap();
+
+        // Complications moved to slots are drawn by the placement layer,
+        // followed by the overlays shown on every face
+        let hidden: Vec<&str> = self
+            .sensors
+            .lock()
+            .unwrap()
+            .disabled
+            .iter()
+            .flat_map(|collector| faces::collector_complications(collector))
+            .copied()
+            .collect();
+        let layout = placement::face_layout(&complications, face.name(), &hidden);
+        let mut badges =
+            placement::placed_badges(face.name(), system_data, &complications, &hidden);
+        badges.extend(overlays::badges(
+            &self.overlays.read().unwrap(),
+            system_data,
+            self.recorder.is_some(),
+            &hidden,
+        ));
+        let render = |canvas: &mut Canvas| {
+            face.render(canvas, system_data, theme, &layout);
+            placement::draw_badges(canvas, &badges, theme);
+        };
 
-            // Send to LCD
-            if let Some(ref lcd) = self.lcd {
-                let device = lcd.lock().unwrap();
-                device.redraw(&framebuffer)?;
+        canvas.clear();
+        // The skin lock is released before the face draws, so a face that
+        // panics can't poison it
+        match self.skin_for(face.name(), canvas.dimensions()) {
+            Some(skin) => {
+                canvas.draw_pixmap(0, 0, &skin.background);
+                canvas.draw_inset(skin.margin, render);
             }
+            None => render(canvas),
         }
 
-        // Handle LED updates
-        let needs_led = *self.needs_led_update.read().unwrap();
-        if needs_led {
-            let (theme, intensity, speed) = self.led_settings();
-            if let Err(e) = self.set_led(theme, intensity, speed).await {
-                tracing::warn!("LED update failed: {}", e);
+        // Alerts are held back during do-not-disturb; a requested speedtest
+        // still shows its progress
+        let banner = self.speedtest_banner(system_data).or_else(|| {
+            if self.dnd_active() {
+                None
+            } else {
+                Self::overlay_banner(face.name(), &complications, system_data)
             }
-            *self.needs_led_update.write().unwrap() = false;
+        });
+        if let Some(text) = banner {
+            draw_banner(canvas, &text, theme);
+        }
+    }
+
+    /// Returns the skin configured for `face`, loading it when the face,
+    /// its configuration or the canvas size changed. A skin that fails to
+    /// load is logged once and skipped until one of those changes.
+    fn skin_for(&self, face: &str, size: (u32, u32)) -> Option<Arc<Skin>> {
+        let mut loaded = self.skin.lock().unwrap();
+        let Some(config) = self.config.read().unwrap().skins.get(face).cloned() else {
+            *loaded = None;
+            return None;
+        };
+        let current = loaded
+            .as_ref()
+            .is_some_and(|l| l.face == face && l.config == config && l.size == size);
+        if !current {
+            let skin = Skin::load(
+                &config.image,
+                config.slice.map(Into::into),
+                config.margin.into(),
+                size.0,
+                size.1,
+            );
+            let skin = match skin {
+                Ok(skin) => Some(Arc::new(skin)),
+                Err(e) => {
+                    warn!("Failed to load skin for face {}: {:#}", face, e);
+                    None
+                }
+            };
+            *loaded = Some(LoadedSkin {
+                face: face.to_string(),
+                config,
+                size,
+                skin,
+            });
+        }
+        loaded.as_ref().and_then(|l| l.skin.clone())
+    }
+
+    /// Transforms the canvas into the framebuffer and sends it to the LCD.
+    /// Face frames (`is_final == false`) are dropped once shutdown has begun.
+    fn present_canvas(&self, is_final: bool) -> Result<()> {
+        let canvas = self.canvas.read().unwrap();
+        if !is_final && *self.shutting_down.read().unwrap() {
+            return Ok(());
+        }
+        let orientation = *self.orientation.read().unwrap();
+        self.present(&canvas, orientation, true)
+    }
+
+    /// Sends `canvas`, drawn for `orientation`, to the display. `effects`
+    /// applies the post effects, night light and brightness.
+    fn present(&self, canvas: &Canvas, orientation: Orientation, effects: bool) -> Result<()> {
+        *self.frame_number.write().unwrap() += 1;
+        let mut framebuffer = self.framebuffer.write().unwrap();
+
+        // Transform canvas to framebuffer based on orientation
+        self.render_with_orientation(canvas, &mut framebuffer, orientation)?;
+        if !*self.display_on.read().unwrap() {
+            framebuffer.clear(0);
+        } else if effects {
+            let mut effects = self.post_effects.read().unwrap().clone();
+            let night = *self.night_light_strength.read().unwrap();
+            if night > 0.0 {
+                effects.push(PostEffect::Warm(night));
+            }
+            apply_effects(&mut framebuffer, &effects, orientation.is_portrait());
+            dim(&mut framebuffer, *self.brightness.read().unwrap());
         }
+        // Mirroring follows how the panel is mounted, so applies to test
+        // patterns too
+        let (flip_h, flip_v) = self.transform();
+        mirror(
+            &mut framebuffer,
+            &self.visible_area(),
+            flip_h,
+            flip_v,
+            orientation.is_portrait(),
+        );
 
+        // Send to the display
+        if let Some(ref output) = self.output {
+            let device = output.lock().unwrap();
+            let result = device.redraw(&framebuffer);
+            self.set_lcd_responding(result.as_ref().err().map(|e| e.to_string()));
+            result.inspect_err(|_| self.count_usb_error())?;
+        }
         Ok(())
     }
 
-    /// Renders canvas to framebuffer with orientation transformation.
+    fn count_usb_error(&self) {
+        *self.usb_errors.write().unwrap() += 1;
+    }
+
+    /// Tracks whether frames reach the LCD, publishing an event on changes.
+    fn set_lcd_responding(&self, error: Option<String>) {
+        let ok = error.is_none();
+        *self.output_error.write().unwrap() = error.clone();
+        let mut responding = self.lcd_responding.write().unwrap();
+        if *responding == ok {
+            return;
+        }
+        *responding = ok;
+        self.events.publish(match error {
+            None => Event::DeviceReconnected,
+            Some(e) => Event::DeviceDisconnected(e),
+        });
+    }
+
+    /// Starts the test named `name`, a [`TestPattern`] or "all", pausing
+    /// face rendering. Returns its steps for [`Self::play_test_pattern`].
+    pub fn start_test_pattern(&self, name: &str) -> Result<Vec<(TestPattern, Orientation)>> {
+        let orientation = *self.orientation.read().unwrap();
+        let steps = test_steps(name, orientation).with_context(|| {
+            let names: Vec<&str> = TestPattern::ALL.iter().map(|p| p.name()).collect();
+            format!(
+                "Unknown test pattern: {}. Available: all, {}",
+                name,
+                names.join(", ")
+            )
+        })?;
+        let mut testing = self.testing.write().unwrap();
+        if *testing {
+            anyhow::bail!("A test pattern is already running");
+        }
+        *testing = true;
+        info!("Showing test pattern: {}", name);
+        Ok(steps)
+    }
+
+    /// Returns how long the test with `steps` runs.
+    pub fn test_pattern_duration(steps: &[(TestPattern, Orientation)]) -> Duration {
+        TEST_PATTERN_STEP * steps.len() as u32
+    }
+
+    /// Shows each step of a started test pattern, without post effects or
+    /// dimming so colors are shown as drawn, then resumes the face.
+    pub async fn play_test_pattern(&self, steps: Vec<(TestPattern, Orientation)>) {
+        for (pattern, orientation) in steps {
+            let (width, height) = self.visible_area().canvas_size(orientation);
+            let mut canvas = Canvas::new(width, height);
+            render_test_pattern(&mut canvas, pattern, orientation);
+            if let Err(e) = self.present(&canvas, orientation, false) {
+                warn!("Failed to show test pattern: {}", e);
+            }
+            tokio::time::sleep(TEST_PATTERN_STEP).await;
+        }
+        *self.testing.write().unwrap() = false;
+        *self.needs_redraw.write().unwrap() = true;
+        info!("Test pattern finished");
+    }
+
+    /// Shows the boot splash with hostname and IP address.
+    /// Also takes the first sensor sample so rates are primed for the first face frame.
+    pub async fn show_splash(&self) -> Result<()> {
+        self.read_sensors(&COLLECTORS.iter().copied().collect())
+            .await;
+        let config = self.config.read().unwrap().splash.clone();
+        let system_data = self.sample_sensors();
+        let theme = *self.theme.read().unwrap();
+
+        {
+            let mut canvas = self.canvas.write().unwrap();
+            let logo = config.logo.as_deref().and_then(|path| {
+                let (w, h) = canvas.dimensions();
+                load_pixmap(path, w * 2 / 3, h / 2, false)
+                    .map_err(|e| warn!("Failed to load splash logo: {:#}", e))
+                    .ok()
+            });
+            render_splash(
+                &mut canvas,
+                logo.as_ref(),
+                &system_data.hostname,
+                system_data.display_ip.as_deref(),
+                &theme,
+            );
+        }
+
+        self.present_canvas(false)
+    }
+
+    /// Writes the network traffic totals to the state directory.
+    pub fn save_traffic_totals(&self) {
+        let traffic = self
+            .sensors
+            .lock()
+            .unwrap()
+            .traffic
+            .as_ref()
+            .map(Sampled::handle);
+        if let Some(traffic) = traffic {
+            traffic.with(|traffic| traffic.save());
+        }
+    }
+
+    /// Replaces the current frame with the shutdown screen.
+    /// No face frames are drawn after this is called.
+    pub fn show_shutdown_screen(&self) -> Result<()> {
+        *self.shutting_down.write().unwrap() = true;
+
+        let message = self.config.read().unwrap().splash.shutdown_message.clone();
+        let hostname = self
+            .display_label
+            .read()
+            .unwrap()
+            .clone()
+            .unwrap_or_else(|| self.sensors.lock().unwrap().system.hostname());
+        let theme = *self.theme.read().unwrap();
+
+        render_shutdown(
+            &mut self.canvas.write().unwrap(),
+            &message,
+            &hostname,
+            &theme,
+        );
+        self.present_canvas(true)
+    }
+
+    /// Shows a diagnostic frame after the render loop panicked.
+    ///
+    /// The panic may have happened while a lock was held, so poisoned locks
+    /// used by rendering are cleared first to let the loop be restarted.
+    pub fn show_panic_screen(&self, summary: &str) -> Result<()> {
+        self.recover_from_panic();
+
+        let timestamp = chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
+        render_panic(&mut self.canvas.write().unwrap(), summary, &timestamp);
+        self.present_canvas(false)
+    }
+
+    /// Clears lock poisoning left behind by a panicking render.
+    fn recover_from_panic(&self) {
+        self.canvas.clear_poison();
+        self.framebuffer.clear_poison();
+        self.face.clear_poison();
+        self.complications.clear_poison();
+        self.theme_name.clear_poison();
+        self.orientation.clear_poison();
+        self.needs_led_update.clear_poison();
+        self.sensors.clear_poison();
+        self.skin.clear_poison();
+        self.overlays.clear_poison();
+        self.config.clear_poison();
+        self.rules.clear_poison();
+        self.render_stats.clear_poison();
+        self.last_system_data.clear_poison();
+        self.frame_number.clear_poison();
+        self.screen_cache.clear_poison();
+        if let Some(ref output) = self.output {
+            output.clear_poison();
+        }
+    }
+
+    /// Renders canvas to framebuffer with orientation transformation,
+    /// placed in the visible area.
     fn render_with_orientation(
         &self,
         canvas: &Canvas,
@@ -622,231 +2245,1189 @@ impl AppState {
     ) -> Result<()> {
         use ht32_panel_hw::lcd::rgb888_to_rgb565;
 
+        let area = self.visible_area();
         let pixels = canvas.pixmap_pixels();
+        let fb_width = framebuffer.width() as u32;
         let fb_data = framebuffer.data_mut();
         let (cw, ch) = canvas.dimensions();
+        if (cw, ch) != area.canvas_size(orientation) {
+            // Resized since this frame was drawn; the next one will fit
+            debug!("Skipping a {}x{} frame drawn for another size", cw, ch);
+            return Ok(());
+        }
 
-        match orientation {
-            Orientation::Landscape => {
-                // Direct copy - canvas is 320x170, framebuffer is 320x170
-                for (i, pixel) in pixels.iter().enumerate() {
-                    if i < fb_data.len() {
-                        fb_data[i] = rgb888_to_rgb565(pixel.red(), pixel.green(), pixel.blue());
-                    }
-                }
+        if area == VisibleArea::FULL && orientation == Orientation::Landscape {
+            // Direct copy - canvas is 320x170, framebuffer is 320x170
+            for (dst, pixel) in fb_data.iter_mut().zip(pixels) {
+                *dst = rgb888_to_rgb565(pixel.red(), pixel.green(), pixel.blue());
             }
-            Orientation::LandscapeUpsideDown => {
-                // Copy reversed (180° rotation)
-                let len = fb_data.len();
-                for (i, pixel) in pixels.iter().enumerate() {
-                    if i < len {
-                        fb_data[len - 1 - i] =
-                            rgb888_to_rgb565(pixel.red(), pixel.green(), pixel.blue());
-                    }
-                }
-            }
-            Orientation::Portrait => {
-                // Canvas is 170x320, rotate 90° CW to get 320x170
-                // For each pixel at (x, y) in canvas, place at (ch - 1 - y, x) in framebuffer
-                for y in 0..ch {
-                    for x in 0..cw {
-                        let src_idx = (y * cw + x) as usize;
-                        let dst_x = ch - 1 - y;
-                        let dst_y = x;
-                        let dst_idx = (dst_y * 320 + dst_x) as usize;
-                        if src_idx < pixels.len() && dst_idx < fb_data.len() {
-                            let pixel = &pixels[src_idx];
-                            fb_data[dst_idx] =
-                                rgb888_to_rgb565(pixel.red(), pixel.green(), pixel.blue());
-                        }
-                    }
+            return Ok(());
+        }
+
+        // Hidden edges stay black
+        if area != VisibleArea::FULL {
+            fb_data.fill(0);
+        }
+        for y in 0..ch {
+            for x in 0..cw {
+                let src_idx = (y * cw + x) as usize;
+                let Some((dst_x, dst_y)) = area.panel_position(orientation, x, y) else {
+                    continue;
+                };
+                let dst_idx = (dst_y * fb_width + dst_x) as usize;
+                if src_idx < pixels.len() && dst_idx < fb_data.len() {
+                    let pixel = &pixels[src_idx];
+                    fb_data[dst_idx] = rgb888_to_rgb565(pixel.red(), pixel.green(), pixel.blue());
                 }
             }
-            Orientation::PortraitUpsideDown => {
-                // Canvas is 170x320, rotate 90° CCW to get 320x170
-                // For each pixel at (x, y) in canvas, place at (y, cw - 1 - x) in framebuffer
-                for y in 0..ch {
-                    for x in 0..cw {
-                        let src_idx = (y * cw + x) as usize;
-                        let dst_x = y;
-                        let dst_y = cw - 1 - x;
-                        let dst_idx = (dst_y * 320 + dst_x) as usize;
-                        if src_idx < pixels.len() && dst_idx < fb_data.len() {
-                            let pixel = &pixels[src_idx];
-                            fb_data[dst_idx] =
-                                rgb888_to_rgb565(pixel.red(), pixel.green(), pixel.blue());
-                        }
-                    }
-                }
+        }
+
+        Ok(())
+    }
+
+    /// Triggers a full redraw on the next frame.
+    pub fn force_redraw(&self) {
+        *self.needs_redraw.write().unwrap() = true;
+    }
+
+    /// Returns the current canvas encoded as `format`, with `quality` (1-100,
+    /// 0 for the default) for JPEG. This shows the logical orientation
+    /// (portrait/landscape) as seen by the user. Encodings are cached until
+    /// the next frame.
+    pub async fn get_screen_image(&self, format: ScreenFormat, quality: u8) -> Result<Vec<u8>> {
+        let (frame, width, height, pixels) = {
+            let canvas = self.canvas.read().unwrap();
+            let frame = *self.frame_number.read().unwrap();
+            if let Some(data) = self
+                .screen_cache
+                .lock()
+                .unwrap()
+                .get(frame, format, quality)
+            {
+                return Ok(data);
             }
+            let (width, height) = canvas.dimensions();
+            (frame, width, height, canvas.pixels().to_vec())
+        };
+        let data = tokio::task::spawn_blocking(move || {
+            encode_screen(format, quality, width, height, &pixels)
+        })
+        .await??;
+        self.screen_cache
+            .lock()
+            .unwrap()
+            .insert(frame, format, quality, data.clone());
+        Ok(data)
+    }
+
+    /// Renders the current face with theme `name` and returns it as PNG
+    /// bytes, without changing the display. Uses the sensor data of the last
+    /// frame so the preview matches what is on screen.
+    pub fn render_theme_preview(&self, name: &str) -> Result<Vec<u8>> {
+        let theme = resolve_theme(name, &self.custom_themes.read().unwrap())
+            .ok_or_else(|| anyhow::anyhow!("Unknown theme: {}", name))?;
+        let last_data = self.last_system_data.read().unwrap().clone();
+        let system_data = last_data.unwrap_or_else(|| self.sample_sensors());
+
+        let (width, height) = self.canvas.read().unwrap().dimensions();
+        let mut canvas = Canvas::new(width, height);
+        canvas.set_background(theme.background);
+        self.draw_face(&mut canvas, &system_data, &theme);
+        encode_png(width, height, canvas.pixels())
+    }
+
+    /// Returns a square PNG icon of `size` pixels cut from the middle of the
+    /// current frame, for macro keypads such as the Stream Deck.
+    pub fn get_icon_png(&self, size: u32) -> Result<Vec<u8>> {
+        let frame = {
+            let canvas = self.canvas.read().unwrap();
+            let (width, height) = canvas.dimensions();
+            image::RgbaImage::from_raw(width, height, canvas.pixels().to_vec())
+                .context("Canvas size does not match its pixel data")?
+        };
+        let side = frame.width().min(frame.height());
+        let square = image::imageops::crop_imm(
+            &frame,
+            (frame.width() - side) / 2,
+            (frame.height() - side) / 2,
+            side,
+            side,
+        )
+        .to_image();
+        let icon =
+            image::imageops::resize(&square, size, size, image::imageops::FilterType::Triangle);
+        encode_png(size, size, icon.as_raw())
+    }
+
+    /// Clears the display to a color.
+    pub fn clear_display(&self, color: u16) -> Result<()> {
+        {
+            let mut fb = self.framebuffer.write().unwrap();
+            fb.clear(color);
+        }
+        self.force_redraw();
+        Ok(())
+    }
+
+    /// Gets a mutable reference to the canvas.
+    pub fn with_canvas<F, R>(&self, f: F) -> R
+    where
+        F: FnOnce(&mut Canvas) -> R,
+    {
+        let mut canvas = self.canvas.write().unwrap();
+        let result = f(&mut canvas);
+        *self.needs_redraw.write().unwrap() = true;
+        result
+    }
+
+    /// Gets a read reference to the canvas.
+    pub fn read_canvas<F, R>(&self, f: F) -> R
+    where
+        F: FnOnce(&Canvas) -> R,
+    {
+        let canvas = self.canvas.read().unwrap();
+        f(&canvas)
+    }
+
+    /// Sets the display face.
+    pub fn set_face(&self, name: &str) -> Result<()> {
+        if name.eq_ignore_ascii_case("random") {
+            return self.shuffle_face();
+        }
+
+        self.switch_face(name)?;
+        self.save_display_settings();
+        Ok(())
+    }
+
+    /// Shows face `name` without saving it, as face switching rules do.
+    fn switch_face(&self, name: &str) -> Result<()> {
+        if let Some(new_face) = faces::create_face(name) {
+            // Initialize complications from defaults for this face
+            {
+                let mut complications = self.complications.write().unwrap();
+                complications.init_from_defaults(new_face.as_ref());
+            }
+            *self.face.write().unwrap() = new_face;
+            info!("Display face changed to: {}", name);
+            self.events.publish(Event::FaceChanged(name.to_string()));
+            Ok(())
+        } else {
+            Err(anyhow::anyhow!("Unknown face: {}", name))
+        }
+    }
+
+    /// Returns the path of the image drawn behind every face, if any.
+    pub fn background_image(&self) -> Option<String> {
+        self.background_image.read().unwrap().clone()
+    }
+
+    /// Sets the image drawn behind every face; None removes it.
+    pub fn set_background_image(&self, path: Option<&str>) -> Result<()> {
+        {
+            let mut canvas = self.canvas.write().unwrap();
+            match path {
+                Some(path) => {
+                    let (width, height) = canvas.dimensions();
+                    let image = load_pixmap(path, width, height, true)?;
+                    canvas.set_background_image(Some(image));
+                }
+                None => canvas.set_background_image(None),
+            }
+        }
+        *self.background_image.write().unwrap() = path.map(String::from);
+        self.save_display_settings();
+        *self.needs_redraw.write().unwrap() = true;
+        match path {
+            Some(path) => info!("Background image set to {}", path),
+            None => info!("Background image removed"),
+        }
+        Ok(())
+    }
+
+    /// Loads the background image at the canvas size, logging failures.
+    fn load_background_image(canvas: &mut Canvas, path: &str) {
+        let (width, height) = canvas.dimensions();
+        match load_pixmap(path, width, height, true) {
+            Ok(image) => canvas.set_background_image(Some(image)),
+            Err(e) => warn!("Failed to load background image: {:#}", e),
+        }
+    }
+
+    /// Returns the image shown on the image face, if one is configured.
+    pub fn image_face_source(&self) -> Option<String> {
+        self.complications
+            .read()
+            .unwrap()
+            .get_option("image", "settings", "path")
+            .filter(|path| !path.is_empty())
+            .cloned()
+    }
+
+    /// Shows `path` on the image face and switches to it.
+    pub fn set_image_face_source(&self, path: &str) -> Result<()> {
+        self.complications.write().unwrap().set_option(
+            "image",
+            "settings",
+            "path",
+            path.to_string(),
+        );
+        self.set_face("image")?;
+        *self.needs_redraw.write().unwrap() = true;
+        info!("Image face showing {}", path);
+        Ok(())
+    }
+
+    /// Stores an uploaded image in the state directory and returns its path.
+    /// Older uploads that are no longer used are removed.
+    pub fn store_uploaded_image(&self, filename: &str, data: &[u8]) -> Result<String> {
+        let dir = self.state_dir.join("images");
+        let path = uploads::store_image(&dir, filename, data)?;
+        let path = path.to_string_lossy().into_owned();
+
+        let mut in_use: Vec<String> = self.background_image().into_iter().collect();
+        in_use.extend(self.image_face_source());
+        in_use.push(path.clone());
+        uploads::prune(&dir, &in_use);
+        Ok(path)
+    }
+
+    /// Returns the canvas size in pixels for the current orientation.
+    pub fn canvas_size(&self) -> (u32, u32) {
+        self.canvas.read().unwrap().dimensions()
+    }
+
+    /// Returns the layout of the custom face.
+    pub fn custom_layout(&self) -> Layout {
+        self.complications
+            .read()
+            .unwrap()
+            .get_option(
+                custom::FACE_ID,
+                custom::LAYOUT_COMPLICATION,
+                custom::LAYOUT_OPTION,
+            )
+            .and_then(|json| Layout::from_json(json).ok())
+            .unwrap_or_default()
+    }
+
+    /// Checks a custom face layout against the current canvas size.
+    pub fn validate_custom_layout(&self, layout: &Layout) -> Vec<String> {
+        let (width, height) = self.canvas_size();
+        layout.validate(width, height)
+    }
+
+    /// Validates and saves the layout of the custom face.
+    pub fn set_custom_layout(&self, layout: &Layout) -> Result<()> {
+        let errors = self.validate_custom_layout(layout);
+        if !errors.is_empty() {
+            return Err(anyhow::anyhow!(errors.join("; ")));
+        }
+        self.complications.write().unwrap().set_option(
+            custom::FACE_ID,
+            custom::LAYOUT_COMPLICATION,
+            custom::LAYOUT_OPTION,
+            layout.to_json(),
+        );
+        self.save_display_settings();
+        *self.needs_redraw.write().unwrap() = true;
+        info!(
+            "Custom face layout saved ({} widgets)",
+            layout.widgets.len()
+        );
+        Ok(())
+    }
+
+    /// Switches to the face `step` places after the current one in the list
+    /// of available faces (negative steps go backwards), skipping the image
+    /// face. Returns the new face ID.
+    pub fn cycle_face(&self, step: i32) -> Result<String> {
+        let faces: Vec<_> = faces::available_faces()
+            .into_iter()
+            .map(|f| f.id)
+            .filter(|id| *id != "image")
+            .collect();
+        let next = cycle(&faces, &self.face_name(), step);
+        self.set_face(next)?;
+        Ok(next.to_string())
+    }
+
+    /// Switches to the theme `step` places after the current one. Returns
+    /// the new theme ID.
+    pub fn cycle_theme(&self, step: i32) -> Result<String> {
+        let themes: Vec<_> = faces::available_themes()
+            .into_iter()
+            .map(|t| t.id)
+            .collect();
+        let next = cycle(&themes, &self.theme_name(), step);
+        self.set_theme(next)?;
+        Ok(next.to_string())
+    }
+
+    /// Switches to a random face and theme, different from the current ones.
+    /// The image face is skipped since it needs a configured image.
+    pub fn shuffle_face(&self) -> Result<()> {
+        let current_face = self.face_name();
+        let current_theme = self.theme_name();
+
+        let faces: Vec<_> = faces::available_faces()
+            .into_iter()
+            .filter(|f| f.id != "image" && f.id != current_face)
+            .collect();
+        let themes: Vec<_> = faces::available_themes()
+            .into_iter()
+            .filter(|t| t.id != current_theme)
+            .collect();
+
+        if let Some(face) = faces.get(fastrand::usize(..faces.len().max(1))) {
+            self.set_face(face.id)?;
+        }
+        if let Some(theme) = themes.get(fastrand::usize(..themes.len().max(1))) {
+            self.set_theme(theme.id)?;
+        }
+        Ok(())
+    }
+
+    /// Gets the daily random switch time as "HH:MM", if enabled.
+    pub fn daily_random(&self) -> Option<String> {
+        self.daily_random
+            .read()
+            .unwrap()
+            .map(|t| t.format("%H:%M").to_string())
+    }
+
+    /// Sets the time of day ("HH:MM") to switch to a random face and theme.
+    /// An empty string or "off" disables the daily switch.
+    pub fn set_daily_random(&self, time: &str) -> Result<()> {
+        let time = time.trim();
+        let at = if time.is_empty() || time.eq_ignore_ascii_case("off") {
+            None
+        } else {
+            Some(
+                chrono::NaiveTime::parse_from_str(time, "%H:%M")
+                    .map_err(|_| anyhow::anyhow!("Invalid time (expected HH:MM): {}", time))?,
+            )
+        };
+
+        // Don't switch straight away if today's time has already passed
+        let (hour, minute, day, month, year, _, _) =
+            self.sensors.lock().unwrap().system.time_components();
+        let now = chrono::NaiveTime::from_hms_opt(hour as u32, minute as u32, 0);
+        let today = chrono::NaiveDate::from_ymd_opt(year as i32, month as u32, day as u32);
+        if let (Some(at), Some(now)) = (at, now) {
+            if now >= at {
+                *self.last_random.write().unwrap() = today;
+            }
+        }
+
+        *self.daily_random.write().unwrap() = at;
+        self.save_display_settings();
+        match at {
+            Some(at) => info!("Daily random face at {}", at.format("%H:%M")),
+            None => info!("Daily random face disabled"),
+        }
+        Ok(())
+    }
+
+    /// Updates the night light warmth for the current time.
+    fn update_night_light(&self) {
+        let Some(ref night_light) = self.night_light else {
+            return;
+        };
+        let now = self.sensors.lock().unwrap().system.now();
+        let strength = night_light.strength(now);
+        let mut current = self.night_light_strength.write().unwrap();
+        if (strength > 0.0) != (*current > 0.0) {
+            info!(
+                "Night light {}",
+                if strength > 0.0 { "starting" } else { "ended" }
+            );
+        }
+        *current = strength;
+    }
+
+    /// Updates whether the current time falls in a do-not-disturb window.
+    fn update_dnd_schedule(&self) {
+        let now = self.sensors.lock().unwrap().system.now();
+        let scheduled = self.dnd_schedule.is_active(now);
+        let mut current = self.dnd_scheduled.write().unwrap();
+        if scheduled != *current {
+            info!(
+                "Scheduled do-not-disturb {}",
+                if scheduled { "starting" } else { "ended" }
+            );
+        }
+        *current = scheduled;
+    }
+
+    /// Returns whether do-not-disturb was switched on by hand.
+    pub fn dnd(&self) -> bool {
+        *self.dnd.read().unwrap()
+    }
+
+    /// Switches do-not-disturb on or off by hand. The schedule still
+    /// applies while it is off.
+    pub fn set_dnd(&self, on: bool) {
+        *self.dnd.write().unwrap() = on;
+        *self.needs_redraw.write().unwrap() = true;
+        self.save_display_settings();
+        info!("Do-not-disturb {}", if on { "on" } else { "off" });
+    }
+
+    /// Returns whether alerts are held back, by hand or by the schedule.
+    pub fn dnd_active(&self) -> bool {
+        self.dnd() || *self.dnd_scheduled.read().unwrap()
+    }
+
+    /// Returns each built-in collector and whether it is switched on.
+    pub fn collectors(&self) -> Vec<(&'static str, bool)> {
+        let sensors = self.sensors.lock().unwrap();
+        COLLECTORS
+            .iter()
+            .map(|&name| (name, sensors.enabled(name)))
+            .collect()
+    }
+
+    /// Switches the built-in collector `name` on or off. Complications
+    /// showing its data are hidden while it is off.
+    pub fn set_sensor_enabled(&self, name: &str, enabled: bool) -> Result<()> {
+        if !COLLECTORS.contains(&name) {
+            anyhow::bail!(
+                "Unknown sensor: {}. Available: {}",
+                name,
+                COLLECTORS.join(", ")
+            );
+        }
+        self.sensor_overrides
+            .write()
+            .unwrap()
+            .insert(name.to_string(), enabled);
+        self.apply_sensor_overrides();
+        self.save_display_settings();
+        info!("Sensor {} {}", name, if enabled { "on" } else { "off" });
+        Ok(())
+    }
+
+    /// Switches the collectors as configured, with the D-Bus overrides.
+    fn apply_sensor_overrides(&self) {
+        let disabled = disabled_collectors(
+            &self.config.read().unwrap().sensors.collectors,
+            &self.sensor_overrides.read().unwrap(),
+        );
+        self.sensors.lock().unwrap().disabled = disabled;
+        *self.needs_redraw.write().unwrap() = true;
+    }
+
+    /// Switches to a random face and theme once the daily time is reached.
+    fn check_daily_random(&self, data: &SystemData) {
+        let Some(at) = *self.daily_random.read().unwrap() else {
+            return;
+        };
+        let Some(today) =
+            chrono::NaiveDate::from_ymd_opt(data.year as i32, data.month as u32, data.day as u32)
+        else {
+            return;
+        };
+        let now = chrono::NaiveTime::from_hms_opt(data.hour as u32, data.minute as u32, 0);
+        if now.is_none_or(|now| now < at) || *self.last_random.read().unwrap() == Some(today) {
+            return;
+        }
+
+        *self.last_random.write().unwrap() = Some(today);
+        match self.shuffle_face() {
+            Ok(()) => info!("Daily random: {} / {}", self.face_name(), self.theme_name()),
+            Err(e) => warn!("Daily random face switch failed: {}", e),
+        }
+    }
+
+    /// Switches faces as rules start and stop holding.
+    fn check_rules(&self, data: &SystemData) {
+        let face = self.face_name();
+        let switch = self
+            .rules
+            .lock()
+            .unwrap()
+            .evaluate(data, &face, Instant::now());
+        // Rules switch faces for a while; the saved face stays the chosen one
+        if let Some(target) = switch {
+            match self.switch_face(&target) {
+                Ok(()) => info!("Rule switched face from {} to {}", face, target),
+                Err(e) => warn!("Rule face switch to {} failed: {}", target, e),
+            }
+        }
+    }
+
+    /// Returns the face switching rules in effect.
+    pub fn rules(&self) -> Vec<RuleConfig> {
+        self.rules.lock().unwrap().rules().to_vec()
+    }
+
+    /// Returns whether the rules were set over D-Bus.
+    pub fn custom_rules(&self) -> bool {
+        *self.custom_rules.read().unwrap()
+    }
+
+    /// Replaces the face switching rules; None returns to the configured
+    /// rules. A face switched to by a rule in effect is switched back.
+    pub fn set_rules(&self, rules: Option<Vec<RuleConfig>>) -> Result<()> {
+        let custom = rules.is_some();
+        let rules = rules.unwrap_or_else(|| self.config.read().unwrap().rules.clone());
+        for rule in &rules {
+            validate_rule(rule)?;
+        }
+
+        let count = rules.len();
+        let restore = self
+            .rules
+            .lock()
+            .unwrap()
+            .set_rules(rules, &self.face_name());
+        *self.custom_rules.write().unwrap() = custom;
+        if let Some(face) = restore {
+            self.set_face(&face)?;
+        }
+        self.save_display_settings();
+        info!(
+            "{} face switching rules set{}",
+            count,
+            if custom { "" } else { " from the config" }
+        );
+        Ok(())
+    }
+
+    /// Gets the current face name.
+    pub fn face_name(&self) -> String {
+        self.face.read().unwrap().name().to_string()
+    }
+
+    /// Gets available complications for the current face.
+    pub fn available_complications(&self) -> Vec<faces::Complication> {
+        self.face.read().unwrap().available_complications()
+    }
+
+    /// Gets enabled complications for the current face.
+    pub fn enabled_complications(&self) -> std::collections::HashSet<String> {
+        let face_name = self.face.read().unwrap().name().to_string();
+        self.complications.read().unwrap().get_enabled(&face_name)
+    }
+
+    /// Sets whether a complication is enabled for the current face.
+    pub fn set_complication_enabled(&self, complication_id: &str, enabled: bool) -> Result<()> {
+        let face_name = self.face.read().unwrap().name().to_string();
+        let available: Vec<_> = self.face.read().unwrap().available_complications();
+
+        // Validate complication exists for this face
+        if !available.iter().any(|c| c.id == complication_id) {
+            return Err(anyhow::anyhow!(
+                "Unknown complication '{}' for face '{}'",
+                complication_id,
+                face_name
+            ));
+        }
+
+        self.complications
+            .write()
+            .unwrap()
+            .set_enabled(&face_name, complication_id, enabled);
+        self.save_display_settings();
+        *self.needs_redraw.write().unwrap() = true;
+
+        info!(
+            "Complication '{}' {} for face '{}'",
+            complication_id,
+            if enabled { "enabled" } else { "disabled" },
+            face_name
+        );
+        Ok(())
+    }
+
+    /// Gets the current theme name.
+    pub fn theme_name(&self) -> String {
+        self.theme_name.read().unwrap().clone()
+    }
+
+    /// Sets the theme by name, a preset or a saved theme. Discards colors
+    /// edited live.
+    pub fn set_theme(&self, name: &str) -> Result<()> {
+        if resolve_theme(name, &self.custom_themes.read().unwrap()).is_none() {
+            return Err(anyhow::anyhow!("Unknown theme: {}", name));
+        }
+        self.apply_theme(name);
+        Ok(())
+    }
+
+    /// Switches to theme `name` without checking that it exists, for
+    /// restoring saved settings. Unknown names get the default colors.
+    fn apply_theme(&self, name: &str) {
+        *self.theme_name.write().unwrap() = name.to_string();
+        let theme = resolve_theme(name, &self.custom_themes.read().unwrap())
+            .unwrap_or_else(|| Theme::from_preset(name));
+        self.show_theme(theme);
+        self.apply_theme_led();
+        self.save_display_settings();
+        info!("Theme set to: {}", name);
+    }
+
+    /// Draws with `theme` from the next frame.
+    fn show_theme(&self, theme: Theme) {
+        *self.theme.write().unwrap() = theme;
+        self.canvas
+            .write()
+            .unwrap()
+            .set_background(theme.background);
+        *self.needs_redraw.write().unwrap() = true;
+    }
+
+    /// Returns the current theme colors as "#RRGGBB" by name.
+    pub fn theme_colors(&self) -> BTreeMap<String, String> {
+        self.theme.read().unwrap().colors()
+    }
+
+    /// Returns the current theme colors as "name=#RRGGBB" pairs, for the
+    /// audit log.
+    pub fn describe_theme_colors(&self) -> String {
+        self.theme_colors()
+            .iter()
+            .map(|(name, hex)| format!("{}={}", name, hex))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// Changes some of the current theme colors ("primary", "secondary",
+    /// "text" or "background" to a color such as "#RRGGBB") for a live
+    /// preview. They are not saved, so they are lost on restart or theme
+    /// change unless saved with [`Self::save_current_theme`].
+    pub fn set_theme_colors(&self, colors: &HashMap<String, String>) -> Result<()> {
+        let mut theme = *self.theme.read().unwrap();
+        for (name, value) in colors {
+            theme
+                .set_color(name, value)
+                .map_err(|e| anyhow::anyhow!(e))?;
+        }
+        self.show_theme(theme);
+        Ok(())
+    }
+
+    /// Saves the current theme colors as theme `name` and switches to it.
+    /// Presets cannot be overwritten.
+    pub fn save_current_theme(&self, name: &str) -> Result<()> {
+        let name = custom_theme_name(name)?;
+        let colors = self.theme_colors();
+        self.custom_themes
+            .write()
+            .unwrap()
+            .insert(name.to_string(), colors);
+        self.apply_theme(name);
+        Ok(())
+    }
+
+    /// Deletes the saved theme `name`, switching to the default theme if
+    /// it is the current one. Presets cannot be deleted.
+    pub fn delete_theme(&self, name: &str) -> Result<()> {
+        if self.custom_themes.write().unwrap().remove(name).is_none() {
+            return Err(anyhow::anyhow!("No saved theme named {}", name));
+        }
+        if self.theme_name() == name {
+            self.apply_theme(&default_theme());
+        } else {
+            self.save_display_settings();
+        }
+        info!("Theme deleted: {}", name);
+        Ok(())
+    }
+
+    /// Returns the names of the themes saved from the theme editor.
+    pub fn custom_themes(&self) -> Vec<String> {
+        self.custom_themes.read().unwrap().keys().cloned().collect()
+    }
+
+    /// Returns whether the LEDs follow the display theme.
+    pub fn led_follow_theme(&self) -> bool {
+        *self.led_follow_theme.read().unwrap()
+    }
+
+    /// Enables or disables deriving the LED settings from the display theme.
+    pub fn set_led_follow_theme(&self, follow: bool) {
+        *self.led_follow_theme.write().unwrap() = follow;
+        self.apply_theme_led();
+        self.save_display_settings();
+        info!("LED follow theme: {}", if follow { "on" } else { "off" });
+    }
+
+    /// Queues the LED settings mapped to the current theme, if following it.
+    /// The render loop sends them to the hardware on its next frame.
+    fn apply_theme_led(&self) {
+        if !*self.led_follow_theme.read().unwrap() {
+            return;
+        }
+        let mapping = self
+            .config
+            .read()
+            .unwrap()
+            .led
+            .mapping_for(&self.theme_name.read().unwrap());
+        *self.led.write().unwrap() =
+            LedSettings::clamped(mapping.theme, mapping.intensity, mapping.speed);
+        *self.needs_led_update.write().unwrap() = true;
+    }
+
+    /// Returns a list of available themes.
+    pub fn available_themes(&self) -> Vec<faces::ThemeInfo> {
+        faces::available_themes()
+    }
+
+    /// Returns the presets followed by the saved themes.
+    pub fn theme_infos(&self) -> Vec<ThemeInfo> {
+        let presets = faces::available_themes().into_iter().map(|t| ThemeInfo {
+            id: t.id.to_string(),
+            display_name: t.display_name.to_string(),
+            custom: false,
+        });
+        let saved = self.custom_themes().into_iter().map(|name| ThemeInfo {
+            id: name.clone(),
+            display_name: name,
+            custom: true,
+        });
+        presets.chain(saved).collect()
+    }
+
+    /// Returns the current theme. A name restored from settings that is no
+    /// longer a theme is shown as it is.
+    pub fn theme_info(&self) -> ThemeInfo {
+        let name = self.theme_name();
+        self.theme_infos()
+            .into_iter()
+            .find(|t| t.id == name)
+            .unwrap_or_else(|| ThemeInfo {
+                display_name: name.clone(),
+                id: name,
+                custom: false,
+            })
+    }
+
+    /// Gets the current display settings as a struct.
+    pub fn display_settings(&self) -> DisplaySettings {
+        let led = self.led_settings();
+        let face = self.face_name();
+        let rule_face = self
+            .rules
+            .lock()
+            .unwrap()
+            .saved_face(&face)
+            .map(String::from);
+        DisplaySettings {
+            face: rule_face.unwrap_or(face),
+            orientation: if self.is_orientation_auto() {
+                "auto".to_string()
+            } else {
+                self.orientation.read().unwrap().to_string()
+            },
+            theme: self.theme_name.read().unwrap().clone(),
+            custom_themes: self.custom_themes.read().unwrap().clone(),
+            led_theme: led.theme(),
+            led_intensity: led.intensity(),
+            led_speed: led.speed(),
+            refresh_interval: *self.refresh_interval.read().unwrap(),
+            network_interface: None,
+            ip_display: None,
+            complications: self.complications.read().unwrap().clone(),
+            display_label: self.display_label.read().unwrap().clone(),
+            timezone: self.timezone(),
+            binary_units: self.rate_units.read().unwrap().binary,
+            network_bits: self.rate_units.read().unwrap().network_bits,
+            daily_random: self.daily_random(),
+            last_random: self
+                .last_random
+                .read()
+                .unwrap()
+                .map(|date| date.to_string()),
+            led_follow_theme: *self.led_follow_theme.read().unwrap(),
+            disk_devices: self.disk_devices.read().unwrap().clone(),
+            brightness: *self.brightness.read().unwrap(),
+            post_effects: self.post_effects(),
+            flip_h: self.transform().0,
+            flip_v: self.transform().1,
+            visible_area: self.visible_area(),
+            background_image: self.background_image(),
+            overlays: self.overlays(),
+            rules: self.custom_rules().then(|| self.rules()),
+            sensors: self.sensor_overrides.read().unwrap().clone(),
+            temperature_sensor: self.temperature_override.read().unwrap().clone(),
+            dnd: self.dnd(),
+        }
+    }
+
+    /// Reverts the last display settings change. Returns the settings that
+    /// changed, e.g. "theme, LEDs".
+    pub fn undo(&self) -> Result<String> {
+        let (from, target) = {
+            let mut history = self.history.lock().unwrap();
+            let from = history.current().clone();
+            let target = history
+                .begin_undo()
+                .ok_or_else(|| anyhow::anyhow!("Nothing to undo"))?;
+            (from, target)
+        };
+        let changes = self.restore_display_settings(&from, &target);
+        info!("Undid change to {}", changes);
+        Ok(changes)
+    }
+
+    /// Reapplies the last undone display settings change. Returns the
+    /// settings that changed.
+    pub fn redo(&self) -> Result<String> {
+        let (from, target) = {
+            let mut history = self.history.lock().unwrap();
+            let from = history.current().clone();
+            let target = history
+                .begin_redo()
+                .ok_or_else(|| anyhow::anyhow!("Nothing to redo"))?;
+            (from, target)
+        };
+        let changes = self.restore_display_settings(&from, &target);
+        info!("Redid change to {}", changes);
+        Ok(changes)
+    }
+
+    /// Applies `target` over the current settings `from` while undo
+    /// recording is paused, then resumes it. Settings that can no longer be
+    /// applied, such as a deleted background image, are skipped.
+    fn restore_display_settings(&self, from: &DisplaySettings, target: &DisplaySettings) -> String {
+        let skip = |what: &str, result: Result<()>| {
+            if let Err(e) = result {
+                warn!("Not restoring {}: {:#}", what, e);
+            }
+        };
+
+        if target.face != from.face {
+            skip("face", self.set_face(&target.face));
+        }
+        *self.complications.write().unwrap() = target.complications.clone();
+        if target.orientation != from.orientation {
+            match target.orientation.parse::<Orientation>() {
+                Ok(orientation) => skip("orientation", self.set_orientation(orientation)),
+                Err(_) if target.orientation == "auto" => {
+                    skip("orientation", self.set_orientation_auto().map(|_| ()))
+                }
+                Err(_) => warn!("Not restoring orientation {}", target.orientation),
+            }
+        }
+        *self.led_follow_theme.write().unwrap() = target.led_follow_theme;
+        *self.custom_themes.write().unwrap() = target.custom_themes.clone();
+        if target.theme != from.theme || target.custom_themes != from.custom_themes {
+            self.apply_theme(&target.theme);
+        }
+        // After the theme, which may have set the LEDs from its mapping
+        *self.led.write().unwrap() =
+            LedSettings::clamped(target.led_theme, target.led_intensity, target.led_speed);
+        *self.needs_led_update.write().unwrap() = true;
+
+        if target.refresh_interval != from.refresh_interval {
+            self.set_refresh_interval(target.refresh_interval);
+        }
+        if target.display_label != from.display_label {
+            self.set_display_label(target.display_label.as_deref().unwrap_or_default());
+        }
+        if target.timezone != from.timezone {
+            skip(
+                "timezone",
+                self.set_timezone(target.timezone.as_deref().unwrap_or_default()),
+            );
+        }
+        if (target.binary_units, target.network_bits) != (from.binary_units, from.network_bits) {
+            self.set_rate_units(RateUnits {
+                binary: target.binary_units,
+                network_bits: target.network_bits,
+            });
+        }
+        if target.daily_random != from.daily_random {
+            skip(
+                "daily random face",
+                self.set_daily_random(target.daily_random.as_deref().unwrap_or_default()),
+            );
+        }
+        if target.disk_devices != from.disk_devices {
+            skip(
+                "disk devices",
+                self.set_disk_devices(target.disk_devices.clone()),
+            );
+        }
+        if target.brightness != from.brightness {
+            self.set_brightness(target.brightness as i32);
+        }
+        if target.post_effects != from.post_effects {
+            skip("effects", self.set_post_effects(&target.post_effects));
+        }
+        if (target.flip_h, target.flip_v) != (from.flip_h, from.flip_v) {
+            self.set_transform(target.flip_h, target.flip_v);
+        }
+        if target.visible_area != from.visible_area {
+            self.set_visible_area(target.visible_area);
+        }
+        if target.background_image != from.background_image {
+            skip(
+                "background image",
+                self.set_background_image(target.background_image.as_deref()),
+            );
+        }
+        *self.overlays.write().unwrap() = target.overlays.clone();
+        if target.rules != from.rules {
+            skip("rules", self.set_rules(target.rules.clone()));
+        }
+        if target.sensors != from.sensors {
+            *self.sensor_overrides.write().unwrap() = target.sensors.clone();
+            self.apply_sensor_overrides();
+        }
+        if target.temperature_sensor != from.temperature_sensor {
+            skip(
+                "temperature sensor",
+                self.set_temperature_sensor(target.temperature_sensor.clone()),
+            );
         }
+        if target.dnd != from.dnd {
+            self.set_dnd(target.dnd);
+        }
+        *self.needs_redraw.write().unwrap() = true;
+        self.save_display_settings();
 
-        Ok(())
+        let applied = self.display_settings();
+        let mut history = self.history.lock().unwrap();
+        history.finish(&applied);
+        let (undo, redo) = history.depth();
+        debug!("Settings history: {} to undo, {} to redo", undo, redo);
+        history::describe_changes(from, &applied)
     }
 
-    /// Triggers a full redraw on the next frame.
-    pub fn force_redraw(&self) {
-        *self.needs_redraw.write().unwrap() = true;
+    /// Returns frame time percentiles of recent frames.
+    pub fn render_stats(&self) -> (usize, Vec<StageStats>) {
+        let stats = self.render_stats.lock().unwrap();
+        (stats.frames(), stats.summary())
     }
 
-    /// Returns the current canvas as PNG bytes.
-    /// This shows the logical orientation (portrait/landscape) as seen by the user.
-    pub fn get_screen_png(&self) -> Result<Vec<u8>> {
-        let canvas = self.canvas.read().unwrap();
-        let (width, height) = canvas.dimensions();
-        let rgba = canvas.pixels();
+    /// Returns the health of the daemon.
+    pub fn health(&self) -> Health {
+        let mut problems = Vec::new();
+        let lcd_responding = *self.lcd_responding.read().unwrap();
+        if self.output.is_some() && !lcd_responding {
+            problems.push("LCD is not responding".to_string());
+        }
 
-        let mut png_data = Vec::new();
-        {
-            let mut encoder = png::Encoder::new(&mut png_data, width, height);
-            encoder.set_color(png::ColorType::Rgba);
-            encoder.set_depth(png::BitDepth::Eight);
-            let mut writer = encoder.write_header()?;
-            writer.write_image_data(rgba)?;
+        // Frames may be slow, but several missed intervals mean a stuck loop
+        let last_frame = *self.last_frame.read().unwrap();
+        let max_age = (Duration::from_millis(self.refresh_interval_ms() as u64) * 3)
+            .max(Duration::from_secs(10));
+        let frame_age = last_frame.unwrap_or(self.started).elapsed();
+        // Face frames pause while a test pattern is shown
+        let testing = *self.testing.read().unwrap();
+        if frame_age > max_age && !*self.shutting_down.read().unwrap() && !testing {
+            problems.push(format!("No frame rendered for {}s", frame_age.as_secs()));
         }
 
-        Ok(png_data)
-    }
+        let reads = self.sensors.lock().unwrap().read_health();
+        for read in reads.iter().filter(|r| r.stalled) {
+            problems.push(format!("{} sensor read has stalled", read.name));
+        }
 
-    /// Clears the display to a color.
-    pub fn clear_display(&self, color: u16) -> Result<()> {
-        {
-            let mut fb = self.framebuffer.write().unwrap();
-            fb.clear(color);
+        Health {
+            status: health::status(&problems, testing),
+            problems,
+            version: env!("CARGO_PKG_VERSION"),
+            capabilities: health::capabilities(),
+            uptime_secs: self.started.elapsed().as_secs(),
+            frames_rendered: *self.frame_number.read().unwrap(),
+            last_frame_secs: last_frame.map(|t| t.elapsed().as_secs_f64()),
+            usb_errors: *self.usb_errors.read().unwrap(),
+            sensor_failures: reads.iter().map(|r| r.failures).sum(),
+            memory_bytes: health::resident_memory(),
+            lcd_connected: self.output.is_some(),
+            lcd_responding,
         }
-        self.force_redraw();
-        Ok(())
     }
 
-    /// Gets a mutable reference to the canvas.
-    pub fn with_canvas<F, R>(&self, f: F) -> R
-    where
-        F: FnOnce(&mut Canvas) -> R,
-    {
-        let mut canvas = self.canvas.write().unwrap();
-        let result = f(&mut canvas);
-        *self.needs_redraw.write().unwrap() = true;
-        result
+    /// Returns the log of changes made over D-Bus and HTTP.
+    pub fn audit_log(&self) -> &AuditLog {
+        &self.audit
     }
 
-    /// Gets a read reference to the canvas.
-    pub fn read_canvas<F, R>(&self, f: F) -> R
-    where
-        F: FnOnce(&Canvas) -> R,
-    {
-        let canvas = self.canvas.read().unwrap();
-        f(&canvas)
+    /// Returns the event bus.
+    pub fn events(&self) -> &EventBus {
+        &self.events
     }
 
-    /// Sets the display face.
-    pub fn set_face(&self, name: &str) -> Result<()> {
-        if let Some(new_face) = faces::create_face(name) {
-            // Initialize complications from defaults for this face
-            {
-                let mut complications = self.complications.write().unwrap();
-                complications.init_from_defaults(new_face.as_ref());
-            }
-            *self.face.write().unwrap() = new_face;
-            self.save_display_settings();
-            info!("Display face changed to: {}", name);
-            Ok(())
-        } else {
-            Err(anyhow::anyhow!("Unknown face: {}", name))
-        }
+    /// Returns the timeline of recent notable events.
+    pub fn timeline(&self) -> Arc<Timeline> {
+        self.timeline.clone()
     }
 
-    /// Gets the current face name.
-    pub fn face_name(&self) -> String {
-        self.face.read().unwrap().name().to_string()
+    /// Returns the system hostname.
+    pub fn hostname(&self) -> String {
+        self.sensors.lock().unwrap().system.hostname()
     }
 
-    /// Gets available complications for the current face.
-    pub fn available_complications(&self) -> Vec<faces::Complication> {
-        self.face.read().unwrap().available_complications()
+    /// Gets the label shown instead of the hostname, if any.
+    pub fn display_label(&self) -> Option<String> {
+        self.display_label.read().unwrap().clone()
     }
 
-    /// Gets enabled complications for the current face.
-    pub fn enabled_complications(&self) -> std::collections::HashSet<String> {
-        let face_name = self.face.read().unwrap().name().to_string();
-        self.complications.read().unwrap().get_enabled(&face_name)
+    /// Sets the label shown instead of the hostname.
+    /// An empty label restores the hostname.
+    pub fn set_display_label(&self, label: &str) {
+        let label = label.trim();
+        let value = (!label.is_empty()).then(|| label.to_string());
+        *self.display_label.write().unwrap() = value;
+        *self.needs_redraw.write().unwrap() = true;
+        self.save_display_settings();
+        if label.is_empty() {
+            info!("Display label cleared, showing hostname");
+        } else {
+            info!("Display label set to: {}", label);
+        }
     }
 
-    /// Sets whether a complication is enabled for the current face.
-    pub fn set_complication_enabled(&self, complication_id: &str, enabled: bool) -> Result<()> {
-        let face_name = self.face.read().unwrap().name().to_string();
-        let available: Vec<_> = self.face.read().unwrap().available_complications();
+    /// Gets the timezone override name, if any.
+    pub fn timezone(&self) -> Option<String> {
+        let sensors = self.sensors.lock().unwrap();
+        sensors.system.timezone().map(|tz| tz.name().to_string())
+    }
 
-        // Validate complication exists for this face
-        if !available.iter().any(|c| c.id == complication_id) {
-            return Err(anyhow::anyhow!(
-                "Unknown complication '{}' for face '{}'",
-                complication_id,
-                face_name
-            ));
-        }
+    /// Sets the timezone used on faces from an IANA name (e.g. "America/New_York").
+    /// An empty name or "local" restores the system timezone.
+    pub fn set_timezone(&self, name: &str) -> Result<()> {
+        let name = name.trim();
+        let timezone = if name.is_empty() || name.eq_ignore_ascii_case("local") {
+            None
+        } else {
+            Some(
+                name.parse::<chrono_tz::Tz>()
+                    .map_err(|_| anyhow::anyhow!("Unknown timezone: {}", name))?,
+            )
+        };
 
-        self.complications
-            .write()
-            .unwrap()
-            .set_enabled(&face_name, complication_id, enabled);
-        self.save_display_settings();
+        self.sensors.lock().unwrap().system.set_timezone(timezone);
         *self.needs_redraw.write().unwrap() = true;
+        self.save_display_settings();
+        match timezone {
+            Some(tz) => info!("Timezone set to: {}", tz),
+            None => info!("Timezone set to system default"),
+        }
+        Ok(())
+    }
+
+    /// Gets the units used for disk and network rates.
+    pub fn rate_units(&self) -> RateUnits {
+        *self.rate_units.read().unwrap()
+    }
 
+    /// Sets the units used for disk and network rates.
+    pub fn set_rate_units(&self, units: RateUnits) {
+        *self.rate_units.write().unwrap() = units;
+        *self.needs_redraw.write().unwrap() = true;
+        self.save_display_settings();
         info!(
-            "Complication '{}' {} for face '{}'",
-            complication_id,
-            if enabled { "enabled" } else { "disabled" },
-            face_name
+            "Rate units set to: {}, network in {}",
+            if units.binary { "binary" } else { "decimal" },
+            if units.network_bits { "bits" } else { "bytes" }
         );
-        Ok(())
     }
 
-    /// Gets the current theme name.
-    pub fn theme_name(&self) -> String {
-        self.theme_name.read().unwrap().clone()
+    /// Gets the configured disk devices (empty when auto-detecting).
+    pub fn disk_devices(&self) -> Vec<String> {
+        self.disk_devices.read().unwrap().clone()
     }
 
-    /// Sets the theme by name.
-    pub fn set_theme(&self, name: &str) -> Result<()> {
-        // Validate theme exists
-        if !faces::available_themes().iter().any(|t| t.id == name) {
-            return Err(anyhow::anyhow!("Unknown theme: {}", name));
+    /// Sets the block devices tracked by the disk sensor.
+    /// An empty list returns to auto-detecting the primary disk.
+    pub fn set_disk_devices(&self, devices: Vec<String>) -> Result<()> {
+        let available = DiskSensor::list_devices();
+        if let Some(unknown) = devices.iter().find(|d| !available.contains(d)) {
+            anyhow::bail!(
+                "Unknown disk device: {}. Available: {}",
+                unknown,
+                available.join(", ")
+            );
         }
-
-        *self.theme_name.write().unwrap() = name.to_string();
-
-        // Update canvas background
-        let theme = Theme::from_preset(name);
-        self.canvas
-            .write()
-            .unwrap()
-            .set_background(theme.background);
-
+        let disk = self.sensors.lock().unwrap().disk.handle();
+        disk.with(|disk| disk.set_devices(&devices));
+        *self.disk_devices.write().unwrap() = devices.clone();
         *self.needs_redraw.write().unwrap() = true;
         self.save_display_settings();
-        info!("Theme set to: {}", name);
+        if devices.is_empty() {
+            info!("Disk devices set to auto-detect");
+        } else {
+            info!("Disk devices set to: {}", devices.join(", "));
+        }
         Ok(())
     }
 
-    /// Returns a list of available themes.
-    pub fn available_themes(&self) -> Vec<faces::ThemeInfo> {
-        faces::available_themes()
+    /// Lists block devices the disk sensor can track.
+    pub fn list_disk_devices(&self) -> Vec<String> {
+        DiskSensor::list_devices()
     }
 
-    /// Gets the current display settings as a struct.
-    pub fn display_settings(&self) -> DisplaySettings {
-        DisplaySettings {
-            face: self.face.read().unwrap().name().to_string(),
-            orientation: self.orientation.read().unwrap().to_string(),
-            theme: self.theme_name.read().unwrap().clone(),
-            led_theme: *self.led_theme.read().unwrap(),
-            led_intensity: *self.led_intensity.read().unwrap(),
-            led_speed: *self.led_speed.read().unwrap(),
-            refresh_interval: *self.refresh_interval.read().unwrap(),
-            network_interface: None,
-            ip_display: None,
-            complications: self.complications.read().unwrap().clone(),
+    /// Returns the selected CPU temperature input, from D-Bus or the
+    /// config. None when auto-detecting.
+    pub fn temperature_sensor(&self) -> Option<String> {
+        temperature_selector(
+            &self.config.read().unwrap(),
+            &self.temperature_override.read().unwrap(),
+        )
+    }
+
+    /// Selects the CPU temperature input by path, label or chip name.
+    /// None or an empty selector returns to the configured input.
+    pub fn set_temperature_sensor(&self, selector: Option<String>) -> Result<()> {
+        let selector = selector.filter(|s| !s.is_empty());
+        if let Some(ref selector) = selector {
+            if TemperatureSensor::resolve(selector).is_none() {
+                let labels: Vec<String> = TemperatureSensor::candidates()
+                    .into_iter()
+                    .map(|candidate| candidate.label)
+                    .collect();
+                anyhow::bail!(
+                    "Unknown temperature sensor: {}. Available: {}",
+                    selector,
+                    labels.join(", ")
+                );
+            }
         }
+        *self.temperature_override.write().unwrap() = selector;
+
+        let selected = self.temperature_sensor();
+        let temperature = self.sensors.lock().unwrap().temperature.handle();
+        temperature.with(|temp| temp.select(selected.as_deref()));
+        *self.needs_redraw.write().unwrap() = true;
+        self.save_display_settings();
+        info!(
+            "Temperature sensor set to: {}",
+            selected.as_deref().unwrap_or("auto")
+        );
+        Ok(())
+    }
+
+    /// Lists the readable temperature inputs with their current reading
+    /// and whether each is the one being read. The input being read shows
+    /// its last sample, so this never waits for a read in progress.
+    pub fn list_temperature_sensors(&self) -> Vec<(TemperatureCandidate, f64, bool)> {
+        let sampled = self
+            .sensors
+            .lock()
+            .unwrap()
+            .temperature
+            .latest()
+            .temperature;
+        let current = TemperatureSensor::selected_path(self.temperature_sensor().as_deref());
+        TemperatureSensor::candidates()
+            .into_iter()
+            .filter_map(|candidate| {
+                let active = current.as_ref() == Some(&candidate.path);
+                let celsius = if active {
+                    sampled.or_else(|| candidate.read())?
+                } else {
+                    candidate.read()?
+                };
+                Some((candidate, celsius, active))
+            })
+            .collect()
     }
 
     /// Gets the current IP display preference from complications.
@@ -864,6 +3445,7 @@ impl AppState {
             preference.to_string(),
         );
         self.save_display_settings();
+        *self.needs_redraw.write().unwrap() = true;
         info!("IP display preference set to: {}", preference);
     }
 
@@ -874,8 +3456,8 @@ impl AppState {
 
     /// Gets the currently active network interface name (resolved from auto if needed).
     pub fn network_interface_config(&self) -> String {
-        let sensors = self.sensors.lock().unwrap();
-        sensors.network.interface_name().to_string()
+        let network = self.sensors.lock().unwrap().network.handle();
+        network.with(|network| network.interface_name().to_string())
     }
 
     /// Sets the network interface to monitor via complication option.
@@ -891,14 +3473,137 @@ impl AppState {
         );
 
         // Update the sensor
-        let mut sensors = self.sensors.lock().unwrap();
+        let network = self.sensors.lock().unwrap().network.handle();
         if value == "auto" || value.is_empty() {
-            sensors.network.set_auto();
+            network.with(|network| network.set_auto());
+        } else {
+            network.with(|network| network.set_interface(&value));
+        }
+
+        self.save_display_settings();
+    }
+
+    /// Gets where a complication of the current face is placed; None if
+    /// the face lays it out itself.
+    pub fn complication_placement(&self, complication_id: &str) -> Option<Placement> {
+        let face_name = self.face.read().unwrap().name().to_string();
+        self.complications
+            .read()
+            .unwrap()
+            .placement(&face_name, complication_id)
+    }
+
+    /// Moves a complication of the current face to a slot ("top-left",
+    /// "top-right", "bottom-left", "bottom-right" or "bottom-bar") with a
+    /// size ("small" or "large"). An empty slot returns it to the face's
+    /// own layout.
+    pub fn set_complication_placement(
+        &self,
+        complication_id: &str,
+        slot: &str,
+        size: &str,
+    ) -> Result<()> {
+        let face_name = self.face.read().unwrap().name().to_string();
+        let available = self.face.read().unwrap().available_complications();
+        if !available.iter().any(|c| c.id == complication_id) {
+            return Err(anyhow::anyhow!(
+                "Unknown complication '{}' for face '{}'",
+                complication_id,
+                face_name
+            ));
+        }
+        let placement = if slot.is_empty() {
+            None
         } else {
-            sensors.network.set_interface(&value);
+            if !placement::PLACEABLE.contains(&complication_id) {
+                return Err(anyhow::anyhow!(
+                    "Complication '{}' cannot be moved; movable ones are {}",
+                    complication_id,
+                    placement::PLACEABLE.join(", ")
+                ));
+            }
+            Some(parse_placement(slot, size)?)
+        };
+
+        self.complications
+            .write()
+            .unwrap()
+            .set_placement(&face_name, complication_id, placement);
+        self.save_display_settings();
+        *self.needs_redraw.write().unwrap() = true;
+
+        match placement {
+            Some(p) => info!(
+                "Complication '{}' on face '{}' moved to {} ({})",
+                complication_id,
+                face_name,
+                p.slot.name(),
+                p.size.name()
+            ),
+            None => info!(
+                "Complication '{}' on face '{}' returned to the face layout",
+                complication_id, face_name
+            ),
+        }
+        Ok(())
+    }
+
+    /// Returns the overlays drawn on top of every face.
+    pub fn overlays(&self) -> Vec<Overlay> {
+        self.overlays.read().unwrap().clone()
+    }
+
+    /// Shows overlay `id` in a slot with a size, see
+    /// [`Self::set_complication_placement`]. An overlay that is already
+    /// shown is moved.
+    pub fn set_overlay(&self, id: &str, slot: &str, size: &str) -> Result<()> {
+        let available = overlays::available();
+        if !available.contains(&id) {
+            return Err(anyhow::anyhow!(
+                "Unknown overlay '{}', expected one of {}",
+                id,
+                available.join(", ")
+            ));
         }
+        let placement = parse_placement(slot, size)?;
+        let overlay = Overlay {
+            id: id.to_string(),
+            slot: placement.slot,
+            size: placement.size,
+        };
 
+        {
+            let mut overlays = self.overlays.write().unwrap();
+            match overlays.iter_mut().find(|o| o.id == id) {
+                Some(existing) => *existing = overlay,
+                None => overlays.push(overlay),
+            }
+        }
         self.save_display_settings();
+        *self.needs_redraw.write().unwrap() = true;
+        info!(
+            "Overlay '{}' shown at {} ({})",
+            id,
+            placement.slot.name(),
+            placement.size.name()
+        );
+        Ok(())
+    }
+
+    /// Hides overlay `id`. Returns false if it was not shown.
+    pub fn remove_overlay(&self, id: &str) -> bool {
+        let removed = {
+            let mut overlays = self.overlays.write().unwrap();
+            let before = overlays.len();
+            overlays.retain(|o| o.id != id);
+            overlays.len() != before
+        };
+        if removed {
+            self.save_display_settings();
+            *self.needs_redraw.write().unwrap() = true;
+            info!("Overlay '{}' removed", id);
+        }
+        removed
     }
 
     /// Lists all available network interfaces.
@@ -993,11 +3698,11 @@ impl AppState {
         if complication_id == faces::complication_names::NETWORK
             && option_id == faces::complication_options::INTERFACE
         {
-            let mut sensors = self.sensors.lock().unwrap();
+            let network = self.sensors.lock().unwrap().network.handle();
             if value == "auto" || value.is_empty() {
-                sensors.network.set_auto();
+                network.with(|network| network.set_auto());
             } else {
-                sensors.network.set_interface(value);
+                network.with(|network| network.set_interface(value));
             }
         }
 
@@ -1010,3 +3715,257 @@ impl AppState {
         Ok(())
     }
 }
+
+/// Checks that `rule` names a known face and has a limit.
+fn validate_rule(rule: &RuleConfig) -> Result<()> {
+    if !faces::available_faces().iter().any(|f| f.id == rule.face) {
+        anyhow::bail!("Unknown face '{}' in rule for {}", rule.face, rule.when);
+    }
+    if rule.above.is_none() && rule.below.is_none() {
+        anyhow::bail!("Rule for {} needs an above or below limit", rule.when);
+    }
+    Ok(())
+}
+
+/// Returns the built-in collectors switched off by `config` or by
+/// `overrides`, which take precedence.
+fn disabled_collectors(
+    config: &CollectorsConfig,
+    overrides: &BTreeMap<String, bool>,
+) -> BTreeSet<&'static str> {
+    COLLECTORS
+        .iter()
+        .copied()
+        .filter(|&name| {
+            !overrides
+                .get(name)
+                .copied()
+                .unwrap_or_else(|| config.is_enabled(name))
+        })
+        .collect()
+}
+
+/// Returns the orientation hinted by the host, or `configured` (landscape
+/// if that is "auto" too) without a hint.
+fn detect_orientation(configured: &str) -> Orientation {
+    auto_orientation::detect().unwrap_or_else(|| {
+        info!("No orientation hint found, using the configured orientation");
+        configured.parse().unwrap_or_default()
+    })
+}
+
+/// Returns the CPU temperature input selected by `override_selector` or,
+/// failing that, by `config`. None when auto-detecting.
+fn temperature_selector(config: &Config, override_selector: &Option<String>) -> Option<String> {
+    override_selector
+        .clone()
+        .or_else(|| Some(config.sensors.temperature.sensor.clone()))
+        .filter(|selector| !selector.is_empty())
+}
+
+/// Parses a slot name ("top-left", "top-right", "bottom-left",
+/// "bottom-right" or "bottom-bar") and a size name ("small" or "large").
+fn parse_placement(slot: &str, size: &str) -> Result<Placement> {
+    let slot = Slot::from_name(slot).ok_or_else(|| {
+        let slots: Vec<&str> = Slot::ALL.iter().map(|s| s.name()).collect();
+        anyhow::anyhow!(
+            "Unknown slot '{}', expected one of {}",
+            slot,
+            slots.join(", ")
+        )
+    })?;
+    let size = BadgeSize::from_name(size)
+        .ok_or_else(|| anyhow::anyhow!("Unknown size '{}', expected small or large", size))?;
+    Ok(Placement { slot, size })
+}
+
+/// Returns the colors of theme `name`, a theme saved in `custom` or a
+/// preset, or None if there is no such theme.
+fn resolve_theme(name: &str, custom: &BTreeMap<String, BTreeMap<String, String>>) -> Option<Theme> {
+    if let Some(colors) = custom.get(name) {
+        let mut theme = Theme::default();
+        for (color, hex) in colors {
+            if let Err(e) = theme.set_color(color, hex) {
+                warn!("Theme {}: {}", name, e);
+            }
+        }
+        return Some(theme);
+    }
+    faces::available_themes()
+        .iter()
+        .any(|t| t.id == name)
+        .then(|| Theme::from_preset(name))
+}
+
+/// Returns `name` without surrounding whitespace if it can name a saved
+/// theme: letters, digits, '-' and '_', and not the name of a preset.
+fn custom_theme_name(name: &str) -> Result<&str> {
+    let name = name.trim();
+    if name.is_empty()
+        || !name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(anyhow::anyhow!(
+            "Theme names may only contain letters, digits, '-' and '_'"
+        ));
+    }
+    if faces::available_themes().iter().any(|t| t.id == name) {
+        return Err(anyhow::anyhow!("Cannot overwrite the {} preset", name));
+    }
+    Ok(name)
+}
+
+/// Replaces `active` with the alert keys in `current`, returning the
+/// messages of alerts that were not already active.
+fn raise_alerts(active: &mut BTreeSet<String>, current: BTreeMap<String, String>) -> Vec<String> {
+    let raised = current
+        .iter()
+        .filter(|(key, _)| !active.contains(*key))
+        .map(|(_, message)| message.clone())
+        .collect();
+    *active = current.into_keys().collect();
+    raised
+}
+
+/// Returns the LED effect and flash count to pulse for the alerts `raised`
+/// on one frame: a single pulse however many were raised, and none during
+/// do-not-disturb or with an unusable pulse configuration.
+fn alert_pulse(
+    raised: &[String],
+    config: Option<&AlertPulseConfig>,
+    dnd: bool,
+) -> Option<(LedTheme, u32)> {
+    let config = config?;
+    if raised.is_empty() || dnd {
+        return None;
+    }
+    match config.theme.parse() {
+        Ok(LedTheme::Off) | Err(_) => {
+            warn!("Ignoring alert pulse with LED effect '{}'", config.theme);
+            None
+        }
+        Ok(theme) => Some((theme, config.count.clamp(1, 20))),
+    }
+}
+
+/// Returns the entry `step` places after `current`, wrapping around. Starts
+/// from the first entry if `current` is not in the list.
+fn cycle<'a>(ids: &[&'a str], current: &str, step: i32) -> &'a str {
+    let len = ids.len() as i32;
+    match ids.iter().position(|id| *id == current) {
+        Some(i) => ids[(i as i32 + step).rem_euclid(len) as usize],
+        None => ids[0],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cycle() {
+        let ids = ["a", "b", "c"];
+        assert_eq!(cycle(&ids, "a", 1), "b");
+        assert_eq!(cycle(&ids, "c", 1), "a");
+        assert_eq!(cycle(&ids, "a", -1), "c");
+        assert_eq!(cycle(&ids, "b", -4), "a");
+        // An unknown current ID starts over at the first
+        assert_eq!(cycle(&ids, "z", 1), "a");
+    }
+
+    #[test]
+    fn test_disabled_collectors() {
+        let config: CollectorsConfig = toml::from_str("disk = false\nsessions = false").unwrap();
+        assert!(!config.is_enabled(collector_names::DISK));
+        assert!(config.is_enabled(collector_names::NETWORK));
+
+        // Overrides set over D-Bus take precedence over the config
+        let overrides = BTreeMap::from([
+            (collector_names::DISK.to_string(), true),
+            (collector_names::NETWORK.to_string(), false),
+        ]);
+        let disabled = disabled_collectors(&config, &overrides);
+        assert_eq!(
+            disabled,
+            BTreeSet::from([collector_names::NETWORK, collector_names::SESSIONS])
+        );
+    }
+
+    #[test]
+    fn test_alert_pulse() {
+        let pulse = AlertPulseConfig {
+            theme: "colors".to_string(),
+            count: 3,
+        };
+        let frames = [
+            BTreeMap::new(),
+            BTreeMap::from([
+                ("job:backup".to_string(), "Job backup failed".to_string()),
+                (
+                    "throttle".to_string(),
+                    "CPU is thermally throttled".to_string(),
+                ),
+            ]),
+            BTreeMap::from([("job:backup".to_string(), "Job backup failed".to_string())]),
+        ];
+        let mut active = BTreeSet::new();
+        let mut pulses = Vec::new();
+        for current in frames {
+            let raised = raise_alerts(&mut active, current);
+            pulses.extend(alert_pulse(&raised, Some(&pulse), false));
+        }
+        // Both alerts are raised on the same frame and stay raised
+        assert_eq!(pulses, [(LedTheme::Colors, 3)]);
+
+        let raised = vec!["Job backup failed".to_string()];
+        assert_eq!(alert_pulse(&raised, None, false), None);
+        assert_eq!(alert_pulse(&raised, Some(&pulse), true), None);
+    }
+
+    #[test]
+    fn test_dnd_setting() {
+        let settings = DisplaySettings {
+            dnd: true,
+            ..DisplaySettings::default()
+        };
+        let saved = toml::to_string_pretty(&settings).unwrap();
+        let loaded: DisplaySettings = toml::from_str(&saved).unwrap();
+        assert!(loaded.dnd);
+
+        // Settings saved before do-not-disturb was persisted
+        let loaded: DisplaySettings = toml::from_str("theme = \"nord\"").unwrap();
+        assert!(!loaded.dnd);
+    }
+
+    #[test]
+    fn test_resolve_theme() {
+        let custom = BTreeMap::from([(
+            "mine".to_string(),
+            BTreeMap::from([
+                ("primary".to_string(), "#112233".to_string()),
+                ("text".to_string(), "not a color".to_string()),
+            ]),
+        )]);
+        let theme = resolve_theme("mine", &custom).unwrap();
+        assert_eq!(theme.primary, 0x112233);
+        // Bad colors keep the default
+        assert_eq!(theme.text, Theme::default().text);
+
+        assert_eq!(
+            resolve_theme("hacker", &custom),
+            Some(Theme::from_preset("hacker"))
+        );
+        assert_eq!(resolve_theme("missing", &custom), None);
+    }
+
+    #[test]
+    fn test_custom_theme_name() {
+        assert_eq!(custom_theme_name(" my-theme_2 ").unwrap(), "my-theme_2");
+        assert!(custom_theme_name("").is_err());
+        assert!(custom_theme_name("   ").is_err());
+        assert!(custom_theme_name("../etc").is_err());
+        assert!(custom_theme_name("two words").is_err());
+        assert!(custom_theme_name("nord").is_err());
+    }
+}
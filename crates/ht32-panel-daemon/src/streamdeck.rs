@@ -0,0 +1,152 @@
+//! Stream Deck and macro button HTTP endpoint.
+//!
+//! A small JSON API on localhost for streamdeck-ui, the Elgato software's
+//! web request plugins and similar tools:
+//!
+//! - `GET /actions` lists the actions from [`crate::actions`]
+//! - `POST /actions/{id}?args=a,b` runs an action
+//! - `GET /icon.png?size=72` returns a square icon of the current frame
+
+use anyhow::{Context, Result};
+use axum::{
+    extract::{ConnectInfo, Path, Query, State},
+    http::{header, StatusCode},
+    middleware,
+    response::{IntoResponse, Response},
+    routing::{get, post},
+    Json, Router,
+};
+use serde::Deserialize;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use tokio::net::TcpListener;
+use tracing::{debug, error, info};
+
+use crate::actions;
+use crate::config::StreamDeckConfig;
+use crate::events::EventBus;
+use crate::state::AppState;
+use crate::web::{self, WebState};
+
+/// Default icon size (Stream Deck MK.2 keys are 72x72).
+const DEFAULT_ICON_SIZE: u32 = 72;
+
+/// Largest icon size served.
+const MAX_ICON_SIZE: u32 = 512;
+
+/// Creates the endpoint router.
+pub fn create_router(state: Arc<AppState>, events: EventBus) -> Router {
+    let web_state = WebState { app: state, events };
+
+    Router::new()
+        .route("/actions", get(actions_list))
+        .route("/actions/:id", post(action_invoke))
+        .route("/icon.png", get(icon_png))
+        .route_layer(middleware::from_fn_with_state(
+            web_state.clone(),
+            web::kiosk_guard,
+        ))
+        .with_state(web_state)
+}
+
+/// Binds the endpoint and serves it in the background.
+pub async fn serve(
+    config: &StreamDeckConfig,
+    state: Arc<AppState>,
+    events: EventBus,
+) -> Result<()> {
+    let app = create_router(state, events);
+    let addr: SocketAddr = config
+        .listen
+        .parse()
+        .context("Invalid Stream Deck listen address")?;
+    let listener = TcpListener::bind(addr).await?;
+    info!("Stream Deck endpoint listening on http://{}", addr);
+    tokio::spawn(async move {
+        let app = app.into_make_service_with_connect_info::<SocketAddr>();
+        if let Err(e) = axum::serve(listener, app).await {
+            error!("Stream Deck endpoint failed: {}", e);
+        }
+    });
+    Ok(())
+}
+
+/// GET /actions - Available actions as JSON
+pub(crate) async fn actions_list() -> impl IntoResponse {
+    let actions: Vec<_> = actions::available_actions()
+        .iter()
+        .map(|a| a.to_json())
+        .collect();
+    Json(actions)
+}
+
+/// Query parameters for running an action.
+#[derive(Deserialize)]
+pub(crate) struct ActionQuery {
+    /// Comma-separated positional arguments
+    #[serde(default)]
+    args: String,
+}
+
+/// POST /actions/{id} - Run an action
+pub(crate) async fn action_invoke(
+    State(state): State<WebState>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    Path(id): Path<String>,
+    Query(query): Query<ActionQuery>,
+) -> Response {
+    let args: Vec<String> = query
+        .args
+        .split(',')
+        .map(str::trim)
+        .filter(|a| !a.is_empty())
+        .map(String::from)
+        .collect();
+    match actions::invoke(&state.app, &id, &args).await {
+        Ok(outcome) => {
+            web::audit(
+                &state,
+                addr,
+                "InvokeAction",
+                "",
+                &actions::describe(&id, &args),
+            );
+            if let Some(event) = outcome.event {
+                state.events.publish(event);
+            }
+            debug!("Stream Deck: {} {:?}", id, args);
+            (StatusCode::OK, outcome.message).into_response()
+        }
+        Err(e) => (StatusCode::BAD_REQUEST, e.to_string()).into_response(),
+    }
+}
+
+/// Query parameters for the icon.
+#[derive(Deserialize)]
+struct IconQuery {
+    size: Option<u32>,
+}
+
+/// GET /icon.png - Square icon of the current frame
+async fn icon_png(State(state): State<WebState>, Query(query): Query<IconQuery>) -> Response {
+    let size = query
+        .size
+        .unwrap_or(DEFAULT_ICON_SIZE)
+        .clamp(16, MAX_ICON_SIZE);
+    match state.app.get_icon_png(size) {
+        Ok(png_data) => (
+            StatusCode::OK,
+            [
+                (header::CONTENT_TYPE, "image/png"),
+                (header::CACHE_CONTROL, "no-cache"),
+            ],
+            png_data,
+        )
+            .into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to generate icon: {}", e),
+        )
+            .into_response(),
+    }
+}
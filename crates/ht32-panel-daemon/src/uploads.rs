@@ -0,0 +1,154 @@
+//! Storage for images uploaded through the web UI.
+//!
+//! Uploads are checked to be a supported, decodable image within the size
+//! limits, then written to the `images` directory under the state directory
+//! with a sanitized name. Older uploads are pruned so the directory does not
+//! grow without bound.
+
+use std::fs;
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use image::{ImageFormat, ImageReader};
+use tracing::{debug, info};
+
+/// Largest accepted upload in bytes.
+pub const MAX_UPLOAD_BYTES: usize = 10 * 1024 * 1024;
+
+/// Largest accepted image width or height in pixels.
+const MAX_DIMENSION: u32 = 8192;
+
+/// Uploads kept in the images directory, besides those in use.
+const MAX_STORED: usize = 16;
+
+/// Formats accepted for upload.
+const FORMATS: [ImageFormat; 5] = [
+    ImageFormat::Png,
+    ImageFormat::Jpeg,
+    ImageFormat::Gif,
+    ImageFormat::WebP,
+    ImageFormat::Bmp,
+];
+
+/// Validates an uploaded image and stores it in `dir`, returning its path.
+/// `filename` is the name given by the client, used only to label the file.
+pub fn store_image(dir: &Path, filename: &str, data: &[u8]) -> Result<PathBuf> {
+    if data.is_empty() {
+        bail!("No image data received");
+    }
+    if data.len() > MAX_UPLOAD_BYTES {
+        bail!(
+            "Image is too large ({} KiB, limit {} MiB)",
+            data.len() / 1024,
+            MAX_UPLOAD_BYTES / (1024 * 1024)
+        );
+    }
+
+    let reader = ImageReader::new(Cursor::new(data))
+        .with_guessed_format()
+        .context("Failed to read image")?;
+    let format = reader
+        .format()
+        .filter(|f| FORMATS.contains(f))
+        .context("Unsupported image format (use PNG, JPEG, GIF, WebP or BMP)")?;
+    let (width, height) = reader
+        .into_dimensions()
+        .context("Failed to read image size")?;
+    if width > MAX_DIMENSION || height > MAX_DIMENSION {
+        bail!(
+            "Image is too large ({}x{}, limit {} pixels per side)",
+            width,
+            height,
+            MAX_DIMENSION
+        );
+    }
+    image::load_from_memory_with_format(data, format).context("Image data is corrupt")?;
+
+    fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    let extension = format.extensions_str()[0];
+    let name = format!(
+        "{}-{}.{}",
+        chrono::Local::now().format("%Y%m%d-%H%M%S"),
+        sanitize_stem(filename),
+        extension
+    );
+    let path = dir.join(name);
+    fs::write(&path, data).with_context(|| format!("Failed to write {}", path.display()))?;
+    info!(
+        "Stored uploaded {}x{} image at {}",
+        width,
+        height,
+        path.display()
+    );
+    Ok(path)
+}
+
+/// Deletes all but the newest uploads in `dir`, keeping any in `in_use`.
+pub fn prune(dir: &Path, in_use: &[String]) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    // Names start with the upload time, so sorting puts the newest last
+    let mut files: Vec<PathBuf> = entries
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.is_file())
+        .filter(|p| !in_use.iter().any(|u| Path::new(u) == p))
+        .collect();
+    files.sort();
+    let excess = files.len().saturating_sub(MAX_STORED);
+    for path in &files[..excess] {
+        match fs::remove_file(path) {
+            Ok(()) => debug!("Removed old upload {}", path.display()),
+            Err(e) => debug!("Failed to remove {}: {}", path.display(), e),
+        }
+    }
+}
+
+/// Reduces a client file name to a short, safe file name stem.
+fn sanitize_stem(filename: &str) -> String {
+    let name = filename.rsplit(['/', '\\']).next().unwrap_or_default();
+    let stem = name.rsplit_once('.').map_or(name, |(stem, _)| stem);
+    let clean: String = stem
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .take(40)
+        .collect();
+    let clean = clean.trim_matches('_');
+    if clean.is_empty() {
+        "image".to_string()
+    } else {
+        clean.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_store_image() {
+        assert_eq!(sanitize_stem("../../etc/passwd"), "passwd");
+        assert_eq!(sanitize_stem("C:\\Photos\\My Cat.jpeg"), "My_Cat");
+        assert_eq!(sanitize_stem(".png"), "image");
+
+        let dir = std::env::temp_dir().join(format!("ht32-uploads-{}", std::process::id()));
+        assert!(store_image(&dir, "notes.png", b"not an image").is_err());
+
+        let mut png = Vec::new();
+        image::RgbaImage::new(4, 4)
+            .write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+            .unwrap();
+        let path = store_image(&dir, "wallpaper.jpg", &png).unwrap();
+        assert_eq!(path.extension().unwrap(), "png");
+        assert!(path.starts_with(&dir));
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -1,29 +1,45 @@
 //! HTMX web UI module.
 
+use anyhow::{Context, Result};
 use askama::Template;
 use axum::{
-    extract::{Form, State},
-    http::{header, StatusCode},
+    extract::{
+        multipart::MultipartError, ConnectInfo, DefaultBodyLimit, Form, Multipart, Path, Query,
+        Request, State,
+    },
+    http::{header, Method, StatusCode},
+    middleware::{self, Next},
     response::{
         sse::{Event, KeepAlive, Sse},
         Html, IntoResponse, Response,
     },
     routing::{get, post},
-    Router,
+    Json, Router,
 };
 use serde::Deserialize;
+use std::collections::HashMap;
 use std::convert::Infallible;
+use std::future::Future;
+use std::net::SocketAddr;
 use std::sync::Arc;
-use tokio::sync::broadcast;
+use tokio::net::TcpListener;
 use tokio_stream::wrappers::BroadcastStream;
 use tokio_stream::{Stream, StreamExt};
+use tracing::{info, warn};
 
-use crate::dbus::DaemonSignals;
+use crate::config::WebConfig;
+use crate::events::{self, EventBus};
+use crate::faces::custom::{self, Layout};
 use crate::faces::{
-    available_faces, available_themes, complication_names, complication_options,
-    ComplicationOptionType,
+    available_faces, complication_names, complication_options, ComplicationOptionType,
 };
+use crate::mdns;
+use crate::rendering::ScreenFormat;
 use crate::state::AppState;
+use crate::streamdeck;
+use crate::uploads;
+
+mod pwa;
 
 /// Face option for template rendering.
 #[derive(Clone)]
@@ -32,24 +48,25 @@ struct FaceOption {
     display_name: String,
 }
 
-/// Theme option for template rendering.
-#[derive(Clone)]
-struct ThemeOption {
-    id: String,
-    display_name: String,
-}
 use ht32_panel_hw::Orientation;
+use ht32_panel_types::{LedSettings, ThemeInfo};
 
 /// Main index page template.
 #[derive(Template)]
 #[template(path = "index.html")]
 struct IndexTemplate;
 
+/// Custom face layout editor page template.
+#[derive(Template)]
+#[template(path = "editor.html")]
+struct EditorTemplate;
+
 /// Status partial template.
 #[derive(Template)]
 #[template(path = "partials/status.html")]
 struct StatusTemplate {
     connected: bool,
+    kiosk: bool,
 }
 
 /// Orientation partial template.
@@ -57,6 +74,17 @@ struct StatusTemplate {
 #[template(path = "partials/orientation.html")]
 struct OrientationTemplate {
     current: String,
+    /// Whether the orientation was detected from the host
+    auto: bool,
+}
+
+impl OrientationTemplate {
+    fn new(app: &AppState) -> Self {
+        Self {
+            current: app.orientation().to_string(),
+            auto: app.is_orientation_auto(),
+        }
+    }
 }
 
 /// Face partial template.
@@ -82,7 +110,23 @@ struct LedTemplate {
 #[template(path = "partials/theme.html")]
 struct ThemeTemplate {
     current: String,
-    themes: Vec<ThemeOption>,
+    themes: Vec<ThemeInfo>,
+    /// Current colors by name, as "#rrggbb" for color inputs
+    colors: Vec<(String, String)>,
+    error: Option<String>,
+}
+
+/// Image upload partial template.
+#[derive(Template)]
+#[template(path = "partials/images.html")]
+struct ImagesTemplate {
+    /// File name of the background image
+    background: Option<String>,
+    /// File name of the image face's image
+    face_image: Option<String>,
+    max_mb: usize,
+    message: Option<String>,
+    error: Option<String>,
 }
 
 /// Preview partial template.
@@ -133,25 +177,71 @@ struct ComplicationsTemplate {
     complications: Vec<ComplicationItem>,
 }
 
-/// Shared state for the web server including signal channel.
+/// Shared state for the web server including the event bus.
 #[derive(Clone)]
 pub struct WebState {
     pub app: Arc<AppState>,
-    pub signal_tx: broadcast::Sender<DaemonSignals>,
+    pub events: EventBus,
 }
 
-/// Creates the web router with all routes.
-pub fn create_router(state: Arc<AppState>, signal_tx: broadcast::Sender<DaemonSignals>) -> Router {
-    let web_state = WebState {
-        app: state,
-        signal_tx,
+/// Serves the web UI until `shutdown` completes, advertising it via mDNS
+/// unless it only listens on loopback.
+pub async fn serve(
+    config: &WebConfig,
+    state: Arc<AppState>,
+    events: EventBus,
+    shutdown: impl Future<Output = ()>,
+) -> Result<()> {
+    let app = create_router(state, events);
+    let addr: SocketAddr = config.listen.parse().context("Invalid listen address")?;
+    let listener = TcpListener::bind(addr).await?;
+    info!("Web server listening on http://{}", addr);
+
+    // Advertise on the LAN unless only reachable from this machine
+    let advertisement = if !config.mdns {
+        None
+    } else if addr.ip().is_loopback() {
+        info!("Not advertising the web UI via mDNS: it listens on loopback only");
+        None
+    } else {
+        let hostname = crate::sensors::SystemInfo::new().hostname();
+        mdns::Advertisement::start(addr, &hostname)
+            .inspect_err(|e| warn!("mDNS advertisement failed: {:#}", e))
+            .ok()
     };
 
+    // Run server with shutdown handling
+    tokio::select! {
+        result = axum::serve(
+            listener,
+            app.into_make_service_with_connect_info::<SocketAddr>(),
+        ) => {
+            result?;
+        }
+        _ = shutdown => {}
+    }
+
+    if let Some(advertisement) = advertisement {
+        advertisement.stop();
+    }
+    Ok(())
+}
+
+/// Creates the web router with all routes.
+pub fn create_router(state: Arc<AppState>, events: EventBus) -> Router {
+    let web_state = WebState { app: state, events };
+
     Router::new()
         // Main page
         .route("/", get(index))
+        // Installable app: manifest, service worker and icons
+        .route("/manifest.webmanifest", get(pwa::manifest))
+        .route("/sw.js", get(pwa::service_worker))
+        .route("/icons/:file", get(pwa::icon))
         // LCD preview image
         .route("/lcd.png", get(lcd_png))
+        .route("/lcd.jpg", get(lcd_jpeg))
+        .route("/lcd.webp", get(lcd_webp))
         // Server-Sent Events for live updates
         .route("/events", get(events_stream))
         // Partials for HTMX
@@ -160,34 +250,93 @@ pub fn create_router(state: Arc<AppState>, signal_tx: broadcast::Sender<DaemonSi
         .route("/face", get(face_get).post(face_set))
         .route("/led", get(led_get).post(led_set))
         .route("/theme", get(theme_get).post(theme_set))
+        .route("/theme/colors", post(theme_colors_set))
+        .route("/theme/save", post(theme_save))
+        .route("/theme/delete", post(theme_delete))
+        .route("/theme-preview/:theme", get(theme_preview))
         .route(
             "/complications",
             get(complications_get).post(complications_set),
         )
         .route("/complication-option", post(complication_option_set))
         .route("/preview", get(preview_get))
+        // Image uploads
+        .route("/images", get(images_get))
+        .route(
+            "/upload",
+            post(upload).layer(DefaultBodyLimit::max(uploads::MAX_UPLOAD_BYTES + 64 * 1024)),
+        )
+        .route("/background/clear", post(background_clear))
+        // Custom face layout editor
+        .route("/editor", get(editor))
+        .route("/layout", get(layout_get).put(layout_put))
+        .route("/layout/widgets", get(layout_widgets))
+        .route("/layout/validate", post(layout_validate))
+        // JSON API for remote control, e.g. from a FleetClient
+        .route("/api/status", get(api_status))
+        .route("/healthz", get(healthz))
+        .route("/api/actions", get(streamdeck::actions_list))
+        .route("/api/actions/:id", post(streamdeck::action_invoke))
+        .route_layer(middleware::from_fn_with_state(
+            web_state.clone(),
+            kiosk_guard,
+        ))
         // State
         .with_state(web_state)
 }
 
+/// Records a change made by the HTTP client at `addr` in the audit log.
+pub(crate) fn audit(state: &WebState, addr: SocketAddr, method: &str, old: &str, new: &str) {
+    state
+        .app
+        .audit_log()
+        .record("http", &addr.ip().to_string(), method, old, new);
+}
+
+/// Rejects requests that could change anything while in kiosk mode.
+pub(crate) async fn kiosk_guard(
+    State(state): State<WebState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let read_only = matches!(*request.method(), Method::GET | Method::HEAD);
+    if !read_only && state.app.is_kiosk() {
+        tracing::debug!(
+            "Kiosk mode: rejected {} {}",
+            request.method(),
+            request.uri()
+        );
+        return (StatusCode::FORBIDDEN, "Panel is in kiosk mode").into_response();
+    }
+    next.run(request).await
+}
+
 /// GET / - Main page
 async fn index() -> impl IntoResponse {
     Html(IndexTemplate.render().unwrap())
 }
 
+/// GET /editor - Custom face layout editor
+async fn editor() -> impl IntoResponse {
+    Html(EditorTemplate.render().unwrap())
+}
+
 /// GET /events - Server-Sent Events stream for live updates
 async fn events_stream(
     State(state): State<WebState>,
 ) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
-    let rx = state.signal_tx.subscribe();
+    let rx = state.events.subscribe();
     let stream = BroadcastStream::new(rx).filter_map(|result| {
         match result {
-            Ok(signal) => {
-                let event_type = match signal {
-                    DaemonSignals::OrientationChanged => "orientation",
-                    DaemonSignals::LedChanged => "led",
-                    DaemonSignals::DisplaySettingsChanged => "display",
-                    DaemonSignals::ComplicationOptionChanged => "complication",
+            Ok(event) => {
+                let event_type = match event {
+                    events::Event::OrientationChanged => "orientation",
+                    events::Event::LedChanged => "led",
+                    events::Event::DisplaySettingsChanged => "display",
+                    events::Event::ComplicationOptionChanged => "complication",
+                    events::Event::ButtonPressed(_) => "button",
+                    // Alerts and other notices do not change the page
+                    _ => return None,
                 };
                 Some(Ok(Event::default().event(event_type).data("reload")))
             }
@@ -198,21 +347,42 @@ async fn events_stream(
     Sse::new(stream).keep_alive(KeepAlive::default())
 }
 
+/// Query of the screenshot routes.
+#[derive(Deserialize)]
+struct ScreenQuery {
+    /// JPEG quality (1-100)
+    quality: Option<u8>,
+}
+
 /// GET /lcd.png - LCD framebuffer as PNG
 async fn lcd_png(State(state): State<WebState>) -> Response {
-    match state.app.get_screen_png() {
-        Ok(png_data) => (
+    screen_image(&state, ScreenFormat::Png, 0).await
+}
+
+/// GET /lcd.jpg?quality=N - LCD framebuffer as JPEG
+async fn lcd_jpeg(State(state): State<WebState>, Query(query): Query<ScreenQuery>) -> Response {
+    screen_image(&state, ScreenFormat::Jpeg, query.quality.unwrap_or(0)).await
+}
+
+/// GET /lcd.webp - LCD framebuffer as lossless WebP
+async fn lcd_webp(State(state): State<WebState>) -> Response {
+    screen_image(&state, ScreenFormat::WebP, 0).await
+}
+
+async fn screen_image(state: &WebState, format: ScreenFormat, quality: u8) -> Response {
+    match state.app.get_screen_image(format, quality).await {
+        Ok(data) => (
             StatusCode::OK,
             [
-                (header::CONTENT_TYPE, "image/png"),
+                (header::CONTENT_TYPE, format.mime_type()),
                 (header::CACHE_CONTROL, "no-cache"),
             ],
-            png_data,
+            data,
         )
             .into_response(),
         Err(e) => (
             StatusCode::INTERNAL_SERVER_ERROR,
-            format!("Failed to generate PNG: {}", e),
+            format!("Failed to generate {}: {}", format, e),
         )
             .into_response(),
     }
@@ -220,14 +390,40 @@ async fn lcd_png(State(state): State<WebState>) -> Response {
 
 /// GET /status - Connection status partial
 async fn status(State(state): State<WebState>) -> impl IntoResponse {
-    let connected = state.app.is_lcd_connected();
-    Html(StatusTemplate { connected }.render().unwrap())
+    let template = StatusTemplate {
+        connected: state.app.is_lcd_connected(),
+        kiosk: state.app.is_kiosk(),
+    };
+    Html(template.render().unwrap())
+}
+
+/// GET /api/status - Panel state as JSON
+async fn api_status(State(state): State<WebState>) -> impl IntoResponse {
+    Json(serde_json::json!({
+        "hostname": state.app.hostname(),
+        "version": env!("CARGO_PKG_VERSION"),
+        "connected": state.app.is_lcd_connected(),
+        "face": state.app.face_name(),
+        "theme": state.app.theme_name(),
+        "orientation": state.app.orientation().to_string(),
+        "kiosk": state.app.is_kiosk(),
+    }))
+}
+
+/// GET /healthz - Daemon health as JSON, with 503 while degraded
+async fn healthz(State(state): State<WebState>) -> impl IntoResponse {
+    let health = state.app.health();
+    let code = if health.is_ok() {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    (code, Json(health))
 }
 
 /// GET /orientation - Orientation controls partial
 async fn orientation_get(State(state): State<WebState>) -> impl IntoResponse {
-    let current = state.app.orientation().to_string();
-    Html(OrientationTemplate { current }.render().unwrap())
+    Html(OrientationTemplate::new(&state.app).render().unwrap())
 }
 
 /// Form data for orientation.
@@ -239,13 +435,32 @@ struct OrientationForm {
 /// POST /orientation - Set orientation
 async fn orientation_set(
     State(state): State<WebState>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
     Form(form): Form<OrientationForm>,
 ) -> impl IntoResponse {
-    if let Ok(orientation) = form.orientation.parse::<Orientation>() {
-        let _ = state.app.set_orientation(orientation);
+    let old = state.app.orientation().to_string();
+    if form.orientation == "auto" {
+        if let Ok(detected) = state.app.set_orientation_auto() {
+            audit(
+                &state,
+                addr,
+                "SetOrientation",
+                &old,
+                &format!("auto ({})", detected),
+            );
+        }
+    } else if let Ok(orientation) = form.orientation.parse::<Orientation>() {
+        if state.app.set_orientation(orientation).is_ok() {
+            audit(
+                &state,
+                addr,
+                "SetOrientation",
+                &old,
+                &orientation.to_string(),
+            );
+        }
     }
-    let current = state.app.orientation().to_string();
-    Html(OrientationTemplate { current }.render().unwrap())
+    Html(OrientationTemplate::new(&state.app).render().unwrap())
 }
 
 /// GET /face - Face controls partial
@@ -268,9 +483,17 @@ struct FaceForm {
 }
 
 /// POST /face - Set face
-async fn face_set(State(state): State<WebState>, Form(form): Form<FaceForm>) -> impl IntoResponse {
-    let _ = state.app.set_face(&form.face);
+async fn face_set(
+    State(state): State<WebState>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    Form(form): Form<FaceForm>,
+) -> impl IntoResponse {
+    let old = state.app.face_name();
+    let changed = state.app.set_face(&form.face).is_ok();
     let current = state.app.face_name();
+    if changed {
+        audit(&state, addr, "SetFace", &old, &current);
+    }
     let faces: Vec<FaceOption> = available_faces()
         .iter()
         .map(|f| FaceOption {
@@ -283,12 +506,12 @@ async fn face_set(State(state): State<WebState>, Form(form): Form<FaceForm>) ->
 
 /// GET /led - LED controls partial
 async fn led_get(State(state): State<WebState>) -> impl IntoResponse {
-    let (theme, intensity, speed) = state.app.led_settings();
+    let led = state.app.led_settings();
     Html(
         LedTemplate {
-            theme,
-            intensity,
-            speed,
+            theme: led.theme(),
+            intensity: led.intensity(),
+            speed: led.speed(),
             error: None,
         }
         .render()
@@ -311,25 +534,51 @@ fn default_led() -> u8 {
 }
 
 /// POST /led - Set LED settings
-async fn led_set(State(state): State<WebState>, Form(form): Form<LedForm>) -> impl IntoResponse {
-    let theme = form.theme.clamp(1, 5);
-    let intensity = form.intensity.clamp(1, 5);
-    let speed = form.speed.clamp(1, 5);
+async fn led_set(
+    State(state): State<WebState>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    Form(form): Form<LedForm>,
+) -> impl IntoResponse {
+    let settings = LedSettings::clamped(form.theme, form.intensity, form.speed);
 
-    let error = match state.app.set_led(theme, intensity, speed).await {
-        Ok(()) => None,
+    let old = state.app.led_settings().to_string();
+    let error = match state.app.set_led(settings).await {
+        Ok(()) => {
+            let new = state.app.led_settings().to_string();
+            audit(&state, addr, "SetLed", &old, &new);
+            None
+        }
         Err(e) => {
             tracing::error!("Failed to set LED: {}", e);
             Some(e.to_string())
         }
     };
 
-    let (theme, intensity, speed) = state.app.led_settings();
+    let led = state.app.led_settings();
     Html(
         LedTemplate {
-            theme,
-            intensity,
-            speed,
+            theme: led.theme(),
+            intensity: led.intensity(),
+            speed: led.speed(),
+            error,
+        }
+        .render()
+        .unwrap(),
+    )
+}
+
+/// Renders the theme controls, showing `error` if there is one.
+fn render_theme(app: &AppState, error: Option<String>) -> Html<String> {
+    let colors = app
+        .theme_colors()
+        .into_iter()
+        .map(|(name, hex)| (name, hex.to_lowercase()))
+        .collect();
+    Html(
+        ThemeTemplate {
+            current: app.theme_name(),
+            themes: app.theme_infos(),
+            colors,
             error,
         }
         .render()
@@ -339,15 +588,7 @@ async fn led_set(State(state): State<WebState>, Form(form): Form<LedForm>) -> im
 
 /// GET /theme - Theme controls partial
 async fn theme_get(State(state): State<WebState>) -> impl IntoResponse {
-    let current = state.app.theme_name();
-    let themes: Vec<ThemeOption> = available_themes()
-        .iter()
-        .map(|t| ThemeOption {
-            id: t.id.to_string(),
-            display_name: t.display_name.to_string(),
-        })
-        .collect();
-    Html(ThemeTemplate { current, themes }.render().unwrap())
+    render_theme(&state.app, None)
 }
 
 /// Form data for theme.
@@ -359,18 +600,275 @@ struct ThemeForm {
 /// POST /theme - Set theme
 async fn theme_set(
     State(state): State<WebState>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
     Form(form): Form<ThemeForm>,
 ) -> impl IntoResponse {
-    let _ = state.app.set_theme(&form.theme);
-    let current = state.app.theme_name();
-    let themes: Vec<ThemeOption> = available_themes()
-        .iter()
-        .map(|t| ThemeOption {
-            id: t.id.to_string(),
-            display_name: t.display_name.to_string(),
-        })
-        .collect();
-    Html(ThemeTemplate { current, themes }.render().unwrap())
+    let old = state.app.theme_name();
+    let changed = state.app.set_theme(&form.theme).is_ok();
+    if changed {
+        audit(&state, addr, "SetTheme", &old, &state.app.theme_name());
+    }
+    render_theme(&state.app, None)
+}
+
+/// POST /theme/colors - Change theme colors live, without saving them
+async fn theme_colors_set(
+    State(state): State<WebState>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    Form(colors): Form<HashMap<String, String>>,
+) -> Response {
+    let old = state.app.describe_theme_colors();
+    match state.app.set_theme_colors(&colors) {
+        Ok(()) => {
+            let new = state.app.describe_theme_colors();
+            audit(&state, addr, "SetThemeColors", &old, &new);
+            StatusCode::NO_CONTENT.into_response()
+        }
+        Err(e) => (StatusCode::BAD_REQUEST, e.to_string()).into_response(),
+    }
+}
+
+/// Form data for saving or deleting a theme.
+#[derive(Deserialize)]
+struct ThemeNameForm {
+    name: String,
+}
+
+/// POST /theme/save - Save the current colors as a theme
+async fn theme_save(
+    State(state): State<WebState>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    Form(form): Form<ThemeNameForm>,
+) -> impl IntoResponse {
+    let old = state.app.theme_name();
+    let error = match state.app.save_current_theme(&form.name) {
+        Ok(()) => {
+            audit(
+                &state,
+                addr,
+                "SaveCurrentTheme",
+                &old,
+                &state.app.theme_name(),
+            );
+            state.events.publish(events::Event::DisplaySettingsChanged);
+            None
+        }
+        Err(e) => Some(e.to_string()),
+    };
+    render_theme(&state.app, error)
+}
+
+/// POST /theme/delete - Delete a saved theme
+async fn theme_delete(
+    State(state): State<WebState>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    Form(form): Form<ThemeNameForm>,
+) -> impl IntoResponse {
+    let error = match state.app.delete_theme(&form.name) {
+        Ok(()) => {
+            audit(&state, addr, "DeleteTheme", &form.name, "");
+            state.events.publish(events::Event::DisplaySettingsChanged);
+            None
+        }
+        Err(e) => Some(e.to_string()),
+    };
+    render_theme(&state.app, error)
+}
+
+/// GET /theme-preview/{theme} - Current face rendered with a theme as PNG
+async fn theme_preview(State(state): State<WebState>, Path(theme): Path<String>) -> Response {
+    match state.app.render_theme_preview(&theme) {
+        Ok(png_data) => (
+            StatusCode::OK,
+            [
+                (header::CONTENT_TYPE, "image/png"),
+                (header::CACHE_CONTROL, "no-cache"),
+            ],
+            png_data,
+        )
+            .into_response(),
+        Err(e) => (StatusCode::NOT_FOUND, e.to_string()).into_response(),
+    }
+}
+
+/// GET /layout/widgets - Widget types for the layout editor
+async fn layout_widgets() -> impl IntoResponse {
+    Json(custom::widget_kinds())
+}
+
+/// GET /layout - Custom face layout and the canvas size it is drawn on
+async fn layout_get(State(state): State<WebState>) -> impl IntoResponse {
+    let (width, height) = state.app.canvas_size();
+    Json(serde_json::json!({
+        "width": width,
+        "height": height,
+        "layout": state.app.custom_layout(),
+    }))
+}
+
+/// Parses and checks a layout from a request body.
+fn check_layout(state: &WebState, body: &str) -> Result<Layout, Vec<String>> {
+    let layout = Layout::from_json(body).map_err(|e| vec![e])?;
+    let errors = state.app.validate_custom_layout(&layout);
+    if errors.is_empty() {
+        Ok(layout)
+    } else {
+        Err(errors)
+    }
+}
+
+/// POST /layout/validate - Check a layout without saving it
+async fn layout_validate(State(state): State<WebState>, body: String) -> impl IntoResponse {
+    let errors = check_layout(&state, &body).err().unwrap_or_default();
+    Json(serde_json::json!({ "errors": errors }))
+}
+
+/// PUT /layout - Validate and save the custom face layout
+async fn layout_put(
+    State(state): State<WebState>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    body: String,
+) -> Response {
+    let old = state.app.custom_layout().to_json();
+    let result = check_layout(&state, &body).and_then(|layout| {
+        state
+            .app
+            .set_custom_layout(&layout)
+            .map_err(|e| vec![e.to_string()])?;
+        Ok(layout)
+    });
+    match result {
+        Ok(layout) => {
+            audit(&state, addr, "SetCustomLayout", &old, &layout.to_json());
+            state
+                .events
+                .publish(events::Event::ComplicationOptionChanged);
+            Json(serde_json::json!({ "errors": [] })).into_response()
+        }
+        Err(errors) => (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({ "errors": errors })),
+        )
+            .into_response(),
+    }
+}
+
+/// Renders the image upload partial with an optional outcome.
+fn images_partial(state: &WebState, result: Result<String, String>) -> Html<String> {
+    let file_name = |path: String| {
+        std::path::Path::new(&path)
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or(path)
+    };
+    let face_image = state.app.image_face_source().map(file_name);
+    let (message, error) = match result {
+        Ok(message) if message.is_empty() => (None, None),
+        Ok(message) => (Some(message), None),
+        Err(error) => (None, Some(error)),
+    };
+    Html(
+        ImagesTemplate {
+            background: state.app.background_image().map(file_name),
+            face_image,
+            max_mb: uploads::MAX_UPLOAD_BYTES / (1024 * 1024),
+            message,
+            error,
+        }
+        .render()
+        .unwrap(),
+    )
+}
+
+/// GET /images - Image upload partial
+async fn images_get(State(state): State<WebState>) -> impl IntoResponse {
+    images_partial(&state, Ok(String::new()))
+}
+
+/// POST /upload - Store an uploaded image and use it as the background or
+/// on the image face
+async fn upload(
+    State(state): State<WebState>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    multipart: Multipart,
+) -> impl IntoResponse {
+    let result = receive_upload(&state, addr, multipart)
+        .await
+        .map_err(|e| format!("{:#}", e));
+    if result.is_ok() {
+        state.events.publish(events::Event::DisplaySettingsChanged);
+    }
+    images_partial(&state, result)
+}
+
+/// Reads the `target` and `file` fields of an upload and applies the image.
+async fn receive_upload(
+    state: &WebState,
+    addr: SocketAddr,
+    mut multipart: Multipart,
+) -> anyhow::Result<String> {
+    let mut target = String::new();
+    let mut file = None;
+    while let Some(field) = multipart.next_field().await.map_err(multipart_error)? {
+        match field.name() {
+            Some("target") => target = field.text().await.map_err(multipart_error)?,
+            Some("file") => {
+                let name = field.file_name().unwrap_or_default().to_string();
+                file = Some((name, field.bytes().await.map_err(multipart_error)?));
+            }
+            _ => {}
+        }
+    }
+    // Checked before storing, so a bad target neither keeps the file nor
+    // prunes older uploads
+    let background = match target.as_str() {
+        "background" => true,
+        "face" => false,
+        other => anyhow::bail!("Unknown upload target: {}", other),
+    };
+    let (name, data) = file.ok_or_else(|| anyhow::anyhow!("No file uploaded"))?;
+    let path = state.app.store_uploaded_image(&name, &data)?;
+    if background {
+        let old = state.app.background_image().unwrap_or_default();
+        state.app.set_background_image(Some(&path))?;
+        audit(state, addr, "SetBackgroundImage", &old, &path);
+        Ok("Background image updated".to_string())
+    } else {
+        let old = state.app.image_face_source().unwrap_or_default();
+        state.app.set_image_face_source(&path)?;
+        audit(state, addr, "SetImageFace", &old, &path);
+        Ok("Image face updated".to_string())
+    }
+}
+
+/// Describes a failure to read an upload.
+fn multipart_error(e: MultipartError) -> anyhow::Error {
+    if e.status() == StatusCode::PAYLOAD_TOO_LARGE {
+        anyhow::anyhow!(
+            "Image is too large (limit {} MiB)",
+            uploads::MAX_UPLOAD_BYTES / (1024 * 1024)
+        )
+    } else {
+        anyhow::anyhow!("Upload failed: {}", e.body_text())
+    }
+}
+
+/// POST /background/clear - Remove the background image
+async fn background_clear(
+    State(state): State<WebState>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+) -> impl IntoResponse {
+    let old = state.app.background_image().unwrap_or_default();
+    let result = state
+        .app
+        .set_background_image(None)
+        .map(|()| "Background image removed".to_string())
+        .map_err(|e| e.to_string());
+    if result.is_ok() {
+        audit(&state, addr, "SetBackgroundImage", &old, "");
+    }
+    state.events.publish(events::Event::DisplaySettingsChanged);
+    images_partial(&state, result)
 }
 
 /// GET /preview - Preview image partial
@@ -397,12 +895,19 @@ struct ComplicationForm {
 /// POST /complications - Toggle a complication
 async fn complications_set(
     State(state): State<WebState>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
     Form(form): Form<ComplicationForm>,
 ) -> impl IntoResponse {
     let enabled = form.enabled.as_deref() == Some("on");
-    let _ = state
+    let changed = state
         .app
-        .set_complication_enabled(&form.complication, enabled);
+        .set_complication_enabled(&form.complication, enabled)
+        .is_ok();
+    if changed && enabled {
+        audit(&state, addr, "EnableComplication", "", &form.complication);
+    } else if changed {
+        audit(&state, addr, "DisableComplication", &form.complication, "");
+    }
 
     // Re-render the complications list
     render_complications(&state.app)
@@ -419,11 +924,27 @@ struct ComplicationOptionForm {
 /// POST /complication-option - Set a complication option value
 async fn complication_option_set(
     State(state): State<WebState>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
     Form(form): Form<ComplicationOptionForm>,
 ) -> impl IntoResponse {
-    let _ = state
+    let old = state
         .app
-        .set_complication_option(&form.complication, &form.option, &form.value);
+        .get_complication_option(&form.complication, &form.option)
+        .unwrap_or_default();
+    let changed = state
+        .app
+        .set_complication_option(&form.complication, &form.option, &form.value)
+        .is_ok();
+    if changed {
+        let key = format!("{}.{}", form.complication, form.option);
+        audit(
+            &state,
+            addr,
+            "SetComplicationOption",
+            &format!("{}={}", key, old),
+            &format!("{}={}", key, form.value),
+        );
+    }
 
     // Re-render the complications list
     render_complications(&state.app)
@@ -0,0 +1,154 @@
+//! Progressive web app support: manifest, service worker and app icons.
+//!
+//! These let the web UI be installed to a phone's home screen and keep
+//! showing the last known panel state while the daemon is unreachable.
+
+use askama::Template;
+use axum::{
+    extract::Path,
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+};
+use tiny_skia::{Color, Paint, PathBuilder, Pixmap, Rect, Stroke, Transform};
+
+/// Icon sizes served under /icons, in pixels.
+const ICON_SIZES: [u32; 3] = [180, 192, 512];
+
+/// Service worker script template.
+#[derive(Template)]
+#[template(path = "sw.js", escape = "none")]
+struct ServiceWorkerTemplate {
+    version: &'static str,
+}
+
+/// GET /manifest.webmanifest - Web app manifest
+pub async fn manifest() -> impl IntoResponse {
+    let icons: Vec<_> = ICON_SIZES
+        .iter()
+        .map(|size| {
+            serde_json::json!({
+                "src": format!("/icons/{}.png", size),
+                "sizes": format!("{0}x{0}", size),
+                "type": "image/png",
+                "purpose": "any maskable",
+            })
+        })
+        .collect();
+    let manifest = serde_json::json!({
+        "name": "HT32 Panel",
+        "short_name": "HT32 Panel",
+        "description": "Control the HT32 panel display and LEDs",
+        "start_url": "/",
+        "scope": "/",
+        "display": "standalone",
+        "background_color": "#1a1a2e",
+        "theme_color": "#1a1a2e",
+        "icons": icons,
+    });
+    (
+        [(header::CONTENT_TYPE, "application/manifest+json")],
+        manifest.to_string(),
+    )
+}
+
+/// GET /sw.js - Service worker
+pub async fn service_worker() -> impl IntoResponse {
+    let script = ServiceWorkerTemplate {
+        version: env!("CARGO_PKG_VERSION"),
+    };
+    (
+        [
+            (header::CONTENT_TYPE, "text/javascript"),
+            (header::CACHE_CONTROL, "no-cache"),
+        ],
+        script.render().unwrap(),
+    )
+}
+
+/// GET /icons/:file - App icon, e.g. /icons/192.png
+pub async fn icon(Path(file): Path<String>) -> Response {
+    let size = file
+        .strip_suffix(".png")
+        .and_then(|s| s.parse::<u32>().ok())
+        .filter(|s| ICON_SIZES.contains(s));
+    let Some(size) = size else {
+        return (StatusCode::NOT_FOUND, "No such icon").into_response();
+    };
+    match draw_icon(size).and_then(|pixmap| pixmap.encode_png().ok()) {
+        Some(png_data) => (
+            StatusCode::OK,
+            [
+                (header::CONTENT_TYPE, "image/png"),
+                (header::CACHE_CONTROL, "max-age=86400"),
+            ],
+            png_data,
+        )
+            .into_response(),
+        None => (StatusCode::INTERNAL_SERVER_ERROR, "Failed to draw icon").into_response(),
+    }
+}
+
+/// Draws the app icon: a small panel showing a graph, on a full-bleed
+/// background so launchers can mask it to any shape.
+fn draw_icon(size: u32) -> Option<Pixmap> {
+    let mut pixmap = Pixmap::new(size, size)?;
+    pixmap.fill(Color::from_rgba8(0x1a, 0x1a, 0x2e, 0xff));
+    let s = size as f32;
+
+    let mut paint = Paint {
+        anti_alias: true,
+        ..Paint::default()
+    };
+
+    // Panel body, kept inside the maskable safe zone
+    let panel = Rect::from_ltrb(0.18 * s, 0.33 * s, 0.82 * s, 0.67 * s)?;
+    paint.set_color_rgba8(0x0f, 0x0f, 0x23, 0xff);
+    pixmap.fill_rect(panel, &paint, Transform::identity(), None);
+    paint.set_color_rgba8(0x00, 0xd4, 0xff, 0xff);
+    let outline = PathBuilder::from_rect(panel);
+    let stroke = Stroke {
+        width: 0.03 * s,
+        ..Stroke::default()
+    };
+    pixmap.stroke_path(&outline, &paint, &stroke, Transform::identity(), None);
+
+    // Graph line across the panel
+    let points = [0.55, 0.45, 0.5, 0.38, 0.44, 0.52, 0.41];
+    let mut pb = PathBuilder::new();
+    for (i, y) in points.iter().enumerate() {
+        let x = 0.24 + 0.52 * i as f32 / (points.len() - 1) as f32;
+        if i == 0 {
+            pb.move_to(x * s, y * s);
+        } else {
+            pb.line_to(x * s, y * s);
+        }
+    }
+    let line = pb.finish()?;
+    let stroke = Stroke {
+        width: 0.025 * s,
+        ..Stroke::default()
+    };
+    pixmap.stroke_path(&line, &paint, &stroke, Transform::identity(), None);
+
+    // Usage bar along the bottom
+    paint.set_color_rgba8(0x4c, 0xaf, 0x50, 0xff);
+    let bar = Rect::from_ltrb(0.24 * s, 0.59 * s, 0.6 * s, 0.62 * s)?;
+    pixmap.fill_rect(bar, &paint, Transform::identity(), None);
+
+    Some(pixmap)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_draw_icon() {
+        for size in ICON_SIZES {
+            let pixmap = draw_icon(size).unwrap();
+            assert_eq!(pixmap.width(), size);
+            // Corners are the opaque background, for maskable icons
+            assert_eq!(pixmap.pixel(0, 0).unwrap().alpha(), 255);
+        }
+    }
+}
@@ -0,0 +1,346 @@
+//! Outbound webhooks.
+//!
+//! Daemon events are queued to a background thread that POSTs them to every
+//! configured URL subscribed to the event, retrying failed deliveries with
+//! an increasing delay. Bodies default to a JSON object but can be templated
+//! to suit services such as ntfy, Discord or Slack.
+
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use tokio::sync::broadcast;
+use tracing::{debug, warn};
+
+use crate::config::WebhookConfig;
+use crate::events;
+use crate::sensors::data::EventKind;
+use crate::sensors::http;
+
+/// A metric, storage pool or job entered an alert state.
+pub const ALERT: &str = "alert";
+
+/// The LCD stopped accepting frames.
+pub const DEVICE_DISCONNECTED: &str = "device_disconnected";
+
+/// The LCD accepts frames again after a disconnect.
+pub const DEVICE_RECONNECTED: &str = "device_reconnected";
+
+/// The display face changed.
+pub const FACE_CHANGED: &str = "face_changed";
+
+/// Delay before the first retry; doubled for each further attempt.
+const RETRY_DELAY: Duration = Duration::from_secs(5);
+
+/// An event waiting to be delivered.
+struct Event {
+    name: &'static str,
+    message: String,
+    timestamp: String,
+}
+
+/// A failed delivery waiting for its next attempt.
+struct Retry {
+    hook: usize,
+    event: &'static str,
+    body: String,
+    attempt: u32,
+    due: Instant,
+}
+
+/// Webhook dispatcher. Sending is a no-op when no webhooks are configured.
+pub struct Webhooks {
+    sender: Option<Sender<Event>>,
+}
+
+impl Webhooks {
+    /// Starts the delivery thread for `hooks`, reporting `hostname` in
+    /// every event.
+    pub fn start(hooks: &[WebhookConfig], hostname: String) -> Self {
+        if hooks.is_empty() {
+            return Self { sender: None };
+        }
+
+        let (sender, receiver) = mpsc::channel::<Event>();
+        let hooks = hooks.to_vec();
+        let spawned = thread::Builder::new()
+            .name("webhooks".to_string())
+            .spawn(move || deliver_all(&hooks, &receiver, &hostname));
+        match spawned {
+            Ok(_) => Self {
+                sender: Some(sender),
+            },
+            Err(e) => {
+                warn!("Failed to start webhook delivery: {}", e);
+                Self { sender: None }
+            }
+        }
+    }
+
+    /// Queues an event for delivery.
+    pub fn send(&self, event: &'static str, message: impl Into<String>) {
+        let Some(ref sender) = self.sender else {
+            return;
+        };
+        let event = Event {
+            name: event,
+            message: message.into(),
+            timestamp: chrono::Local::now().to_rfc3339(),
+        };
+        debug!("Webhook event {}: {}", event.name, event.message);
+        let _ = sender.send(event);
+    }
+
+    /// Queues the bus events from `receiver` that webhooks can subscribe
+    /// to, until the bus is gone.
+    pub async fn run(self, mut receiver: broadcast::Receiver<events::Event>) {
+        if self.sender.is_none() {
+            return;
+        }
+        while let Some(event) = events::next(&mut receiver, "Webhooks").await {
+            if let Some((kind, message)) = event.notice() {
+                if let Some(name) = event_name(kind) {
+                    self.send(name, message);
+                }
+            }
+        }
+    }
+}
+
+/// Returns the webhook event name for a kind of bus event, if webhooks
+/// are sent for it.
+fn event_name(kind: EventKind) -> Option<&'static str> {
+    match kind {
+        EventKind::Alert => Some(ALERT),
+        EventKind::FaceChanged => Some(FACE_CHANGED),
+        EventKind::DeviceDisconnected => Some(DEVICE_DISCONNECTED),
+        EventKind::DeviceReconnected => Some(DEVICE_RECONNECTED),
+        EventKind::Journal => None,
+    }
+}
+
+/// Whether `hook` wants `event`. An empty event list subscribes to all.
+fn subscribed(hook: &WebhookConfig, event: &str) -> bool {
+    hook.events.is_empty() || hook.events.iter().any(|e| e == event)
+}
+
+/// Delivers queued events until the sender is dropped. Failed deliveries
+/// are retried with a doubling delay while new events keep going out.
+fn deliver_all(hooks: &[WebhookConfig], receiver: &Receiver<Event>, hostname: &str) {
+    let mut retries: Vec<Retry> = Vec::new();
+    loop {
+        let received = match retries.iter().map(|r| r.due).min() {
+            Some(due) => receiver.recv_timeout(due.saturating_duration_since(Instant::now())),
+            None => receiver.recv().map_err(|_| RecvTimeoutError::Disconnected),
+        };
+        match received {
+            Ok(event) => {
+                for (index, hook) in hooks.iter().enumerate() {
+                    if subscribed(hook, event.name) {
+                        let escape_json = hook.content_type.contains("json");
+                        let body =
+                            render_body(hook.template.as_deref(), escape_json, &event, hostname);
+                        attempt(hooks, index, event.name, body, 0, &mut retries);
+                    }
+                }
+            }
+            Err(RecvTimeoutError::Timeout) => {}
+            // The daemon is shutting down; pending retries are dropped
+            Err(RecvTimeoutError::Disconnected) => return,
+        }
+
+        let now = Instant::now();
+        let (due, waiting): (Vec<_>, Vec<_>) = retries.drain(..).partition(|r| r.due <= now);
+        retries = waiting;
+        for retry in due {
+            attempt(
+                hooks,
+                retry.hook,
+                retry.event,
+                retry.body,
+                retry.attempt,
+                &mut retries,
+            );
+        }
+    }
+}
+
+/// Posts `body` to `hooks[hook]`, scheduling a retry on failure until the
+/// hook's retries are used up.
+fn attempt(
+    hooks: &[WebhookConfig],
+    hook: usize,
+    event: &'static str,
+    body: String,
+    attempt: u32,
+    retries: &mut Vec<Retry>,
+) {
+    let config = &hooks[hook];
+    match post(config, &body) {
+        Ok(()) => {}
+        Err(e) => {
+            debug!(
+                "Webhook {} attempt {} failed: {:#}",
+                config.url,
+                attempt + 1,
+                e
+            );
+            if attempt >= config.retries {
+                warn!("Giving up on webhook {} for event {}", config.url, event);
+                return;
+            }
+            retries.push(Retry {
+                hook,
+                event,
+                body,
+                attempt: attempt + 1,
+                due: Instant::now() + RETRY_DELAY * 2u32.saturating_pow(attempt),
+            });
+        }
+    }
+}
+
+fn post(hook: &WebhookConfig, body: &str) -> Result<()> {
+    let headers: Vec<(&str, &str)> = hook
+        .headers
+        .iter()
+        .map(|(name, value)| (name.as_str(), value.as_str()))
+        .collect();
+    http::request(
+        "POST",
+        &hook.url,
+        &headers,
+        Some((hook.content_type.as_str(), body)),
+    )?
+    .check()?;
+    Ok(())
+}
+
+/// Builds the request body. With `escape_json`, template placeholders are
+/// replaced with JSON-escaped values (without quotes) so they can sit inside
+/// JSON strings.
+fn render_body(template: Option<&str>, escape_json: bool, event: &Event, hostname: &str) -> String {
+    let Some(template) = template else {
+        return serde_json::json!({
+            "event": event.name,
+            "message": event.message,
+            "hostname": hostname,
+            "timestamp": event.timestamp,
+        })
+        .to_string();
+    };
+
+    let escape = |value: &str| {
+        if !escape_json {
+            return value.to_string();
+        }
+        let quoted = serde_json::Value::from(value).to_string();
+        quoted[1..quoted.len() - 1].to_string()
+    };
+    // One pass, so placeholders inside substituted values stay as they are
+    let mut body = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        body.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let value = after.find("}}").and_then(|end| {
+            let value = match &after[..end] {
+                "event" => event.name,
+                "message" => &event.message,
+                "hostname" => hostname,
+                "timestamp" => &event.timestamp,
+                _ => return None,
+            };
+            Some((value, end))
+        });
+        match value {
+            Some((value, end)) => {
+                body.push_str(&escape(value));
+                rest = &after[end + 2..];
+            }
+            None => {
+                body.push_str("{{");
+                rest = after;
+            }
+        }
+    }
+    body.push_str(rest);
+    body
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_body() {
+        let event = Event {
+            name: ALERT,
+            message: "tank \"A\" low".to_string(),
+            timestamp: "2024-01-01T00:00:00+00:00".to_string(),
+        };
+        let body = render_body(
+            Some(r#"{"content": "{{hostname}}: {{message}}"}"#),
+            true,
+            &event,
+            "nas",
+        );
+        assert_eq!(body, r#"{"content": "nas: tank \"A\" low"}"#);
+
+        let json: serde_json::Value =
+            serde_json::from_str(&render_body(None, true, &event, "nas")).unwrap();
+        assert_eq!(json["event"], "alert");
+        assert_eq!(json["hostname"], "nas");
+
+        // Placeholders in values are not expanded; unknown ones are kept
+        let event = Event {
+            message: "{{hostname}}".to_string(),
+            ..event
+        };
+        let body = render_body(Some("{{message}} {{other}} {{event"), false, &event, "nas");
+        assert_eq!(body, "{{hostname}} {{other}} {{event");
+    }
+
+    #[test]
+    fn test_retry_does_not_block_delivery() {
+        use std::io::{Read, Write};
+        use std::net::TcpListener;
+
+        // Answers the first request with a 500, later ones with a 200
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+        let (sender, received) = mpsc::channel();
+        thread::spawn(move || {
+            for (index, stream) in listener.incoming().enumerate() {
+                let mut stream = stream.unwrap();
+                // Read until the (non-empty) body after the headers arrives
+                let mut request = String::new();
+                let mut buf = [0; 4096];
+                while request
+                    .split_once("\r\n\r\n")
+                    .is_none_or(|(_, body)| body.is_empty())
+                {
+                    let len = stream.read(&mut buf).unwrap();
+                    request.push_str(&String::from_utf8_lossy(&buf[..len]));
+                }
+                let status = if index == 0 { "500 Error" } else { "200 OK" };
+                let _ = write!(stream, "HTTP/1.1 {status}\r\nContent-Length: 0\r\n\r\n");
+                let _ = sender.send(request);
+            }
+        });
+
+        let hook: WebhookConfig = toml::from_str(&format!(
+            "url = \"{url}\"\nretries = 1\ntemplate = \"{{{{message}}}}\"\ncontent_type = \"text/plain\""
+        ))
+        .unwrap();
+        let webhooks = Webhooks::start(&[hook], "nas".to_string());
+        webhooks.send(ALERT, "first");
+        webhooks.send(ALERT, "second");
+
+        // The second event goes out while the first waits for its retry
+        let timeout = Duration::from_secs(2);
+        assert!(received.recv_timeout(timeout).unwrap().ends_with("first"));
+        assert!(received.recv_timeout(timeout).unwrap().ends_with("second"));
+    }
+}
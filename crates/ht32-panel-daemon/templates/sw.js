@@ -0,0 +1,93 @@
+// HT32 Panel service worker.
+//
+// Caches the app shell so the UI opens without the daemon, and answers GET
+// requests from the network when possible, falling back to the last cached
+// response when offline. Responses served from the cache carry an
+// X-HT32-Offline header so the page can say the state may be stale.
+
+const CACHE = 'ht32-panel-{{ version }}';
+const SHELL = [
+    '/',
+    '/editor',
+    '/manifest.webmanifest',
+    '/icons/180.png',
+    '/icons/192.png',
+    '/icons/512.png',
+];
+const HTMX = 'https://unpkg.com/htmx.org@2.0.4';
+
+self.addEventListener('install', event => {
+    event.waitUntil((async () => {
+        const cache = await caches.open(CACHE);
+        await cache.addAll(SHELL);
+        // Cross-origin, so stored as an opaque response
+        try {
+            await cache.put(HTMX, await fetch(HTMX, { mode: 'no-cors' }));
+        } catch (e) {
+            // Cached later if the page loads it while online
+        }
+        await self.skipWaiting();
+    })());
+});
+
+self.addEventListener('activate', event => {
+    event.waitUntil((async () => {
+        for (const key of await caches.keys()) {
+            if (key !== CACHE) {
+                await caches.delete(key);
+            }
+        }
+        await self.clients.claim();
+    })());
+});
+
+// Previews are requested with a ?t= cache buster; store them under one key
+function cacheKey(request) {
+    const url = new URL(request.url);
+    if (url.origin === self.location.origin) {
+        url.search = '';
+        return url.href;
+    }
+    return request.url;
+}
+
+async function offline(request) {
+    const cache = await caches.open(CACHE);
+    let cached = await cache.match(cacheKey(request));
+    if (!cached && request.mode === 'navigate') {
+        cached = await cache.match('/');
+    }
+    if (!cached) {
+        return new Response('Panel is unreachable', {
+            status: 503,
+            headers: { 'X-HT32-Offline': '1' },
+        });
+    }
+    if (cached.type === 'opaque') {
+        return cached;
+    }
+    const headers = new Headers(cached.headers);
+    headers.set('X-HT32-Offline', '1');
+    return new Response(cached.body, { status: cached.status, headers: headers });
+}
+
+self.addEventListener('fetch', event => {
+    const request = event.request;
+    const url = new URL(request.url);
+    // Changes and the live event stream always go to the daemon
+    if (request.method !== 'GET' || url.pathname === '/events') {
+        return;
+    }
+    event.respondWith((async () => {
+        try {
+            const response = await fetch(request);
+            if (response.ok || response.type === 'opaque') {
+                const cache = await caches.open(CACHE);
+                await cache.put(cacheKey(request), response.clone());
+            }
+            return response;
+        } catch (e) {
+            return offline(request);
+        }
+    })());
+});
@@ -0,0 +1,66 @@
+//! Benchmarks of getting a frame to the LCD: pixel conversion and the
+//! chunked HID transfer, with writes going to a sink instead of a device.
+//!
+//! Run with `cargo bench -p ht32-panel-hw`.
+
+use std::hint::black_box;
+use std::io::Write;
+
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use ht32_panel_hw::lcd::RedrawPackets;
+use ht32_panel_hw::{Framebuffer, LCD_HEIGHT, LCD_WIDTH};
+
+/// RGBA pixels of a full frame with a gradient, so every pixel differs.
+fn rgba_frame() -> Vec<u8> {
+    let (width, height) = (LCD_WIDTH as usize, LCD_HEIGHT as usize);
+    let mut rgba = Vec::with_capacity(width * height * 4);
+    for y in 0..height {
+        for x in 0..width {
+            rgba.extend_from_slice(&[(x * 255 / width) as u8, (y * 255 / height) as u8, 128, 255]);
+        }
+    }
+    rgba
+}
+
+fn conversion(c: &mut Criterion) {
+    let rgba = rgba_frame();
+    let mut framebuffer = Framebuffer::new();
+    let mut group = c.benchmark_group("conversion");
+    group.throughput(Throughput::Elements(rgba.len() as u64 / 4));
+
+    group.bench_function("rgba8_to_rgb565", |b| {
+        b.iter(|| framebuffer.copy_from_rgba8(black_box(&rgba)).unwrap())
+    });
+    framebuffer.copy_from_rgba8(&rgba).unwrap();
+    group.bench_function("rgb565_to_rgba8", |b| {
+        b.iter(|| black_box(framebuffer.to_rgba8()))
+    });
+    group.bench_function("rotate_180", |b| b.iter(|| framebuffer.rotate_180()));
+    group.finish();
+}
+
+fn transfer(c: &mut Criterion) {
+    let mut framebuffer = Framebuffer::new();
+    framebuffer.copy_from_rgba8(&rgba_frame()).unwrap();
+    let mut group = c.benchmark_group("transfer");
+    group.throughput(Throughput::Bytes(framebuffer.data().len() as u64 * 2));
+
+    group.bench_function("redraw_packets", |b| {
+        // Stands in for the device: each packet is copied out, as a HID
+        // write copies it to the kernel
+        let mut packets = RedrawPackets::new();
+        let mut device = Vec::new();
+        b.iter(|| {
+            device.clear();
+            packets.fill(black_box(framebuffer.data()), false);
+            for packet in packets.packets() {
+                device.write_all(packet).unwrap();
+            }
+            black_box(&device);
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, conversion, transfer);
+criterion_main!(benches);
@@ -0,0 +1,168 @@
+//! Device permission checks and udev rules.
+//!
+//! Without udev rules only root may open the panel's device nodes, and
+//! opening them fails with the same error as a missing device. These
+//! helpers find the nodes through sysfs, check whether the current user can
+//! open them and generate the rules that grant access.
+
+use std::fs::{self, OpenOptions};
+use std::os::unix::fs::MetadataExt;
+use std::path::{Path, PathBuf};
+
+use crate::{LCD_PID, LCD_VID};
+
+/// USB VID:PID of the CH340 serial adapter driving the LEDs.
+pub const LED_VID: u16 = 0x1A86;
+pub const LED_PID: u16 = 0x7523;
+
+/// Group granted access by the packaged udev rules.
+pub const DEFAULT_GROUP: &str = "plugdev";
+
+/// Symlink the udev rules create for the LED serial adapter.
+pub const LED_SYMLINK: &str = "/dev/ht32-led";
+
+/// Whether a device node can be opened.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Access {
+    /// The device is not present.
+    Missing,
+    /// The node can be opened for reading and writing.
+    Granted(PathBuf),
+    /// The node exists but cannot be opened.
+    Denied {
+        /// Device node
+        path: PathBuf,
+        /// Owner, group and mode of the node
+        detail: String,
+    },
+}
+
+/// Returns udev rules giving `group` read and write access to the LCD and
+/// the LED serial adapter.
+pub fn udev_rules(group: &str) -> String {
+    let lcd = format!(
+        "ATTR{{idVendor}}==\"{:04x}\", ATTR{{idProduct}}==\"{:04x}\"",
+        LCD_VID, LCD_PID
+    );
+    format!(
+        "# HT32 Panel LCD (VID:PID {:04X}:{:04X})\n\
+         SUBSYSTEM==\"usb\", {lcd}, MODE=\"0660\", GROUP=\"{group}\"\n\
+         SUBSYSTEM==\"hidraw\", {lcds}, MODE=\"0660\", GROUP=\"{group}\"\n\
+         \n\
+         # CH340 serial adapter for LED strip\n\
+         SUBSYSTEM==\"tty\", ATTRS{{idVendor}}==\"{:04x}\", ATTRS{{idProduct}}==\"{:04x}\", \
+         MODE=\"0660\", GROUP=\"{group}\", SYMLINK+=\"{}\"\n",
+        LCD_VID,
+        LCD_PID,
+        LED_VID,
+        LED_PID,
+        LED_SYMLINK.trim_start_matches("/dev/"),
+        lcd = lcd,
+        lcds = lcd.replace("ATTR{", "ATTRS{"),
+        group = group,
+    )
+}
+
+/// Checks access to the LCD's device node: the USB device node the hidapi
+/// libusb backend opens, or the hidraw node of the hidraw backend.
+pub fn lcd_access() -> Access {
+    #[cfg(feature = "hidraw")]
+    let node = crate::hid::enumerate(LCD_VID, LCD_PID)
+        .ok()
+        .and_then(|interfaces| interfaces.into_iter().next())
+        .map(|interface| PathBuf::from(interface.path));
+    #[cfg(not(feature = "hidraw"))]
+    let node = find_usb_node(LCD_VID, LCD_PID);
+    match node {
+        Some(path) => node_access(&path),
+        None => Access::Missing,
+    }
+}
+
+/// Checks access to the serial port at `path`.
+pub fn serial_access(path: &str) -> Access {
+    let path = Path::new(path);
+    if path.exists() {
+        node_access(path)
+    } else {
+        Access::Missing
+    }
+}
+
+/// Finds /dev/bus/usb/BBB/DDD of the first USB device with `vid`:`pid`.
+#[cfg(not(feature = "hidraw"))]
+fn find_usb_node(vid: u16, pid: u16) -> Option<PathBuf> {
+    fs::read_dir("/sys/bus/usb/devices")
+        .ok()?
+        .flatten()
+        .find_map(|entry| {
+            let dir = entry.path();
+            let read = |name: &str| fs::read_to_string(dir.join(name)).ok();
+            let hex = |name: &str| u16::from_str_radix(read(name)?.trim(), 16).ok();
+            let number = |name: &str| read(name)?.trim().parse::<u32>().ok();
+            if hex("idVendor")? != vid || hex("idProduct")? != pid {
+                return None;
+            }
+            Some(PathBuf::from(format!(
+                "/dev/bus/usb/{:03}/{:03}",
+                number("busnum")?,
+                number("devnum")?
+            )))
+        })
+}
+
+/// Tries to open `path` for reading and writing.
+pub(crate) fn node_access(path: &Path) -> Access {
+    match OpenOptions::new().read(true).write(true).open(path) {
+        Ok(_) => Access::Granted(path.to_path_buf()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Access::Missing,
+        Err(e) => {
+            let detail = match fs::metadata(path) {
+                Ok(meta) => format!(
+                    "owner {}, group {}, mode {:o}",
+                    meta.uid(),
+                    group_name(meta.gid()).unwrap_or_else(|| meta.gid().to_string()),
+                    meta.mode() & 0o777
+                ),
+                Err(_) => e.to_string(),
+            };
+            Access::Denied {
+                path: path.to_path_buf(),
+                detail,
+            }
+        }
+    }
+}
+
+/// Looks up the name of group `gid` in /etc/group.
+fn group_name(gid: u32) -> Option<String> {
+    let groups = fs::read_to_string("/etc/group").ok()?;
+    groups.lines().find_map(|line| {
+        let mut fields = line.split(':');
+        let name = fields.next()?;
+        let id = fields.nth(1)?.parse::<u32>().ok()?;
+        (id == gid).then(|| name.to_string())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_udev_rules_match_packaged_file() {
+        let packaged = include_str!("../../../99-ht32-panel.rules");
+        assert_eq!(udev_rules(DEFAULT_GROUP), packaged);
+    }
+
+    #[test]
+    fn test_udev_rules_group() {
+        let rules = udev_rules("ht32-panel");
+        assert_eq!(rules.matches("GROUP=\"ht32-panel\"").count(), 3);
+    }
+
+    #[test]
+    fn test_missing_serial_port() {
+        assert_eq!(serial_access("/dev/does-not-exist"), Access::Missing);
+    }
+}
@@ -16,9 +16,17 @@ pub enum Error {
     #[error("LED device not found at {0}")]
     LedNotFound(String),
 
+    /// A device node exists but the current user may not open it.
+    #[error("No permission to open {path} ({detail}); install the udev rules and add the user to their group")]
+    PermissionDenied { path: String, detail: String },
+
     /// USB HID communication error.
     #[error("USB HID error: {0}")]
-    Hid(#[from] hidapi::HidError),
+    Hid(String),
+
+    /// Invalid USB transfer settings.
+    #[error("Invalid transfer settings: {0}")]
+    InvalidTransfer(String),
 
     /// Serial port communication error.
     #[error("Serial port error: {0}")]
@@ -28,14 +36,6 @@ pub enum Error {
     #[error("Serial I/O error: {0}")]
     SerialIo(#[from] std::io::Error),
 
-    /// Invalid orientation value.
-    #[error("Invalid orientation: {0}")]
-    InvalidOrientation(String),
-
-    /// Invalid LED theme value.
-    #[error("Invalid LED theme: {0}")]
-    InvalidTheme(u8),
-
     /// Invalid LED intensity or speed value.
     #[error("Invalid LED value (must be 1-5): {0}")]
     InvalidLedValue(u8),
@@ -0,0 +1,59 @@
+//! hidapi backend, using libusb on Linux.
+
+use std::ffi::CString;
+
+use ::hidapi::{HidApi, HidError};
+
+use super::HidInterface;
+use crate::{Error, Result};
+
+impl From<HidError> for Error {
+    fn from(e: HidError) -> Self {
+        Error::Hid(e.to_string())
+    }
+}
+
+/// Lists the HID interfaces of the devices with `vid`:`pid`.
+pub(crate) fn enumerate(vid: u16, pid: u16) -> Result<Vec<HidInterface>> {
+    let api = HidApi::new()?;
+    Ok(api
+        .device_list()
+        .filter(|d| d.vendor_id() == vid && d.product_id() == pid)
+        .map(|d| HidInterface {
+            path: d.path().to_string_lossy().into_owned(),
+            number: d.interface_number(),
+        })
+        .collect())
+}
+
+/// An open HID interface.
+pub(crate) struct HidDevice(::hidapi::HidDevice);
+
+impl HidDevice {
+    /// Opens the interface at `path`. Reads wait for a report unless
+    /// `blocking` is false.
+    pub fn open(path: &str, blocking: bool) -> Result<Self> {
+        let api = HidApi::new()?;
+        let path = CString::new(path).map_err(|e| Error::Hid(e.to_string()))?;
+        let device = api.open_path(&path)?;
+        if !blocking {
+            device.set_blocking_mode(false)?;
+        }
+        Ok(Self(device))
+    }
+
+    /// Writes a report. The first byte is the report ID, 0 for none.
+    pub fn write(&self, data: &[u8]) -> Result<usize> {
+        Ok(self.0.write(data)?)
+    }
+
+    /// Sends a feature report. The first byte is the report ID, 0 for none.
+    pub fn send_feature_report(&self, data: &[u8]) -> Result<()> {
+        Ok(self.0.send_feature_report(data)?)
+    }
+
+    /// Reads a report, returning 0 when a non-blocking interface has none.
+    pub fn read(&self, buf: &mut [u8]) -> Result<usize> {
+        Ok(self.0.read(buf)?)
+    }
+}
@@ -0,0 +1,143 @@
+//! hidraw backend: reads and writes `/dev/hidraw*` directly.
+//!
+//! Interfaces are found through sysfs. Each `/sys/class/hidraw/hidrawN`
+//! links to its HID device, whose `uevent` names the vendor and product and
+//! whose parent is the USB interface with its `bInterfaceNumber`.
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{ErrorKind, Read, Write};
+use std::os::fd::AsRawFd;
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::Path;
+
+use super::HidInterface;
+use crate::access::{self, Access};
+use crate::{Error, Result};
+
+/// sysfs directory listing the hidraw nodes.
+const SYSFS_HIDRAW: &str = "/sys/class/hidraw";
+
+/// Lists the HID interfaces of the devices with `vid`:`pid`.
+pub(crate) fn enumerate(vid: u16, pid: u16) -> Result<Vec<HidInterface>> {
+    let entries = match fs::read_dir(SYSFS_HIDRAW) {
+        Ok(entries) => entries,
+        // No hidraw driver loaded, so no devices
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(Error::Hid(e.to_string())),
+    };
+    let mut interfaces: Vec<HidInterface> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let device = entry.path().join("device");
+            let uevent = fs::read_to_string(device.join("uevent")).ok()?;
+            if parse_hid_id(&uevent)? != (vid, pid) {
+                return None;
+            }
+            let number = fs::read_to_string(device.join("../bInterfaceNumber")).ok()?;
+            Some(HidInterface {
+                path: format!("/dev/{}", entry.file_name().to_string_lossy()),
+                number: i32::from_str_radix(number.trim(), 16).ok()?,
+            })
+        })
+        .collect();
+    interfaces.sort_by_key(|interface| interface.number);
+    Ok(interfaces)
+}
+
+/// Parses the vendor and product IDs from the `HID_ID=0003:000004D9:0000FD01`
+/// line of a HID device's uevent.
+fn parse_hid_id(uevent: &str) -> Option<(u16, u16)> {
+    let id = uevent
+        .lines()
+        .find_map(|line| line.strip_prefix("HID_ID="))?;
+    let mut fields = id.trim().split(':').skip(1);
+    let mut hex = || u16::try_from(u32::from_str_radix(fields.next()?, 16).ok()?).ok();
+    Some((hex()?, hex()?))
+}
+
+/// `HIDIOCSFEATURE(len)` from `linux/hidraw.h`: `_IOC(_IOC_WRITE |
+/// _IOC_READ, 'H', 0x06, len)`.
+fn hidiocsfeature(len: usize) -> libc::c_ulong {
+    const IOC_READ_WRITE: libc::c_ulong = 3;
+    (IOC_READ_WRITE << 30) | ((len as libc::c_ulong) << 16) | ((b'H' as libc::c_ulong) << 8) | 0x06
+}
+
+/// Converts an I/O error on a hidraw node.
+fn hid_error(e: std::io::Error) -> Error {
+    Error::Hid(e.to_string())
+}
+
+/// An open hidraw node.
+pub(crate) struct HidDevice {
+    file: File,
+}
+
+impl HidDevice {
+    /// Opens the hidraw node at `path`. Reads wait for a report unless
+    /// `blocking` is false.
+    pub fn open(path: &str, blocking: bool) -> Result<Self> {
+        let mut options = OpenOptions::new();
+        options.read(true).write(true);
+        if !blocking {
+            options.custom_flags(libc::O_NONBLOCK);
+        }
+        let file = options.open(path).map_err(|e| match e.kind() {
+            ErrorKind::NotFound => Error::LcdNotFound,
+            ErrorKind::PermissionDenied => match access::node_access(Path::new(path)) {
+                Access::Denied { path, detail } => Error::PermissionDenied {
+                    path: path.display().to_string(),
+                    detail,
+                },
+                _ => hid_error(e),
+            },
+            _ => hid_error(e),
+        })?;
+        Ok(Self { file })
+    }
+
+    /// Writes a report. The first byte is the report ID, 0 for none, as
+    /// with hidapi.
+    pub fn write(&self, data: &[u8]) -> Result<usize> {
+        (&self.file).write(data).map_err(hid_error)
+    }
+
+    /// Sends a feature report. The first byte is the report ID, 0 for none.
+    pub fn send_feature_report(&self, data: &[u8]) -> Result<()> {
+        // SAFETY: the kernel reads at most `data.len()` bytes from `data`
+        let ret = unsafe {
+            libc::ioctl(
+                self.file.as_raw_fd(),
+                hidiocsfeature(data.len()) as _,
+                data.as_ptr(),
+            )
+        };
+        if ret < 0 {
+            return Err(hid_error(std::io::Error::last_os_error()));
+        }
+        Ok(())
+    }
+
+    /// Reads a report, returning 0 when a non-blocking node has none.
+    pub fn read(&self, buf: &mut [u8]) -> Result<usize> {
+        match (&self.file).read(buf) {
+            Ok(len) => Ok(len),
+            Err(e) if e.kind() == ErrorKind::WouldBlock => Ok(0),
+            Err(e) => Err(hid_error(e)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_hid_id() {
+        let uevent = "DRIVER=hid-generic\nHID_ID=0003:000004D9:0000FD01\n\
+                      HID_NAME=HT32 Panel\nHID_PHYS=usb-0000:00:14.0-8/input1\n";
+        assert_eq!(parse_hid_id(uevent), Some((0x04D9, 0xFD01)));
+        assert_eq!(parse_hid_id("HID_ID=0003:000004D9\n"), None);
+        assert_eq!(parse_hid_id("DRIVER=hid-generic\n"), None);
+        assert_eq!(hidiocsfeature(9), 0xC009_4806);
+    }
+}
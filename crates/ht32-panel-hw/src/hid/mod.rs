@@ -0,0 +1,34 @@
+//! USB HID backends.
+//!
+//! The panel is driven through hidapi by default. The `hidraw` feature
+//! reads and writes `/dev/hidraw*` directly instead, with no C library to
+//! link, which eases cross-compiling, e.g. to aarch64. It takes precedence
+//! when both features are enabled. Without either, the crate is built
+//! without [`LcdDevice`](crate::LcdDevice), for tools that only need the
+//! framebuffer and protocol.
+
+#[cfg(all(feature = "hidapi", not(feature = "hidraw")))]
+mod hidapi;
+#[cfg(feature = "hidraw")]
+mod hidraw;
+
+#[cfg(all(feature = "hidapi", not(feature = "hidraw")))]
+pub(crate) use self::hidapi::{enumerate, HidDevice};
+#[cfg(feature = "hidraw")]
+pub(crate) use self::hidraw::{enumerate, HidDevice};
+
+/// Name of the HID backend in use, "hidapi" or "hidraw".
+#[cfg(feature = "hidraw")]
+pub const BACKEND: &str = "hidraw";
+/// Name of the HID backend in use, "hidapi" or "hidraw".
+#[cfg(not(feature = "hidraw"))]
+pub const BACKEND: &str = "hidapi";
+
+/// A HID interface of a USB device.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct HidInterface {
+    /// Path the backend opens the interface with
+    pub path: String,
+    /// USB interface number
+    pub number: i32,
+}
@@ -0,0 +1,177 @@
+//! Changed regions between frames.
+//!
+//! The firmware has no compressed transfer, but it takes partial refreshes
+//! of rectangles up to 255x255 pixels and 4096 pixel bytes. Faces that are
+//! mostly static, such as a clock, change a few lines per frame, which fit in
+//! far fewer bytes than a full redraw. Refresh packets are no larger than
+//! the transfer's redraw reports, so hubs that need smaller reports get them.
+
+use super::protocol::{HEADER_SIZE, REPORT_SIZE};
+use crate::{LCD_HEIGHT, LCD_WIDTH};
+
+/// Widest and tallest refresh a packet can describe.
+const MAX_REFRESH_SIDE: usize = u8::MAX as usize;
+
+/// A rectangle of the display, in the device's pixel order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Region {
+    pub x: u16,
+    pub y: u16,
+    pub width: u8,
+    pub height: u8,
+}
+
+impl Region {
+    /// Copies the region's pixels out of a full frame, row by row.
+    pub fn pixels(&self, frame: &[u16]) -> Vec<u16> {
+        let stride = LCD_WIDTH as usize;
+        let (x, y) = (self.x as usize, self.y as usize);
+        (y..y + self.height as usize)
+            .flat_map(|row| &frame[row * stride + x..row * stride + x + self.width as usize])
+            .copied()
+            .collect()
+    }
+}
+
+/// Finds the parts of `current` that differ from `previous`, both full
+/// frames, as regions that each fit in one refresh packet of `chunk_size`
+/// pixel bytes.
+///
+/// Runs of changed lines are covered by the columns that changed in them.
+/// Returns `None` when the refresh packets would take `max_bytes` or more
+/// on the wire, so a full redraw of that size is as cheap.
+pub fn changed_regions(
+    previous: &[u16],
+    current: &[u16],
+    chunk_size: usize,
+    max_bytes: usize,
+) -> Option<Vec<Region>> {
+    let packet_size = REPORT_SIZE + HEADER_SIZE + chunk_size;
+    let stride = LCD_WIDTH as usize;
+    let mut regions = Vec::new();
+    // First row, and first and last changed columns, of the current band
+    let mut band: Option<(usize, usize, usize)> = None;
+
+    for row in 0..=LCD_HEIGHT as usize {
+        let changed = (row < LCD_HEIGHT as usize)
+            .then(|| {
+                let line = row * stride..(row + 1) * stride;
+                let (old, new) = (&previous[line.clone()], &current[line]);
+                let first = old.iter().zip(new).position(|(a, b)| a != b)?;
+                let last = old.iter().zip(new).rposition(|(a, b)| a != b)?;
+                Some((first, last))
+            })
+            .flatten();
+
+        band = match (band, changed) {
+            (Some((top, left, right)), Some((first, last))) => {
+                Some((top, left.min(first), right.max(last)))
+            }
+            (None, Some((first, last))) => Some((row, first, last)),
+            (Some((top, left, right)), None) => {
+                let width = right - left + 1;
+                tile(&mut regions, left, top, width, row - top, chunk_size / 2);
+                if regions.len() * packet_size >= max_bytes {
+                    return None;
+                }
+                None
+            }
+            (None, None) => None,
+        };
+    }
+    Some(regions)
+}
+
+/// Splits a band into regions of at most `max_pixels` that each fit in a
+/// refresh packet.
+fn tile(
+    regions: &mut Vec<Region>,
+    x: usize,
+    y: usize,
+    width: usize,
+    height: usize,
+    max_pixels: usize,
+) {
+    let columns = width.div_ceil(MAX_REFRESH_SIDE.min(max_pixels));
+    let tile_width = width.div_ceil(columns);
+    let tile_height = (max_pixels / tile_width).min(MAX_REFRESH_SIDE);
+
+    for top in (y..y + height).step_by(tile_height) {
+        for left in (x..x + width).step_by(tile_width) {
+            regions.push(Region {
+                x: left as u16,
+                y: top as u16,
+                width: tile_width.min(x + width - left) as u8,
+                height: tile_height.min(y + height - top) as u8,
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Bytes of a full redraw in 4096-byte chunks.
+    const FULL_REDRAW: usize = 27 * 4105;
+
+    fn region(x: u16, y: u16, width: u8, height: u8) -> Region {
+        Region {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    #[test]
+    fn test_changed_regions() {
+        let previous = vec![0u16; LCD_WIDTH as usize * LCD_HEIGHT as usize];
+        assert_eq!(
+            changed_regions(&previous, &previous, 4096, FULL_REDRAW),
+            Some(vec![])
+        );
+
+        // Two digits of a clock change in rows 40-59, columns 100-179
+        let mut current = previous.clone();
+        for row in 40..60 {
+            current[row * 320 + 100] = 0xFFFF;
+            current[row * 320 + 179] = 0xFFFF;
+        }
+        let regions = changed_regions(&previous, &current, 4096, FULL_REDRAW).unwrap();
+        assert_eq!(regions, [region(100, 40, 80, 20)]);
+        assert_eq!(regions[0].pixels(&current).len(), 80 * 20);
+        assert_eq!(regions[0].pixels(&current)[79], 0xFFFF);
+
+        // A full-width change is split in halves
+        current[5 * 320] = 1;
+        current[5 * 320 + 319] = 1;
+        let regions = changed_regions(&previous, &current, 4096, FULL_REDRAW).unwrap();
+        assert_eq!(
+            regions,
+            [
+                region(0, 5, 160, 1),
+                region(160, 5, 160, 1),
+                region(100, 40, 80, 20)
+            ]
+        );
+
+        // A whole new frame is left to a full redraw
+        let inverted: Vec<u16> = previous.iter().map(|p| !p).collect();
+        assert_eq!(
+            changed_regions(&previous, &inverted, 4096, FULL_REDRAW),
+            None
+        );
+
+        // Smaller reports carry 512 pixels, six rows of the clock digits
+        let regions = changed_regions(&previous, &current, 1024, 107 * 1033).unwrap();
+        assert_eq!(regions.len(), 2 + 4);
+        assert!(regions
+            .iter()
+            .all(|r| r.width as usize * r.height as usize <= 512));
+        assert_eq!(regions[2], region(100, 40, 80, 6));
+        assert_eq!(regions[5], region(100, 58, 80, 2));
+        // Six 1033-byte packets cost more than a 6000-byte redraw
+        assert_eq!(changed_regions(&previous, &current, 1024, 6000), None);
+    }
+}
@@ -1,27 +1,43 @@
 //! LCD device communication via USB HID.
 
-use crate::orientation::Orientation;
+use crate::access::{self, Access};
+use crate::hid::{self, HidDevice};
+use crate::Orientation;
 use crate::{Error, Result, LCD_PID, LCD_VID};
-use hidapi::{HidApi, HidDevice};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Mutex;
-use tracing::{debug, info};
+use std::time::{Duration, Instant};
+use tracing::{debug, info, warn};
 
+use super::delta::{changed_regions, Region};
 use super::framebuffer::Framebuffer;
 use super::protocol::{
-    build_heartbeat_packet, build_orientation_packet, build_redraw_chunk, build_refresh_packet,
-    CHUNK_COUNT, DATA_SIZE,
+    build_heartbeat_packet, build_orientation_packet, build_refresh_packet, RedrawPackets,
+    DATA_SIZE,
 };
+use super::transfer::{ReportKind, TransferSettings};
+
+/// Frames sent with each candidate while tuning the transfer.
+const PROBE_FRAMES: u32 = 3;
 
 /// LCD device controller.
 pub struct LcdDevice {
     device: Mutex<HidDevice>,
     current_orientation: Mutex<Orientation>,
+    /// Packets reused by every full redraw
+    redraw_packets: Mutex<RedrawPackets>,
+    /// How redraws are sent
+    transfer: Mutex<TransferSettings>,
+    /// Whether [`LcdDevice::update`] may send only the changed regions
+    partial_updates: AtomicBool,
+    /// Last frame sent, in the device's pixel order, if known
+    last_frame: Mutex<Option<Vec<u16>>>,
 }
 
 /// The HID interface number used for LCD data transfer.
 /// The device has multiple interfaces; interface 1 is for display data.
 /// Reference implementation uses interface 1 (path "1-8:1.1").
-const LCD_INTERFACE: i32 = 1;
+pub(crate) const LCD_INTERFACE: i32 = 1;
 
 impl LcdDevice {
     /// Opens the LCD device by VID:PID.
@@ -29,13 +45,8 @@ impl LcdDevice {
     /// The device has multiple HID interfaces. This function finds and opens
     /// the correct interface for display control (interface 1).
     pub fn open() -> Result<Self> {
-        let api = HidApi::new()?;
-
         // Enumerate all devices to find the correct interface
-        let devices: Vec<_> = api
-            .device_list()
-            .filter(|d| d.vendor_id() == LCD_VID && d.product_id() == LCD_PID)
-            .collect();
+        let devices = hid::enumerate(LCD_VID, LCD_PID)?;
 
         if devices.is_empty() {
             return Err(Error::LcdNotFound);
@@ -45,28 +56,34 @@ impl LcdDevice {
         for dev in &devices {
             debug!(
                 "Found HID device: path={:?}, interface={}",
-                dev.path(),
-                dev.interface_number()
+                dev.path, dev.number
             );
         }
 
         // Find the interface we need (interface 1 for display data)
         let device_info = devices
             .iter()
-            .find(|d| d.interface_number() == LCD_INTERFACE)
+            .find(|d| d.number == LCD_INTERFACE)
             .or_else(|| devices.first()) // Fallback to first device if interface 2 not found
             .ok_or(Error::LcdNotFound)?;
 
-        let device = device_info.open_device(&api).map_err(|e| {
+        let device = HidDevice::open(&device_info.path, true).map_err(|e| {
             debug!("Failed to open device: {}", e);
-            Error::LcdNotFound
+            match access::lcd_access() {
+                Access::Denied { path, detail } => Error::PermissionDenied {
+                    path: path.display().to_string(),
+                    detail,
+                },
+                _ => Error::LcdNotFound,
+            }
         })?;
 
         info!(
-            "LCD device opened (VID:{:04X} PID:{:04X}, interface={})",
+            "LCD device opened (VID:{:04X} PID:{:04X}, interface={}, {})",
             LCD_VID,
             LCD_PID,
-            device_info.interface_number()
+            device_info.number,
+            hid::BACKEND
         );
 
         // Initial cooldown period - device needs time to initialize after opening
@@ -77,31 +94,179 @@ impl LcdDevice {
         Ok(Self {
             device: Mutex::new(device),
             current_orientation: Mutex::new(Orientation::default()),
+            redraw_packets: Mutex::new(RedrawPackets::new()),
+            transfer: Mutex::new(TransferSettings::default()),
+            partial_updates: AtomicBool::new(false),
+            last_frame: Mutex::new(None),
         })
     }
 
     /// Opens a specific LCD device by path.
     pub fn open_path(path: &str) -> Result<Self> {
-        let api = HidApi::new()?;
-
-        let device = api
-            .open_path(std::ffi::CString::new(path).unwrap().as_c_str())
-            .map_err(|_| Error::LcdNotFound)?;
+        let device = HidDevice::open(path, true).map_err(|_| Error::LcdNotFound)?;
 
         info!("LCD device opened at path: {}", path);
 
         Ok(Self {
             device: Mutex::new(device),
             current_orientation: Mutex::new(Orientation::default()),
+            redraw_packets: Mutex::new(RedrawPackets::new()),
+            transfer: Mutex::new(TransferSettings::default()),
+            partial_updates: AtomicBool::new(false),
+            last_frame: Mutex::new(None),
         })
     }
 
+    /// Sets how redraws are sent.
+    pub fn set_transfer(&self, settings: TransferSettings) -> Result<()> {
+        settings.validate()?;
+        let mut packets = self.redraw_packets.lock().unwrap();
+        if packets.chunk_size() != settings.chunk_size {
+            *packets = RedrawPackets::with_chunk_size(settings.chunk_size);
+        }
+        *self.transfer.lock().unwrap() = settings;
+        debug!("Transfer settings: {}", settings);
+        Ok(())
+    }
+
+    /// Gets how redraws are sent.
+    pub fn transfer(&self) -> TransferSettings {
+        *self.transfer.lock().unwrap()
+    }
+
+    /// Finds the fastest of `candidates` that the device takes without
+    /// errors and switches to it.
+    ///
+    /// Each candidate sends a few black frames and a heartbeat; it fails on
+    /// any write error or short write. Corruption the host never hears about
+    /// can't be detected, so hubs that garble frames silently still need the
+    /// settings chosen by hand. When every candidate fails, the settings in
+    /// use before are restored and the last error returned.
+    pub fn tune_transfer(&self, candidates: &[TransferSettings]) -> Result<TransferSettings> {
+        let previous = self.transfer();
+        let black = Framebuffer::new();
+        let mut fastest: Option<(TransferSettings, Duration)> = None;
+        let mut last_error = Error::InvalidTransfer("no candidates to try".to_string());
+
+        for &candidate in candidates {
+            self.set_transfer(candidate)?;
+            let started = Instant::now();
+            let probe = (0..PROBE_FRAMES)
+                .try_for_each(|_| self.redraw(&black))
+                .and_then(|()| self.heartbeat());
+            let frame_time = started.elapsed() / PROBE_FRAMES;
+            match probe {
+                Ok(()) => {
+                    debug!("Transfer with {}: {:?} per frame", candidate, frame_time);
+                    if fastest.is_none_or(|(_, best)| frame_time < best) {
+                        fastest = Some((candidate, frame_time));
+                    }
+                }
+                Err(e) => {
+                    debug!("Transfer with {} failed: {}", candidate, e);
+                    last_error = e;
+                }
+            }
+        }
+
+        match fastest {
+            Some((settings, frame_time)) => {
+                self.set_transfer(settings)?;
+                info!(
+                    "Tuned LCD transfer: {} ({:?} per frame)",
+                    settings, frame_time
+                );
+                Ok(settings)
+            }
+            None => {
+                warn!("No transfer settings worked, keeping {}", previous);
+                self.set_transfer(previous)?;
+                Err(last_error)
+            }
+        }
+    }
+
+    /// Lets [`LcdDevice::update`] send only the parts of a frame that
+    /// changed, as partial refreshes.
+    pub fn set_partial_updates(&self, enabled: bool) {
+        self.partial_updates.store(enabled, Ordering::Relaxed);
+        *self.last_frame.lock().unwrap() = None;
+    }
+
+    /// Returns true when partial updates are on.
+    pub fn partial_updates(&self) -> bool {
+        self.partial_updates.load(Ordering::Relaxed)
+    }
+
+    /// Sends a frame. With partial updates on, only the regions that
+    /// changed since the last frame are sent, in packets no larger than the
+    /// transfer's reports, unless that takes as many bytes as a full redraw. A failed partial refresh turns partial
+    /// updates off and falls back to a full redraw.
+    pub fn update(&self, framebuffer: &Framebuffer) -> Result<()> {
+        if !self.partial_updates() {
+            return self.redraw(framebuffer);
+        }
+
+        // Software rotation of a full frame reverses its pixels
+        let mut frame = framebuffer.data().to_vec();
+        if self.orientation().needs_rotation() {
+            frame.reverse();
+        }
+        let (chunk_size, full_redraw) = {
+            let packets = self.redraw_packets.lock().unwrap();
+            (packets.chunk_size(), packets.wire_size())
+        };
+        let regions = self
+            .last_frame
+            .lock()
+            .unwrap()
+            .as_deref()
+            .and_then(|last| changed_regions(last, &frame, chunk_size, full_redraw));
+
+        match regions {
+            Some(regions) => {
+                if let Err(e) = self.refresh_regions(&frame, &regions) {
+                    warn!("Partial refresh failed, sending full frames: {}", e);
+                    self.set_partial_updates(false);
+                    return self.redraw(framebuffer);
+                }
+                debug!("Partial update ({} regions)", regions.len());
+            }
+            None => self.redraw(framebuffer)?,
+        }
+        *self.last_frame.lock().unwrap() = Some(frame);
+        Ok(())
+    }
+
+    /// Sends partial refreshes of `regions` of `frame`, a full frame in the
+    /// device's pixel order.
+    fn refresh_regions(&self, frame: &[u16], regions: &[Region]) -> Result<()> {
+        let transfer = self.transfer();
+        let device = self.device.lock().unwrap();
+        for (i, region) in regions.iter().enumerate() {
+            let packet = build_refresh_packet(
+                region.x,
+                region.y,
+                region.width,
+                region.height,
+                &region.pixels(frame),
+                transfer.chunk_size,
+            );
+            send_report(&device, &packet, transfer.report)?;
+            if !transfer.chunk_delay.is_zero() && i + 1 < regions.len() {
+                std::thread::sleep(transfer.chunk_delay);
+            }
+        }
+        Ok(())
+    }
+
     /// Sets the display orientation.
     pub fn set_orientation(&self, orientation: Orientation) -> Result<()> {
         let packet = build_orientation_packet(orientation.is_portrait());
 
         debug!("Orientation packet header: {:02X?}", &packet[0..10]);
 
+        *self.last_frame.lock().unwrap() = None;
         let device = self.device.lock().unwrap();
         device.write(&packet)?;
 
@@ -132,19 +297,17 @@ impl LcdDevice {
     /// Performs a full screen redraw.
     pub fn redraw(&self, framebuffer: &Framebuffer) -> Result<()> {
         let orientation = *self.current_orientation.lock().unwrap();
-        let mut data = framebuffer.data().to_vec();
-
-        // Apply software rotation if needed
-        if orientation.needs_rotation() {
-            Orientation::rotate_180(&mut data, framebuffer.width(), framebuffer.height());
-        }
+        let transfer = self.transfer();
+        // Frames sent here are not tracked; `update` records its own
+        *self.last_frame.lock().unwrap() = None;
+        let mut packets = self.redraw_packets.lock().unwrap();
+        // Software rotation is applied while filling the packets
+        packets.fill(framebuffer.data(), orientation.needs_rotation());
 
         let device = self.device.lock().unwrap();
+        let chunk_count = packets.chunk_count();
 
-        for chunk_idx in 0..CHUNK_COUNT {
-            let offset = chunk_idx * (DATA_SIZE / 2);
-            let packet = build_redraw_chunk(chunk_idx, &data, offset);
-
+        for (chunk_idx, packet) in packets.packets().enumerate() {
             // Log first chunk header for debugging
             if chunk_idx == 0 {
                 debug!(
@@ -154,14 +317,18 @@ impl LcdDevice {
                 );
             }
 
-            device.write(&packet)?;
+            send_report(&device, packet, transfer.report)?;
+            if !transfer.chunk_delay.is_zero() && chunk_idx + 1 < chunk_count {
+                std::thread::sleep(transfer.chunk_delay);
+            }
         }
 
-        debug!("Full redraw completed ({} chunks)", CHUNK_COUNT);
+        debug!("Full redraw completed ({} chunks)", chunk_count);
         Ok(())
     }
 
-    /// Performs a partial refresh of a rectangular region.
+    /// Performs a partial refresh of a rectangular region, in one full-size
+    /// report whatever the transfer settings.
     pub fn refresh(&self, x: u16, y: u16, width: u8, height: u8, pixels: &[u16]) -> Result<()> {
         let orientation = *self.current_orientation.lock().unwrap();
         let mut data = pixels.to_vec();
@@ -171,8 +338,9 @@ impl LcdDevice {
             Orientation::rotate_180(&mut data, width as u16, height as u16);
         }
 
-        let packet = build_refresh_packet(x, y, width, height, &data);
+        let packet = build_refresh_packet(x, y, width, height, &data, DATA_SIZE);
 
+        *self.last_frame.lock().unwrap() = None;
         let device = self.device.lock().unwrap();
         device.write(&packet)?;
 
@@ -205,6 +373,25 @@ impl LcdDevice {
     }
 }
 
+/// Sends `report` as the kind of report given; a write that sends less
+/// than the whole report is an error.
+fn send_report(device: &HidDevice, report: &[u8], kind: ReportKind) -> Result<()> {
+    match kind {
+        ReportKind::Output => {
+            let written = device.write(report)?;
+            if written < report.len() {
+                return Err(Error::Hid(format!(
+                    "short write: {} of {} bytes",
+                    written,
+                    report.len()
+                )));
+            }
+            Ok(())
+        }
+        ReportKind::Feature => device.send_feature_report(report),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -210,18 +210,6 @@ pub fn rgb565_to_rgb888(pixel: u16) -> (u8, u8, u8) {
     (r8, g8, b8)
 }
 
-/// Parses a hex color string to RGB565.
-pub fn parse_hex_color(hex: &str) -> Option<u16> {
-    let hex = hex.trim_start_matches('#');
-    if hex.len() != 6 {
-        return None;
-    }
-    let r = u8::from_str_radix(&hex[0..2], 16).ok()?;
-    let g = u8::from_str_radix(&hex[2..4], 16).ok()?;
-    let b = u8::from_str_radix(&hex[4..6], 16).ok()?;
-    Some(rgb888_to_rgb565(r, g, b))
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -249,15 +237,6 @@ mod tests {
         assert_eq!(black, 0x0000);
     }
 
-    #[test]
-    fn test_parse_hex_color() {
-        assert_eq!(parse_hex_color("#FF0000"), Some(0xF800));
-        assert_eq!(parse_hex_color("00FF00"), Some(0x07E0));
-        assert_eq!(parse_hex_color("#000000"), Some(0x0000));
-        assert_eq!(parse_hex_color("#FFFFFF"), Some(0xFFFF));
-        assert_eq!(parse_hex_color("invalid"), None);
-    }
-
     #[test]
     fn test_framebuffer_ops() {
         let mut fb = Framebuffer::new();
@@ -0,0 +1,110 @@
+//! Input reports from the panel's other HID interfaces.
+//!
+//! Some panel revisions have buttons that report on a HID interface next to
+//! the display one. The report format is undocumented, so reports are passed
+//! through as raw bytes and identified by their hex code.
+
+use crate::hid::{self, HidDevice};
+use crate::{Error, Result, LCD_PID, LCD_VID};
+use tracing::{debug, info};
+
+use super::device::LCD_INTERFACE;
+
+/// Largest input report read.
+const REPORT_SIZE: usize = 64;
+
+/// A raw input report.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InputReport {
+    /// HID interface the report came from
+    pub interface: i32,
+    /// Report bytes
+    pub data: Vec<u8>,
+}
+
+impl InputReport {
+    /// Returns the report as space-separated hex bytes without trailing
+    /// zeros (e.g. "01 05"), used to map reports to actions.
+    pub fn code(&self) -> String {
+        let end = self.data.iter().rposition(|b| *b != 0).map_or(0, |i| i + 1);
+        self.data[..end]
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// Returns true if every byte is zero, as in a release report.
+    pub fn is_release(&self) -> bool {
+        self.data.iter().all(|b| *b == 0)
+    }
+}
+
+/// The panel's non-display HID interfaces, read for input reports.
+pub struct PanelInput {
+    devices: Vec<(i32, HidDevice)>,
+}
+
+impl PanelInput {
+    /// Opens every HID interface of the panel except the display one.
+    pub fn open() -> Result<Self> {
+        let mut devices = Vec::new();
+        for info in hid::enumerate(LCD_VID, LCD_PID)?
+            .into_iter()
+            .filter(|d| d.number != LCD_INTERFACE)
+        {
+            match HidDevice::open(&info.path, false) {
+                Ok(device) => devices.push((info.number, device)),
+                Err(e) => debug!("Failed to open input interface {}: {}", info.number, e),
+            }
+        }
+        if devices.is_empty() {
+            return Err(Error::LcdNotFound);
+        }
+
+        info!("Reading panel input from {} interface(s)", devices.len());
+        Ok(Self { devices })
+    }
+
+    /// Returns the next pending report, or None if there is none. Does not
+    /// block.
+    pub fn poll(&self) -> Result<Option<InputReport>> {
+        let mut buf = [0u8; REPORT_SIZE];
+        for (interface, device) in &self.devices {
+            let len = device.read(&mut buf)?;
+            if len > 0 {
+                return Ok(Some(InputReport {
+                    interface: *interface,
+                    data: buf[..len].to_vec(),
+                }));
+            }
+        }
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report(data: &[u8]) -> InputReport {
+        InputReport {
+            interface: 1,
+            data: data.to_vec(),
+        }
+    }
+
+    #[test]
+    fn test_code() {
+        assert_eq!(report(&[0x01, 0x0a, 0, 0]).code(), "01 0a");
+        assert_eq!(report(&[0, 0x05]).code(), "00 05");
+        assert_eq!(report(&[0, 0]).code(), "");
+    }
+
+    #[test]
+    fn test_is_release() {
+        assert!(report(&[0, 0, 0]).is_release());
+        assert!(report(&[]).is_release());
+        assert!(!report(&[0, 1]).is_release());
+    }
+}
@@ -1,12 +1,27 @@
 //! LCD display module.
 //!
-//! Provides control over the 320x170 RGB565 LCD display via USB HID.
+//! Provides control over the 320x170 RGB565 LCD display via USB HID. The
+//! device and its input reports need a HID backend feature.
 
+#[cfg(any(feature = "hidapi", feature = "hidraw"))]
+mod delta;
+#[cfg(any(feature = "hidapi", feature = "hidraw"))]
 mod device;
+#[cfg(any(feature = "hidapi", feature = "hidraw"))]
+mod input;
+// Only the device sends the command packets
+#[cfg_attr(not(any(feature = "hidapi", feature = "hidraw")), allow(dead_code))]
 mod protocol;
+mod transfer;
 
 pub mod framebuffer;
 
+#[cfg(any(feature = "hidapi", feature = "hidraw"))]
 pub use device::LcdDevice;
-pub use framebuffer::{parse_hex_color, rgb565_to_rgb888, rgb888_to_rgb565, Framebuffer};
-pub use protocol::{Command, SubCommand};
+pub use framebuffer::{rgb565_to_rgb888, rgb888_to_rgb565, Framebuffer};
+#[cfg(any(feature = "hidapi", feature = "hidraw"))]
+pub use input::{InputReport, PanelInput};
+pub use protocol::{Command, RedrawPackets, SubCommand};
+pub use transfer::{
+    ReportKind, TransferSettings, CHUNK_SIZE_STEP, MAX_CHUNK_DELAY, MAX_CHUNK_SIZE,
+};
@@ -5,6 +5,9 @@
 //! - Signature byte: 0x55
 //! - Command bytes: 0xA1 (config), 0xA2 (refresh), 0xA3 (redraw)
 
+use super::transfer::CHUNK_SIZE_STEP;
+use crate::{LCD_HEIGHT, LCD_WIDTH};
+
 /// Total buffer size including report byte.
 pub const BUFFER_SIZE: usize = 4105; // 1 report + 8 header + 4096 data
 
@@ -20,11 +23,8 @@ pub const REPORT_SIZE: usize = 1;
 /// Protocol signature byte.
 pub const LCD_SIGNATURE: u8 = 0x55;
 
-/// Number of chunks for full screen redraw.
-pub const CHUNK_COUNT: usize = 27;
-
-/// Size of the final chunk (320 * 170 * 2 = 108800; 108800 % 4096 = 2304).
-pub const FINAL_CHUNK_SIZE: usize = 2304;
+/// Bytes of pixel data in a full frame (320 * 170 * 2 = 108800).
+const FRAME_SIZE: usize = LCD_WIDTH as usize * LCD_HEIGHT as usize * 2;
 
 /// LCD command types.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -83,15 +83,18 @@ pub fn build_heartbeat_packet(hours: u8, minutes: u8, seconds: u8) -> [u8; BUFFE
     buffer
 }
 
-/// Builds a partial refresh command packet.
+/// Builds a partial refresh command packet with room for `chunk_size`
+/// pixel bytes, the report size of the transfer. Pixels beyond that are
+/// left out.
 pub fn build_refresh_packet(
     x: u16,
     y: u16,
     width: u8,
     height: u8,
     pixel_data: &[u16],
-) -> [u8; BUFFER_SIZE] {
-    let mut buffer = [0u8; BUFFER_SIZE];
+    chunk_size: usize,
+) -> Vec<u8> {
+    let mut buffer = vec![0u8; REPORT_SIZE + HEADER_SIZE + chunk_size];
     buffer[1] = LCD_SIGNATURE;
     buffer[2] = Command::Refresh as u8;
 
@@ -109,7 +112,7 @@ pub fn build_refresh_packet(
     let data_start = REPORT_SIZE + HEADER_SIZE;
     for (i, &pixel) in pixel_data.iter().enumerate() {
         let offset = data_start + i * 2;
-        if offset + 1 < BUFFER_SIZE {
+        if offset + 1 < buffer.len() {
             buffer[offset] = (pixel >> 8) as u8;
             buffer[offset + 1] = (pixel & 0xFF) as u8;
         }
@@ -118,20 +121,21 @@ pub fn build_refresh_packet(
     buffer
 }
 
-/// Builds a redraw chunk packet.
-pub fn build_redraw_chunk(
+/// Writes the header of redraw chunk `chunk_index` of `chunk_count`, each
+/// carrying `chunk_size` pixel bytes, into `buffer`.
+fn write_redraw_header(
+    buffer: &mut [u8],
     chunk_index: usize,
-    pixel_data: &[u16],
-    offset_in_image: usize,
-) -> [u8; BUFFER_SIZE] {
-    let mut buffer = [0u8; BUFFER_SIZE];
+    chunk_count: usize,
+    chunk_size: usize,
+) {
     buffer[1] = LCD_SIGNATURE;
     buffer[2] = Command::Redraw as u8;
 
     // Determine phase
     let phase = match chunk_index {
         0 => RedrawPhase::Start,
-        i if i == CHUNK_COUNT - 1 => RedrawPhase::End,
+        i if i == chunk_count - 1 => RedrawPhase::End,
         _ => RedrawPhase::Continue,
     };
     buffer[3] = phase as u8;
@@ -140,34 +144,112 @@ pub fn build_redraw_chunk(
     buffer[4] = (chunk_index + 1) as u8;
 
     // Offset into image (big-endian for offset)
-    let byte_offset = offset_in_image * 2;
+    let byte_offset = chunk_index * chunk_size;
     buffer[5] = 0; // High byte of offset (unused in original)
     buffer[6] = (byte_offset >> 8) as u8;
     buffer[7] = (byte_offset & 0xFF) as u8;
 
     // Chunk size
-    let chunk_size = if chunk_index == CHUNK_COUNT - 1 {
-        FINAL_CHUNK_SIZE
-    } else {
-        DATA_SIZE
-    };
-    buffer[8] = (chunk_size >> 8) as u8;
-    buffer[9] = (chunk_size & 0xFF) as u8;
+    let data_size = redraw_data_size(chunk_index, chunk_size);
+    buffer[8] = (data_size >> 8) as u8;
+    buffer[9] = (data_size & 0xFF) as u8;
+}
 
-    // Copy pixel data (big-endian)
-    let pixel_count = chunk_size / 2;
-    let data_start = REPORT_SIZE + HEADER_SIZE;
-    for i in 0..pixel_count {
-        let pixel_idx = offset_in_image + i;
-        if pixel_idx < pixel_data.len() {
-            let pixel = pixel_data[pixel_idx];
-            let offset = data_start + i * 2;
-            buffer[offset] = (pixel >> 8) as u8;
-            buffer[offset + 1] = (pixel & 0xFF) as u8;
+/// Returns the number of data bytes in redraw chunk `chunk_index`; only the
+/// last chunk is short.
+fn redraw_data_size(chunk_index: usize, chunk_size: usize) -> usize {
+    chunk_size.min(FRAME_SIZE - chunk_index * chunk_size)
+}
+
+/// Packets of a full screen redraw, allocated once and refilled for each
+/// frame. Headers are written on creation; [`RedrawPackets::fill`] only
+/// writes the pixel bytes.
+pub struct RedrawPackets {
+    /// The packets back to back, `packet_size` bytes each
+    buffer: Vec<u8>,
+    packet_size: usize,
+    chunk_size: usize,
+}
+
+impl Default for RedrawPackets {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RedrawPackets {
+    /// Allocates the packets with their headers, in full-size chunks.
+    pub fn new() -> Self {
+        Self::with_chunk_size(DATA_SIZE)
+    }
+
+    /// Allocates the packets for chunks of `chunk_size` pixel bytes. Smaller
+    /// chunks make more, shorter reports.
+    ///
+    /// # Panics
+    ///
+    /// Panics unless `chunk_size` is a multiple of [`CHUNK_SIZE_STEP`] up to
+    /// [`DATA_SIZE`].
+    pub fn with_chunk_size(chunk_size: usize) -> Self {
+        assert!(
+            chunk_size > 0 && chunk_size <= DATA_SIZE && chunk_size.is_multiple_of(CHUNK_SIZE_STEP),
+            "invalid redraw chunk size {}",
+            chunk_size
+        );
+        let chunk_count = FRAME_SIZE.div_ceil(chunk_size);
+        let packet_size = REPORT_SIZE + HEADER_SIZE + chunk_size;
+        let mut buffer = vec![0u8; chunk_count * packet_size];
+        for (chunk_index, packet) in buffer.chunks_exact_mut(packet_size).enumerate() {
+            write_redraw_header(packet, chunk_index, chunk_count, chunk_size);
+        }
+        Self {
+            buffer,
+            packet_size,
+            chunk_size,
         }
     }
 
-    buffer
+    /// Pixel bytes carried by each packet but the last.
+    pub fn chunk_size(&self) -> usize {
+        self.chunk_size
+    }
+
+    /// Number of packets in a redraw.
+    pub fn chunk_count(&self) -> usize {
+        self.buffer.len() / self.packet_size
+    }
+
+    /// Bytes written to the device for a redraw, report IDs included.
+    pub fn wire_size(&self) -> usize {
+        self.buffer.len()
+    }
+
+    /// Writes `pixel_data` into the packets, in reverse order when
+    /// `rotate_180` is set. Missing pixels are left as they were.
+    pub fn fill(&mut self, pixel_data: &[u16], rotate_180: bool) {
+        if rotate_180 {
+            self.write_pixels(pixel_data.iter().rev());
+        } else {
+            self.write_pixels(pixel_data.iter());
+        }
+    }
+
+    fn write_pixels<'a>(&mut self, mut pixels: impl Iterator<Item = &'a u16>) {
+        let data_start = REPORT_SIZE + HEADER_SIZE;
+        let chunk_size = self.chunk_size;
+        for (chunk_index, packet) in self.buffer.chunks_exact_mut(self.packet_size).enumerate() {
+            let data =
+                &mut packet[data_start..data_start + redraw_data_size(chunk_index, chunk_size)];
+            for (bytes, pixel) in data.chunks_exact_mut(2).zip(&mut pixels) {
+                bytes.copy_from_slice(&pixel.to_be_bytes());
+            }
+        }
+    }
+
+    /// Returns the packets in the order they are sent.
+    pub fn packets(&self) -> impl Iterator<Item = &[u8]> {
+        self.buffer.chunks_exact(self.packet_size)
+    }
 }
 
 #[cfg(test)]
@@ -196,4 +278,60 @@ mod tests {
         assert_eq!(packet[5], 30);
         assert_eq!(packet[6], 45);
     }
+
+    #[test]
+    fn test_redraw_packets() {
+        let pixels: Vec<u16> = (0..320 * 170).map(|i| i as u16).collect();
+        let mut packets = RedrawPackets::new();
+        packets.fill(&pixels, false);
+        let all: Vec<_> = packets.packets().collect();
+        assert_eq!(all.len(), 27);
+
+        let second = all[1];
+        assert_eq!(
+            second[..9],
+            [0, LCD_SIGNATURE, 0xA3, 0xF1, 2, 0, 0x10, 0x00, 0x10]
+        );
+        // Pixel 2048 in big-endian
+        assert_eq!(second[9..11], [0x08, 0x00]);
+        let last = all[26];
+        assert_eq!(last[3..9], [0xF2, 27, 0, 0xA0, 0x00, 0x09]);
+        // Last pixel 54399 (0xD47F), then padding
+        let end = REPORT_SIZE + HEADER_SIZE + 2304;
+        assert_eq!(last[end - 2..end + 1], [0xD4, 0x7F, 0]);
+
+        packets.fill(&pixels, true);
+        let first = packets.packets().next().unwrap();
+        assert_eq!(first[9..13], [0xD4, 0x7F, 0xD4, 0x7E]);
+    }
+
+    #[test]
+    fn test_redraw_packets_small_chunks() {
+        let pixels: Vec<u16> = (0..54400).map(|i| i as u16).collect();
+        let mut packets = RedrawPackets::with_chunk_size(1024);
+        packets.fill(&pixels, false);
+        let all: Vec<_> = packets.packets().collect();
+        assert_eq!(all.len(), 107);
+        assert!(all
+            .iter()
+            .all(|p| p.len() == REPORT_SIZE + HEADER_SIZE + 1024));
+
+        assert_eq!(all[1][3..9], [0xF1, 2, 0, 0x04, 0x00, 0x04]);
+        // Pixel 512 starts the second chunk
+        assert_eq!(all[1][9..11], [0x02, 0x00]);
+        // 108800 % 1024 = 256 bytes in the last chunk
+        assert_eq!(all[106][3..9], [0xF2, 107, 0, 0xA8, 0x00, 0x01]);
+        assert_eq!(packets.wire_size(), 107 * all[0].len());
+    }
+
+    #[test]
+    fn test_refresh_packet() {
+        let packet = build_refresh_packet(300, 2, 2, 3, &[0x1234; 6], 1024);
+        assert_eq!(packet.len(), REPORT_SIZE + HEADER_SIZE + 1024);
+        assert_eq!(packet[1..9], [0x55, 0xA2, 0x2C, 0x01, 2, 0, 2, 3]);
+        assert_eq!(packet[9..11], [0x12, 0x34]);
+        // Pixels that don't fit are left out
+        let packet = build_refresh_packet(0, 0, 255, 255, &[0xFFFF; 1024], 1024);
+        assert_eq!(packet.len(), REPORT_SIZE + HEADER_SIZE + 1024);
+    }
 }
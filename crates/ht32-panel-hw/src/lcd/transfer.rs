@@ -0,0 +1,169 @@
+//! USB transfer tuning.
+//!
+//! A full redraw is sent as 27 output reports of 4096 pixel bytes, back to
+//! back. Some USB hubs corrupt or drop frames at that pace. Smaller chunks
+//! (sent as shorter reports), a pause between reports, or feature reports on
+//! the control endpoint instead of output reports can make them reliable.
+
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+
+use super::protocol::DATA_SIZE;
+use crate::{Error, Result};
+
+/// Chunk sizes must be a multiple of this, so a redraw needs at most 213
+/// reports and its sequence numbers fit in a byte.
+pub const CHUNK_SIZE_STEP: usize = 512;
+
+/// Largest chunk size, a full report.
+pub const MAX_CHUNK_SIZE: usize = DATA_SIZE;
+
+/// Longest pause between reports.
+pub const MAX_CHUNK_DELAY: Duration = Duration::from_millis(100);
+
+/// Kind of HID report frames are sent in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ReportKind {
+    /// Output reports on the interrupt endpoint
+    #[default]
+    Output,
+    /// Feature reports on the control endpoint
+    Feature,
+}
+
+impl fmt::Display for ReportKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReportKind::Output => write!(f, "output"),
+            ReportKind::Feature => write!(f, "feature"),
+        }
+    }
+}
+
+impl FromStr for ReportKind {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "output" => Ok(ReportKind::Output),
+            "feature" => Ok(ReportKind::Feature),
+            _ => Err(Error::InvalidTransfer(format!(
+                "unknown report kind \"{}\"; use output or feature",
+                s
+            ))),
+        }
+    }
+}
+
+/// How redraws are sent to the LCD.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TransferSettings {
+    /// Pixel bytes per report, a multiple of [`CHUNK_SIZE_STEP`] up to
+    /// [`MAX_CHUNK_SIZE`]
+    pub chunk_size: usize,
+    /// Pause after each report of a redraw but the last
+    pub chunk_delay: Duration,
+    /// Kind of report the chunks are sent in
+    pub report: ReportKind,
+}
+
+impl Default for TransferSettings {
+    fn default() -> Self {
+        Self {
+            chunk_size: MAX_CHUNK_SIZE,
+            chunk_delay: Duration::ZERO,
+            report: ReportKind::Output,
+        }
+    }
+}
+
+impl TransferSettings {
+    /// Checks that the settings can be used.
+    pub fn validate(&self) -> Result<()> {
+        if self.chunk_size == 0
+            || self.chunk_size > MAX_CHUNK_SIZE
+            || !self.chunk_size.is_multiple_of(CHUNK_SIZE_STEP)
+        {
+            return Err(Error::InvalidTransfer(format!(
+                "chunk size must be a multiple of {} up to {}, got {}",
+                CHUNK_SIZE_STEP, MAX_CHUNK_SIZE, self.chunk_size
+            )));
+        }
+        if self.chunk_delay > MAX_CHUNK_DELAY {
+            return Err(Error::InvalidTransfer(format!(
+                "chunk delay must be at most {} ms, got {} ms",
+                MAX_CHUNK_DELAY.as_millis(),
+                self.chunk_delay.as_millis()
+            )));
+        }
+        Ok(())
+    }
+
+    /// Settings tried by [`LcdDevice::tune_transfer`](super::LcdDevice::tune_transfer),
+    /// from the default pace to the gentlest.
+    pub fn candidates() -> Vec<TransferSettings> {
+        let settings = |chunk_size, delay_ms, report| TransferSettings {
+            chunk_size,
+            chunk_delay: Duration::from_millis(delay_ms),
+            report,
+        };
+        vec![
+            settings(4096, 0, ReportKind::Output),
+            settings(4096, 0, ReportKind::Feature),
+            settings(4096, 1, ReportKind::Output),
+            settings(2048, 1, ReportKind::Output),
+            settings(1024, 2, ReportKind::Output),
+            settings(512, 5, ReportKind::Output),
+        ]
+    }
+}
+
+impl fmt::Display for TransferSettings {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}-byte {} reports", self.chunk_size, self.report)?;
+        if self.chunk_delay.is_zero() {
+            write!(f, ", no delay")
+        } else {
+            write!(f, ", {} ms apart", self.chunk_delay.as_millis())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transfer_settings() {
+        assert!(TransferSettings::default().validate().is_ok());
+        assert!(TransferSettings::candidates()
+            .iter()
+            .all(|settings| settings.validate().is_ok()));
+        assert_eq!(
+            TransferSettings::candidates()[0],
+            TransferSettings::default()
+        );
+
+        let odd = TransferSettings {
+            chunk_size: 1000,
+            ..Default::default()
+        };
+        assert!(odd.validate().is_err());
+        let slow = TransferSettings {
+            chunk_delay: Duration::from_secs(1),
+            ..Default::default()
+        };
+        assert!(slow.validate().is_err());
+
+        assert_eq!(
+            "feature".parse::<ReportKind>().unwrap(),
+            ReportKind::Feature
+        );
+        assert!("input".parse::<ReportKind>().is_err());
+        assert_eq!(
+            TransferSettings::candidates()[3].to_string(),
+            "2048-byte output reports, 1 ms apart"
+        );
+    }
+}
@@ -1,7 +1,8 @@
 //! LED strip device communication via serial port.
 
+use crate::access::{self, Access};
 use crate::{Error, Result};
-use std::str::FromStr;
+use ht32_panel_types::LedTheme;
 use std::time::Duration;
 use tokio::io::AsyncWriteExt;
 use tokio::time::sleep;
@@ -17,64 +18,6 @@ const BAUD_RATE: u32 = 10000;
 /// Delay between bytes in milliseconds (must write one byte at a time).
 const BYTE_DELAY_MS: u64 = 5;
 
-/// LED theme options.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
-#[repr(u8)]
-pub enum LedTheme {
-    /// Rainbow cycling effect.
-    Rainbow = 0x01,
-    /// Breathing/pulsing effect.
-    #[default]
-    Breathing = 0x02,
-    /// Solid colors cycling.
-    Colors = 0x03,
-    /// LEDs off.
-    Off = 0x04,
-    /// Automatic mode.
-    Auto = 0x05,
-}
-
-impl LedTheme {
-    /// Converts a byte value to LedTheme.
-    pub fn from_byte(value: u8) -> Result<Self> {
-        match value {
-            0x01 => Ok(LedTheme::Rainbow),
-            0x02 => Ok(LedTheme::Breathing),
-            0x03 => Ok(LedTheme::Colors),
-            0x04 => Ok(LedTheme::Off),
-            0x05 => Ok(LedTheme::Auto),
-            _ => Err(Error::InvalidTheme(value)),
-        }
-    }
-}
-
-impl FromStr for LedTheme {
-    type Err = Error;
-
-    fn from_str(s: &str) -> Result<Self> {
-        match s.to_lowercase().as_str() {
-            "rainbow" => Ok(LedTheme::Rainbow),
-            "breathing" => Ok(LedTheme::Breathing),
-            "colors" => Ok(LedTheme::Colors),
-            "off" => Ok(LedTheme::Off),
-            "auto" => Ok(LedTheme::Auto),
-            _ => Err(Error::InvalidTheme(0)),
-        }
-    }
-}
-
-impl std::fmt::Display for LedTheme {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            LedTheme::Rainbow => write!(f, "rainbow"),
-            LedTheme::Breathing => write!(f, "breathing"),
-            LedTheme::Colors => write!(f, "colors"),
-            LedTheme::Off => write!(f, "off"),
-            LedTheme::Auto => write!(f, "auto"),
-        }
-    }
-}
-
 /// LED strip device controller.
 pub struct LedDevice {
     port_path: String,
@@ -102,9 +45,15 @@ impl LedDevice {
                     if *kind == std::io::ErrorKind::NotFound
                         || *kind == std::io::ErrorKind::PermissionDenied
                     {
-                        // Check if file actually exists
-                        if !std::path::Path::new(&self.port_path).exists() {
-                            return Error::LedNotFound(self.port_path.clone());
+                        match access::serial_access(&self.port_path) {
+                            Access::Missing => return Error::LedNotFound(self.port_path.clone()),
+                            Access::Denied { path, detail } => {
+                                return Error::PermissionDenied {
+                                    path: path.display().to_string(),
+                                    detail,
+                                }
+                            }
+                            Access::Granted(_) => {}
                         }
                     }
                 }
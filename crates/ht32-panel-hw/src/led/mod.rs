@@ -4,4 +4,5 @@
 
 mod device;
 
-pub use device::{LedDevice, LedTheme};
+pub use device::LedDevice;
+pub use ht32_panel_types::LedTheme;
@@ -3,19 +3,22 @@
 //! Provides hardware abstraction for LCD and LED control on HT32-based
 //! mini PC display panels (AceMagic, Agni, and other whitelabeled devices).
 
+pub mod access;
 pub mod error;
+#[cfg(any(feature = "hidapi", feature = "hidraw"))]
+mod hid;
 pub mod lcd;
 pub mod led;
-pub mod orientation;
+pub mod stream;
 
 pub use error::{Error, Result};
-pub use lcd::{Framebuffer, LcdDevice};
+#[cfg(any(feature = "hidapi", feature = "hidraw"))]
+pub use hid::BACKEND as HID_BACKEND;
+pub use ht32_panel_types::{Orientation, LCD_HEIGHT, LCD_WIDTH};
+pub use lcd::Framebuffer;
+#[cfg(any(feature = "hidapi", feature = "hidraw"))]
+pub use lcd::LcdDevice;
 pub use led::{LedDevice, LedTheme};
-pub use orientation::Orientation;
-
-/// LCD display dimensions
-pub const LCD_WIDTH: u16 = 320;
-pub const LCD_HEIGHT: u16 = 170;
 
 /// USB VID:PID for the LCD device
 pub const LCD_VID: u16 = 0x04D9;
@@ -0,0 +1,108 @@
+//! Frame streaming protocol.
+//!
+//! Frames sent over a network connection to a panel on another machine.
+//! Each frame is a header followed by the pixels:
+//!
+//! | Bytes | Content                                 |
+//! |-------|-----------------------------------------|
+//! | 4     | Magic `HT32`                            |
+//! | 2     | Width, little-endian                    |
+//! | 2     | Height, little-endian                   |
+//! | w×h×2 | RGB565 pixels, row-major, little-endian |
+//!
+//! A full 320x170 frame is about 109 KB.
+
+use std::io::{self, Read, Write};
+
+use crate::lcd::Framebuffer;
+
+/// Magic bytes starting every frame.
+pub const MAGIC: &[u8; 4] = b"HT32";
+
+/// Default port the receiver listens on.
+pub const DEFAULT_PORT: u16 = 8688;
+
+/// Largest accepted width or height, guarding against allocating for a
+/// corrupt header.
+const MAX_DIMENSION: u16 = 4096;
+
+/// Writes `framebuffer` as one frame.
+pub fn write_frame<W: Write>(writer: &mut W, framebuffer: &Framebuffer) -> io::Result<()> {
+    let mut buffer = Vec::with_capacity(8 + framebuffer.data().len() * 2);
+    buffer.extend_from_slice(MAGIC);
+    buffer.extend_from_slice(&framebuffer.width().to_le_bytes());
+    buffer.extend_from_slice(&framebuffer.height().to_le_bytes());
+    for pixel in framebuffer.data() {
+        buffer.extend_from_slice(&pixel.to_le_bytes());
+    }
+    writer.write_all(&buffer)?;
+    writer.flush()
+}
+
+/// Reads one frame into `framebuffer`, resizing it to the frame's
+/// dimensions.
+///
+/// Returns an `UnexpectedEof` error when the sender closes the connection.
+pub fn read_frame<R: Read>(reader: &mut R, framebuffer: &mut Framebuffer) -> io::Result<()> {
+    let mut header = [0u8; 8];
+    reader.read_exact(&mut header)?;
+    if &header[..4] != MAGIC {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "not an HT32 frame stream",
+        ));
+    }
+    let width = u16::from_le_bytes([header[4], header[5]]);
+    let height = u16::from_le_bytes([header[6], header[7]]);
+    if width == 0 || height == 0 || width > MAX_DIMENSION || height > MAX_DIMENSION {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("invalid frame size {}x{}", width, height),
+        ));
+    }
+
+    let mut bytes = vec![0u8; width as usize * height as usize * 2];
+    reader.read_exact(&mut bytes)?;
+    framebuffer.resize(width, height);
+    for (pixel, chunk) in framebuffer.data_mut().iter_mut().zip(bytes.chunks_exact(2)) {
+        *pixel = u16::from_le_bytes([chunk[0], chunk[1]]);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let mut sent = Framebuffer::with_dimensions(4, 2);
+        sent.set_pixel(0, 0, 0xF800);
+        sent.set_pixel(3, 1, 0x07E0);
+
+        let mut stream = Vec::new();
+        write_frame(&mut stream, &sent).unwrap();
+        write_frame(&mut stream, &sent).unwrap();
+        assert_eq!(stream.len(), 2 * (8 + 4 * 2 * 2));
+
+        let mut reader = stream.as_slice();
+        let mut received = Framebuffer::new();
+        for _ in 0..2 {
+            read_frame(&mut reader, &mut received).unwrap();
+            assert_eq!((received.width(), received.height()), (4, 2));
+            assert_eq!(received.data(), sent.data());
+        }
+        let end = read_frame(&mut reader, &mut received).unwrap_err();
+        assert_eq!(end.kind(), io::ErrorKind::UnexpectedEof);
+    }
+
+    #[test]
+    fn test_rejects_bad_header() {
+        let mut received = Framebuffer::new();
+        let err = read_frame(&mut &b"GET / HTTP/1.1\r\n"[..], &mut received).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+        let err = read_frame(&mut &b"HT32\0\0\x10\0"[..], &mut received).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+}
@@ -0,0 +1,131 @@
+//! HT32 Panel network receiver.
+//!
+//! Shows frames streamed by `ht32paneld` with `devices.output = "network"`
+//! on the panel attached to this machine.
+
+use std::io::BufReader;
+use std::net::{IpAddr, SocketAddr, TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use clap::Parser;
+use ht32_panel_hw::{stream, Framebuffer, LcdDevice, Orientation};
+use tracing::{info, warn};
+use tracing_subscriber::EnvFilter;
+
+#[derive(Parser)]
+#[command(name = "ht32panelrx")]
+#[command(about = "Show frames streamed by ht32paneld on this machine's panel")]
+#[command(version)]
+struct Cli {
+    /// Address to listen on
+    #[arg(short, long, default_value_t = SocketAddr::from(([0, 0, 0, 0], stream::DEFAULT_PORT)))]
+    listen: SocketAddr,
+
+    /// Only accept senders from these addresses (repeatable; default: any)
+    #[arg(short, long)]
+    allow: Vec<IpAddr>,
+
+    /// Heartbeat interval in milliseconds
+    #[arg(long, default_value_t = 1000)]
+    heartbeat: u64,
+
+    /// Seconds without a frame before dropping the sender, so a stalled
+    /// sender does not lock out others; the daemon reconnects with its
+    /// next frame
+    #[arg(long, default_value_t = 30)]
+    timeout: u64,
+
+    /// Enable verbose output
+    #[arg(short, long)]
+    verbose: bool,
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+
+    let filter = if cli.verbose {
+        EnvFilter::new("debug")
+    } else {
+        EnvFilter::new("info")
+    };
+    tracing_subscriber::fmt().with_env_filter(filter).init();
+
+    let lcd = LcdDevice::open().context("Failed to open the panel")?;
+    lcd.heartbeat()?;
+    lcd.set_orientation(Orientation::Landscape)?;
+    let lcd = Arc::new(Mutex::new(lcd));
+
+    // Keep the panel awake while no frames arrive
+    let heartbeat_lcd = Arc::clone(&lcd);
+    let interval = Duration::from_millis(cli.heartbeat.max(100));
+    thread::spawn(move || loop {
+        thread::sleep(interval);
+        if let Err(e) = heartbeat_lcd.lock().unwrap().heartbeat() {
+            warn!("Heartbeat failed: {}", e);
+        }
+    });
+
+    let listener = TcpListener::bind(cli.listen)
+        .with_context(|| format!("Failed to listen on {}", cli.listen))?;
+    info!("Listening on {}", cli.listen);
+
+    for connection in listener.incoming() {
+        let socket = match connection {
+            Ok(socket) => socket,
+            Err(e) => {
+                warn!("Failed to accept connection: {}", e);
+                continue;
+            }
+        };
+        let Ok(peer) = socket.peer_addr() else {
+            continue;
+        };
+        if !cli.allow.is_empty() && !cli.allow.contains(&peer.ip().to_canonical()) {
+            warn!("Rejected connection from {}", peer);
+            continue;
+        }
+
+        let timeout = Duration::from_secs(cli.timeout.max(1));
+        if let Err(e) = socket.set_read_timeout(Some(timeout)) {
+            warn!("Failed to set read timeout for {}: {}", peer, e);
+            continue;
+        }
+
+        info!("Receiving frames from {}", peer);
+        match receive(socket, &lcd) {
+            Ok(frames) => info!("{} disconnected after {} frames", peer, frames),
+            Err(e) => warn!("Connection from {} failed: {:#}", peer, e),
+        }
+    }
+    Ok(())
+}
+
+/// Shows frames from `socket` until the sender disconnects or its read
+/// timeout expires, returning the number of frames shown.
+fn receive(socket: TcpStream, lcd: &Mutex<LcdDevice>) -> Result<u64> {
+    let mut reader = BufReader::new(socket);
+    let mut framebuffer = Framebuffer::new();
+    let mut frames = 0;
+    loop {
+        match stream::read_frame(&mut reader, &mut framebuffer) {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => return Ok(frames),
+            Err(e)
+                if matches!(
+                    e.kind(),
+                    std::io::ErrorKind::WouldBlock | std::io::ErrorKind::TimedOut
+                ) =>
+            {
+                anyhow::bail!("No frame received within the read timeout")
+            }
+            Err(e) => return Err(e).context("Failed to read frame"),
+        }
+        if let Err(e) = lcd.lock().unwrap().redraw(&framebuffer) {
+            warn!("Failed to draw frame: {}", e);
+        }
+        frames += 1;
+    }
+}
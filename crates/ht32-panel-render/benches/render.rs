@@ -0,0 +1,69 @@
+//! Benchmarks of rendering a frame: drawing each face, converting the
+//! canvas to RGB565 and encoding PNG screenshots.
+//!
+//! Run with `cargo bench -p ht32-panel-render`.
+
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use ht32_panel_hw::{Framebuffer, Orientation};
+
+use ht32_panel_render::faces::{available_faces, create_face, EnabledComplications, Theme};
+use ht32_panel_render::fixture;
+use ht32_panel_render::rendering::{encode_png, Canvas};
+
+/// Returns a canvas sized for `orientation`.
+fn canvas(orientation: Orientation) -> Canvas {
+    let (width, height) = orientation.dimensions();
+    Canvas::new(width as u32, height as u32)
+}
+
+fn face_render(c: &mut Criterion) {
+    let data = fixture::system_data();
+    let theme = Theme::from_preset("default");
+    let mut group = c.benchmark_group("face_render");
+
+    for info in available_faces() {
+        let face = create_face(info.id).unwrap();
+        let mut complications = EnabledComplications::new();
+        complications.init_from_defaults(face.as_ref());
+        for (orientation, suffix) in [
+            (Orientation::Landscape, "landscape"),
+            (Orientation::Portrait, "portrait"),
+        ] {
+            let mut canvas = canvas(orientation);
+            group.bench_function(format!("{}/{}", info.id, suffix), |b| {
+                b.iter(|| {
+                    canvas.clear();
+                    face.render(&mut canvas, black_box(&data), &theme, &complications);
+                })
+            });
+        }
+    }
+    group.finish();
+}
+
+fn output(c: &mut Criterion) {
+    let face = create_face("professional").unwrap();
+    let mut complications = EnabledComplications::new();
+    complications.init_from_defaults(face.as_ref());
+    let mut canvas = canvas(Orientation::Landscape);
+    face.render(
+        &mut canvas,
+        &fixture::system_data(),
+        &Theme::from_preset("default"),
+        &complications,
+    );
+    let (width, height) = canvas.dimensions();
+
+    let mut framebuffer = Framebuffer::new();
+    c.bench_function("canvas_to_rgb565", |b| {
+        b.iter(|| canvas.render_to_framebuffer(&mut framebuffer).unwrap())
+    });
+    c.bench_function("png_encode", |b| {
+        b.iter(|| encode_png(width, height, black_box(canvas.pixels())).unwrap())
+    });
+}
+
+criterion_group!(benches, face_render, output);
+criterion_main!(benches);
@@ -0,0 +1,1497 @@
+//! System data aggregation for faces.
+
+use std::collections::{BTreeMap, VecDeque};
+
+use chrono::{Datelike, NaiveDate, TimeZone, Timelike, Utc};
+use chrono_tz::Tz;
+pub use ht32_panel_types::IpDisplayPreference;
+use serde::{Deserialize, Serialize};
+
+/// Number of history samples to keep for graphs.
+pub const HISTORY_SIZE: usize = 60;
+
+/// Seconds between samples of the temperature history.
+pub const TEMP_HISTORY_INTERVAL: u64 = 30;
+
+/// Number of temperature history samples kept: one hour.
+pub const TEMP_HISTORY_SIZE: usize = 120;
+
+/// Names of the built-in collectors that can be switched off.
+pub mod collector_names {
+    pub const TEMPERATURE: &str = "temperature";
+    pub const DISK: &str = "disk";
+    pub const NETWORK: &str = "network";
+    pub const PROCESSES: &str = "processes";
+    pub const SESSIONS: &str = "sessions";
+}
+
+/// Built-in collectors that can be switched off.
+pub const COLLECTORS: &[&str] = &[
+    collector_names::TEMPERATURE,
+    collector_names::DISK,
+    collector_names::NETWORK,
+    collector_names::PROCESSES,
+    collector_names::SESSIONS,
+];
+
+/// Uptime milestones (in days) worth celebrating.
+pub const UPTIME_MILESTONES: &[u64] = &[30, 100, 200, 365, 500, 730, 1000];
+
+/// How long a milestone stays celebrated after it is reached.
+const MILESTONE_WINDOW_SECS: u64 = 300;
+
+/// How multiple IP addresses on an interface are shown.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum IpDisplayMode {
+    /// Show only the address matching the display preference
+    #[default]
+    Single,
+    /// Cycle through all addresses
+    Rotate,
+    /// Stack all addresses (faces without room rotate instead)
+    List,
+}
+
+impl std::fmt::Display for IpDisplayMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            IpDisplayMode::Single => write!(f, "single"),
+            IpDisplayMode::Rotate => write!(f, "rotate"),
+            IpDisplayMode::List => write!(f, "list"),
+        }
+    }
+}
+
+impl std::str::FromStr for IpDisplayMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "single" => Ok(IpDisplayMode::Single),
+            "rotate" => Ok(IpDisplayMode::Rotate),
+            "list" => Ok(IpDisplayMode::List),
+            _ => Err(format!("Unknown IP display mode: {}", s)),
+        }
+    }
+}
+
+/// Units used when formatting throughput.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RateUnits {
+    /// Use 1024-based prefixes (KiB/s) instead of 1000-based (KB/s)
+    pub binary: bool,
+    /// Show network throughput in bits (Mbit/s) instead of bytes
+    pub network_bits: bool,
+}
+
+/// Pending package updates.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PendingUpdates {
+    /// Number of packages with updates available
+    pub total: u32,
+    /// Number of those that are security updates
+    pub security: u32,
+}
+
+/// GPU readings. Fields are None when the driver does not report them.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct GpuStats {
+    /// GPU model name
+    pub name: String,
+    /// Utilization percentage (0-100)
+    pub utilization: Option<f32>,
+    /// Core temperature in Celsius
+    pub temperature: Option<f32>,
+    /// Fan speed as a percentage of maximum
+    pub fan_percent: Option<f32>,
+    /// Fan speed in RPM
+    pub fan_rpm: Option<u32>,
+    /// Power draw in watts
+    pub power_watts: Option<f32>,
+}
+
+impl GpuStats {
+    /// Formats utilization, temperature, fan and power as compact cells
+    /// ("45%", "62°C", "F40%", "120W"), skipping readings not reported.
+    pub fn cells(&self) -> Vec<String> {
+        let fan = self
+            .fan_percent
+            .map(|p| format!("F{:.0}%", p))
+            .or_else(|| self.fan_rpm.map(|r| format!("F{}", r)));
+        [
+            self.utilization.map(|u| format!("{:.0}%", u)),
+            self.temperature.map(|t| format!("{:.0}°C", t)),
+            fan,
+            self.power_watts.map(|w| format!("{:.0}W", w)),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+}
+
+/// State of a virtual machine.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum VmState {
+    Running,
+    Paused,
+    #[default]
+    Stopped,
+    Crashed,
+    /// Blocked, suspended or shutting down
+    Other,
+}
+
+impl VmState {
+    /// Maps a libvirt `virDomainState` value.
+    pub fn from_libvirt(state: u32) -> Self {
+        match state {
+            1 => Self::Running,
+            3 => Self::Paused,
+            5 => Self::Stopped,
+            6 => Self::Crashed,
+            _ => Self::Other,
+        }
+    }
+}
+
+/// A libvirt virtual machine.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct VmStatus {
+    /// Domain name
+    pub name: String,
+    /// Current state
+    pub state: VmState,
+    /// CPU usage across the VM's vCPUs (0-100), None until two samples exist
+    pub cpu_percent: Option<f32>,
+}
+
+/// State of an externally reported job such as a backup.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JobState {
+    Running,
+    Succeeded,
+    Failed,
+}
+
+impl std::str::FromStr for JobState {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "running" | "started" | "start" => Ok(JobState::Running),
+            "success" | "succeeded" | "ok" | "done" => Ok(JobState::Succeeded),
+            "failed" | "failure" | "error" | "fail" => Ok(JobState::Failed),
+            _ => Err(format!("Unknown job status: {}", s)),
+        }
+    }
+}
+
+/// Last reported status of a job.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JobStatus {
+    pub state: JobState,
+    /// Progress (0-100) while running, if reported
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub progress: Option<f32>,
+    /// Unix timestamp of the last report (the finish time once completed)
+    pub updated: u64,
+}
+
+impl JobStatus {
+    /// Formats the status relative to `now`, e.g. "ok 5h ago" or "42%".
+    pub fn format(&self, now: u64) -> String {
+        let age = format_age(now.saturating_sub(self.updated));
+        match self.state {
+            JobState::Running => match self.progress {
+                Some(progress) => format!("{:.0}%", progress),
+                None => "running".to_string(),
+            },
+            JobState::Succeeded => format!("ok {} ago", age),
+            JobState::Failed => format!("failed {} ago", age),
+        }
+    }
+}
+
+/// Formats a duration in seconds as a short age ("45s", "5m", "3h", "2d").
+pub fn format_age(secs: u64) -> String {
+    if secs < 60 {
+        format!("{}s", secs)
+    } else if secs < 3600 {
+        format!("{}m", secs / 60)
+    } else if secs < 86400 {
+        format!("{}h", secs / 3600)
+    } else {
+        format!("{}d", secs / 86400)
+    }
+}
+
+/// Formats an uptime in seconds as "3d 4h 12m", "4h 12m" or "12m", the
+/// short form faces show by default.
+pub fn format_uptime(secs: u64) -> String {
+    let (days, hours, minutes) = (secs / 86400, (secs % 86400) / 3600, (secs % 3600) / 60);
+    if days > 0 {
+        format!("{}d {}h {}m", days, hours, minutes)
+    } else if hours > 0 {
+        format!("{}h {}m", hours, minutes)
+    } else {
+        format!("{}m", minutes)
+    }
+}
+
+/// A heater's current and target temperature in °C.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct HeaterTemp {
+    pub actual: f32,
+    /// Target temperature (0 when the heater is off)
+    pub target: f32,
+}
+
+/// 3D printer status from OctoPrint or Moonraker.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PrinterStatus {
+    /// Printer state as reported (e.g. "Printing", "Operational", "paused")
+    pub state: String,
+    /// Whether a job is printing or paused
+    pub active: bool,
+    /// File being printed
+    pub file: Option<String>,
+    /// Job progress (0-100)
+    pub progress: Option<f32>,
+    /// Print time so far in seconds
+    pub elapsed: Option<u64>,
+    /// Estimated time remaining in seconds
+    pub remaining: Option<u64>,
+    pub nozzle: Option<HeaterTemp>,
+    pub bed: Option<HeaterTemp>,
+}
+
+/// A torrent that is downloading or seeding.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Torrent {
+    pub name: String,
+    /// Completion (0-100)
+    pub progress: f32,
+    /// Download rate in bytes/second
+    pub download_rate: f64,
+    /// Upload rate in bytes/second
+    pub upload_rate: f64,
+    /// Estimated seconds until complete, if known
+    pub eta: Option<u64>,
+}
+
+/// Torrent client statistics.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TorrentStats {
+    /// Total download rate in bytes/second
+    pub download_rate: f64,
+    /// Total upload rate in bytes/second
+    pub upload_rate: f64,
+    /// Incomplete torrents, fastest first
+    pub downloading: Vec<Torrent>,
+    /// Number of torrents in the client
+    pub total: usize,
+}
+
+/// A stream playing from a Jellyfin or Plex server.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MediaStream {
+    /// Title, prefixed with the series name for episodes
+    pub title: String,
+    /// User watching
+    pub user: String,
+    /// Whether the server is transcoding (otherwise direct play or stream)
+    pub transcoding: bool,
+}
+
+/// Kind of storage pool.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PoolKind {
+    #[default]
+    Zfs,
+    /// Linux software RAID
+    Md,
+}
+
+/// Health of a ZFS pool or md array.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PoolStatus {
+    /// Pool or array name (e.g. "tank", "md0")
+    pub name: String,
+    pub kind: PoolKind,
+    /// State as reported (e.g. "ONLINE", "DEGRADED", "FAULTED")
+    pub state: String,
+    /// Whether the pool is not fully healthy
+    pub degraded: bool,
+    /// Scrub, resilver or resync progress (0-100) while one is running
+    pub scrub_progress: Option<f32>,
+}
+
+/// DNS blocking statistics from Pi-hole or AdGuard Home.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct DnsStats {
+    /// Queries answered (today for Pi-hole, the stats period for AdGuard)
+    pub queries: u64,
+    /// Queries blocked
+    pub blocked: u64,
+    /// Blocked share of queries (0-100)
+    pub percent: f32,
+    /// Whether blocking is currently enabled
+    pub enabled: bool,
+}
+
+impl DnsStats {
+    /// Creates stats, computing the blocked percentage.
+    pub fn new(queries: u64, blocked: u64, enabled: bool) -> Self {
+        let percent = if queries > 0 {
+            blocked as f32 * 100.0 / queries as f32
+        } else {
+            0.0
+        };
+        Self {
+            queries,
+            blocked,
+            percent,
+            enabled,
+        }
+    }
+}
+
+/// Reachability of a monitored LAN host.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct HostStatus {
+    /// Display name
+    pub name: String,
+    /// Hostname or IP address that is pinged
+    pub address: String,
+    /// Whether the host answered the last ping (None before the first check)
+    pub up: Option<bool>,
+    /// Round-trip time of the last ping in milliseconds
+    pub latency_ms: Option<f32>,
+}
+
+/// Returns whether `value` is above `above` or below `below`. Shared by
+/// metric alerts and automation rules.
+pub fn outside_limits(value: f64, above: Option<f64>, below: Option<f64>) -> bool {
+    above.is_some_and(|limit| value > limit) || below.is_some_and(|limit| value < limit)
+}
+
+/// A named custom metric from an external source such as SNMP.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Metric {
+    /// Current value
+    pub value: f64,
+    /// Unit shown after the value (e.g. "°C", "Mbit/s")
+    pub unit: String,
+    /// Whether the value is outside its configured alert thresholds
+    pub alert: bool,
+    /// Text shown instead of the value for string metrics
+    pub text: Option<String>,
+}
+
+impl Metric {
+    /// Creates a numeric metric, checking it against optional thresholds.
+    pub fn new(value: f64, unit: &str, alert_above: Option<f64>, alert_below: Option<f64>) -> Self {
+        Self {
+            value,
+            unit: unit.to_string(),
+            alert: outside_limits(value, alert_above, alert_below),
+            text: None,
+        }
+    }
+
+    /// Creates a text metric.
+    pub fn text(text: &str) -> Self {
+        Self {
+            text: Some(text.to_string()),
+            ..Default::default()
+        }
+    }
+
+    /// Formats the value with its unit, e.g. "42.5°C".
+    pub fn format(&self) -> String {
+        if let Some(ref text) = self.text {
+            return text.clone();
+        }
+        let value = if self.value.abs() >= 100.0 || self.value.fract() == 0.0 {
+            format!("{:.0}", self.value)
+        } else {
+            format!("{:.1}", self.value)
+        };
+        format!("{}{}", value, self.unit)
+    }
+}
+
+/// Active SSH sessions.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SshSessions {
+    /// Number of active remote sessions
+    pub count: u32,
+    /// Source host of the most recent login
+    pub last_source: Option<String>,
+}
+
+/// Static operating system information, read once at startup.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OsInfo {
+    /// Distribution name from os-release (e.g., "Debian GNU/Linux 12 (bookworm)")
+    pub distro: String,
+    /// Kernel release (e.g., "6.1.0-18-amd64")
+    pub kernel: String,
+    /// Machine architecture (e.g., "x86_64")
+    pub arch: String,
+}
+
+impl OsInfo {
+    /// Formats the info for display ("distro", "kernel" or "full").
+    pub fn format(&self, format: &str) -> String {
+        match format {
+            "distro" => self.distro.clone(),
+            "kernel" => format!("Linux {} {}", self.kernel, self.arch),
+            _ => format!("{} \u{b7} {}", self.distro, self.kernel),
+        }
+    }
+}
+
+/// Static CPU information, read once at startup.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CpuInfo {
+    /// Shortened model name (e.g., "Ryzen 7 5800U")
+    pub model: String,
+    /// Physical core count
+    pub cores: u32,
+    /// Hardware thread count
+    pub threads: u32,
+}
+
+impl CpuInfo {
+    /// Formats as "Ryzen 7 5800U · 8C/16T".
+    pub fn summary(&self) -> String {
+        format!("{} \u{b7} {}C/{}T", self.model, self.cores, self.threads)
+    }
+}
+
+/// Process and thread counts.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProcessCounts {
+    /// Number of processes
+    pub processes: u32,
+    /// Number of threads across all processes
+    pub threads: u32,
+    /// Number of zombie (defunct) processes
+    pub zombies: u32,
+}
+
+/// The application using the most CPU, with its processes combined.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AppUsage {
+    /// Command name shared by the processes
+    pub name: String,
+    /// Share of all CPU time (0-100)
+    pub cpu_percent: f64,
+}
+
+impl AppUsage {
+    /// Formats as "firefox 23%".
+    pub fn format(&self) -> String {
+        format!("{} {:.0}%", self.name, self.cpu_percent)
+    }
+}
+
+/// Errors logged to the system journal recently.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JournalErrors {
+    /// Number of errors in the last hour
+    pub count: u32,
+    /// Latest error as "identifier: message"
+    pub latest: Option<String>,
+}
+
+/// CPU thermal throttling state.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct ThrottleStatus {
+    /// Whether the CPU is being slowed down to limit its temperature
+    pub throttled: bool,
+    /// Current clock as a percentage of the hardware maximum (None if
+    /// cpufreq is unavailable)
+    pub clock_percent: Option<f64>,
+}
+
+impl ThrottleStatus {
+    /// Formats the state as "Throttled 62%" or "Not throttled".
+    pub fn format(&self) -> String {
+        match (self.throttled, self.clock_percent) {
+            (true, Some(percent)) => format!("Throttled {:.0}%", percent),
+            (true, None) => "Throttled".to_string(),
+            (false, _) => "Not throttled".to_string(),
+        }
+    }
+}
+
+/// Bytes transferred on an interface today and this month.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NetworkTotals {
+    /// Bytes received today
+    #[serde(default)]
+    pub day_rx: u64,
+    /// Bytes sent today
+    #[serde(default)]
+    pub day_tx: u64,
+    /// Bytes received this month
+    #[serde(default)]
+    pub month_rx: u64,
+    /// Bytes sent this month
+    #[serde(default)]
+    pub month_tx: u64,
+}
+
+impl NetworkTotals {
+    /// Returns the bytes received and sent today.
+    pub fn day(&self) -> u64 {
+        self.day_rx + self.day_tx
+    }
+
+    /// Returns the bytes received and sent this month.
+    pub fn month(&self) -> u64 {
+        self.month_rx + self.month_tx
+    }
+}
+
+/// Current I/O rates of one block device.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DiskDeviceRate {
+    /// Block device name (e.g. "nvme0n1")
+    pub device: String,
+    /// Read rate in bytes/sec
+    pub read_rate: f64,
+    /// Write rate in bytes/sec
+    pub write_rate: f64,
+}
+
+impl DiskDeviceRate {
+    /// Returns the combined read and write rate.
+    pub fn total_rate(&self) -> f64 {
+        self.read_rate + self.write_rate
+    }
+}
+
+/// Current, peak and average of a history graph's window.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct GraphStats {
+    /// Latest sample
+    pub current: f64,
+    /// Highest sample
+    pub peak: f64,
+    /// Mean of all samples
+    pub average: f64,
+}
+
+/// Audio output level and spectrum.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AudioLevels {
+    /// Overall level (0.0-1.0)
+    pub level: f32,
+    /// Spectrum bands from bass to treble (0.0-1.0 each)
+    pub bands: Vec<f32>,
+    /// Number of beats detected since capture started
+    pub beats: u64,
+}
+
+/// The current time in another timezone.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WorldTime {
+    /// City or user-supplied label
+    pub label: String,
+    /// Hour (0-23)
+    pub hour: u8,
+    /// Minute (0-59)
+    pub minute: u8,
+    /// Day of week (0=Sunday, 1=Monday, ..., 6=Saturday)
+    pub weekday: u8,
+    /// Days ahead (+1) or behind (-1) the panel's own date
+    pub day_offset: i32,
+}
+
+impl WorldTime {
+    /// Formats as "Label HH:MM", with "+1"/"-1" when the date differs.
+    pub fn format(&self) -> String {
+        let mut s = format!("{} {:02}:{:02}", self.label, self.hour, self.minute);
+        if self.day_offset != 0 {
+            s.push_str(&format!("{:+}", self.day_offset));
+        }
+        s
+    }
+}
+
+/// Kind of an event on the timeline.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EventKind {
+    /// A metric, storage pool or job entered an alert state
+    Alert,
+    FaceChanged,
+    /// The LCD stopped accepting frames
+    DeviceDisconnected,
+    /// The LCD accepts frames again
+    DeviceReconnected,
+    /// A warning or error logged to the system journal
+    Journal,
+}
+
+/// An event published on the daemon's event bus.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TimelineEvent {
+    /// Unix timestamp of the event
+    pub timestamp: u64,
+    pub kind: EventKind,
+    pub message: String,
+}
+
+/// Aggregated system data from all sensors.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SystemData {
+    /// Hostname of the system
+    pub hostname: String,
+    /// Current time formatted as "HH:MM" (24h format, for backwards compatibility)
+    pub time: String,
+    /// Hour (0-23)
+    pub hour: u8,
+    /// Minute (0-59)
+    pub minute: u8,
+    /// Day of month (1-31)
+    pub day: u8,
+    /// Month (1-12)
+    pub month: u8,
+    /// Year (e.g., 2024)
+    pub year: u16,
+    /// Day of week (0=Sunday, 1=Monday, ..., 6=Saturday)
+    pub day_of_week: u8,
+    /// Unix timestamp of the sample (seconds, UTC)
+    pub timestamp: u64,
+    /// Uptime formatted as "Xd Yh Zm"
+    pub uptime: String,
+    /// Uptime in seconds
+    pub uptime_secs: u64,
+    /// Boot time formatted as "Jan 15 09:32"
+    pub boot_time: String,
+    /// CPU usage percentage (0-100)
+    pub cpu_percent: f64,
+    /// CPU usage over the last [`HISTORY_SIZE`] samples, oldest first
+    pub cpu_history: VecDeque<f64>,
+    /// CPU temperature in Celsius (None if unavailable)
+    pub cpu_temp: Option<f64>,
+    /// CPU temperature over the last hour, one sample every
+    /// [`TEMP_HISTORY_INTERVAL`] seconds, oldest first
+    pub temp_history: VecDeque<f64>,
+    /// CPU thermal throttling (None if the system does not report it)
+    pub cpu_throttle: Option<ThrottleStatus>,
+    /// RAM usage percentage (0-100)
+    pub ram_percent: f64,
+    /// Disk read rate in bytes/second
+    pub disk_read_rate: f64,
+    /// Disk write rate in bytes/second
+    pub disk_write_rate: f64,
+    /// Disk I/O history (combined read+write rates, newest last)
+    pub disk_history: VecDeque<f64>,
+    /// Disk read history (bytes/sec, newest last)
+    pub disk_read_history: VecDeque<f64>,
+    /// Disk write history (bytes/sec, newest last)
+    pub disk_write_history: VecDeque<f64>,
+    /// Current rates of each tracked disk
+    pub disk_devices: Vec<DiskDeviceRate>,
+    /// Traffic totals for the monitored interface
+    pub net_totals: NetworkTotals,
+    /// Network interface name
+    pub net_interface: String,
+    /// Network receive rate in bytes/second
+    pub net_rx_rate: f64,
+    /// Network transmit rate in bytes/second
+    pub net_tx_rate: f64,
+    /// Network I/O history (combined rx+tx rates, newest last)
+    pub net_history: VecDeque<f64>,
+    /// Network receive history (bytes/sec, newest last)
+    pub net_rx_history: VecDeque<f64>,
+    /// Network transmit history (bytes/sec, newest last)
+    pub net_tx_history: VecDeque<f64>,
+    /// IP address to display (based on preference)
+    pub display_ip: Option<String>,
+    /// All addresses on the monitored interface (IPv4, GUA, ULA, LLA order)
+    pub ip_addresses: Vec<String>,
+    /// Units used for disk and network rates
+    pub rate_units: RateUnits,
+    /// Pending package updates (None if not checked yet or disabled)
+    pub pending_updates: Option<PendingUpdates>,
+    /// Active SSH sessions
+    pub ssh_sessions: SshSessions,
+    /// Process, thread and zombie counts
+    pub process_counts: ProcessCounts,
+    /// Application using the most CPU (None until measured)
+    pub top_app: Option<AppUsage>,
+    /// Recent journal errors (None unless the journal is followed)
+    pub journal_errors: Option<JournalErrors>,
+    /// Distribution, kernel and architecture
+    pub os_info: OsInfo,
+    /// CPU model and core counts
+    pub cpu_info: CpuInfo,
+    /// Audio levels (None when audio capture is not running)
+    pub audio: Option<AudioLevels>,
+    /// Monitored LAN hosts
+    pub hosts: Vec<HostStatus>,
+    /// Named custom metrics
+    pub metrics: BTreeMap<String, Metric>,
+    /// DNS blocking statistics (None if disabled or unreachable)
+    pub dns_stats: Option<DnsStats>,
+    /// GPU readings (None if no supported GPU was found)
+    pub gpu: Option<GpuStats>,
+    /// Libvirt virtual machines
+    pub vms: Vec<VmStatus>,
+    /// ZFS pools and md arrays
+    pub pools: Vec<PoolStatus>,
+    /// Jobs reported over D-Bus, by name
+    pub jobs: BTreeMap<String, JobStatus>,
+    /// 3D printer status (None if disabled or unreachable)
+    pub printer: Option<PrinterStatus>,
+    /// Torrent client statistics (None if disabled or unreachable)
+    pub torrents: Option<TorrentStats>,
+    /// Active media server streams (None if disabled or unreachable)
+    pub media_streams: Option<Vec<MediaStream>>,
+    /// Recent events from the event bus, newest first
+    pub events: Vec<TimelineEvent>,
+}
+
+impl SystemData {
+    /// Starts building sensor data from empty readings.
+    pub fn builder() -> SystemDataBuilder {
+        Self::default().to_builder()
+    }
+
+    /// Starts building sensor data from this data, to change some values.
+    pub fn to_builder(self) -> SystemDataBuilder {
+        SystemDataBuilder { data: self }
+    }
+
+    /// Formats time according to the specified format.
+    pub fn format_time(&self, format: &str) -> String {
+        match format {
+            "digital-12h" => {
+                let (hour_12, am_pm) = if self.hour == 0 {
+                    (12, "AM")
+                } else if self.hour < 12 {
+                    (self.hour, "AM")
+                } else if self.hour == 12 {
+                    (12, "PM")
+                } else {
+                    (self.hour - 12, "PM")
+                };
+                format!("{:2}:{:02} {}", hour_12, self.minute, am_pm)
+            }
+            "analogue" => {
+                // For analogue, return empty - faces should draw a clock
+                String::new()
+            }
+            // Default to digital-24h
+            _ => format!("{:02}:{:02}", self.hour, self.minute),
+        }
+    }
+
+    /// Returns the current time in another timezone for the world clock.
+    ///
+    /// `entry` is either `Label=Area/City` or a bare tz database name, in
+    /// which case the city part is used as the label. Returns None for
+    /// empty or unknown entries.
+    pub fn world_time(&self, entry: &str) -> Option<WorldTime> {
+        let entry = entry.trim();
+        let (label, zone) = match entry.split_once('=') {
+            Some((label, zone)) => (label.trim().to_string(), zone.trim()),
+            None => {
+                let city = entry.rsplit('/').next().unwrap_or(entry);
+                (city.replace('_', " "), entry)
+            }
+        };
+        let tz: Tz = zone.parse().ok()?;
+        let now = Utc.timestamp_opt(self.timestamp as i64, 0).single()?;
+        let zoned = now.with_timezone(&tz);
+
+        let local_date =
+            NaiveDate::from_ymd_opt(self.year as i32, self.month as u32, self.day as u32);
+        let day_offset = local_date
+            .map(|local| (zoned.date_naive() - local).num_days() as i32)
+            .unwrap_or(0);
+
+        Some(WorldTime {
+            label,
+            hour: zoned.hour() as u8,
+            minute: zoned.minute() as u8,
+            weekday: zoned.weekday().num_days_from_sunday() as u8,
+            day_offset,
+        })
+    }
+
+    /// Formats the uptime according to the format option.
+    ///
+    /// Formats: "short" (3d 4h 12m), "precise" (3d 04:12:45),
+    /// "days" (3 days) and "boot" (since Jan 15 09:32).
+    pub fn format_uptime(&self, format: &str) -> String {
+        let days = self.uptime_secs / 86400;
+        match format {
+            "precise" => format!(
+                "{}d {:02}:{:02}:{:02}",
+                days,
+                (self.uptime_secs % 86400) / 3600,
+                (self.uptime_secs % 3600) / 60,
+                self.uptime_secs % 60
+            ),
+            "days" if days == 1 => "1 day".to_string(),
+            "days" => format!("{} days", days),
+            "boot" => format!("since {}", self.boot_time),
+            _ => self.uptime.clone(),
+        }
+    }
+
+    /// Returns the milestone (in days) if one was reached in the last few minutes.
+    pub fn uptime_milestone(&self) -> Option<u64> {
+        let days = self.uptime_secs / 86400;
+        let into_day = self.uptime_secs % 86400;
+        (UPTIME_MILESTONES.contains(&days) && into_day < MILESTONE_WINDOW_SECS).then_some(days)
+    }
+
+    /// Formats date according to the specified format.
+    pub fn format_date(&self, format: &str) -> Option<String> {
+        let month_names = [
+            "January",
+            "February",
+            "March",
+            "April",
+            "May",
+            "June",
+            "July",
+            "August",
+            "September",
+            "October",
+            "November",
+            "December",
+        ];
+        let month_abbrev = [
+            "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+        ];
+        let weekday_abbrev = ["Sun", "Mon", "Tue", "Wed", "Thu", "Fri", "Sat"];
+
+        let month_idx = (self.month.saturating_sub(1) as usize).min(11);
+        let weekday_idx = (self.day_of_week as usize).min(6);
+
+        match format {
+            "hidden" => None,
+            "iso" => Some(format!(
+                "{:04}-{:02}-{:02}",
+                self.year, self.month, self.day
+            )),
+            "us" => Some(format!(
+                "{:02}/{:02}/{:04}",
+                self.month, self.day, self.year
+            )),
+            "eu" => Some(format!(
+                "{:02}/{:02}/{:04}",
+                self.day, self.month, self.year
+            )),
+            "short" => Some(format!("{} {}", month_abbrev[month_idx], self.day)),
+            "long" => Some(format!(
+                "{} {}, {}",
+                month_names[month_idx], self.day, self.year
+            )),
+            "weekday" => Some(format!(
+                "{}, {} {}",
+                weekday_abbrev[weekday_idx], month_abbrev[month_idx], self.day
+            )),
+            _ => None,
+        }
+    }
+
+    /// Formats a byte rate as a human-readable string (e.g., "1.2 MB/s")
+    pub fn format_rate(bytes_per_sec: f64) -> String {
+        Self::format_rate_with(bytes_per_sec, false, false)
+    }
+
+    /// Formats a byte rate compactly (e.g., "1.2M")
+    pub fn format_rate_compact(bytes_per_sec: f64) -> String {
+        Self::format_rate_compact_with(bytes_per_sec, false, false)
+    }
+
+    /// Scales a value to the largest fitting prefix, returning the scaled
+    /// value and the prefix index (0 = none, 1 = K, 2 = M, 3 = G).
+    ///
+    /// Values that would round up to the base move to the next prefix, so
+    /// 999.7 B/s is shown as 1.0 KB/s rather than 1000 B/s.
+    pub fn scale_rate(value: f64, binary: bool) -> (f64, usize) {
+        let base = if binary { 1024.0 } else { 1000.0 };
+        let mut scaled = value;
+        let mut prefix = 0;
+        while scaled.round() >= base && prefix < 3 {
+            scaled /= base;
+            prefix += 1;
+        }
+        (scaled, prefix)
+    }
+
+    /// Returns the unit prefix for a [`scale_rate`](Self::scale_rate) index.
+    pub fn rate_prefix(prefix: usize, binary: bool) -> &'static str {
+        let prefixes = if binary {
+            ["", "Ki", "Mi", "Gi"]
+        } else {
+            ["", "K", "M", "G"]
+        };
+        prefixes[prefix.min(3)]
+    }
+
+    /// Formats a byte rate in the given units (e.g., "1.2 MiB/s", "9.6 Mbit/s").
+    pub fn format_rate_with(bytes_per_sec: f64, binary: bool, bits: bool) -> String {
+        let value = if bits {
+            bytes_per_sec * 8.0
+        } else {
+            bytes_per_sec
+        };
+        let (scaled, prefix) = Self::scale_rate(value, binary);
+        let prefix = Self::rate_prefix(prefix, binary);
+        let unit = if bits { "bit/s" } else { "B/s" };
+        if prefix.is_empty() {
+            format!("{:.0} {}", scaled, unit)
+        } else {
+            format!("{:.1} {}{}", scaled, prefix, unit)
+        }
+    }
+
+    /// Formats a byte count using the configured prefixes (e.g., "412 GB").
+    pub fn format_bytes(&self, bytes: u64) -> String {
+        let binary = self.rate_units.binary;
+        let base: f64 = if binary { 1024.0 } else { 1000.0 };
+        let mut scaled = bytes as f64;
+        let mut prefix = 0;
+        while scaled.round() >= base && prefix < 4 {
+            scaled /= base;
+            prefix += 1;
+        }
+        let unit = match (prefix, binary) {
+            (0, _) => "B",
+            (1, false) => "KB",
+            (2, false) => "MB",
+            (3, false) => "GB",
+            (_, false) => "TB",
+            (1, true) => "KiB",
+            (2, true) => "MiB",
+            (3, true) => "GiB",
+            (_, true) => "TiB",
+        };
+        if prefix == 0 || scaled >= 100.0 {
+            format!("{:.0} {}", scaled, unit)
+        } else {
+            format!("{:.1} {}", scaled, unit)
+        }
+    }
+
+    /// Formats a byte rate compactly in the given units (e.g., "1.2M",
+    /// "1.2Mi", "9.6Mb").
+    pub fn format_rate_compact_with(bytes_per_sec: f64, binary: bool, bits: bool) -> String {
+        let value = if bits {
+            bytes_per_sec * 8.0
+        } else {
+            bytes_per_sec
+        };
+        let (scaled, prefix) = Self::scale_rate(value, binary);
+        let prefix = Self::rate_prefix(prefix, binary);
+        match (prefix.is_empty(), bits) {
+            (true, false) => format!("{:.0}B", scaled),
+            (true, true) => format!("{:.0}b", scaled),
+            (false, false) => format!("{:.1}{}", scaled, prefix),
+            (false, true) => format!("{:.1}{}b", scaled, prefix),
+        }
+    }
+
+    /// Formats a disk rate using the configured units.
+    pub fn format_disk_rate(&self, bytes_per_sec: f64) -> String {
+        Self::format_rate_with(bytes_per_sec, self.rate_units.binary, false)
+    }
+
+    /// Returns the tracked disk with the highest combined rate, if any is busy.
+    pub fn busiest_disk(&self) -> Option<&DiskDeviceRate> {
+        self.disk_devices
+            .iter()
+            .filter(|d| d.total_rate() > 0.0)
+            .max_by(|a, b| a.total_rate().total_cmp(&b.total_rate()))
+    }
+
+    /// Formats a disk rate compactly using the configured units.
+    pub fn format_disk_rate_compact(&self, bytes_per_sec: f64) -> String {
+        Self::format_rate_compact_with(bytes_per_sec, self.rate_units.binary, false)
+    }
+
+    /// Formats a network rate using the configured units.
+    pub fn format_net_rate(&self, bytes_per_sec: f64) -> String {
+        Self::format_rate_with(
+            bytes_per_sec,
+            self.rate_units.binary,
+            self.rate_units.network_bits,
+        )
+    }
+
+    /// Formats a network rate compactly using the configured units.
+    pub fn format_net_rate_compact(&self, bytes_per_sec: f64) -> String {
+        Self::format_rate_compact_with(
+            bytes_per_sec,
+            self.rate_units.binary,
+            self.rate_units.network_bits,
+        )
+    }
+
+    /// Computes an appropriate max scale value for graphing I/O history.
+    ///
+    /// This provides auto-scaling so graphs remain useful at any rate.
+    /// The returned value is rounded up to a "nice" number to avoid
+    /// constant scale changes.
+    pub fn compute_graph_scale(history: &VecDeque<f64>) -> f64 {
+        const MIN_SCALE: f64 = 1_000_000.0; // 1 MB/s minimum
+
+        let max_val = history.iter().copied().fold(0.0_f64, |a, b| a.max(b));
+
+        if max_val <= MIN_SCALE {
+            return MIN_SCALE;
+        }
+
+        // Round up to next power of 10, then snap to 1x, 2x, or 5x
+        let magnitude = 10_f64.powf(max_val.log10().floor());
+        let normalized = max_val / magnitude;
+
+        let multiplier = if normalized <= 1.0 {
+            1.0
+        } else if normalized <= 2.0 {
+            2.0
+        } else if normalized <= 5.0 {
+            5.0
+        } else {
+            10.0
+        };
+
+        (magnitude * multiplier).max(MIN_SCALE)
+    }
+
+    /// Returns the current, peak and average values of a history, all zero
+    /// if it is empty. Pass the raw history so smoothing does not hide
+    /// spikes.
+    pub fn graph_stats(history: &VecDeque<f64>) -> GraphStats {
+        if history.is_empty() {
+            return GraphStats::default();
+        }
+        GraphStats {
+            current: history.back().copied().unwrap_or(0.0),
+            peak: history.iter().copied().fold(0.0, f64::max),
+            average: history.iter().sum::<f64>() / history.len() as f64,
+        }
+    }
+
+    /// Smooths a history with an exponential moving average spanning
+    /// roughly `window` samples. A window of 1 or less returns the raw data.
+    pub fn smooth_history(history: &VecDeque<f64>, window: u32) -> VecDeque<f64> {
+        if window <= 1 {
+            return history.clone();
+        }
+        let alpha = 2.0 / (window as f64 + 1.0);
+        let mut average = None;
+        history
+            .iter()
+            .map(|&value| {
+                let next = average.map_or(value, |avg: f64| avg + alpha * (value - avg));
+                average = Some(next);
+                next
+            })
+            .collect()
+    }
+}
+
+/// Builder for [`SystemData`], mainly for tests and previews.
+///
+/// Setters keep derived fields consistent, such as the time components of a
+/// timestamp or the combined history of read and write rates. Anything
+/// without a setter can be changed with [`with`](Self::with).
+#[derive(Debug, Clone)]
+pub struct SystemDataBuilder {
+    data: SystemData,
+}
+
+impl SystemDataBuilder {
+    /// Sets the hostname.
+    pub fn hostname(mut self, hostname: impl Into<String>) -> Self {
+        self.data.hostname = hostname.into();
+        self
+    }
+
+    /// Sets the sample time from a Unix timestamp, filling in the time
+    /// components in UTC. Timestamps past the year 262143, which chrono
+    /// can't represent, leave the sample time unchanged.
+    pub fn timestamp(mut self, timestamp: u64) -> Self {
+        let time = i64::try_from(timestamp)
+            .ok()
+            .and_then(|secs| Utc.timestamp_opt(secs, 0).single());
+        let Some(time) = time else {
+            return self;
+        };
+        let data = &mut self.data;
+        data.timestamp = timestamp;
+        data.hour = time.hour() as u8;
+        data.minute = time.minute() as u8;
+        data.day = time.day() as u8;
+        data.month = time.month() as u8;
+        data.year = time.year() as u16;
+        data.day_of_week = time.weekday().num_days_from_sunday() as u8;
+        data.time = format!("{:02}:{:02}", data.hour, data.minute);
+        self
+    }
+
+    /// Sets the uptime, formatted as the daemon does. The boot time is
+    /// filled in on [`build`](Self::build) unless set.
+    pub fn uptime(mut self, secs: u64) -> Self {
+        self.data.uptime_secs = secs;
+        self.data.uptime = format_uptime(secs);
+        self.data.boot_time.clear();
+        self
+    }
+
+    /// Sets the CPU usage percentage.
+    pub fn cpu_percent(mut self, percent: f64) -> Self {
+        self.data.cpu_percent = percent;
+        self
+    }
+
+    /// Sets the CPU usage history, oldest first.
+    pub fn cpu_history(mut self, history: impl IntoIterator<Item = f64>) -> Self {
+        self.data.cpu_history = history.into_iter().collect();
+        self
+    }
+
+    /// Sets the CPU temperature, None for a system without a sensor.
+    pub fn cpu_temp(mut self, celsius: Option<f64>) -> Self {
+        self.data.cpu_temp = celsius;
+        self
+    }
+
+    /// Sets the CPU temperature history, oldest first.
+    pub fn temp_history(mut self, history: impl IntoIterator<Item = f64>) -> Self {
+        self.data.temp_history = history.into_iter().collect();
+        self
+    }
+
+    /// Sets the CPU throttling status.
+    pub fn cpu_throttle(mut self, throttle: Option<ThrottleStatus>) -> Self {
+        self.data.cpu_throttle = throttle;
+        self
+    }
+
+    /// Sets the RAM usage percentage.
+    pub fn ram_percent(mut self, percent: f64) -> Self {
+        self.data.ram_percent = percent;
+        self
+    }
+
+    /// Sets the disk read and write rates in bytes/second.
+    pub fn disk_rates(mut self, read: f64, write: f64) -> Self {
+        self.data.disk_read_rate = read;
+        self.data.disk_write_rate = write;
+        self
+    }
+
+    /// Sets the disk read and write histories, newest last, and their
+    /// combined history.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the histories have different lengths.
+    pub fn disk_history(
+        mut self,
+        read: impl IntoIterator<Item = f64>,
+        write: impl IntoIterator<Item = f64>,
+    ) -> Self {
+        let (read, write, combined) = split_history(read, write);
+        self.data.disk_read_history = read;
+        self.data.disk_write_history = write;
+        self.data.disk_history = combined;
+        self
+    }
+
+    /// Sets the monitored network interface.
+    pub fn net_interface(mut self, interface: impl Into<String>) -> Self {
+        self.data.net_interface = interface.into();
+        self
+    }
+
+    /// Sets the network receive and transmit rates in bytes/second.
+    pub fn net_rates(mut self, rx: f64, tx: f64) -> Self {
+        self.data.net_rx_rate = rx;
+        self.data.net_tx_rate = tx;
+        self
+    }
+
+    /// Sets the network receive and transmit histories, newest last, and
+    /// their combined history.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the histories have different lengths.
+    pub fn net_history(
+        mut self,
+        rx: impl IntoIterator<Item = f64>,
+        tx: impl IntoIterator<Item = f64>,
+    ) -> Self {
+        let (rx, tx, combined) = split_history(rx, tx);
+        self.data.net_rx_history = rx;
+        self.data.net_tx_history = tx;
+        self.data.net_history = combined;
+        self
+    }
+
+    /// Sets the interface's addresses, displaying the first.
+    pub fn ip_addresses<S: Into<String>>(mut self, addresses: impl IntoIterator<Item = S>) -> Self {
+        self.data.ip_addresses = addresses.into_iter().map(Into::into).collect();
+        self.data.display_ip = self.data.ip_addresses.first().cloned();
+        self
+    }
+
+    /// Clears the CPU, temperature, disk and network histories, as right
+    /// after the daemon starts.
+    pub fn empty_histories(mut self) -> Self {
+        let data = &mut self.data;
+        for history in [
+            &mut data.cpu_history,
+            &mut data.temp_history,
+            &mut data.disk_history,
+            &mut data.disk_read_history,
+            &mut data.disk_write_history,
+            &mut data.net_history,
+            &mut data.net_rx_history,
+            &mut data.net_tx_history,
+        ] {
+            history.clear();
+        }
+        self
+    }
+
+    /// Changes any other field.
+    pub fn with(mut self, change: impl FnOnce(&mut SystemData)) -> Self {
+        change(&mut self.data);
+        self
+    }
+
+    /// Returns the data.
+    pub fn build(mut self) -> SystemData {
+        let data = &mut self.data;
+        if data.boot_time.is_empty() && data.uptime_secs > 0 {
+            let boot = data.timestamp.saturating_sub(data.uptime_secs);
+            if let Some(boot) = Utc.timestamp_opt(boot as i64, 0).single() {
+                data.boot_time = boot.format("%b %d %H:%M").to_string();
+            }
+        }
+        self.data
+    }
+}
+
+/// Collects two histories and their sum, as the sensors keep them. The
+/// sensors sample both together, so the histories must be the same length.
+fn split_history(
+    first: impl IntoIterator<Item = f64>,
+    second: impl IntoIterator<Item = f64>,
+) -> (VecDeque<f64>, VecDeque<f64>, VecDeque<f64>) {
+    let first: VecDeque<f64> = first.into_iter().collect();
+    let second: VecDeque<f64> = second.into_iter().collect();
+    assert_eq!(
+        first.len(),
+        second.len(),
+        "histories of one rate pair must be the same length"
+    );
+    let combined = first.iter().zip(&second).map(|(a, b)| a + b).collect();
+    (first, second, combined)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_throttle_format() {
+        let status = |throttled, clock_percent| ThrottleStatus {
+            throttled,
+            clock_percent,
+        };
+        assert_eq!(status(true, Some(61.6)).format(), "Throttled 62%");
+        assert_eq!(status(true, None).format(), "Throttled");
+        assert_eq!(status(false, Some(100.0)).format(), "Not throttled");
+    }
+
+    #[test]
+    fn test_graph_stats() {
+        assert_eq!(
+            SystemData::graph_stats(&VecDeque::new()),
+            GraphStats::default()
+        );
+        let stats = SystemData::graph_stats(&VecDeque::from([2.0, 8.0, 5.0, 1.0]));
+        assert_eq!(
+            stats,
+            GraphStats {
+                current: 1.0,
+                peak: 8.0,
+                average: 4.0,
+            }
+        );
+    }
+
+    #[test]
+    fn test_busiest_disk() {
+        let disk = |device: &str, read_rate: f64, write_rate: f64| DiskDeviceRate {
+            device: device.to_string(),
+            read_rate,
+            write_rate,
+        };
+        let mut data = SystemData::default();
+        assert!(data.busiest_disk().is_none());
+
+        data.disk_devices = vec![disk("sda", 0.0, 0.0), disk("nvme0n1", 0.0, 0.0)];
+        assert!(data.busiest_disk().is_none());
+
+        data.disk_devices = vec![
+            disk("sda", 3.0, 4.0),
+            disk("nvme0n1", 6.0, 0.0),
+            disk("sdb", 0.0, 0.0),
+        ];
+        assert_eq!(data.busiest_disk().unwrap().device, "sda");
+    }
+
+    #[test]
+    fn test_smooth_history() {
+        let history = VecDeque::from([0.0, 10.0, 10.0, 0.0]);
+        assert_eq!(SystemData::smooth_history(&history, 0), history);
+        assert_eq!(SystemData::smooth_history(&history, 1), history);
+
+        // A window of 3 weighs each new sample by a half
+        assert_eq!(
+            SystemData::smooth_history(&history, 3),
+            VecDeque::from([0.0, 5.0, 7.5, 3.75])
+        );
+        assert!(SystemData::smooth_history(&VecDeque::new(), 5).is_empty());
+    }
+
+    #[test]
+    fn test_world_time() {
+        // 14:32 UTC on Friday 2026-10-16, shown in UTC on the panel
+        let data = SystemData::builder().timestamp(1792161120).build();
+
+        let tokyo = data.world_time("Office=Asia/Tokyo").unwrap();
+        assert_eq!(tokyo.label, "Office");
+        assert_eq!((tokyo.hour, tokyo.minute, tokyo.day_offset), (23, 32, 0));
+
+        let los_angeles = data.world_time(" America/Los_Angeles ").unwrap();
+        assert_eq!(los_angeles.label, "Los Angeles");
+        assert_eq!((los_angeles.hour, los_angeles.day_offset), (7, 0));
+
+        // Auckland is already into Saturday
+        let auckland = data.world_time("Pacific/Auckland").unwrap();
+        assert_eq!((auckland.hour, auckland.minute), (3, 32));
+        assert_eq!((auckland.weekday, auckland.day_offset), (6, 1));
+        assert_eq!(auckland.format(), "Auckland 03:32+1");
+
+        assert_eq!(data.world_time(""), None);
+        assert_eq!(data.world_time("Home=Mars/Olympus_Mons"), None);
+    }
+
+    #[test]
+    fn test_format_rates() {
+        assert_eq!(SystemData::format_rate(0.0), "0 B/s");
+        assert_eq!(SystemData::format_rate(999.4), "999 B/s");
+        assert_eq!(SystemData::format_rate(999.7), "1.0 KB/s");
+        assert_eq!(SystemData::format_rate(1_500_000.0), "1.5 MB/s");
+        assert_eq!(SystemData::format_rate(2e12), "2000.0 GB/s");
+        assert_eq!(
+            SystemData::format_rate_with(1_048_576.0, true, false),
+            "1.0 MiB/s"
+        );
+        assert_eq!(
+            SystemData::format_rate_with(1023.8, true, false),
+            "1.0 KiB/s"
+        );
+        assert_eq!(
+            SystemData::format_rate_with(125_000.0, false, true),
+            "1.0 Mbit/s"
+        );
+
+        assert_eq!(SystemData::format_rate_compact(999.7), "1.0K");
+        assert_eq!(SystemData::format_rate_compact(12.0), "12B");
+        assert_eq!(
+            SystemData::format_rate_compact_with(1536.0, true, false),
+            "1.5Ki"
+        );
+        assert_eq!(
+            SystemData::format_rate_compact_with(125_000.0, false, true),
+            "1.0Mb"
+        );
+
+        let mut data = SystemData::default();
+        assert_eq!(data.format_bytes(999), "999 B");
+        assert_eq!(data.format_bytes(999_999), "1.0 MB");
+        assert_eq!(data.format_bytes(412_000_000_000), "412 GB");
+        data.rate_units.binary = true;
+        assert_eq!(data.format_bytes(1536), "1.5 KiB");
+        assert_eq!(data.format_net_rate(2048.0), "2.0 KiB/s");
+        data.rate_units.network_bits = true;
+        assert_eq!(data.format_net_rate(128.0), "1.0 Kibit/s");
+        assert_eq!(data.format_disk_rate(128.0), "128 B/s");
+    }
+
+    #[test]
+    fn test_gpu_cells() {
+        let gpu = GpuStats {
+            name: "RTX".to_string(),
+            utilization: Some(45.4),
+            temperature: Some(61.6),
+            fan_percent: None,
+            fan_rpm: Some(1200),
+            power_watts: Some(120.0),
+        };
+        assert_eq!(gpu.cells(), ["45%", "62°C", "F1200", "120W"]);
+
+        let gpu = GpuStats {
+            fan_percent: Some(40.0),
+            ..gpu
+        };
+        assert_eq!(gpu.cells()[2], "F40%");
+        assert!(GpuStats::default().cells().is_empty());
+    }
+
+    #[test]
+    fn test_builder() {
+        let data = SystemData::builder()
+            .timestamp(1792161120)
+            .uptime(274_000)
+            .disk_history([1.0, 2.0], [3.0, 4.0])
+            .build();
+        assert_eq!(data.time, "14:32");
+        assert_eq!((data.year, data.month, data.day), (2026, 10, 16));
+        assert_eq!(data.day_of_week, 5);
+        assert_eq!(data.uptime, "3d 4h 6m");
+        assert_eq!(data.boot_time, "Oct 13 10:25");
+        assert_eq!(data.disk_history, [4.0, 6.0]);
+
+        let data = data.to_builder().empty_histories().build();
+        assert!(data.disk_history.is_empty() && data.disk_read_history.is_empty());
+        assert_eq!(data.boot_time, "Oct 13 10:25");
+
+        // Out of chrono's range
+        let data = data.to_builder().timestamp(u64::MAX).build();
+        assert_eq!(data.timestamp, 1792161120);
+        assert_eq!(data.time, "14:32");
+
+        assert_eq!(format_uptime(59), "0m");
+        assert_eq!(format_uptime(3600 + 120), "1h 2m");
+    }
+
+    #[test]
+    #[should_panic(expected = "same length")]
+    fn test_builder_uneven_history() {
+        SystemData::builder().net_history([1.0, 2.0], [3.0]);
+    }
+}
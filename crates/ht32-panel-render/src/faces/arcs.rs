@@ -7,10 +7,10 @@ use std::f32::consts::PI;
 
 use super::{
     complication_names, complication_options, complications, date_formats, draw_mini_analog_clock,
-    time_formats, Complication, EnabledComplications, Face, Theme,
+    time_formats, uptime_formats, Complication, EnabledComplications, Face, Theme,
 };
+use crate::data::{collector_names, SystemData};
 use crate::rendering::Canvas;
-use crate::sensors::data::SystemData;
 
 /// Dim a color by mixing it toward the background.
 fn dim_color(color: u32, background: u32, factor: f32) -> u32 {
@@ -60,31 +60,25 @@ const FONT_NORMAL: f32 = 14.0;
 const FONT_SMALL: f32 = 12.0;
 const FONT_TINY: f32 = 11.0;
 
-/// Formats a byte rate compactly with max 4 characters (e.g., "1.2M", "12M", "999K").
-fn format_rate_short(bytes_per_sec: f64) -> String {
-    if bytes_per_sec >= 1_000_000_000.0 {
-        let val = bytes_per_sec / 1_000_000_000.0;
-        if val >= 10.0 {
-            format!("{:.0}G", val)
-        } else {
-            format!("{:.1}G", val)
-        }
-    } else if bytes_per_sec >= 1_000_000.0 {
-        let val = bytes_per_sec / 1_000_000.0;
-        if val >= 10.0 {
-            format!("{:.0}M", val)
-        } else {
-            format!("{:.1}M", val)
-        }
-    } else if bytes_per_sec >= 1_000.0 {
-        let val = bytes_per_sec / 1_000.0;
-        if val >= 10.0 {
-            format!("{:.0}K", val)
-        } else {
-            format!("{:.1}K", val)
-        }
+/// Formats a rate compactly with max 4 characters (e.g., "1.2M", "12M", "999K"),
+/// plus an "i" for binary prefixes.
+///
+/// Bit rates get a trailing "b" and drop the decimal place to stay short.
+fn format_rate_short(bytes_per_sec: f64, binary: bool, bits: bool) -> String {
+    let value = if bits {
+        bytes_per_sec * 8.0
     } else {
-        format!("{:.0}", bytes_per_sec)
+        bytes_per_sec
+    };
+    let (val, prefix) = SystemData::scale_rate(value, binary);
+    let prefix = SystemData::rate_prefix(prefix, binary);
+    let suffix = if bits { "b" } else { "" };
+    if prefix.is_empty() {
+        format!("{:.0}{}", val, suffix)
+    } else if val >= 9.95 || bits {
+        format!("{:.0}{}{}", val, prefix, suffix)
+    } else {
+        format!("{:.1}{}", val, prefix)
     }
 }
 
@@ -210,9 +204,15 @@ impl Face for ArcsFace {
             complications::network(true),
             complications::disk_io(true),
             complications::cpu_temp(false),
+            complications::hostname(true),
+            complications::uptime(true),
         ]
     }
 
+    fn required_sensors(&self) -> &'static [&'static str] {
+        &[collector_names::DISK, collector_names::NETWORK]
+    }
+
     fn render(
         &self,
         canvas: &mut Canvas,
@@ -246,6 +246,16 @@ impl Face for ArcsFace {
             .map(|s| s.as_str())
             .unwrap_or(date_formats::ISO);
 
+        // Get uptime format option
+        let uptime_format = comp
+            .get_option(
+                self.name(),
+                complication_names::UPTIME,
+                complication_options::UPTIME_FORMAT,
+            )
+            .map(|s| s.as_str())
+            .unwrap_or(uptime_formats::SHORT);
+
         if portrait {
             // Portrait layout: CPU, RAM stacked vertically, disk/net on separate rows at bottom
             let margin = 6;
@@ -380,7 +390,8 @@ impl Face for ArcsFace {
                     colors.primary,
                     colors.arc_bg,
                 );
-                let disk_r_text = format_rate_short(data.disk_read_rate);
+                let disk_r_text =
+                    format_rate_short(data.disk_read_rate, data.rate_units.binary, false);
                 let disk_r_w = canvas.text_width(&disk_r_text, FONT_TINY);
                 canvas.draw_text(
                     disk_r_cx - disk_r_w / 2,
@@ -409,7 +420,8 @@ impl Face for ArcsFace {
                     colors.primary,
                     colors.arc_bg,
                 );
-                let disk_w_text = format_rate_short(data.disk_write_rate);
+                let disk_w_text =
+                    format_rate_short(data.disk_write_rate, data.rate_units.binary, false);
                 let disk_w_w = canvas.text_width(&disk_w_text, FONT_TINY);
                 canvas.draw_text(
                     disk_w_cx - disk_w_w / 2,
@@ -443,7 +455,11 @@ impl Face for ArcsFace {
                     colors.secondary,
                     colors.arc_bg,
                 );
-                let net_rx_text = format_rate_short(data.net_rx_rate);
+                let net_rx_text = format_rate_short(
+                    data.net_rx_rate,
+                    data.rate_units.binary,
+                    data.rate_units.network_bits,
+                );
                 let net_rx_w = canvas.text_width(&net_rx_text, FONT_TINY);
                 canvas.draw_text(
                     net_rx_cx - net_rx_w / 2,
@@ -472,7 +488,11 @@ impl Face for ArcsFace {
                     colors.secondary,
                     colors.arc_bg,
                 );
-                let net_tx_text = format_rate_short(data.net_tx_rate);
+                let net_tx_text = format_rate_short(
+                    data.net_tx_rate,
+                    data.rate_units.binary,
+                    data.rate_units.network_bits,
+                );
                 let net_tx_w = canvas.text_width(&net_tx_text, FONT_TINY);
                 canvas.draw_text(
                     net_tx_cx - net_tx_w / 2,
@@ -494,18 +514,22 @@ impl Face for ArcsFace {
             let bottom_y = height as i32 - margin - 46;
 
             // Hostname centered on its own line
-            let host_width = canvas.text_width(&data.hostname, FONT_TINY);
-            canvas.draw_text(
-                (width as i32 - host_width) / 2,
-                bottom_y,
-                &data.hostname,
-                FONT_TINY,
-                colors.dim,
-            );
+            if is_on(complication_names::HOSTNAME) {
+                let host_width = canvas.text_width(&data.hostname, FONT_TINY);
+                canvas.draw_text(
+                    (width as i32 - host_width) / 2,
+                    bottom_y,
+                    &data.hostname,
+                    FONT_TINY,
+                    colors.dim,
+                );
+            }
 
             // Uptime on its own line
-            let uptime_text = format!("Up: {}", data.uptime);
-            canvas.draw_text(margin, bottom_y + 12, &uptime_text, FONT_TINY, colors.dim);
+            if is_on(complication_names::UPTIME) {
+                let uptime_text = format!("Up: {}", data.format_uptime(uptime_format));
+                canvas.draw_text(margin, bottom_y + 12, &uptime_text, FONT_TINY, colors.dim);
+            }
 
             // IP on the next line
             if is_on(complication_names::IP_ADDRESS) {
@@ -546,15 +570,17 @@ impl Face for ArcsFace {
                 }
             }
 
-            // Hostname at top right (always shown)
-            let host_width = canvas.text_width(&data.hostname, FONT_SMALL);
-            canvas.draw_text(
-                width as i32 - margin - host_width,
-                top_y,
-                &data.hostname,
-                FONT_SMALL,
-                colors.dim,
-            );
+            // Complication: Hostname at top right
+            if is_on(complication_names::HOSTNAME) {
+                let host_width = canvas.text_width(&data.hostname, FONT_SMALL);
+                canvas.draw_text(
+                    width as i32 - margin - host_width,
+                    top_y,
+                    &data.hostname,
+                    FONT_SMALL,
+                    colors.dim,
+                );
+            }
 
             // Complication: Date (below hostname if shown)
             if is_on(complication_names::DATE) {
@@ -637,7 +663,8 @@ impl Face for ArcsFace {
                     colors.arc_bg,
                 );
                 // Number centered in dial
-                let disk_r_text = format_rate_short(data.disk_read_rate);
+                let disk_r_text =
+                    format_rate_short(data.disk_read_rate, data.rate_units.binary, false);
                 let disk_r_w = canvas.text_width(&disk_r_text, FONT_TINY);
                 canvas.draw_text(
                     disk_r_cx - disk_r_w / 2,
@@ -668,7 +695,8 @@ impl Face for ArcsFace {
                     colors.arc_bg,
                 );
                 // Number centered in dial
-                let disk_w_text = format_rate_short(data.disk_write_rate);
+                let disk_w_text =
+                    format_rate_short(data.disk_write_rate, data.rate_units.binary, false);
                 let disk_w_w = canvas.text_width(&disk_w_text, FONT_TINY);
                 canvas.draw_text(
                     disk_w_cx - disk_w_w / 2,
@@ -702,7 +730,11 @@ impl Face for ArcsFace {
                     colors.arc_bg,
                 );
                 // Number centered in dial
-                let net_rx_text = format_rate_short(data.net_rx_rate);
+                let net_rx_text = format_rate_short(
+                    data.net_rx_rate,
+                    data.rate_units.binary,
+                    data.rate_units.network_bits,
+                );
                 let net_rx_w = canvas.text_width(&net_rx_text, FONT_TINY);
                 canvas.draw_text(
                     disk_r_cx - net_rx_w / 2,
@@ -733,7 +765,11 @@ impl Face for ArcsFace {
                     colors.arc_bg,
                 );
                 // Number centered in dial
-                let net_tx_text = format_rate_short(data.net_tx_rate);
+                let net_tx_text = format_rate_short(
+                    data.net_tx_rate,
+                    data.rate_units.binary,
+                    data.rate_units.network_bits,
+                );
                 let net_tx_w = canvas.text_width(&net_tx_text, FONT_TINY);
                 canvas.draw_text(
                     net_w_cx - net_tx_w / 2,
@@ -752,10 +788,12 @@ impl Face for ArcsFace {
                 );
             }
 
-            // Base element: Uptime at bottom (always shown)
+            // Complication: Uptime at bottom
             let bottom_y = height as i32 - margin - 14;
-            let uptime_text = format!("Up: {}", data.uptime);
-            canvas.draw_text(margin, bottom_y, &uptime_text, FONT_TINY, colors.dim);
+            if is_on(complication_names::UPTIME) {
+                let uptime_text = format!("Up: {}", data.format_uptime(uptime_format));
+                canvas.draw_text(margin, bottom_y, &uptime_text, FONT_TINY, colors.dim);
+            }
 
             // Complication: IP address
             if is_on(complication_names::IP_ADDRESS) {
@@ -774,3 +812,19 @@ impl Face for ArcsFace {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_rate_short() {
+        assert_eq!(format_rate_short(999.0, false, false), "999");
+        assert_eq!(format_rate_short(1_200_000.0, false, false), "1.2M");
+        assert_eq!(format_rate_short(9_970_000.0, false, false), "10M");
+        assert_eq!(format_rate_short(999_700.0, false, false), "1.0M");
+        assert_eq!(format_rate_short(1_258_291.0, true, false), "1.2Mi");
+        assert_eq!(format_rate_short(1_500_000.0, false, true), "12Mb");
+        assert_eq!(format_rate_short(1_572_864.0, true, true), "12Mib");
+    }
+}
@@ -29,13 +29,30 @@
 //! NET  D:1.2M U:0.8M
 //! [__..--==++**##**++==..]
 //! ```
+//!
+//! The optional GPU strip gets its own section after RAM in portrait and
+//! shares the IP line in landscape.
 
 use super::{
     complication_names, complication_options, complications, date_formats, draw_mini_analog_clock,
-    time_formats, Complication, EnabledComplications, Face, Theme,
+    time_formats, uptime_formats, Complication, EnabledComplications, Face, Theme,
 };
+use crate::data::{collector_names, GraphStats, JournalErrors, SystemData};
 use crate::rendering::Canvas;
-use crate::sensors::data::SystemData;
+
+/// Formats the peak and average of a sparkline as "max:16M avg:9.1M".
+fn stats_text(stats: GraphStats, format: impl Fn(f64) -> String) -> String {
+    format!("max:{} avg:{}", format(stats.peak), format(stats.average))
+}
+
+/// Joins as many GPU reading cells as fit in `max_width`, dropping them
+/// from the end. None if not even the first one fits.
+fn fit_cells(canvas: &Canvas, cells: &[String], max_width: i32, size: f32) -> Option<String> {
+    (1..=cells.len())
+        .rev()
+        .map(|n| cells[..n].join(" "))
+        .find(|text| canvas.text_width(text, size) <= max_width)
+}
 
 /// Dim a color by mixing it toward the background.
 fn dim_color(color: u32, background: u32, factor: f32) -> u32 {
@@ -137,6 +154,28 @@ impl AsciiFace {
     pub fn new() -> Self {
         Self
     }
+
+    /// Draws the journal error count, and the latest error if `latest`,
+    /// as an inverted status line along the bottom edge.
+    fn draw_error_line(
+        canvas: &mut Canvas,
+        errors: &JournalErrors,
+        latest: bool,
+        theme: &Theme,
+        margin: i32,
+    ) {
+        let (width, height) = canvas.dimensions();
+        let line_height = canvas.line_height(FONT_SMALL);
+        let y = height as i32 - line_height - 2;
+        canvas.fill_rect(0, y - 1, width, line_height as u32 + 3, theme.primary);
+
+        let mut text = format!("ERR:{}", errors.count);
+        if let Some(message) = errors.latest.as_ref().filter(|_| latest) {
+            text = format!("{} {}", text, message);
+        }
+        let text = canvas.truncate_to_width(&text, FONT_SMALL, width as i32 - margin * 2);
+        canvas.draw_text(margin, y, &text, FONT_SMALL, theme.background);
+    }
 }
 
 impl Default for AsciiFace {
@@ -155,9 +194,27 @@ impl Face for AsciiFace {
             complications::time(true),
             complications::date(true, date_formats::ISO),
             complications::ip_address(true),
-            complications::network(true),
-            complications::disk_io(true),
+            complications::network(true).with_extra_options(vec![
+                complications::smoothing_option(),
+                complications::stats_option(),
+            ]),
+            complications::disk_io(true).with_extra_options(vec![
+                complications::smoothing_option(),
+                complications::stats_option(),
+            ]),
             complications::cpu_temp(true),
+            complications::hostname(true),
+            complications::uptime(true),
+            complications::journal_errors(false),
+            complications::gpu(false),
+        ]
+    }
+
+    fn required_sensors(&self) -> &'static [&'static str] {
+        &[
+            collector_names::TEMPERATURE,
+            collector_names::DISK,
+            collector_names::NETWORK,
         ]
     }
 
@@ -169,13 +226,19 @@ impl Face for AsciiFace {
         complications: &EnabledComplications,
     ) {
         let colors = FaceColors::from_theme(theme);
-        let (width, _height) = canvas.dimensions();
+        let (width, height) = canvas.dimensions();
         let portrait = width < 200;
         let margin = 6;
         let mut y = 4; // Start near top
         let bar_chars = if portrait { 10 } else { 16 };
 
         let is_enabled = |id: &str| complications.is_enabled(self.name(), id, true);
+        let gpu_cells = data
+            .gpu
+            .as_ref()
+            .filter(|_| complications.is_enabled(self.name(), complication_names::GPU, false))
+            .map(|gpu| gpu.cells())
+            .filter(|cells| !cells.is_empty());
 
         // Get time format option
         let time_format = complications
@@ -197,15 +260,60 @@ impl Face for AsciiFace {
             .map(|s| s.as_str())
             .unwrap_or(date_formats::ISO);
 
+        // Get uptime format option
+        let uptime_format = complications
+            .get_option(
+                self.name(),
+                complication_names::UPTIME,
+                complication_options::UPTIME_FORMAT,
+            )
+            .map(|s| s.as_str())
+            .unwrap_or(uptime_formats::SHORT);
+
+        // Smooth the sparkline histories
+        let disk_history = SystemData::smooth_history(
+            &data.disk_history,
+            complications.smoothing(self.name(), complication_names::DISK_IO),
+        );
+        let net_history = SystemData::smooth_history(
+            &data.net_history,
+            complications.smoothing(self.name(), complication_names::NETWORK),
+        );
+
+        // Peak and average over the sparkline window, from the raw samples
+        let show_stats = |id: &str| {
+            complications
+                .get_option(self.name(), id, complication_options::STATS)
+                .is_some_and(|s| s == "true")
+        };
+        let disk_stats = show_stats(complication_names::DISK_IO).then(|| {
+            stats_text(SystemData::graph_stats(&data.disk_history), |rate| {
+                data.format_disk_rate_compact(rate)
+            })
+        });
+        let net_stats = show_stats(complication_names::NETWORK).then(|| {
+            stats_text(SystemData::graph_stats(&data.net_history), |rate| {
+                data.format_net_rate_compact(rate)
+            })
+        });
+
         if portrait {
             // Portrait layout - labels on separate lines, wider graphs
             let line_height = canvas.line_height(FONT_SMALL);
             let section_spacing = 6; // Extra spacing between label/value pairs
-                                     // Calculate bar width to fill most of the line (leave margin on each side)
-            let bar_width = ((width as i32 - margin * 2) / 7).max(12) as usize; // ~7 pixels per char
-
-            // Hostname (always shown)
-            canvas.draw_text(margin, y, &data.hostname, FONT_LARGE, colors.highlight);
+                                     // Widest bar that still fits the line when full ('#' is wider than '.')
+            let line_width = width as i32 - margin * 2;
+            let bar_width = (12..=(line_width / 7).max(12) as usize)
+                .rev()
+                .find(|&chars| {
+                    canvas.text_width(&ascii_bar(100.0, chars), FONT_SMALL) <= line_width
+                })
+                .unwrap_or(12);
+
+            // Complication: Hostname
+            if is_enabled(complication_names::HOSTNAME) {
+                canvas.draw_text(margin, y, &data.hostname, FONT_LARGE, colors.highlight);
+            }
 
             // Complication: Time (right-aligned)
             if is_enabled(complication_names::TIME) {
@@ -255,8 +363,10 @@ impl Face for AsciiFace {
             y += line_height; // Extra line before Up
 
             // Up: on its own line (two lines below date)
-            let uptime_text = format!("Up: {}", data.uptime);
-            canvas.draw_text(margin, y, &uptime_text, FONT_SMALL, colors.dim);
+            if is_enabled(complication_names::UPTIME) {
+                let uptime_text = format!("Up: {}", data.format_uptime(uptime_format));
+                canvas.draw_text(margin, y, &uptime_text, FONT_SMALL, colors.dim);
+            }
             y += line_height + section_spacing;
 
             // IP: on its own line
@@ -314,10 +424,33 @@ impl Face for AsciiFace {
             canvas.draw_text(margin, y, &ram_bar, FONT_SMALL, colors.text);
             y += line_height + section_spacing;
 
+            // GPU: label and readings line, then utilization bar
+            if let Some(ref cells) = gpu_cells {
+                canvas.draw_text(margin, y, "GPU:", FONT_SMALL, colors.dim);
+                let label_w = canvas.text_width("GPU: ", FONT_SMALL);
+                if let Some(readings) = fit_cells(canvas, cells, line_width - label_w, FONT_SMALL) {
+                    let readings_w = canvas.text_width(&readings, FONT_SMALL);
+                    canvas.draw_text(
+                        width as i32 - margin - readings_w,
+                        y,
+                        &readings,
+                        FONT_SMALL,
+                        colors.text,
+                    );
+                }
+                y += line_height;
+                if let Some(utilization) = data.gpu.as_ref().and_then(|g| g.utilization) {
+                    let gpu_bar = ascii_bar(utilization as f64, bar_width);
+                    canvas.draw_text(margin, y, &gpu_bar, FONT_SMALL, colors.text);
+                    y += line_height;
+                }
+                y += section_spacing;
+            }
+
             // DSK: label line, then sparkline on next line
             if is_enabled(complication_names::DISK_IO) {
-                let disk_r = SystemData::format_rate_compact(data.disk_read_rate);
-                let disk_w = SystemData::format_rate_compact(data.disk_write_rate);
+                let disk_r = data.format_disk_rate_compact(data.disk_read_rate);
+                let disk_w = data.format_disk_rate_compact(data.disk_write_rate);
                 canvas.draw_text(margin, y, "DSK:", FONT_SMALL, colors.dim);
                 let disk_rates = format!("R:{} W:{}", disk_r, disk_w);
                 let disk_rates_w = canvas.text_width(&disk_rates, FONT_SMALL);
@@ -330,8 +463,8 @@ impl Face for AsciiFace {
                 );
                 y += line_height;
                 let sparkline = ascii_sparkline(
-                    &data.disk_history,
-                    SystemData::compute_graph_scale(&data.disk_history),
+                    &disk_history,
+                    SystemData::compute_graph_scale(&disk_history),
                     bar_width,
                 );
                 canvas.draw_text(
@@ -341,13 +474,18 @@ impl Face for AsciiFace {
                     FONT_SMALL,
                     colors.bar_disk,
                 );
-                y += line_height + section_spacing;
+                y += line_height;
+                if let Some(ref stats) = disk_stats {
+                    canvas.draw_text(margin, y, stats, FONT_SMALL, colors.dim);
+                    y += line_height;
+                }
+                y += section_spacing;
             }
 
             // NET: label line, then sparkline on next line
             if is_enabled(complication_names::NETWORK) {
-                let net_rx = SystemData::format_rate_compact(data.net_rx_rate);
-                let net_tx = SystemData::format_rate_compact(data.net_tx_rate);
+                let net_rx = data.format_net_rate_compact(data.net_rx_rate);
+                let net_tx = data.format_net_rate_compact(data.net_tx_rate);
                 canvas.draw_text(margin, y, "NET:", FONT_SMALL, colors.dim);
                 let net_rates = format!("D:{} U:{}", net_rx, net_tx);
                 let net_rates_w = canvas.text_width(&net_rates, FONT_SMALL);
@@ -360,8 +498,8 @@ impl Face for AsciiFace {
                 );
                 y += line_height;
                 let sparkline = ascii_sparkline(
-                    &data.net_history,
-                    SystemData::compute_graph_scale(&data.net_history),
+                    &net_history,
+                    SystemData::compute_graph_scale(&net_history),
                     bar_width,
                 );
                 canvas.draw_text(
@@ -371,11 +509,19 @@ impl Face for AsciiFace {
                     FONT_SMALL,
                     colors.bar_net,
                 );
+                if let Some(ref stats) = net_stats {
+                    y += line_height;
+                    if y + line_height <= height as i32 {
+                        canvas.draw_text(margin, y, stats, FONT_SMALL, colors.dim);
+                    }
+                }
             }
         } else {
             // Landscape layout
-            // Hostname (always shown)
-            canvas.draw_text(margin, y, &data.hostname, FONT_LARGE, colors.highlight);
+            // Complication: Hostname
+            if is_enabled(complication_names::HOSTNAME) {
+                canvas.draw_text(margin, y, &data.hostname, FONT_LARGE, colors.highlight);
+            }
 
             // Complication: Time (right-aligned)
             if is_enabled(complication_names::TIME) {
@@ -422,19 +568,46 @@ impl Face for AsciiFace {
                 }
             }
 
-            // Base element: Uptime (always shown, same line as date on left)
-            let uptime_text = format!("Up: {}", data.uptime);
-            canvas.draw_text(margin, y, &uptime_text, FONT_NORMAL, colors.dim);
+            // Complication: Uptime (same line as date on left)
+            if is_enabled(complication_names::UPTIME) {
+                let uptime_text = format!("Up: {}", data.format_uptime(uptime_format));
+                canvas.draw_text(margin, y, &uptime_text, FONT_NORMAL, colors.dim);
+            }
             y += canvas.line_height(FONT_NORMAL) + 1;
 
-            // Complication: IP address
-            if is_enabled(complication_names::IP_ADDRESS) {
-                if let Some(ref ip) = data.display_ip {
-                    canvas.draw_text(margin, y, &format!("IP: {}", ip), FONT_SMALL, colors.dim);
-                    y += canvas.line_height(FONT_SMALL) + 4;
-                } else {
-                    y += 4;
-                }
+            // Complication: IP address, with the GPU readings right-aligned
+            // on the same line as far as they fit
+            let ip_text = data
+                .display_ip
+                .as_ref()
+                .filter(|_| is_enabled(complication_names::IP_ADDRESS))
+                .map(|ip| format!("IP: {}", ip));
+            if let Some(ref text) = ip_text {
+                canvas.draw_text(margin, y, text, FONT_SMALL, colors.dim);
+            }
+            let gpu_text = gpu_cells.as_ref().and_then(|cells| {
+                let ip_w = ip_text.as_ref().map_or(0, |text| {
+                    canvas.text_width(&format!("{}  ", text), FONT_SMALL)
+                });
+                let label_w = canvas.text_width("GPU ", FONT_SMALL);
+                let max_width = width as i32 - margin * 2 - ip_w - label_w;
+                fit_cells(canvas, cells, max_width, FONT_SMALL)
+                    .map(|readings| format!("GPU {}", readings))
+            });
+            if let Some(ref text) = gpu_text {
+                let text_w = canvas.text_width(text, FONT_SMALL);
+                canvas.draw_text(
+                    width as i32 - margin - text_w,
+                    y,
+                    text,
+                    FONT_SMALL,
+                    colors.text,
+                );
+            }
+            if ip_text.is_some() || gpu_text.is_some() {
+                y += canvas.line_height(FONT_SMALL) + 4;
+            } else if is_enabled(complication_names::IP_ADDRESS) {
+                y += 4;
             }
 
             // Base element: CPU bar with optional temperature (always shown)
@@ -459,20 +632,23 @@ impl Face for AsciiFace {
 
             // Complication: Disk I/O
             if is_enabled(complication_names::DISK_IO) {
-                let disk_r = SystemData::format_rate_compact(data.disk_read_rate);
-                let disk_w = SystemData::format_rate_compact(data.disk_write_rate);
+                let disk_r = data.format_disk_rate_compact(data.disk_read_rate);
+                let disk_w = data.format_disk_rate_compact(data.disk_write_rate);
                 canvas.draw_text(margin, y, "DSK", FONT_NORMAL, colors.text);
-                canvas.draw_text(
-                    margin + 40,
-                    y,
-                    &format!("R:{} W:{}", disk_r, disk_w),
-                    FONT_NORMAL,
-                    colors.dim,
-                );
+                let rates = format!("R:{} W:{}", disk_r, disk_w);
+                canvas.draw_text(margin + 40, y, &rates, FONT_NORMAL, colors.dim);
+                // Statistics after the rates, if they fit
+                if let Some(ref stats) = disk_stats {
+                    let stats_x =
+                        margin + 40 + canvas.text_width(&format!("{} ", rates), FONT_NORMAL);
+                    if stats_x + canvas.text_width(stats, FONT_NORMAL) <= width as i32 - margin {
+                        canvas.draw_text(stats_x, y, stats, FONT_NORMAL, colors.dim);
+                    }
+                }
                 y += canvas.line_height(FONT_NORMAL);
                 let sparkline = ascii_sparkline(
-                    &data.disk_history,
-                    SystemData::compute_graph_scale(&data.disk_history),
+                    &disk_history,
+                    SystemData::compute_graph_scale(&disk_history),
                     bar_chars + 20,
                 );
                 canvas.draw_text(
@@ -487,20 +663,23 @@ impl Face for AsciiFace {
 
             // Complication: Network
             if is_enabled(complication_names::NETWORK) {
-                let net_rx = SystemData::format_rate_compact(data.net_rx_rate);
-                let net_tx = SystemData::format_rate_compact(data.net_tx_rate);
+                let net_rx = data.format_net_rate_compact(data.net_rx_rate);
+                let net_tx = data.format_net_rate_compact(data.net_tx_rate);
                 canvas.draw_text(margin, y, "NET", FONT_NORMAL, colors.text);
-                canvas.draw_text(
-                    margin + 40,
-                    y,
-                    &format!("D:{} U:{}", net_rx, net_tx),
-                    FONT_NORMAL,
-                    colors.dim,
-                );
+                let rates = format!("D:{} U:{}", net_rx, net_tx);
+                canvas.draw_text(margin + 40, y, &rates, FONT_NORMAL, colors.dim);
+                // Statistics after the rates, if they fit
+                if let Some(ref stats) = net_stats {
+                    let stats_x =
+                        margin + 40 + canvas.text_width(&format!("{} ", rates), FONT_NORMAL);
+                    if stats_x + canvas.text_width(stats, FONT_NORMAL) <= width as i32 - margin {
+                        canvas.draw_text(stats_x, y, stats, FONT_NORMAL, colors.dim);
+                    }
+                }
                 y += canvas.line_height(FONT_NORMAL);
                 let sparkline = ascii_sparkline(
-                    &data.net_history,
-                    SystemData::compute_graph_scale(&data.net_history),
+                    &net_history,
+                    SystemData::compute_graph_scale(&net_history),
                     bar_chars + 20,
                 );
                 canvas.draw_text(
@@ -513,5 +692,19 @@ impl Face for AsciiFace {
             }
         }
         let _ = y;
+
+        // Complication: Journal errors, over the bottom line like a status bar
+        if complications.is_enabled(self.name(), complication_names::JOURNAL_ERRORS, false) {
+            if let Some(errors) = data.journal_errors.as_ref().filter(|e| e.count > 0) {
+                let latest = complications
+                    .get_option(
+                        self.name(),
+                        complication_names::JOURNAL_ERRORS,
+                        complication_options::LATEST_ERROR,
+                    )
+                    .is_none_or(|s| s == "true");
+                Self::draw_error_line(canvas, errors, latest, theme, margin);
+            }
+        }
     }
 }
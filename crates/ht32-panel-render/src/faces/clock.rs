@@ -1,19 +1,19 @@
 //! Clock face displaying a clean analog clock.
 //!
 //! A minimalist watch face focused on time display with optional
-//! date and hostname complications.
+//! date, hostname, world clock and OS info complications.
 
 use std::f32::consts::PI;
 
 use super::{
-    complication_names, complication_options, complications, date_formats, Complication,
-    EnabledComplications, Face, Theme,
+    complication_names, complication_options, complications, date_formats, os_formats,
+    Complication, EnabledComplications, Face, Theme,
 };
 
 /// Default font size for digital time.
 const DEFAULT_TIME_SIZE: f32 = 32.0;
+use crate::data::SystemData;
 use crate::rendering::Canvas;
-use crate::sensors::data::SystemData;
 
 /// Dim a color by mixing it toward the background.
 fn dim_color(color: u32, background: u32, factor: f32) -> u32 {
@@ -73,6 +73,38 @@ struct ClockLayout {
     show_date: bool,
     hostname: String,
     date: Option<String>,
+    /// Formatted world clock entries (empty when disabled)
+    world: Vec<String>,
+    /// OS info footer (None when disabled)
+    footer: Option<String>,
+}
+
+impl ClockLayout {
+    /// Lines drawn below the clock: world clock entries side by side when
+    /// they fit (otherwise one per line), followed by the footer.
+    fn bottom_lines(&self, canvas: &Canvas) -> Vec<String> {
+        let (width, _) = canvas.dimensions();
+        let joined = self.world.join("   ");
+        let mut lines = if self.world.is_empty() {
+            Vec::new()
+        } else if canvas.text_width(&joined, FONT_SMALL) <= width as i32 - 8 {
+            vec![joined]
+        } else {
+            self.world.clone()
+        };
+        lines.extend(self.footer.clone());
+        lines
+    }
+
+    /// Height reserved for the lines below the clock.
+    fn bottom_height(&self, canvas: &Canvas) -> i32 {
+        let lines = self.bottom_lines(canvas).len() as i32;
+        if lines == 0 {
+            0
+        } else {
+            lines * (canvas.line_height(FONT_SMALL) + 2) + 4
+        }
+    }
 }
 
 impl ClockFace {
@@ -96,6 +128,14 @@ impl ClockFace {
         canvas.line_height(font_size)
     }
 
+    /// Draws the world clock and footer lines starting at `y`.
+    fn draw_bottom_lines(canvas: &mut Canvas, mut y: i32, layout: &ClockLayout, color: u32) {
+        for line in layout.bottom_lines(canvas) {
+            let h = Self::draw_centered_text(canvas, y, &line, FONT_SMALL, color);
+            y += h + 2;
+        }
+    }
+
     /// Draws a digital time display with optional hostname and date.
     fn draw_digital_time(
         canvas: &mut Canvas,
@@ -127,8 +167,12 @@ impl ClockFace {
 
                 // Available height for the time digits (with some margin)
                 let margin_v = 8.0;
-                let available_height =
-                    height as f32 - hostname_space as f32 - date_space as f32 - margin_v * 2.0;
+                let bottom_space = layout.bottom_height(canvas);
+                let available_height = height as f32
+                    - hostname_space as f32
+                    - date_space as f32
+                    - bottom_space as f32
+                    - margin_v * 2.0;
 
                 // Calculate font size that would fill the available height
                 // line_height is approximately font_size * 1.2
@@ -172,6 +216,7 @@ impl ClockFace {
         if layout.show_date && layout.date.is_some() {
             total_height += canvas.line_height(FONT_NORMAL) + 4;
         }
+        total_height += layout.bottom_height(canvas);
 
         let mut y = (height as i32 - total_height) / 2;
 
@@ -192,12 +237,16 @@ impl ClockFace {
 
         if layout.show_date {
             if let Some(date) = &layout.date {
-                Self::draw_centered_text(canvas, y, date, FONT_NORMAL, colors.dim);
+                let h = Self::draw_centered_text(canvas, y, date, FONT_NORMAL, colors.dim);
+                y += h + 4;
             }
         }
+
+        Self::draw_bottom_lines(canvas, y, layout, colors.dim);
     }
 
-    /// Draws an analog clock with optional hostname above and date below.
+    /// Draws an analog clock with optional hostname above and date and
+    /// bottom lines below.
     fn draw_analog_clock(
         canvas: &mut Canvas,
         hour: u8,
@@ -220,13 +269,16 @@ impl ClockFace {
             0
         };
 
+        let bottom_height = layout.bottom_height(canvas);
+
         // Calculate clock size based on available space
-        let available_height = height as i32 - margin * 2 - hostname_height - date_height;
+        let available_height =
+            height as i32 - margin * 2 - hostname_height - date_height - bottom_height;
         let available_width = width as i32 - margin * 2;
         let radius = (available_height.min(available_width) / 2) as u32;
 
         // Calculate total content height and center vertically
-        let total_height = hostname_height + (radius as i32 * 2) + date_height;
+        let total_height = hostname_height + (radius as i32 * 2) + date_height + bottom_height;
         let start_y = (height as i32 - total_height) / 2;
 
         let cx = width as i32 / 2;
@@ -249,6 +301,9 @@ impl ClockFace {
                 Self::draw_centered_text(canvas, date_y, date, FONT_NORMAL, colors.text);
             }
         }
+
+        let bottom_y = cy + radius as i32 + date_height + 4;
+        Self::draw_bottom_lines(canvas, bottom_y, layout, colors.dim);
     }
 
     /// Draws the analog clock face (circle, markers, and hands).
@@ -332,9 +387,15 @@ impl Face for ClockFace {
             complications::hostname(false),
             complications::digital_time(false),
             complications::date(false, date_formats::SHORT),
+            complications::world_clock(false),
+            complications::os_info(false),
         ]
     }
 
+    fn required_sensors(&self) -> &'static [&'static str] {
+        &[]
+    }
+
     fn render(
         &self,
         canvas: &mut Canvas,
@@ -361,12 +422,36 @@ impl Face for ClockFace {
             .and_then(|s| s.parse::<f32>().ok())
             .unwrap_or(DEFAULT_TIME_SIZE);
 
+        // Get OS info format option
+        let os_format = comp
+            .get_option(
+                self.name(),
+                complication_names::OS_INFO,
+                complication_options::OS_FORMAT,
+            )
+            .map(|s| s.as_str())
+            .unwrap_or(os_formats::FULL);
+
+        // Resolve world clock zones
+        let world = if is_on(complication_names::WORLD_CLOCK) {
+            complication_options::WORLD_ZONES
+                .iter()
+                .filter_map(|id| comp.get_option(self.name(), complication_names::WORLD_CLOCK, id))
+                .filter_map(|entry| data.world_time(entry))
+                .map(|t| t.format())
+                .collect()
+        } else {
+            Vec::new()
+        };
+
         // Build layout options
         let layout = ClockLayout {
-            show_hostname: is_on("hostname"),
+            show_hostname: is_on(complication_names::HOSTNAME),
             show_date: is_on(complication_names::DATE),
             hostname: data.hostname.clone(),
             date: data.format_date(date_format),
+            world,
+            footer: is_on(complication_names::OS_INFO).then(|| data.os_info.format(os_format)),
         };
 
         if is_on("digital_time") {
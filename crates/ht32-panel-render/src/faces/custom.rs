@@ -0,0 +1,616 @@
+//! Custom face drawn from a declarative widget layout.
+//!
+//! The layout is a JSON list of widgets, each with a type, a position in
+//! canvas pixels and a few style properties. It is stored as an option of
+//! the face's `layout` complication, so it is persisted with the other
+//! display settings and can be edited from the web UI layout editor.
+
+use std::sync::Mutex;
+
+use ht32_panel_types::Color;
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use super::{
+    complication_names, complications, date_formats, time_formats, uptime_formats, Complication,
+    ComplicationOption,
+};
+use super::{EnabledComplications, Face, Theme};
+use crate::data::SystemData;
+use crate::rendering::{Canvas, GraphStyle};
+
+/// Face ID.
+pub const FACE_ID: &str = "custom";
+
+/// Complication holding the layout.
+pub const LAYOUT_COMPLICATION: &str = "layout";
+
+/// Option of the layout complication holding the layout JSON.
+pub const LAYOUT_OPTION: &str = "json";
+
+/// Most widgets allowed in one layout.
+pub const MAX_WIDGETS: usize = 64;
+
+/// Font sizes accepted for text widgets.
+const FONT_SIZES: std::ops::RangeInclusive<f32> = 8.0..=96.0;
+
+/// Graph widget format labelling the current, peak and average rate.
+const GRAPH_STATS: &str = "stats";
+
+/// Font size of graph statistics.
+const STATS_SIZE: f32 = 9.0;
+
+/// Named colors taken from the theme.
+const THEME_COLORS: [&str; 3] = ["text", "primary", "secondary"];
+
+/// Dim a color by mixing it toward the background.
+fn dim_color(color: u32, background: u32, factor: f32) -> u32 {
+    let r1 = ((color >> 16) & 0xFF) as f32;
+    let g1 = ((color >> 8) & 0xFF) as f32;
+    let b1 = (color & 0xFF) as f32;
+    let r2 = ((background >> 16) & 0xFF) as f32;
+    let g2 = ((background >> 8) & 0xFF) as f32;
+    let b2 = (background & 0xFF) as f32;
+
+    let r = (r1 * factor + r2 * (1.0 - factor)) as u32;
+    let g = (g1 * factor + g2 * (1.0 - factor)) as u32;
+    let b = (b1 * factor + b2 * (1.0 - factor)) as u32;
+
+    (r << 16) | (g << 8) | b
+}
+
+/// A widget type that can be placed in a layout.
+#[derive(Debug, Clone, Serialize)]
+pub struct WidgetKind {
+    /// Value of the widget's `type` field
+    pub id: &'static str,
+    /// Human-readable name
+    pub name: &'static str,
+    /// What the widget shows
+    pub description: &'static str,
+    /// Whether the widget is sized by `width` and `height` rather than by
+    /// its font `size`
+    pub sized: bool,
+    /// Accepted `format` values, first is the default (empty if unused)
+    pub formats: &'static [&'static str],
+}
+
+impl WidgetKind {
+    const fn text(id: &'static str, name: &'static str, description: &'static str) -> Self {
+        Self {
+            id,
+            name,
+            description,
+            sized: false,
+            formats: &[],
+        }
+    }
+
+    const fn sized(id: &'static str, name: &'static str, description: &'static str) -> Self {
+        Self {
+            sized: true,
+            ..Self::text(id, name, description)
+        }
+    }
+
+    const fn with_formats(self, formats: &'static [&'static str]) -> Self {
+        Self { formats, ..self }
+    }
+}
+
+/// Returns every widget type.
+pub fn widget_kinds() -> Vec<WidgetKind> {
+    vec![
+        WidgetKind::text("text", "Text", "Fixed text from the `text` property"),
+        WidgetKind::text("time", "Time", "Current time")
+            .with_formats(&[time_formats::DIGITAL_24H, time_formats::DIGITAL_12H]),
+        WidgetKind::text("date", "Date", "Current date").with_formats(&[
+            date_formats::ISO,
+            date_formats::US,
+            date_formats::EU,
+            date_formats::SHORT,
+            date_formats::LONG,
+            date_formats::WEEKDAY,
+        ]),
+        WidgetKind::text("hostname", "Hostname", "Hostname or display label"),
+        WidgetKind::text("ip", "IP address", "Address of the monitored interface"),
+        WidgetKind::text("uptime", "Uptime", "Time since boot").with_formats(&[
+            uptime_formats::SHORT,
+            uptime_formats::PRECISE,
+            uptime_formats::DAYS,
+            uptime_formats::BOOT,
+        ]),
+        WidgetKind::text("cpu", "CPU usage", "CPU usage as a percentage"),
+        WidgetKind::text("cpu_temp", "CPU temperature", "CPU temperature in Celsius"),
+        WidgetKind::text("ram", "RAM usage", "RAM usage as a percentage"),
+        WidgetKind::text("net_rate", "Network rate", "Receive and transmit rates"),
+        WidgetKind::text("disk_rate", "Disk rate", "Read and write rates"),
+        WidgetKind::sized("cpu_bar", "CPU bar", "CPU usage as a bar"),
+        WidgetKind::sized("ram_bar", "RAM bar", "RAM usage as a bar"),
+        WidgetKind::sized("cpu_graph", "CPU graph", "CPU usage history")
+            .with_formats(&["plain", GRAPH_STATS]),
+        WidgetKind::sized("net_graph", "Network graph", "Network throughput history")
+            .with_formats(&["plain", GRAPH_STATS]),
+        WidgetKind::sized("disk_graph", "Disk graph", "Disk throughput history")
+            .with_formats(&["plain", GRAPH_STATS]),
+    ]
+}
+
+fn default_width() -> u32 {
+    100
+}
+
+fn default_height() -> u32 {
+    12
+}
+
+fn default_size() -> f32 {
+    16.0
+}
+
+fn default_color() -> String {
+    "text".to_string()
+}
+
+/// A widget placed on the canvas.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Widget {
+    /// Widget type (see [`widget_kinds`])
+    #[serde(rename = "type")]
+    pub kind: String,
+    /// Left edge in pixels
+    pub x: i32,
+    /// Top edge in pixels
+    pub y: i32,
+    /// Width of bars and graphs
+    #[serde(default = "default_width")]
+    pub width: u32,
+    /// Height of bars and graphs
+    #[serde(default = "default_height")]
+    pub height: u32,
+    /// Font size of text widgets
+    #[serde(default = "default_size")]
+    pub size: f32,
+    /// "text", "primary", "secondary" or a color such as "#RRGGBB" or
+    /// "orange"
+    #[serde(default = "default_color")]
+    pub color: String,
+    /// Text of `text` widgets
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub text: String,
+    /// Display format, for widgets that have several
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub format: String,
+}
+
+impl Widget {
+    fn new(kind: &str, x: i32, y: i32) -> Self {
+        Self {
+            kind: kind.to_string(),
+            x,
+            y,
+            width: default_width(),
+            height: default_height(),
+            size: default_size(),
+            color: default_color(),
+            text: String::new(),
+            format: String::new(),
+        }
+    }
+
+    /// Resolves the widget color against `theme`.
+    fn color(&self, theme: &Theme) -> u32 {
+        match self.color.as_str() {
+            "primary" => theme.primary,
+            "secondary" => theme.secondary,
+            value => value.parse::<Color>().map_or(theme.text, Color::to_rgb888),
+        }
+    }
+}
+
+/// A custom face layout.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Layout {
+    /// Widgets in drawing order
+    #[serde(default)]
+    pub widgets: Vec<Widget>,
+}
+
+impl Default for Layout {
+    /// A starting point for the editor: hostname, clock and usage bars.
+    fn default() -> Self {
+        let mut hostname = Widget::new("hostname", 8, 8);
+        hostname.color = "primary".to_string();
+        let mut time = Widget::new("time", 8, 32);
+        time.size = 40.0;
+        let mut cpu_bar = Widget::new("cpu_bar", 84, 104);
+        cpu_bar.width = 80;
+        cpu_bar.color = "primary".to_string();
+        let mut ram_bar = Widget::new("ram_bar", 84, 128);
+        ram_bar.width = 80;
+        ram_bar.color = "secondary".to_string();
+        Self {
+            widgets: vec![
+                hostname,
+                time,
+                Widget::new("cpu", 8, 100),
+                cpu_bar,
+                Widget::new("ram", 8, 124),
+                ram_bar,
+            ],
+        }
+    }
+}
+
+impl Layout {
+    /// Parses a layout from JSON.
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        serde_json::from_str(json).map_err(|e| format!("Invalid layout: {}", e))
+    }
+
+    /// Returns the layout as JSON.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+
+    /// Checks the layout against a canvas of `width` x `height` pixels,
+    /// returning a message for each problem found.
+    pub fn validate(&self, width: u32, height: u32) -> Vec<String> {
+        let kinds = widget_kinds();
+        let mut errors = Vec::new();
+        if self.widgets.len() > MAX_WIDGETS {
+            errors.push(format!("Too many widgets (at most {})", MAX_WIDGETS));
+        }
+        for (i, widget) in self.widgets.iter().enumerate() {
+            let name = format!("Widget {} ({})", i + 1, widget.kind);
+            let Some(kind) = kinds.iter().find(|k| k.id == widget.kind) else {
+                errors.push(format!("{}: unknown type", name));
+                continue;
+            };
+            if widget.x < 0 || widget.y < 0 || widget.x >= width as i32 || widget.y >= height as i32
+            {
+                errors.push(format!(
+                    "{}: position {},{} is outside the {}x{} canvas",
+                    name, widget.x, widget.y, width, height
+                ));
+            }
+            if kind.sized {
+                if widget.width == 0 || widget.height == 0 {
+                    errors.push(format!("{}: width and height must be positive", name));
+                } else if i64::from(widget.x) + i64::from(widget.width) > i64::from(width)
+                    || i64::from(widget.y) + i64::from(widget.height) > i64::from(height)
+                {
+                    errors.push(format!("{}: extends past the canvas edge", name));
+                }
+            } else if !FONT_SIZES.contains(&widget.size) {
+                errors.push(format!(
+                    "{}: size must be {}-{}",
+                    name,
+                    FONT_SIZES.start(),
+                    FONT_SIZES.end()
+                ));
+            }
+            if !THEME_COLORS.contains(&widget.color.as_str())
+                && widget.color.parse::<Color>().is_err()
+            {
+                errors.push(format!(
+                    "{}: color must be text, primary, secondary or a color such as #RRGGBB",
+                    name
+                ));
+            }
+            if !widget.format.is_empty() && !kind.formats.contains(&widget.format.as_str()) {
+                errors.push(format!("{}: unknown format '{}'", name, widget.format));
+            }
+            if widget.kind == "text" && widget.text.is_empty() {
+                errors.push(format!("{}: text is empty", name));
+            }
+        }
+        errors
+    }
+}
+
+/// Face that draws the widgets of a user layout.
+pub struct CustomFace {
+    /// Last parsed layout and the JSON it came from
+    cache: Mutex<Option<(String, Layout)>>,
+}
+
+impl CustomFace {
+    /// Creates a new custom face.
+    pub fn new() -> Self {
+        Self {
+            cache: Mutex::new(None),
+        }
+    }
+
+    /// Returns the layout for `json`, reusing the last parse when unchanged.
+    /// Unparseable layouts fall back to the default one.
+    fn layout(&self, json: Option<&String>) -> Layout {
+        let Some(json) = json else {
+            return Layout::default();
+        };
+        let mut cache = self.cache.lock().unwrap();
+        if let Some((cached_json, layout)) = cache.as_ref() {
+            if cached_json == json {
+                return layout.clone();
+            }
+        }
+        let layout = Layout::from_json(json).unwrap_or_else(|e| {
+            warn!("{}; using the default layout", e);
+            Layout::default()
+        });
+        *cache = Some((json.clone(), layout.clone()));
+        layout
+    }
+
+    /// Returns the complication whose data a widget shows, if it is one
+    /// hidden while its collector is switched off.
+    fn widget_complication(kind: &str) -> Option<&'static str> {
+        match kind {
+            "cpu_temp" => Some(complication_names::CPU_TEMP),
+            "ip" => Some(complication_names::IP_ADDRESS),
+            "net_rate" | "net_graph" => Some(complication_names::NETWORK),
+            "disk_rate" | "disk_graph" => Some(complication_names::DISK_IO),
+            _ => None,
+        }
+    }
+
+    /// Returns the text shown by a text widget.
+    fn widget_text(widget: &Widget, data: &SystemData) -> String {
+        let format = widget.format.as_str();
+        match widget.kind.as_str() {
+            "text" => widget.text.clone(),
+            "time" => data.format_time(format),
+            "date" => data
+                .format_date(if format.is_empty() {
+                    date_formats::ISO
+                } else {
+                    format
+                })
+                .unwrap_or_default(),
+            "hostname" => data.hostname.clone(),
+            "ip" => data.display_ip.clone().unwrap_or_else(|| "-".to_string()),
+            "uptime" => data.format_uptime(format),
+            "cpu" => format!("CPU {:.0}%", data.cpu_percent),
+            "cpu_temp" => data
+                .cpu_temp
+                .map(|t| format!("{:.0}°C", t))
+                .unwrap_or_else(|| "--°C".to_string()),
+            "ram" => format!("RAM {:.0}%", data.ram_percent),
+            "net_rate" => format!(
+                "↓{} ↑{}",
+                data.format_net_rate_compact(data.net_rx_rate),
+                data.format_net_rate_compact(data.net_tx_rate)
+            ),
+            "disk_rate" => format!(
+                "R:{} W:{}",
+                data.format_disk_rate_compact(data.disk_read_rate),
+                data.format_disk_rate_compact(data.disk_write_rate)
+            ),
+            _ => String::new(),
+        }
+    }
+
+    /// Draws one widget, clipping it to the canvas.
+    fn draw_widget(
+        canvas: &mut Canvas,
+        widget: &Widget,
+        data: &SystemData,
+        theme: &Theme,
+        graph_style: GraphStyle,
+        smoothing: u32,
+    ) {
+        let (canvas_w, canvas_h) = canvas.dimensions();
+        let (canvas_w, canvas_h) = (canvas_w as i32, canvas_h as i32);
+        if widget.x < 0 || widget.y < 0 || widget.x >= canvas_w || widget.y >= canvas_h {
+            return;
+        }
+        let color = widget.color(theme);
+        let track = dim_color(theme.text, theme.background, 0.2);
+
+        match widget.kind.as_str() {
+            "cpu_bar" | "ram_bar" | "cpu_graph" | "net_graph" | "disk_graph" => {
+                let width = widget.width.min((canvas_w - widget.x) as u32);
+                let height = widget.height.min((canvas_h - widget.y) as u32);
+                if width == 0 || height == 0 {
+                    return;
+                }
+                match widget.kind.as_str() {
+                    "cpu_bar" | "ram_bar" => {
+                        let percent = if widget.kind == "cpu_bar" {
+                            data.cpu_percent
+                        } else {
+                            data.ram_percent
+                        };
+                        canvas.fill_rect(widget.x, widget.y, width, height, track);
+                        let fill = ((width as f64 * percent / 100.0) as u32).min(width);
+                        if fill > 0 {
+                            canvas.fill_rect(widget.x, widget.y, fill, height, color);
+                        }
+                    }
+                    kind => {
+                        let history = match kind {
+                            "cpu_graph" => &data.cpu_history,
+                            "net_graph" => &data.net_history,
+                            _ => &data.disk_history,
+                        };
+                        let smoothed = SystemData::smooth_history(history, smoothing);
+                        // Usage is drawn against 100%, rates against their recent peak
+                        let scale = if kind == "cpu_graph" {
+                            100.0
+                        } else {
+                            SystemData::compute_graph_scale(&smoothed)
+                        };
+                        canvas.draw_graph(
+                            widget.x,
+                            widget.y,
+                            width,
+                            height,
+                            &smoothed,
+                            scale,
+                            color,
+                            track,
+                            graph_style,
+                        );
+                        if widget.format == GRAPH_STATS
+                            && canvas.line_height(STATS_SIZE) <= height as i32
+                        {
+                            let format = |value| match kind {
+                                "cpu_graph" => format!("{:.0}%", value),
+                                "net_graph" => data.format_net_rate_compact(value),
+                                _ => data.format_disk_rate_compact(value),
+                            };
+                            let stats = SystemData::graph_stats(history);
+                            let text = format!(
+                                "{} max {} avg {}",
+                                format(stats.current),
+                                format(stats.peak),
+                                format(stats.average)
+                            );
+                            let text_w = canvas.text_width(&text, STATS_SIZE);
+                            let x_scale = ((width as f32 - 2.0) / text_w as f32).min(1.0);
+                            canvas.draw_text_scaled(
+                                widget.x + 1,
+                                widget.y,
+                                &text,
+                                STATS_SIZE,
+                                theme.text,
+                                x_scale,
+                            );
+                        }
+                    }
+                }
+            }
+            _ => {
+                let text = Self::widget_text(widget, data);
+                let size = widget.size.clamp(*FONT_SIZES.start(), *FONT_SIZES.end());
+                let text_w = canvas.text_width(&text, size);
+                let text_h = canvas.line_height(size);
+                if text.is_empty() || text_w > canvas_w || text_h > canvas_h {
+                    return;
+                }
+                // Slide text back inside the canvas rather than cutting it off
+                let x = widget.x.min(canvas_w - text_w);
+                let y = widget.y.min(canvas_h - text_h);
+                canvas.draw_text(x, y, &text, size, color);
+            }
+        }
+    }
+}
+
+impl Default for CustomFace {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Face for CustomFace {
+    fn name(&self) -> &str {
+        FACE_ID
+    }
+
+    fn available_complications(&self) -> Vec<Complication> {
+        vec![Complication::with_options(
+            LAYOUT_COMPLICATION,
+            "Layout",
+            "Widget layout, edited in the web UI layout editor",
+            true,
+            vec![ComplicationOption::text(
+                LAYOUT_OPTION,
+                "Layout JSON",
+                "Widgets to draw, as saved by the layout editor",
+                &Layout::default().to_json(),
+            )],
+        )
+        .with_extra_options(complications::graph_options())]
+    }
+
+    fn render(
+        &self,
+        canvas: &mut Canvas,
+        data: &SystemData,
+        theme: &Theme,
+        complications: &EnabledComplications,
+    ) {
+        canvas.clear();
+        let layout =
+            self.layout(complications.get_option(FACE_ID, LAYOUT_COMPLICATION, LAYOUT_OPTION));
+        // Graph widgets share the style and smoothing set on the layout
+        let graph_style = complications.graph_style(FACE_ID, LAYOUT_COMPLICATION);
+        let smoothing = complications.smoothing(FACE_ID, LAYOUT_COMPLICATION);
+        // Widgets of switched off collectors are disabled in the layout
+        let shown = |widget: &&Widget| {
+            Self::widget_complication(&widget.kind)
+                .is_none_or(|id| complications.is_enabled(FACE_ID, id, true))
+        };
+        for widget in layout.widgets.iter().filter(shown) {
+            Self::draw_widget(canvas, widget, data, theme, graph_style, smoothing);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_layout() {
+        assert!(Layout::default().validate(320, 170).is_empty());
+        assert!(Layout::default().validate(170, 320).is_empty());
+
+        let layout = Layout::from_json(
+            r##"{"widgets": [
+                {"type": "clock", "x": 0, "y": 0},
+                {"type": "cpu_bar", "x": 300, "y": 0, "width": 40},
+                {"type": "text", "x": 10, "y": 10, "color": "#12345"},
+                {"type": "time", "x": 10, "y": 200, "format": "digital-12h"}
+            ]}"##,
+        )
+        .unwrap();
+        let errors = layout.validate(320, 170);
+        assert_eq!(errors.len(), 5, "{:?}", errors);
+        assert!(errors[0].contains("unknown type"));
+        assert!(errors[1].contains("past the canvas edge"));
+        assert!(errors[4].contains("outside"));
+
+        // Sizes that would overflow an i32 are caught, not wrapped
+        let layout = Layout::from_json(
+            r#"{"widgets": [{"type": "cpu_bar", "x": 10, "y": 10, "width": 4294967295}]}"#,
+        )
+        .unwrap();
+        let errors = layout.validate(320, 170);
+        assert_eq!(errors.len(), 1, "{:?}", errors);
+        assert!(errors[0].contains("past the canvas edge"));
+    }
+
+    #[test]
+    fn test_hidden_widgets() {
+        let face = CustomFace::new();
+        let mut complications = EnabledComplications::new();
+        let layout = r#"{"widgets": [{"type": "cpu_temp", "x": 10, "y": 10}]}"#;
+        complications.set_option(
+            FACE_ID,
+            LAYOUT_COMPLICATION,
+            LAYOUT_OPTION,
+            layout.to_string(),
+        );
+        let data = SystemData {
+            cpu_temp: Some(50.0),
+            ..Default::default()
+        };
+        let render = |complications: &EnabledComplications| {
+            let mut canvas = Canvas::new(320, 170);
+            face.render(&mut canvas, &data, &Theme::default(), complications);
+            canvas.pixels().to_vec()
+        };
+        let blank = {
+            let mut canvas = Canvas::new(320, 170);
+            canvas.clear();
+            canvas.pixels().to_vec()
+        };
+
+        assert_ne!(render(&complications), blank);
+        complications.set_enabled(FACE_ID, complication_names::CPU_TEMP, false);
+        assert_eq!(render(&complications), blank);
+    }
+}
@@ -5,10 +5,10 @@
 
 use super::{
     complication_names, complication_options, complications, date_formats, draw_mini_analog_clock,
-    time_formats, Complication, EnabledComplications, Face, Theme,
+    time_formats, uptime_formats, Complication, EnabledComplications, Face, Theme,
 };
+use crate::data::{collector_names, SystemData};
 use crate::rendering::Canvas;
-use crate::sensors::data::SystemData;
 
 /// Dim a color by mixing it toward the background.
 fn dim_color(color: u32, background: u32, factor: f32) -> u32 {
@@ -117,6 +117,16 @@ impl Face for DigitsFace {
             complications::network(true),
             complications::disk_io(true),
             complications::cpu_temp(true),
+            complications::hostname(true),
+            complications::uptime(true),
+        ]
+    }
+
+    fn required_sensors(&self) -> &'static [&'static str] {
+        &[
+            collector_names::TEMPERATURE,
+            collector_names::DISK,
+            collector_names::NETWORK,
         ]
     }
 
@@ -155,14 +165,26 @@ impl Face for DigitsFace {
             .map(|s| s.as_str())
             .unwrap_or(date_formats::ISO);
 
+        // Get uptime format option
+        let uptime_format = comp
+            .get_option(
+                self.name(),
+                complication_names::UPTIME,
+                complication_options::UPTIME_FORMAT,
+            )
+            .map(|s| s.as_str())
+            .unwrap_or(uptime_formats::SHORT);
+
         if portrait {
             // Portrait layout
             let col_width = (width as i32 - margin * 3) / 2;
 
-            // Hostname at top (always shown)
-            let host_width = canvas.text_width(&data.hostname, FONT_MEDIUM);
-            let host_x = (width as i32 - host_width) / 2;
-            canvas.draw_text(host_x, y, &data.hostname, FONT_MEDIUM, colors.label);
+            // Complication: Hostname at top
+            if is_on(complication_names::HOSTNAME) {
+                let host_width = canvas.text_width(&data.hostname, FONT_MEDIUM);
+                let host_x = (width as i32 - host_width) / 2;
+                canvas.draw_text(host_x, y, &data.hostname, FONT_MEDIUM, colors.label);
+            }
             y += canvas.line_height(FONT_MEDIUM) + 2;
 
             // Complication: Time
@@ -236,8 +258,8 @@ impl Face for DigitsFace {
             if is_on(complication_names::DISK_IO) {
                 Self::draw_divider(canvas, y, width, margin, colors.divider);
                 y += 6;
-                let disk_r = SystemData::format_rate_compact(data.disk_read_rate);
-                let disk_w = SystemData::format_rate_compact(data.disk_write_rate);
+                let disk_r = data.format_disk_rate_compact(data.disk_read_rate);
+                let disk_w = data.format_disk_rate_compact(data.disk_write_rate);
                 Self::draw_segment_value(
                     canvas,
                     margin,
@@ -263,8 +285,8 @@ impl Face for DigitsFace {
             if is_on(complication_names::NETWORK) {
                 Self::draw_divider(canvas, y, width, margin, colors.divider);
                 y += 6;
-                let net_rx = SystemData::format_rate_compact(data.net_rx_rate);
-                let net_tx = SystemData::format_rate_compact(data.net_tx_rate);
+                let net_rx = data.format_net_rate_compact(data.net_rx_rate);
+                let net_tx = data.format_net_rate_compact(data.net_tx_rate);
                 Self::draw_segment_value(
                     canvas,
                     margin,
@@ -290,14 +312,16 @@ impl Face for DigitsFace {
             Self::draw_divider(canvas, y, width, margin, colors.divider);
             y += 6;
 
-            // Uptime (always shown)
-            canvas.draw_text(
-                margin,
-                y,
-                &format!("UP {}", data.uptime),
-                FONT_SMALL,
-                colors.label,
-            );
+            // Uptime
+            if is_on(complication_names::UPTIME) {
+                canvas.draw_text(
+                    margin,
+                    y,
+                    &format!("UP {}", data.format_uptime(uptime_format)),
+                    FONT_SMALL,
+                    colors.label,
+                );
+            }
             y += canvas.line_height(FONT_SMALL) + 6;
 
             // Complication: IP address
@@ -315,7 +339,9 @@ impl Face for DigitsFace {
             let col_width = (width as i32 - margin * 5) / 4;
 
             // Row 1: Hostname on left, Time on right
-            canvas.draw_text(margin, y, &data.hostname, FONT_MEDIUM, colors.label);
+            if is_on(complication_names::HOSTNAME) {
+                canvas.draw_text(margin, y, &data.hostname, FONT_MEDIUM, colors.label);
+            }
             if is_on(complication_names::TIME) {
                 if time_format == time_formats::ANALOGUE {
                     // Draw small analog clock on the right
@@ -347,8 +373,10 @@ impl Face for DigitsFace {
             y += canvas.line_height(FONT_LARGE);
 
             // Row 2: Uptime on left, Date on right (below time)
-            let uptime_text = format!("Up: {}", data.uptime);
-            canvas.draw_text(margin, y, &uptime_text, FONT_SMALL, colors.label);
+            if is_on(complication_names::UPTIME) {
+                let uptime_text = format!("Up: {}", data.format_uptime(uptime_format));
+                canvas.draw_text(margin, y, &uptime_text, FONT_SMALL, colors.label);
+            }
             if is_on(complication_names::DATE) {
                 if let Some(date_str) = data.format_date(date_format) {
                     let date_width = canvas.text_width(&date_str, FONT_SMALL);
@@ -415,8 +443,8 @@ impl Face for DigitsFace {
 
             // Row 2: Disk R, Disk W (complication), Net Down, Net Up (complication) - smaller text
             if is_on(complication_names::DISK_IO) {
-                let disk_r = SystemData::format_rate_compact(data.disk_read_rate);
-                let disk_w = SystemData::format_rate_compact(data.disk_write_rate);
+                let disk_r = data.format_disk_rate_compact(data.disk_read_rate);
+                let disk_w = data.format_disk_rate_compact(data.disk_write_rate);
                 Self::draw_segment_value(
                     canvas,
                     margin,
@@ -437,8 +465,8 @@ impl Face for DigitsFace {
                 );
             }
             if is_on(complication_names::NETWORK) {
-                let net_rx = SystemData::format_rate_compact(data.net_rx_rate);
-                let net_tx = SystemData::format_rate_compact(data.net_tx_rate);
+                let net_rx = data.format_net_rate_compact(data.net_rx_rate);
+                let net_tx = data.format_net_rate_compact(data.net_tx_rate);
                 Self::draw_segment_value(
                     canvas,
                     margin + (col_width + margin) * 2,
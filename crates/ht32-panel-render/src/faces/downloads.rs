@@ -0,0 +1,194 @@
+//! Downloads face showing active torrents.
+//!
+//! Shows the client's total transfer rates and one row per downloading
+//! torrent with a progress bar, rate and ETA. Requires `[sensors.torrent]`
+//! to be enabled.
+
+use super::{
+    complication_names, complication_options, complications, time_formats, Complication,
+    EnabledComplications, Face, Theme,
+};
+use crate::data::{SystemData, Torrent};
+use crate::rendering::Canvas;
+
+/// Font size for the header.
+const FONT_HEADER: f32 = 14.0;
+
+/// Font size for torrent rows.
+const FONT_ROW: f32 = 12.0;
+
+/// Height of each progress bar in pixels.
+const BAR_HEIGHT: u32 = 6;
+
+/// Dim a color by mixing it toward the background.
+fn dim_color(color: u32, background: u32, factor: f32) -> u32 {
+    let r1 = ((color >> 16) & 0xFF) as f32;
+    let g1 = ((color >> 8) & 0xFF) as f32;
+    let b1 = (color & 0xFF) as f32;
+    let r2 = ((background >> 16) & 0xFF) as f32;
+    let g2 = ((background >> 8) & 0xFF) as f32;
+    let b2 = (background & 0xFF) as f32;
+
+    let r = (r1 * factor + r2 * (1.0 - factor)) as u32;
+    let g = (g1 * factor + g2 * (1.0 - factor)) as u32;
+    let b = (b1 * factor + b2 * (1.0 - factor)) as u32;
+
+    (r << 16) | (g << 8) | b
+}
+
+/// Formats an ETA as "2h05", "12m" or "40s".
+fn format_eta(secs: u64) -> String {
+    if secs >= 3600 {
+        format!("{}h{:02}", secs / 3600, secs % 3600 / 60)
+    } else if secs >= 60 {
+        format!("{}m", secs / 60)
+    } else {
+        format!("{}s", secs)
+    }
+}
+
+/// A torrent downloads face.
+pub struct DownloadsFace;
+
+impl DownloadsFace {
+    /// Creates a new downloads face.
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Draws one torrent: name and details on a line, progress bar below.
+    /// Returns the height used.
+    fn draw_torrent(
+        canvas: &mut Canvas,
+        x: i32,
+        y: i32,
+        width: i32,
+        torrent: &Torrent,
+        data: &SystemData,
+        theme: &Theme,
+    ) -> i32 {
+        let dim = dim_color(theme.text, theme.background, 0.5);
+
+        let mut detail = format!(
+            "{:.0}% {}",
+            torrent.progress,
+            data.format_net_rate_compact(torrent.download_rate)
+        );
+        if let Some(eta) = torrent.eta {
+            detail.push_str(&format!(" {}", format_eta(eta)));
+        }
+        let detail_w = canvas.text_width(&detail, FONT_ROW);
+        canvas.draw_text(x + width - detail_w, y, &detail, FONT_ROW, dim);
+
+        // Truncate the name to the space left of the details
+        let max_name_w = width - detail_w - 6;
+        let name = canvas.truncate_to_width(&torrent.name, FONT_ROW, max_name_w);
+        canvas.draw_text(x, y, &name, FONT_ROW, theme.text);
+
+        let bar_y = y + canvas.line_height(FONT_ROW) + 1;
+        let bar_bg = dim_color(theme.primary, theme.background, 0.2);
+        canvas.fill_rect(x, bar_y, width as u32, BAR_HEIGHT, bar_bg);
+        let fill = (width as f32 * torrent.progress.clamp(0.0, 100.0) / 100.0) as u32;
+        if fill > 0 {
+            canvas.fill_rect(x, bar_y, fill, BAR_HEIGHT, theme.primary);
+        }
+        canvas.line_height(FONT_ROW) + 1 + BAR_HEIGHT as i32
+    }
+}
+
+impl Default for DownloadsFace {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Face for DownloadsFace {
+    fn name(&self) -> &str {
+        "downloads"
+    }
+
+    fn available_complications(&self) -> Vec<Complication> {
+        vec![complications::time(true)]
+    }
+
+    fn required_sensors(&self) -> &'static [&'static str] {
+        &[]
+    }
+
+    fn render(
+        &self,
+        canvas: &mut Canvas,
+        data: &SystemData,
+        theme: &Theme,
+        complications: &EnabledComplications,
+    ) {
+        let (width, height) = canvas.dimensions();
+        let margin = 6;
+        let inner_width = width as i32 - margin * 2;
+        let dim = dim_color(theme.text, theme.background, 0.5);
+        let mut y = margin;
+
+        // Header: total rates, with the time on the right
+        let header = match data.torrents {
+            Some(ref stats) => format!(
+                "\u{2193}{} \u{2191}{}",
+                data.format_net_rate_compact(stats.download_rate),
+                data.format_net_rate_compact(stats.upload_rate)
+            ),
+            None => "Downloads".to_string(),
+        };
+        canvas.draw_text(margin, y, &header, FONT_HEADER, theme.primary);
+        if complications.is_enabled(self.name(), complication_names::TIME, true) {
+            let format = complications
+                .get_option(
+                    self.name(),
+                    complication_names::TIME,
+                    complication_options::TIME_FORMAT,
+                )
+                .map(|s| s.as_str())
+                .filter(|f| *f != time_formats::ANALOGUE)
+                .unwrap_or(time_formats::DIGITAL_24H);
+            let time = data.format_time(format);
+            let time_w = canvas.text_width(&time, FONT_HEADER);
+            canvas.draw_text(
+                width as i32 - margin - time_w,
+                y,
+                &time,
+                FONT_HEADER,
+                theme.text,
+            );
+        }
+        y += canvas.line_height(FONT_HEADER) + 6;
+
+        let Some(ref stats) = data.torrents else {
+            canvas.draw_text(margin, y, "Client unreachable", FONT_ROW, dim);
+            return;
+        };
+        if stats.downloading.is_empty() {
+            let text = format!("No active downloads ({} torrents)", stats.total);
+            canvas.draw_text(margin, y, &text, FONT_ROW, dim);
+            return;
+        }
+
+        let row_height = canvas.line_height(FONT_ROW) + 1 + BAR_HEIGHT as i32 + 5;
+        let mut shown = 0;
+        for torrent in &stats.downloading {
+            // Keep the last line free for the "+N more" note when needed
+            let remaining = stats.downloading.len() - shown;
+            let reserve = if remaining > 1 {
+                canvas.line_height(FONT_ROW)
+            } else {
+                0
+            };
+            if y + row_height + reserve > height as i32 - margin + 5 {
+                break;
+            }
+            y += Self::draw_torrent(canvas, margin, y, inner_width, torrent, data, theme) + 5;
+            shown += 1;
+        }
+        if shown < stats.downloading.len() {
+            let text = format!("+{} more", stats.downloading.len() - shown);
+            canvas.draw_text(margin, y, &text, FONT_ROW, dim);
+        }
+    }
+}
@@ -0,0 +1,120 @@
+//! Golden-image tests of every face.
+//!
+//! Each face is rendered at both orientations from the synthetic sensor data
+//! in [`fixture`] and compared with the PNGs in `tests/golden/`. Run with
+//! `cargo test -p ht32-panel-render --features golden-tests`. After an
+//! intended visual change, set `UPDATE_GOLDEN=1` to rewrite the images and
+//! review them before committing. Mismatching renders are written to
+//! `ht32-golden/` in the system temp directory.
+
+use std::path::{Path, PathBuf};
+
+use ht32_panel_hw::Orientation;
+use image::{Rgba, RgbaImage};
+
+use super::{available_faces, create_face, EnabledComplications, Theme};
+use crate::fixture;
+use crate::rendering::Canvas;
+
+/// Color distance (0-1) above which two pixels count as different.
+const PIXEL_THRESHOLD: f64 = 0.1;
+
+/// Fraction of differing pixels tolerated, for antialiasing noise.
+const MAX_DIFF_RATIO: f64 = 0.001;
+
+/// Renders `face` with default complications at `orientation`.
+fn render(face: &str, orientation: Orientation) -> RgbaImage {
+    let face = create_face(face).unwrap();
+    let mut complications = EnabledComplications::new();
+    complications.init_from_defaults(face.as_ref());
+    let (width, height) = orientation.dimensions();
+    let mut canvas = Canvas::new(width as u32, height as u32);
+    face.render(
+        &mut canvas,
+        &fixture::system_data(),
+        &Theme::from_preset("default"),
+        &complications,
+    );
+    RgbaImage::from_raw(width as u32, height as u32, canvas.pixels().to_vec()).unwrap()
+}
+
+/// Perceptual distance between two pixels, from 0 (same) to 1, weighting
+/// brightness over hue as in YIQ.
+fn pixel_distance(a: &Rgba<u8>, b: &Rgba<u8>) -> f64 {
+    let yiq = |p: &Rgba<u8>| {
+        let [r, g, b] = [p[0], p[1], p[2]].map(|c| c as f64 / 255.0);
+        (
+            0.299 * r + 0.587 * g + 0.114 * b,
+            0.596 * r - 0.274 * g - 0.322 * b,
+            0.211 * r - 0.523 * g + 0.312 * b,
+        )
+    };
+    let (y1, i1, q1) = yiq(a);
+    let (y2, i2, q2) = yiq(b);
+    // Black against white gives the maximum weighted sum, about 0.5415
+    let delta = 0.5053 * (y1 - y2).powi(2) + 0.299 * (i1 - i2).powi(2) + 0.1957 * (q1 - q2).powi(2);
+    (delta / 0.5415).sqrt()
+}
+
+/// Compares `actual` with `expected`, returning a description of the
+/// difference if it is visible.
+fn compare(actual: &RgbaImage, expected: &RgbaImage) -> Option<String> {
+    if actual.dimensions() != expected.dimensions() {
+        return Some(format!(
+            "size {:?}, expected {:?}",
+            actual.dimensions(),
+            expected.dimensions()
+        ));
+    }
+    let differing = actual
+        .pixels()
+        .zip(expected.pixels())
+        .filter(|(a, b)| pixel_distance(a, b) > PIXEL_THRESHOLD)
+        .count();
+    let ratio = differing as f64 / (actual.width() * actual.height()) as f64;
+    (ratio > MAX_DIFF_RATIO).then(|| format!("{} pixels differ ({:.2}%)", differing, ratio * 100.0))
+}
+
+fn golden_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/golden")
+}
+
+#[test]
+fn test_faces_match_golden_images() {
+    let update = std::env::var_os("UPDATE_GOLDEN").is_some();
+    let output = std::env::temp_dir().join("ht32-golden");
+    let mut failures = Vec::new();
+
+    for face in available_faces() {
+        for (orientation, suffix) in [
+            (Orientation::Landscape, "landscape"),
+            (Orientation::Portrait, "portrait"),
+        ] {
+            let name = format!("{}-{}.png", face.id, suffix);
+            let path = golden_dir().join(&name);
+            let actual = render(face.id, orientation);
+            if update {
+                std::fs::create_dir_all(golden_dir()).unwrap();
+                actual.save(&path).unwrap();
+                continue;
+            }
+            let problem = match image::open(&path) {
+                Ok(expected) => compare(&actual, &expected.to_rgba8()),
+                Err(e) => Some(format!("cannot read {}: {}", path.display(), e)),
+            };
+            if let Some(problem) = problem {
+                std::fs::create_dir_all(&output).unwrap();
+                actual.save(output.join(&name)).unwrap();
+                failures.push(format!("{}: {}", name, problem));
+            }
+        }
+    }
+
+    assert!(
+        failures.is_empty(),
+        "Faces differ from their golden images (renders in {}; rerun with \
+         UPDATE_GOLDEN=1 if intended):\n{}",
+        output.display(),
+        failures.join("\n")
+    );
+}
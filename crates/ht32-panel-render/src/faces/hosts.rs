@@ -0,0 +1,170 @@
+//! Hosts face showing the reachability of LAN hosts.
+//!
+//! Lists the hosts from `[sensors.hosts]` with an up/down dot and the last
+//! ping time, switching to multiple columns when the list does not fit.
+
+use super::{
+    complication_names, complication_options, complications, time_formats, Complication,
+    EnabledComplications, Face, Theme,
+};
+use crate::data::{HostStatus, SystemData};
+use crate::rendering::Canvas;
+
+/// Font size for the header.
+const FONT_HEADER: f32 = 14.0;
+
+/// Font size for host rows.
+const FONT_ROW: f32 = 12.0;
+
+/// Radius of the status dot in pixels.
+const DOT_RADIUS: u32 = 4;
+
+/// Dot color for hosts that did not answer.
+const DOWN_COLOR: u32 = 0xE04040;
+
+/// Dim a color by mixing it toward the background.
+fn dim_color(color: u32, background: u32, factor: f32) -> u32 {
+    let r1 = ((color >> 16) & 0xFF) as f32;
+    let g1 = ((color >> 8) & 0xFF) as f32;
+    let b1 = (color & 0xFF) as f32;
+    let r2 = ((background >> 16) & 0xFF) as f32;
+    let g2 = ((background >> 8) & 0xFF) as f32;
+    let b2 = (background & 0xFF) as f32;
+
+    let r = (r1 * factor + r2 * (1.0 - factor)) as u32;
+    let g = (g1 * factor + g2 * (1.0 - factor)) as u32;
+    let b = (b1 * factor + b2 * (1.0 - factor)) as u32;
+
+    (r << 16) | (g << 8) | b
+}
+
+/// A LAN host status face.
+pub struct HostsFace;
+
+impl HostsFace {
+    /// Creates a new hosts face.
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Draws one host row within `width` pixels.
+    fn draw_host(
+        canvas: &mut Canvas,
+        x: i32,
+        y: i32,
+        width: i32,
+        host: &HostStatus,
+        theme: &Theme,
+    ) {
+        let dim = dim_color(theme.text, theme.background, 0.5);
+        let (dot_color, detail) = match host.up {
+            Some(true) => (
+                theme.primary,
+                host.latency_ms
+                    .map(|ms| format!("{:.0}ms", ms.max(1.0)))
+                    .unwrap_or_default(),
+            ),
+            Some(false) => (DOWN_COLOR, "down".to_string()),
+            None => (dim, String::new()),
+        };
+
+        let line_height = canvas.line_height(FONT_ROW);
+        let radius = DOT_RADIUS as i32;
+        canvas.fill_circle(x + radius, y + line_height / 2, DOT_RADIUS, dot_color);
+
+        let detail_w = canvas.text_width(&detail, FONT_ROW);
+        let detail_x = x + width - detail_w;
+        if !detail.is_empty() {
+            canvas.draw_text(detail_x, y, &detail, FONT_ROW, dim);
+        }
+
+        // Truncate the name to the space left of the detail text
+        let name_x = x + radius * 2 + 5;
+        let max_name_w = detail_x - name_x - 4;
+        let name = canvas.truncate_to_width(&host.name, FONT_ROW, max_name_w);
+        canvas.draw_text(name_x, y, &name, FONT_ROW, theme.text);
+    }
+}
+
+impl Default for HostsFace {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Face for HostsFace {
+    fn name(&self) -> &str {
+        "hosts"
+    }
+
+    fn available_complications(&self) -> Vec<Complication> {
+        vec![complications::time(true)]
+    }
+
+    fn required_sensors(&self) -> &'static [&'static str] {
+        &[]
+    }
+
+    fn render(
+        &self,
+        canvas: &mut Canvas,
+        data: &SystemData,
+        theme: &Theme,
+        complications: &EnabledComplications,
+    ) {
+        let (width, height) = canvas.dimensions();
+        let margin = 6;
+        let mut y = margin;
+
+        // Header: title and up count, with the time on the right
+        let up = data.hosts.iter().filter(|h| h.up == Some(true)).count();
+        let title = if data.hosts.is_empty() {
+            "Hosts".to_string()
+        } else {
+            format!("Hosts {}/{}", up, data.hosts.len())
+        };
+        canvas.draw_text(margin, y, &title, FONT_HEADER, theme.primary);
+        if complications.is_enabled(self.name(), complication_names::TIME, true) {
+            let format = complications
+                .get_option(
+                    self.name(),
+                    complication_names::TIME,
+                    complication_options::TIME_FORMAT,
+                )
+                .map(|s| s.as_str())
+                .filter(|f| *f != time_formats::ANALOGUE)
+                .unwrap_or(time_formats::DIGITAL_24H);
+            let time = data.format_time(format);
+            let time_w = canvas.text_width(&time, FONT_HEADER);
+            canvas.draw_text(
+                width as i32 - margin - time_w,
+                y,
+                &time,
+                FONT_HEADER,
+                theme.text,
+            );
+        }
+        y += canvas.line_height(FONT_HEADER) + 6;
+
+        if data.hosts.is_empty() {
+            let dim = dim_color(theme.text, theme.background, 0.5);
+            canvas.draw_text(margin, y, "No hosts configured", FONT_ROW, dim);
+            return;
+        }
+
+        // Fill columns top to bottom, adding columns when rows run out
+        let row_height = canvas.line_height(FONT_ROW) + 3;
+        let rows = ((height as i32 - y - margin) / row_height).max(1) as usize;
+        let columns = data.hosts.len().div_ceil(rows).clamp(1, 3);
+        let gap = 10;
+        let column_width =
+            (width as i32 - margin * 2 - gap * (columns as i32 - 1)) / columns as i32;
+
+        for (i, host) in data.hosts.iter().take(rows * columns).enumerate() {
+            let column = (i / rows) as i32;
+            let row = (i % rows) as i32;
+            let x = margin + column * (column_width + gap);
+            Self::draw_host(canvas, x, y + row * row_height, column_width, host, theme);
+        }
+    }
+}
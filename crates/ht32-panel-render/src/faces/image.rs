@@ -1,8 +1,8 @@
 //! Image face that displays a user-selected image.
 
 use super::{Complication, ComplicationOption, EnabledComplications, Face, Theme};
-use crate::rendering::Canvas;
-use crate::sensors::data::SystemData;
+use crate::data::SystemData;
+use crate::rendering::{load_pixmap, Canvas};
 use std::sync::Mutex;
 use tiny_skia::{Pixmap};
 use tracing::{error, info};
@@ -21,42 +21,25 @@ impl ImageFace {
         }
     }
 
-    /// Loads an image from path and resizes it to fit the canvas.
+    /// Loads an image from path and resizes it to fill the canvas.
     fn load_image(&self, path: &str, width: u32, height: u32) -> Option<Pixmap> {
         info!("Loading image from: {}", path);
-        match image::open(path) {
-            Ok(img) => {
-                // Resize to fill canvas (cropping if necessary)
-                let img = img.resize_to_fill(width, height, image::imageops::FilterType::Lanczos3);
-                let rgba = img.to_rgba8();
-                let (w, h) = rgba.dimensions();
-                
-                let mut data = rgba.into_raw();
-                
-                // Premultiply alpha for tiny-skia
-                for chunk in data.chunks_mut(4) {
-                    let a = chunk[3] as u32;
-                    if a != 255 {
-                        chunk[0] = ((chunk[0] as u32 * a) / 255) as u8;
-                        chunk[1] = ((chunk[1] as u32 * a) / 255) as u8;
-                        chunk[2] = ((chunk[2] as u32 * a) / 255) as u8;
-                    }
-                }
-
-                if let Some(size) = tiny_skia::IntSize::from_wh(w, h) {
-                    return Some(Pixmap::from_vec(data, size).unwrap());
-                } else {
-                    return None;
-                }
-            }
+        match load_pixmap(path, width, height, true) {
+            Ok(pixmap) => Some(pixmap),
             Err(e) => {
-                error!("Failed to load image: {}", e);
+                error!("Failed to load image: {:#}", e);
                 None
             }
         }
     }
 }
 
+impl Default for ImageFace {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Face for ImageFace {
     fn name(&self) -> &str {
         "image"
@@ -77,6 +60,10 @@ impl Face for ImageFace {
         )]
     }
 
+    fn required_sensors(&self) -> &'static [&'static str] {
+        &[]
+    }
+
     fn render(
         &self,
         canvas: &mut Canvas,
@@ -0,0 +1,1533 @@
+//! Face system for pre-configured layouts.
+//!
+//! Faces display system information in different styles and colours.
+//! Each face supports configurable complications that allow users to
+//! enable or disable specific display elements.
+
+#![allow(dead_code)]
+
+mod arcs;
+mod ascii;
+mod clock;
+pub mod custom;
+mod digits;
+mod downloads;
+#[cfg(all(test, feature = "golden-tests"))]
+mod golden;
+mod hosts;
+mod image;
+pub mod overlays;
+pub mod placement;
+mod printer;
+mod professional;
+#[cfg(feature = "audio")]
+mod spectrum;
+mod timeline;
+mod vms;
+
+pub use arcs::ArcsFace;
+pub use ascii::AsciiFace;
+pub use clock::ClockFace;
+pub use custom::CustomFace;
+pub use digits::DigitsFace;
+pub use downloads::DownloadsFace;
+pub use hosts::HostsFace;
+pub use image::ImageFace;
+pub use printer::PrinterFace;
+pub use professional::ProfessionalFace;
+#[cfg(feature = "audio")]
+pub use spectrum::SpectrumFace;
+pub use timeline::TimelineFace;
+pub use vms::VmsFace;
+
+use crate::data::{collector_names, SystemData, COLLECTORS};
+use crate::rendering::{Canvas, GraphStyle};
+use ht32_panel_types::Color;
+use placement::Placement;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::f32::consts::PI;
+
+/// Color theme for face rendering.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Theme {
+    /// Primary color (used for highlights, interface names) - RGB888
+    pub primary: u32,
+    /// Secondary color (used for accents) - RGB888
+    pub secondary: u32,
+    /// Main text color - RGB888
+    pub text: u32,
+    /// Background color - RGB888
+    pub background: u32,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::from_preset("default")
+    }
+}
+
+impl Theme {
+    /// Creates a theme from a preset name.
+    /// All themes are designed for good contrast ratios (WCAG AA compliant).
+    pub fn from_preset(name: &str) -> Self {
+        match name.to_lowercase().as_str() {
+            "hacker" => Self {
+                // Matrix-like green on black - high contrast
+                primary: 0x00FF00,   // Bright green
+                secondary: 0x00DD00, // Slightly darker green
+                text: 0x00FF00,      // Green text
+                background: 0x000000,
+            },
+            "ember" | "fire" => Self {
+                // Red/orange warm theme
+                primary: 0xFF6B35,   // Bright orange
+                secondary: 0xFF4444, // Red
+                text: 0xFFEEDD,      // Warm white
+                background: 0x1A0A00,
+            },
+            "solarized-light" | "solarized_light" => Self {
+                // Solarized Light
+                primary: 0x268BD2,    // Blue
+                secondary: 0x859900,  // Green
+                text: 0x073642,       // Base02 (darker for better contrast)
+                background: 0xFDF6E3, // Base3
+            },
+            "solarized-dark" | "solarized_dark" => Self {
+                // Solarized Dark
+                primary: 0x268BD2,    // Blue
+                secondary: 0x2AA198,  // Cyan (more visible)
+                text: 0xEEE8D5,       // Base2 (brighter for better contrast)
+                background: 0x002B36, // Base03
+            },
+            "nord" => Self {
+                // Nord
+                primary: 0x88C0D0,    // Nord8 (frost cyan)
+                secondary: 0x81A1C1,  // Nord9 (frost blue)
+                text: 0xECEFF4,       // Nord6 (snow storm white)
+                background: 0x2E3440, // Nord0
+            },
+            "tokyonight" | "tokyo-night" | "tokyo_night" => Self {
+                // Tokyo Night
+                primary: 0x7AA2F7,   // Blue
+                secondary: 0xBB9AF7, // Magenta
+                text: 0xE0E0FF,      // Brighter foreground
+                background: 0x1A1B26,
+            },
+            // Unknown theme - fall back to nord
+            _ => Self::from_preset("nord"),
+        }
+    }
+
+    /// Sets the color `name` ("primary", "secondary", "text" or
+    /// "background") from any syntax [`Color`] accepts.
+    pub fn set_color(&mut self, name: &str, value: &str) -> Result<(), String> {
+        let color = value
+            .parse::<Color>()
+            .map_err(|e| format!("{}: {}", name, e))?
+            .to_rgb888();
+        match name {
+            "primary" => self.primary = color,
+            "secondary" => self.secondary = color,
+            "text" => self.text = color,
+            "background" => self.background = color,
+            _ => return Err(format!("Unknown theme color: {}", name)),
+        }
+        Ok(())
+    }
+
+    /// Returns the colors as "#RRGGBB" by name.
+    pub fn colors(&self) -> BTreeMap<String, String> {
+        [
+            ("primary", self.primary),
+            ("secondary", self.secondary),
+            ("text", self.text),
+            ("background", self.background),
+        ]
+        .into_iter()
+        .map(|(name, color)| (name.to_string(), Color::from_rgb888(color).to_string()))
+        .collect()
+    }
+}
+
+/// Lighten a color by blending it towards white.
+fn lighten_color(color: u32, factor: f32) -> u32 {
+    let r = ((color >> 16) & 0xFF) as f32;
+    let g = ((color >> 8) & 0xFF) as f32;
+    let b = (color & 0xFF) as f32;
+
+    let r_light = r + (255.0 - r) * factor;
+    let g_light = g + (255.0 - g) * factor;
+    let b_light = b + (255.0 - b) * factor;
+
+    ((r_light as u32) << 16) | ((g_light as u32) << 8) | (b_light as u32)
+}
+
+/// Draws a small analog clock for use in complications.
+///
+/// This is a minimal clock face with hands, suitable for
+/// displaying as a small complication within larger faces.
+#[allow(clippy::too_many_arguments)]
+pub fn draw_mini_analog_clock(
+    canvas: &mut Canvas,
+    cx: i32,
+    cy: i32,
+    radius: u32,
+    hour: u8,
+    minute: u8,
+    primary_color: u32,
+    hand_color: u32,
+) {
+    let radius_f = radius as f32;
+
+    // Draw clock face outline
+    canvas.draw_arc(cx, cy, radius, 0.0, 2.0 * PI, 1.5, primary_color);
+
+    // Calculate hand angles (12 o'clock = -PI/2)
+    let minute_angle = (minute as f32) * PI / 30.0 - PI / 2.0;
+    let hour_angle = ((hour % 12) as f32 + minute as f32 / 60.0) * PI / 6.0 - PI / 2.0;
+
+    // Draw hour hand (shorter, thicker, original color)
+    let hour_length = radius_f * 0.5;
+    let hour_x = cx as f32 + hour_length * hour_angle.cos();
+    let hour_y = cy as f32 + hour_length * hour_angle.sin();
+    canvas.draw_line(cx, cy, hour_x as i32, hour_y as i32, 2.5, hand_color);
+
+    // Draw minute hand (longer, thinner, lighter color)
+    let minute_color = lighten_color(hand_color, 0.4);
+    let minute_length = radius_f * 0.7;
+    let minute_x = cx as f32 + minute_length * minute_angle.cos();
+    let minute_y = cy as f32 + minute_length * minute_angle.sin();
+    canvas.draw_line(cx, cy, minute_x as i32, minute_y as i32, 1.5, minute_color);
+
+    // Draw center dot
+    canvas.fill_circle(cx, cy, 2, primary_color);
+}
+
+/// Draws the temperature history as a graph with its maximum and minimum
+/// labelled to the right, level with the top and bottom of the graph.
+///
+/// The vertical scale spans the recorded range plus 2°C either side, so
+/// slow drifts stay visible instead of hugging a 0-100°C axis.
+#[allow(clippy::too_many_arguments)]
+pub fn draw_temperature_graph(
+    canvas: &mut Canvas,
+    x: i32,
+    y: i32,
+    width: u32,
+    height: u32,
+    history: &VecDeque<f64>,
+    line_color: u32,
+    bg_color: u32,
+    label_color: u32,
+    label_size: f32,
+    style: GraphStyle,
+) {
+    let min = history.iter().copied().fold(f64::INFINITY, f64::min);
+    let max = history.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    if !min.is_finite() || !max.is_finite() {
+        return;
+    }
+
+    let max_label = format!("{:.0}°", max);
+    let min_label = format!("{:.0}°", min);
+    let label_w = canvas
+        .text_width(&max_label, label_size)
+        .max(canvas.text_width(&min_label, label_size));
+    let graph_width = (width as i32 - label_w - 4).max(0) as u32;
+
+    let floor = min.floor() - 2.0;
+    let shifted: VecDeque<f64> = history.iter().map(|t| t - floor).collect();
+    let range = max.ceil() + 2.0 - floor;
+    canvas.draw_graph(
+        x,
+        y,
+        graph_width,
+        height,
+        &shifted,
+        range,
+        line_color,
+        bg_color,
+        style,
+    );
+
+    let right = x + width as i32;
+    let max_w = canvas.text_width(&max_label, label_size);
+    canvas.draw_text(right - max_w, y, &max_label, label_size, label_color);
+    let bottom = y + height as i32 - canvas.line_height(label_size);
+    let min_w = canvas.text_width(&min_label, label_size);
+    canvas.draw_text(right - min_w, bottom, &min_label, label_size, label_color);
+}
+
+/// Information about an available theme.
+#[derive(Debug, Clone)]
+pub struct ThemeInfo {
+    /// Internal identifier used for setting the theme.
+    pub id: &'static str,
+    /// Human-readable display name.
+    pub display_name: &'static str,
+}
+
+/// Returns a list of available theme presets with display names.
+pub fn available_themes() -> Vec<ThemeInfo> {
+    vec![
+        ThemeInfo {
+            id: "ember",
+            display_name: "Ember",
+        },
+        ThemeInfo {
+            id: "hacker",
+            display_name: "Hacker",
+        },
+        ThemeInfo {
+            id: "nord",
+            display_name: "Nord",
+        },
+        ThemeInfo {
+            id: "solarized-dark",
+            display_name: "Solarized Dark",
+        },
+        ThemeInfo {
+            id: "solarized-light",
+            display_name: "Solarized Light",
+        },
+        ThemeInfo {
+            id: "tokyonight",
+            display_name: "Tokyo Night",
+        },
+    ]
+}
+
+/// Type of complication option value.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum ComplicationOptionType {
+    /// A choice from a list of values.
+    Choice(Vec<ComplicationChoice>),
+    /// A boolean toggle.
+    Boolean,
+    /// A numeric range (slider). Values are stored as strings but represent f32.
+    Range {
+        /// Minimum value.
+        min: f32,
+        /// Maximum value.
+        max: f32,
+        /// Step increment.
+        step: f32,
+    },
+    /// Free text input.
+    Text,
+}
+
+/// A choice value for a complication option.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ComplicationChoice {
+    /// The stored value.
+    pub value: String,
+    /// Human-readable label.
+    pub label: String,
+}
+
+impl ComplicationChoice {
+    /// Creates a new choice.
+    pub fn new(value: &str, label: &str) -> Self {
+        Self {
+            value: value.to_string(),
+            label: label.to_string(),
+        }
+    }
+}
+
+/// An option that can be configured for a complication.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ComplicationOption {
+    /// Unique identifier for this option.
+    pub id: String,
+    /// Human-readable name.
+    pub name: String,
+    /// Description of what this option controls.
+    pub description: String,
+    /// The type of this option (choice or boolean).
+    pub option_type: ComplicationOptionType,
+    /// Default value for this option.
+    pub default_value: String,
+}
+
+impl ComplicationOption {
+    /// Creates a new choice-based option.
+    pub fn choice(
+        id: &str,
+        name: &str,
+        description: &str,
+        choices: Vec<ComplicationChoice>,
+        default: &str,
+    ) -> Self {
+        Self {
+            id: id.to_string(),
+            name: name.to_string(),
+            description: description.to_string(),
+            option_type: ComplicationOptionType::Choice(choices),
+            default_value: default.to_string(),
+        }
+    }
+
+    /// Creates a new range-based option (slider).
+    pub fn range(
+        id: &str,
+        name: &str,
+        description: &str,
+        min: f32,
+        max: f32,
+        step: f32,
+        default: f32,
+    ) -> Self {
+        Self {
+            id: id.to_string(),
+            name: name.to_string(),
+            description: description.to_string(),
+            option_type: ComplicationOptionType::Range { min, max, step },
+            default_value: default.to_string(),
+        }
+    }
+
+    /// Creates a new yes/no option.
+    pub fn boolean(id: &str, name: &str, description: &str, default: bool) -> Self {
+        Self {
+            id: id.to_string(),
+            name: name.to_string(),
+            description: description.to_string(),
+            option_type: ComplicationOptionType::Boolean,
+            default_value: default.to_string(),
+        }
+    }
+
+    /// Creates a new text input option.
+    pub fn text(
+        id: &str,
+        name: &str,
+        description: &str,
+        default: &str,
+    ) -> Self {
+        Self {
+            id: id.to_string(),
+            name: name.to_string(),
+            description: description.to_string(),
+            option_type: ComplicationOptionType::Text,
+            default_value: default.to_string(),
+        }
+    }
+}
+
+/// A complication is an optional display element that can be enabled or disabled.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Complication {
+    /// Unique identifier for this complication.
+    pub id: String,
+    /// Human-readable name.
+    pub name: String,
+    /// Description of what this complication shows.
+    pub description: String,
+    /// Whether this complication is enabled by default.
+    pub default_enabled: bool,
+    /// Configuration options for this complication.
+    #[serde(default)]
+    pub options: Vec<ComplicationOption>,
+}
+
+impl Complication {
+    /// Creates a new complication without options.
+    pub fn new(id: &str, name: &str, description: &str, default_enabled: bool) -> Self {
+        Self {
+            id: id.to_string(),
+            name: name.to_string(),
+            description: description.to_string(),
+            default_enabled,
+            options: Vec::new(),
+        }
+    }
+
+    /// Appends extra options to a pre-built complication.
+    pub fn with_extra_options(mut self, options: Vec<ComplicationOption>) -> Self {
+        self.options.extend(options);
+        self
+    }
+
+    /// Creates a new complication with options.
+    pub fn with_options(
+        id: &str,
+        name: &str,
+        description: &str,
+        default_enabled: bool,
+        options: Vec<ComplicationOption>,
+    ) -> Self {
+        Self {
+            id: id.to_string(),
+            name: name.to_string(),
+            description: description.to_string(),
+            default_enabled,
+            options,
+        }
+    }
+}
+
+/// Complication IDs used across faces.
+pub mod complication_names {
+    pub const TIME: &str = "time";
+    pub const DATE: &str = "date";
+    pub const NETWORK: &str = "network";
+    pub const DISK_IO: &str = "disk_io";
+    pub const CPU_TEMP: &str = "cpu_temp";
+    pub const THROTTLING: &str = "throttling";
+    pub const TEMP_GRAPH: &str = "temp_graph";
+    pub const IP_ADDRESS: &str = "ip_address";
+    pub const HOSTNAME: &str = "hostname";
+    pub const WORLD_CLOCK: &str = "world_clock";
+    pub const UPTIME: &str = "uptime";
+    pub const UPDATES: &str = "updates";
+    pub const SSH_SESSIONS: &str = "ssh_sessions";
+    pub const OS_INFO: &str = "os_info";
+    pub const CPU_MODEL: &str = "cpu_model";
+    pub const PROCESSES: &str = "processes";
+    pub const TOP_APP: &str = "top_app";
+    pub const JOURNAL_ERRORS: &str = "journal_errors";
+    pub const NETWORK_TOTALS: &str = "network_totals";
+    pub const METRICS: &str = "metrics";
+    pub const DNS_BLOCKING: &str = "dns_blocking";
+    pub const GPU: &str = "gpu";
+    pub const VM_CPU: &str = "vm_cpu";
+    pub const STOPPED_VMS: &str = "stopped_vms";
+    pub const STORAGE_POOLS: &str = "storage_pools";
+    pub const JOBS: &str = "jobs";
+    pub const DOWNLOADS: &str = "downloads";
+    pub const MEDIA_STREAMS: &str = "media_streams";
+}
+
+/// Returns the complications showing data of the built-in collector
+/// `collector`, hidden while it is switched off.
+pub fn collector_complications(collector: &str) -> &'static [&'static str] {
+    match collector {
+        collector_names::TEMPERATURE => &[
+            complication_names::CPU_TEMP,
+            complication_names::THROTTLING,
+            complication_names::TEMP_GRAPH,
+        ],
+        collector_names::DISK => &[complication_names::DISK_IO],
+        collector_names::NETWORK => &[
+            complication_names::NETWORK,
+            complication_names::IP_ADDRESS,
+            complication_names::NETWORK_TOTALS,
+        ],
+        collector_names::PROCESSES => &[complication_names::PROCESSES, complication_names::TOP_APP],
+        collector_names::SESSIONS => &[complication_names::SSH_SESSIONS],
+        _ => &[],
+    }
+}
+
+/// Returns the built-in collector whose data the complication `id` shows,
+/// if any.
+pub fn complication_collector(id: &str) -> Option<&'static str> {
+    COLLECTORS
+        .iter()
+        .copied()
+        .find(|collector| collector_complications(collector).contains(&id))
+}
+
+/// Complication option IDs.
+pub mod complication_options {
+    pub const TIME_FORMAT: &str = "format";
+    pub const DATE_FORMAT: &str = "format";
+    pub const IP_TYPE: &str = "ip_type";
+    pub const IP_MODE: &str = "mode";
+    pub const ROTATE_INTERVAL: &str = "rotate_interval";
+    pub const INTERFACE: &str = "interface";
+    pub const SIZE: &str = "size";
+    pub const UPTIME_FORMAT: &str = "format";
+    pub const MILESTONES: &str = "milestones";
+    pub const OS_FORMAT: &str = "format";
+    pub const ZOMBIE_THRESHOLD: &str = "zombie_threshold";
+    pub const GRAPH_STYLE: &str = "graph_style";
+    pub const SMOOTHING: &str = "smoothing";
+    pub const GRAPH_LAYOUT: &str = "layout";
+    pub const PEAKS: &str = "peaks";
+    pub const STATS: &str = "stats";
+    pub const BUSIEST_DEVICE: &str = "busiest_device";
+    pub const TOTALS_PERIOD: &str = "period";
+    pub const METRIC_NAMES: &str = "names";
+    pub const JOB_NAMES: &str = "names";
+    pub const LATEST_ERROR: &str = "latest";
+    pub const WORLD_ZONES: [&str; 3] = ["zone1", "zone2", "zone3"];
+}
+
+/// Time format options.
+pub mod time_formats {
+    pub const DIGITAL_24H: &str = "digital-24h";
+    pub const DIGITAL_12H: &str = "digital-12h";
+    pub const ANALOGUE: &str = "analogue";
+}
+
+/// Date format options.
+pub mod date_formats {
+    pub const ISO: &str = "iso"; // 2024-01-15
+    pub const US: &str = "us"; // 01/15/2024
+    pub const EU: &str = "eu"; // 15/01/2024
+    pub const SHORT: &str = "short"; // Jan 15
+    pub const LONG: &str = "long"; // January 15, 2024
+    pub const WEEKDAY: &str = "weekday"; // Mon, Jan 15
+}
+
+/// Uptime format options.
+pub mod uptime_formats {
+    pub const SHORT: &str = "short"; // 3d 4h 12m
+    pub const PRECISE: &str = "precise"; // 3d 04:12:45
+    pub const DAYS: &str = "days"; // 3 days
+    pub const BOOT: &str = "boot"; // since Jan 15 09:32
+}
+
+/// OS info format options.
+pub mod os_formats {
+    pub const DISTRO: &str = "distro"; // Debian GNU/Linux 12 (bookworm)
+    pub const KERNEL: &str = "kernel"; // Linux 6.1.0-18-amd64 x86_64
+    pub const FULL: &str = "full"; // Debian GNU/Linux 12 · 6.1.0-18-amd64
+}
+
+/// Periods for network traffic totals.
+pub mod totals_periods {
+    pub const DAY: &str = "day";
+    pub const MONTH: &str = "month";
+}
+
+/// Graph style options for history graphs.
+pub mod graph_styles {
+    pub const BARS: &str = "bars";
+    pub const LINE: &str = "line";
+    pub const AREA: &str = "area";
+}
+
+/// Layouts for graphs with two series.
+pub mod graph_layouts {
+    pub const OVERLAY: &str = "overlay"; // both series from the bottom
+    pub const MIRRORED: &str = "mirrored"; // first above, second below a midline
+}
+
+/// Pre-built complications used across faces.
+pub mod complications {
+    use super::*;
+
+    /// Time complication with format options.
+    pub fn time(default_enabled: bool) -> Complication {
+        Complication::with_options(
+            complication_names::TIME,
+            "Time",
+            "Display the current time",
+            default_enabled,
+            vec![ComplicationOption::choice(
+                complication_options::TIME_FORMAT,
+                "Format",
+                "Time display format",
+                vec![
+                    ComplicationChoice::new(time_formats::DIGITAL_24H, "Digital (24h)"),
+                    ComplicationChoice::new(time_formats::DIGITAL_12H, "Digital (12h)"),
+                    ComplicationChoice::new(time_formats::ANALOGUE, "Analogue"),
+                ],
+                time_formats::DIGITAL_24H,
+            )],
+        )
+    }
+
+    /// Date complication with format options.
+    pub fn date(default_enabled: bool, default_format: &str) -> Complication {
+        Complication::with_options(
+            complication_names::DATE,
+            "Date",
+            "Display the current date",
+            default_enabled,
+            vec![ComplicationOption::choice(
+                complication_options::DATE_FORMAT,
+                "Format",
+                "Date display format",
+                vec![
+                    ComplicationChoice::new(date_formats::ISO, "ISO (2024-01-15)"),
+                    ComplicationChoice::new(date_formats::US, "US (01/15/2024)"),
+                    ComplicationChoice::new(date_formats::EU, "EU (15/01/2024)"),
+                    ComplicationChoice::new(date_formats::SHORT, "Short (Jan 15)"),
+                    ComplicationChoice::new(date_formats::LONG, "Long (January 15, 2024)"),
+                    ComplicationChoice::new(date_formats::WEEKDAY, "Weekday (Mon, Jan 15)"),
+                ],
+                default_format,
+            )],
+        )
+    }
+
+    /// IP address complication with type options.
+    pub fn ip_address(default_enabled: bool) -> Complication {
+        Complication::with_options(
+            complication_names::IP_ADDRESS,
+            "IP Address",
+            "Display network IP address",
+            default_enabled,
+            vec![
+                ComplicationOption::choice(
+                    complication_options::IP_TYPE,
+                    "IP Type",
+                    "Type of IP address to display",
+                    vec![
+                        ComplicationChoice::new("ipv6-gua", "IPv6 Global"),
+                        ComplicationChoice::new("ipv6-lla", "IPv6 Link-Local"),
+                        ComplicationChoice::new("ipv6-ula", "IPv6 ULA"),
+                        ComplicationChoice::new("ipv4", "IPv4"),
+                    ],
+                    "ipv6-gua",
+                ),
+                ComplicationOption::choice(
+                    complication_options::IP_MODE,
+                    "Addresses",
+                    "How to show interfaces with several addresses",
+                    vec![
+                        ComplicationChoice::new("single", "Selected type only"),
+                        ComplicationChoice::new("rotate", "Rotate through all"),
+                        ComplicationChoice::new("list", "Stacked list"),
+                    ],
+                    "single",
+                ),
+                ComplicationOption::range(
+                    complication_options::ROTATE_INTERVAL,
+                    "Rotate Every",
+                    "Seconds each address is shown when rotating",
+                    2.0,
+                    30.0,
+                    1.0,
+                    5.0,
+                ),
+            ],
+        )
+    }
+
+    /// Network activity complication with interface options.
+    pub fn network(default_enabled: bool) -> Complication {
+        Complication::with_options(
+            complication_names::NETWORK,
+            "Network",
+            "Display network activity graph",
+            default_enabled,
+            vec![ComplicationOption::choice(
+                complication_options::INTERFACE,
+                "Interface",
+                "Network interface to monitor",
+                vec![ComplicationChoice::new("auto", "Auto-detect")],
+                "auto",
+            )],
+        )
+    }
+
+    /// Graph style and smoothing options for complications that draw a history graph.
+    pub fn graph_options() -> Vec<ComplicationOption> {
+        vec![
+            ComplicationOption::choice(
+                complication_options::GRAPH_STYLE,
+                "Graph Style",
+                "How the history graph is drawn",
+                vec![
+                    ComplicationChoice::new(graph_styles::BARS, "Bars"),
+                    ComplicationChoice::new(graph_styles::LINE, "Line"),
+                    ComplicationChoice::new(graph_styles::AREA, "Filled area"),
+                ],
+                graph_styles::BARS,
+            ),
+            smoothing_option(),
+        ]
+    }
+
+    /// Layout and peak label options for graphs with two series.
+    pub fn dual_graph_options() -> Vec<ComplicationOption> {
+        vec![
+            ComplicationOption::choice(
+                complication_options::GRAPH_LAYOUT,
+                "Graph Layout",
+                "Overlay both series or mirror them around a midline",
+                vec![
+                    ComplicationChoice::new(graph_layouts::OVERLAY, "Overlay"),
+                    ComplicationChoice::new(graph_layouts::MIRRORED, "Mirrored"),
+                ],
+                graph_layouts::OVERLAY,
+            ),
+            ComplicationOption::boolean(
+                complication_options::PEAKS,
+                "Peak Labels",
+                "Label each series with its peak rate",
+                false,
+            ),
+        ]
+    }
+
+    /// Option to print the window's peak and average under a rate graph.
+    pub fn stats_option() -> ComplicationOption {
+        ComplicationOption::boolean(
+            complication_options::STATS,
+            "Statistics",
+            "Show the peak and average rate over the graph's window",
+            false,
+        )
+    }
+
+    /// Option to name the busiest tracked disk next to its rate.
+    pub fn busiest_device_option() -> ComplicationOption {
+        ComplicationOption::boolean(
+            complication_options::BUSIEST_DEVICE,
+            "Busiest Device",
+            "Show the busiest disk's name and rate",
+            false,
+        )
+    }
+
+    /// Smoothing option for history graphs (EMA window in samples, 1 = raw).
+    pub fn smoothing_option() -> ComplicationOption {
+        ComplicationOption::range(
+            complication_options::SMOOTHING,
+            "Smoothing",
+            "Average the graph over this many samples (1 = raw)",
+            1.0,
+            10.0,
+            1.0,
+            1.0,
+        )
+    }
+
+    /// Disk I/O complication.
+    pub fn disk_io(default_enabled: bool) -> Complication {
+        Complication::new(
+            complication_names::DISK_IO,
+            "Disk I/O",
+            "Display disk read/write activity graph",
+            default_enabled,
+        )
+    }
+
+    /// CPU temperature complication.
+    pub fn cpu_temp(default_enabled: bool) -> Complication {
+        Complication::new(
+            complication_names::CPU_TEMP,
+            "CPU Temperature",
+            "Display CPU temperature",
+            default_enabled,
+        )
+    }
+
+    /// CPU throttling complication, shown while the CPU is thermally limited.
+    pub fn throttling(default_enabled: bool) -> Complication {
+        Complication::new(
+            complication_names::THROTTLING,
+            "CPU Throttling",
+            "Warn while the CPU is slowed down to limit its temperature",
+            default_enabled,
+        )
+    }
+
+    /// World clock complication with up to three extra timezones.
+    pub fn world_clock(default_enabled: bool) -> Complication {
+        let defaults = ["London=Europe/London", "New York=America/New_York", ""];
+        Complication::with_options(
+            complication_names::WORLD_CLOCK,
+            "World Clock",
+            "Display the time in other timezones",
+            default_enabled,
+            complication_options::WORLD_ZONES
+                .iter()
+                .zip(defaults)
+                .enumerate()
+                .map(|(i, (id, default))| {
+                    ComplicationOption::text(
+                        id,
+                        &format!("Zone {}", i + 1),
+                        "Timezone as Label=Area/City or Area/City (blank to hide)",
+                        default,
+                    )
+                })
+                .collect(),
+        )
+    }
+
+    /// Uptime complication with format and milestone options.
+    pub fn uptime(default_enabled: bool) -> Complication {
+        Complication::with_options(
+            complication_names::UPTIME,
+            "Uptime",
+            "Display how long the system has been running",
+            default_enabled,
+            vec![
+                ComplicationOption::choice(
+                    complication_options::UPTIME_FORMAT,
+                    "Format",
+                    "Uptime display format",
+                    vec![
+                        ComplicationChoice::new(uptime_formats::SHORT, "Short (3d 4h 12m)"),
+                        ComplicationChoice::new(uptime_formats::PRECISE, "Precise (3d 04:12:45)"),
+                        ComplicationChoice::new(uptime_formats::DAYS, "Days (3 days)"),
+                        ComplicationChoice::new(uptime_formats::BOOT, "Boot time"),
+                    ],
+                    uptime_formats::SHORT,
+                ),
+                ComplicationOption::choice(
+                    complication_options::MILESTONES,
+                    "Milestones",
+                    "Briefly celebrate uptime milestones such as 100 days",
+                    vec![
+                        ComplicationChoice::new("off", "Off"),
+                        ComplicationChoice::new("on", "On"),
+                    ],
+                    "off",
+                ),
+            ],
+        )
+    }
+
+    /// Pending package updates complication.
+    pub fn updates(default_enabled: bool) -> Complication {
+        Complication::new(
+            complication_names::UPDATES,
+            "Pending Updates",
+            "Display the number of pending package updates (highlighted for security updates)",
+            default_enabled,
+        )
+    }
+
+    /// Network traffic totals complication (data usage today or this month).
+    pub fn network_totals(default_enabled: bool) -> Complication {
+        Complication::with_options(
+            complication_names::NETWORK_TOTALS,
+            "Data Usage",
+            "Display bytes transferred on the monitored interface",
+            default_enabled,
+            vec![ComplicationOption::choice(
+                complication_options::TOTALS_PERIOD,
+                "Period",
+                "Total for today or for this month",
+                vec![
+                    ComplicationChoice::new(totals_periods::DAY, "Today"),
+                    ComplicationChoice::new(totals_periods::MONTH, "This month"),
+                ],
+                totals_periods::MONTH,
+            )],
+        )
+    }
+
+    /// Custom metrics complication (values from SNMP and other sources).
+    pub fn metrics(default_enabled: bool) -> Complication {
+        Complication::with_options(
+            complication_names::METRICS,
+            "Custom Metrics",
+            "Display named custom metrics from SNMP, JSON or command sources",
+            default_enabled,
+            vec![ComplicationOption::text(
+                complication_options::METRIC_NAMES,
+                "Metrics",
+                "Comma-separated metric names (blank for all)",
+                "",
+            )],
+        )
+    }
+
+    /// Job status complication (last backup time and result).
+    pub fn jobs(default_enabled: bool) -> Complication {
+        Complication::with_options(
+            complication_names::JOBS,
+            "Jobs",
+            "Display the last result of jobs such as backups reported via ReportJob",
+            default_enabled,
+            vec![ComplicationOption::text(
+                complication_options::JOB_NAMES,
+                "Jobs",
+                "Comma-separated job names (blank for all)",
+                "",
+            )],
+        )
+    }
+
+    /// Torrent downloads complication.
+    pub fn downloads(default_enabled: bool) -> Complication {
+        Complication::new(
+            complication_names::DOWNLOADS,
+            "Downloads",
+            "Display active torrent downloads and transfer rates",
+            default_enabled,
+        )
+    }
+
+    /// Jellyfin / Plex active streams complication.
+    pub fn media_streams(default_enabled: bool) -> Complication {
+        Complication::new(
+            complication_names::MEDIA_STREAMS,
+            "Media Streams",
+            "Display active Jellyfin or Plex streams and whether they are transcoded",
+            default_enabled,
+        )
+    }
+
+    /// GPU strip complication (utilization, temperature, fan and power).
+    pub fn gpu(default_enabled: bool) -> Complication {
+        Complication::new(
+            complication_names::GPU,
+            "GPU",
+            "Display GPU utilization, temperature, fan speed and power draw",
+            default_enabled,
+        )
+    }
+
+    /// Per-VM CPU usage bars complication.
+    pub fn vm_cpu(default_enabled: bool) -> Complication {
+        Complication::new(
+            complication_names::VM_CPU,
+            "VM CPU",
+            "Display CPU usage bars for running VMs",
+            default_enabled,
+        )
+    }
+
+    /// Stopped VMs complication.
+    pub fn stopped_vms(default_enabled: bool) -> Complication {
+        Complication::new(
+            complication_names::STOPPED_VMS,
+            "Stopped VMs",
+            "List VMs that are shut off",
+            default_enabled,
+        )
+    }
+
+    /// ZFS pool / md array health complication.
+    pub fn storage_pools(default_enabled: bool) -> Complication {
+        Complication::new(
+            complication_names::STORAGE_POOLS,
+            "Storage Pools",
+            "Display ZFS pool and RAID array health, with a red badge when degraded",
+            default_enabled,
+        )
+    }
+
+    /// Pi-hole / AdGuard Home blocking statistics complication.
+    pub fn dns_blocking(default_enabled: bool) -> Complication {
+        Complication::new(
+            complication_names::DNS_BLOCKING,
+            "DNS Blocking",
+            "Display the share of DNS queries blocked by Pi-hole or AdGuard Home",
+            default_enabled,
+        )
+    }
+
+    /// Active SSH sessions complication.
+    pub fn ssh_sessions(default_enabled: bool) -> Complication {
+        Complication::new(
+            complication_names::SSH_SESSIONS,
+            "SSH Sessions",
+            "Display active SSH sessions and the most recent source address",
+            default_enabled,
+        )
+    }
+
+    /// OS info complication (distribution, kernel, architecture).
+    pub fn os_info(default_enabled: bool) -> Complication {
+        Complication::with_options(
+            complication_names::OS_INFO,
+            "OS Info",
+            "Display the distribution and kernel version",
+            default_enabled,
+            vec![ComplicationOption::choice(
+                complication_options::OS_FORMAT,
+                "Format",
+                "What to show",
+                vec![
+                    ComplicationChoice::new(os_formats::DISTRO, "Distribution"),
+                    ComplicationChoice::new(os_formats::KERNEL, "Kernel and architecture"),
+                    ComplicationChoice::new(os_formats::FULL, "Distribution and kernel"),
+                ],
+                os_formats::FULL,
+            )],
+        )
+    }
+
+    /// CPU model and core count complication.
+    pub fn cpu_model(default_enabled: bool) -> Complication {
+        Complication::new(
+            complication_names::CPU_MODEL,
+            "CPU Model",
+            "Display the CPU model and core/thread count",
+            default_enabled,
+        )
+    }
+
+    /// Process count complication with a zombie alert threshold.
+    pub fn processes(default_enabled: bool) -> Complication {
+        Complication::with_options(
+            complication_names::PROCESSES,
+            "Processes",
+            "Display process and thread counts, highlighting zombie processes",
+            default_enabled,
+            vec![ComplicationOption::range(
+                complication_options::ZOMBIE_THRESHOLD,
+                "Zombie Alert",
+                "Show an alert banner at this many zombie processes (0 = off)",
+                0.0,
+                50.0,
+                1.0,
+                0.0,
+            )],
+        )
+    }
+
+    /// Temperature history complication, graphing the last hour.
+    pub fn temp_graph(default_enabled: bool) -> Complication {
+        Complication::new(
+            complication_names::TEMP_GRAPH,
+            "Temperature Graph",
+            "Display the CPU temperature over the last hour with its minimum and maximum",
+            default_enabled,
+        )
+        .with_extra_options(graph_options())
+    }
+
+    /// Top application complication, naming the biggest CPU user.
+    pub fn top_app(default_enabled: bool) -> Complication {
+        Complication::new(
+            complication_names::TOP_APP,
+            "Top Application",
+            "Display the application using the most CPU, its processes combined",
+            default_enabled,
+        )
+    }
+
+    /// Journal error count complication, optionally with the latest error.
+    pub fn journal_errors(default_enabled: bool) -> Complication {
+        Complication::with_options(
+            complication_names::JOURNAL_ERRORS,
+            "Journal Errors",
+            "Display the number of errors logged to the system journal in the last hour",
+            default_enabled,
+            vec![ComplicationOption::boolean(
+                complication_options::LATEST_ERROR,
+                "Latest Error",
+                "Show the latest error message",
+                true,
+            )],
+        )
+    }
+
+    /// Hostname complication.
+    pub fn hostname(default_enabled: bool) -> Complication {
+        Complication::new(
+            complication_names::HOSTNAME,
+            "Hostname",
+            "Display the system hostname",
+            default_enabled,
+        )
+    }
+
+    /// Digital time complication (replaces analog clock).
+    pub fn digital_time(default_enabled: bool) -> Complication {
+        Complication::with_options(
+            "digital_time",
+            "Digital Time",
+            "Display the current time in digital format",
+            default_enabled,
+            vec![ComplicationOption::range(
+                complication_options::SIZE,
+                "Size",
+                "Size of the digital clock display",
+                32.0, // min (current default)
+                96.0, // max (large enough to fill screen)
+                4.0,  // step
+                32.0, // default
+            )],
+        )
+    }
+}
+
+/// Configuration for a single complication instance.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+pub struct ComplicationConfig {
+    /// Whether this complication is enabled.
+    pub enabled: bool,
+    /// Option values for this complication.
+    #[serde(default)]
+    pub options: HashMap<String, String>,
+    /// Slot the complication is moved to, if not where the face puts it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub placement: Option<Placement>,
+}
+
+/// Set of enabled complications with their configurations.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct EnabledComplications {
+    /// Map of face name to map of complication ID to configuration.
+    #[serde(default)]
+    face_complications: HashMap<String, HashMap<String, ComplicationConfig>>,
+}
+
+impl EnabledComplications {
+    /// Creates a new empty set.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Checks if a complication is enabled for a face.
+    /// If the face has no explicit settings, returns the complication's default.
+    pub fn is_enabled(&self, face: &str, complication_id: &str, default: bool) -> bool {
+        if let Some(configs) = self.face_complications.get(face) {
+            configs
+                .get(complication_id)
+                .map(|c| c.enabled)
+                .unwrap_or(default)
+        } else {
+            default
+        }
+    }
+
+    /// Sets whether a complication is enabled for a face.
+    pub fn set_enabled(&mut self, face: &str, complication_id: &str, enabled: bool) {
+        let face_map = self.face_complications.entry(face.to_string()).or_default();
+        let config = face_map.entry(complication_id.to_string()).or_default();
+        config.enabled = enabled;
+    }
+
+    /// Initializes complications for a face from its defaults.
+    /// Existing settings are kept; complications and options added since they
+    /// were saved are filled in with their defaults.
+    pub fn init_from_defaults(&mut self, face: &dyn Face) {
+        let configs = self
+            .face_complications
+            .entry(face.name().to_string())
+            .or_default();
+        for comp in face.available_complications() {
+            let config = configs
+                .entry(comp.id.clone())
+                .or_insert_with(|| ComplicationConfig {
+                    enabled: comp.default_enabled,
+                    ..Default::default()
+                });
+            // Initialize options with defaults
+            for opt in &comp.options {
+                config
+                    .options
+                    .entry(opt.id.clone())
+                    .or_insert_with(|| opt.default_value.clone());
+            }
+        }
+    }
+
+    /// Gets all enabled complication IDs for a face.
+    pub fn get_enabled(&self, face: &str) -> std::collections::HashSet<String> {
+        self.face_complications
+            .get(face)
+            .map(|configs| {
+                configs
+                    .iter()
+                    .filter(|(_, c)| c.enabled)
+                    .map(|(id, _)| id.clone())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Gets an option value for a complication.
+    pub fn get_option(
+        &self,
+        face: &str,
+        complication_id: &str,
+        option_id: &str,
+    ) -> Option<&String> {
+        self.face_complications
+            .get(face)
+            .and_then(|configs| configs.get(complication_id))
+            .and_then(|config| config.options.get(option_id))
+    }
+
+    /// Gets the graph style selected for a complication's history graph.
+    pub fn graph_style(&self, face: &str, complication_id: &str) -> GraphStyle {
+        self.get_option(face, complication_id, complication_options::GRAPH_STYLE)
+            .map(|s| GraphStyle::from_name(s))
+            .unwrap_or_default()
+    }
+
+    /// Gets the smoothing window (in samples) for a complication's history graph.
+    pub fn smoothing(&self, face: &str, complication_id: &str) -> u32 {
+        self.get_option(face, complication_id, complication_options::SMOOTHING)
+            .and_then(|s| s.parse::<f32>().ok())
+            .unwrap_or(1.0) as u32
+    }
+
+    /// Sets an option value for a complication.
+    pub fn set_option(
+        &mut self,
+        face: &str,
+        complication_id: &str,
+        option_id: &str,
+        value: String,
+    ) {
+        let face_map = self.face_complications.entry(face.to_string()).or_default();
+        let config = face_map.entry(complication_id.to_string()).or_default();
+        config.options.insert(option_id.to_string(), value);
+    }
+
+    /// Moves a complication to a slot, or back into the face's layout
+    /// with None.
+    pub fn set_placement(
+        &mut self,
+        face: &str,
+        complication_id: &str,
+        placement: Option<Placement>,
+    ) {
+        let face_map = self.face_complications.entry(face.to_string()).or_default();
+        let config = face_map.entry(complication_id.to_string()).or_default();
+        config.placement = placement;
+    }
+
+    /// Gets where a complication is placed, if it was moved to a slot.
+    pub fn placement(&self, face: &str, complication_id: &str) -> Option<Placement> {
+        self.get_config(face, complication_id)
+            .and_then(|config| config.placement)
+    }
+
+    /// Gets the enabled complications placed in slots on a face, in the
+    /// order of [`placement::PLACEABLE`].
+    pub fn placed(&self, face: &str) -> Vec<(String, Placement)> {
+        let mut placed: Vec<(String, Placement)> = self
+            .face_complications
+            .get(face)
+            .into_iter()
+            .flatten()
+            .filter(|(_, config)| config.enabled)
+            .filter_map(|(id, config)| Some((id.clone(), config.placement?)))
+            .collect();
+        placed.sort_by_key(|(id, _)| placement::PLACEABLE.iter().position(|p| *p == id.as_str()));
+        placed
+    }
+
+    /// Gets the full configuration for a complication.
+    pub fn get_config(&self, face: &str, complication_id: &str) -> Option<&ComplicationConfig> {
+        self.face_complications
+            .get(face)
+            .and_then(|configs| configs.get(complication_id))
+    }
+}
+
+/// Trait for display faces.
+pub trait Face: Send + Sync {
+    /// Returns the name of the face.
+    fn name(&self) -> &str;
+
+    /// Returns the list of available complications for this face.
+    fn available_complications(&self) -> Vec<Complication>;
+
+    /// Returns the built-in collectors (see [`COLLECTORS`]) whose data the
+    /// face shows. The others are not read while the face is active.
+    fn required_sensors(&self) -> &'static [&'static str] {
+        COLLECTORS
+    }
+
+    /// Renders the face onto the canvas using current system data, theme,
+    /// and enabled complications.
+    fn render(
+        &self,
+        canvas: &mut Canvas,
+        data: &SystemData,
+        theme: &Theme,
+        complications: &EnabledComplications,
+    );
+}
+
+/// Creates a face by name.
+pub fn create_face(name: &str) -> Option<Box<dyn Face>> {
+    match name.to_lowercase().as_str() {
+        "arcs" => Some(Box::new(ArcsFace::new())),
+        "ascii" => Some(Box::new(AsciiFace::new())),
+        "image" => Some(Box::new(ImageFace::new())),
+        "clock" => Some(Box::new(ClockFace::new())),
+        "custom" => Some(Box::new(CustomFace::new())),
+        "digits" => Some(Box::new(DigitsFace::new())),
+        "hosts" => Some(Box::new(HostsFace::new())),
+        "professional" => Some(Box::new(ProfessionalFace::new())),
+        #[cfg(feature = "audio")]
+        "spectrum" => Some(Box::new(SpectrumFace::new())),
+        "vms" => Some(Box::new(VmsFace::new())),
+        "printer" => Some(Box::new(PrinterFace::new())),
+        "downloads" => Some(Box::new(DownloadsFace::new())),
+        "timeline" => Some(Box::new(TimelineFace::new())),
+        _ => None,
+    }
+}
+
+/// Information about an available face.
+#[derive(Debug, Clone)]
+pub struct FaceInfo {
+    /// Internal identifier used for setting the face.
+    pub id: &'static str,
+    /// Human-readable display name.
+    pub display_name: &'static str,
+}
+
+/// Returns a list of available faces with display names.
+pub fn available_faces() -> Vec<FaceInfo> {
+    vec![
+        FaceInfo {
+            id: "arcs",
+            display_name: "Arcs",
+        },
+        FaceInfo {
+            id: "ascii",
+            display_name: "ASCII",
+        },
+        FaceInfo {
+            id: "clock",
+            display_name: "Clock",
+        },
+        FaceInfo {
+            id: "custom",
+            display_name: "Custom",
+        },
+        FaceInfo {
+            id: "digits",
+            display_name: "Digits",
+        },
+        FaceInfo {
+            id: "hosts",
+            display_name: "Hosts",
+        },
+        FaceInfo {
+            id: "image",
+            display_name: "Image",
+        },
+        FaceInfo {
+            id: "professional",
+            display_name: "Professional",
+        },
+        #[cfg(feature = "audio")]
+        FaceInfo {
+            id: "spectrum",
+            display_name: "Spectrum",
+        },
+        FaceInfo {
+            id: "vms",
+            display_name: "VMs",
+        },
+        FaceInfo {
+            id: "printer",
+            display_name: "Printer",
+        },
+        FaceInfo {
+            id: "downloads",
+            display_name: "Downloads",
+        },
+        FaceInfo {
+            id: "timeline",
+            display_name: "Timeline",
+        },
+    ]
+}
+
+/// Returns available complications for a face by name.
+pub fn face_complications(name: &str) -> Vec<Complication> {
+    create_face(name)
+        .map(|f| f.available_complications())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns the rows and columns of the bounding box of pixels drawn in
+    /// pure red, green or blue (`channel` 0, 1 or 2).
+    fn drawn(canvas: &Canvas, channel: usize) -> Option<(u32, u32, u32, u32)> {
+        let (width, _) = canvas.dimensions();
+        let mut bounds: Option<(u32, u32, u32, u32)> = None;
+        for (i, pixel) in canvas.pixels().chunks(4).enumerate() {
+            let others = (0..3).filter(|&c| c != channel).map(|c| pixel[c]);
+            if pixel[channel] > 128 && others.max() == Some(0) {
+                let (x, y) = (i as u32 % width, i as u32 / width);
+                bounds = Some(match bounds {
+                    Some((x0, y0, x1, y1)) => (x0.min(x), y0.min(y), x1.max(x), y1.max(y)),
+                    None => (x, y, x, y),
+                });
+            }
+        }
+        bounds
+    }
+
+    #[test]
+    fn test_theme_colors() {
+        let mut theme = Theme::from_preset("nord");
+        theme.set_color("primary", "#112233").unwrap();
+        theme.set_color("background", "#fff").unwrap();
+        assert_eq!(theme.primary, 0x112233);
+        assert_eq!(theme.background, 0xFFFFFF);
+
+        let colors = theme.colors();
+        assert_eq!(
+            colors.keys().collect::<Vec<_>>(),
+            ["background", "primary", "secondary", "text"]
+        );
+        assert_eq!(colors["primary"], "#112233");
+        assert_eq!(colors["background"], "#FFFFFF");
+
+        // Colors read back set the same theme
+        let mut copy = Theme::from_preset("hacker");
+        for (name, value) in &colors {
+            copy.set_color(name, value).unwrap();
+        }
+        assert_eq!(copy, theme);
+
+        assert!(theme.set_color("border", "#000000").is_err());
+        assert!(theme.set_color("text", "blue-ish").is_err());
+        assert_eq!(theme.primary, 0x112233);
+    }
+
+    #[test]
+    fn test_draw_temperature_graph() {
+        let draw = |history: &[f64]| {
+            let mut canvas = Canvas::new(200, 100);
+            canvas.clear();
+            draw_temperature_graph(
+                &mut canvas,
+                10,
+                10,
+                160,
+                80,
+                &history.iter().copied().collect(),
+                0xFF0000,
+                0x000000,
+                0x00FF00,
+                12.0,
+                GraphStyle::Line,
+            );
+            canvas
+        };
+
+        // Nothing is drawn without history
+        let canvas = draw(&[]);
+        assert_eq!(drawn(&canvas, 0), None);
+        assert_eq!(drawn(&canvas, 1), None);
+
+        // A steady temperature sits midway within the 2°C margins
+        let canvas = draw(&[50.0; 20]);
+        let (line_left, line_top, line_right, line_bottom) = drawn(&canvas, 0).unwrap();
+        let (label_left, label_top, label_right, label_bottom) = drawn(&canvas, 1).unwrap();
+        assert!(line_left >= 10);
+        assert!(
+            line_top > 30 && line_bottom < 70,
+            "{}..{}",
+            line_top,
+            line_bottom
+        );
+        // Labels are right aligned, level with the top and bottom
+        assert!(line_right < label_left);
+        assert!(label_right < 170);
+        assert!(label_top < 25 && label_bottom > 75);
+
+        // The range spans the history, so the extremes stay off the edges
+        let canvas = draw(&[40.0, 60.0, 40.0, 60.0]);
+        let (_, line_top, _, line_bottom) = drawn(&canvas, 0).unwrap();
+        assert!(
+            line_top > 10 && line_bottom < 89,
+            "{}..{}",
+            line_top,
+            line_bottom
+        );
+        assert!(line_bottom - line_top > 50);
+    }
+}
@@ -0,0 +1,130 @@
+//! Overlays drawn on top of every face.
+//!
+//! Placed complications belong to one face, while overlays stay on screen
+//! whichever face is shown: a clock in a corner, a dot while sensor data is
+//! being recorded, or the host's network connection status. They share the
+//! slots of the placement layer and stack after the face's own placed
+//! complications.
+
+use serde::{Deserialize, Serialize};
+
+use super::complication_names;
+use super::placement::{badge_text, Badge, BadgeSize, Placement, Slot, PLACEABLE};
+use crate::data::SystemData;
+
+/// Red dot shown while sensor data is recorded with `--record`.
+pub const RECORDING: &str = "recording";
+
+/// Dot showing whether the host has a network address.
+pub const CONNECTION: &str = "connection";
+
+/// Color of the recording dot.
+const RECORDING_COLOR: u32 = 0xE04040;
+
+/// Connection dot color while the host is online.
+const ONLINE_COLOR: u32 = 0x40C060;
+
+/// Connection dot color while the host is offline.
+const OFFLINE_COLOR: u32 = 0xE04040;
+
+/// Returns the ids of all overlays: the placeable complications, drawn
+/// with their default formats, and the status dots.
+pub fn available() -> Vec<&'static str> {
+    PLACEABLE
+        .iter()
+        .copied()
+        .chain([RECORDING, CONNECTION])
+        .collect()
+}
+
+/// An overlay shown in a slot.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Overlay {
+    pub id: String,
+    pub slot: Slot,
+    #[serde(default)]
+    pub size: BadgeSize,
+}
+
+impl Overlay {
+    /// Returns where the overlay is drawn.
+    pub fn placement(&self) -> Placement {
+        Placement {
+            slot: self.slot,
+            size: self.size,
+        }
+    }
+}
+
+/// Returns the badges of `overlays`, leaving out those with nothing to
+/// show and the complications in `hidden`; the connection dot is left out
+/// with the IP address. `recording` tells whether sensor data is being
+/// recorded.
+pub fn badges(
+    overlays: &[Overlay],
+    data: &SystemData,
+    recording: bool,
+    hidden: &[&str],
+) -> Vec<(Placement, Badge)> {
+    overlays
+        .iter()
+        .filter(|overlay| {
+            let id = match overlay.id.as_str() {
+                CONNECTION => complication_names::IP_ADDRESS,
+                id => id,
+            };
+            !hidden.contains(&id)
+        })
+        .filter_map(|overlay| {
+            let badge = match overlay.id.as_str() {
+                RECORDING => recording.then_some(Badge::Dot(RECORDING_COLOR)),
+                CONNECTION => Some(Badge::Dot(if data.display_ip.is_some() {
+                    ONLINE_COLOR
+                } else {
+                    OFFLINE_COLOR
+                })),
+                id => badge_text(id, data, |_| None).map(Badge::Text),
+            };
+            badge.map(|badge| (overlay.placement(), badge))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_badges() {
+        let overlay = |id: &str| Overlay {
+            id: id.to_string(),
+            slot: Slot::TopRight,
+            size: BadgeSize::Small,
+        };
+        let overlays = [
+            overlay(complication_names::HOSTNAME),
+            overlay(RECORDING),
+            overlay(CONNECTION),
+        ];
+        let data = SystemData {
+            hostname: "nas".to_string(),
+            ..Default::default()
+        };
+
+        let shown = badges(&overlays, &data, false, &[]);
+        assert_eq!(shown.len(), 2);
+        assert_eq!(shown[0].1, Badge::Text("nas".to_string()));
+        assert_eq!(shown[1].1, Badge::Dot(OFFLINE_COLOR));
+
+        let shown = badges(&overlays, &data, true, &[]);
+        assert_eq!(shown[1].1, Badge::Dot(RECORDING_COLOR));
+
+        // Hidden with the collectors behind them
+        let hidden = [complication_names::HOSTNAME, complication_names::IP_ADDRESS];
+        let shown = badges(&overlays, &data, true, &hidden);
+        assert_eq!(
+            shown,
+            [(overlays[1].placement(), Badge::Dot(RECORDING_COLOR))]
+        );
+    }
+}
@@ -0,0 +1,390 @@
+//! Shared placement of complications in screen slots.
+//!
+//! Compact complications (time, date, hostname and similar one-line values)
+//! can be given a position: a corner or the bottom bar. A placed
+//! complication is hidden from the face's own layout and drawn here instead,
+//! on top of the face, so users can move the clock on any face without the
+//! face knowing about slots.
+
+use std::borrow::Cow;
+
+use serde::{Deserialize, Serialize};
+
+use super::{
+    complication_names, complication_options, date_formats, time_formats, uptime_formats,
+    EnabledComplications, Theme,
+};
+use crate::data::SystemData;
+use crate::rendering::Canvas;
+
+/// Complications that can be placed in a slot.
+pub const PLACEABLE: &[&str] = &[
+    complication_names::TIME,
+    complication_names::DATE,
+    complication_names::HOSTNAME,
+    complication_names::IP_ADDRESS,
+    complication_names::CPU_TEMP,
+    complication_names::THROTTLING,
+    complication_names::TOP_APP,
+    complication_names::JOURNAL_ERRORS,
+    complication_names::UPTIME,
+    complication_names::UPDATES,
+    complication_names::SSH_SESSIONS,
+];
+
+/// Gap between the screen edge and placed complications.
+const EDGE: i32 = 4;
+
+/// Padding around the text of a badge.
+const PADDING: i32 = 2;
+
+/// A screen position for placed complications.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Slot {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+    /// Full-width strip along the bottom edge
+    BottomBar,
+}
+
+impl Slot {
+    /// All slots, in drawing order.
+    pub const ALL: [Slot; 5] = [
+        Slot::TopLeft,
+        Slot::TopRight,
+        Slot::BottomLeft,
+        Slot::BottomRight,
+        Slot::BottomBar,
+    ];
+
+    /// Returns the slot's name as used in settings and over D-Bus.
+    pub fn name(self) -> &'static str {
+        match self {
+            Slot::TopLeft => "top-left",
+            Slot::TopRight => "top-right",
+            Slot::BottomLeft => "bottom-left",
+            Slot::BottomRight => "bottom-right",
+            Slot::BottomBar => "bottom-bar",
+        }
+    }
+
+    /// Parses a slot name.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|slot| slot.name() == name)
+    }
+}
+
+/// Text size of a placed complication.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BadgeSize {
+    #[default]
+    Small,
+    Large,
+}
+
+impl BadgeSize {
+    /// Returns the size's name as used over D-Bus.
+    pub fn name(self) -> &'static str {
+        match self {
+            BadgeSize::Small => "small",
+            BadgeSize::Large => "large",
+        }
+    }
+
+    /// Parses a size name; empty means small.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "" | "small" => Some(BadgeSize::Small),
+            "large" => Some(BadgeSize::Large),
+            _ => None,
+        }
+    }
+
+    fn font_size(self) -> f32 {
+        match self {
+            BadgeSize::Small => 12.0,
+            BadgeSize::Large => 20.0,
+        }
+    }
+}
+
+/// Where and how large a placed complication is drawn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Placement {
+    pub slot: Slot,
+    #[serde(default)]
+    pub size: BadgeSize,
+}
+
+/// Returns the complications `face` lays out itself: placed complications
+/// and those in `hidden` are disabled so the face leaves them out.
+pub fn face_layout<'a>(
+    complications: &'a EnabledComplications,
+    face: &str,
+    hidden: &[&str],
+) -> Cow<'a, EnabledComplications> {
+    let placed = complications.placed(face);
+    if placed.is_empty() && hidden.is_empty() {
+        return Cow::Borrowed(complications);
+    }
+    let mut layout = complications.clone();
+    for (id, _) in placed {
+        layout.set_enabled(face, &id, false);
+    }
+    for id in hidden {
+        layout.set_enabled(face, id, false);
+    }
+    Cow::Owned(layout)
+}
+
+/// What a badge in a slot shows.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Badge {
+    Text(String),
+    /// A filled status dot of the given RGB888 color
+    Dot(u32),
+}
+
+/// Returns the badges of the complications placed on `face`. Complications
+/// in `hidden` or with nothing to show are left out.
+pub fn placed_badges(
+    face: &str,
+    data: &SystemData,
+    complications: &EnabledComplications,
+    hidden: &[&str],
+) -> Vec<(Placement, Badge)> {
+    complications
+        .placed(face)
+        .into_iter()
+        .filter(|(id, _)| !hidden.contains(&id.as_str()))
+        .filter_map(|(id, placement)| {
+            let option = |option_id: &str| {
+                complications
+                    .get_option(face, &id, option_id)
+                    .map(String::as_str)
+            };
+            badge_text(&id, data, option).map(|text| (placement, Badge::Text(text)))
+        })
+        .collect()
+}
+
+/// Draws `badges` in their slots. Badges sharing a slot stack in the
+/// order given.
+pub fn draw_badges(canvas: &mut Canvas, badges: &[(Placement, Badge)], theme: &Theme) {
+    for slot in Slot::ALL {
+        let in_slot: Vec<(&Badge, BadgeSize)> = badges
+            .iter()
+            .filter(|(placement, _)| placement.slot == slot)
+            .map(|(placement, badge)| (badge, placement.size))
+            .collect();
+        if in_slot.is_empty() {
+            continue;
+        }
+        if slot == Slot::BottomBar {
+            draw_bar(canvas, &in_slot, theme);
+        } else {
+            draw_corner(canvas, slot, &in_slot, theme);
+        }
+    }
+}
+
+/// Stacks badges in a corner, growing away from the nearest edge.
+fn draw_corner(canvas: &mut Canvas, slot: Slot, badges: &[(&Badge, BadgeSize)], theme: &Theme) {
+    let (width, height) = canvas.dimensions();
+    let right = matches!(slot, Slot::TopRight | Slot::BottomRight);
+    let bottom = matches!(slot, Slot::BottomLeft | Slot::BottomRight);
+
+    let mut y = if bottom { height as i32 - EDGE } else { EDGE };
+    for &(badge, size) in badges {
+        let font = size.font_size();
+        let badge_w = content_width(canvas, badge, font) + PADDING * 2;
+        let badge_h = canvas.line_height(font) + PADDING * 2;
+        let x = if right {
+            width as i32 - EDGE - badge_w
+        } else {
+            EDGE
+        };
+        let top = if bottom { y - badge_h } else { y };
+        let (x, top) = (x.max(0), top.max(0));
+        canvas.fill_rect(
+            x,
+            top,
+            badge_w.max(0) as u32,
+            badge_h.max(0) as u32,
+            theme.background,
+        );
+        draw_content(canvas, x + PADDING, top + PADDING, badge, font, theme);
+        y = if bottom {
+            top - PADDING
+        } else {
+            y + badge_h + PADDING
+        };
+    }
+}
+
+/// Draws badges side by side, centered in a strip along the bottom edge.
+fn draw_bar(canvas: &mut Canvas, badges: &[(&Badge, BadgeSize)], theme: &Theme) {
+    let (width, height) = canvas.dimensions();
+    let font = badges
+        .iter()
+        .map(|(_, size)| size.font_size())
+        .fold(0.0, f32::max);
+    let gap = canvas.text_width("  ", font);
+    let total_w = badges
+        .iter()
+        .map(|(badge, _)| content_width(canvas, badge, font))
+        .sum::<i32>()
+        + gap * (badges.len() as i32 - 1);
+
+    let bar_h = canvas.line_height(font) + PADDING * 2;
+    let y = height as i32 - bar_h;
+    canvas.fill_rect(0, y, width, bar_h as u32, theme.background);
+    let mut x = ((width as i32 - total_w) / 2).max(EDGE);
+    for &(badge, _) in badges {
+        draw_content(canvas, x, y + PADDING, badge, font, theme);
+        x += content_width(canvas, badge, font) + gap;
+    }
+}
+
+/// Diameter of a status dot for a font size.
+fn dot_diameter(font: f32) -> i32 {
+    (font * 0.6).round() as i32
+}
+
+/// Returns the width of a badge's content, without padding.
+fn content_width(canvas: &Canvas, badge: &Badge, font: f32) -> i32 {
+    match badge {
+        Badge::Text(text) => canvas.text_width(text, font),
+        Badge::Dot(_) => dot_diameter(font),
+    }
+}
+
+/// Draws a badge's content with its top-left corner at (`x`, `y`).
+fn draw_content(canvas: &mut Canvas, x: i32, y: i32, badge: &Badge, font: f32, theme: &Theme) {
+    match badge {
+        Badge::Text(text) => canvas.draw_text(x, y, text, font, theme.text),
+        Badge::Dot(color) => {
+            let diameter = dot_diameter(font);
+            let radius = diameter / 2;
+            let cy = y + canvas.line_height(font) / 2;
+            canvas.fill_circle(x + radius, cy, radius as u32, *color);
+        }
+    }
+}
+
+/// Formats a placeable complication as one line of text, reading its
+/// options through `option`. Returns None if there is nothing to show.
+pub(super) fn badge_text<'a>(
+    id: &str,
+    data: &SystemData,
+    option: impl Fn(&str) -> Option<&'a str>,
+) -> Option<String> {
+    match id {
+        complication_names::TIME => {
+            // Badges are text only, so the analogue clock shows as digital
+            let format = option(complication_options::TIME_FORMAT)
+                .filter(|f| *f != time_formats::ANALOGUE)
+                .unwrap_or(time_formats::DIGITAL_24H);
+            Some(data.format_time(format))
+        }
+        complication_names::DATE => {
+            data.format_date(option(complication_options::DATE_FORMAT).unwrap_or(date_formats::ISO))
+        }
+        complication_names::HOSTNAME => Some(data.hostname.clone()),
+        complication_names::IP_ADDRESS => data.display_ip.clone(),
+        complication_names::CPU_TEMP => data.cpu_temp.map(|t| format!("{:.0}°C", t)),
+        complication_names::THROTTLING => data
+            .cpu_throttle
+            .filter(|throttle| throttle.throttled)
+            .map(|throttle| throttle.format()),
+        complication_names::TOP_APP => data.top_app.as_ref().map(|app| app.format()),
+        complication_names::JOURNAL_ERRORS => data
+            .journal_errors
+            .as_ref()
+            .filter(|errors| errors.count > 0)
+            .map(|errors| format!("{} err", errors.count)),
+        complication_names::UPTIME => Some(format!(
+            "Up {}",
+            data.format_uptime(
+                option(complication_options::UPTIME_FORMAT).unwrap_or(uptime_formats::SHORT)
+            )
+        )),
+        complication_names::UPDATES => data
+            .pending_updates
+            .map(|updates| format!("\u{2191}{}", updates.total)),
+        complication_names::SSH_SESSIONS => Some(format!("SSH:{}", data.ssh_sessions.count)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slot_names() {
+        for slot in Slot::ALL {
+            assert_eq!(Slot::from_name(slot.name()), Some(slot));
+        }
+        assert_eq!(Slot::from_name("middle"), None);
+    }
+
+    #[test]
+    fn test_face_layout_hides_placed() {
+        let mut complications = EnabledComplications::new();
+        complications.set_enabled("clock", complication_names::TIME, true);
+        complications.set_enabled("clock", complication_names::DATE, true);
+        assert!(matches!(
+            face_layout(&complications, "clock", &[]),
+            Cow::Borrowed(_)
+        ));
+        let layout = face_layout(&complications, "clock", &[complication_names::DATE]);
+        assert!(!layout.is_enabled("clock", complication_names::DATE, true));
+
+        complications.set_placement(
+            "clock",
+            complication_names::TIME,
+            Some(Placement {
+                slot: Slot::TopRight,
+                size: BadgeSize::Large,
+            }),
+        );
+        let layout = face_layout(&complications, "clock", &[]);
+        assert!(!layout.is_enabled("clock", complication_names::TIME, true));
+        assert!(layout.is_enabled("clock", complication_names::DATE, false));
+        // The stored settings keep it enabled
+        assert!(complications.is_enabled("clock", complication_names::TIME, false));
+    }
+
+    #[test]
+    fn test_placed_badges_leave_out_hidden() {
+        let mut complications = EnabledComplications::new();
+        let top_right = Some(Placement {
+            slot: Slot::TopRight,
+            size: BadgeSize::Small,
+        });
+        for id in [complication_names::HOSTNAME, complication_names::CPU_TEMP] {
+            complications.set_enabled("clock", id, true);
+            complications.set_placement("clock", id, top_right);
+        }
+        let data = SystemData {
+            hostname: "nas".to_string(),
+            cpu_temp: Some(50.0),
+            ..Default::default()
+        };
+
+        assert_eq!(placed_badges("clock", &data, &complications, &[]).len(), 2);
+        let badges = placed_badges(
+            "clock",
+            &data,
+            &complications,
+            &[complication_names::CPU_TEMP],
+        );
+        assert_eq!(badges.len(), 1);
+        assert_eq!(badges[0].1, Badge::Text("nas".to_string()));
+    }
+}
@@ -0,0 +1,214 @@
+//! Printer face showing 3D print progress.
+//!
+//! Shows the printer state, file name, a large progress figure with a bar,
+//! the estimated finish time and the nozzle and bed temperatures. Requires
+//! `[sensors.printer]` to be enabled.
+
+use super::{
+    complication_names, complication_options, complications, time_formats, Complication,
+    EnabledComplications, Face, Theme,
+};
+use crate::data::{HeaterTemp, SystemData};
+use crate::rendering::Canvas;
+
+/// Font size for the header.
+const FONT_HEADER: f32 = 14.0;
+
+/// Font size for the progress percentage.
+const FONT_PROGRESS: f32 = 36.0;
+
+/// Font size for detail lines.
+const FONT_SMALL: f32 = 12.0;
+
+/// Height of the progress bar in pixels.
+const BAR_HEIGHT: u32 = 12;
+
+/// Dim a color by mixing it toward the background.
+fn dim_color(color: u32, background: u32, factor: f32) -> u32 {
+    let r1 = ((color >> 16) & 0xFF) as f32;
+    let g1 = ((color >> 8) & 0xFF) as f32;
+    let b1 = (color & 0xFF) as f32;
+    let r2 = ((background >> 16) & 0xFF) as f32;
+    let g2 = ((background >> 8) & 0xFF) as f32;
+    let b2 = (background & 0xFF) as f32;
+
+    let r = (r1 * factor + r2 * (1.0 - factor)) as u32;
+    let g = (g1 * factor + g2 * (1.0 - factor)) as u32;
+    let b = (b1 * factor + b2 * (1.0 - factor)) as u32;
+
+    (r << 16) | (g << 8) | b
+}
+
+/// Formats seconds as "1h 12m" or "12m".
+fn format_duration(secs: u64) -> String {
+    let minutes = secs.div_ceil(60);
+    if minutes >= 60 {
+        format!("{}h {:02}m", minutes / 60, minutes % 60)
+    } else {
+        format!("{}m", minutes)
+    }
+}
+
+/// Formats a heater as "210/215°C", or just "25°C" when it is off.
+fn format_heater(label: &str, heater: &HeaterTemp) -> String {
+    if heater.target > 0.0 {
+        format!("{} {:.0}/{:.0}°C", label, heater.actual, heater.target)
+    } else {
+        format!("{} {:.0}°C", label, heater.actual)
+    }
+}
+
+/// A 3D printer progress face.
+pub struct PrinterFace;
+
+impl PrinterFace {
+    /// Creates a new printer face.
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for PrinterFace {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Face for PrinterFace {
+    fn name(&self) -> &str {
+        "printer"
+    }
+
+    fn available_complications(&self) -> Vec<Complication> {
+        vec![complications::time(true)]
+    }
+
+    fn required_sensors(&self) -> &'static [&'static str] {
+        &[]
+    }
+
+    fn render(
+        &self,
+        canvas: &mut Canvas,
+        data: &SystemData,
+        theme: &Theme,
+        complications: &EnabledComplications,
+    ) {
+        let (width, height) = canvas.dimensions();
+        let margin = 8;
+        let inner_width = width as i32 - margin * 2;
+        let dim = dim_color(theme.text, theme.background, 0.5);
+        let mut y = margin;
+
+        // Header: printer state, with the time on the right
+        let state = data
+            .printer
+            .as_ref()
+            .map(|p| p.state.as_str())
+            .unwrap_or("Printer offline");
+        canvas.draw_text(margin, y, state, FONT_HEADER, theme.primary);
+        if complications.is_enabled(self.name(), complication_names::TIME, true) {
+            let format = complications
+                .get_option(
+                    self.name(),
+                    complication_names::TIME,
+                    complication_options::TIME_FORMAT,
+                )
+                .map(|s| s.as_str())
+                .filter(|f| *f != time_formats::ANALOGUE)
+                .unwrap_or(time_formats::DIGITAL_24H);
+            let time = data.format_time(format);
+            let time_w = canvas.text_width(&time, FONT_HEADER);
+            canvas.draw_text(
+                width as i32 - margin - time_w,
+                y,
+                &time,
+                FONT_HEADER,
+                theme.text,
+            );
+        }
+        y += canvas.line_height(FONT_HEADER) + 4;
+
+        let Some(ref printer) = data.printer else {
+            return;
+        };
+
+        // File name, truncated to fit
+        if let Some(ref file) = printer.file {
+            let name = canvas.truncate_to_width(file, FONT_SMALL, inner_width);
+            canvas.draw_text(margin, y, &name, FONT_SMALL, dim);
+            y += canvas.line_height(FONT_SMALL) + 4;
+        }
+
+        if printer.active {
+            // Large percentage with elapsed time on the right
+            let progress = printer.progress.unwrap_or(0.0).clamp(0.0, 100.0);
+            let percent = format!("{:.0}%", progress);
+            canvas.draw_text(margin, y, &percent, FONT_PROGRESS, theme.text);
+            if let Some(elapsed) = printer.elapsed {
+                let text = format!("{} elapsed", format_duration(elapsed));
+                let text_w = canvas.text_width(&text, FONT_SMALL);
+                let text_y = y + canvas.line_height(FONT_PROGRESS) - canvas.line_height(FONT_SMALL);
+                canvas.draw_text(
+                    width as i32 - margin - text_w,
+                    text_y,
+                    &text,
+                    FONT_SMALL,
+                    dim,
+                );
+            }
+            y += canvas.line_height(FONT_PROGRESS) + 2;
+
+            // Progress bar
+            let bar_bg = dim_color(theme.primary, theme.background, 0.2);
+            canvas.fill_rect(margin, y, inner_width as u32, BAR_HEIGHT, bar_bg);
+            let fill = (inner_width as f32 * progress / 100.0) as u32;
+            if fill > 0 {
+                canvas.fill_rect(margin, y, fill, BAR_HEIGHT, theme.primary);
+            }
+            y += BAR_HEIGHT as i32 + 6;
+
+            // Finish time as a clock time plus the remaining duration
+            if let Some(remaining) = printer.remaining {
+                let now_minutes = data.hour as u64 * 60 + data.minute as u64;
+                let finish = (now_minutes + remaining.div_ceil(60)) % (24 * 60);
+                let text = format!(
+                    "ETA {:02}:{:02} ({} left)",
+                    finish / 60,
+                    finish % 60,
+                    format_duration(remaining)
+                );
+                canvas.draw_text(margin, y, &text, FONT_HEADER, theme.secondary);
+                y += canvas.line_height(FONT_HEADER) + 4;
+            }
+        } else {
+            canvas.draw_text(margin, y, "No active print", FONT_HEADER, dim);
+            y += canvas.line_height(FONT_HEADER) + 4;
+        }
+
+        // Heater temperatures on the bottom line
+        let temps: Vec<String> = [
+            printer.nozzle.map(|h| format_heater("Nozzle", &h)),
+            printer.bed.map(|h| format_heater("Bed", &h)),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+        let line_height = canvas.line_height(FONT_SMALL);
+        let joined = temps.join("  ");
+        // Stack the heaters when they do not fit side by side
+        let lines = if canvas.text_width(&joined, FONT_SMALL) <= inner_width {
+            vec![joined]
+        } else {
+            temps
+        };
+        let block_height = line_height * lines.len() as i32;
+        if !lines.is_empty() && y + block_height <= height as i32 {
+            let mut line_y = (height as i32 - margin - block_height).max(y);
+            for line in &lines {
+                canvas.draw_text(margin, line_y, line, FONT_SMALL, theme.text);
+                line_y += line_height;
+            }
+        }
+    }
+}
@@ -0,0 +1,1627 @@
+//! Professional face with graphical progress bars.
+//!
+//! Landscape layout (320x170):
+//! ```text
+//! endeavour               18:45
+//! Up: 5d 12h 34m          2025-01-31
+//! IP:                  192.168.1.100
+//! Temp:                        45°C
+//! CPU: 45%
+//! [████████████░░░░░░░░░░░░░░░░░░]
+//! RAM: 67%
+//! [██████████████████░░░░░░░░░░░░]
+//! DSK:                   R:12M W:5M
+//! [▁▁▂▃▄▅▆▇███▇▆▅▄▃▂▁▁▁▁▁▁▁▁▁▁▁▁]
+//! NET:                 ↓:1.2M ↑:0.8M
+//! [▁▁▁▂▂▃▃▄▄▅▅▆▆▇▇████▇▇▆▆▅▅▄▄▃▃]
+//! ```
+
+use super::{
+    complication_names, complication_options, complications, date_formats, draw_mini_analog_clock,
+    draw_temperature_graph, graph_layouts, time_formats, totals_periods, uptime_formats,
+    Complication, EnabledComplications, Face, Theme,
+};
+use crate::data::{
+    GpuStats, GraphStats, IpDisplayMode, JobState, JobStatus, MediaStream, Metric, PoolStatus,
+    SystemData,
+};
+use crate::rendering::{Canvas, GraphStyle};
+use std::collections::VecDeque;
+
+/// Dim a color by mixing it toward the background.
+fn dim_color(color: u32, background: u32, factor: f32) -> u32 {
+    let r1 = ((color >> 16) & 0xFF) as f32;
+    let g1 = ((color >> 8) & 0xFF) as f32;
+    let b1 = (color & 0xFF) as f32;
+    let r2 = ((background >> 16) & 0xFF) as f32;
+    let g2 = ((background >> 8) & 0xFF) as f32;
+    let b2 = (background & 0xFF) as f32;
+
+    let r = (r1 * factor + r2 * (1.0 - factor)) as u32;
+    let g = (g1 * factor + g2 * (1.0 - factor)) as u32;
+    let b = (b1 * factor + b2 * (1.0 - factor)) as u32;
+
+    (r << 16) | (g << 8) | b
+}
+
+/// Formats a count compactly (e.g., "950", "12.3k", "1.2M").
+fn format_count(count: u64) -> String {
+    if count >= 1_000_000 {
+        format!("{:.1}M", count as f64 / 1_000_000.0)
+    } else if count >= 10_000 {
+        format!("{:.0}k", count as f64 / 1_000.0)
+    } else if count >= 1_000 {
+        format!("{:.1}k", count as f64 / 1_000.0)
+    } else {
+        count.to_string()
+    }
+}
+
+/// Derive colors from theme for the professional face.
+struct FaceColors {
+    /// Primary highlight color (hostname, interface name)
+    highlight: u32,
+    /// Main text color
+    text: u32,
+    /// Dimmed text color (uptime, IPs)
+    dim: u32,
+    /// Progress bar background
+    bar_bg: u32,
+    /// CPU bar fill color
+    bar_cpu: u32,
+    /// RAM bar fill color
+    bar_ram: u32,
+    /// Disk read bar fill color
+    bar_disk_read: u32,
+    /// Disk write bar fill color
+    bar_disk_write: u32,
+    /// Network receive bar fill color
+    bar_net_rx: u32,
+    /// Network transmit bar fill color
+    bar_net_tx: u32,
+}
+
+impl FaceColors {
+    fn from_theme(theme: &Theme) -> Self {
+        // Create distinct shades for read/write and rx/tx
+        let disk_base = dim_color(theme.primary, theme.secondary, 0.5);
+        let net_base = theme.secondary;
+
+        Self {
+            highlight: theme.primary,
+            text: theme.text,
+            dim: dim_color(theme.text, theme.background, 0.7), // Higher for better contrast
+            bar_bg: dim_color(theme.primary, theme.background, 0.2),
+            bar_cpu: theme.primary,
+            bar_ram: theme.secondary,
+            // Disk: read is brighter, write is dimmer
+            bar_disk_read: disk_base,
+            bar_disk_write: dim_color(disk_base, theme.background, 0.6),
+            // Network: rx (download) is brighter, tx (upload) is dimmer
+            bar_net_rx: net_base,
+            bar_net_tx: dim_color(net_base, theme.background, 0.6),
+        }
+    }
+}
+
+/// Font sizes.
+const FONT_LARGE: f32 = 16.0;
+const FONT_NORMAL: f32 = 14.0;
+const FONT_SMALL: f32 = 12.0;
+
+/// Progress bar dimensions.
+const BAR_WIDTH: u32 = 120;
+const BAR_HEIGHT: u32 = 10;
+
+/// Graph dimensions.
+const GRAPH_HEIGHT: u32 = 16;
+
+/// Font size for peak labels drawn inside graphs.
+const FONT_TINY: f32 = 9.0;
+
+/// Color for degraded storage pools, failed jobs and CPU throttling.
+const ALERT_COLOR: u32 = 0xE04040;
+
+/// Text color on degraded pool badges.
+const BADGE_TEXT_COLOR: u32 = 0xFFFFFF;
+
+/// Maximum stream titles listed (portrait only).
+const MAX_STREAM_LIST: usize = 3;
+
+/// Maximum addresses in the stacked IP list (portrait only).
+const MAX_IP_LIST: usize = 4;
+
+/// A professional face with graphical progress bars.
+pub struct ProfessionalFace;
+
+impl ProfessionalFace {
+    /// Creates a new professional face.
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Draws the CPU model summary, squeezed horizontally to fit `max_width`.
+    fn draw_cpu_model(
+        canvas: &mut Canvas,
+        x: i32,
+        y: i32,
+        max_width: i32,
+        data: &SystemData,
+        color: u32,
+    ) {
+        let text = data.cpu_info.summary();
+        let text_width = canvas.text_width(&text, FONT_SMALL);
+        let x_scale = (max_width as f32 / text_width as f32).min(1.0);
+        canvas.draw_text_scaled(x, y, &text, FONT_SMALL, color, x_scale);
+    }
+
+    /// Draws the network rx/tx graph. Rx is drawn in its own color above tx
+    /// (overlaid or mirrored), with optional peak labels at the right edge.
+    #[allow(clippy::too_many_arguments)]
+    fn draw_network_graph(
+        canvas: &mut Canvas,
+        x: i32,
+        y: i32,
+        width: u32,
+        data: &SystemData,
+        rx_history: &VecDeque<f64>,
+        tx_history: &VecDeque<f64>,
+        scale: f64,
+        style: GraphStyle,
+        mirrored: bool,
+        peaks: bool,
+        colors: &FaceColors,
+    ) {
+        if mirrored {
+            canvas.draw_mirrored_graph(
+                x,
+                y,
+                width,
+                GRAPH_HEIGHT,
+                rx_history,
+                tx_history,
+                scale,
+                colors.bar_net_rx,
+                colors.bar_net_tx,
+                colors.bar_bg,
+                style,
+            );
+        } else {
+            canvas.draw_dual_graph(
+                x,
+                y,
+                width,
+                GRAPH_HEIGHT,
+                rx_history,
+                tx_history,
+                scale,
+                colors.bar_net_rx,
+                colors.bar_net_tx,
+                colors.bar_bg,
+                style,
+            );
+        }
+
+        if peaks {
+            // Peaks come from the raw samples so smoothing does not hide spikes
+            let peak = |history: &VecDeque<f64>| history.iter().copied().fold(0.0, f64::max);
+            let rx_peak = format!(
+                "\u{2193}{}",
+                data.format_net_rate_compact(peak(&data.net_rx_history))
+            );
+            let tx_peak = format!(
+                "\u{2191}{}",
+                data.format_net_rate_compact(peak(&data.net_tx_history))
+            );
+            let right = x + width as i32 - 2;
+            let tiny_height = canvas.line_height(FONT_TINY);
+            let rx_w = canvas.text_width(&rx_peak, FONT_TINY);
+            let tx_w = canvas.text_width(&tx_peak, FONT_TINY);
+            canvas.draw_text(right - rx_w, y, &rx_peak, FONT_TINY, colors.text);
+            canvas.draw_text(
+                right - tx_w,
+                y + GRAPH_HEIGHT as i32 - tiny_height,
+                &tx_peak,
+                FONT_TINY,
+                colors.text,
+            );
+        }
+    }
+
+    /// Formats the peak and average of both series of a rate graph as
+    /// "max ↓2.3M ↑410K avg ↓1.1M ↑96K".
+    fn graph_stats_text(series: [(&str, GraphStats); 2], format: impl Fn(f64) -> String) -> String {
+        let [(first, a), (second, b)] = series;
+        format!(
+            "max {}{} {}{} avg {}{} {}{}",
+            first,
+            format(a.peak),
+            second,
+            format(b.peak),
+            first,
+            format(a.average),
+            second,
+            format(b.average)
+        )
+    }
+
+    /// Returns the horizontal scale that squeezes graph statistics into
+    /// `width`. Scaled text advances by fractional glyph widths, which
+    /// `text_width` truncates, so allow up to a pixel per character.
+    fn graph_stats_scale(canvas: &Canvas, text: &str, width: i32) -> f32 {
+        let text_width = canvas.text_width(text, FONT_TINY) + text.chars().count() as i32;
+        (width as f32 / text_width as f32).min(1.0)
+    }
+
+    /// Draws graph statistics on their own line below a portrait graph,
+    /// squeezed to fit `width`.
+    fn draw_graph_stats(canvas: &mut Canvas, x: i32, y: i32, width: i32, text: &str, color: u32) {
+        let x_scale = Self::graph_stats_scale(canvas, text, width);
+        canvas.draw_text_scaled(x, y, text, FONT_TINY, color, x_scale);
+    }
+
+    /// Draws graph statistics in the gap between a landscape graph's label
+    /// and its rates, squeezed to fit between `x` and `max_x` unless that
+    /// would make them illegible.
+    fn draw_graph_stats_inline(
+        canvas: &mut Canvas,
+        x: i32,
+        y: i32,
+        max_x: i32,
+        text: &str,
+        color: u32,
+    ) {
+        let x_scale = Self::graph_stats_scale(canvas, text, max_x - x);
+        if x_scale >= 0.6 {
+            // Center the smaller text on the label line
+            let offset = (canvas.line_height(FONT_SMALL) - canvas.line_height(FONT_TINY)) / 2;
+            canvas.draw_text_scaled(x, y + offset, text, FONT_TINY, color, x_scale);
+        }
+    }
+
+    /// Draws "GPU" followed by utilization, temperature, fan and power in
+    /// equal-width cells. Readings the driver does not report are skipped.
+    fn draw_gpu_strip(
+        canvas: &mut Canvas,
+        x: i32,
+        y: i32,
+        width: i32,
+        gpu: &GpuStats,
+        colors: &FaceColors,
+    ) {
+        canvas.draw_text(x, y, "GPU", FONT_SMALL, colors.dim);
+        let label_w = canvas.text_width("GPU ", FONT_SMALL);
+
+        let cells = gpu.cells();
+        if cells.is_empty() {
+            return;
+        }
+
+        // Right-align each value in its cell so the columns line up
+        let cell_w = (width - label_w) / cells.len() as i32;
+        for (i, cell) in cells.iter().enumerate() {
+            let cell_right = x + label_w + cell_w * (i as i32 + 1);
+            let text_w = canvas.text_width(cell, FONT_SMALL);
+            canvas.draw_text(cell_right - text_w, y, cell, FONT_SMALL, colors.text);
+        }
+    }
+
+    /// Draws storage pools on one line: healthy pools as plain names (with
+    /// scrub progress while running), degraded pools as red badges.
+    fn draw_pool_badges(
+        canvas: &mut Canvas,
+        x: i32,
+        y: i32,
+        width: i32,
+        pools: &[PoolStatus],
+        colors: &FaceColors,
+    ) {
+        canvas.draw_text(x, y, "POOL", FONT_SMALL, colors.dim);
+        let mut item_x = x + canvas.text_width("POOL ", FONT_SMALL);
+        let right = x + width;
+        let line_height = canvas.line_height(FONT_SMALL);
+
+        // Degraded pools first so they are never cut off
+        let mut pools: Vec<&PoolStatus> = pools.iter().collect();
+        pools.sort_by_key(|p| !p.degraded);
+
+        for pool in pools {
+            let text = match pool.scrub_progress {
+                Some(progress) => format!("{} {:.0}%", pool.name, progress),
+                None => pool.name.clone(),
+            };
+            let text_w = canvas.text_width(&text, FONT_SMALL);
+            if item_x + text_w + 4 > right {
+                break;
+            }
+            if pool.degraded {
+                canvas.fill_rect(
+                    item_x,
+                    y,
+                    (text_w + 4) as u32,
+                    line_height as u32,
+                    ALERT_COLOR,
+                );
+                canvas.draw_text(item_x + 2, y, &text, FONT_SMALL, BADGE_TEXT_COLOR);
+            } else {
+                canvas.draw_text(item_x + 2, y, &text, FONT_SMALL, colors.text);
+            }
+            item_x += text_w + 8;
+        }
+    }
+
+    /// Returns the metrics named in the comma-separated `names` option, in
+    /// that order, or all metrics if it is blank.
+    fn selected_metrics<'a>(
+        data: &'a SystemData,
+        names: Option<&String>,
+    ) -> Vec<(&'a str, &'a Metric)> {
+        match names.map(|n| n.trim()).filter(|n| !n.is_empty()) {
+            Some(names) => names
+                .split(',')
+                .filter_map(|name| data.metrics.get_key_value(name.trim()))
+                .map(|(name, metric)| (name.as_str(), metric))
+                .collect(),
+            None => data
+                .metrics
+                .iter()
+                .map(|(name, metric)| (name.as_str(), metric))
+                .collect(),
+        }
+    }
+
+    /// Returns the jobs named in the comma-separated `names` option, in that
+    /// order, or all jobs if it is blank.
+    fn selected_jobs<'a>(
+        data: &'a SystemData,
+        names: Option<&String>,
+    ) -> Vec<(&'a str, &'a JobStatus)> {
+        match names.map(|n| n.trim()).filter(|n| !n.is_empty()) {
+            Some(names) => names
+                .split(',')
+                .filter_map(|name| data.jobs.get_key_value(name.trim()))
+                .map(|(name, job)| (name.as_str(), job))
+                .collect(),
+            None => data
+                .jobs
+                .iter()
+                .map(|(name, job)| (name.as_str(), job))
+                .collect(),
+        }
+    }
+
+    /// Text color for a job's status.
+    fn job_color(job: &JobStatus, colors: &FaceColors) -> u32 {
+        match job.state {
+            JobState::Running => colors.highlight,
+            JobState::Succeeded => colors.text,
+            JobState::Failed => ALERT_COLOR,
+        }
+    }
+
+    /// Summarizes streams as "2 (1 TC)", counting transcodes.
+    fn stream_summary(streams: &[MediaStream]) -> String {
+        let transcoding = streams.iter().filter(|s| s.transcoding).count();
+        if transcoding > 0 {
+            format!("{} ({} TC)", streams.len(), transcoding)
+        } else {
+            streams.len().to_string()
+        }
+    }
+
+    /// Draws the busiest disk's name and combined rate if it fits before `max_x`.
+    fn draw_busiest_disk(
+        canvas: &mut Canvas,
+        x: i32,
+        y: i32,
+        max_x: i32,
+        data: &SystemData,
+        color: u32,
+    ) {
+        let Some(disk) = data.busiest_disk() else {
+            return;
+        };
+        let text = format!(
+            "{} {}",
+            disk.device,
+            data.format_disk_rate_compact(disk.total_rate())
+        );
+        if x + canvas.text_width(&text, FONT_SMALL) <= max_x {
+            canvas.draw_text(x, y, &text, FONT_SMALL, color);
+        }
+    }
+
+    /// Draws a progress bar.
+    #[allow(clippy::too_many_arguments)]
+    fn draw_progress_bar(
+        canvas: &mut Canvas,
+        x: i32,
+        y: i32,
+        width: u32,
+        height: u32,
+        percent: f64,
+        fill_color: u32,
+        bg_color: u32,
+    ) {
+        // Draw background
+        canvas.fill_rect(x, y, width, height, bg_color);
+
+        // Draw filled portion
+        let fill_width = ((width as f64 * (percent / 100.0)) as u32).min(width);
+        if fill_width > 0 {
+            canvas.fill_rect(x, y, fill_width, height, fill_color);
+        }
+    }
+}
+
+impl Default for ProfessionalFace {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Face for ProfessionalFace {
+    fn name(&self) -> &str {
+        "professional"
+    }
+
+    fn available_complications(&self) -> Vec<Complication> {
+        vec![
+            complications::time(true),
+            complications::date(true, date_formats::ISO),
+            complications::ip_address(true),
+            complications::network(true)
+                .with_extra_options(complications::graph_options())
+                .with_extra_options(complications::dual_graph_options())
+                .with_extra_options(vec![complications::stats_option()]),
+            complications::disk_io(true)
+                .with_extra_options(complications::graph_options())
+                .with_extra_options(vec![
+                    complications::busiest_device_option(),
+                    complications::stats_option(),
+                ]),
+            complications::cpu_temp(true),
+            complications::throttling(true),
+            complications::temp_graph(false),
+            complications::hostname(true),
+            complications::uptime(true),
+            complications::updates(false),
+            complications::ssh_sessions(false),
+            complications::cpu_model(false),
+            complications::processes(false),
+            complications::top_app(false),
+            complications::network_totals(false),
+            complications::metrics(false),
+            complications::dns_blocking(false),
+            complications::gpu(false),
+            complications::storage_pools(false),
+            complications::jobs(false),
+            complications::downloads(false),
+            complications::media_streams(false),
+        ]
+    }
+
+    fn render(
+        &self,
+        canvas: &mut Canvas,
+        data: &SystemData,
+        theme: &Theme,
+        complications: &EnabledComplications,
+    ) {
+        let colors = FaceColors::from_theme(theme);
+        let (width, height) = canvas.dimensions();
+        let portrait = width < 200;
+        let margin = 8;
+        let mut y = margin;
+
+        // Helper to check if a complication is enabled
+        let is_enabled = |id: &str| -> bool { complications.is_enabled(self.name(), id, true) };
+
+        // Get time format option
+        let time_format = complications
+            .get_option(
+                self.name(),
+                complication_names::TIME,
+                complication_options::TIME_FORMAT,
+            )
+            .map(|s| s.as_str())
+            .unwrap_or(time_formats::DIGITAL_24H);
+
+        // Get IP multi-address mode
+        let ip_mode: IpDisplayMode = complications
+            .get_option(
+                self.name(),
+                complication_names::IP_ADDRESS,
+                complication_options::IP_MODE,
+            )
+            .and_then(|s| s.parse().ok())
+            .unwrap_or_default();
+
+        // Get date format option
+        let date_format = complications
+            .get_option(
+                self.name(),
+                complication_names::DATE,
+                complication_options::DATE_FORMAT,
+            )
+            .map(|s| s.as_str())
+            .unwrap_or(date_formats::ISO);
+
+        // Get uptime format option
+        let uptime_format = complications
+            .get_option(
+                self.name(),
+                complication_names::UPTIME,
+                complication_options::UPTIME_FORMAT,
+            )
+            .map(|s| s.as_str())
+            .unwrap_or(uptime_formats::SHORT);
+
+        // Graph styles and smoothed histories
+        let disk_style = complications.graph_style(self.name(), complication_names::DISK_IO);
+        let disk_window = complications.smoothing(self.name(), complication_names::DISK_IO);
+        let disk_read_history = SystemData::smooth_history(&data.disk_read_history, disk_window);
+        let disk_write_history = SystemData::smooth_history(&data.disk_write_history, disk_window);
+        let disk_scale = SystemData::compute_graph_scale(&SystemData::smooth_history(
+            &data.disk_history,
+            disk_window,
+        ));
+        let show_busiest_disk = complications
+            .get_option(
+                self.name(),
+                complication_names::DISK_IO,
+                complication_options::BUSIEST_DEVICE,
+            )
+            .is_some_and(|s| s == "true");
+        let disk_stats = complications
+            .get_option(
+                self.name(),
+                complication_names::DISK_IO,
+                complication_options::STATS,
+            )
+            .is_some_and(|s| s == "true")
+            .then(|| {
+                Self::graph_stats_text(
+                    [
+                        ("R", SystemData::graph_stats(&data.disk_read_history)),
+                        ("W", SystemData::graph_stats(&data.disk_write_history)),
+                    ],
+                    |rate| data.format_disk_rate_compact(rate),
+                )
+            });
+        let net_totals_period = complications
+            .get_option(
+                self.name(),
+                complication_names::NETWORK_TOTALS,
+                complication_options::TOTALS_PERIOD,
+            )
+            .map(|s| s.as_str())
+            .unwrap_or(totals_periods::MONTH);
+        let net_total = if net_totals_period == totals_periods::DAY {
+            data.net_totals.day()
+        } else {
+            data.net_totals.month()
+        };
+        let metrics = Self::selected_metrics(
+            data,
+            complications.get_option(
+                self.name(),
+                complication_names::METRICS,
+                complication_options::METRIC_NAMES,
+            ),
+        );
+        let jobs = Self::selected_jobs(
+            data,
+            complications.get_option(
+                self.name(),
+                complication_names::JOBS,
+                complication_options::JOB_NAMES,
+            ),
+        );
+        let net_style = complications.graph_style(self.name(), complication_names::NETWORK);
+        let net_window = complications.smoothing(self.name(), complication_names::NETWORK);
+        let net_rx_history = SystemData::smooth_history(&data.net_rx_history, net_window);
+        let net_tx_history = SystemData::smooth_history(&data.net_tx_history, net_window);
+        let net_mirrored = complications
+            .get_option(
+                self.name(),
+                complication_names::NETWORK,
+                complication_options::GRAPH_LAYOUT,
+            )
+            .is_some_and(|s| s == graph_layouts::MIRRORED);
+        let net_peaks = complications
+            .get_option(
+                self.name(),
+                complication_names::NETWORK,
+                complication_options::PEAKS,
+            )
+            .is_some_and(|s| s == "true");
+        let net_stats = complications
+            .get_option(
+                self.name(),
+                complication_names::NETWORK,
+                complication_options::STATS,
+            )
+            .is_some_and(|s| s == "true")
+            .then(|| {
+                Self::graph_stats_text(
+                    [
+                        ("\u{2193}", SystemData::graph_stats(&data.net_rx_history)),
+                        ("\u{2191}", SystemData::graph_stats(&data.net_tx_history)),
+                    ],
+                    |rate| data.format_net_rate_compact(rate),
+                )
+            });
+        let net_scale = SystemData::compute_graph_scale(&SystemData::smooth_history(
+            &data.net_history,
+            net_window,
+        ));
+
+        if portrait {
+            // Portrait layout - full width bars on own lines, stacked text
+            let bar_width = (width - (margin * 2) as u32).min(200);
+            let tall_bar_height = 14_u32; // Taller bars for CPU/RAM
+            let section_spacing = 6; // Extra spacing between sections
+            let line_height = canvas.line_height(FONT_SMALL);
+
+            // Complication: Hostname
+            if is_enabled(complication_names::HOSTNAME) {
+                canvas.draw_text(margin, y, &data.hostname, FONT_LARGE, colors.highlight);
+            }
+
+            // Complication: Time (right-aligned)
+            if is_enabled(complication_names::TIME) {
+                if time_format == time_formats::ANALOGUE {
+                    // Draw small analog clock on the right
+                    let clock_radius = 10_u32;
+                    let clock_cx = width as i32 - margin - clock_radius as i32;
+                    let clock_cy = y + clock_radius as i32;
+                    draw_mini_analog_clock(
+                        canvas,
+                        clock_cx,
+                        clock_cy,
+                        clock_radius,
+                        data.hour,
+                        data.minute,
+                        colors.highlight,
+                        colors.text,
+                    );
+                } else {
+                    let time_str = data.format_time(time_format);
+                    let time_width = canvas.text_width(&time_str, FONT_LARGE);
+                    canvas.draw_text(
+                        width as i32 - margin - time_width,
+                        y,
+                        &time_str,
+                        FONT_LARGE,
+                        colors.text,
+                    );
+                }
+            }
+            y += canvas.line_height(FONT_LARGE) + 2;
+
+            // Complication: CPU model header, squeezed to fit
+            if is_enabled(complication_names::CPU_MODEL) {
+                Self::draw_cpu_model(
+                    canvas,
+                    margin,
+                    y,
+                    width as i32 - margin * 2,
+                    data,
+                    colors.dim,
+                );
+                y += line_height;
+            }
+
+            // Complication: Date (right-aligned, under time)
+            if is_enabled(complication_names::DATE) {
+                if let Some(date_str) = data.format_date(date_format) {
+                    let date_width = canvas.text_width(&date_str, FONT_SMALL);
+                    canvas.draw_text(
+                        width as i32 - margin - date_width,
+                        y,
+                        &date_str,
+                        FONT_SMALL,
+                        colors.dim,
+                    );
+                    y += line_height;
+                }
+            }
+
+            // Stacked IP list reuses the gap before Uptime
+            let ip_list = is_enabled(complication_names::IP_ADDRESS)
+                && ip_mode == IpDisplayMode::List
+                && !data.ip_addresses.is_empty();
+
+            // Two lines lower before Uptime
+            if !ip_list {
+                y += line_height * 2;
+            }
+
+            // Complication: Uptime
+            if is_enabled(complication_names::UPTIME) {
+                let uptime_text = format!("Up: {}", data.format_uptime(uptime_format));
+                canvas.draw_text(margin, y, &uptime_text, FONT_SMALL, colors.dim);
+            }
+            y += line_height + section_spacing;
+
+            // Complication: IP address with label on its own line
+            if ip_list {
+                canvas.draw_text(margin, y, "IP:", FONT_SMALL, colors.dim);
+                y += line_height;
+                // One address per line, squeezed horizontally if needed
+                let max_width = width as i32 - margin * 2;
+                for ip in data.ip_addresses.iter().take(MAX_IP_LIST) {
+                    let ip_width = canvas.text_width(ip, FONT_SMALL);
+                    let x_scale = (max_width as f32 / ip_width as f32).min(1.0);
+                    canvas.draw_text_scaled(margin, y, ip, FONT_SMALL, colors.text, x_scale);
+                    y += line_height;
+                }
+                y += section_spacing * 2;
+            } else if is_enabled(complication_names::IP_ADDRESS) {
+                if let Some(ref ip) = data.display_ip {
+                    // IP label on its own line
+                    canvas.draw_text(margin, y, "IP:", FONT_SMALL, colors.dim);
+                    y += line_height;
+                    // IP address on next line
+                    let max_width = width as i32 - margin * 2;
+                    let ip_width = canvas.text_width(ip, FONT_SMALL);
+                    if ip_width > max_width && ip.contains(':') {
+                        let mid = ip.len() / 2;
+                        let split_pos = ip[..mid].rfind(':').map(|p| p + 1).unwrap_or(mid);
+                        let (first, second) = ip.split_at(split_pos);
+                        canvas.draw_text(margin, y, first, FONT_SMALL, colors.text);
+                        y += line_height;
+                        canvas.draw_text(margin, y, second, FONT_SMALL, colors.text);
+                    } else {
+                        canvas.draw_text(margin, y, ip, FONT_SMALL, colors.text);
+                    }
+                    y += line_height + section_spacing * 2;
+                }
+            }
+
+            // Complication: CPU temperature
+            if is_enabled(complication_names::CPU_TEMP) {
+                if let Some(temp) = data.cpu_temp {
+                    canvas.draw_text(margin, y, "Temp:", FONT_SMALL, colors.dim);
+                    let temp_val = format!("{:.0}°C", temp);
+                    let temp_w = canvas.text_width(&temp_val, FONT_SMALL);
+                    canvas.draw_text(
+                        width as i32 - margin - temp_w,
+                        y,
+                        &temp_val,
+                        FONT_SMALL,
+                        colors.text,
+                    );
+                    y += line_height + section_spacing;
+                }
+            }
+
+            // Complication: CPU throttling, only while throttled
+            if is_enabled(complication_names::THROTTLING) {
+                if let Some(throttle) = data.cpu_throttle.filter(|t| t.throttled) {
+                    canvas.draw_text(margin, y, "Throttle:", FONT_SMALL, colors.dim);
+                    let value = throttle
+                        .clock_percent
+                        .map(|percent| format!("{:.0}%", percent))
+                        .unwrap_or_else(|| "Yes".to_string());
+                    let value_w = canvas.text_width(&value, FONT_SMALL);
+                    canvas.draw_text(
+                        width as i32 - margin - value_w,
+                        y,
+                        &value,
+                        FONT_SMALL,
+                        ALERT_COLOR,
+                    );
+                    y += line_height + section_spacing;
+                }
+            }
+
+            // Complication: Temperature over the last hour
+            if is_enabled(complication_names::TEMP_GRAPH) && !data.temp_history.is_empty() {
+                let graph_height = 24_u32;
+                let window = complications.smoothing(self.name(), complication_names::TEMP_GRAPH);
+                draw_temperature_graph(
+                    canvas,
+                    margin,
+                    y,
+                    bar_width,
+                    graph_height,
+                    &SystemData::smooth_history(&data.temp_history, window),
+                    colors.highlight,
+                    colors.bar_bg,
+                    colors.dim,
+                    FONT_TINY,
+                    complications.graph_style(self.name(), complication_names::TEMP_GRAPH),
+                );
+                y += graph_height as i32 + section_spacing;
+            }
+
+            // Complication: Pending updates
+            if is_enabled(complication_names::UPDATES) {
+                if let Some(updates) = data.pending_updates {
+                    canvas.draw_text(margin, y, "Updates:", FONT_SMALL, colors.dim);
+                    let (text, color) = if updates.security > 0 {
+                        (
+                            format!("{} ({} sec)", updates.total, updates.security),
+                            colors.highlight,
+                        )
+                    } else {
+                        (updates.total.to_string(), colors.text)
+                    };
+                    let text_w = canvas.text_width(&text, FONT_SMALL);
+                    canvas.draw_text(width as i32 - margin - text_w, y, &text, FONT_SMALL, color);
+                    y += line_height + section_spacing;
+                }
+            }
+
+            // Complication: SSH sessions, with the latest source below
+            if is_enabled(complication_names::SSH_SESSIONS) {
+                let sessions = &data.ssh_sessions;
+                canvas.draw_text(margin, y, "SSH:", FONT_SMALL, colors.dim);
+                let count = sessions.count.to_string();
+                let count_w = canvas.text_width(&count, FONT_SMALL);
+                let color = if sessions.count > 0 {
+                    colors.highlight
+                } else {
+                    colors.text
+                };
+                canvas.draw_text(
+                    width as i32 - margin - count_w,
+                    y,
+                    &count,
+                    FONT_SMALL,
+                    color,
+                );
+                y += line_height;
+                if let Some(ref source) = sessions.last_source {
+                    canvas.draw_text(margin, y, source, FONT_SMALL, colors.text);
+                    y += line_height;
+                }
+                y += section_spacing;
+            }
+
+            // Complication: Process/thread counts, zombies highlighted
+            if is_enabled(complication_names::PROCESSES) {
+                let counts = data.process_counts;
+                canvas.draw_text(margin, y, "Procs:", FONT_SMALL, colors.dim);
+                let (text, color) = if counts.zombies > 0 {
+                    (
+                        format!("{} Z:{}", counts.processes, counts.zombies),
+                        colors.highlight,
+                    )
+                } else {
+                    (
+                        format!("{}/{}", counts.processes, counts.threads),
+                        colors.text,
+                    )
+                };
+                let text_w = canvas.text_width(&text, FONT_SMALL);
+                canvas.draw_text(width as i32 - margin - text_w, y, &text, FONT_SMALL, color);
+                y += line_height + section_spacing;
+            }
+
+            // Complication: Application using the most CPU, name shortened to fit
+            if is_enabled(complication_names::TOP_APP) {
+                if let Some(ref app) = data.top_app {
+                    canvas.draw_text(margin, y, "Top:", FONT_SMALL, colors.dim);
+                    let percent = format!(" {:.0}%", app.cpu_percent);
+                    let label_w = canvas.text_width("Top: ", FONT_SMALL);
+                    let max_w = width as i32
+                        - margin * 2
+                        - label_w
+                        - canvas.text_width(&percent, FONT_SMALL);
+                    let name = canvas.truncate_to_width(&app.name, FONT_SMALL, max_w);
+                    let text = format!("{}{}", name, percent);
+                    let text_w = canvas.text_width(&text, FONT_SMALL);
+                    canvas.draw_text(
+                        width as i32 - margin - text_w,
+                        y,
+                        &text,
+                        FONT_SMALL,
+                        colors.text,
+                    );
+                    y += line_height + section_spacing;
+                }
+            }
+
+            // Complication: Network data usage
+            if is_enabled(complication_names::NETWORK_TOTALS) {
+                let label = if net_totals_period == totals_periods::DAY {
+                    "Today:"
+                } else {
+                    "This month:"
+                };
+                canvas.draw_text(margin, y, label, FONT_SMALL, colors.dim);
+                let text = data.format_bytes(net_total);
+                let text_w = canvas.text_width(&text, FONT_SMALL);
+                canvas.draw_text(
+                    width as i32 - margin - text_w,
+                    y,
+                    &text,
+                    FONT_SMALL,
+                    colors.text,
+                );
+                y += line_height + section_spacing;
+            }
+
+            // Complication: Custom metrics, one per line
+            if is_enabled(complication_names::METRICS) && !metrics.is_empty() {
+                for (name, metric) in &metrics {
+                    canvas.draw_text(margin, y, &format!("{}:", name), FONT_SMALL, colors.dim);
+                    let text = metric.format();
+                    let color = if metric.alert {
+                        colors.highlight
+                    } else {
+                        colors.text
+                    };
+                    let text_w = canvas.text_width(&text, FONT_SMALL);
+                    canvas.draw_text(width as i32 - margin - text_w, y, &text, FONT_SMALL, color);
+                    y += line_height;
+                }
+                y += section_spacing;
+            }
+
+            // Complication: Job status, one per line
+            if is_enabled(complication_names::JOBS) && !jobs.is_empty() {
+                for (name, job) in &jobs {
+                    canvas.draw_text(margin, y, &format!("{}:", name), FONT_SMALL, colors.dim);
+                    let text = job.format(data.timestamp);
+                    let text_w = canvas.text_width(&text, FONT_SMALL);
+                    canvas.draw_text(
+                        width as i32 - margin - text_w,
+                        y,
+                        &text,
+                        FONT_SMALL,
+                        Self::job_color(job, &colors),
+                    );
+                    y += line_height;
+                }
+                y += section_spacing;
+            }
+
+            // Complication: Torrent downloads, label line then fastest
+            // torrent's progress below
+            if is_enabled(complication_names::DOWNLOADS) {
+                if let Some(ref stats) = data.torrents {
+                    let label = format!("DL: {} active", stats.downloading.len());
+                    canvas.draw_text(margin, y, &label, FONT_SMALL, colors.dim);
+                    let rates = format!(
+                        "\u{2193}{} \u{2191}{}",
+                        data.format_net_rate_compact(stats.download_rate),
+                        data.format_net_rate_compact(stats.upload_rate)
+                    );
+                    let rates_w = canvas.text_width(&rates, FONT_SMALL);
+                    canvas.draw_text(
+                        width as i32 - margin - rates_w,
+                        y,
+                        &rates,
+                        FONT_SMALL,
+                        colors.text,
+                    );
+                    y += line_height;
+                    if let Some(torrent) = stats.downloading.first() {
+                        Self::draw_progress_bar(
+                            canvas,
+                            margin,
+                            y,
+                            bar_width,
+                            tall_bar_height / 2,
+                            torrent.progress as f64,
+                            colors.bar_net_rx,
+                            colors.bar_bg,
+                        );
+                        y += (tall_bar_height / 2) as i32;
+                    }
+                    y += section_spacing;
+                }
+            }
+
+            // Complication: Media streams, count then one title per line
+            if is_enabled(complication_names::MEDIA_STREAMS) {
+                if let Some(ref streams) = data.media_streams {
+                    canvas.draw_text(margin, y, "Streams:", FONT_SMALL, colors.dim);
+                    let summary = Self::stream_summary(streams);
+                    let summary_w = canvas.text_width(&summary, FONT_SMALL);
+                    canvas.draw_text(
+                        width as i32 - margin - summary_w,
+                        y,
+                        &summary,
+                        FONT_SMALL,
+                        colors.text,
+                    );
+                    y += line_height;
+                    for stream in streams.iter().take(MAX_STREAM_LIST) {
+                        let (tag, tag_color) = if stream.transcoding {
+                            ("TC", colors.highlight)
+                        } else {
+                            ("DP", colors.dim)
+                        };
+                        let tag_w = canvas.text_width(tag, FONT_SMALL);
+                        canvas.draw_text(
+                            width as i32 - margin - tag_w,
+                            y,
+                            tag,
+                            FONT_SMALL,
+                            tag_color,
+                        );
+                        let max_w = width as i32 - margin * 2 - tag_w - 4;
+                        let title = canvas.truncate_to_width(&stream.title, FONT_SMALL, max_w);
+                        canvas.draw_text(margin, y, &title, FONT_SMALL, colors.text);
+                        y += line_height;
+                    }
+                    y += section_spacing;
+                }
+            }
+
+            // Complication: DNS blocking, label line then bar below
+            if is_enabled(complication_names::DNS_BLOCKING) {
+                if let Some(stats) = data.dns_stats {
+                    let (label, color) = if stats.enabled {
+                        (format!("DNS: {:2.0}%", stats.percent), colors.dim)
+                    } else {
+                        ("DNS: off".to_string(), colors.highlight)
+                    };
+                    canvas.draw_text(margin, y, &label, FONT_SMALL, color);
+                    let counts = format!(
+                        "{}/{}",
+                        format_count(stats.blocked),
+                        format_count(stats.queries)
+                    );
+                    let counts_w = canvas.text_width(&counts, FONT_SMALL);
+                    canvas.draw_text(
+                        width as i32 - margin - counts_w,
+                        y,
+                        &counts,
+                        FONT_SMALL,
+                        colors.text,
+                    );
+                    y += line_height;
+                    Self::draw_progress_bar(
+                        canvas,
+                        margin,
+                        y,
+                        bar_width,
+                        tall_bar_height / 2,
+                        stats.percent as f64,
+                        colors.bar_net_rx,
+                        colors.bar_bg,
+                    );
+                    y += (tall_bar_height / 2) as i32 + section_spacing;
+                }
+            }
+
+            // Complication: Storage pool health
+            if is_enabled(complication_names::STORAGE_POOLS) && !data.pools.is_empty() {
+                let pools_width = width as i32 - margin * 2;
+                Self::draw_pool_badges(canvas, margin, y, pools_width, &data.pools, &colors);
+                y += line_height + section_spacing;
+            }
+
+            // Base element: CPU label on its own line, then bar below
+            let cpu_label = format!("CPU: {:2.0}%", data.cpu_percent);
+            canvas.draw_text(margin, y, &cpu_label, FONT_SMALL, colors.dim);
+            y += line_height;
+            Self::draw_progress_bar(
+                canvas,
+                margin,
+                y,
+                bar_width,
+                tall_bar_height,
+                data.cpu_percent,
+                colors.bar_cpu,
+                colors.bar_bg,
+            );
+            y += tall_bar_height as i32 + section_spacing;
+
+            // Base element: RAM label on its own line, then bar below
+            let ram_label = format!("RAM: {:2.0}%", data.ram_percent);
+            canvas.draw_text(margin, y, &ram_label, FONT_SMALL, colors.dim);
+            y += line_height;
+            Self::draw_progress_bar(
+                canvas,
+                margin,
+                y,
+                bar_width,
+                tall_bar_height,
+                data.ram_percent,
+                colors.bar_ram,
+                colors.bar_bg,
+            );
+            y += tall_bar_height as i32 + section_spacing;
+
+            // Complication: GPU strip
+            if is_enabled(complication_names::GPU) {
+                if let Some(ref gpu) = data.gpu {
+                    Self::draw_gpu_strip(canvas, margin, y, bar_width as i32, gpu, &colors);
+                    y += line_height + section_spacing;
+                }
+            }
+
+            // Complication: Disk I/O graph
+            if is_enabled(complication_names::DISK_IO) {
+                let disk_r = data.format_disk_rate_compact(data.disk_read_rate);
+                let disk_w = data.format_disk_rate_compact(data.disk_write_rate);
+                canvas.draw_text(margin, y, "DSK:", FONT_SMALL, colors.dim);
+                // Draw R: and W: in their respective colors
+                let r_text = format!("R:{}", disk_r);
+                let w_text = format!(" W:{}", disk_w);
+                let w_text_w = canvas.text_width(&w_text, FONT_SMALL);
+                let r_text_w = canvas.text_width(&r_text, FONT_SMALL);
+                let r_x = width as i32 - margin - w_text_w - r_text_w;
+                canvas.draw_text(r_x, y, &r_text, FONT_SMALL, colors.bar_disk_read);
+                canvas.draw_text(
+                    r_x + r_text_w,
+                    y,
+                    &w_text,
+                    FONT_SMALL,
+                    colors.bar_disk_write,
+                );
+                if show_busiest_disk {
+                    let label_w = canvas.text_width("DSK: ", FONT_SMALL);
+                    Self::draw_busiest_disk(canvas, margin + label_w, y, r_x - 4, data, colors.dim);
+                }
+                y += line_height;
+                canvas.draw_dual_graph(
+                    margin,
+                    y,
+                    bar_width,
+                    GRAPH_HEIGHT,
+                    &disk_read_history,
+                    &disk_write_history,
+                    disk_scale,
+                    colors.bar_disk_read,
+                    colors.bar_disk_write,
+                    colors.bar_bg,
+                    disk_style,
+                );
+                y += GRAPH_HEIGHT as i32;
+                if let Some(ref stats) = disk_stats {
+                    Self::draw_graph_stats(
+                        canvas,
+                        margin,
+                        y + 2,
+                        bar_width as i32,
+                        stats,
+                        colors.dim,
+                    );
+                    y += canvas.line_height(FONT_TINY) + 2;
+                }
+                y += section_spacing;
+            }
+
+            // Complication: Network I/O graph
+            if is_enabled(complication_names::NETWORK) {
+                let net_rx = data.format_net_rate_compact(data.net_rx_rate);
+                let net_tx = data.format_net_rate_compact(data.net_tx_rate);
+                canvas.draw_text(margin, y, "NET:", FONT_SMALL, colors.dim);
+                // Draw ↓: and ↑: in their respective colors
+                let rx_text = format!("\u{2193}:{}", net_rx);
+                let tx_text = format!(" \u{2191}:{}", net_tx);
+                let tx_text_w = canvas.text_width(&tx_text, FONT_SMALL);
+                let rx_text_w = canvas.text_width(&rx_text, FONT_SMALL);
+                let rx_x = width as i32 - margin - tx_text_w - rx_text_w;
+                canvas.draw_text(rx_x, y, &rx_text, FONT_SMALL, colors.bar_net_rx);
+                canvas.draw_text(rx_x + rx_text_w, y, &tx_text, FONT_SMALL, colors.bar_net_tx);
+                y += line_height;
+                Self::draw_network_graph(
+                    canvas,
+                    margin,
+                    y,
+                    bar_width,
+                    data,
+                    &net_rx_history,
+                    &net_tx_history,
+                    net_scale,
+                    net_style,
+                    net_mirrored,
+                    net_peaks,
+                    &colors,
+                );
+                if let Some(ref stats) = net_stats {
+                    Self::draw_graph_stats(
+                        canvas,
+                        margin,
+                        y + GRAPH_HEIGHT as i32 + 2,
+                        bar_width as i32,
+                        stats,
+                        colors.dim,
+                    );
+                }
+            }
+        } else {
+            // Landscape layout - compact with bars on same line as labels
+            let line_height = canvas.line_height(FONT_SMALL);
+            let label_width = 70_i32; // Space for "CPU: 99%" or "RAM: 99%"
+            let bar_x = margin + label_width;
+            let bar_width = (width as i32 - bar_x - margin - 40) as u32; // Leave room for temp
+
+            // Complication: Hostname
+            y = 1;
+            if is_enabled(complication_names::HOSTNAME) {
+                canvas.draw_text(margin, y, &data.hostname, FONT_LARGE, colors.highlight);
+            }
+
+            // Complication: Time (right-aligned)
+            if is_enabled(complication_names::TIME) {
+                if time_format == time_formats::ANALOGUE {
+                    // Draw small analog clock on the right
+                    let clock_radius = 10_u32;
+                    let clock_cx = width as i32 - margin - clock_radius as i32;
+                    let clock_cy = y + clock_radius as i32 + 2;
+                    draw_mini_analog_clock(
+                        canvas,
+                        clock_cx,
+                        clock_cy,
+                        clock_radius,
+                        data.hour,
+                        data.minute,
+                        colors.highlight,
+                        colors.text,
+                    );
+                } else {
+                    let time_str = data.format_time(time_format);
+                    let time_width = canvas.text_width(&time_str, FONT_LARGE);
+                    canvas.draw_text(
+                        width as i32 - margin - time_width,
+                        y,
+                        &time_str,
+                        FONT_LARGE,
+                        colors.text,
+                    );
+                }
+            }
+            y += canvas.line_height(FONT_LARGE) + 1;
+
+            // Complication: CPU model header, squeezed to fit
+            if is_enabled(complication_names::CPU_MODEL) {
+                Self::draw_cpu_model(
+                    canvas,
+                    margin,
+                    y,
+                    width as i32 - margin * 2,
+                    data,
+                    colors.dim,
+                );
+                y += line_height + 1;
+            }
+
+            // SSH session count, right-aligned on the IP line when it fits
+            // beside the address and before the date otherwise
+            let ssh = is_enabled(complication_names::SSH_SESSIONS).then(|| {
+                let text = format!("SSH:{}", data.ssh_sessions.count);
+                let color = if data.ssh_sessions.count > 0 {
+                    colors.highlight
+                } else {
+                    colors.dim
+                };
+                (canvas.text_width(&text, FONT_SMALL), text, color)
+            });
+            let ip_text = data
+                .display_ip
+                .as_ref()
+                .filter(|_| is_enabled(complication_names::IP_ADDRESS))
+                .map(|ip| format!("IP: {}", ip));
+            let ip_width = ip_text
+                .as_ref()
+                .map(|text| canvas.text_width(text, FONT_SMALL));
+            let ssh_on_ip_line = ssh.as_ref().is_some_and(|(ssh_w, ..)| {
+                ip_width.is_none_or(|w| margin + w + 8 + ssh_w <= width as i32 - margin)
+            });
+
+            // Up: on left side
+            let mut up_end = margin;
+            if is_enabled(complication_names::UPTIME) {
+                let uptime_text = format!("Up: {}", data.format_uptime(uptime_format));
+                canvas.draw_text(margin, y, &uptime_text, FONT_SMALL, colors.dim);
+                up_end += canvas.text_width(&uptime_text, FONT_SMALL);
+            }
+
+            // Complication: Date (right-aligned), left out when the SSH
+            // count needs its place
+            let mut right_x = width as i32 - margin;
+            let ssh_on_up_line = ssh.as_ref().filter(|_| !ssh_on_ip_line);
+            if is_enabled(complication_names::DATE) {
+                if let Some(date_str) = data.format_date(date_format) {
+                    let date_width = canvas.text_width(&date_str, FONT_SMALL);
+                    let room = ssh_on_up_line.map_or(0, |(ssh_w, ..)| ssh_w + 8);
+                    if up_end + 8 + room + date_width <= right_x {
+                        right_x -= date_width;
+                        canvas.draw_text(right_x, y, &date_str, FONT_SMALL, colors.dim);
+                        right_x -= 8;
+                    }
+                }
+            }
+            if let Some((ssh_w, text, color)) = ssh_on_up_line {
+                canvas.draw_text(right_x - ssh_w, y, text, FONT_SMALL, *color);
+            }
+            y += line_height + 1;
+
+            // IP: label and address on same line, left aligned
+            if let Some(ref ip_text) = ip_text {
+                canvas.draw_text(margin, y, ip_text, FONT_SMALL, colors.dim);
+            }
+            if let Some((ssh_w, text, color)) = ssh.as_ref().filter(|_| ssh_on_ip_line) {
+                canvas.draw_text(width as i32 - margin - ssh_w, y, text, FONT_SMALL, *color);
+            }
+            if ip_text.is_some() || ssh_on_ip_line {
+                y += line_height + 2;
+            }
+
+            // CPU: label, bar, and temp all on same line
+            let cpu_label = format!("CPU: {:2.0}%", data.cpu_percent);
+            canvas.draw_text(margin, y, &cpu_label, FONT_SMALL, colors.dim);
+            Self::draw_progress_bar(
+                canvas,
+                bar_x,
+                y + 2,
+                bar_width,
+                BAR_HEIGHT,
+                data.cpu_percent,
+                colors.bar_cpu,
+                colors.bar_bg,
+            );
+            // CPU temp on same line (no label), highlighted while throttled
+            if is_enabled(complication_names::CPU_TEMP) {
+                if let Some(temp) = data.cpu_temp {
+                    let throttled = is_enabled(complication_names::THROTTLING)
+                        && data.cpu_throttle.is_some_and(|t| t.throttled);
+                    let temp_val = format!("{:.0}°C", temp);
+                    let temp_w = canvas.text_width(&temp_val, FONT_SMALL);
+                    canvas.draw_text(
+                        width as i32 - margin - temp_w,
+                        y,
+                        &temp_val,
+                        FONT_SMALL,
+                        if throttled { ALERT_COLOR } else { colors.text },
+                    );
+                }
+            }
+            y += line_height + 2;
+
+            // RAM: label and bar on same line
+            let ram_label = format!("RAM: {:2.0}%", data.ram_percent);
+            canvas.draw_text(margin, y, &ram_label, FONT_SMALL, colors.dim);
+            Self::draw_progress_bar(
+                canvas,
+                bar_x,
+                y + 2,
+                bar_width,
+                BAR_HEIGHT,
+                data.ram_percent,
+                colors.bar_ram,
+                colors.bar_bg,
+            );
+            // Pending updates on same line (no label)
+            if is_enabled(complication_names::UPDATES) {
+                if let Some(updates) = data.pending_updates {
+                    let text = format!("\u{2191}{}", updates.total);
+                    let color = if updates.security > 0 {
+                        colors.highlight
+                    } else {
+                        colors.text
+                    };
+                    let text_w = canvas.text_width(&text, FONT_SMALL);
+                    canvas.draw_text(width as i32 - margin - text_w, y, &text, FONT_SMALL, color);
+                }
+            }
+            y += line_height + 8;
+
+            // DSK: label line, then graph on next line
+            if is_enabled(complication_names::DISK_IO) {
+                let disk_r = data.format_disk_rate_compact(data.disk_read_rate);
+                let disk_w = data.format_disk_rate_compact(data.disk_write_rate);
+                canvas.draw_text(margin, y, "DSK:", FONT_SMALL, colors.dim);
+                // Draw R: and W: in their respective colors
+                let r_text = format!("R:{}", disk_r);
+                let w_text = format!(" W:{}", disk_w);
+                let w_text_w = canvas.text_width(&w_text, FONT_SMALL);
+                let r_text_w = canvas.text_width(&r_text, FONT_SMALL);
+                let r_x = width as i32 - margin - w_text_w - r_text_w;
+                canvas.draw_text(r_x, y, &r_text, FONT_SMALL, colors.bar_disk_read);
+                canvas.draw_text(
+                    r_x + r_text_w,
+                    y,
+                    &w_text,
+                    FONT_SMALL,
+                    colors.bar_disk_write,
+                );
+                let label_w = canvas.text_width("DSK: ", FONT_SMALL);
+                if show_busiest_disk {
+                    Self::draw_busiest_disk(canvas, margin + label_w, y, r_x - 4, data, colors.dim);
+                } else if let Some(ref stats) = disk_stats {
+                    Self::draw_graph_stats_inline(
+                        canvas,
+                        margin + label_w,
+                        y,
+                        r_x - 4,
+                        stats,
+                        colors.dim,
+                    );
+                }
+                y += line_height + 4;
+                canvas.draw_dual_graph(
+                    margin,
+                    y,
+                    width - (margin * 2) as u32,
+                    GRAPH_HEIGHT,
+                    &disk_read_history,
+                    &disk_write_history,
+                    disk_scale,
+                    colors.bar_disk_read,
+                    colors.bar_disk_write,
+                    colors.bar_bg,
+                    disk_style,
+                );
+                y += GRAPH_HEIGHT as i32 + 4;
+            }
+
+            // NET: label line, then graph on next line
+            if is_enabled(complication_names::NETWORK) {
+                let net_rx = data.format_net_rate_compact(data.net_rx_rate);
+                let net_tx = data.format_net_rate_compact(data.net_tx_rate);
+                canvas.draw_text(margin, y, "NET:", FONT_SMALL, colors.dim);
+                // Draw ↓: and ↑: in their respective colors
+                let rx_text = format!("\u{2193}:{}", net_rx);
+                let tx_text = format!(" \u{2191}:{}", net_tx);
+                let tx_text_w = canvas.text_width(&tx_text, FONT_SMALL);
+                let rx_text_w = canvas.text_width(&rx_text, FONT_SMALL);
+                let rx_x = width as i32 - margin - tx_text_w - rx_text_w;
+                canvas.draw_text(rx_x, y, &rx_text, FONT_SMALL, colors.bar_net_rx);
+                canvas.draw_text(rx_x + rx_text_w, y, &tx_text, FONT_SMALL, colors.bar_net_tx);
+                // Data usage between the label and the rates, if it fits
+                // or else the statistics
+                let total_x = margin + canvas.text_width("NET: ", FONT_SMALL);
+                if is_enabled(complication_names::NETWORK_TOTALS) {
+                    let total = data.format_bytes(net_total);
+                    if total_x + canvas.text_width(&total, FONT_SMALL) <= rx_x - 4 {
+                        canvas.draw_text(total_x, y, &total, FONT_SMALL, colors.dim);
+                    }
+                } else if let Some(ref stats) = net_stats {
+                    Self::draw_graph_stats_inline(canvas, total_x, y, rx_x - 4, stats, colors.dim);
+                }
+                y += line_height + 4;
+                Self::draw_network_graph(
+                    canvas,
+                    margin,
+                    y,
+                    width - (margin * 2) as u32,
+                    data,
+                    &net_rx_history,
+                    &net_tx_history,
+                    net_scale,
+                    net_style,
+                    net_mirrored,
+                    net_peaks,
+                    &colors,
+                );
+                y += GRAPH_HEIGHT as i32 + 4;
+            }
+
+            // GPU strip on one line at the bottom, if there is room
+            if is_enabled(complication_names::GPU) && y + line_height <= height as i32 {
+                if let Some(ref gpu) = data.gpu {
+                    let strip_width = width as i32 - margin * 2;
+                    Self::draw_gpu_strip(canvas, margin, y, strip_width, gpu, &colors);
+                    y += line_height;
+                }
+            }
+
+            // Storage pools on one line at the bottom, if there is room
+            if is_enabled(complication_names::STORAGE_POOLS)
+                && !data.pools.is_empty()
+                && y + line_height <= height as i32
+            {
+                let pools_width = width as i32 - margin * 2;
+                Self::draw_pool_badges(canvas, margin, y, pools_width, &data.pools, &colors);
+                y += line_height;
+            }
+
+            // Torrent downloads on one line at the bottom, if there is room
+            if is_enabled(complication_names::DOWNLOADS) && y + line_height <= height as i32 {
+                if let Some(ref stats) = data.torrents {
+                    canvas.draw_text(margin, y, "DL:", FONT_SMALL, colors.dim);
+                    let text = format!(
+                        "{} active \u{2193}{} \u{2191}{}",
+                        stats.downloading.len(),
+                        data.format_net_rate_compact(stats.download_rate),
+                        data.format_net_rate_compact(stats.upload_rate)
+                    );
+                    let text_x = margin + canvas.text_width("DL: ", FONT_SMALL);
+                    canvas.draw_text(text_x, y, &text, FONT_SMALL, colors.text);
+                    y += line_height;
+                }
+            }
+
+            // Media streams on one line at the bottom, if there is room
+            if is_enabled(complication_names::MEDIA_STREAMS) && y + line_height <= height as i32 {
+                if let Some(ref streams) = data.media_streams {
+                    canvas.draw_text(margin, y, "Streams:", FONT_SMALL, colors.dim);
+                    let mut text = Self::stream_summary(streams);
+                    if let Some(stream) = streams.first() {
+                        text.push_str(&format!(" {}", stream.title));
+                    }
+                    let text_x = margin + canvas.text_width("Streams: ", FONT_SMALL);
+                    let text =
+                        canvas.truncate_to_width(&text, FONT_SMALL, width as i32 - margin - text_x);
+                    canvas.draw_text(text_x, y, &text, FONT_SMALL, colors.text);
+                    y += line_height;
+                }
+            }
+
+            // DNS blocking on one line at the bottom, if there is room
+            if is_enabled(complication_names::DNS_BLOCKING) && y + line_height <= height as i32 {
+                if let Some(stats) = data.dns_stats {
+                    canvas.draw_text(margin, y, "DNS:", FONT_SMALL, colors.dim);
+                    let text = if stats.enabled {
+                        format!(
+                            "{:.1}% blocked ({}/{})",
+                            stats.percent,
+                            format_count(stats.blocked),
+                            format_count(stats.queries)
+                        )
+                    } else {
+                        "blocking off".to_string()
+                    };
+                    let text_x = margin + canvas.text_width("DNS: ", FONT_SMALL);
+                    let color = if stats.enabled {
+                        colors.text
+                    } else {
+                        colors.highlight
+                    };
+                    canvas.draw_text(text_x, y, &text, FONT_SMALL, color);
+                    y += line_height;
+                }
+            }
+
+            // Custom metrics on one line at the bottom, if there is room
+            if is_enabled(complication_names::METRICS)
+                && !metrics.is_empty()
+                && y + line_height <= height as i32
+            {
+                let mut x = margin;
+                for (name, metric) in &metrics {
+                    let label = format!("{} ", name);
+                    let value = metric.format();
+                    let label_w = canvas.text_width(&label, FONT_SMALL);
+                    let value_w = canvas.text_width(&value, FONT_SMALL);
+                    if x + label_w + value_w > width as i32 - margin {
+                        break;
+                    }
+                    canvas.draw_text(x, y, &label, FONT_SMALL, colors.dim);
+                    let color = if metric.alert {
+                        colors.highlight
+                    } else {
+                        colors.text
+                    };
+                    canvas.draw_text(x + label_w, y, &value, FONT_SMALL, color);
+                    x += label_w + value_w + 10;
+                }
+                y += line_height;
+            }
+
+            // Job status on one line at the bottom, if there is room
+            if is_enabled(complication_names::JOBS)
+                && !jobs.is_empty()
+                && y + line_height <= height as i32
+            {
+                let mut x = margin;
+                for (name, job) in &jobs {
+                    let label = format!("{} ", name);
+                    let value = job.format(data.timestamp);
+                    let label_w = canvas.text_width(&label, FONT_SMALL);
+                    let value_w = canvas.text_width(&value, FONT_SMALL);
+                    if x + label_w + value_w > width as i32 - margin {
+                        break;
+                    }
+                    canvas.draw_text(x, y, &label, FONT_SMALL, colors.dim);
+                    let color = Self::job_color(job, &colors);
+                    canvas.draw_text(x + label_w, y, &value, FONT_SMALL, color);
+                    x += label_w + value_w + 10;
+                }
+            }
+        }
+        // Suppress unused variable warning when all complications are disabled
+        let _ = y;
+    }
+}
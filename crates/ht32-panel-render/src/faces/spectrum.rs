@@ -0,0 +1,119 @@
+//! Spectrum face that turns the panel into a music visualizer.
+//!
+//! Draws the audio spectrum bands as vertical bars with a level meter along
+//! the bottom. Requires audio capture to be enabled.
+
+use super::{
+    complication_names, complication_options, complications, time_formats, Complication,
+    EnabledComplications, Face, Theme,
+};
+use crate::data::SystemData;
+use crate::rendering::Canvas;
+
+/// Font size for the time and status text.
+const FONT_SMALL: f32 = 12.0;
+
+/// Height of the level meter in pixels.
+const METER_HEIGHT: u32 = 4;
+
+/// Gap between bars in pixels.
+const BAR_GAP: i32 = 2;
+
+/// A music spectrum visualizer face.
+pub struct SpectrumFace;
+
+impl SpectrumFace {
+    /// Creates a new spectrum face.
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for SpectrumFace {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Face for SpectrumFace {
+    fn name(&self) -> &str {
+        "spectrum"
+    }
+
+    fn available_complications(&self) -> Vec<Complication> {
+        vec![complications::time(false)]
+    }
+
+    fn required_sensors(&self) -> &'static [&'static str] {
+        &[]
+    }
+
+    fn render(
+        &self,
+        canvas: &mut Canvas,
+        data: &SystemData,
+        theme: &Theme,
+        complications: &EnabledComplications,
+    ) {
+        let (width, height) = canvas.dimensions();
+        let margin = 6;
+        let mut top = margin;
+
+        if complications.is_enabled(self.name(), complication_names::TIME, false) {
+            let format = complications
+                .get_option(
+                    self.name(),
+                    complication_names::TIME,
+                    complication_options::TIME_FORMAT,
+                )
+                .map(|s| s.as_str())
+                .filter(|f| *f != time_formats::ANALOGUE)
+                .unwrap_or(time_formats::DIGITAL_24H);
+            canvas.draw_text(
+                margin,
+                top,
+                &data.format_time(format),
+                FONT_SMALL,
+                theme.text,
+            );
+            top += canvas.line_height(FONT_SMALL) + 2;
+        }
+
+        let Some(audio) = data.audio.as_ref().filter(|a| !a.bands.is_empty()) else {
+            let text = "No audio";
+            let x = (width as i32 - canvas.text_width(text, FONT_SMALL)) / 2;
+            let y = (height as i32 - canvas.line_height(FONT_SMALL)) / 2;
+            canvas.draw_text(x, y, text, FONT_SMALL, theme.text);
+            return;
+        };
+
+        // Bars fill the space between the top row and the level meter
+        let meter_y = height as i32 - margin - METER_HEIGHT as i32;
+        let bottom = meter_y - 4;
+        let max_bar = (bottom - top).max(1);
+        let count = audio.bands.len() as i32;
+        let bar_width = ((width as i32 - margin * 2 - BAR_GAP * (count - 1)) / count).max(1);
+        let used = bar_width * count + BAR_GAP * (count - 1);
+        let mut x = (width as i32 - used) / 2;
+
+        for &band in &audio.bands {
+            let bar = (band.clamp(0.0, 1.0) * max_bar as f32) as i32;
+            if bar > 0 {
+                // Loud bands switch to the accent color at the top
+                let color = if band > 0.8 {
+                    theme.secondary
+                } else {
+                    theme.primary
+                };
+                canvas.fill_rect(x, bottom - bar, bar_width as u32, bar as u32, color);
+            }
+            x += bar_width + BAR_GAP;
+        }
+
+        let meter_width = width - margin as u32 * 2;
+        let level_width = (audio.level.clamp(0.0, 1.0) * meter_width as f32) as u32;
+        if level_width > 0 {
+            canvas.fill_rect(margin, meter_y, level_width, METER_HEIGHT, theme.text);
+        }
+    }
+}
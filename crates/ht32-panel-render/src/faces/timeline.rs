@@ -0,0 +1,200 @@
+//! Timeline face showing recent daemon events.
+//!
+//! Lists alerts, face changes, LCD disconnects and reconnects and journal
+//! warnings from the event bus, newest at the top, each with its age and a
+//! dot colored by kind. Older events scroll off the bottom as new ones
+//! arrive.
+
+use super::{
+    complication_names, complication_options, complications, time_formats, Complication,
+    EnabledComplications, Face, Theme,
+};
+use crate::data::{format_age, EventKind, SystemData, TimelineEvent};
+use crate::rendering::Canvas;
+
+/// Font size for the header.
+const FONT_HEADER: f32 = 14.0;
+
+/// Font size for event rows.
+const FONT_ROW: f32 = 12.0;
+
+/// Radius of the event dot in pixels.
+const DOT_RADIUS: u32 = 4;
+
+/// Dot color for alerts.
+const ALERT_COLOR: u32 = 0xE04040;
+
+/// Dot color for the LCD disconnecting.
+const DISCONNECTED_COLOR: u32 = 0xE09040;
+
+/// Dot color for the LCD reconnecting.
+const RECONNECTED_COLOR: u32 = 0x40C060;
+
+/// Dot color for journal warnings.
+const JOURNAL_COLOR: u32 = 0xE0C040;
+
+/// Dim a color by mixing it toward the background.
+fn dim_color(color: u32, background: u32, factor: f32) -> u32 {
+    let r1 = ((color >> 16) & 0xFF) as f32;
+    let g1 = ((color >> 8) & 0xFF) as f32;
+    let b1 = (color & 0xFF) as f32;
+    let r2 = ((background >> 16) & 0xFF) as f32;
+    let g2 = ((background >> 8) & 0xFF) as f32;
+    let b2 = (background & 0xFF) as f32;
+
+    let r = (r1 * factor + r2 * (1.0 - factor)) as u32;
+    let g = (g1 * factor + g2 * (1.0 - factor)) as u32;
+    let b = (b1 * factor + b2 * (1.0 - factor)) as u32;
+
+    (r << 16) | (g << 8) | b
+}
+
+/// A recent events face.
+pub struct TimelineFace;
+
+impl TimelineFace {
+    /// Creates a new timeline face.
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Returns the dot color for an event kind.
+    fn kind_color(kind: EventKind, theme: &Theme) -> u32 {
+        match kind {
+            EventKind::Alert => ALERT_COLOR,
+            EventKind::FaceChanged => theme.primary,
+            EventKind::DeviceDisconnected => DISCONNECTED_COLOR,
+            EventKind::DeviceReconnected => RECONNECTED_COLOR,
+            EventKind::Journal => JOURNAL_COLOR,
+        }
+    }
+
+    /// Draws one event row within `width` pixels, with the age right
+    /// aligned in a column `age_w` pixels wide.
+    #[allow(clippy::too_many_arguments)]
+    fn draw_event(
+        canvas: &mut Canvas,
+        x: i32,
+        y: i32,
+        width: i32,
+        age_w: i32,
+        event: &TimelineEvent,
+        now: u64,
+        theme: &Theme,
+    ) {
+        let dim = dim_color(theme.text, theme.background, 0.5);
+        let age = format_age(now.saturating_sub(event.timestamp));
+        let text_w = canvas.text_width(&age, FONT_ROW);
+        canvas.draw_text(x + age_w - text_w, y, &age, FONT_ROW, dim);
+
+        let line_height = canvas.line_height(FONT_ROW);
+        let radius = DOT_RADIUS as i32;
+        let dot_x = x + age_w + 6 + radius;
+        canvas.fill_circle(
+            dot_x,
+            y + line_height / 2,
+            DOT_RADIUS,
+            Self::kind_color(event.kind, theme),
+        );
+
+        // Truncate the message to the space left of the row
+        let message_x = dot_x + radius + 6;
+        let max_message_w = x + width - message_x;
+        let message = canvas.truncate_to_width(&event.message, FONT_ROW, max_message_w);
+        canvas.draw_text(message_x, y, &message, FONT_ROW, theme.text);
+    }
+}
+
+impl Default for TimelineFace {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Face for TimelineFace {
+    fn name(&self) -> &str {
+        "timeline"
+    }
+
+    fn available_complications(&self) -> Vec<Complication> {
+        vec![complications::time(true)]
+    }
+
+    fn required_sensors(&self) -> &'static [&'static str] {
+        &[]
+    }
+
+    fn render(
+        &self,
+        canvas: &mut Canvas,
+        data: &SystemData,
+        theme: &Theme,
+        complications: &EnabledComplications,
+    ) {
+        let (width, height) = canvas.dimensions();
+        let margin = 6;
+        let mut y = margin;
+
+        // Header: title with the time on the right
+        canvas.draw_text(margin, y, "Timeline", FONT_HEADER, theme.primary);
+        if complications.is_enabled(self.name(), complication_names::TIME, true) {
+            let format = complications
+                .get_option(
+                    self.name(),
+                    complication_names::TIME,
+                    complication_options::TIME_FORMAT,
+                )
+                .map(|s| s.as_str())
+                .filter(|f| *f != time_formats::ANALOGUE)
+                .unwrap_or(time_formats::DIGITAL_24H);
+            let time = data.format_time(format);
+            let time_w = canvas.text_width(&time, FONT_HEADER);
+            canvas.draw_text(
+                width as i32 - margin - time_w,
+                y,
+                &time,
+                FONT_HEADER,
+                theme.text,
+            );
+        }
+        y += canvas.line_height(FONT_HEADER) + 6;
+
+        let dim = dim_color(theme.text, theme.background, 0.5);
+        if data.events.is_empty() {
+            canvas.draw_text(margin, y, "No events yet", FONT_ROW, dim);
+            return;
+        }
+
+        let row_height = canvas.line_height(FONT_ROW) + 3;
+        let rows = ((height as i32 - y - margin) / row_height).max(1) as usize;
+        let age_w = canvas.text_width("59m", FONT_ROW);
+
+        // A line through the dots joins the events into a timeline
+        let shown = data.events.len().min(rows) as i32;
+        if shown > 1 {
+            let line_x = margin + age_w + 6 + DOT_RADIUS as i32;
+            let center = canvas.line_height(FONT_ROW) / 2;
+            canvas.draw_line(
+                line_x,
+                y + center,
+                line_x,
+                y + (shown - 1) * row_height + center,
+                1.0,
+                dim,
+            );
+        }
+
+        for (i, event) in data.events.iter().take(rows).enumerate() {
+            Self::draw_event(
+                canvas,
+                margin,
+                y + i as i32 * row_height,
+                width as i32 - margin * 2,
+                age_w,
+                event,
+                data.timestamp,
+                theme,
+            );
+        }
+    }
+}
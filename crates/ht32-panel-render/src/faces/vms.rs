@@ -0,0 +1,222 @@
+//! VMs face listing libvirt virtual machines.
+//!
+//! Shows each defined VM with a state dot and, for running VMs, a CPU usage
+//! bar. Requires `[sensors.vms]` to be enabled.
+
+use super::{
+    complication_names, complication_options, complications, time_formats, Complication,
+    EnabledComplications, Face, Theme,
+};
+use crate::data::{SystemData, VmState, VmStatus};
+use crate::rendering::Canvas;
+
+/// Font size for the header.
+const FONT_HEADER: f32 = 14.0;
+
+/// Font size for VM rows.
+const FONT_ROW: f32 = 12.0;
+
+/// Radius of the state dot in pixels.
+const DOT_RADIUS: u32 = 4;
+
+/// Width of the CPU bar in pixels.
+const CPU_BAR_WIDTH: u32 = 48;
+
+/// Dot color for crashed VMs.
+const CRASHED_COLOR: u32 = 0xE04040;
+
+/// Dim a color by mixing it toward the background.
+fn dim_color(color: u32, background: u32, factor: f32) -> u32 {
+    let r1 = ((color >> 16) & 0xFF) as f32;
+    let g1 = ((color >> 8) & 0xFF) as f32;
+    let b1 = (color & 0xFF) as f32;
+    let r2 = ((background >> 16) & 0xFF) as f32;
+    let g2 = ((background >> 8) & 0xFF) as f32;
+    let b2 = (background & 0xFF) as f32;
+
+    let r = (r1 * factor + r2 * (1.0 - factor)) as u32;
+    let g = (g1 * factor + g2 * (1.0 - factor)) as u32;
+    let b = (b1 * factor + b2 * (1.0 - factor)) as u32;
+
+    (r << 16) | (g << 8) | b
+}
+
+/// A libvirt VM status face.
+pub struct VmsFace;
+
+impl VmsFace {
+    /// Creates a new VMs face.
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Draws one VM row within `width` pixels.
+    fn draw_vm(
+        canvas: &mut Canvas,
+        x: i32,
+        y: i32,
+        width: i32,
+        vm: &VmStatus,
+        show_cpu: bool,
+        theme: &Theme,
+    ) {
+        let dim = dim_color(theme.text, theme.background, 0.5);
+        let dot_color = match vm.state {
+            VmState::Running => theme.primary,
+            VmState::Paused | VmState::Other => theme.secondary,
+            VmState::Crashed => CRASHED_COLOR,
+            VmState::Stopped => dim,
+        };
+        let line_height = canvas.line_height(FONT_ROW);
+        let radius = DOT_RADIUS as i32;
+        canvas.fill_circle(x + radius, y + line_height / 2, DOT_RADIUS, dot_color);
+
+        // Right side: CPU bar and percentage when running, else the state
+        let right = x + width;
+        let detail_x = if vm.state == VmState::Running && show_cpu {
+            let percent = vm.cpu_percent.unwrap_or(0.0);
+            let text = format!("{:3.0}%", percent);
+            let text_w = canvas.text_width(&text, FONT_ROW);
+            canvas.draw_text(right - text_w, y, &text, FONT_ROW, theme.text);
+
+            let bar_x = right - text_w - 4 - CPU_BAR_WIDTH as i32;
+            let bar_h = (line_height / 2).max(4) as u32;
+            let bar_y = y + (line_height - bar_h as i32) / 2;
+            let bg = dim_color(theme.text, theme.background, 0.15);
+            canvas.fill_rect(bar_x, bar_y, CPU_BAR_WIDTH, bar_h, bg);
+            let fill = (CPU_BAR_WIDTH as f32 * percent.clamp(0.0, 100.0) / 100.0) as u32;
+            if fill > 0 {
+                canvas.fill_rect(bar_x, bar_y, fill, bar_h, theme.primary);
+            }
+            bar_x
+        } else {
+            let text = match vm.state {
+                VmState::Running => "running",
+                VmState::Paused => "paused",
+                VmState::Stopped => "off",
+                VmState::Crashed => "crashed",
+                VmState::Other => "busy",
+            };
+            let text_w = canvas.text_width(text, FONT_ROW);
+            canvas.draw_text(right - text_w, y, text, FONT_ROW, dim);
+            right - text_w
+        };
+
+        // Truncate the name to the space left of the details
+        let name_x = x + radius * 2 + 5;
+        let max_name_w = detail_x - name_x - 4;
+        let name = canvas.truncate_to_width(&vm.name, FONT_ROW, max_name_w);
+        let color = if vm.state == VmState::Stopped {
+            dim
+        } else {
+            theme.text
+        };
+        canvas.draw_text(name_x, y, &name, FONT_ROW, color);
+    }
+}
+
+impl Default for VmsFace {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Face for VmsFace {
+    fn name(&self) -> &str {
+        "vms"
+    }
+
+    fn available_complications(&self) -> Vec<Complication> {
+        vec![
+            complications::time(true),
+            complications::vm_cpu(true),
+            complications::stopped_vms(true),
+        ]
+    }
+
+    fn required_sensors(&self) -> &'static [&'static str] {
+        &[]
+    }
+
+    fn render(
+        &self,
+        canvas: &mut Canvas,
+        data: &SystemData,
+        theme: &Theme,
+        complications: &EnabledComplications,
+    ) {
+        let (width, height) = canvas.dimensions();
+        let margin = 6;
+        let mut y = margin;
+        let show_cpu = complications.is_enabled(self.name(), complication_names::VM_CPU, true);
+        let show_stopped =
+            complications.is_enabled(self.name(), complication_names::STOPPED_VMS, true);
+
+        // Header: running count, with the time on the right
+        let running = data
+            .vms
+            .iter()
+            .filter(|vm| vm.state == VmState::Running)
+            .count();
+        let title = format!("VMs {}/{}", running, data.vms.len());
+        canvas.draw_text(margin, y, &title, FONT_HEADER, theme.primary);
+        if complications.is_enabled(self.name(), complication_names::TIME, true) {
+            let format = complications
+                .get_option(
+                    self.name(),
+                    complication_names::TIME,
+                    complication_options::TIME_FORMAT,
+                )
+                .map(|s| s.as_str())
+                .filter(|f| *f != time_formats::ANALOGUE)
+                .unwrap_or(time_formats::DIGITAL_24H);
+            let time = data.format_time(format);
+            let time_w = canvas.text_width(&time, FONT_HEADER);
+            canvas.draw_text(
+                width as i32 - margin - time_w,
+                y,
+                &time,
+                FONT_HEADER,
+                theme.text,
+            );
+        }
+        y += canvas.line_height(FONT_HEADER) + 6;
+
+        // Running VMs first, then the rest by name
+        let mut vms: Vec<&VmStatus> = data
+            .vms
+            .iter()
+            .filter(|vm| show_stopped || vm.state != VmState::Stopped)
+            .collect();
+        vms.sort_by_key(|vm| vm.state != VmState::Running);
+
+        if vms.is_empty() {
+            let dim = dim_color(theme.text, theme.background, 0.5);
+            canvas.draw_text(margin, y, "No VMs", FONT_ROW, dim);
+            return;
+        }
+
+        // Fill columns top to bottom, adding a column when rows run out
+        let row_height = canvas.line_height(FONT_ROW) + 3;
+        let rows = ((height as i32 - y - margin) / row_height).max(1) as usize;
+        let columns = vms.len().div_ceil(rows).clamp(1, 2);
+        let gap = 12;
+        let column_width =
+            (width as i32 - margin * 2 - gap * (columns as i32 - 1)) / columns as i32;
+
+        for (i, vm) in vms.iter().take(rows * columns).enumerate() {
+            let column = (i / rows) as i32;
+            let row = (i % rows) as i32;
+            let x = margin + column * (column_width + gap);
+            Self::draw_vm(
+                canvas,
+                x,
+                y + row * row_height,
+                column_width,
+                vm,
+                show_cpu,
+                theme,
+            );
+        }
+    }
+}
@@ -0,0 +1,356 @@
+//! Fixed sensor data for golden-image tests, benchmarks and previews.
+//!
+//! [`system_data`] covers everything the faces can show. The profiles below
+//! start from it and push the readings to the edges a face has to cope
+//! with: an idle machine that just booted and has no history yet, a busy one
+//! running hot, a saturated network link, and a system without a
+//! temperature sensor. [`profiles`] lists them all by name.
+
+use std::collections::{BTreeMap, VecDeque};
+
+use crate::data::*;
+
+/// Returns a repeatable history of `len` values between `low` and `high`.
+fn history(len: usize, low: f64, high: f64, phase: f64) -> VecDeque<f64> {
+    (0..len)
+        .map(|i| {
+            let t = i as f64 / 6.0 + phase;
+            low + (high - low) * (0.5 + 0.35 * t.sin() + 0.15 * (t * 2.7).cos())
+        })
+        .collect()
+}
+
+/// Returns sensor data covering everything the faces can show.
+pub fn system_data() -> SystemData {
+    SystemData {
+        hostname: "panel-host".to_string(),
+        time: "14:32".to_string(),
+        hour: 14,
+        minute: 32,
+        day: 16,
+        month: 10,
+        year: 2026,
+        day_of_week: 5,
+        timestamp: 1792161120,
+        uptime: "3d 4h".to_string(),
+        uptime_secs: 274_000,
+        boot_time: "Oct 13 10:25".to_string(),
+        cpu_percent: 42.5,
+        cpu_history: history(HISTORY_SIZE, 15.0, 60.0, 0.9),
+        cpu_temp: Some(58.0),
+        temp_history: history(TEMP_HISTORY_SIZE, 45.0, 72.0, 0.7),
+        cpu_throttle: Some(ThrottleStatus {
+            throttled: false,
+            clock_percent: Some(96.0),
+        }),
+        ram_percent: 63.2,
+        disk_read_rate: 12_500_000.0,
+        disk_write_rate: 3_400_000.0,
+        disk_history: history(60, 0.0, 16_000_000.0, 0.0),
+        disk_read_history: history(60, 0.0, 12_000_000.0, 0.5),
+        disk_write_history: history(60, 0.0, 4_000_000.0, 1.5),
+        disk_devices: vec![DiskDeviceRate {
+            device: "nvme0n1".to_string(),
+            read_rate: 12_500_000.0,
+            write_rate: 3_400_000.0,
+        }],
+        net_totals: NetworkTotals {
+            day_rx: 4_200_000_000,
+            day_tx: 600_000_000,
+            month_rx: 96_000_000_000,
+            month_tx: 14_000_000_000,
+        },
+        net_interface: "eth0".to_string(),
+        net_rx_rate: 2_300_000.0,
+        net_tx_rate: 410_000.0,
+        net_history: history(60, 0.0, 2_800_000.0, 1.0),
+        net_rx_history: history(60, 0.0, 2_400_000.0, 2.0),
+        net_tx_history: history(60, 0.0, 500_000.0, 3.0),
+        display_ip: Some("192.168.1.20".to_string()),
+        ip_addresses: vec!["192.168.1.20".to_string(), "2001:db8::20".to_string()],
+        pending_updates: Some(PendingUpdates {
+            total: 12,
+            security: 2,
+        }),
+        ssh_sessions: SshSessions {
+            count: 1,
+            last_source: Some("192.168.1.5".to_string()),
+        },
+        process_counts: ProcessCounts {
+            processes: 312,
+            threads: 1480,
+            zombies: 0,
+        },
+        top_app: Some(AppUsage {
+            name: "cargo".to_string(),
+            cpu_percent: 18.0,
+        }),
+        journal_errors: Some(JournalErrors {
+            count: 2,
+            latest: Some("smartd: Device /dev/sda has 8 bad sectors".to_string()),
+        }),
+        os_info: OsInfo {
+            distro: "NixOS 26.05".to_string(),
+            kernel: "6.12.8".to_string(),
+            arch: "x86_64".to_string(),
+        },
+        cpu_info: CpuInfo {
+            model: "AMD Ryzen 7 5800U".to_string(),
+            cores: 8,
+            threads: 16,
+        },
+        audio: Some(AudioLevels {
+            level: 0.6,
+            bands: (0..32).map(|i| 1.0 - i as f32 / 40.0).collect(),
+            beats: 42,
+        }),
+        hosts: vec![
+            HostStatus {
+                name: "router".to_string(),
+                address: "192.168.1.1".to_string(),
+                up: Some(true),
+                latency_ms: Some(0.8),
+            },
+            HostStatus {
+                name: "nas".to_string(),
+                address: "192.168.1.10".to_string(),
+                up: Some(false),
+                latency_ms: None,
+            },
+        ],
+        metrics: BTreeMap::from([("load".to_string(), Metric::new(1.25, "", None, None))]),
+        dns_stats: Some(DnsStats::new(24_000, 3_100, true)),
+        gpu: Some(GpuStats {
+            name: "Radeon Vega 8".to_string(),
+            utilization: Some(35.0),
+            temperature: Some(52.0),
+            ..Default::default()
+        }),
+        vms: vec![
+            VmStatus {
+                name: "homeassistant".to_string(),
+                state: VmState::Running,
+                cpu_percent: Some(4.5),
+            },
+            VmStatus {
+                name: "windows".to_string(),
+                state: VmState::Stopped,
+                cpu_percent: None,
+            },
+        ],
+        pools: vec![PoolStatus {
+            name: "tank".to_string(),
+            kind: PoolKind::Zfs,
+            state: "ONLINE".to_string(),
+            degraded: false,
+            scrub_progress: None,
+        }],
+        printer: Some(PrinterStatus {
+            state: "printing".to_string(),
+            active: true,
+            file: Some("bracket.gcode".to_string()),
+            progress: Some(37.0),
+            elapsed: Some(1_800),
+            remaining: Some(3_100),
+            nozzle: Some(HeaterTemp {
+                actual: 214.0,
+                target: 215.0,
+            }),
+            bed: Some(HeaterTemp {
+                actual: 60.0,
+                target: 60.0,
+            }),
+        }),
+        torrents: Some(TorrentStats {
+            download_rate: 5_200_000.0,
+            upload_rate: 240_000.0,
+            downloading: vec![Torrent {
+                name: "debian-13.iso".to_string(),
+                progress: 64.0,
+                download_rate: 5_200_000.0,
+                upload_rate: 240_000.0,
+                eta: Some(95),
+            }],
+            total: 3,
+        }),
+        media_streams: Some(vec![MediaStream {
+            title: "Big Buck Bunny".to_string(),
+            user: "alice".to_string(),
+            transcoding: false,
+        }]),
+        events: vec![
+            TimelineEvent {
+                timestamp: 1792161060,
+                kind: EventKind::Alert,
+                message: "Storage pool tank is DEGRADED".to_string(),
+            },
+            TimelineEvent {
+                timestamp: 1792160400,
+                kind: EventKind::Journal,
+                message: "smartd: Device /dev/sda, 8 Currently unreadable sectors".to_string(),
+            },
+            TimelineEvent {
+                timestamp: 1792158000,
+                kind: EventKind::DeviceReconnected,
+                message: "LCD is responding again".to_string(),
+            },
+            TimelineEvent {
+                timestamp: 1792157940,
+                kind: EventKind::DeviceDisconnected,
+                message: "LCD stopped responding: USB write failed".to_string(),
+            },
+            TimelineEvent {
+                timestamp: 1792150000,
+                kind: EventKind::FaceChanged,
+                message: "Face changed to timeline".to_string(),
+            },
+        ],
+        ..Default::default()
+    }
+}
+
+/// Returns a freshly booted, quiet machine: low load and empty histories.
+pub fn idle() -> SystemData {
+    system_data()
+        .to_builder()
+        .uptime(180)
+        .cpu_percent(1.5)
+        .cpu_temp(Some(38.0))
+        .cpu_throttle(Some(ThrottleStatus {
+            throttled: false,
+            clock_percent: Some(35.0),
+        }))
+        .ram_percent(12.0)
+        .disk_rates(0.0, 0.0)
+        .net_rates(180.0, 60.0)
+        .empty_histories()
+        .with(|data| {
+            data.disk_devices.clear();
+            data.top_app = None;
+            data.journal_errors = None;
+            data.ssh_sessions = SshSessions::default();
+            data.audio = None;
+            data.printer = None;
+            data.torrents = None;
+            data.media_streams = Some(Vec::new());
+            data.events.clear();
+        })
+        .build()
+}
+
+/// Returns a machine under full load: maxed CPU and RAM, a throttling CPU
+/// and heavy disk traffic.
+pub fn busy() -> SystemData {
+    system_data()
+        .to_builder()
+        .cpu_percent(100.0)
+        .cpu_temp(Some(97.0))
+        .temp_history(history(TEMP_HISTORY_SIZE, 85.0, 98.0, 0.3))
+        .cpu_throttle(Some(ThrottleStatus {
+            throttled: true,
+            clock_percent: Some(61.0),
+        }))
+        .ram_percent(99.2)
+        .disk_rates(1_850_000_000.0, 920_000_000.0)
+        .disk_history(
+            history(HISTORY_SIZE, 900_000_000.0, 1_900_000_000.0, 0.2),
+            history(HISTORY_SIZE, 400_000_000.0, 950_000_000.0, 1.1),
+        )
+        .with(|data| {
+            data.disk_devices[0].read_rate = data.disk_read_rate;
+            data.disk_devices[0].write_rate = data.disk_write_rate;
+            data.process_counts = ProcessCounts {
+                processes: 2_914,
+                threads: 31_550,
+                zombies: 7,
+            };
+            data.top_app = Some(AppUsage {
+                name: "rustc".to_string(),
+                cpu_percent: 1_480.0,
+            });
+        })
+        .build()
+}
+
+/// Returns a gigabit link running flat out in both directions, as a full
+/// graph of constant values.
+pub fn network_saturated() -> SystemData {
+    const LINK_RATE: f64 = 125_000_000.0;
+    system_data()
+        .to_builder()
+        .net_rates(LINK_RATE, LINK_RATE * 0.94)
+        .net_history(
+            vec![LINK_RATE; HISTORY_SIZE],
+            vec![LINK_RATE * 0.94; HISTORY_SIZE],
+        )
+        .with(|data| {
+            data.net_totals = NetworkTotals {
+                day_rx: 9_800_000_000_000,
+                day_tx: 9_200_000_000_000,
+                month_rx: 240_000_000_000_000,
+                month_tx: 210_000_000_000_000,
+            };
+        })
+        .build()
+}
+
+/// Returns a system without a temperature sensor or GPU, such as a virtual
+/// machine.
+pub fn no_temp_sensor() -> SystemData {
+    system_data()
+        .to_builder()
+        .cpu_temp(None)
+        .temp_history([])
+        .cpu_throttle(None)
+        .with(|data| data.gpu = None)
+        .build()
+}
+
+/// Returns every profile, including the full [`system_data`], by name.
+pub fn profiles() -> Vec<(&'static str, SystemData)> {
+    vec![
+        ("full", system_data()),
+        ("idle", idle()),
+        ("busy", busy()),
+        ("network-saturated", network_saturated()),
+        ("no-temp-sensor", no_temp_sensor()),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::faces::{available_faces, create_face, EnabledComplications, Theme};
+    use crate::rendering::Canvas;
+    use ht32_panel_hw::Orientation;
+
+    #[test]
+    fn test_profiles_render() {
+        assert!(idle().temp_history.is_empty() && idle().net_history.is_empty());
+        assert_eq!(idle().uptime, "3m");
+        assert!(busy()
+            .cpu_throttle
+            .is_some_and(|throttle| throttle.throttled));
+        assert_eq!(network_saturated().net_history.len(), HISTORY_SIZE);
+        assert_eq!(no_temp_sensor().cpu_temp, None);
+
+        // Every face renders every profile in both orientations without panicking
+        for (_, data) in profiles() {
+            for info in available_faces() {
+                let face = create_face(info.id).unwrap();
+                let mut complications = EnabledComplications::new();
+                complications.init_from_defaults(face.as_ref());
+                for orientation in [Orientation::Landscape, Orientation::Portrait] {
+                    let (width, height) = orientation.dimensions();
+                    let mut canvas = Canvas::new(width as u32, height as u32);
+                    face.render(
+                        &mut canvas,
+                        &data,
+                        &Theme::from_preset("default"),
+                        &complications,
+                    );
+                }
+            }
+        }
+    }
+}
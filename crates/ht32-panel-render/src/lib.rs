@@ -0,0 +1,16 @@
+//! HT32 Panel Rendering
+//!
+//! Draws the panel's faces without a daemon: the [`Canvas`] with its widgets
+//! and bundled font, the [`Face`] trait with the built-in faces and themes,
+//! and the [`SystemData`] they are drawn from. Nothing here talks to D-Bus,
+//! sensors or USB, so previews, layout editors and tests can render faces
+//! headlessly.
+
+pub mod data;
+pub mod faces;
+pub mod fixture;
+pub mod rendering;
+
+pub use data::SystemData;
+pub use faces::{available_faces, create_face, EnabledComplications, Face, Theme};
+pub use rendering::Canvas;
@@ -5,6 +5,7 @@ use ht32_panel_hw::lcd::framebuffer::{rgb888_to_rgb565, Framebuffer};
 use std::collections::VecDeque;
 use tiny_skia::{Color, Paint, PathBuilder, Pixmap, Rect, Stroke, Transform};
 
+use super::skin::Insets;
 use super::text::TextRenderer;
 
 /// Brightens a color by the given factor.
@@ -20,12 +21,64 @@ fn brighten_color(color: u32, factor: f32) -> u32 {
     (r << 16) | (g << 8) | b
 }
 
+/// How history graphs are drawn.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum GraphStyle {
+    /// One vertical bar per sample.
+    #[default]
+    Bars,
+    /// A line through the samples.
+    Line,
+    /// A line with the area below it shaded.
+    Area,
+}
+
+impl GraphStyle {
+    /// Parses a style name, falling back to bars for unknown names.
+    pub fn from_name(name: &str) -> Self {
+        match name {
+            "line" => Self::Line,
+            "area" => Self::Area,
+            _ => Self::Bars,
+        }
+    }
+}
+
+/// One series of a line or area graph, see [`Canvas::draw_series_path`].
+#[derive(Clone, Copy)]
+struct SeriesPath<'a> {
+    /// Samples, oldest first
+    data: &'a VecDeque<f64>,
+    /// Value drawn at the full graph height
+    max_value: f64,
+    color: u32,
+    /// Shade the area between the line and the baseline
+    fill: bool,
+    /// Use the top edge as the baseline instead of the bottom one
+    downward: bool,
+}
+
+impl<'a> SeriesPath<'a> {
+    /// Creates an upward series, shaded for the area style.
+    fn new(data: &'a VecDeque<f64>, max_value: f64, color: u32, style: GraphStyle) -> Self {
+        Self {
+            data,
+            max_value,
+            color,
+            fill: style == GraphStyle::Area,
+            downward: false,
+        }
+    }
+}
+
 /// Canvas for rendering.
 pub struct Canvas {
     width: u32,
     height: u32,
     pixmap: Pixmap,
     background_color: u32,
+    /// Image drawn over the background color when clearing
+    background_image: Option<Pixmap>,
     text_renderer: TextRenderer,
 }
 
@@ -39,6 +92,7 @@ impl Canvas {
             height,
             pixmap,
             background_color: 0x000000, // Black
+            background_image: None,
             text_renderer: TextRenderer::new(),
         }
     }
@@ -54,6 +108,8 @@ impl Canvas {
             self.width = width;
             self.height = height;
             self.pixmap = Pixmap::new(width, height).expect("Failed to create pixmap");
+            // A background image no longer fits; the owner loads a new one
+            self.background_image = None;
         }
     }
 
@@ -62,12 +118,28 @@ impl Canvas {
         self.background_color = color;
     }
 
+    /// Sets an image drawn behind every face. It should match the canvas
+    /// size; see [`crate::rendering::load_pixmap`].
+    pub fn set_background_image(&mut self, image: Option<Pixmap>) {
+        self.background_image = image;
+    }
+
     /// Clears the canvas.
     pub fn clear(&mut self) {
         let r = ((self.background_color >> 16) & 0xFF) as f32 / 255.0;
         let g = ((self.background_color >> 8) & 0xFF) as f32 / 255.0;
         let b = (self.background_color & 0xFF) as f32 / 255.0;
         self.pixmap.fill(Color::from_rgba(r, g, b, 1.0).unwrap());
+        if let Some(ref image) = self.background_image {
+            self.pixmap.draw_pixmap(
+                0,
+                0,
+                image.as_ref(),
+                &tiny_skia::PixmapPaint::default(),
+                Transform::identity(),
+                None,
+            );
+        }
     }
 
     /// Draws a filled rectangle.
@@ -168,6 +240,34 @@ impl Canvas {
         );
     }
 
+    /// Runs `draw` with the canvas narrowed to the area inside `margin`, so
+    /// that faces laid out from [`Canvas::dimensions`] keep clear of the
+    /// edges. The area keeps what is already drawn there, and clearing it
+    /// restores that. Margins that leave no room are ignored.
+    pub fn draw_inset<R>(&mut self, margin: Insets, draw: impl FnOnce(&mut Canvas) -> R) -> R {
+        let width = self.width.saturating_sub(margin.left + margin.right);
+        let height = self.height.saturating_sub(margin.top + margin.bottom);
+        let area =
+            tiny_skia::IntRect::from_xywh(margin.left as i32, margin.top as i32, width, height)
+                .and_then(|rect| self.pixmap.clone_rect(rect));
+        let Some(area) = area.filter(|_| margin != Insets::default()) else {
+            return draw(self);
+        };
+
+        let full = std::mem::replace(&mut self.pixmap, area);
+        let full_size = (self.width, self.height);
+        let background = self.background_image.replace(self.pixmap.clone());
+        (self.width, self.height) = (width, height);
+
+        let result = draw(self);
+
+        let area = std::mem::replace(&mut self.pixmap, full);
+        (self.width, self.height) = full_size;
+        self.background_image = background;
+        self.draw_pixmap(margin.left as i32, margin.top as i32, &area);
+        result
+    }
+
     /// Draws a line between two points.
     ///
     /// # Arguments
@@ -381,6 +481,17 @@ impl Canvas {
         self.text_renderer.text_width(text, size)
     }
 
+    /// Shortens `text` from the end until it is at most `max_width` pixels
+    /// wide at the specified size. Returns an empty string if not even the
+    /// first character fits.
+    pub fn truncate_to_width(&self, text: &str, size: f32, max_width: i32) -> String {
+        let mut text = text.to_string();
+        while !text.is_empty() && self.text_width(&text, size) > max_width {
+            text.pop();
+        }
+        text
+    }
+
     /// Returns the width of text when rendered with horizontal scaling.
     pub fn text_width_scaled(&self, text: &str, size: f32, x_scale: f32) -> i32 {
         self.text_renderer.text_width_scaled(text, size, x_scale)
@@ -402,6 +513,7 @@ impl Canvas {
     /// * `max_value` - Maximum value for scaling (values above this are clamped)
     /// * `line_color` - Color for the line/bars
     /// * `bg_color` - Background color for the graph area
+    /// * `style` - Bars, line or shaded area
     #[allow(clippy::too_many_arguments)]
     pub fn draw_graph(
         &mut self,
@@ -413,6 +525,7 @@ impl Canvas {
         max_value: f64,
         line_color: u32,
         bg_color: u32,
+        style: GraphStyle,
     ) {
         debug_assert!(
             x >= 0 && y >= 0,
@@ -450,6 +563,13 @@ impl Canvas {
             return;
         }
 
+        if style != GraphStyle::Bars {
+            let bar_width = (width as f64 / data.len() as f64).max(1.0);
+            let series = SeriesPath::new(data, max_value, line_color, style);
+            self.draw_series_path(x, y, height, bar_width, &series);
+            return;
+        }
+
         // Compute highlight colors for high values
         let high_color = brighten_color(line_color, 1.4); // 95-99%: brighter
         let max_color = 0xFFFFFF; // 100%: white
@@ -493,6 +613,7 @@ impl Canvas {
     /// * `color1` - Color for first series
     /// * `color2` - Color for second series
     /// * `bg_color` - Background color for the graph area
+    /// * `style` - Bars, line or shaded area
     #[allow(clippy::too_many_arguments)]
     pub fn draw_dual_graph(
         &mut self,
@@ -506,6 +627,7 @@ impl Canvas {
         color1: u32,
         color2: u32,
         bg_color: u32,
+        style: GraphStyle,
     ) {
         debug_assert!(
             x >= 0 && y >= 0,
@@ -550,6 +672,16 @@ impl Canvas {
         }
         let bar_width = (width as f64 / num_points as f64).max(1.0);
 
+        if style != GraphStyle::Bars {
+            for series in [
+                SeriesPath::new(data1, max_value, color1, style),
+                SeriesPath::new(data2, max_value, color2, style),
+            ] {
+                self.draw_series_path(x, y, height, bar_width, &series);
+            }
+            return;
+        }
+
         // Compute highlight colors for high values
         let high_color1 = brighten_color(color1, 1.4);
         let high_color2 = brighten_color(color2, 1.4);
@@ -601,15 +733,178 @@ impl Canvas {
         }
     }
 
+    /// Draws a two-series graph mirrored around a horizontal midline: the
+    /// first series grows upward from the middle, the second downward.
+    ///
+    /// # Arguments
+    /// * `x` - X position (left edge)
+    /// * `y` - Y position (top edge)
+    /// * `width` - Width of the graph area
+    /// * `height` - Height of the graph area (each series gets half)
+    /// * `upper` - Series drawn above the midline (e.g., rx rates)
+    /// * `lower` - Series drawn below the midline (e.g., tx rates)
+    /// * `max_value` - Maximum value for scaling (values above this are clamped)
+    /// * `upper_color` - Color for the upper series
+    /// * `lower_color` - Color for the lower series
+    /// * `bg_color` - Background color for the graph area
+    /// * `style` - Bars, line or shaded area
+    #[allow(clippy::too_many_arguments)]
+    pub fn draw_mirrored_graph(
+        &mut self,
+        x: i32,
+        y: i32,
+        width: u32,
+        height: u32,
+        upper: &VecDeque<f64>,
+        lower: &VecDeque<f64>,
+        max_value: f64,
+        upper_color: u32,
+        lower_color: u32,
+        bg_color: u32,
+        style: GraphStyle,
+    ) {
+        self.fill_rect(x, y, width, height, bg_color);
+
+        let num_points = upper.len().max(lower.len());
+        if num_points == 0 || max_value <= 0.0 {
+            return;
+        }
+        let half = height / 2;
+        let mid = y + half as i32;
+        let bar_width = (width as f64 / num_points as f64).max(1.0);
+
+        if style != GraphStyle::Bars {
+            let upper = SeriesPath::new(upper, max_value, upper_color, style);
+            self.draw_series_path(x, y, half, bar_width, &upper);
+            let lower = SeriesPath {
+                downward: true,
+                ..SeriesPath::new(lower, max_value, lower_color, style)
+            };
+            self.draw_series_path(x, mid, height - half, bar_width, &lower);
+            return;
+        }
+
+        for (i, &value) in upper.iter().enumerate() {
+            let bar_height = ((value / max_value).min(1.0) * half as f64) as u32;
+            if bar_height > 0 {
+                let bar_x = x + (i as f64 * bar_width) as i32;
+                let bar_y = mid - bar_height as i32;
+                self.fill_rect(
+                    bar_x,
+                    bar_y,
+                    bar_width.ceil() as u32,
+                    bar_height,
+                    upper_color,
+                );
+            }
+        }
+        for (i, &value) in lower.iter().enumerate() {
+            let bar_height = ((value / max_value).min(1.0) * (height - half) as f64) as u32;
+            if bar_height > 0 {
+                let bar_x = x + (i as f64 * bar_width) as i32;
+                self.fill_rect(bar_x, mid, bar_width.ceil() as u32, bar_height, lower_color);
+            }
+        }
+    }
+
+    /// Draws one graph series as a line through the middle of each sample's
+    /// column, `column_width` apart, in the `height` rows below `y`.
+    fn draw_series_path(
+        &mut self,
+        x: i32,
+        y: i32,
+        height: u32,
+        column_width: f64,
+        series: &SeriesPath,
+    ) {
+        let SeriesPath {
+            data,
+            max_value,
+            color,
+            fill,
+            downward,
+        } = *series;
+        if data.is_empty() || height == 0 {
+            return;
+        }
+
+        let (baseline, zero_row, direction) = if downward {
+            (y as f32, y as f64, 1.0)
+        } else {
+            let bottom = y + height as i32;
+            (bottom as f32, bottom as f64 - 1.0, -1.0)
+        };
+        let points: Vec<(f32, f32)> = data
+            .iter()
+            .enumerate()
+            .map(|(i, &value)| {
+                let normalized = (value / max_value).clamp(0.0, 1.0);
+                let px = x as f64 + (i as f64 + 0.5) * column_width;
+                // Keep the line inside the graph so zero values stay visible
+                let py = zero_row + direction * normalized * (height as f64 - 1.0);
+                (px as f32, py as f32)
+            })
+            .collect();
+
+        let r = ((color >> 16) & 0xFF) as f32 / 255.0;
+        let g = ((color >> 8) & 0xFF) as f32 / 255.0;
+        let b = (color & 0xFF) as f32 / 255.0;
+        let mut paint = Paint {
+            anti_alias: true,
+            ..Default::default()
+        };
+
+        if fill {
+            let mut pb = PathBuilder::new();
+            pb.move_to(points[0].0, baseline);
+            for &(px, py) in &points {
+                pb.line_to(px, py);
+            }
+            pb.line_to(points[points.len() - 1].0, baseline);
+            pb.close();
+            if let Some(path) = pb.finish() {
+                paint.set_color(Color::from_rgba(r, g, b, 0.35).unwrap());
+                self.pixmap.fill_path(
+                    &path,
+                    &paint,
+                    tiny_skia::FillRule::Winding,
+                    Transform::identity(),
+                    None,
+                );
+            }
+        }
+
+        // A single sample has no segment to stroke; draw it as a dot
+        if points.len() == 1 {
+            let (px, py) = points[0];
+            self.fill_rect(px as i32, py as i32, 1, 1, color);
+            return;
+        }
+
+        let mut pb = PathBuilder::new();
+        pb.move_to(points[0].0, points[0].1);
+        for &(px, py) in &points[1..] {
+            pb.line_to(px, py);
+        }
+        if let Some(path) = pb.finish() {
+            paint.set_color(Color::from_rgba(r, g, b, 1.0).unwrap());
+            let stroke = Stroke {
+                width: 1.5,
+                line_join: tiny_skia::LineJoin::Round,
+                ..Default::default()
+            };
+            self.pixmap
+                .stroke_path(&path, &paint, &stroke, Transform::identity(), None);
+        }
+    }
+
     /// Renders the canvas to a framebuffer.
     pub fn render_to_framebuffer(&self, fb: &mut Framebuffer) -> Result<()> {
         let pixels = self.pixmap.pixels();
         let data = fb.data_mut();
 
-        for (i, pixel) in pixels.iter().enumerate() {
-            if i < data.len() {
-                data[i] = rgb888_to_rgb565(pixel.red(), pixel.green(), pixel.blue());
-            }
+        for (dst, pixel) in data.iter_mut().zip(pixels) {
+            *dst = rgb888_to_rgb565(pixel.red(), pixel.green(), pixel.blue());
         }
 
         Ok(())
@@ -635,4 +930,130 @@ mod tests {
         let canvas = Canvas::new(320, 170);
         assert_eq!(canvas.dimensions(), (320, 170));
     }
+
+    #[test]
+    fn test_truncate_to_width() {
+        let canvas = Canvas::new(320, 170);
+        let text = "a-very-long-hostname.example";
+        let full = canvas.text_width(text, 12.0);
+        assert_eq!(canvas.truncate_to_width(text, 12.0, full), text);
+
+        let half = canvas.truncate_to_width(text, 12.0, full / 2);
+        assert!(text.starts_with(&half));
+        assert!(!half.is_empty() && half.len() < text.len());
+        assert!(canvas.text_width(&half, 12.0) <= full / 2);
+        let one_more = &text[..half.len() + 1];
+        assert!(canvas.text_width(one_more, 12.0) > full / 2);
+
+        assert_eq!(canvas.truncate_to_width(text, 12.0, 0), "");
+        assert_eq!(canvas.truncate_to_width("héllo", 12.0, -5), "");
+    }
+
+    #[test]
+    fn test_graph_style_from_name() {
+        assert_eq!(GraphStyle::from_name("line"), GraphStyle::Line);
+        assert_eq!(GraphStyle::from_name("area"), GraphStyle::Area);
+        assert_eq!(GraphStyle::from_name("bars"), GraphStyle::Bars);
+        assert_eq!(GraphStyle::from_name("unknown"), GraphStyle::Bars);
+    }
+
+    #[test]
+    fn test_draw_series_path() {
+        let data = VecDeque::from([5.0, 5.0, 5.0, 5.0]);
+        let draw = |series: SeriesPath| {
+            let mut canvas = Canvas::new(8, 12);
+            canvas.set_background(0x000000);
+            canvas.clear();
+            canvas.draw_series_path(0, 0, 12, 2.0, &series);
+            // Red channel of the middle column, top to bottom
+            (0..12)
+                .map(|y| canvas.pixels()[(y * 8 + 4) * 4])
+                .collect::<Vec<u8>>()
+        };
+        let lit = |column: &[u8]| {
+            column
+                .iter()
+                .enumerate()
+                .filter(|(_, &red)| red > 200)
+                .map(|(y, _)| y)
+                .collect::<Vec<_>>()
+        };
+
+        // Half of max_value is drawn halfway up the graph
+        let line = draw(SeriesPath::new(&data, 10.0, 0xFF0000, GraphStyle::Line));
+        assert_eq!(lit(&line), vec![5]);
+
+        // The area style shades the rows below the line
+        let area = draw(SeriesPath::new(&data, 10.0, 0xFF0000, GraphStyle::Area));
+        assert!(area[..4].iter().all(|&red| red == 0));
+        assert!(area[7..].iter().all(|&red| red > 0 && red < 128));
+
+        // Downward series shade between the top edge and the line
+        let downward = draw(SeriesPath {
+            downward: true,
+            ..SeriesPath::new(&data, 10.0, 0xFF0000, GraphStyle::Area)
+        });
+        assert_eq!(lit(&downward), vec![5]);
+        assert!(downward[..4].iter().all(|&red| red > 0 && red < 128));
+        assert!(downward[7..].iter().all(|&red| red == 0));
+
+        // Values past max_value are clamped to the top row
+        let clamped = VecDeque::from([50.0, 50.0, 50.0, 50.0]);
+        let line = draw(SeriesPath::new(&clamped, 10.0, 0xFF0000, GraphStyle::Line));
+        assert!(line[0] > 128);
+        assert!(line[1..].iter().all(|&red| red == 0));
+    }
+
+    #[test]
+    fn test_draw_mirrored_graph() {
+        let mut canvas = Canvas::new(4, 10);
+        canvas.draw_mirrored_graph(
+            0,
+            0,
+            4,
+            10,
+            &VecDeque::from([10.0, 5.0]),
+            &VecDeque::from([0.0, 10.0]),
+            10.0,
+            0xFF0000,
+            0x0000FF,
+            0x000000,
+            GraphStyle::Bars,
+        );
+        let column = |x: usize| {
+            (0..10)
+                .map(|y| &canvas.pixels()[(y * 4 + x) * 4..][..3])
+                .collect::<Vec<_>>()
+        };
+
+        // The first sample fills the upper half, the second sample half of
+        // it and all of the lower half
+        let first = column(0);
+        assert!(first[..5].iter().all(|p| *p == [255, 0, 0]));
+        assert!(first[5..].iter().all(|p| *p == [0, 0, 0]));
+        let second = column(2);
+        assert!(second[..3].iter().all(|p| *p == [0, 0, 0]));
+        assert!(second[3..5].iter().all(|p| *p == [255, 0, 0]));
+        assert!(second[5..].iter().all(|p| *p == [0, 0, 255]));
+    }
+
+    #[test]
+    fn test_draw_inset() {
+        let mut canvas = Canvas::new(20, 10);
+        canvas.set_background(0xFF0000);
+        canvas.clear();
+
+        let size = canvas.draw_inset(Insets::from([2, 3, 2, 3]), |inner| {
+            inner.clear();
+            inner.fill_rect(0, 0, 1, 1, 0x0000FF);
+            inner.dimensions()
+        });
+        assert_eq!(size, (14, 6));
+        assert_eq!(canvas.dimensions(), (20, 10));
+
+        let pixel = |x: usize, y: usize| &canvas.pixels()[(y * 20 + x) * 4..][..3];
+        assert_eq!(pixel(2, 1), [255, 0, 0]);
+        assert_eq!(pixel(3, 2), [0, 0, 255]);
+        assert_eq!(pixel(4, 2), [255, 0, 0]);
+    }
 }
@@ -0,0 +1,197 @@
+//! Post-processing of the final frame.
+//!
+//! Effects and mirroring are applied to the RGB565 framebuffer after
+//! orientation handling, just before it is sent to the panel, so previews
+//! and screenshots show the face without them.
+
+use std::fmt;
+use std::str::FromStr;
+
+use ht32_panel_hw::lcd::{rgb565_to_rgb888, rgb888_to_rgb565};
+use ht32_panel_hw::Framebuffer;
+
+use super::VisibleArea;
+
+/// How much scanlines darken every other line (0-1).
+const SCANLINE_DARKEN: f32 = 0.4;
+
+/// A filter applied to the final frame.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PostEffect {
+    /// Inverts every color
+    Invert,
+    /// Converts to shades of gray
+    Grayscale,
+    /// Shifts colors toward a warmer temperature; strength 0-1
+    Warm(f32),
+    /// Darkens every other line like a CRT
+    Scanlines,
+}
+
+impl FromStr for PostEffect {
+    type Err = String;
+
+    /// Parses "invert", "grayscale", "scanlines", "warm" or "warm:0.5".
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim().to_lowercase();
+        let (name, value) = match s.split_once(':') {
+            Some((name, value)) => (name, Some(value)),
+            None => (s.as_str(), None),
+        };
+        match (name, value) {
+            ("invert", None) => Ok(PostEffect::Invert),
+            ("grayscale" | "greyscale", None) => Ok(PostEffect::Grayscale),
+            ("scanlines", None) => Ok(PostEffect::Scanlines),
+            ("warm" | "night", None) => Ok(PostEffect::Warm(1.0)),
+            ("warm" | "night", Some(value)) => value
+                .parse::<f32>()
+                .ok()
+                .filter(|v| (0.0..=1.0).contains(v))
+                .map(PostEffect::Warm)
+                .ok_or_else(|| format!("Invalid warm strength (expected 0-1): {}", value)),
+            _ => Err(format!(
+                "Unknown effect: {}. Use: invert, grayscale, warm[:0-1], scanlines",
+                s
+            )),
+        }
+    }
+}
+
+impl fmt::Display for PostEffect {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PostEffect::Invert => write!(f, "invert"),
+            PostEffect::Grayscale => write!(f, "grayscale"),
+            PostEffect::Warm(strength) if *strength >= 1.0 => write!(f, "warm"),
+            PostEffect::Warm(strength) => write!(f, "warm:{}", strength),
+            PostEffect::Scanlines => write!(f, "scanlines"),
+        }
+    }
+}
+
+/// Applies `effects` in order. `portrait` turns scanlines so they stay
+/// horizontal on a panel mounted upright.
+pub fn apply_effects(framebuffer: &mut Framebuffer, effects: &[PostEffect], portrait: bool) {
+    if effects.is_empty() {
+        return;
+    }
+    let width = framebuffer.width() as usize;
+    for (i, pixel) in framebuffer.data_mut().iter_mut().enumerate() {
+        let (r, g, b) = rgb565_to_rgb888(*pixel);
+        let (mut r, mut g, mut b) = (r as f32, g as f32, b as f32);
+        for effect in effects {
+            match *effect {
+                PostEffect::Invert => {
+                    (r, g, b) = (255.0 - r, 255.0 - g, 255.0 - b);
+                }
+                PostEffect::Grayscale => {
+                    let luma = 0.299 * r + 0.587 * g + 0.114 * b;
+                    (r, g, b) = (luma, luma, luma);
+                }
+                PostEffect::Warm(strength) => {
+                    // Roughly 6500K down to 3400K at full strength
+                    g *= 1.0 - 0.22 * strength;
+                    b *= 1.0 - 0.55 * strength;
+                }
+                PostEffect::Scanlines => {
+                    let line = if portrait { i % width } else { i / width };
+                    if line % 2 == 1 {
+                        let keep = 1.0 - SCANLINE_DARKEN;
+                        (r, g, b) = (r * keep, g * keep, b * keep);
+                    }
+                }
+            }
+        }
+        *pixel = rgb888_to_rgb565(r as u8, g as u8, b as u8);
+    }
+}
+
+/// Scales every pixel to `percent` brightness.
+pub fn dim(framebuffer: &mut Framebuffer, percent: u8) {
+    if percent >= 100 {
+        return;
+    }
+    let scale = |c: u8| (c as u32 * percent as u32 / 100) as u8;
+    for pixel in framebuffer.data_mut() {
+        let (r, g, b) = rgb565_to_rgb888(*pixel);
+        *pixel = rgb888_to_rgb565(scale(r), scale(g), scale(b));
+    }
+}
+
+/// Mirrors the frame within `area` horizontally and/or vertically as seen
+/// by the viewer. `portrait` swaps the axes, as the panel's long edge is
+/// then upright.
+pub fn mirror(
+    framebuffer: &mut Framebuffer,
+    area: &VisibleArea,
+    flip_h: bool,
+    flip_v: bool,
+    portrait: bool,
+) {
+    let (flip_rows, flip_columns) = if portrait {
+        (flip_h, flip_v)
+    } else {
+        (flip_v, flip_h)
+    };
+    let stride = framebuffer.width() as usize;
+    let (x, y) = (area.x as usize, area.y as usize);
+    let (width, height) = (area.width as usize, area.height as usize);
+    let data = framebuffer.data_mut();
+    if data.len() < (y + height) * stride || x + width > stride {
+        return;
+    }
+    let row = |i: usize| (y + i) * stride + x;
+    if flip_columns {
+        for i in 0..height {
+            data[row(i)..row(i) + width].reverse();
+        }
+    }
+    if flip_rows {
+        for i in 0..height / 2 {
+            let (top, bottom) = data.split_at_mut(row(height - 1 - i));
+            top[row(i)..row(i) + width].swap_with_slice(&mut bottom[..width]);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_effects() {
+        assert_eq!("Invert".parse(), Ok(PostEffect::Invert));
+        assert_eq!("warm:0.5".parse(), Ok(PostEffect::Warm(0.5)));
+        assert!("warm:2".parse::<PostEffect>().is_err());
+        assert!("blur".parse::<PostEffect>().is_err());
+        assert_eq!(PostEffect::Warm(0.5).to_string(), "warm:0.5");
+        assert_eq!(PostEffect::Warm(1.0).to_string(), "warm");
+    }
+
+    #[test]
+    fn test_mirror() {
+        let mut framebuffer = Framebuffer::with_dimensions(3, 2);
+        framebuffer.copy_from_rgb565(&[1, 2, 3, 4, 5, 6]).unwrap();
+        let whole = VisibleArea {
+            x: 0,
+            y: 0,
+            width: 3,
+            height: 2,
+        };
+        mirror(&mut framebuffer, &whole, true, false, false);
+        assert_eq!(framebuffer.data(), &[3, 2, 1, 6, 5, 4]);
+        mirror(&mut framebuffer, &whole, true, false, true);
+        assert_eq!(framebuffer.data(), &[6, 5, 4, 3, 2, 1]);
+        mirror(&mut framebuffer, &whole, true, true, false);
+        assert_eq!(framebuffer.data(), &[1, 2, 3, 4, 5, 6]);
+
+        // Pixels outside the area stay put
+        let right = VisibleArea {
+            x: 1,
+            width: 2,
+            ..whole
+        };
+        mirror(&mut framebuffer, &right, true, true, false);
+        assert_eq!(framebuffer.data(), &[1, 6, 5, 4, 3, 2]);
+    }
+}
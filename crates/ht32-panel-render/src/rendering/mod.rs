@@ -0,0 +1,24 @@
+//! Rendering module for canvas.
+
+#![allow(dead_code)]
+
+mod canvas;
+mod effects;
+mod overlay;
+mod pixmap;
+mod screenshot;
+mod skin;
+mod splash;
+mod test_pattern;
+mod text;
+mod visible_area;
+
+pub use canvas::{Canvas, GraphStyle};
+pub use effects::{apply_effects, dim, mirror, PostEffect};
+pub use overlay::draw_banner;
+pub use pixmap::{encode_png, load_pixmap};
+pub use screenshot::{encode_screen, ScreenCache, ScreenFormat};
+pub use skin::Skin;
+pub use splash::{render_panic, render_shutdown, render_splash};
+pub use test_pattern::{render_test_pattern, test_steps, TestPattern};
+pub use visible_area::VisibleArea;
@@ -0,0 +1,26 @@
+//! Transient overlays drawn on top of the current face.
+
+use super::Canvas;
+use crate::faces::Theme;
+
+/// Font size for banner text.
+const BANNER_FONT: f32 = 16.0;
+
+/// Vertical padding inside the banner.
+const BANNER_PADDING: i32 = 6;
+
+/// Draws a full-width banner across the middle of the canvas.
+///
+/// The banner uses the theme's primary color with background-colored text
+/// so it stands out from any face.
+pub fn draw_banner(canvas: &mut Canvas, text: &str, theme: &Theme) {
+    let (width, height) = canvas.dimensions();
+    let banner_height = canvas.line_height(BANNER_FONT) + BANNER_PADDING * 2;
+    let y = (height as i32 - banner_height) / 2;
+
+    canvas.fill_rect(0, y, width, banner_height as u32, theme.primary);
+
+    let text_width = canvas.text_width(text, BANNER_FONT);
+    let x = ((width as i32 - text_width) / 2).max(0);
+    canvas.draw_text(x, y + BANNER_PADDING, text, BANNER_FONT, theme.background);
+}
@@ -0,0 +1,52 @@
+//! Image loading into tiny-skia pixmaps, and PNG encoding.
+
+use anyhow::{anyhow, Context, Result};
+use image::imageops::FilterType;
+use tiny_skia::{IntSize, Pixmap};
+
+/// Loads an image file and scales it to the given bounds.
+///
+/// When `fill` is true the image covers the whole area (cropping as needed),
+/// otherwise it is scaled to fit inside the area preserving aspect ratio.
+pub fn load_pixmap(path: &str, width: u32, height: u32, fill: bool) -> Result<Pixmap> {
+    let img = image::open(path).with_context(|| format!("Failed to open image {}", path))?;
+    let img = if fill {
+        img.resize_to_fill(width, height, FilterType::Lanczos3)
+    } else {
+        img.resize(width, height, FilterType::Lanczos3)
+    };
+    to_pixmap(img.to_rgba8(), path)
+}
+
+/// Converts RGBA pixels into a pixmap; `path` names the image in errors.
+pub(super) fn to_pixmap(rgba: image::RgbaImage, path: &str) -> Result<Pixmap> {
+    let (w, h) = rgba.dimensions();
+
+    let mut data = rgba.into_raw();
+
+    // Premultiply alpha for tiny-skia
+    for chunk in data.chunks_mut(4) {
+        let a = chunk[3] as u32;
+        if a != 255 {
+            chunk[0] = ((chunk[0] as u32 * a) / 255) as u8;
+            chunk[1] = ((chunk[1] as u32 * a) / 255) as u8;
+            chunk[2] = ((chunk[2] as u32 * a) / 255) as u8;
+        }
+    }
+
+    let size = IntSize::from_wh(w, h).ok_or_else(|| anyhow!("Image {} is empty", path))?;
+    Pixmap::from_vec(data, size).ok_or_else(|| anyhow!("Invalid pixel data in {}", path))
+}
+
+/// Encodes RGBA pixels as a PNG image.
+pub fn encode_png(width: u32, height: u32, rgba: &[u8]) -> Result<Vec<u8>> {
+    let mut png_data = Vec::new();
+    {
+        let mut encoder = png::Encoder::new(&mut png_data, width, height);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header()?;
+        writer.write_image_data(rgba)?;
+    }
+    Ok(png_data)
+}
@@ -0,0 +1,189 @@
+//! Encoded screenshots of the current frame.
+//!
+//! Screenshots are encoded on the blocking thread pool and cached until the
+//! next frame, so frequent preview requests reuse one encoding instead of
+//! competing with the render loop.
+
+use std::fmt;
+use std::io::Cursor;
+use std::str::FromStr;
+
+use anyhow::Result;
+use image::codecs::jpeg::JpegEncoder;
+use image::codecs::webp::WebPEncoder;
+use image::{ExtendedColorType, ImageEncoder};
+
+use super::encode_png;
+
+/// JPEG quality used when none is given.
+pub const DEFAULT_QUALITY: u8 = 80;
+
+/// Most encodings cached for one frame.
+const MAX_CACHED: usize = 4;
+
+/// Image format of a screenshot.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ScreenFormat {
+    #[default]
+    Png,
+    /// Lossy, with a quality of 1-100
+    Jpeg,
+    /// Lossless
+    WebP,
+}
+
+impl ScreenFormat {
+    /// Returns the quality `quality` encodes with in this format: the
+    /// default for 0, and 0 for formats without a quality setting.
+    fn effective_quality(self, quality: u8) -> u8 {
+        match self {
+            Self::Jpeg if quality == 0 => DEFAULT_QUALITY,
+            Self::Jpeg => quality.min(100),
+            Self::Png | Self::WebP => 0,
+        }
+    }
+
+    /// Returns the MIME type of the format.
+    pub fn mime_type(self) -> &'static str {
+        match self {
+            Self::Png => "image/png",
+            Self::Jpeg => "image/jpeg",
+            Self::WebP => "image/webp",
+        }
+    }
+}
+
+impl FromStr for ScreenFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "" | "png" => Ok(Self::Png),
+            "jpeg" | "jpg" => Ok(Self::Jpeg),
+            "webp" => Ok(Self::WebP),
+            _ => Err(format!(
+                "Unknown image format: {} (expected png, jpeg or webp)",
+                s
+            )),
+        }
+    }
+}
+
+impl fmt::Display for ScreenFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Png => "png",
+            Self::Jpeg => "jpeg",
+            Self::WebP => "webp",
+        })
+    }
+}
+
+/// Encodes RGBA pixels in `format`. `quality` (1-100, 0 for the default)
+/// only applies to JPEG.
+pub fn encode_screen(
+    format: ScreenFormat,
+    quality: u8,
+    width: u32,
+    height: u32,
+    rgba: &[u8],
+) -> Result<Vec<u8>> {
+    let mut data = Vec::new();
+    match format {
+        ScreenFormat::Png => return encode_png(width, height, rgba),
+        ScreenFormat::Jpeg => {
+            let quality = format.effective_quality(quality);
+            // JPEG has no alpha channel; frames are opaque anyway
+            let rgb: Vec<u8> = rgba
+                .chunks_exact(4)
+                .flat_map(|p| [p[0], p[1], p[2]])
+                .collect();
+            JpegEncoder::new_with_quality(&mut data, quality).write_image(
+                &rgb,
+                width,
+                height,
+                ExtendedColorType::Rgb8,
+            )?;
+        }
+        ScreenFormat::WebP => {
+            WebPEncoder::new_lossless(Cursor::new(&mut data)).write_image(
+                rgba,
+                width,
+                height,
+                ExtendedColorType::Rgba8,
+            )?;
+        }
+    }
+    Ok(data)
+}
+
+/// Encodings of one frame, keyed by format and quality. Holds at most
+/// [`MAX_CACHED`] encodings, dropping the oldest.
+#[derive(Default)]
+pub struct ScreenCache {
+    frame: u64,
+    images: Vec<(ScreenFormat, u8, Vec<u8>)>,
+}
+
+impl ScreenCache {
+    /// Returns the cached encoding of `frame`, if any.
+    pub fn get(&self, frame: u64, format: ScreenFormat, quality: u8) -> Option<Vec<u8>> {
+        if frame != self.frame {
+            return None;
+        }
+        let quality = format.effective_quality(quality);
+        self.images
+            .iter()
+            .find(|(f, q, _)| *f == format && *q == quality)
+            .map(|(_, _, data)| data.clone())
+    }
+
+    /// Caches an encoding of `frame`, dropping those of earlier frames.
+    pub fn insert(&mut self, frame: u64, format: ScreenFormat, quality: u8, data: Vec<u8>) {
+        if frame != self.frame {
+            self.frame = frame;
+            self.images.clear();
+        }
+        if self.images.len() >= MAX_CACHED {
+            self.images.remove(0);
+        }
+        self.images
+            .push((format, format.effective_quality(quality), data));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_and_cache() {
+        let rgba = [255, 0, 0, 255].repeat(16 * 8);
+        let jpeg = encode_screen(ScreenFormat::Jpeg, 0, 16, 8, &rgba).unwrap();
+        assert_eq!(jpeg[..2], [0xFF, 0xD8]);
+        let webp = encode_screen("webp".parse().unwrap(), 0, 16, 8, &rgba).unwrap();
+        assert_eq!(&webp[8..12], b"WEBP");
+        assert!("gif".parse::<ScreenFormat>().is_err());
+
+        let mut cache = ScreenCache::default();
+        cache.insert(1, ScreenFormat::Jpeg, 0, jpeg.clone());
+        assert_eq!(cache.get(1, ScreenFormat::Jpeg, 0), Some(jpeg.clone()));
+        assert_eq!(cache.get(1, ScreenFormat::Jpeg, 50), None);
+        assert_eq!(cache.get(2, ScreenFormat::Jpeg, 0), None);
+        cache.insert(2, ScreenFormat::WebP, 0, webp.clone());
+        assert_eq!(cache.images.len(), 1);
+
+        // Qualities that encode the same are one entry
+        assert_eq!(cache.get(2, ScreenFormat::WebP, 50), Some(webp));
+        cache.insert(2, ScreenFormat::Jpeg, DEFAULT_QUALITY, jpeg.clone());
+        assert_eq!(cache.get(2, ScreenFormat::Jpeg, 0), Some(jpeg.clone()));
+
+        // Distinct qualities evict the oldest encodings
+        for quality in 1..=10 {
+            cache.insert(2, ScreenFormat::Jpeg, quality, jpeg.clone());
+        }
+        assert_eq!(cache.images.len(), MAX_CACHED);
+        assert_eq!(cache.get(2, ScreenFormat::Jpeg, 10), Some(jpeg));
+        assert_eq!(cache.get(2, ScreenFormat::Jpeg, 1), None);
+    }
+}
@@ -0,0 +1,160 @@
+//! Face skins: textured backgrounds with content-safe margins.
+//!
+//! A skin is an image drawn behind a face, either scaled to cover the canvas
+//! or scaled as a nine-patch: the image is cut into a 3x3 grid by `slice`
+//! insets, the corners keep their size, the edges stretch along one axis and
+//! the middle stretches both ways. Frames and bezels therefore stay crisp at
+//! any canvas size or orientation. The face is drawn inside `margin` so its
+//! content stays clear of the skin's border.
+
+use anyhow::{Context, Result};
+use image::imageops::{self, FilterType};
+use image::RgbaImage;
+use tiny_skia::Pixmap;
+
+use super::pixmap::{load_pixmap, to_pixmap};
+
+/// Distances from each edge of a rectangle, in pixels.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Insets {
+    pub top: u32,
+    pub right: u32,
+    pub bottom: u32,
+    pub left: u32,
+}
+
+impl From<[u32; 4]> for Insets {
+    /// Converts `[top, right, bottom, left]`, the order used by CSS.
+    fn from([top, right, bottom, left]: [u32; 4]) -> Self {
+        Self {
+            top,
+            right,
+            bottom,
+            left,
+        }
+    }
+}
+
+/// A background loaded at the canvas size, with the area faces draw in.
+pub struct Skin {
+    /// Background drawn over the canvas' background color
+    pub background: Pixmap,
+    /// Margins kept clear of face content
+    pub margin: Insets,
+}
+
+impl Skin {
+    /// Loads the image at `path` for a `width`x`height` canvas, as a
+    /// nine-patch if `slice` is given and scaled to cover the canvas
+    /// otherwise.
+    pub fn load(
+        path: &str,
+        slice: Option<Insets>,
+        margin: Insets,
+        width: u32,
+        height: u32,
+    ) -> Result<Self> {
+        let background = match slice {
+            Some(slice) => {
+                let source = image::open(path)
+                    .with_context(|| format!("Failed to open image {}", path))?
+                    .to_rgba8();
+                to_pixmap(nine_patch(&source, slice, width, height), path)?
+            }
+            None => load_pixmap(path, width, height, true)?,
+        };
+        Ok(Self { background, margin })
+    }
+}
+
+/// Scales `source` to `width`x`height` as a nine-patch cut by `slice`.
+///
+/// Corners are shrunk proportionally when they do not fit the target.
+fn nine_patch(source: &RgbaImage, slice: Insets, width: u32, height: u32) -> RgbaImage {
+    let columns = bands(source.width(), slice.left, slice.right, width);
+    let rows = bands(source.height(), slice.top, slice.bottom, height);
+
+    let mut out = RgbaImage::new(width, height);
+    for &(src_y, src_h, dst_y, dst_h) in &rows {
+        for &(src_x, src_w, dst_x, dst_w) in &columns {
+            if src_w == 0 || src_h == 0 || dst_w == 0 || dst_h == 0 {
+                continue;
+            }
+            let cell = imageops::crop_imm(source, src_x, src_y, src_w, src_h).to_image();
+            let cell = if (src_w, src_h) == (dst_w, dst_h) {
+                cell
+            } else {
+                imageops::resize(&cell, dst_w, dst_h, FilterType::Triangle)
+            };
+            imageops::replace(&mut out, &cell, dst_x as i64, dst_y as i64);
+        }
+    }
+    out
+}
+
+/// Splits a source length into start, middle and end bands by the `start`
+/// and `end` insets, returning `(source offset, source length, target
+/// offset, target length)` for each band scaled to `target`.
+fn bands(source: u32, start: u32, end: u32, target: u32) -> [(u32, u32, u32, u32); 3] {
+    // Insets covering the whole source leave nothing to stretch
+    let start = start.min(source.saturating_sub(1));
+    let end = end.min(source.saturating_sub(start + 1));
+    let middle = source - start - end;
+
+    let (dst_start, dst_end) = if start + end <= target {
+        (start, end)
+    } else {
+        let dst_start = start * target / (start + end);
+        (dst_start, target - dst_start)
+    };
+    let dst_middle = target - dst_start - dst_end;
+
+    [
+        (0, start, 0, dst_start),
+        (start, middle, dst_start, dst_middle),
+        (start + middle, end, dst_start + dst_middle, dst_end),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::Rgba;
+
+    #[test]
+    fn test_bands() {
+        // 30px source with 10px insets stretched to 100px
+        assert_eq!(
+            bands(30, 10, 10, 100),
+            [(0, 10, 0, 10), (10, 10, 10, 80), (20, 10, 90, 10)]
+        );
+        // Insets larger than the target shrink proportionally
+        assert_eq!(
+            bands(30, 10, 10, 10),
+            [(0, 10, 0, 5), (10, 10, 5, 0), (20, 10, 5, 5)]
+        );
+    }
+
+    #[test]
+    fn test_nine_patch_keeps_corners() {
+        // 3x3 source: red corners, green edges, blue middle
+        let red = Rgba([255, 0, 0, 255]);
+        let green = Rgba([0, 255, 0, 255]);
+        let blue = Rgba([0, 0, 255, 255]);
+        let source = RgbaImage::from_fn(3, 3, |x, y| match (x == 1, y == 1) {
+            (false, false) => red,
+            (true, true) => blue,
+            _ => green,
+        });
+
+        let out = nine_patch(&source, Insets::from([1, 1, 1, 1]), 20, 10);
+        assert_eq!(out.dimensions(), (20, 10));
+        for corner in [(0, 0), (19, 0), (0, 9), (19, 9)] {
+            assert_eq!(*out.get_pixel(corner.0, corner.1), red);
+        }
+        assert_eq!(*out.get_pixel(10, 0), green);
+        assert_eq!(*out.get_pixel(0, 5), green);
+        assert_eq!(*out.get_pixel(10, 5), blue);
+        assert_eq!(*out.get_pixel(1, 1), blue);
+    }
+}
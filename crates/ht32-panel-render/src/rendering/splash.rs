@@ -0,0 +1,257 @@
+//! Boot splash, shutdown and error screens.
+//!
+//! These are drawn outside of the face system: the splash is shown while the
+//! sensors collect their first samples, the shutdown screen replaces the
+//! last face frame when the daemon exits, and the panic screen is shown when
+//! the render loop crashes.
+
+use tiny_skia::Pixmap;
+
+use super::Canvas;
+use crate::faces::Theme;
+
+/// Horizontal margin kept free on both sides of centered text.
+const MARGIN: i32 = 8;
+
+/// Draws `text` horizontally centered at `y`, truncating it to fit the canvas.
+fn draw_centered(canvas: &mut Canvas, y: i32, text: &str, size: f32, color: u32) {
+    let (width, height) = canvas.dimensions();
+    if y < 0 || y + canvas.line_height(size) > height as i32 {
+        return;
+    }
+
+    let max_width = width as i32 - 2 * MARGIN;
+    let text = canvas.truncate_to_width(text, size, max_width);
+    if text.is_empty() {
+        return;
+    }
+
+    let x = (width as i32 - canvas.text_width(&text, size)) / 2;
+    canvas.draw_text(x, y, &text, size, color);
+}
+
+/// Splits `text` into lines no wider than `max_width` at the given size.
+fn wrap_text(canvas: &Canvas, text: &str, size: f32, max_width: i32) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut line = String::new();
+
+    for word in text.split_whitespace() {
+        let candidate = if line.is_empty() {
+            word.to_string()
+        } else {
+            format!("{} {}", line, word)
+        };
+        if canvas.text_width(&candidate, size) <= max_width {
+            line = candidate;
+            continue;
+        }
+        if !line.is_empty() {
+            lines.push(std::mem::take(&mut line));
+        }
+        // Break words that are too long on their own
+        let mut rest = word;
+        while canvas.text_width(rest, size) > max_width {
+            let head = canvas.truncate_to_width(rest, size, max_width);
+            // Take at least one character so a narrow width cannot stall
+            let split = head
+                .len()
+                .max(rest.chars().next().map_or(0, char::len_utf8));
+            lines.push(rest[..split].to_string());
+            rest = &rest[split..];
+        }
+        line = rest.to_string();
+    }
+    if !line.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
+
+/// Renders the boot splash: optional logo, hostname and IP address.
+pub fn render_splash(
+    canvas: &mut Canvas,
+    logo: Option<&Pixmap>,
+    hostname: &str,
+    ip: Option<&str>,
+    theme: &Theme,
+) {
+    canvas.clear();
+    let (width, height) = canvas.dimensions();
+    let (width, height) = (width as i32, height as i32);
+
+    let title_size = if width < 200 { 18.0 } else { 24.0 };
+    let detail_size = if width < 200 { 12.0 } else { 14.0 };
+    let title_h = canvas.line_height(title_size);
+    let detail_h = canvas.line_height(detail_size);
+
+    // Text block: hostname, IP, status line
+    let text_h = title_h + 2 * (detail_h + 4);
+
+    let mut y = match logo {
+        Some(logo) => {
+            let logo_h = logo.height() as i32;
+            let top = ((height - logo_h - text_h - 8) / 2).max(0);
+            let x = (width - logo.width() as i32) / 2;
+            canvas.draw_pixmap(x, top, logo);
+            top + logo_h + 8
+        }
+        None => {
+            let top = (height - text_h - 10) / 2;
+            canvas.fill_rect(width / 2 - 30, top, 60, 3, theme.primary);
+            top + 10
+        }
+    };
+
+    draw_centered(canvas, y, hostname, title_size, theme.text);
+    y += title_h + 4;
+
+    draw_centered(
+        canvas,
+        y,
+        ip.unwrap_or("No network"),
+        detail_size,
+        theme.secondary,
+    );
+    y += detail_h + 4;
+
+    draw_centered(canvas, y, "Starting...", detail_size, theme.primary);
+}
+
+/// Renders the screen shown after the daemon stops updating the panel.
+pub fn render_shutdown(canvas: &mut Canvas, message: &str, hostname: &str, theme: &Theme) {
+    canvas.clear();
+    let (width, height) = canvas.dimensions();
+    let (width, height) = (width as i32, height as i32);
+
+    let title_size = if width < 200 { 18.0 } else { 24.0 };
+    let detail_size = if width < 200 { 12.0 } else { 14.0 };
+    let title_h = canvas.line_height(title_size);
+    let detail_h = canvas.line_height(detail_size);
+
+    let top = (height - title_h - detail_h - 14) / 2;
+    canvas.fill_rect(width / 2 - 30, top, 60, 3, theme.secondary);
+
+    let y = top + 10;
+    draw_centered(canvas, y, message, title_size, theme.text);
+    draw_centered(
+        canvas,
+        y + title_h + 4,
+        hostname,
+        detail_size,
+        theme.secondary,
+    );
+}
+
+/// Renders the diagnostic screen shown when the render loop panics.
+pub fn render_panic(canvas: &mut Canvas, summary: &str, timestamp: &str) {
+    const BACKGROUND: u32 = 0x000000;
+    const HEADER: u32 = 0xCC2222;
+    const TEXT: u32 = 0xFFFFFF;
+    const DIM: u32 = 0x999999;
+
+    let (width, height) = canvas.dimensions();
+    canvas.fill_rect(0, 0, width, height, BACKGROUND);
+
+    let (width, height) = (width as i32, height as i32);
+    let title_size = if width < 200 { 14.0 } else { 16.0 };
+    let body_size = 12.0;
+    let title_h = canvas.line_height(title_size);
+    let body_h = canvas.line_height(body_size);
+
+    canvas.fill_rect(0, 0, width as u32, (title_h + 8) as u32, HEADER);
+    canvas.draw_text(MARGIN, 4, "Render loop crashed", title_size, TEXT);
+
+    // Footer: timestamp and restart notice
+    let footer_y = height - 2 * body_h - 6;
+    canvas.draw_text(MARGIN, footer_y, timestamp, body_size, DIM);
+    canvas.draw_text(
+        MARGIN,
+        footer_y + body_h + 2,
+        "Restarting...",
+        body_size,
+        DIM,
+    );
+
+    let mut y = title_h + 14;
+    for line in wrap_text(canvas, summary, body_size, width - 2 * MARGIN) {
+        if y + body_h > footer_y - 4 {
+            break;
+        }
+        canvas.draw_text(MARGIN, y, &line, body_size, TEXT);
+        y += body_h + 2;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns true if anything but black was drawn in columns `x0..x1`.
+    fn drawn(canvas: &Canvas, x0: u32, x1: u32) -> bool {
+        let (width, _) = canvas.dimensions();
+        canvas
+            .pixels()
+            .chunks(4)
+            .enumerate()
+            .filter(|(i, _)| (x0..x1).contains(&(*i as u32 % width)))
+            .any(|(_, pixel)| pixel[..3] != [0, 0, 0])
+    }
+
+    #[test]
+    fn test_splash_fits_both_orientations() {
+        let theme = Theme::default();
+        let summary = "index out of bounds: the len is 3 but the index is 7 at src/faces/x.rs:42:9";
+        for (w, h) in [(320, 170), (170, 320)] {
+            let mut canvas = Canvas::new(w, h);
+            let margin = MARGIN as u32;
+
+            // Long text is cut off at the margins instead of running off the panel
+            render_splash(
+                &mut canvas,
+                None,
+                "a-very-long-hostname.example.internal",
+                Some("2001:db8::1234:5678:9abc"),
+                &theme,
+            );
+            assert!(drawn(&canvas, margin, w - margin));
+            assert!(!drawn(&canvas, 0, margin / 2));
+            assert!(!drawn(&canvas, w - margin / 2, w));
+
+            render_shutdown(
+                &mut canvas,
+                "Panel offline",
+                "a-very-long-hostname.example",
+                &theme,
+            );
+            assert!(drawn(&canvas, margin, w - margin));
+            assert!(!drawn(&canvas, 0, margin / 2));
+            assert!(!drawn(&canvas, w - margin / 2, w));
+
+            // The summary wraps to lines that fit between the margins
+            render_panic(&mut canvas, summary, "2026-01-01 12:00:00");
+            let max_width = w as i32 - 2 * MARGIN;
+            let lines = wrap_text(&canvas, summary, 12.0, max_width);
+            assert!(lines.len() > 1);
+            assert!(lines
+                .iter()
+                .all(|line| canvas.text_width(line, 12.0) <= max_width));
+            assert_eq!(lines.concat().replace(' ', ""), summary.replace(' ', ""));
+        }
+    }
+
+    #[test]
+    fn test_wrap_long_word() {
+        let canvas = Canvas::new(320, 170);
+        let word = "x".repeat(80);
+        let lines = wrap_text(&canvas, &format!("ab {}", word), 12.0, 100);
+        assert_eq!(lines[0], "ab");
+        assert!(lines.len() > 2);
+        assert!(lines
+            .iter()
+            .all(|line| canvas.text_width(line, 12.0) <= 100));
+        assert_eq!(lines[1..].concat(), word);
+
+        // Narrower than one character: one character per line
+        assert_eq!(wrap_text(&canvas, "abc", 12.0, 1), ["a", "b", "c"]);
+    }
+}
@@ -0,0 +1,198 @@
+//! Panel test patterns.
+//!
+//! Drawn in place of the face to check a newly installed panel: color bars
+//! and gradients show dead pixels and color reproduction, text at each size
+//! shows sharpness, and the orientation pattern marks the top edge and
+//! numbered corners so rotation can be checked.
+
+use ht32_panel_hw::Orientation;
+
+use super::Canvas;
+
+/// Color bars: white, yellow, cyan, green, magenta, red, blue and black.
+const BARS: [u32; 8] = [
+    0xFFFFFF, 0xFFFF00, 0x00FFFF, 0x00FF00, 0xFF00FF, 0xFF0000, 0x0000FF, 0x000000,
+];
+
+/// Gradient bands: red, green, blue and grey.
+const BANDS: [u32; 4] = [0xFF0000, 0x00FF00, 0x0000FF, 0xFFFFFF];
+
+/// Sizes shown by the text pattern.
+const TEXT_SIZES: [f32; 7] = [8.0, 10.0, 12.0, 14.0, 18.0, 24.0, 32.0];
+
+const BACKGROUND: u32 = 0x000000;
+const FOREGROUND: u32 = 0xFFFFFF;
+const ACCENT: u32 = 0xFF0000;
+
+/// A test pattern.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TestPattern {
+    ColorBars,
+    Gradient,
+    Text,
+    /// Top edge and numbered corners, shown in each orientation
+    Orientation,
+}
+
+impl TestPattern {
+    /// All patterns, in the order they are shown.
+    pub const ALL: [TestPattern; 4] = [
+        TestPattern::ColorBars,
+        TestPattern::Gradient,
+        TestPattern::Text,
+        TestPattern::Orientation,
+    ];
+
+    /// Returns the name used over D-Bus.
+    pub fn name(self) -> &'static str {
+        match self {
+            TestPattern::ColorBars => "bars",
+            TestPattern::Gradient => "gradient",
+            TestPattern::Text => "text",
+            TestPattern::Orientation => "orientation",
+        }
+    }
+
+    /// Parses a name from [`TestPattern::name`].
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|p| p.name() == name)
+    }
+}
+
+/// Returns the pattern and orientation of each step of the test named
+/// `name`: one of the patterns or "all". The orientation pattern is shown
+/// in every orientation, the others in `current`.
+pub fn test_steps(name: &str, current: Orientation) -> Option<Vec<(TestPattern, Orientation)>> {
+    let patterns = match name {
+        "all" => TestPattern::ALL.to_vec(),
+        _ => vec![TestPattern::from_name(name)?],
+    };
+    Some(
+        patterns
+            .into_iter()
+            .flat_map(|pattern| match pattern {
+                TestPattern::Orientation => [
+                    Orientation::Landscape,
+                    Orientation::Portrait,
+                    Orientation::LandscapeUpsideDown,
+                    Orientation::PortraitUpsideDown,
+                ]
+                .map(|orientation| (pattern, orientation))
+                .to_vec(),
+                _ => vec![(pattern, current)],
+            })
+            .collect(),
+    )
+}
+
+/// Renders `pattern`, labelling the orientation pattern with `orientation`.
+pub fn render_test_pattern(canvas: &mut Canvas, pattern: TestPattern, orientation: Orientation) {
+    let (width, height) = canvas.dimensions();
+    canvas.fill_rect(0, 0, width, height, BACKGROUND);
+    match pattern {
+        TestPattern::ColorBars => draw_bars(canvas),
+        TestPattern::Gradient => draw_gradients(canvas),
+        TestPattern::Text => draw_text_sizes(canvas),
+        TestPattern::Orientation => draw_orientation(canvas, orientation),
+    }
+}
+
+/// Draws vertical bars of the primary and secondary colors.
+fn draw_bars(canvas: &mut Canvas) {
+    let (width, height) = canvas.dimensions();
+    let count = BARS.len() as u32;
+    for (i, color) in BARS.into_iter().enumerate() {
+        let x = i as u32 * width / count;
+        let next = (i as u32 + 1) * width / count;
+        canvas.fill_rect(x as i32, 0, next - x, height, color);
+    }
+}
+
+/// Draws horizontal bands ramping from black to each color.
+fn draw_gradients(canvas: &mut Canvas) {
+    let (width, height) = canvas.dimensions();
+    let count = BANDS.len() as u32;
+    for (i, color) in BANDS.into_iter().enumerate() {
+        let y = i as u32 * height / count;
+        let next = (i as u32 + 1) * height / count;
+        for x in 0..width {
+            let level = x as f32 / (width - 1).max(1) as f32;
+            canvas.fill_rect(x as i32, y as i32, 1, next - y, scale(color, level));
+        }
+    }
+}
+
+/// Draws a line of text at each size until the canvas is full.
+fn draw_text_sizes(canvas: &mut Canvas) {
+    let (_, height) = canvas.dimensions();
+    let mut y = 2;
+    for size in TEXT_SIZES {
+        let line_height = canvas.line_height(size);
+        if y + line_height > height as i32 {
+            break;
+        }
+        canvas.draw_text(4, y, &format!("{}px Aa0", size), size, FOREGROUND);
+        y += line_height;
+    }
+}
+
+/// Outlines the edges, numbers the corners clockwise from the top left
+/// and marks the top edge.
+fn draw_orientation(canvas: &mut Canvas, orientation: Orientation) {
+    let (width, height) = canvas.dimensions();
+    let (w, h) = (width as i32, height as i32);
+    canvas.fill_rect(0, 0, width, 1, FOREGROUND);
+    canvas.fill_rect(0, h - 1, width, 1, FOREGROUND);
+    canvas.fill_rect(0, 0, 1, height, FOREGROUND);
+    canvas.fill_rect(w - 1, 0, 1, height, FOREGROUND);
+
+    // The top edge is thicker and red
+    canvas.fill_rect(0, 0, width, 4, ACCENT);
+
+    let size = 16.0;
+    let line_height = canvas.line_height(size);
+    let margin = 6;
+    let digit_w = canvas.text_width("0", size);
+    let corners = [
+        (margin, margin),
+        (w - margin - digit_w, margin),
+        (w - margin - digit_w, h - margin - line_height),
+        (margin, h - margin - line_height),
+    ];
+    for (i, (x, y)) in corners.into_iter().enumerate() {
+        canvas.draw_text(x, y, &(i + 1).to_string(), size, FOREGROUND);
+    }
+
+    let label_size = 14.0;
+    let top_w = canvas.text_width("TOP", label_size);
+    canvas.draw_text((w - top_w) / 2, margin, "TOP", label_size, ACCENT);
+    let name = orientation.to_string();
+    let name_w = canvas.text_width(&name, label_size);
+    let name_y = (h - canvas.line_height(label_size)) / 2;
+    canvas.draw_text((w - name_w) / 2, name_y, &name, label_size, FOREGROUND);
+}
+
+/// Scales each channel of `color` by `level` (0.0-1.0).
+fn scale(color: u32, level: f32) -> u32 {
+    let channel = |shift: u32| ((((color >> shift) & 0xFF) as f32 * level).round() as u32) << shift;
+    channel(16) | channel(8) | channel(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_steps_flip_orientation() {
+        let current = Orientation::Portrait;
+        assert_eq!(
+            test_steps("bars", current),
+            Some(vec![(TestPattern::ColorBars, current)])
+        );
+        let all = test_steps("all", current).unwrap();
+        assert_eq!(all.len(), 7);
+        assert_eq!(all[3], (TestPattern::Orientation, Orientation::Landscape));
+        assert!(test_steps("checkerboard", current).is_none());
+        assert_eq!(scale(0xFF8000, 0.5), 0x804000);
+    }
+}
@@ -0,0 +1,146 @@
+//! Visible area of a partially hidden panel.
+//!
+//! Some case cutouts hide the panel edges. The visible area is a rectangle
+//! in panel coordinates (the 320x170 landscape frame as wired); the canvas
+//! is sized to it so faces lay out within it, and each frame is placed at
+//! its offset with the hidden edges left black.
+
+use std::fmt;
+
+use ht32_panel_hw::{Orientation, LCD_HEIGHT, LCD_WIDTH};
+use serde::{Deserialize, Serialize};
+
+/// Smallest visible width or height in pixels.
+const MIN_SIZE: u32 = 32;
+
+/// A rectangle of the panel left visible by the case.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VisibleArea {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl VisibleArea {
+    /// The whole panel.
+    pub const FULL: VisibleArea = VisibleArea {
+        x: 0,
+        y: 0,
+        width: LCD_WIDTH as u32,
+        height: LCD_HEIGHT as u32,
+    };
+
+    /// Returns the area if it lies within the panel and is at least
+    /// `MIN_SIZE` pixels in each direction.
+    pub fn new(x: u32, y: u32, width: u32, height: u32) -> Result<Self, String> {
+        if width < MIN_SIZE || height < MIN_SIZE {
+            return Err(format!(
+                "Visible area must be at least {}x{} pixels",
+                MIN_SIZE, MIN_SIZE
+            ));
+        }
+        let right = x.checked_add(width);
+        let bottom = y.checked_add(height);
+        if right.is_none_or(|right| right > LCD_WIDTH as u32)
+            || bottom.is_none_or(|bottom| bottom > LCD_HEIGHT as u32)
+        {
+            return Err(format!(
+                "Visible area must lie within the {}x{} panel",
+                LCD_WIDTH, LCD_HEIGHT
+            ));
+        }
+        Ok(Self {
+            x,
+            y,
+            width,
+            height,
+        })
+    }
+
+    /// Returns the canvas size for `orientation`, swapped when portrait.
+    pub fn canvas_size(&self, orientation: Orientation) -> (u32, u32) {
+        if orientation.is_portrait() {
+            (self.height, self.width)
+        } else {
+            (self.width, self.height)
+        }
+    }
+
+    /// Returns the panel position of canvas pixel (`x`, `y`) drawn for
+    /// `orientation`, or None if the pixel is outside the canvas.
+    pub fn panel_position(&self, orientation: Orientation, x: u32, y: u32) -> Option<(u32, u32)> {
+        let (w, h) = (self.width, self.height);
+        let flip = |size: u32, at: u32| size.checked_sub(1)?.checked_sub(at);
+        let (px, py) = match orientation {
+            Orientation::Landscape => (x, y),
+            Orientation::LandscapeUpsideDown => (flip(w, x)?, flip(h, y)?),
+            // Rotated 90° clockwise: the canvas is h wide and w high
+            Orientation::Portrait => (flip(w, y)?, x),
+            // Rotated 90° counter-clockwise
+            Orientation::PortraitUpsideDown => (y, flip(h, x)?),
+        };
+        if px >= w || py >= h {
+            return None;
+        }
+        Some((self.x.checked_add(px)?, self.y.checked_add(py)?))
+    }
+}
+
+impl Default for VisibleArea {
+    fn default() -> Self {
+        Self::FULL
+    }
+}
+
+impl fmt::Display for VisibleArea {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}x{}+{}+{}", self.width, self.height, self.x, self.y)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_visible_area_maps_corners() {
+        assert!(VisibleArea::new(10, 10, 320, 160).is_err());
+        assert!(VisibleArea::new(0, 0, 16, 170).is_err());
+
+        let area = VisibleArea::new(10, 5, 300, 160).unwrap();
+        assert_eq!(area.to_string(), "300x160+10+5");
+        assert_eq!(area.canvas_size(Orientation::Portrait), (160, 300));
+        assert_eq!(
+            area.panel_position(Orientation::Landscape, 0, 0),
+            Some((10, 5))
+        );
+        assert_eq!(
+            area.panel_position(Orientation::LandscapeUpsideDown, 0, 0),
+            Some((309, 164))
+        );
+        // The top left of a portrait canvas is the panel's top right
+        assert_eq!(
+            area.panel_position(Orientation::Portrait, 0, 0),
+            Some((309, 5))
+        );
+        assert_eq!(
+            area.panel_position(Orientation::PortraitUpsideDown, 0, 0),
+            Some((10, 164))
+        );
+        assert_eq!(
+            VisibleArea::FULL.panel_position(Orientation::Portrait, 169, 319),
+            Some((0, 169))
+        );
+
+        // Offsets and pixels past the edges are rejected, not wrapped
+        assert!(VisibleArea::new(u32::MAX, 0, 100, 100).is_err());
+        assert!(VisibleArea::new(0, 1, 100, u32::MAX).is_err());
+        assert_eq!(area.panel_position(Orientation::Landscape, 300, 0), None);
+        assert_eq!(area.panel_position(Orientation::Portrait, 0, 300), None);
+        assert_eq!(
+            area.panel_position(Orientation::LandscapeUpsideDown, 0, 160),
+            None
+        );
+    }
+}
@@ -0,0 +1,308 @@
+//! Colors accepted over D-Bus.
+//!
+//! A [`Color`] parses "#RRGGBB" (with or without the "#"), the "#RGB"
+//! shorthand, "rgb(r, g, b)" and the CSS named colors, so the daemon, the
+//! client and the CLI agree on what a valid color is. On the bus it is
+//! sent as its "#RRGGBB" string.
+
+use std::fmt;
+use std::str::FromStr;
+
+use zvariant::Type;
+
+use crate::Error;
+
+/// An RGB color.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Type)]
+#[zvariant(signature = "s")]
+pub struct Color {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+}
+
+impl Color {
+    /// Black.
+    pub const BLACK: Color = Color::rgb(0, 0, 0);
+
+    /// Creates a color from its components.
+    pub const fn rgb(r: u8, g: u8, b: u8) -> Self {
+        Self { r, g, b }
+    }
+
+    /// Creates a color from a 0xRRGGBB value.
+    pub const fn from_rgb888(rgb: u32) -> Self {
+        Self::rgb((rgb >> 16) as u8, (rgb >> 8) as u8, rgb as u8)
+    }
+
+    /// Returns the color as 0xRRGGBB.
+    pub const fn to_rgb888(self) -> u32 {
+        ((self.r as u32) << 16) | ((self.g as u32) << 8) | self.b as u32
+    }
+
+    /// Returns the color in the panel's RGB565 pixel format.
+    pub const fn to_rgb565(self) -> u16 {
+        ((self.r as u16 >> 3) << 11) | ((self.g as u16 >> 2) << 5) | (self.b as u16 >> 3)
+    }
+
+    /// Looks up a CSS color name, ignoring case.
+    fn named(name: &str) -> Option<Self> {
+        let name = name.to_ascii_lowercase();
+        NAMED_COLORS
+            .binary_search_by(|(candidate, _)| candidate.cmp(&name.as_str()))
+            .ok()
+            .map(|i| Self::from_rgb888(NAMED_COLORS[i].1))
+    }
+
+    /// Parses "RRGGBB" or "RGB" hex digits.
+    fn hex(digits: &str) -> Option<Self> {
+        if !digits.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return None;
+        }
+        let value = u32::from_str_radix(digits, 16).ok()?;
+        match digits.len() {
+            6 => Some(Self::from_rgb888(value)),
+            // Each digit is doubled: #09F is #0099FF
+            3 => {
+                let expand = |shift: u32| ((value >> shift) & 0xF) as u8 * 0x11;
+                Some(Self::rgb(expand(8), expand(4), expand(0)))
+            }
+            _ => None,
+        }
+    }
+
+    /// Parses the "r, g, b" inside "rgb(...)".
+    fn functional(args: &str) -> Option<Self> {
+        let mut parts = args.split(',').map(|part| part.trim().parse::<u8>());
+        let (Some(Ok(r)), Some(Ok(g)), Some(Ok(b)), None) =
+            (parts.next(), parts.next(), parts.next(), parts.next())
+        else {
+            return None;
+        };
+        Some(Self::rgb(r, g, b))
+    }
+}
+
+impl FromStr for Color {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let trimmed = s.trim();
+        let lower = trimmed.to_ascii_lowercase();
+        let color = if let Some(digits) = trimmed.strip_prefix('#') {
+            Self::hex(digits)
+        } else if let Some(args) = lower
+            .strip_prefix("rgb(")
+            .and_then(|rest| rest.strip_suffix(')'))
+        {
+            Self::functional(args)
+        } else {
+            Self::named(trimmed).or_else(|| Self::hex(trimmed).filter(|_| trimmed.len() == 6))
+        };
+        color.ok_or_else(|| Error::InvalidColor(s.to_string()))
+    }
+}
+
+impl fmt::Display for Color {
+    /// Formats the color as "#RRGGBB".
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "#{:02X}{:02X}{:02X}", self.r, self.g, self.b)
+    }
+}
+
+crate::serde_as_string!(Color);
+
+/// CSS named colors, sorted by name for binary search.
+const NAMED_COLORS: [(&str, u32); 148] = [
+    ("aliceblue", 0xF0F8FF),
+    ("antiquewhite", 0xFAEBD7),
+    ("aqua", 0x00FFFF),
+    ("aquamarine", 0x7FFFD4),
+    ("azure", 0xF0FFFF),
+    ("beige", 0xF5F5DC),
+    ("bisque", 0xFFE4C4),
+    ("black", 0x000000),
+    ("blanchedalmond", 0xFFEBCD),
+    ("blue", 0x0000FF),
+    ("blueviolet", 0x8A2BE2),
+    ("brown", 0xA52A2A),
+    ("burlywood", 0xDEB887),
+    ("cadetblue", 0x5F9EA0),
+    ("chartreuse", 0x7FFF00),
+    ("chocolate", 0xD2691E),
+    ("coral", 0xFF7F50),
+    ("cornflowerblue", 0x6495ED),
+    ("cornsilk", 0xFFF8DC),
+    ("crimson", 0xDC143C),
+    ("cyan", 0x00FFFF),
+    ("darkblue", 0x00008B),
+    ("darkcyan", 0x008B8B),
+    ("darkgoldenrod", 0xB8860B),
+    ("darkgray", 0xA9A9A9),
+    ("darkgreen", 0x006400),
+    ("darkgrey", 0xA9A9A9),
+    ("darkkhaki", 0xBDB76B),
+    ("darkmagenta", 0x8B008B),
+    ("darkolivegreen", 0x556B2F),
+    ("darkorange", 0xFF8C00),
+    ("darkorchid", 0x9932CC),
+    ("darkred", 0x8B0000),
+    ("darksalmon", 0xE9967A),
+    ("darkseagreen", 0x8FBC8F),
+    ("darkslateblue", 0x483D8B),
+    ("darkslategray", 0x2F4F4F),
+    ("darkslategrey", 0x2F4F4F),
+    ("darkturquoise", 0x00CED1),
+    ("darkviolet", 0x9400D3),
+    ("deeppink", 0xFF1493),
+    ("deepskyblue", 0x00BFFF),
+    ("dimgray", 0x696969),
+    ("dimgrey", 0x696969),
+    ("dodgerblue", 0x1E90FF),
+    ("firebrick", 0xB22222),
+    ("floralwhite", 0xFFFAF0),
+    ("forestgreen", 0x228B22),
+    ("fuchsia", 0xFF00FF),
+    ("gainsboro", 0xDCDCDC),
+    ("ghostwhite", 0xF8F8FF),
+    ("gold", 0xFFD700),
+    ("goldenrod", 0xDAA520),
+    ("gray", 0x808080),
+    ("green", 0x008000),
+    ("greenyellow", 0xADFF2F),
+    ("grey", 0x808080),
+    ("honeydew", 0xF0FFF0),
+    ("hotpink", 0xFF69B4),
+    ("indianred", 0xCD5C5C),
+    ("indigo", 0x4B0082),
+    ("ivory", 0xFFFFF0),
+    ("khaki", 0xF0E68C),
+    ("lavender", 0xE6E6FA),
+    ("lavenderblush", 0xFFF0F5),
+    ("lawngreen", 0x7CFC00),
+    ("lemonchiffon", 0xFFFACD),
+    ("lightblue", 0xADD8E6),
+    ("lightcoral", 0xF08080),
+    ("lightcyan", 0xE0FFFF),
+    ("lightgoldenrodyellow", 0xFAFAD2),
+    ("lightgray", 0xD3D3D3),
+    ("lightgreen", 0x90EE90),
+    ("lightgrey", 0xD3D3D3),
+    ("lightpink", 0xFFB6C1),
+    ("lightsalmon", 0xFFA07A),
+    ("lightseagreen", 0x20B2AA),
+    ("lightskyblue", 0x87CEFA),
+    ("lightslategray", 0x778899),
+    ("lightslategrey", 0x778899),
+    ("lightsteelblue", 0xB0C4DE),
+    ("lightyellow", 0xFFFFE0),
+    ("lime", 0x00FF00),
+    ("limegreen", 0x32CD32),
+    ("linen", 0xFAF0E6),
+    ("magenta", 0xFF00FF),
+    ("maroon", 0x800000),
+    ("mediumaquamarine", 0x66CDAA),
+    ("mediumblue", 0x0000CD),
+    ("mediumorchid", 0xBA55D3),
+    ("mediumpurple", 0x9370DB),
+    ("mediumseagreen", 0x3CB371),
+    ("mediumslateblue", 0x7B68EE),
+    ("mediumspringgreen", 0x00FA9A),
+    ("mediumturquoise", 0x48D1CC),
+    ("mediumvioletred", 0xC71585),
+    ("midnightblue", 0x191970),
+    ("mintcream", 0xF5FFFA),
+    ("mistyrose", 0xFFE4E1),
+    ("moccasin", 0xFFE4B5),
+    ("navajowhite", 0xFFDEAD),
+    ("navy", 0x000080),
+    ("oldlace", 0xFDF5E6),
+    ("olive", 0x808000),
+    ("olivedrab", 0x6B8E23),
+    ("orange", 0xFFA500),
+    ("orangered", 0xFF4500),
+    ("orchid", 0xDA70D6),
+    ("palegoldenrod", 0xEEE8AA),
+    ("palegreen", 0x98FB98),
+    ("paleturquoise", 0xAFEEEE),
+    ("palevioletred", 0xDB7093),
+    ("papayawhip", 0xFFEFD5),
+    ("peachpuff", 0xFFDAB9),
+    ("peru", 0xCD853F),
+    ("pink", 0xFFC0CB),
+    ("plum", 0xDDA0DD),
+    ("powderblue", 0xB0E0E6),
+    ("purple", 0x800080),
+    ("rebeccapurple", 0x663399),
+    ("red", 0xFF0000),
+    ("rosybrown", 0xBC8F8F),
+    ("royalblue", 0x4169E1),
+    ("saddlebrown", 0x8B4513),
+    ("salmon", 0xFA8072),
+    ("sandybrown", 0xF4A460),
+    ("seagreen", 0x2E8B57),
+    ("seashell", 0xFFF5EE),
+    ("sienna", 0xA0522D),
+    ("silver", 0xC0C0C0),
+    ("skyblue", 0x87CEEB),
+    ("slateblue", 0x6A5ACD),
+    ("slategray", 0x708090),
+    ("slategrey", 0x708090),
+    ("snow", 0xFFFAFA),
+    ("springgreen", 0x00FF7F),
+    ("steelblue", 0x4682B4),
+    ("tan", 0xD2B48C),
+    ("teal", 0x008080),
+    ("thistle", 0xD8BFD8),
+    ("tomato", 0xFF6347),
+    ("turquoise", 0x40E0D0),
+    ("violet", 0xEE82EE),
+    ("wheat", 0xF5DEB3),
+    ("white", 0xFFFFFF),
+    ("whitesmoke", 0xF5F5F5),
+    ("yellow", 0xFFFF00),
+    ("yellowgreen", 0x9ACD32),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_color_syntaxes() {
+        let dodger_blue = Color::rgb(0x1E, 0x90, 0xFF);
+        for input in [
+            "#1E90FF",
+            "1e90ff",
+            "rgb(30, 144, 255)",
+            "RGB(30,144,255)",
+            "DodgerBlue",
+            " dodgerblue ",
+        ] {
+            assert_eq!(input.parse(), Ok(dodger_blue), "{}", input);
+        }
+        assert_eq!("#09F".parse(), Ok(Color::rgb(0x00, 0x99, 0xFF)));
+        assert_eq!("rebeccapurple".parse(), Ok(Color::from_rgb888(0x663399)));
+
+        for input in [
+            "",
+            "#12345",
+            "#GGGGGG",
+            "09F",
+            "rgb(1, 2)",
+            "rgb(256, 0, 0)",
+            "blurple",
+        ] {
+            assert_eq!(
+                input.parse::<Color>(),
+                Err(Error::InvalidColor(input.to_string())),
+                "{}",
+                input
+            );
+        }
+
+        assert_eq!(dodger_blue.to_string(), "#1E90FF");
+        assert_eq!(Color::rgb(255, 0, 0).to_rgb565(), 0xF800);
+        assert_eq!(Color::from_rgb888(0xFFFFFF).to_rgb565(), 0xFFFF);
+    }
+}
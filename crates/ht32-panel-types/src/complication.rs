@@ -0,0 +1,33 @@
+//! Complication descriptions.
+
+use serde::{Deserialize, Serialize};
+use zvariant::Type;
+
+/// A complication of the current face.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub struct ComplicationInfo {
+    /// Identifier passed to `EnableComplication` and friends
+    pub id: String,
+    /// Name shown to users
+    pub name: String,
+    /// One-line description
+    pub description: String,
+    /// Whether it is currently shown
+    pub enabled: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_signature_matches_tuples() {
+        // ListComplications returned (id, name, description, enabled)
+        // tuples before this struct; clients of either decode the other
+        assert_eq!(ComplicationInfo::SIGNATURE.to_string(), "(sssb)");
+        assert_eq!(
+            ComplicationInfo::SIGNATURE,
+            <(String, String, String, bool)>::SIGNATURE
+        );
+    }
+}
@@ -0,0 +1,79 @@
+//! Error types for the shared types.
+//!
+//! An `Error` can itself be sent over D-Bus as `(us)`, and the daemon
+//! returns each kind as the named D-Bus error given by [`Error::dbus_name`].
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use zvariant::Type;
+
+/// Result type alias using our Error type.
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Prefix of the named D-Bus errors returned by the daemon.
+pub const DBUS_ERROR_PREFIX: &str = "org.ht32panel.Daemon1.Error";
+
+/// Errors from parsing or validating a shared type. Each holds the value
+/// that was refused.
+#[derive(Error, Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub enum Error {
+    /// Color string in none of the accepted syntaxes.
+    #[error("Invalid color '{0}' (expected #RRGGBB, #RGB, rgb(r, g, b) or a CSS color name)")]
+    InvalidColor(String),
+
+    /// Unknown orientation name.
+    #[error("Invalid orientation: {0}")]
+    InvalidOrientation(String),
+
+    /// LED theme, intensity or speed outside 1-5, as "name=value".
+    #[error("LED {0} is outside 1-5")]
+    InvalidLedSetting(String),
+
+    /// Unknown LED theme name.
+    #[error("Invalid LED theme '{0}' (expected rainbow, breathing, colors, static, off or auto)")]
+    InvalidLedTheme(String),
+
+    /// LED intensity or speed that is neither 1-5 nor a percentage.
+    #[error("Invalid LED level '{0}' (expected 1-5 or a percentage such as 60%)")]
+    InvalidLedLevel(String),
+
+    /// Unknown IP display preference.
+    #[error("Invalid IP display preference '{0}' (expected ipv6-gua, ipv6-lla, ipv6-ula or ipv4)")]
+    InvalidIpDisplayPreference(String),
+}
+
+impl Error {
+    /// Returns the name of the D-Bus error the daemon returns for this
+    /// error, e.g. "org.ht32panel.Daemon1.Error.InvalidColor".
+    pub fn dbus_name(&self) -> String {
+        let kind = match self {
+            Error::InvalidColor(_) => "InvalidColor",
+            Error::InvalidOrientation(_) => "InvalidOrientation",
+            Error::InvalidLedSetting(_) => "InvalidLedSetting",
+            Error::InvalidLedTheme(_) => "InvalidLedTheme",
+            Error::InvalidLedLevel(_) => "InvalidLedLevel",
+            Error::InvalidIpDisplayPreference(_) => "InvalidIpDisplayPreference",
+        };
+        format!("{}.{}", DBUS_ERROR_PREFIX, kind)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zvariant::serialized::Context;
+    use zvariant::{to_bytes, LE};
+
+    #[test]
+    fn test_error_round_trips_over_dbus() {
+        assert_eq!(Error::SIGNATURE.to_string(), "(us)");
+        let error = Error::InvalidLedTheme("disco".to_string());
+        let encoded = to_bytes(Context::new_dbus(LE, 0), &error).unwrap();
+        let (decoded, _): (Error, _) = encoded.deserialize().unwrap();
+        assert_eq!(decoded, error);
+        assert_eq!(
+            error.dbus_name(),
+            "org.ht32panel.Daemon1.Error.InvalidLedTheme"
+        );
+    }
+}
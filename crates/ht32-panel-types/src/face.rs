@@ -0,0 +1,13 @@
+//! Display face descriptions.
+
+use serde::{Deserialize, Serialize};
+use zvariant::Type;
+
+/// A face the daemon can show.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub struct FaceInfo {
+    /// Identifier passed to `SetFace`
+    pub id: String,
+    /// Name shown to users
+    pub display_name: String,
+}
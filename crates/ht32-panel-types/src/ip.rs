@@ -0,0 +1,90 @@
+//! IP address display preference.
+
+use std::fmt;
+use std::str::FromStr;
+
+use zvariant::Type;
+
+use crate::{Error, Result};
+
+/// Which of an interface's addresses network complications show, sent
+/// over D-Bus as its name.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Type)]
+#[zvariant(signature = "s")]
+pub enum IpDisplayPreference {
+    /// IPv6 Global Unicast Address (2000::/3)
+    #[default]
+    Ipv6Gua,
+    /// IPv6 Link-Local Address (fe80::/10)
+    Ipv6Lla,
+    /// IPv6 Unique Local Address (fc00::/7)
+    Ipv6Ula,
+    /// IPv4 address
+    Ipv4,
+}
+
+impl IpDisplayPreference {
+    /// Returns all available preferences.
+    pub fn all() -> &'static [IpDisplayPreference] {
+        &[
+            IpDisplayPreference::Ipv6Gua,
+            IpDisplayPreference::Ipv6Lla,
+            IpDisplayPreference::Ipv6Ula,
+            IpDisplayPreference::Ipv4,
+        ]
+    }
+
+    /// Returns the display name for this preference.
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            IpDisplayPreference::Ipv6Gua => "IPv6 GUA",
+            IpDisplayPreference::Ipv6Lla => "IPv6 LLA",
+            IpDisplayPreference::Ipv6Ula => "IPv6 ULA",
+            IpDisplayPreference::Ipv4 => "IPv4",
+        }
+    }
+}
+
+impl fmt::Display for IpDisplayPreference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IpDisplayPreference::Ipv6Gua => write!(f, "ipv6-gua"),
+            IpDisplayPreference::Ipv6Lla => write!(f, "ipv6-lla"),
+            IpDisplayPreference::Ipv6Ula => write!(f, "ipv6-ula"),
+            IpDisplayPreference::Ipv4 => write!(f, "ipv4"),
+        }
+    }
+}
+
+impl FromStr for IpDisplayPreference {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "ipv6-gua" | "ipv6_gua" | "ipv6gua" | "gua" => Ok(IpDisplayPreference::Ipv6Gua),
+            "ipv6-lla" | "ipv6_lla" | "ipv6lla" | "lla" => Ok(IpDisplayPreference::Ipv6Lla),
+            "ipv6-ula" | "ipv6_ula" | "ipv6ula" | "ula" => Ok(IpDisplayPreference::Ipv6Ula),
+            "ipv4" | "v4" => Ok(IpDisplayPreference::Ipv4),
+            _ => Err(Error::InvalidIpDisplayPreference(s.to_string())),
+        }
+    }
+}
+
+crate::serde_as_string!(IpDisplayPreference);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ip_display_preference_names() {
+        for preference in IpDisplayPreference::all() {
+            assert_eq!(preference.to_string().parse(), Ok(*preference));
+        }
+        assert_eq!("GUA".parse(), Ok(IpDisplayPreference::Ipv6Gua));
+        assert_eq!(
+            "ipv5".parse::<IpDisplayPreference>(),
+            Err(Error::InvalidIpDisplayPreference("ipv5".to_string()))
+        );
+    }
+}
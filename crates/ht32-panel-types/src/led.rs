@@ -0,0 +1,316 @@
+//! LED strip settings.
+
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use zvariant::Type;
+
+use crate::{Error, Result};
+
+/// Range of each LED setting.
+const RANGE: std::ops::RangeInclusive<u8> = 1..=5;
+
+/// LED theme names.
+const THEME_NAMES: [(&str, LedTheme); 5] = [
+    ("rainbow", LedTheme::Rainbow),
+    ("breathing", LedTheme::Breathing),
+    ("colors", LedTheme::Colors),
+    ("off", LedTheme::Off),
+    ("auto", LedTheme::Auto),
+];
+
+/// Other names for themes. The colors theme shows solid colors, which
+/// users tend to call static.
+const THEME_ALIASES: [(&str, LedTheme); 2] =
+    [("static", LedTheme::Colors), ("solid", LedTheme::Colors)];
+
+/// LED theme, sent to the strip as its byte value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[repr(u8)]
+pub enum LedTheme {
+    /// Rainbow cycling effect.
+    Rainbow = 0x01,
+    /// Breathing/pulsing effect.
+    #[default]
+    Breathing = 0x02,
+    /// Solid colors cycling.
+    Colors = 0x03,
+    /// LEDs off.
+    Off = 0x04,
+    /// Automatic mode.
+    Auto = 0x05,
+}
+
+impl LedTheme {
+    /// Converts a byte value to LedTheme.
+    pub fn from_byte(value: u8) -> Result<Self> {
+        THEME_NAMES
+            .iter()
+            .map(|(_, theme)| *theme)
+            .find(|theme| *theme as u8 == value)
+            .ok_or_else(|| Error::InvalidLedSetting(format!("theme={}", value)))
+    }
+
+    /// Returns the name of the theme, such as "breathing".
+    pub fn name(&self) -> &'static str {
+        THEME_NAMES
+            .iter()
+            .find(|(_, theme)| theme == self)
+            .map_or("unknown", |(name, _)| name)
+    }
+}
+
+impl FromStr for LedTheme {
+    type Err = Error;
+
+    /// Parses a theme name or alias, ignoring case.
+    fn from_str(s: &str) -> Result<Self> {
+        let lower = s.trim().to_ascii_lowercase();
+        THEME_NAMES
+            .iter()
+            .chain(&THEME_ALIASES)
+            .find(|(candidate, _)| *candidate == lower)
+            .map(|(_, theme)| *theme)
+            .ok_or_else(|| Error::InvalidLedTheme(s.to_string()))
+    }
+}
+
+impl fmt::Display for LedTheme {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// LED theme, intensity and speed, each 1-5. The theme is a [`LedTheme`]
+/// byte.
+///
+/// Values are checked when built and when received over D-Bus, so a
+/// `LedSettings` is always in range.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(try_from = "RawLedSettings")]
+pub struct LedSettings {
+    theme: u8,
+    intensity: u8,
+    speed: u8,
+}
+
+impl LedSettings {
+    /// Returns the settings if each value is 1-5.
+    pub fn new(theme: u8, intensity: u8, speed: u8) -> Result<Self> {
+        for (name, value) in [("theme", theme), ("intensity", intensity), ("speed", speed)] {
+            if !RANGE.contains(&value) {
+                return Err(Error::InvalidLedSetting(format!("{}={}", name, value)));
+            }
+        }
+        Ok(Self {
+            theme,
+            intensity,
+            speed,
+        })
+    }
+
+    /// Returns the settings with each value clamped to 1-5.
+    pub fn clamped(theme: u8, intensity: u8, speed: u8) -> Self {
+        Self {
+            theme: theme.clamp(*RANGE.start(), *RANGE.end()),
+            intensity: intensity.clamp(*RANGE.start(), *RANGE.end()),
+            speed: speed.clamp(*RANGE.start(), *RANGE.end()),
+        }
+    }
+
+    /// Starts building settings from the defaults.
+    pub fn builder() -> LedSettingsBuilder {
+        Self::default().to_builder()
+    }
+
+    /// Starts building settings from these ones, to change some values.
+    pub fn to_builder(self) -> LedSettingsBuilder {
+        LedSettingsBuilder {
+            theme: self.theme,
+            intensity: self.intensity,
+            speed: self.speed,
+        }
+    }
+
+    /// Theme byte (1=rainbow, 2=breathing, 3=colors, 4=off, 5=auto).
+    pub fn theme(&self) -> u8 {
+        self.theme
+    }
+
+    /// Brightness, 1-5.
+    pub fn intensity(&self) -> u8 {
+        self.intensity
+    }
+
+    /// Animation speed, 1-5.
+    pub fn speed(&self) -> u8 {
+        self.speed
+    }
+
+    /// Returns the theme. Every byte in range is a theme.
+    pub fn led_theme(&self) -> LedTheme {
+        LedTheme::from_byte(self.theme).unwrap_or_default()
+    }
+
+    /// Returns true if the theme turns the LEDs off.
+    pub fn is_off(&self) -> bool {
+        self.led_theme() == LedTheme::Off
+    }
+
+    /// Returns the name of the theme, such as "breathing".
+    pub fn theme_name(&self) -> &'static str {
+        self.led_theme().name()
+    }
+
+    /// Returns the theme byte for a theme name, ignoring case.
+    pub fn theme_from_name(name: &str) -> Result<u8> {
+        Ok(name.parse::<LedTheme>()? as u8)
+    }
+
+    /// Parses an intensity or speed given as a level ("3") or a percentage
+    /// ("60%"). Percentages are rounded up to the next of the five levels,
+    /// so 1-20% is level 1 and 81-100% is level 5.
+    pub fn parse_level(s: &str) -> Result<u8> {
+        let invalid = || Error::InvalidLedLevel(s.to_string());
+        let trimmed = s.trim();
+        match trimmed.strip_suffix('%') {
+            Some(percent) => {
+                let percent: u8 = percent.trim().parse().map_err(|_| invalid())?;
+                if !(1..=100).contains(&percent) {
+                    return Err(invalid());
+                }
+                Ok(percent.div_ceil(20))
+            }
+            None => trimmed
+                .parse()
+                .ok()
+                .filter(|level| RANGE.contains(level))
+                .ok_or_else(invalid),
+        }
+    }
+
+    /// Returns a level as a percentage of the highest level.
+    pub fn level_percent(level: u8) -> u8 {
+        level.saturating_mul(100 / RANGE.end())
+    }
+}
+
+impl Default for LedSettings {
+    /// Breathing at medium intensity and speed.
+    fn default() -> Self {
+        Self {
+            theme: 2,
+            intensity: 3,
+            speed: 3,
+        }
+    }
+}
+
+impl fmt::Display for LedSettings {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "theme {}, intensity {}, speed {}",
+            self.theme, self.intensity, self.speed
+        )
+    }
+}
+
+/// Builds [`LedSettings`], checking the values in [`build`](Self::build).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LedSettingsBuilder {
+    theme: u8,
+    intensity: u8,
+    speed: u8,
+}
+
+impl LedSettingsBuilder {
+    /// Sets the theme byte.
+    pub fn theme(mut self, theme: u8) -> Self {
+        self.theme = theme;
+        self
+    }
+
+    /// Sets the intensity.
+    pub fn intensity(mut self, intensity: u8) -> Self {
+        self.intensity = intensity;
+        self
+    }
+
+    /// Sets the speed.
+    pub fn speed(mut self, speed: u8) -> Self {
+        self.speed = speed;
+        self
+    }
+
+    /// Returns the settings if each value is 1-5.
+    pub fn build(self) -> Result<LedSettings> {
+        LedSettings::new(self.theme, self.intensity, self.speed)
+    }
+}
+
+/// Unchecked settings as received, checked by `TryFrom`.
+#[derive(Deserialize)]
+struct RawLedSettings {
+    theme: u8,
+    intensity: u8,
+    speed: u8,
+}
+
+impl TryFrom<RawLedSettings> for LedSettings {
+    type Error = Error;
+
+    fn try_from(raw: RawLedSettings) -> Result<Self> {
+        Self::new(raw.theme, raw.intensity, raw.speed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zvariant::serialized::Context;
+    use zvariant::{to_bytes, LE};
+
+    #[test]
+    fn test_led_settings_are_checked() {
+        let settings = LedSettings::builder().theme(1).speed(5).build().unwrap();
+        assert_eq!(
+            (settings.theme(), settings.intensity(), settings.speed()),
+            (1, 3, 5)
+        );
+        assert_eq!(
+            settings.to_builder().intensity(0).build(),
+            Err(Error::InvalidLedSetting("intensity=0".to_string()))
+        );
+        assert_eq!(
+            LedSettings::clamped(9, 0, 3),
+            LedSettings::new(5, 1, 3).unwrap()
+        );
+
+        assert_eq!(LedSettings::theme_from_name("Rainbow"), Ok(1));
+        assert_eq!(LedSettings::theme_from_name("static"), Ok(3));
+        assert!(LedSettings::theme_from_name("disco").is_err());
+        assert_eq!(settings.theme_name(), "rainbow");
+        assert_eq!(settings.led_theme(), LedTheme::Rainbow);
+        assert!(LedSettings::new(4, 1, 1).unwrap().is_off());
+        for (_, theme) in THEME_NAMES {
+            assert_eq!(LedTheme::from_byte(theme as u8), Ok(theme));
+            assert_eq!(theme.to_string().parse(), Ok(theme));
+        }
+        assert!(LedTheme::from_byte(6).is_err());
+        for (input, level) in [("4", 4), ("1%", 1), ("20%", 1), ("21%", 2), ("100 %", 5)] {
+            assert_eq!(LedSettings::parse_level(input), Ok(level), "{}", input);
+        }
+        for input in ["0", "6", "0%", "101%", "fast"] {
+            assert!(LedSettings::parse_level(input).is_err(), "{}", input);
+        }
+        assert_eq!(LedSettings::level_percent(3), 60);
+
+        // Out of range values are refused when received, too
+        let encoded = to_bytes(Context::new_dbus(LE, 0), &(2u8, 6u8, 3u8)).unwrap();
+        assert!(encoded.deserialize::<LedSettings>().is_err());
+        let encoded = to_bytes(Context::new_dbus(LE, 0), &settings).unwrap();
+        assert_eq!(encoded.deserialize::<LedSettings>().unwrap().0, settings);
+    }
+}
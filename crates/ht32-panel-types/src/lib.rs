@@ -0,0 +1,57 @@
+//! HT32 Panel Shared Types
+//!
+//! Types exchanged between the daemon and its clients. Each derives serde
+//! and zvariant's `Type`, so the D-Bus interface and the client proxy use
+//! the same definitions instead of strings and tuples.
+
+/// Implements `Serialize` and `Deserialize` through `Display` and
+/// `FromStr`, so the type travels as a string.
+macro_rules! serde_as_string {
+    ($ty:ty) => {
+        impl serde::Serialize for $ty {
+            fn serialize<S: serde::Serializer>(
+                &self,
+                serializer: S,
+            ) -> std::result::Result<S::Ok, S::Error> {
+                serializer.collect_str(self)
+            }
+        }
+
+        impl<'de> serde::Deserialize<'de> for $ty {
+            fn deserialize<D: serde::Deserializer<'de>>(
+                deserializer: D,
+            ) -> std::result::Result<Self, D::Error> {
+                let s = String::deserialize(deserializer)?;
+                s.parse().map_err(serde::de::Error::custom)
+            }
+        }
+    };
+}
+pub(crate) use serde_as_string;
+
+pub mod color;
+pub mod complication;
+pub mod error;
+pub mod face;
+pub mod ip;
+pub mod led;
+pub mod orientation;
+pub mod state;
+pub mod theme;
+
+pub use color::Color;
+pub use complication::ComplicationInfo;
+pub use error::{Error, Result, DBUS_ERROR_PREFIX};
+pub use face::FaceInfo;
+pub use ip::IpDisplayPreference;
+pub use led::{LedSettings, LedSettingsBuilder, LedTheme};
+pub use orientation::Orientation;
+pub use state::DaemonState;
+pub use theme::ThemeInfo;
+
+/// LCD display dimensions
+pub const LCD_WIDTH: u16 = 320;
+pub const LCD_HEIGHT: u16 = 170;
+
+/// DNS-SD service type the daemon advertises its web UI under.
+pub const SERVICE_TYPE: &str = "_ht32panel._tcp.local.";
@@ -5,9 +5,11 @@
 
 use crate::{Error, Result, LCD_HEIGHT, LCD_WIDTH};
 use std::str::FromStr;
+use zvariant::Type;
 
-/// Display orientation.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+/// Display orientation, sent over D-Bus as its name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Type)]
+#[zvariant(signature = "s")]
 pub enum Orientation {
     /// Landscape mode (320x170), hardware native.
     #[default]
@@ -88,6 +90,8 @@ impl std::fmt::Display for Orientation {
     }
 }
 
+crate::serde_as_string!(Orientation);
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,59 @@
+//! Daemon state snapshot.
+
+use serde::{Deserialize, Serialize};
+use zvariant::Type;
+
+use crate::{IpDisplayPreference, LedSettings, Orientation, ThemeInfo};
+
+/// The settings a client shows, fetched in one `GetState` call.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub struct DaemonState {
+    /// Whether the LCD device is connected
+    pub connected: bool,
+    /// Whether the web UI is enabled
+    pub web_enabled: bool,
+    pub orientation: Orientation,
+    /// Current face ID
+    pub face: String,
+    /// Current color theme
+    pub theme: ThemeInfo,
+    pub led: LedSettings,
+    /// Address family shown by network complications
+    pub ip_display_preference: IpDisplayPreference,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zvariant::serialized::Context;
+    use zvariant::{to_bytes, LE};
+
+    #[test]
+    fn test_state_round_trips_over_dbus() {
+        assert_eq!(DaemonState::SIGNATURE.to_string(), "(bbss(ssb)(yyy)s)");
+
+        let state = DaemonState {
+            connected: true,
+            web_enabled: false,
+            orientation: Orientation::PortraitUpsideDown,
+            face: "professional".to_string(),
+            theme: ThemeInfo {
+                id: "harbor".to_string(),
+                display_name: "harbor".to_string(),
+                custom: true,
+            },
+            led: LedSettings::new(2, 3, 4).unwrap(),
+            ip_display_preference: IpDisplayPreference::Ipv4,
+        };
+        let encoded = to_bytes(Context::new_dbus(LE, 0), &state).unwrap();
+        let (decoded, _): (DaemonState, _) = encoded.deserialize().unwrap();
+        assert_eq!(decoded, state);
+
+        // Orientation travels as a string and is parsed on arrival
+        let encoded = to_bytes(Context::new_dbus(LE, 0), "Landscape_Upside_Down").unwrap();
+        let (decoded, _): (Orientation, _) = encoded.deserialize().unwrap();
+        assert_eq!(decoded, Orientation::LandscapeUpsideDown);
+        let encoded = to_bytes(Context::new_dbus(LE, 0), "sideways").unwrap();
+        assert!(encoded.deserialize::<Orientation>().is_err());
+    }
+}
@@ -0,0 +1,17 @@
+//! Color theme descriptions.
+
+use serde::{Deserialize, Serialize};
+use zvariant::Type;
+
+/// A color theme the daemon can show: a preset or a theme saved from the
+/// theme editor.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub struct ThemeInfo {
+    /// Identifier passed to `SetTheme`
+    pub id: String,
+    /// Name shown to users
+    pub display_name: String,
+    /// Whether it was saved from the theme editor, and can be deleted
+    #[serde(default)]
+    pub custom: bool,
+}
@@ -49,7 +49,11 @@
           src = ./.;
           cargoLock.lockFile = ./Cargo.lock;
           inherit nativeBuildInputs buildInputs;
-          cargoTestFlags = [ "--workspace" "--" "--skip" "test_device_open" ];
+          cargoTestFlags = [
+            "--workspace"
+            "--features" "ht32-panel-render/golden-tests"
+            "--" "--skip" "test_device_open"
+          ];
 
           meta = with pkgs.lib; {
             description = "HT32 Panel - Mini PC Display & LED Control";
@@ -67,6 +71,7 @@
               cp -r config $out/share/ht32-panel/
               cp -r packaging $out/share/ht32-panel/
               cp 99-ht32-panel.rules $out/share/ht32-panel/
+              install -Dm644 packaging/org.ht32panel.policy $out/share/polkit-1/actions/org.ht32panel.policy
               if [ -f octaknight-wallpaper.png ]; then
                 cp octaknight-wallpaper.png $out/share/ht32-panel/
               fi
@@ -87,6 +92,11 @@
             cargoBuildFlags = [ "-p" "ht32-panel-cli" ];
           });
 
+          ht32panelrx = pkgs.rustPlatform.buildRustPackage (cargoArgs // {
+            pname = "ht32panelrx";
+            cargoBuildFlags = [ "-p" "ht32-panel-receiver" ];
+          });
+
           ht32-panel-applet = pkgs.rustPlatform.buildRustPackage (cargoArgs // {
             pname = "ht32-panel-applet";
             cargoBuildFlags = [ "-p" "ht32-panel-applet" ];
@@ -104,6 +114,7 @@
             # Binaries
             cp ${pkg}/bin/ht32paneld dist/
             cp ${pkg}/bin/ht32panelctl dist/
+            cp ${pkg}/bin/ht32panelrx dist/
             cp ${applet}/bin/ht32-panel-applet dist/
             
             # Config
@@ -113,6 +124,9 @@
             cp ${pkg}/share/ht32-panel/packaging/install.sh dist/
             cp ${pkg}/share/ht32-panel/99-ht32-panel.rules dist/
             cp ${pkg}/share/ht32-panel/packaging/ht32-panel.service dist/
+            cp ${pkg}/share/ht32-panel/packaging/org.ht32panel.policy dist/
+            cp ${pkg}/share/ht32-panel/packaging/org.ht32panel.Daemon.conf dist/
+            cp ${pkg}/share/ht32-panel/packaging/org.ht32panel.Daemon.service dist/
             if [ -f ${pkg}/share/ht32-panel/octaknight-wallpaper.png ]; then
               cp ${pkg}/share/ht32-panel/octaknight-wallpaper.png dist/
             fi
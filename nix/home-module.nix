@@ -173,7 +173,8 @@ in
       text = ''
         [D-BUS Service]
         Name=org.ht32panel.Daemon
-        Exec=${cfg.package}/bin/ht32paneld ${configFile}
+        Exec=${cfg.package}/bin/ht32paneld --mode user ${configFile}
+        SystemdService=ht32paneld.service
       '';
     };
 
@@ -187,7 +188,7 @@ in
 
       Service = {
         Type = "simple";
-        ExecStart = "${cfg.package}/bin/ht32paneld ${configFile}";
+        ExecStart = "${cfg.package}/bin/ht32paneld --mode user ${configFile}";
         Restart = "on-failure";
         RestartSec = 5;
 
@@ -9,7 +9,10 @@ let
       enable = cfg.web.enable;
       listen = cfg.web.listen;
     };
-    dbus.bus = cfg.dbus.bus;
+    dbus = {
+      bus = cfg.dbus.bus;
+      polkit = cfg.dbus.polkit;
+    };
     devices = {
       lcd = cfg.devices.lcd;
       led = cfg.devices.led;
@@ -17,6 +20,7 @@ let
   }
   // lib.optionalAttrs (cfg.refresh != null) { refresh_interval = cfg.refresh; }
   // lib.optionalAttrs (cfg.heartbeat != null) { heartbeat = cfg.heartbeat; }
+  // lib.optionalAttrs cfg.kiosk { kiosk = true; }
   // cfg.extraSettings);
 in
 {
@@ -84,6 +88,26 @@ in
           - "auto": Try session bus first, fall back to system bus.
         '';
       };
+
+      polkit = lib.mkOption {
+        type = lib.types.bool;
+        default = false;
+        description = ''
+          Check system bus callers with polkit. Any user may change faces,
+          themes and LEDs; changing network settings, quitting the daemon
+          and clearing the display need an administrator. Requires
+          `security.polkit.enable`.
+        '';
+      };
+    };
+
+    kiosk = lib.mkOption {
+      type = lib.types.bool;
+      default = false;
+      description = ''
+        Read-only mode for shared or public places. The web UI rejects all
+        changes and D-Bus only accepts changes from root.
+      '';
     };
 
     refresh = lib.mkOption {
@@ -204,7 +228,18 @@ in
             </busconfig>
           '';
         })
-      ];
+      ] ++ lib.optional (cfg.dbus.bus != "session") (pkgs.writeTextFile {
+        # System bus activation: the first call starts the service
+        name = "ht32-panel-dbus-activation";
+        destination = "/share/dbus-1/system-services/org.ht32panel.Daemon.service";
+        text = ''
+          [D-BUS Service]
+          Name=org.ht32panel.Daemon
+          Exec=${cfg.package}/bin/ht32paneld ${configFile}
+          User=${cfg.user}
+          SystemdService=ht32paneld.service
+        '';
+      });
 
       # Systemd service
       systemd.services.ht32paneld = {
@@ -40,6 +40,16 @@ if [ -f octaknight-wallpaper.png ]; then
     echo "Installed wallpaper to /usr/share/ht32-panel/"
 fi
 
+# Install polkit actions (checked when dbus.polkit is enabled)
+if [ -d /usr/share/polkit-1/actions ]; then
+    install -m 644 org.ht32panel.policy /usr/share/polkit-1/actions/
+fi
+
+# Install D-Bus policy and activation file, so clients can start the daemon
+install -m 644 org.ht32panel.Daemon.conf /usr/share/dbus-1/system.d/
+install -m 644 org.ht32panel.Daemon.service /usr/share/dbus-1/system-services/
+systemctl reload dbus.service || true
+
 # Install udev rules
 install -m 644 99-ht32-panel.rules /etc/udev/rules.d/
 udevadm control --reload-rules && udevadm trigger